@@ -7,4 +7,9 @@ import type { Settings } from "./Settings";
 /**
  * Collaboration mode for a Codex session.
  */
-export type CollaborationMode = { mode: ModeKind, settings: Settings, };
+export type CollaborationMode = { mode: ModeKind, settings: Settings, 
+/**
+ * Name of the user-defined mode (from `[collaboration_modes.<name>]` in
+ * `config.toml`) this collaboration mode was built from, if any.
+ */
+custom_mode?: string, };
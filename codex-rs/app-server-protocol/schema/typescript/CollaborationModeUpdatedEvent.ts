@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CollaborationMode } from "./CollaborationMode";
+
+export type CollaborationModeUpdatedEvent = { 
+/**
+ * The collaboration mode now in effect.
+ */
+collaboration_mode: CollaborationMode, };
@@ -2,4 +2,4 @@
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type FileChange = { "type": "add", content: string, } | { "type": "delete", content: string, } | { "type": "update", unified_diff: string, move_path: string | null, };
+export type FileChange = { "type": "add", content: string, } | { "type": "add_binary", size_bytes: number, } | { "type": "add_symlink", target: string, } | { "type": "delete", content: string, } | { "type": "update", unified_diff: string, move_path: string | null, } | { "type": "mode_change", executable: boolean, };
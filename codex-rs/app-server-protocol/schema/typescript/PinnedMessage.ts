@@ -0,0 +1,16 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A message that survives compaction verbatim.
+ */
+export type PinnedMessage = { 
+/**
+ * Id of the pinned user or agent message item.
+ */
+id: string, 
+/**
+ * Text of the message at the time it was pinned.
+ */
+text: string, };
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PinnedMessage } from "./PinnedMessage";
+
+export type PinnedMessagesUpdatedEvent = { 
+/**
+ * Every message currently pinned on the thread, oldest first.
+ */
+pinned_messages: Array<PinnedMessage>, };
@@ -6,4 +6,14 @@ import type { ReviewTarget } from "./ReviewTarget";
 /**
  * Review request sent to the review session.
  */
-export type ReviewRequest = { target: ReviewTarget, user_facing_hint?: string, };
+export type ReviewRequest = { target: ReviewTarget, user_facing_hint?: string, 
+/**
+ * When true, findings that do not touch a line changed by the target are
+ * dropped from the review output.
+ */
+only_changed_lines?: boolean, 
+/**
+ * Baseline file of accepted findings. Findings that match an entry are
+ * dropped from the review output so that only new ones are reported.
+ */
+baseline?: string, };
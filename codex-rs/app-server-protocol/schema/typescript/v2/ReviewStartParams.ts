@@ -9,4 +9,14 @@ export type ReviewStartParams = { threadId: string, target: ReviewTarget,
  * Where to run the review: inline (default) on the current thread or
  * detached on a new thread (returned in `reviewThreadId`).
  */
-delivery?: ReviewDelivery | null, };
+delivery?: ReviewDelivery | null, 
+/**
+ * When true, drop findings that do not touch a line changed by the target.
+ */
+onlyChangedLines?: boolean, 
+/**
+ * Baseline file of accepted findings; matching findings are dropped so
+ * that only new ones are reported. Relative paths are resolved against
+ * the thread's working directory.
+ */
+baseline?: string | null, };
@@ -0,0 +1,11 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ThreadPinParams = { threadId: string, 
+/**
+ * Id of a `userMessage` or `agentMessage` item of this thread, as reported by
+ * `item/completed`. Only messages from the thread's current session can be
+ * pinned.
+ */
+itemId: string, };
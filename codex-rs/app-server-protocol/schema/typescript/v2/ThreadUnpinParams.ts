@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ThreadUnpinParams = { threadId: string, 
+/**
+ * Id of the pinned message, as reported by `thread/pinnedMessages/updated`.
+ */
+pinId: string, };
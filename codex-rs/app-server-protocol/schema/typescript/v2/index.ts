@@ -87,6 +87,7 @@ export type { NetworkAccess } from "./NetworkAccess";
 export type { OverriddenMetadata } from "./OverriddenMetadata";
 export type { PatchApplyStatus } from "./PatchApplyStatus";
 export type { PatchChangeKind } from "./PatchChangeKind";
+export type { PinnedMessage } from "./PinnedMessage";
 export type { PlanDeltaNotification } from "./PlanDeltaNotification";
 export type { ProfileV2 } from "./ProfileV2";
 export type { RateLimitSnapshot } from "./RateLimitSnapshot";
@@ -138,6 +139,9 @@ export type { ThreadListResponse } from "./ThreadListResponse";
 export type { ThreadLoadedListParams } from "./ThreadLoadedListParams";
 export type { ThreadLoadedListResponse } from "./ThreadLoadedListResponse";
 export type { ThreadNameUpdatedNotification } from "./ThreadNameUpdatedNotification";
+export type { ThreadPinParams } from "./ThreadPinParams";
+export type { ThreadPinResponse } from "./ThreadPinResponse";
+export type { ThreadPinnedMessagesUpdatedNotification } from "./ThreadPinnedMessagesUpdatedNotification";
 export type { ThreadReadParams } from "./ThreadReadParams";
 export type { ThreadReadResponse } from "./ThreadReadResponse";
 export type { ThreadResumeParams } from "./ThreadResumeParams";
@@ -155,6 +159,8 @@ export type { ThreadTokenUsage } from "./ThreadTokenUsage";
 export type { ThreadTokenUsageUpdatedNotification } from "./ThreadTokenUsageUpdatedNotification";
 export type { ThreadUnarchiveParams } from "./ThreadUnarchiveParams";
 export type { ThreadUnarchiveResponse } from "./ThreadUnarchiveResponse";
+export type { ThreadUnpinParams } from "./ThreadUnpinParams";
+export type { ThreadUnpinResponse } from "./ThreadUnpinResponse";
 export type { TokenUsageBreakdown } from "./TokenUsageBreakdown";
 export type { ToolRequestUserInputAnswer } from "./ToolRequestUserInputAnswer";
 export type { ToolRequestUserInputOption } from "./ToolRequestUserInputOption";
@@ -204,6 +204,14 @@ client_request_definitions! {
         params: v2::ThreadSetNameParams,
         response: v2::ThreadSetNameResponse,
     },
+    ThreadPin => "thread/pin" {
+        params: v2::ThreadPinParams,
+        response: v2::ThreadPinResponse,
+    },
+    ThreadUnpin => "thread/unpin" {
+        params: v2::ThreadUnpinParams,
+        response: v2::ThreadUnpinResponse,
+    },
     ThreadUnarchive => "thread/unarchive" {
         params: v2::ThreadUnarchiveParams,
         response: v2::ThreadUnarchiveResponse,
@@ -690,6 +698,7 @@ server_notification_definitions! {
     Error => "error" (v2::ErrorNotification),
     ThreadStarted => "thread/started" (v2::ThreadStartedNotification),
     ThreadNameUpdated => "thread/name/updated" (v2::ThreadNameUpdatedNotification),
+    ThreadPinnedMessagesUpdated => "thread/pinnedMessages/updated" (v2::ThreadPinnedMessagesUpdatedNotification),
     ThreadTokenUsageUpdated => "thread/tokenUsage/updated" (v2::ThreadTokenUsageUpdatedNotification),
     TurnStarted => "turn/started" (v2::TurnStartedNotification),
     TurnCompleted => "turn/completed" (v2::TurnCompletedNotification),
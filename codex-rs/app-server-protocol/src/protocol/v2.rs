@@ -30,6 +30,7 @@ use codex_protocol::protocol::AskForApproval as CoreAskForApproval;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
 use codex_protocol::protocol::NetworkAccess as CoreNetworkAccess;
+use codex_protocol::protocol::PinnedMessage as CorePinnedMessage;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
 use codex_protocol::protocol::SessionSource as CoreSessionSource;
@@ -1389,6 +1390,36 @@ pub struct ThreadSetNameParams {
     pub name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinParams {
+    pub thread_id: String,
+    /// Id of a `userMessage` or `agentMessage` item of this thread, as reported by
+    /// `item/completed`. Only messages from the thread's current session can be
+    /// pinned.
+    pub item_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUnpinParams {
+    pub thread_id: String,
+    /// Id of the pinned message, as reported by `thread/pinnedMessages/updated`.
+    pub pin_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUnpinResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1983,6 +2014,17 @@ pub struct ReviewStartParams {
     #[serde(default)]
     #[ts(optional = nullable)]
     pub delivery: Option<ReviewDelivery>,
+
+    /// When true, drop findings that do not touch a line changed by the target.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_changed_lines: bool,
+
+    /// Baseline file of accepted findings; matching findings are dropped so
+    /// that only new ones are reported. Relative paths are resolved against
+    /// the thread's working directory.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub baseline: Option<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2022,6 +2064,22 @@ pub enum ReviewTarget {
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
     Custom { instructions: String },
+
+    /// Review the changes between two commits, as in `git diff base..head`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    CommitRange { base: String, head: String },
+
+    /// Review the changes described by a unified diff or patch file on disk.
+    /// Relative paths are resolved against the thread's working directory.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PatchFile { path: PathBuf },
+
+    /// Review the current contents of the given files or directories.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Paths { paths: Vec<PathBuf> },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2518,6 +2576,31 @@ pub struct ThreadNameUpdatedNotification {
     pub thread_name: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PinnedMessage {
+    pub id: String,
+    pub text: String,
+}
+
+impl From<CorePinnedMessage> for PinnedMessage {
+    fn from(value: CorePinnedMessage) -> Self {
+        Self {
+            id: value.id,
+            text: value.text,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinnedMessagesUpdatedNotification {
+    pub thread_id: String,
+    pub pinned_messages: Vec<PinnedMessage>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -54,6 +54,7 @@ use codex_app_server_protocol::ServerRequestPayload;
 use codex_app_server_protocol::TerminalInteractionNotification;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadNameUpdatedNotification;
+use codex_app_server_protocol::ThreadPinnedMessagesUpdatedNotification;
 use codex_app_server_protocol::ThreadRollbackResponse;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
@@ -1124,6 +1125,23 @@ pub(crate) async fn apply_bespoke_event_handling(
                     .await;
             }
         }
+        EventMsg::PinnedMessagesUpdated(pinned_event) => {
+            if let ApiVersion::V2 = api_version {
+                let notification = ThreadPinnedMessagesUpdatedNotification {
+                    thread_id: conversation_id.to_string(),
+                    pinned_messages: pinned_event
+                        .pinned_messages
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                };
+                outgoing
+                    .send_server_notification(ServerNotification::ThreadPinnedMessagesUpdated(
+                        notification,
+                    ))
+                    .await;
+            }
+        }
         EventMsg::TurnDiff(turn_diff_event) => {
             handle_turn_diff(
                 conversation_id,
@@ -1577,18 +1595,31 @@ fn convert_patch_changes(changes: &HashMap<PathBuf, CoreFileChange>) -> Vec<File
 
 fn map_patch_change_kind(change: &CoreFileChange) -> V2PatchChangeKind {
     match change {
-        CoreFileChange::Add { .. } => V2PatchChangeKind::Add,
+        CoreFileChange::Add { .. }
+        | CoreFileChange::AddBinary { .. }
+        | CoreFileChange::AddSymlink { .. } => V2PatchChangeKind::Add,
         CoreFileChange::Delete { .. } => V2PatchChangeKind::Delete,
         CoreFileChange::Update { move_path, .. } => V2PatchChangeKind::Update {
             move_path: move_path.clone(),
         },
+        CoreFileChange::ModeChange { .. } => V2PatchChangeKind::Update { move_path: None },
     }
 }
 
 fn format_file_change_diff(change: &CoreFileChange) -> String {
     match change {
         CoreFileChange::Add { content } => content.clone(),
+        CoreFileChange::AddBinary { size_bytes } => format!("Binary file ({size_bytes} bytes)"),
+        CoreFileChange::AddSymlink { target } => format!("Symlink to {}", target.display()),
         CoreFileChange::Delete { content } => content.clone(),
+        CoreFileChange::ModeChange { executable } => {
+            let (old_mode, new_mode) = if *executable {
+                ("100644", "100755")
+            } else {
+                ("100755", "100644")
+            };
+            format!("old mode {old_mode}\nnew mode {new_mode}")
+        }
         CoreFileChange::Update {
             unified_diff,
             move_path,
@@ -113,6 +113,8 @@ use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadLoadedListParams;
 use codex_app_server_protocol::ThreadLoadedListResponse;
+use codex_app_server_protocol::ThreadPinParams;
+use codex_app_server_protocol::ThreadPinResponse;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadReadResponse;
 use codex_app_server_protocol::ThreadResumeParams;
@@ -127,6 +129,8 @@ use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
 use codex_app_server_protocol::ThreadUnarchiveParams;
 use codex_app_server_protocol::ThreadUnarchiveResponse;
+use codex_app_server_protocol::ThreadUnpinParams;
+use codex_app_server_protocol::ThreadUnpinResponse;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
@@ -376,6 +380,8 @@ impl CodexMessageProcessor {
 
     fn review_request_from_target(
         target: ApiReviewTarget,
+        only_changed_lines: bool,
+        baseline: Option<PathBuf>,
     ) -> Result<(ReviewRequest, String), JSONRPCErrorError> {
         fn invalid_request(message: String) -> JSONRPCErrorError {
             JSONRPCErrorError {
@@ -415,6 +421,32 @@ impl CodexMessageProcessor {
                     instructions: trimmed,
                 }
             }
+            ApiReviewTarget::CommitRange { base, head } => {
+                let base = base.trim().to_string();
+                let head = head.trim().to_string();
+                if base.is_empty() || head.is_empty() {
+                    return Err(invalid_request(
+                        "base and head must not be empty".to_string(),
+                    ));
+                }
+                ApiReviewTarget::CommitRange { base, head }
+            }
+            ApiReviewTarget::PatchFile { path } => {
+                if path.as_os_str().is_empty() {
+                    return Err(invalid_request("path must not be empty".to_string()));
+                }
+                ApiReviewTarget::PatchFile { path }
+            }
+            ApiReviewTarget::Paths { paths } => {
+                let paths: Vec<_> = paths
+                    .into_iter()
+                    .filter(|path| !path.as_os_str().is_empty())
+                    .collect();
+                if paths.is_empty() {
+                    return Err(invalid_request("paths must not be empty".to_string()));
+                }
+                ApiReviewTarget::Paths { paths }
+            }
         };
 
         let core_target = match cleaned_target {
@@ -422,12 +454,19 @@ impl CodexMessageProcessor {
             ApiReviewTarget::BaseBranch { branch } => CoreReviewTarget::BaseBranch { branch },
             ApiReviewTarget::Commit { sha, title } => CoreReviewTarget::Commit { sha, title },
             ApiReviewTarget::Custom { instructions } => CoreReviewTarget::Custom { instructions },
+            ApiReviewTarget::CommitRange { base, head } => {
+                CoreReviewTarget::CommitRange { base, head }
+            }
+            ApiReviewTarget::PatchFile { path } => CoreReviewTarget::PatchFile { path },
+            ApiReviewTarget::Paths { paths } => CoreReviewTarget::Paths { paths },
         };
 
         let hint = codex_core::review_prompts::user_facing_hint(&core_target);
         let review_request = ReviewRequest {
             target: core_target,
             user_facing_hint: Some(hint.clone()),
+            only_changed_lines,
+            baseline,
         };
 
         Ok((review_request, hint))
@@ -454,6 +493,12 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadSetName { request_id, params } => {
                 self.thread_set_name(request_id, params).await;
             }
+            ClientRequest::ThreadPin { request_id, params } => {
+                self.thread_pin(request_id, params).await;
+            }
+            ClientRequest::ThreadUnpin { request_id, params } => {
+                self.thread_unpin(request_id, params).await;
+            }
             ClientRequest::ThreadUnarchive { request_id, params } => {
                 self.thread_unarchive(request_id, params).await;
             }
@@ -520,10 +565,17 @@ impl CodexMessageProcessor {
             ClientRequest::CollaborationModeList { request_id, params } => {
                 let outgoing = self.outgoing.clone();
                 let thread_manager = self.thread_manager.clone();
+                let config = self.config.clone();
 
                 tokio::spawn(async move {
-                    Self::list_collaboration_modes(outgoing, thread_manager, request_id, params)
-                        .await;
+                    Self::list_collaboration_modes(
+                        outgoing,
+                        thread_manager,
+                        config,
+                        request_id,
+                        params,
+                    )
+                    .await;
                 });
             }
             ClientRequest::MockExperimentalMethod { request_id, params } => {
@@ -1881,6 +1933,48 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn thread_pin(&self, request_id: RequestId, params: ThreadPinParams) {
+        let ThreadPinParams { thread_id, item_id } = params;
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::PinMessage { item_id }).await {
+            self.send_internal_error(request_id, format!("failed to pin message: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadPinResponse {})
+            .await;
+    }
+
+    async fn thread_unpin(&self, request_id: RequestId, params: ThreadUnpinParams) {
+        let ThreadUnpinParams { thread_id, pin_id } = params;
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::UnpinMessage { id: pin_id }).await {
+            self.send_internal_error(request_id, format!("failed to unpin message: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadUnpinResponse {})
+            .await;
+    }
+
     async fn thread_unarchive(&mut self, request_id: RequestId, params: ThreadUnarchiveParams) {
         // TODO(jif) mostly rewrite this using sqlite after phase 1
         let thread_id = match ThreadId::from_string(&params.thread_id) {
@@ -3039,11 +3133,12 @@ impl CodexMessageProcessor {
     async fn list_collaboration_modes(
         outgoing: Arc<OutgoingMessageSender>,
         thread_manager: Arc<ThreadManager>,
+        config: Arc<Config>,
         request_id: RequestId,
         params: CollaborationModeListParams,
     ) {
         let CollaborationModeListParams {} = params;
-        let items = thread_manager.list_collaboration_modes();
+        let items = thread_manager.list_collaboration_modes(&config);
         let response = CollaborationModeListResponse { data: items };
         outgoing.send_response(request_id, response).await;
     }
@@ -4465,6 +4560,8 @@ impl CodexMessageProcessor {
             thread_id,
             target,
             delivery,
+            only_changed_lines,
+            baseline,
         } = params;
         let (parent_thread_id, parent_thread) = match self.load_thread(&thread_id).await {
             Ok(v) => v,
@@ -4474,13 +4571,14 @@ impl CodexMessageProcessor {
             }
         };
 
-        let (review_request, display_text) = match Self::review_request_from_target(target) {
-            Ok(value) => value,
-            Err(err) => {
-                self.outgoing.send_error(request_id, err).await;
-                return;
-            }
-        };
+        let (review_request, display_text) =
+            match Self::review_request_from_target(target, only_changed_lines, baseline) {
+                Ok(value) => value,
+                Err(err) => {
+                    self.outgoing.send_error(request_id, err).await;
+                    return;
+                }
+            };
 
         let delivery = delivery.unwrap_or(ApiReviewDelivery::Inline).to_core();
         match delivery {
@@ -18,6 +18,7 @@ use codex_app_server_protocol::RequestId;
 use codex_core::models_manager::test_builtin_collaboration_mode_presets;
 use codex_protocol::config_types::CollaborationModeMask;
 use codex_protocol::config_types::ModeKind;
+use codex_protocol::openai_models::ReasoningEffort;
 use pretty_assertions::assert_eq;
 use tempfile::TempDir;
 use tokio::time::timeout;
@@ -50,6 +51,59 @@ async fn list_collaboration_modes_returns_presets() -> Result<()> {
     Ok(())
 }
 
+/// Confirms user-defined modes from `config.toml` are listed after the built-in presets.
+#[tokio::test]
+async fn list_collaboration_modes_includes_custom_modes() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+[collaboration_modes.review-only]
+base = "plan"
+developer_instructions = "Do not edit files."
+model_reasoning_effort = "high"
+shortcut = "r"
+"#,
+    )?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_list_collaboration_modes_request(CollaborationModeListParams {})
+        .await?;
+
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    let CollaborationModeListResponse { data: items } =
+        to_response::<CollaborationModeListResponse>(response)?;
+
+    let plan_instructions = plan_preset()
+        .developer_instructions
+        .flatten()
+        .unwrap_or_default();
+    let expected = vec![
+        plan_preset(),
+        default_preset(),
+        CollaborationModeMask {
+            name: "review-only".to_string(),
+            mode: Some(ModeKind::Plan),
+            model: None,
+            reasoning_effort: Some(Some(ReasoningEffort::High)),
+            developer_instructions: Some(Some(format!(
+                "{plan_instructions}\n\nDo not edit files."
+            ))),
+            custom_mode: Some("review-only".to_string()),
+        },
+    ];
+    assert_eq!(expected, items);
+    Ok(())
+}
+
 /// Builds the plan preset that the list response is expected to return.
 ///
 /// If the defaults change in the app server, this helper should be updated alongside the
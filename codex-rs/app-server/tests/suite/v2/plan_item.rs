@@ -143,6 +143,7 @@ async fn start_plan_mode_turn(mcp: &mut McpProcess) -> Result<codex_app_server_p
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     };
     let turn_req = mcp
         .send_turn_start_request(TurnStartParams {
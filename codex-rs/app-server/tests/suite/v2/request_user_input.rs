@@ -62,6 +62,7 @@ async fn request_user_input_round_trip() -> Result<()> {
                     reasoning_effort: Some(ReasoningEffort::Medium),
                     developer_instructions: None,
                 },
+                custom_mode: None,
             }),
             ..Default::default()
         })
@@ -65,6 +65,8 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
                 sha: "1234567deadbeef".to_string(),
                 title: Some("Tidy UI colors".to_string()),
             },
+            only_changed_lines: false,
+            baseline: None,
         })
         .await?;
     let review_resp: JSONRPCResponse = timeout(
@@ -166,6 +168,8 @@ async fn review_start_exec_approval_item_id_matches_command_execution_item() ->
                 sha: "1234567deadbeef".to_string(),
                 title: Some("Check review approvals".to_string()),
             },
+            only_changed_lines: false,
+            baseline: None,
         })
         .await?;
     let review_resp: JSONRPCResponse = timeout(
@@ -235,6 +239,8 @@ async fn review_start_rejects_empty_base_branch() -> Result<()> {
             target: ReviewTarget::BaseBranch {
                 branch: "   ".to_string(),
             },
+            only_changed_lines: false,
+            baseline: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
@@ -278,6 +284,8 @@ async fn review_start_with_detached_delivery_returns_new_thread_id() -> Result<(
             target: ReviewTarget::Custom {
                 instructions: "detached review".to_string(),
             },
+            only_changed_lines: false,
+            baseline: None,
         })
         .await?;
     let review_resp: JSONRPCResponse = timeout(
@@ -317,6 +325,8 @@ async fn review_start_rejects_empty_commit_sha() -> Result<()> {
                 sha: "\t".to_string(),
                 title: None,
             },
+            only_changed_lines: false,
+            baseline: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
@@ -351,6 +361,8 @@ async fn review_start_rejects_empty_custom_instructions() -> Result<()> {
             target: ReviewTarget::Custom {
                 instructions: "\n\n".to_string(),
             },
+            only_changed_lines: false,
+            baseline: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_responses_server_sequence_unchecked;
+use app_test_support::to_response;
+use codex_app_server_protocol::ItemCompletedNotification;
+use codex_app_server_protocol::JSONRPCNotification;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::PinnedMessage;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadPinParams;
+use codex_app_server_protocol::ThreadPinResponse;
+use codex_app_server_protocol::ThreadPinnedMessagesUpdatedNotification;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::ThreadUnpinParams;
+use codex_app_server_protocol::ThreadUnpinResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_pin_and_unpin_update_the_pinned_messages() -> Result<()> {
+    let responses = vec![create_final_assistant_message_sse_response(
+        "Use tabs for indentation.",
+    )?];
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+
+    let turn_id = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread.id.clone(),
+            input: vec![V2UserInput::Text {
+                text: "How should I indent?".to_string(),
+                text_elements: Vec::new(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    let _turn_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_id)),
+    )
+    .await??;
+    let agent_message_id = loop {
+        let notification: JSONRPCNotification = timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_notification_message("item/completed"),
+        )
+        .await??;
+        let completed: ItemCompletedNotification =
+            serde_json::from_value(notification.params.ok_or_else(|| {
+                anyhow::anyhow!("item/completed notifications must include params")
+            })?)?;
+        if let ThreadItem::AgentMessage { id, .. } = completed.item {
+            break id;
+        }
+    };
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+
+    let pin_id = mcp
+        .send_thread_pin_request(ThreadPinParams {
+            thread_id: thread.id.clone(),
+            item_id: agent_message_id.clone(),
+        })
+        .await?;
+    let pin_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(pin_id)),
+    )
+    .await??;
+    let ThreadPinResponse {} = to_response::<ThreadPinResponse>(pin_resp)?;
+    let pinned = read_pinned_messages_updated(&mut mcp).await?;
+    assert_eq!(
+        pinned,
+        ThreadPinnedMessagesUpdatedNotification {
+            thread_id: thread.id.clone(),
+            pinned_messages: vec![PinnedMessage {
+                id: agent_message_id.clone(),
+                text: "Use tabs for indentation.".to_string(),
+            }],
+        }
+    );
+
+    let unpin_id = mcp
+        .send_thread_unpin_request(ThreadUnpinParams {
+            thread_id: thread.id.clone(),
+            pin_id: agent_message_id,
+        })
+        .await?;
+    let unpin_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(unpin_id)),
+    )
+    .await??;
+    let ThreadUnpinResponse {} = to_response::<ThreadUnpinResponse>(unpin_resp)?;
+    let unpinned = read_pinned_messages_updated(&mut mcp).await?;
+    assert_eq!(
+        unpinned,
+        ThreadPinnedMessagesUpdatedNotification {
+            thread_id: thread.id,
+            pinned_messages: Vec::new(),
+        }
+    );
+
+    Ok(())
+}
+
+async fn read_pinned_messages_updated(
+    mcp: &mut McpProcess,
+) -> Result<ThreadPinnedMessagesUpdatedNotification> {
+    let notification: JSONRPCNotification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("thread/pinnedMessages/updated"),
+    )
+    .await??;
+    let params = notification.params.ok_or_else(|| {
+        anyhow::anyhow!("thread/pinnedMessages/updated notifications must include params")
+    })?;
+    Ok(serde_json::from_value(params)?)
+}
+
+fn create_config_toml(codex_home: &std::path::Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
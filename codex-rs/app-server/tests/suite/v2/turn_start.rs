@@ -371,6 +371,7 @@ async fn turn_start_accepts_collaboration_mode_override_v2() -> Result<()> {
             reasoning_effort: Some(ReasoningEffort::High),
             developer_instructions: None,
         },
+        custom_mode: None,
     };
 
     let turn_req = mcp
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use tree_sitter::Parser;
@@ -15,6 +16,7 @@ use crate::ApplyPatchFileChange;
 use crate::ApplyPatchFileUpdate;
 use crate::IoError;
 use crate::MaybeApplyPatchVerified;
+use crate::parser::FileMode;
 use crate::parser::Hunk;
 use crate::parser::ParseError;
 use crate::parser::parse_patch;
@@ -165,7 +167,30 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                 let path = hunk.resolve_path(&effective_cwd);
                 match hunk {
                     Hunk::AddFile { contents, .. } => {
-                        changes.insert(path, ApplyPatchFileChange::Add { content: contents });
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::Add {
+                                content: contents,
+                                mode: None,
+                            },
+                        );
+                    }
+                    Hunk::AddBinaryFile { contents, .. } => {
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::AddBinary {
+                                content: contents,
+                                mode: None,
+                            },
+                        );
+                    }
+                    Hunk::AddSymlink { target, .. } => {
+                        changes.insert(path, ApplyPatchFileChange::AddSymlink { target });
+                    }
+                    Hunk::SetMode { mode, .. } => {
+                        if let Err(e) = record_mode_change(&mut changes, path, mode) {
+                            return MaybeApplyPatchVerified::CorrectnessError(e);
+                        }
                     }
                     Hunk::DeleteFile { .. } => {
                         let content = match std::fs::read_to_string(&path) {
@@ -199,6 +224,7 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                                 unified_diff,
                                 move_path: move_path.map(|p| effective_cwd.join(p)),
                                 new_content: contents,
+                                mode: None,
                             },
                         );
                     }
@@ -216,6 +242,44 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
     }
 }
 
+/// Folds a `*** Set Mode` hunk into the change already recorded for `path`
+/// (including the destination of a move) so that each path maps to a single
+/// change.
+fn record_mode_change(
+    changes: &mut HashMap<PathBuf, ApplyPatchFileChange>,
+    path: PathBuf,
+    new_mode: FileMode,
+) -> Result<(), ApplyPatchError> {
+    let key = if changes.contains_key(&path) {
+        Some(path.clone())
+    } else {
+        changes.iter().find_map(|(source, change)| match change {
+            ApplyPatchFileChange::Update {
+                move_path: Some(dest),
+                ..
+            } if dest == &path => Some(source.clone()),
+            _ => None,
+        })
+    };
+    let Some(change) = key.and_then(|key| changes.get_mut(&key)) else {
+        changes.insert(path, ApplyPatchFileChange::SetMode { mode: new_mode });
+        return Ok(());
+    };
+    match change {
+        ApplyPatchFileChange::Add { mode, .. }
+        | ApplyPatchFileChange::AddBinary { mode, .. }
+        | ApplyPatchFileChange::Update { mode, .. } => *mode = Some(new_mode),
+        ApplyPatchFileChange::SetMode { mode } => *mode = new_mode,
+        ApplyPatchFileChange::AddSymlink { .. } | ApplyPatchFileChange::Delete { .. } => {
+            return Err(ApplyPatchError::ComputeReplacements(format!(
+                "Cannot set mode of {}: it is a symlink or is being deleted",
+                path.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Extract the heredoc body (and optional `cd` workdir) from a `bash -lc` script
 /// that invokes the apply_patch tool using a heredoc.
 ///
@@ -756,6 +820,7 @@ PATCH"#,
                         .to_string(),
                         move_path: None,
                         new_content: "updated session directory content\n".to_string(),
+                        mode: None,
                     },
                 )]),
                 patch: argv[1].clone(),
@@ -764,6 +829,57 @@ PATCH"#,
         );
     }
 
+    #[test]
+    fn test_set_mode_is_folded_into_existing_changes() {
+        let session_dir = tempdir().unwrap();
+        fs::write(session_dir.path().join("old.sh"), "echo old\n").unwrap();
+        fs::write(session_dir.path().join("plain.sh"), "echo plain\n").unwrap();
+
+        let argv = vec![
+            "apply_patch".to_string(),
+            wrap_patch(
+                r#"*** Add File: new.sh
++echo new
+*** Set Mode: 100755 new.sh
+*** Update File: old.sh
+*** Move to: moved.sh
+@@
+-echo old
++echo moved
+*** Set Mode: 755 moved.sh
+*** Set Mode: 100755 plain.sh"#,
+            ),
+        ];
+
+        let action = match maybe_parse_apply_patch_verified(&argv, session_dir.path()) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected verified body, got {other:?}"),
+        };
+        let changes = action.changes();
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes.get(&session_dir.path().join("new.sh")),
+            Some(&ApplyPatchFileChange::Add {
+                content: "echo new\n".to_string(),
+                mode: Some(FileMode::Executable),
+            })
+        );
+        assert_matches!(
+            changes.get(&session_dir.path().join("old.sh")),
+            Some(ApplyPatchFileChange::Update {
+                mode: Some(FileMode::Executable),
+                ..
+            })
+        );
+        assert_eq!(
+            changes.get(&session_dir.path().join("plain.sh")),
+            Some(&ApplyPatchFileChange::SetMode {
+                mode: FileMode::Executable
+            })
+        );
+    }
+
     #[test]
     fn test_apply_patch_resolves_move_path_with_effective_cwd() {
         let session_dir = tempdir().unwrap();
@@ -2,13 +2,14 @@ mod invocation;
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod transaction;
 
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 
-use anyhow::Context;
 use anyhow::Result;
+pub use parser::FileMode;
 pub use parser::Hunk;
 pub use parser::ParseError;
 use parser::ParseError::*;
@@ -86,6 +87,15 @@ pub struct ApplyPatchArgs {
 pub enum ApplyPatchFileChange {
     Add {
         content: String,
+        /// Mode set by a `*** Set Mode` hunk for the same path, if any.
+        mode: Option<FileMode>,
+    },
+    AddBinary {
+        content: Vec<u8>,
+        mode: Option<FileMode>,
+    },
+    AddSymlink {
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -95,6 +105,11 @@ pub enum ApplyPatchFileChange {
         move_path: Option<PathBuf>,
         /// new_content that will result after the unified_diff is applied.
         new_content: String,
+        mode: Option<FileMode>,
+    },
+    /// Only the file mode changes; the contents are left untouched.
+    SetMode {
+        mode: FileMode,
     },
 }
 
@@ -157,7 +172,13 @@ impl ApplyPatchAction {
 + {content}
 *** End Patch"#,
         );
-        let changes = HashMap::from([(path.to_path_buf(), ApplyPatchFileChange::Add { content })]);
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::Add {
+                content,
+                mode: None,
+            },
+        )]);
         #[expect(clippy::expect_used)]
         Self {
             changes,
@@ -212,11 +233,11 @@ pub fn apply_hunks(
     let _existing_paths: Vec<&Path> = hunks
         .iter()
         .filter_map(|hunk| match hunk {
-            Hunk::AddFile { .. } => {
+            Hunk::AddFile { .. } | Hunk::AddBinaryFile { .. } | Hunk::AddSymlink { .. } => {
                 // The file is being added, so it doesn't exist yet.
                 None
             }
-            Hunk::DeleteFile { path } => Some(path.as_path()),
+            Hunk::DeleteFile { path } | Hunk::SetMode { path, .. } => Some(path.as_path()),
             Hunk::UpdateFile {
                 path, move_path, ..
             } => match move_path {
@@ -266,67 +287,15 @@ pub struct AffectedPaths {
 }
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
-/// Returns an error if the patch could not be applied.
+/// Every hunk is validated before anything is written, and a failure while
+/// writing restores the files touched so far, so the patch is applied in full
+/// or not at all.
 fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
 
-    let mut added: Vec<PathBuf> = Vec::new();
-    let mut modified: Vec<PathBuf> = Vec::new();
-    let mut deleted: Vec<PathBuf> = Vec::new();
-    for hunk in hunks {
-        match hunk {
-            Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
-                std::fs::write(path, contents)
-                    .with_context(|| format!("Failed to write file {}", path.display()))?;
-                added.push(path.clone());
-            }
-            Hunk::DeleteFile { path } => {
-                std::fs::remove_file(path)
-                    .with_context(|| format!("Failed to delete file {}", path.display()))?;
-                deleted.push(path.clone());
-            }
-            Hunk::UpdateFile {
-                path,
-                move_path,
-                chunks,
-            } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
-                if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
-                    std::fs::write(dest, new_contents)
-                        .with_context(|| format!("Failed to write file {}", dest.display()))?;
-                    std::fs::remove_file(path)
-                        .with_context(|| format!("Failed to remove original {}", path.display()))?;
-                    modified.push(dest.clone());
-                } else {
-                    std::fs::write(path, new_contents)
-                        .with_context(|| format!("Failed to write file {}", path.display()))?;
-                    modified.push(path.clone());
-                }
-            }
-        }
-    }
-    Ok(AffectedPaths {
-        added,
-        modified,
-        deleted,
-    })
+    transaction::Transaction::stage(hunks)?.commit()
 }
 
 struct AppliedPatch {
@@ -349,7 +318,15 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    apply_chunks_to_contents(original_contents, path, chunks)
+}
 
+/// Apply the chunks to `original_contents`, which were read from `path`.
+fn apply_chunks_to_contents(
+    original_contents: String,
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
 
     // Drop the trailing empty element that results from the final newline so
@@ -382,7 +359,19 @@ fn compute_replacements(
     let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
     let mut line_index: usize = 0;
 
-    for chunk in chunks {
+    for (chunk_idx, chunk) in chunks.iter().enumerate() {
+        // Identify the failing chunk when an update has several of them.
+        let location = if chunks.len() > 1 {
+            format!(
+                "{} (chunk {} of {})",
+                path.display(),
+                chunk_idx + 1,
+                chunks.len()
+            )
+        } else {
+            path.display().to_string()
+        };
+
         // If a chunk has a `change_context`, we use seek_sequence to find it, then
         // adjust our `line_index` to continue from there.
         if let Some(ctx_line) = &chunk.change_context {
@@ -394,11 +383,14 @@ fn compute_replacements(
             ) {
                 line_index = idx + 1;
             } else {
-                return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
-                    ctx_line,
-                    path.display()
-                )));
+                let message = format!("Failed to find context '{ctx_line}' in {location}");
+                return Err(ApplyPatchError::ComputeReplacements(
+                    with_closest_match_hint(
+                        message,
+                        original_lines,
+                        std::slice::from_ref(ctx_line),
+                    ),
+                ));
             }
         }
 
@@ -451,11 +443,13 @@ fn compute_replacements(
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
             line_index = start_idx + pattern.len();
         } else {
-            return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
-                path.display(),
+            let message = format!(
+                "Failed to find expected lines in {location}:\n{}",
                 chunk.old_lines.join("\n"),
-            )));
+            );
+            return Err(ApplyPatchError::ComputeReplacements(
+                with_closest_match_hint(message, original_lines, pattern),
+            ));
         }
     }
 
@@ -464,6 +458,19 @@ fn compute_replacements(
     Ok(replacements)
 }
 
+/// Append the region of the file that most closely resembles `pattern` to
+/// `message` so the caller can correct the patch without re-reading the file.
+fn with_closest_match_hint(message: String, lines: &[String], pattern: &[String]) -> String {
+    match seek_sequence::nearest_sequence(lines, pattern) {
+        Some(idx) => format!(
+            "{message}\nClosest match at line {}:\n{}",
+            idx + 1,
+            lines[idx..idx + pattern.len()].join("\n")
+        ),
+        None => message,
+    }
+}
+
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
 /// returning the modified file contents as a vector of lines.
 fn apply_replacements(
@@ -1062,4 +1069,210 @@ g
         let result = apply_patch(&patch, &mut stdout, &mut stderr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_add_binary_file_writes_decoded_bytes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fixtures/blob.bin");
+        let patch = wrap_patch(&format!(
+            "*** Add Binary File: {}\n+AAEC\n+/w==",
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), vec![0, 1, 2, 255]);
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nA {}\n",
+                path.display()
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_add_symlink_and_set_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        let link = dir.path().join("bin/run");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+        let patch = wrap_patch(&format!(
+            "*** Add Symlink: {}\n+../run.sh\n*** Set Mode: 100755 {}",
+            link.display(),
+            script.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("../run.sh"));
+        assert_eq!(
+            fs::metadata(&script).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nA {}\nM {}\n",
+                link.display(),
+                script.display()
+            )
+        );
+
+        let patch = wrap_patch(&format!("*** Set Mode: 644 {}", script.display()));
+        apply_patch(&patch, &mut Vec::new(), &mut Vec::new()).unwrap();
+        assert_eq!(
+            fs::metadata(&script).unwrap().permissions().mode() & 0o777,
+            0o644
+        );
+    }
+
+    #[test]
+    fn test_failed_hunk_leaves_earlier_files_untouched() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        let created = dir.path().join("created.txt");
+        fs::write(&first, "alpha\n").unwrap();
+        fs::write(&second, "fn main() {\n    let total = 1;\n}\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Add File: {}
++hello
+*** Update File: {}
+@@
+-alpha
++beta
+*** Update File: {}
+@@
+-    let totl = 1;
++    let total = 2;"#,
+            created.display(),
+            first.display(),
+            second.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, &mut stdout, &mut stderr);
+
+        assert!(result.is_err());
+        assert!(!created.exists());
+        assert_eq!(fs::read_to_string(&first).unwrap(), "alpha\n");
+        assert_eq!(String::from_utf8(stdout).unwrap(), "");
+        assert_eq!(
+            String::from_utf8(stderr).unwrap(),
+            format!(
+                "Failed to find expected lines in {}:\n    let totl = 1;\nClosest match at line 2:\n    let total = 1;\n",
+                second.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_every_failing_hunk_is_reported() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("multi.txt");
+        let missing = dir.path().join("missing.txt");
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+-one
++uno
+@@
+-four
++cuatro
+*** Delete File: {}"#,
+            path.display(),
+            missing.display()
+        ));
+
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, &mut Vec::new(), &mut stderr);
+
+        assert!(result.is_err());
+        assert_eq!(
+            String::from_utf8(stderr).unwrap(),
+            format!(
+                "Failed to find expected lines in {} (chunk 2 of 2):\nfour\nFailed to delete file {}\n",
+                path.display(),
+                missing.display()
+            )
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn test_write_failure_rolls_back_committed_files() {
+        let dir = tempdir().unwrap();
+        let updated = dir.path().join("updated.txt");
+        let deleted = dir.path().join("deleted.txt");
+        let blocker = dir.path().join("blocker");
+        fs::write(&updated, "before\n").unwrap();
+        fs::write(&deleted, "keep me\n").unwrap();
+        fs::write(&blocker, "not a directory\n").unwrap();
+        // `blocker` is a file, so creating `blocker/nested/child.txt` fails
+        // only once the earlier hunks have been written.
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+-before
++after
+*** Delete File: {}
+*** Add File: {}
++child"#,
+            updated.display(),
+            deleted.display(),
+            blocker.join("nested/child.txt").display()
+        ));
+
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, &mut Vec::new(), &mut stderr);
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&updated).unwrap(), "before\n");
+        assert_eq!(fs::read_to_string(&deleted).unwrap(), "keep me\n");
+        assert_eq!(fs::read_to_string(&blocker).unwrap(), "not a directory\n");
+        let stderr = String::from_utf8(stderr).unwrap();
+        assert!(
+            stderr.ends_with("No changes were applied.\n"),
+            "unexpected stderr: {stderr}"
+        );
+        let leftovers: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers.len(), 3, "unexpected files: {leftovers:?}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_update_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        fs::write(&script, "echo before\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o750)).unwrap();
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-echo before\n+echo after",
+            script.display()
+        ));
+
+        apply_patch(&patch, &mut Vec::new(), &mut Vec::new()).unwrap();
+
+        assert_eq!(fs::read_to_string(&script).unwrap(), "echo after\n");
+        assert_eq!(
+            fs::metadata(&script).unwrap().permissions().mode() & 0o777,
+            0o750
+        );
+    }
 }
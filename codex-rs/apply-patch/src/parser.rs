@@ -7,12 +7,17 @@
 //! begin_patch: "*** Begin Patch" LF
 //! end_patch: "*** End Patch" LF?
 //!
-//! hunk: add_hunk | delete_hunk | update_hunk
+//! hunk: add_hunk | add_binary_hunk | add_symlink_hunk | delete_hunk | update_hunk | set_mode_hunk
 //! add_hunk: "*** Add File: " filename LF add_line+
+//! add_binary_hunk: "*** Add Binary File: " filename LF binary_line+
+//! add_symlink_hunk: "*** Add Symlink: " filename LF add_line
 //! delete_hunk: "*** Delete File: " filename LF
 //! update_hunk: "*** Update File: " filename LF change_move? change?
+//! set_mode_hunk: "*** Set Mode: " mode " " filename LF
 //! filename: /(.+)/
 //! add_line: "+" /(.+)/ LF -> line
+//! binary_line: "+" /([A-Za-z0-9+\/=]*)/ LF
+//! mode: "100644" | "100755" | "644" | "755"
 //!
 //! change_move: "*** Move to: " filename LF
 //! change: (change_context | change_line)+ eof_line?
@@ -23,6 +28,7 @@
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
 use crate::ApplyPatchArgs;
+use base64::Engine;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -31,6 +37,9 @@ use thiserror::Error;
 const BEGIN_PATCH_MARKER: &str = "*** Begin Patch";
 const END_PATCH_MARKER: &str = "*** End Patch";
 const ADD_FILE_MARKER: &str = "*** Add File: ";
+const ADD_BINARY_FILE_MARKER: &str = "*** Add Binary File: ";
+const ADD_SYMLINK_MARKER: &str = "*** Add Symlink: ";
+const SET_MODE_MARKER: &str = "*** Set Mode: ";
 const DELETE_FILE_MARKER: &str = "*** Delete File: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
@@ -62,6 +71,15 @@ pub enum Hunk {
         path: PathBuf,
         contents: String,
     },
+    /// A file whose contents were supplied base64-encoded in the patch.
+    AddBinaryFile {
+        path: PathBuf,
+        contents: Vec<u8>,
+    },
+    AddSymlink {
+        path: PathBuf,
+        target: PathBuf,
+    },
     DeleteFile {
         path: PathBuf,
     },
@@ -73,14 +91,47 @@ pub enum Hunk {
         /// should occur later in the file than the previous chunk.
         chunks: Vec<UpdateFileChunk>,
     },
+    SetMode {
+        path: PathBuf,
+        mode: FileMode,
+    },
 }
 
 impl Hunk {
     pub fn resolve_path(&self, cwd: &Path) -> PathBuf {
         match self {
             Hunk::AddFile { path, .. } => cwd.join(path),
+            Hunk::AddBinaryFile { path, .. } => cwd.join(path),
+            Hunk::AddSymlink { path, .. } => cwd.join(path),
             Hunk::DeleteFile { path } => cwd.join(path),
             Hunk::UpdateFile { path, .. } => cwd.join(path),
+            Hunk::SetMode { path, .. } => cwd.join(path),
+        }
+    }
+}
+
+/// The file modes a patch can set. Like git, only the executable bit is
+/// tracked.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FileMode {
+    Regular,
+    Executable,
+}
+
+impl FileMode {
+    fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "100644" | "644" => Some(FileMode::Regular),
+            "100755" | "755" => Some(FileMode::Executable),
+            _ => None,
+        }
+    }
+
+    /// Git-style octal representation, e.g. `100755`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileMode::Regular => "100644",
+            FileMode::Executable => "100755",
         }
     }
 }
@@ -268,6 +319,79 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             },
             parsed_lines,
         ));
+    } else if let Some(path) = first_line.strip_prefix(ADD_BINARY_FILE_MARKER) {
+        // Add Binary File
+        let mut encoded = String::new();
+        let mut parsed_lines = 1;
+        for add_line in &lines[1..] {
+            if let Some(chunk) = add_line.strip_prefix('+') {
+                encoded.push_str(chunk.trim());
+                parsed_lines += 1;
+            } else {
+                break;
+            }
+        }
+        if parsed_lines == 1 {
+            return Err(InvalidHunkError {
+                message: format!("Add binary file hunk for path '{path}' is empty"),
+                line_number,
+            });
+        }
+        let contents = base64::engine::general_purpose::STANDARD
+            .decode(encoded.as_bytes())
+            .map_err(|err| InvalidHunkError {
+                message: format!("Invalid base64 contents for binary file '{path}': {err}"),
+                line_number,
+            })?;
+        return Ok((
+            AddBinaryFile {
+                path: PathBuf::from(path),
+                contents,
+            },
+            parsed_lines,
+        ));
+    } else if let Some(path) = first_line.strip_prefix(ADD_SYMLINK_MARKER) {
+        // Add Symlink
+        let Some(target) = lines
+            .get(1)
+            .and_then(|line| line.strip_prefix('+'))
+            .filter(|target| !target.is_empty())
+        else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Add symlink hunk for path '{path}' must be followed by a single '+<target>' line"
+                ),
+                line_number,
+            });
+        };
+        return Ok((
+            AddSymlink {
+                path: PathBuf::from(path),
+                target: PathBuf::from(target),
+            },
+            2,
+        ));
+    } else if let Some(rest) = first_line.strip_prefix(SET_MODE_MARKER) {
+        // Set Mode
+        let parsed = rest
+            .split_once(' ')
+            .and_then(|(mode, path)| Some((FileMode::parse(mode)?, path.trim())))
+            .filter(|(_, path)| !path.is_empty());
+        let Some((mode, path)) = parsed else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "'{first_line}' is not a valid set mode hunk. Expected '*** Set Mode: <100644|100755> {{path}}'"
+                ),
+                line_number,
+            });
+        };
+        return Ok((
+            SetMode {
+                path: PathBuf::from(path),
+                mode,
+            },
+            1,
+        ));
     } else if let Some(path) = first_line.strip_prefix(DELETE_FILE_MARKER) {
         // Delete File
         return Ok((
@@ -334,7 +458,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
     Err(InvalidHunkError {
         message: format!(
-            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
+            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Add Binary File: {{path}}', '*** Add Symlink: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}', '*** Set Mode: {{mode}} {{path}}'"
         ),
         line_number,
     })
@@ -675,13 +799,81 @@ fn test_parse_one_hunk() {
         parse_one_hunk(&["bad"], 234),
         Err(InvalidHunkError {
             message: "'bad' is not a valid hunk header. \
-            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
+            Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', \
+            '*** Add Symlink: {path}', '*** Delete File: {path}', '*** Update File: {path}', \
+            '*** Set Mode: {mode} {path}'"
+                .to_string(),
             line_number: 234
         })
     );
     // Other edge cases are already covered by tests above/below.
 }
 
+#[test]
+fn test_parse_binary_symlink_and_mode_hunks() {
+    assert_eq!(
+        parse_patch_text(
+            "*** Begin Patch\n\
+             *** Add Binary File: assets/pixel.bin\n\
+             +AAEC\n\
+             +/w==\n\
+             *** Add Symlink: bin/tool\n\
+             +../scripts/tool.sh\n\
+             *** Set Mode: 100755 scripts/tool.sh\n\
+             *** Set Mode: 644 docs/readme with spaces.md\n\
+             *** End Patch",
+            ParseMode::Strict
+        )
+        .unwrap()
+        .hunks,
+        vec![
+            AddBinaryFile {
+                path: PathBuf::from("assets/pixel.bin"),
+                contents: vec![0, 1, 2, 255],
+            },
+            AddSymlink {
+                path: PathBuf::from("bin/tool"),
+                target: PathBuf::from("../scripts/tool.sh"),
+            },
+            SetMode {
+                path: PathBuf::from("scripts/tool.sh"),
+                mode: FileMode::Executable,
+            },
+            SetMode {
+                path: PathBuf::from("docs/readme with spaces.md"),
+                mode: FileMode::Regular,
+            },
+        ]
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add Binary File: x.bin", "+not base64!"], 7),
+        Err(InvalidHunkError {
+            message:
+                "Invalid base64 contents for binary file 'x.bin': Invalid symbol 32, offset 3."
+                    .to_string(),
+            line_number: 7
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Set Mode: 777 run.sh"], 3),
+        Err(InvalidHunkError {
+            message: "'*** Set Mode: 777 run.sh' is not a valid set mode hunk. \
+                      Expected '*** Set Mode: <100644|100755> {path}'"
+                .to_string(),
+            line_number: 3
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add Symlink: link", "*** End Patch"], 4),
+        Err(InvalidHunkError {
+            message:
+                "Add symlink hunk for path 'link' must be followed by a single '+<target>' line"
+                    .to_string(),
+            line_number: 4
+        })
+    );
+}
+
 #[test]
 fn test_update_file_chunk() {
     assert_eq!(
@@ -73,26 +73,6 @@ pub(crate) fn seek_sequence(
     // differences when locating context lines.
     // ------------------------------------------------------------------
 
-    fn normalise(s: &str) -> String {
-        s.trim()
-            .chars()
-            .map(|c| match c {
-                // Various dash / hyphen code-points → ASCII '-'
-                '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
-                | '\u{2212}' => '-',
-                // Fancy single quotes → '\''
-                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
-                // Fancy double quotes → '"'
-                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
-                // Non-breaking space and other odd spaces → normal space
-                '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
-                | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
-                | '\u{3000}' => ' ',
-                other => other,
-            })
-            .collect::<String>()
-    }
-
     for i in search_start..=lines.len().saturating_sub(pattern.len()) {
         let mut ok = true;
         for (p_idx, pat) in pattern.iter().enumerate() {
@@ -109,9 +89,101 @@ pub(crate) fn seek_sequence(
     None
 }
 
+fn normalise(s: &str) -> String {
+    s.trim()
+        .chars()
+        .map(|c| match c {
+            // Various dash / hyphen code-points → ASCII '-'
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
+            | '\u{2212}' => '-',
+            // Fancy single quotes → '\''
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+            // Fancy double quotes → '"'
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+            // Non-breaking space and other odd spaces → normal space
+            '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
+            | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
+            | '\u{3000}' => ' ',
+            other => other,
+        })
+        .collect::<String>()
+}
+
+/// Locate the window of `lines` that most closely resembles `pattern`. Used to
+/// point the model at the region it most likely meant when [`seek_sequence`]
+/// finds no match. Returns `None` when nothing is similar enough to be a
+/// useful hint, or when the file is too large to search exhaustively.
+pub(crate) fn nearest_sequence(lines: &[String], pattern: &[String]) -> Option<usize> {
+    const MIN_SIMILARITY: f32 = 0.6;
+    const MAX_LINE_COMPARISONS: usize = 20_000;
+
+    if pattern.is_empty() || pattern.len() > lines.len() {
+        return None;
+    }
+    let windows = lines.len() - pattern.len() + 1;
+    if windows.saturating_mul(pattern.len()) > MAX_LINE_COMPARISONS {
+        return None;
+    }
+
+    let normalised_pattern: Vec<String> = pattern.iter().map(|line| normalise(line)).collect();
+    let normalised_lines: Vec<String> = lines.iter().map(|line| normalise(line)).collect();
+    let pattern_lengths: Vec<usize> = normalised_pattern
+        .iter()
+        .map(|line| line.chars().count())
+        .collect();
+    let line_lengths: Vec<usize> = normalised_lines
+        .iter()
+        .map(|line| line.chars().count())
+        .collect();
+    let mut best: Option<(usize, f32)> = None;
+    for i in 0..windows {
+        // Line lengths alone bound the score, so most windows are rejected
+        // without diffing a single line.
+        let bound: f32 = pattern_lengths
+            .iter()
+            .zip(&line_lengths[i..])
+            .map(|(&pat, &line)| similarity_bound(line, pat))
+            .sum();
+        let bound = bound / pattern.len() as f32;
+        if bound < MIN_SIMILARITY || best.is_some_and(|(_, best_score)| bound <= best_score) {
+            continue;
+        }
+
+        let total: f32 = normalised_pattern
+            .iter()
+            .zip(&normalised_lines[i..])
+            .map(|(pat, line)| line_similarity(line, pat))
+            .sum();
+        let score = total / pattern.len() as f32;
+        if score >= MIN_SIMILARITY && best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((i, score));
+        }
+    }
+    best.map(|(idx, _)| idx)
+}
+
+fn line_similarity(a: &str, b: &str) -> f32 {
+    if a == b {
+        return 1.0;
+    }
+    similar::TextDiff::from_chars(a, b).ratio()
+}
+
+/// Upper bound of [`line_similarity`] for lines of `a` and `b` chars: the
+/// ratio is `2 * matches / (a + b)` and at most `min(a, b)` chars can match.
+fn similarity_bound(a: usize, b: usize) -> f32 {
+    if a + b == 0 {
+        return 1.0;
+    }
+    (2 * a.min(b)) as f32 / (a + b) as f32
+}
+
 #[cfg(test)]
 mod tests {
+    use super::line_similarity;
+    use super::nearest_sequence;
     use super::seek_sequence;
+    use super::similarity_bound;
     use std::string::ToString;
 
     fn to_vec(strings: &[&str]) -> Vec<String> {
@@ -148,4 +220,50 @@ mod tests {
         // Should not panic – must return None when pattern cannot possibly fit.
         assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
     }
+
+    #[test]
+    fn test_nearest_sequence_finds_closest_window() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "    let total = 1;",
+            "    println!(total);",
+            "}",
+        ]);
+        let pattern = to_vec(&["    let totl = 1;", "    println!(totl);"]);
+        assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
+        assert_eq!(nearest_sequence(&lines, &pattern), Some(1));
+    }
+
+    #[test]
+    fn test_nearest_sequence_ignores_unrelated_lines() {
+        let lines = to_vec(&["line1", "line2"]);
+        let pattern = to_vec(&["missing"]);
+        assert_eq!(nearest_sequence(&lines, &pattern), None);
+    }
+
+    #[test]
+    fn test_similarity_bound_is_never_below_line_similarity() {
+        let pairs = [
+            ("", ""),
+            ("", "abc"),
+            ("let total = 1;", "let totl = 1;"),
+            ("println!(total);", "}"),
+            ("abc", "cba"),
+        ];
+        for (a, b) in pairs {
+            let bound = similarity_bound(a.chars().count(), b.chars().count());
+            assert!(
+                line_similarity(a, b) <= bound,
+                "bound {bound} is below the similarity of {a:?} and {b:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_sequence_gives_up_on_large_files() {
+        let lines: Vec<String> = (0..20_000).map(|i| format!("line {i}")).collect();
+        let pattern = to_vec(&["line 17", "line 18"]);
+        assert_eq!(nearest_sequence(&lines, &pattern), None);
+        assert_eq!(nearest_sequence(&lines[..100], &pattern), Some(17));
+    }
 }
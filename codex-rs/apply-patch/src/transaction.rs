@@ -0,0 +1,450 @@
+//! Two-phase application of parsed hunks.
+//!
+//! Every hunk is first applied to an in-memory view of the files it touches,
+//! so a patch whose later hunks do not match is rejected before anything is
+//! written. Once staging succeeds the results are committed one path at a
+//! time: contents are written to a temporary file next to the destination and
+//! renamed into place, and the original state of each path is journaled so
+//! that a failed write can restore everything committed before it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+
+use crate::AffectedPaths;
+use crate::AppliedPatch;
+use crate::ApplyPatchError;
+use crate::FileMode;
+use crate::Hunk;
+use crate::IoError;
+use crate::apply_chunks_to_contents;
+
+/// What a path should contain once the patch is committed.
+#[derive(Debug, Clone)]
+enum Staged {
+    File(Vec<u8>),
+    Symlink(PathBuf),
+    Removed,
+}
+
+#[derive(Debug, Default)]
+struct StagedPath {
+    /// `None` when only the mode of the existing file changes.
+    contents: Option<Staged>,
+    mode: Option<FileMode>,
+}
+
+/// The validated result of applying every hunk in a patch, ready to be
+/// written to disk.
+pub(crate) struct Transaction {
+    /// Paths in the order they were first touched by the patch.
+    order: Vec<PathBuf>,
+    staged: HashMap<PathBuf, StagedPath>,
+    affected: AffectedPaths,
+}
+
+impl Transaction {
+    /// Apply `hunks` in memory. Every hunk is checked, and the error lists
+    /// each one that failed; hunks touching a path that already failed are
+    /// skipped to avoid reporting knock-on failures.
+    pub(crate) fn stage(hunks: &[Hunk]) -> anyhow::Result<Self> {
+        let mut transaction = Self {
+            order: Vec::new(),
+            staged: HashMap::new(),
+            affected: AffectedPaths {
+                added: Vec::new(),
+                modified: Vec::new(),
+                deleted: Vec::new(),
+            },
+        };
+        let mut errors: Vec<String> = Vec::new();
+        let mut failed_paths: Vec<&Path> = Vec::new();
+        for hunk in hunks {
+            let paths = hunk_paths(hunk);
+            if paths.iter().any(|path| failed_paths.contains(path)) {
+                continue;
+            }
+            if let Err(err) = transaction.stage_hunk(hunk) {
+                errors.push(err.to_string());
+                failed_paths.extend(paths);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(transaction)
+        } else {
+            anyhow::bail!("{}", errors.join("\n"))
+        }
+    }
+
+    fn stage_hunk(&mut self, hunk: &Hunk) -> anyhow::Result<()> {
+        match hunk {
+            Hunk::AddFile { path, contents } => {
+                self.set_contents(path, Staged::File(contents.clone().into_bytes()));
+                self.affected.added.push(path.clone());
+            }
+            Hunk::AddBinaryFile { path, contents } => {
+                self.set_contents(path, Staged::File(contents.clone()));
+                self.affected.added.push(path.clone());
+            }
+            Hunk::AddSymlink { path, target } => {
+                self.set_contents(path, Staged::Symlink(target.clone()));
+                self.affected.added.push(path.clone());
+            }
+            Hunk::DeleteFile { path } => {
+                if !self.exists(path) {
+                    anyhow::bail!("Failed to delete file {}", path.display());
+                }
+                self.set_contents(path, Staged::Removed);
+                self.affected.deleted.push(path.clone());
+            }
+            Hunk::SetMode { path, mode } => {
+                if !self.is_regular_file(path) {
+                    anyhow::bail!("Failed to read permissions of {}", path.display());
+                }
+                self.entry(path).mode = Some(*mode);
+                if !self.affected.added.contains(path) && !self.affected.modified.contains(path) {
+                    self.affected.modified.push(path.clone());
+                }
+            }
+            Hunk::UpdateFile {
+                path,
+                move_path,
+                chunks,
+            } => {
+                let original_contents = self.read_to_string(path)?;
+                let AppliedPatch { new_contents, .. } =
+                    apply_chunks_to_contents(original_contents, path, chunks)?;
+                match move_path {
+                    Some(dest) if dest != path => {
+                        self.set_contents(dest, Staged::File(new_contents.into_bytes()));
+                        self.set_contents(path, Staged::Removed);
+                        self.affected.modified.push(dest.clone());
+                    }
+                    _ => {
+                        self.set_contents(path, Staged::File(new_contents.into_bytes()));
+                        self.affected.modified.push(path.clone());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, path: &Path) -> &mut StagedPath {
+        if !self.staged.contains_key(path) {
+            self.order.push(path.to_path_buf());
+        }
+        self.staged.entry(path.to_path_buf()).or_default()
+    }
+
+    fn set_contents(&mut self, path: &Path, contents: Staged) {
+        let entry = self.entry(path);
+        if !matches!(contents, Staged::File(_)) {
+            entry.mode = None;
+        }
+        entry.contents = Some(contents);
+    }
+
+    fn staged_contents(&self, path: &Path) -> Option<&Staged> {
+        self.staged
+            .get(path)
+            .and_then(|entry| entry.contents.as_ref())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.staged_contents(path) {
+            Some(Staged::File(_) | Staged::Symlink(_)) => true,
+            Some(Staged::Removed) => false,
+            None => fs::symlink_metadata(path).is_ok_and(|meta| !meta.is_dir()),
+        }
+    }
+
+    fn is_regular_file(&self, path: &Path) -> bool {
+        match self.staged_contents(path) {
+            Some(Staged::File(_)) => true,
+            Some(Staged::Symlink(_) | Staged::Removed) => false,
+            None => fs::metadata(path).is_ok_and(|meta| meta.is_file()),
+        }
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, ApplyPatchError> {
+        let read_error = |source: std::io::Error| {
+            ApplyPatchError::IoError(IoError {
+                context: format!("Failed to read file to update {}", path.display()),
+                source,
+            })
+        };
+        match self.staged_contents(path) {
+            Some(Staged::File(contents)) => String::from_utf8(contents.clone()).map_err(|err| {
+                read_error(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            }),
+            Some(Staged::Symlink(_)) => Err(read_error(std::io::Error::other(
+                "the path is a symlink added earlier in this patch",
+            ))),
+            Some(Staged::Removed) => Err(read_error(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "the file is removed earlier in this patch",
+            ))),
+            None => fs::read_to_string(path).map_err(read_error),
+        }
+    }
+
+    /// Write the staged changes to disk. If any write fails, every path
+    /// committed so far is restored before the error is returned.
+    pub(crate) fn commit(self) -> anyhow::Result<AffectedPaths> {
+        let mut journal: Vec<Backup> = Vec::new();
+        for path in &self.order {
+            let Some(staged) = self.staged.get(path) else {
+                continue;
+            };
+            if let Err(err) = commit_path(path, staged, &mut journal) {
+                let unrestored: Vec<String> = journal
+                    .iter()
+                    .rev()
+                    .filter(|backup| backup.restore().is_err())
+                    .map(|backup| backup.path.display().to_string())
+                    .collect();
+                if unrestored.is_empty() {
+                    anyhow::bail!("{err}. No changes were applied.");
+                }
+                anyhow::bail!(
+                    "{err}. Failed to restore the original contents of: {}",
+                    unrestored.join(", ")
+                );
+            }
+        }
+        Ok(self.affected)
+    }
+}
+
+fn hunk_paths(hunk: &Hunk) -> Vec<&Path> {
+    match hunk {
+        Hunk::UpdateFile {
+            path,
+            move_path: Some(dest),
+            ..
+        } => vec![path.as_path(), dest.as_path()],
+        Hunk::AddFile { path, .. }
+        | Hunk::AddBinaryFile { path, .. }
+        | Hunk::AddSymlink { path, .. }
+        | Hunk::DeleteFile { path }
+        | Hunk::SetMode { path, .. }
+        | Hunk::UpdateFile { path, .. } => vec![path.as_path()],
+    }
+}
+
+fn commit_path(path: &Path, staged: &StagedPath, journal: &mut Vec<Backup>) -> anyhow::Result<()> {
+    // Writing new contents goes through an existing symlink, matching what a
+    // plain write to `path` would do.
+    let target = match &staged.contents {
+        Some(Staged::File(_)) if fs::symlink_metadata(path).is_ok_and(|m| m.is_symlink()) => {
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    };
+    let original = Original::capture(&target)
+        .with_context(|| format!("Failed to write file {}", target.display()))?;
+    let permissions = match &original {
+        Original::File { permissions, .. } => Some(permissions.clone()),
+        Original::Missing | Original::Symlink(_) => None,
+    };
+    journal.push(Backup {
+        path: target.clone(),
+        original,
+        created_dirs: Vec::new(),
+    });
+
+    match &staged.contents {
+        Some(Staged::File(contents)) => {
+            create_parent_dirs(&target, journal)?;
+            write_atomically(&target, contents, permissions)
+                .with_context(|| format!("Failed to write file {}", target.display()))?;
+        }
+        Some(Staged::Symlink(link_target)) => {
+            create_parent_dirs(&target, journal)?;
+            replace_with_symlink(link_target, &target)
+                .with_context(|| format!("Failed to create symlink {}", target.display()))?;
+        }
+        Some(Staged::Removed) => {
+            fs::remove_file(&target)
+                .with_context(|| format!("Failed to delete file {}", target.display()))?;
+        }
+        None => {}
+    }
+    if let Some(mode) = staged.mode {
+        set_file_mode(&target, mode)?;
+    }
+    Ok(())
+}
+
+/// The state of a path before the patch touched it.
+enum Original {
+    Missing,
+    File {
+        contents: Vec<u8>,
+        permissions: fs::Permissions,
+    },
+    Symlink(PathBuf),
+}
+
+impl Original {
+    fn capture(path: &Path) -> std::io::Result<Self> {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::Missing),
+            Err(err) => return Err(err),
+        };
+        if metadata.is_symlink() {
+            Ok(Self::Symlink(fs::read_link(path)?))
+        } else if metadata.is_dir() {
+            Err(std::io::Error::other("the path is a directory"))
+        } else {
+            Ok(Self::File {
+                contents: fs::read(path)?,
+                permissions: metadata.permissions(),
+            })
+        }
+    }
+}
+
+struct Backup {
+    path: PathBuf,
+    original: Original,
+    /// Directories created while committing this path, deepest first.
+    created_dirs: Vec<PathBuf>,
+}
+
+impl Backup {
+    fn restore(&self) -> std::io::Result<()> {
+        match &self.original {
+            Original::Missing => {
+                if fs::symlink_metadata(&self.path).is_ok_and(|meta| !meta.is_dir()) {
+                    fs::remove_file(&self.path)?;
+                }
+            }
+            Original::File {
+                contents,
+                permissions,
+            } => write_atomically(&self.path, contents, Some(permissions.clone()))?,
+            Original::Symlink(target) => replace_with_symlink(target, &self.path)?,
+        }
+        for dir in &self.created_dirs {
+            // Only succeeds if nothing else was written there in the meantime.
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(())
+    }
+}
+
+/// Create any missing parent directories of `path`, recording them on the
+/// latest journal entry so a rollback can remove them again.
+fn create_parent_dirs(path: &Path, journal: &mut [Backup]) -> anyhow::Result<()> {
+    let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    else {
+        return Ok(());
+    };
+    let missing: Vec<PathBuf> = parent
+        .ancestors()
+        .take_while(|dir| !dir.as_os_str().is_empty() && fs::symlink_metadata(dir).is_err())
+        .map(Path::to_path_buf)
+        .collect();
+    if let Some(backup) = journal.last_mut() {
+        backup.created_dirs = missing;
+    }
+    fs::create_dir_all(parent)
+        .with_context(|| format!("Failed to create parent directories for {}", path.display()))
+}
+
+/// A sibling path for staging writes to `path`, so that the final rename
+/// stays on the same filesystem.
+fn temp_path_for(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(
+        ".{file_name}.{}.{unique}.apply_patch.tmp",
+        std::process::id()
+    ))
+}
+
+/// Replace `path` with `contents` by writing a temporary file and renaming it
+/// into place. New files get the default permissions; existing ones keep
+/// `permissions`.
+fn write_atomically(
+    path: &Path,
+    contents: &[u8],
+    permissions: Option<fs::Permissions>,
+) -> std::io::Result<()> {
+    let temp_path = temp_path_for(path);
+    let result = (|| {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)?;
+        file.write_all(contents)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        drop(file);
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(unix)]
+fn replace_with_symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    // Like `*** Add File`, adding a symlink replaces whatever file is at `path`.
+    let temp_path = temp_path_for(path);
+    std::os::unix::fs::symlink(target, &temp_path)?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+#[cfg(not(unix))]
+fn replace_with_symlink(_target: &Path, _path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: FileMode) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = fs::metadata(path)
+        .with_context(|| format!("Failed to read permissions of {}", path.display()))?;
+    let current = metadata.permissions().mode();
+    let updated = match mode {
+        // Grant execute wherever read is granted, mirroring `chmod +x` under a
+        // typical umask.
+        FileMode::Executable => current | ((current & 0o444) >> 2),
+        FileMode::Regular => current & !0o111,
+    };
+    fs::set_permissions(path, fs::Permissions::from_mode(updated))
+        .with_context(|| format!("Failed to set permissions of {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(path: &Path, _mode: FileMode) -> anyhow::Result<()> {
+    // Windows has no executable bit; just make sure the file exists.
+    fs::metadata(path)
+        .map(|_| ())
+        .with_context(|| format!("Failed to read permissions of {}", path.display()))
+}
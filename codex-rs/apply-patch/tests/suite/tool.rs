@@ -214,7 +214,7 @@ fn test_apply_patch_cli_rejects_invalid_hunk_header() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Frobnicate File: foo\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Invalid patch hunk on line 2: '*** Frobnicate File: foo' is not a valid hunk header. Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'\n");
+        .stderr("Invalid patch hunk on line 2: '*** Frobnicate File: foo' is not a valid hunk header. Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', '*** Add Symlink: {path}', '*** Delete File: {path}', '*** Update File: {path}', '*** Set Mode: {mode} {path}'\n");
 
     Ok(())
 }
@@ -240,7 +240,7 @@ fn test_apply_patch_cli_updates_file_appends_trailing_newline() -> anyhow::Resul
 }
 
 #[test]
-fn test_apply_patch_cli_failure_after_partial_success_leaves_changes() -> anyhow::Result<()> {
+fn test_apply_patch_cli_failure_after_partial_success_rolls_back() -> anyhow::Result<()> {
     let tmp = tempdir()?;
     let new_file = tmp.path().join("created.txt");
 
@@ -251,7 +251,7 @@ fn test_apply_patch_cli_failure_after_partial_success_leaves_changes() -> anyhow
         .stdout("")
         .stderr("Failed to read file to update missing.txt: No such file or directory (os error 2)\n");
 
-    assert_eq!(fs::read_to_string(&new_file)?, "hello\n");
+    assert!(!new_file.exists());
 
     Ok(())
 }
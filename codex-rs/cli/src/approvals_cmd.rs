@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::approval_memory::list_remembered_approvals;
+use codex_core::approval_memory::project_root;
+use codex_core::approval_memory::revoke_remembered_approval;
+use codex_core::config::Config;
+
+/// Subcommands:
+/// - `list`   — list approvals remembered with "always allow in this repo"
+/// - `revoke` — forget one (or every) remembered approval for this repo
+///
+/// Approvals are remembered per repository: the root of the main git
+/// repository containing the current directory, or the directory itself
+/// outside of git.
+#[derive(Debug, clap::Parser)]
+pub struct ApprovalsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ApprovalsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ApprovalsSubcommand {
+    /// List approvals remembered for the current repository.
+    List(ListArgs),
+    /// Revoke approvals remembered for the current repository.
+    Revoke(RevokeArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// List remembered approvals for every repository.
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RevokeArgs {
+    /// Number of the approval to revoke, as shown by `codex approvals list`.
+    #[arg(value_name = "N", required_unless_present = "all")]
+    pub index: Option<usize>,
+
+    /// Revoke every approval remembered for the current repository.
+    #[arg(long, conflicts_with = "index")]
+    pub all: bool,
+}
+
+impl ApprovalsCli {
+    pub async fn run(self) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides)
+            .await
+            .context("failed to load configuration")?;
+        let project = project_root(&config.cwd);
+        match self.subcommand {
+            ApprovalsSubcommand::List(args) => run_list(&config.codex_home, &project, args),
+            ApprovalsSubcommand::Revoke(args) => run_revoke(&config.codex_home, &project, args),
+        }
+    }
+}
+
+fn run_list(codex_home: &Path, project: &Path, args: ListArgs) -> Result<()> {
+    if args.all {
+        let entries = list_remembered_approvals(codex_home, None)?;
+        if entries.is_empty() {
+            println!("No approvals remembered.");
+            return Ok(());
+        }
+        let mut current = None;
+        for entry in entries {
+            if current.as_ref() != Some(&entry.project) {
+                println!("{}", entry.project.display());
+                current = Some(entry.project.clone());
+            }
+            println!("  {}", entry.approval);
+        }
+        return Ok(());
+    }
+
+    let entries = list_remembered_approvals(codex_home, Some(project))?;
+    if entries.is_empty() {
+        println!("No approvals remembered for {}.", project.display());
+        return Ok(());
+    }
+    println!("Always allowed in {}:", project.display());
+    for (index, entry) in entries.iter().enumerate() {
+        println!("{:>3}. {}", index + 1, entry.approval);
+    }
+    Ok(())
+}
+
+fn run_revoke(codex_home: &Path, project: &Path, args: RevokeArgs) -> Result<()> {
+    let entries = list_remembered_approvals(codex_home, Some(project))?;
+    let selected = if args.all {
+        entries
+    } else {
+        let index = args.index.unwrap_or_default();
+        let Some(entry) = index.checked_sub(1).and_then(|i| entries.get(i)) else {
+            bail!(
+                "No remembered approval #{index} for {}. Run `codex approvals list` to see them.",
+                project.display()
+            );
+        };
+        vec![entry.clone()]
+    };
+    if selected.is_empty() {
+        println!("No approvals remembered for {}.", project.display());
+        return Ok(());
+    }
+    for entry in selected {
+        revoke_remembered_approval(codex_home, project, &entry.approval)?;
+        println!("Revoked {}", entry.approval);
+    }
+    Ok(())
+}
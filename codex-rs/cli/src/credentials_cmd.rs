@@ -0,0 +1,153 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::migrate_auth_credentials;
+use codex_core::config::Config;
+use codex_core::config::types::McpServerTransportConfig;
+use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_rmcp_client::migrate_oauth_tokens;
+use codex_secrets::SecretsKeyStore;
+use codex_secrets::migrate_secrets_key;
+
+/// Subcommands:
+/// - `migrate` — move stored credentials from one backend to another
+///
+/// Credentials cover the CLI login, OAuth tokens of configured MCP servers and
+/// the key of the local `codex secrets` store.
+#[derive(Debug, clap::Parser)]
+pub struct CredentialsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: CredentialsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum CredentialsSubcommand {
+    /// Move stored credentials from one backend to another.
+    Migrate(MigrateArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CredentialsBackend {
+    /// Plaintext files in CODEX_HOME (`auth.json`, `.credentials.json`).
+    File,
+    /// The OS keyring.
+    Keyring,
+    /// CODEX_HOME/credentials.age, encrypted with a passphrase or key file.
+    Encrypted,
+}
+
+impl CredentialsBackend {
+    fn config_value(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Keyring => "keyring",
+            Self::Encrypted => "encrypted",
+        }
+    }
+
+    fn auth_mode(self) -> AuthCredentialsStoreMode {
+        match self {
+            Self::File => AuthCredentialsStoreMode::File,
+            Self::Keyring => AuthCredentialsStoreMode::Keyring,
+            Self::Encrypted => AuthCredentialsStoreMode::Encrypted,
+        }
+    }
+
+    fn oauth_mode(self) -> OAuthCredentialsStoreMode {
+        match self {
+            Self::File => OAuthCredentialsStoreMode::File,
+            Self::Keyring => OAuthCredentialsStoreMode::Keyring,
+            Self::Encrypted => OAuthCredentialsStoreMode::Encrypted,
+        }
+    }
+
+    /// The secrets key is never written to a plaintext file.
+    fn secrets_key_store(self) -> Option<SecretsKeyStore> {
+        match self {
+            Self::File => None,
+            Self::Keyring => Some(SecretsKeyStore::Keyring),
+            Self::Encrypted => Some(SecretsKeyStore::Encrypted),
+        }
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct MigrateArgs {
+    /// Backend the credentials are currently stored in.
+    #[arg(long, value_enum)]
+    pub from: CredentialsBackend,
+
+    /// Backend to move the credentials to.
+    #[arg(long, value_enum)]
+    pub to: CredentialsBackend,
+}
+
+impl CredentialsCli {
+    pub async fn run(self) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides)
+            .await
+            .context("failed to load configuration")?;
+        match self.subcommand {
+            CredentialsSubcommand::Migrate(args) => run_migrate(&config, args),
+        }
+    }
+}
+
+fn run_migrate(config: &Config, args: MigrateArgs) -> Result<()> {
+    let MigrateArgs { from, to } = args;
+    if from == to {
+        bail!("--from and --to must name different backends.");
+    }
+    let (from_name, to_name) = (from.config_value(), to.config_value());
+
+    if migrate_auth_credentials(&config.codex_home, from.auth_mode(), to.auth_mode())
+        .context("failed to migrate CLI login credentials")?
+    {
+        println!("Moved CLI login credentials from {from_name} to {to_name}.");
+    } else {
+        println!("No CLI login credentials stored in {from_name}.");
+    }
+
+    let mut servers: Vec<_> = config.mcp_servers.iter().collect();
+    servers.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (name, server) in servers {
+        let McpServerTransportConfig::StreamableHttp { url, .. } = &server.transport else {
+            continue;
+        };
+        if migrate_oauth_tokens(name, url, from.oauth_mode(), to.oauth_mode())
+            .with_context(|| format!("failed to migrate OAuth credentials for '{name}'"))?
+        {
+            println!("Moved OAuth credentials for '{name}' from {from_name} to {to_name}.");
+        }
+    }
+
+    match (from.secrets_key_store(), to.secrets_key_store()) {
+        (Some(from_store), Some(to_store)) => {
+            if migrate_secrets_key(&config.codex_home, from_store, to_store)
+                .context("failed to migrate the secrets key")?
+            {
+                println!("Moved the secrets key from {from_name} to {to_name}.");
+            }
+        }
+        _ => println!(
+            "Skipped the secrets key, which is only kept in the keyring or encrypted store."
+        ),
+    }
+
+    println!(
+        "\nTo keep using {to_name}, set these in config.toml:\n  cli_auth_credentials_store = \"{to_name}\"\n  mcp_oauth_credentials_store = \"{to_name}\""
+    );
+    if to.secrets_key_store().is_some() {
+        println!("  secrets_key_store = \"{to_name}\"");
+    }
+    Ok(())
+}
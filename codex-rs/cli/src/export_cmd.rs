@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::RolloutRecorder;
+use codex_core::config::Config;
+use codex_core::find_thread_names_by_ids;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::find_thread_path_by_name_str;
+use codex_core::session_export::Transcript;
+use codex_core::session_export::render_html;
+use codex_core::session_export::render_json;
+use codex_core::session_export::render_markdown;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::InitialHistory;
+
+/// Export a recorded session as a readable transcript.
+#[derive(Debug, clap::Parser)]
+pub struct ExportCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Thread id (UUID) or thread name of the session to export.
+    #[arg(value_name = "SESSION")]
+    pub session: String,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Md)]
+    pub format: ExportFormat,
+
+    /// Write the transcript to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Markdown.
+    Md,
+    /// A standalone HTML page.
+    Html,
+    /// The transcript as JSON.
+    Json,
+}
+
+impl ExportCli {
+    pub async fn run(self) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides)
+            .await
+            .context("failed to load configuration")?;
+
+        let path = if ThreadId::from_string(&self.session).is_ok() {
+            find_thread_path_by_id_str(&config.codex_home, &self.session).await?
+        } else {
+            find_thread_path_by_name_str(&config.codex_home, &self.session).await?
+        };
+        let path = path.with_context(|| format!("no session found for '{}'", self.session))?;
+
+        let history = RolloutRecorder::get_rollout_history(&path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut transcript = Transcript::from_rollout_items(&history.get_rollout_items());
+        if let InitialHistory::Resumed(resumed) = &history {
+            let ids = HashSet::from([resumed.conversation_id]);
+            transcript.name = find_thread_names_by_ids(&config.codex_home, &ids)
+                .await
+                .ok()
+                .and_then(|mut names| names.remove(&resumed.conversation_id));
+        }
+
+        let rendered = match self.format {
+            ExportFormat::Md => render_markdown(&transcript),
+            ExportFormat::Html => render_html(&transcript),
+            ExportFormat::Json => render_json(&transcript)? + "\n",
+        };
+        match self.output {
+            Some(output) => std::fs::write(&output, rendered)
+                .with_context(|| format!("failed to write {}", output.display()))?,
+            None => print!("{rendered}"),
+        }
+        Ok(())
+    }
+}
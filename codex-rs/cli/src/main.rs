@@ -33,13 +33,21 @@ use supports_color::Stream;
 
 #[cfg(target_os = "macos")]
 mod app_cmd;
+mod approvals_cmd;
+mod credentials_cmd;
 #[cfg(target_os = "macos")]
 mod desktop_app;
+mod export_cmd;
 mod mcp_cmd;
+mod secrets_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::approvals_cmd::ApprovalsCli;
+use crate::credentials_cmd::CredentialsCli;
+use crate::export_cmd::ExportCli;
 use crate::mcp_cmd::McpCli;
+use crate::secrets_cmd::SecretsCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -99,6 +107,18 @@ enum Subcommand {
     /// [experimental] Run the Codex MCP server (stdio transport).
     McpServer,
 
+    /// Manage secrets injected into shell commands.
+    Secrets(SecretsCli),
+
+    /// Manage where credentials are stored.
+    Credentials(CredentialsCli),
+
+    /// List or revoke approvals remembered for a repository.
+    Approvals(ApprovalsCli),
+
+    /// Export a recorded session as Markdown, HTML or JSON.
+    Export(ExportCli),
+
     /// [experimental] Run the app server or related tooling.
     AppServer(AppServerCommand),
 
@@ -585,6 +605,34 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Secrets(mut secrets_cli)) => {
+            prepend_config_flags(
+                &mut secrets_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            secrets_cli.run().await?;
+        }
+        Some(Subcommand::Approvals(mut approvals_cli)) => {
+            prepend_config_flags(
+                &mut approvals_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            approvals_cli.run().await?;
+        }
+        Some(Subcommand::Credentials(mut credentials_cli)) => {
+            prepend_config_flags(
+                &mut credentials_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            credentials_cli.run().await?;
+        }
+        Some(Subcommand::Export(mut export_cli)) => {
+            prepend_config_flags(
+                &mut export_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            export_cli.run().await?;
+        }
         Some(Subcommand::AppServer(app_server_cli)) => match app_server_cli.subcommand {
             None => {
                 codex_app_server::run_main(
@@ -0,0 +1,199 @@
+use std::io::IsTerminal;
+use std::io::Read;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsManager;
+use codex_secrets::environment_id_from_cwd;
+
+/// Subcommands:
+/// - `set`    — store a secret read from stdin
+/// - `list`   — list stored secret names (never values)
+/// - `delete` — remove a stored secret
+///
+/// Secrets are injected into `shell` and `exec_command` when their names are
+/// listed under `shell_environment_policy.secrets` in `config.toml`.
+#[derive(Debug, clap::Parser)]
+pub struct SecretsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SecretsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SecretsSubcommand {
+    /// Store a secret. The value is read from stdin.
+    Set(SetArgs),
+    /// List stored secrets.
+    List(ListArgs),
+    /// Delete a stored secret.
+    Delete(DeleteArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ScopeArgs {
+    /// Use the global scope, shared by every repository.
+    #[arg(long, conflicts_with = "env")]
+    pub global: bool,
+
+    /// Use the scope of the named environment instead of the one derived from
+    /// the current directory (the git repository name).
+    #[arg(long, value_name = "ID")]
+    pub env: Option<String>,
+}
+
+impl ScopeArgs {
+    fn scope(&self) -> Result<SecretScope> {
+        if self.global {
+            return Ok(SecretScope::Global);
+        }
+        match &self.env {
+            Some(env) => SecretScope::environment(env.as_str()),
+            None => {
+                let cwd = std::env::current_dir().context("failed to read current directory")?;
+                SecretScope::environment(environment_id_from_cwd(&cwd))
+            }
+        }
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SetArgs {
+    /// Name of the secret (A-Z, 0-9 and _). This is also the name of the
+    /// environment variable it is injected as.
+    pub name: String,
+
+    #[command(flatten)]
+    pub scope: ScopeArgs,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Only list secrets in the global scope.
+    #[arg(long, conflicts_with = "env")]
+    pub global: bool,
+
+    /// Only list secrets in the named environment.
+    #[arg(long, value_name = "ID")]
+    pub env: Option<String>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct DeleteArgs {
+    /// Name of the secret to delete.
+    pub name: String,
+
+    #[command(flatten)]
+    pub scope: ScopeArgs,
+}
+
+impl SecretsCli {
+    pub async fn run(self) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides)
+            .await
+            .context("failed to load configuration")?;
+        let keyring_store = config.secrets_key_store.keyring_store(&config.codex_home);
+        let manager = SecretsManager::new_with_keyring_store(
+            config.codex_home,
+            config.secrets_backend,
+            keyring_store,
+        );
+        match self.subcommand {
+            SecretsSubcommand::Set(args) => run_set(&manager, args),
+            SecretsSubcommand::List(args) => run_list(&manager, args),
+            SecretsSubcommand::Delete(args) => run_delete(&manager, args),
+        }
+    }
+}
+
+fn run_set(manager: &SecretsManager, args: SetArgs) -> Result<()> {
+    let name = SecretName::new(&args.name)?;
+    let scope = args.scope.scope()?;
+
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        bail!(
+            "`codex secrets set` expects the value on stdin. Try piping it, e.g. `printenv {name} | codex secrets set {name}`."
+        );
+    }
+    let mut value = String::new();
+    stdin
+        .read_to_string(&mut value)
+        .context("failed to read secret value from stdin")?;
+    let value = value.trim_end_matches(['\r', '\n']);
+
+    manager.set(&scope, &name, value)?;
+    println!("Stored secret {name} in {}.", describe_scope(&scope));
+    Ok(())
+}
+
+fn run_list(manager: &SecretsManager, args: ListArgs) -> Result<()> {
+    let filter = if args.global {
+        Some(SecretScope::Global)
+    } else {
+        args.env.map(SecretScope::environment).transpose()?
+    };
+
+    let mut entries = manager.list(filter.as_ref())?;
+    if entries.is_empty() {
+        println!("No secrets stored. Add one with `codex secrets set <NAME>`.");
+        return Ok(());
+    }
+    entries.sort_by(|a, b| {
+        scope_label(&a.scope)
+            .cmp(&scope_label(&b.scope))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let name_width = entries
+        .iter()
+        .map(|entry| entry.name.as_str().len())
+        .max()
+        .unwrap_or(0)
+        .max("Name".len());
+    println!("{:<name_width$}  Scope", "Name");
+    for entry in entries {
+        println!(
+            "{:<name_width$}  {}",
+            entry.name.as_str(),
+            scope_label(&entry.scope)
+        );
+    }
+    Ok(())
+}
+
+fn run_delete(manager: &SecretsManager, args: DeleteArgs) -> Result<()> {
+    let name = SecretName::new(&args.name)?;
+    let scope = args.scope.scope()?;
+    if manager.delete(&scope, &name)? {
+        println!("Deleted secret {name} from {}.", describe_scope(&scope));
+    } else {
+        println!("No secret named {name} in {}.", describe_scope(&scope));
+    }
+    Ok(())
+}
+
+fn scope_label(scope: &SecretScope) -> String {
+    match scope {
+        SecretScope::Global => "global".to_string(),
+        SecretScope::Environment(id) => format!("env:{id}"),
+    }
+}
+
+fn describe_scope(scope: &SecretScope) -> String {
+    match scope {
+        SecretScope::Global => "the global scope".to_string(),
+        SecretScope::Environment(id) => format!("environment `{id}`"),
+    }
+}
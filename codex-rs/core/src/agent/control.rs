@@ -1,11 +1,14 @@
 use crate::agent::AgentStatus;
 use crate::agent::guards::Guards;
+use crate::agent::worktree::AgentWorktrees;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::thread_manager::ThreadManagerState;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::Op;
 use codex_protocol::user_input::UserInput;
+use codex_symbol_index::SharedSymbolIndex;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Weak;
 use tokio::sync::watch;
@@ -23,6 +26,7 @@ pub(crate) struct AgentControl {
     /// `ThreadManagerState -> CodexThread -> Session -> SessionServices -> ThreadManagerState`.
     manager: Weak<ThreadManagerState>,
     state: Arc<Guards>,
+    worktrees: Arc<AgentWorktrees>,
 }
 
 impl AgentControl {
@@ -129,6 +133,20 @@ impl AgentControl {
         Ok(thread.subscribe_status())
     }
 
+    /// Worktrees of the agents spawned with `worktree: true`.
+    pub(crate) fn worktrees(&self) -> &AgentWorktrees {
+        &self.worktrees
+    }
+
+    /// Symbol index of `root`, shared with the other threads of the thread
+    /// manager. Without a manager the index belongs to the caller alone.
+    pub(crate) fn symbol_index(&self, root: PathBuf) -> SharedSymbolIndex {
+        match self.manager.upgrade() {
+            Some(state) => state.symbol_indexes().get_or_spawn(root),
+            None => SharedSymbolIndex::spawn(root),
+        }
+    }
+
     fn upgrade(&self) -> CodexResult<Arc<ThreadManagerState>> {
         self.manager
             .upgrade()
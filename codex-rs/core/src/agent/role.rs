@@ -1,8 +1,9 @@
 use crate::config::Config;
+use crate::config::agent_roles::AgentRoleToml;
 use crate::protocol::SandboxPolicy;
+use codex_protocol::config_types::SandboxMode;
 use codex_protocol::openai_models::ReasoningEffort;
-use serde::Deserialize;
-use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Base instructions for the orchestrator role.
 const ORCHESTRATOR_PROMPT: &str = include_str!("../../templates/agents/orchestrator.md");
@@ -10,90 +11,164 @@ const ORCHESTRATOR_PROMPT: &str = include_str!("../../templates/agents/orchestra
 // TODO(jif) update when we have something smarter.
 const EXPLORER_MODEL: &str = "gpt-5.2-codex";
 
-/// Enumerated list of all supported agent roles.
-const ALL_ROLES: [AgentRole; 3] = [
-    AgentRole::Default,
-    AgentRole::Explorer,
-    AgentRole::Worker,
+/// Role used when `spawn_agent` is called without an `agent_type`.
+pub(crate) const DEFAULT_ROLE_NAME: &str = "default";
+
+/// Built-in roles advertised in the `spawn_agent` tool description.
+const LISTED_BUILT_IN_ROLES: [&str; 3] = [
+    DEFAULT_ROLE_NAME,
+    "explorer",
+    "worker",
     // TODO(jif) add when we have stable prompts + models
-    // AgentRole::Orchestrator,
+    // "orchestrator",
 ];
 
-/// Hard-coded agent role selection used when spawning sub-agents.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum AgentRole {
-    /// Inherit the parent agent's configuration unchanged.
-    Default,
-    /// Coordination-only agent that delegates to workers.
-    Orchestrator,
-    /// Task-executing agent with a fixed model override.
-    Worker,
-    /// Task-executing agent with a fixed model override.
-    Explorer,
+/// Agent role selected when spawning sub-agents: either a built-in role or
+/// one defined by the user (see [`crate::config::agent_roles`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentRole {
+    pub name: String,
+    pub profile: AgentProfile,
 }
 
-/// Immutable profile data that drives per-agent configuration overrides.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Profile data that drives per-agent configuration overrides.
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct AgentProfile {
     /// Optional base instructions override.
-    pub base_instructions: Option<&'static str>,
+    pub base_instructions: Option<String>,
+    /// Optional developer instructions appended to the inherited ones.
+    pub developer_instructions: Option<String>,
     /// Optional model override.
-    pub model: Option<&'static str>,
+    pub model: Option<String>,
     /// Optional reasoning effort override.
     pub reasoning_effort: Option<ReasoningEffort>,
-    /// Whether to force a read-only sandbox policy.
-    pub read_only: bool,
+    /// Optional sandbox override. Only ever tightens the parent's sandbox.
+    pub sandbox_mode: Option<SandboxMode>,
+    /// Optional allow-list of tool names.
+    pub tools: Option<Vec<String>>,
     /// Description to include in the tool specs.
-    pub description: &'static str,
+    pub description: String,
+}
+
+impl From<&AgentRoleToml> for AgentProfile {
+    fn from(role: &AgentRoleToml) -> Self {
+        Self {
+            base_instructions: role.base_instructions.clone(),
+            developer_instructions: role.developer_instructions.clone(),
+            model: role.model.clone(),
+            reasoning_effort: role.model_reasoning_effort,
+            sandbox_mode: role.sandbox_mode,
+            tools: role.tools.clone(),
+            description: role.description.clone().unwrap_or_default(),
+        }
+    }
 }
 
 impl AgentRole {
-    /// Returns the string values used by JSON schema enums.
-    pub fn enum_values() -> Vec<String> {
-        ALL_ROLES
+    /// Looks up `name` among the user-defined roles, then the built-in ones.
+    pub fn resolve(name: &str, user_roles: &BTreeMap<String, AgentRoleToml>) -> Option<Self> {
+        let profile = match user_roles.get(name) {
+            Some(role) => AgentProfile::from(role),
+            None => built_in_profile(name)?,
+        };
+        Some(Self {
+            name: name.to_string(),
+            profile,
+        })
+    }
+
+    /// Roles that can be offered to the model: the listed built-in roles plus
+    /// every user-defined role, sorted by name.
+    pub fn available(user_roles: &BTreeMap<String, AgentRoleToml>) -> Vec<Self> {
+        let mut names: Vec<&str> = LISTED_BUILT_IN_ROLES.to_vec();
+        names.extend(user_roles.keys().map(String::as_str));
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .filter_map(|name| Self::resolve(name, user_roles))
+            .collect()
+    }
+
+    /// Returns the role names accepted as `agent_type` in tool specs.
+    pub fn enum_values(roles: &[Self]) -> Vec<String> {
+        roles.iter().map(|role| role.name.clone()).collect()
+    }
+
+    /// Describes the roles that have a description, one `name: description`
+    /// entry per role.
+    pub fn descriptions(roles: &[Self]) -> Vec<String> {
+        roles
             .iter()
-            .filter_map(|role| {
-                let description = role.profile().description;
-                serde_json::to_string(role)
-                    .map(|role| {
-                        let description = if !description.is_empty() {
-                            format!(r#", "description": {description}"#)
-                        } else {
-                            String::new()
-                        };
-                        format!(r#"{{ "name": {role}{description}}}"#)
-                    })
-                    .ok()
-            })
+            .filter(|role| !role.profile.description.is_empty())
+            .map(|role| format!("{}: {}", role.name, role.profile.description))
             .collect()
     }
 
-    /// Returns the hard-coded profile for this role.
-    pub fn profile(self) -> AgentProfile {
-        match self {
-            AgentRole::Default => AgentProfile::default(),
-            AgentRole::Orchestrator => AgentProfile {
-                base_instructions: Some(ORCHESTRATOR_PROMPT),
-                ..Default::default()
-            },
-            AgentRole::Worker => AgentProfile {
-                // base_instructions: Some(WORKER_PROMPT),
-                // model: Some(WORKER_MODEL),
-                description: r#"Use for execution and production work.
+    /// Applies this role's profile onto the provided config.
+    pub fn apply_to_config(&self, config: &mut Config) -> Result<(), String> {
+        let profile = &self.profile;
+        if let Some(base_instructions) = &profile.base_instructions {
+            config.base_instructions = Some(base_instructions.clone());
+        }
+        if let Some(developer_instructions) = &profile.developer_instructions {
+            config.developer_instructions = Some(match config.developer_instructions.take() {
+                Some(existing) => format!("{existing}\n\n{developer_instructions}"),
+                None => developer_instructions.clone(),
+            });
+        }
+        if let Some(model) = &profile.model {
+            config.model = Some(model.clone());
+        }
+        if let Some(reasoning_effort) = profile.reasoning_effort {
+            config.model_reasoning_effort = Some(reasoning_effort)
+        }
+        if let Some(sandbox_mode) = profile.sandbox_mode {
+            let policy = tightened_sandbox_policy(config.sandbox_policy.get(), sandbox_mode)
+                .ok_or_else(|| {
+                    format!(
+                        "agent role `{}` requests sandbox_mode `{sandbox_mode}`, which is less restrictive than the current sandbox",
+                        self.name
+                    )
+                })?;
+            config
+                .sandbox_policy
+                .set(policy)
+                .map_err(|err| format!("sandbox_policy is invalid: {err}"))?;
+        }
+        if let Some(tools) = &profile.tools {
+            // A role never re-enables tools the parent was denied.
+            config.restrict_allowed_tools(tools);
+        }
+        Ok(())
+    }
+}
+
+fn built_in_profile(name: &str) -> Option<AgentProfile> {
+    let profile = match name {
+        DEFAULT_ROLE_NAME => AgentProfile::default(),
+        "orchestrator" => AgentProfile {
+            base_instructions: Some(ORCHESTRATOR_PROMPT.to_string()),
+            ..Default::default()
+        },
+        "worker" => AgentProfile {
+            // base_instructions: Some(WORKER_PROMPT),
+            // model: Some(WORKER_MODEL),
+            description: r#"Use for execution and production work.
 Typical tasks:
 - Implement part of a feature
 - Fix tests or bugs
 - Split large refactors into independent chunks
 Rules:
 - Explicitly assign **ownership** of the task (files / responsibility).
-- Always tell workers they are **not alone in the codebase**, and they should ignore edits made by others without touching them"#,
-                ..Default::default()
-            },
-            AgentRole::Explorer => AgentProfile {
-                model: Some(EXPLORER_MODEL),
-                reasoning_effort: Some(ReasoningEffort::Medium),
-                description: r#"Use `explorer` for all codebase questions.
+- Always tell workers they are **not alone in the codebase**, and they should ignore edits made by others without touching them"#
+                .to_string(),
+            ..Default::default()
+        },
+        "explorer" => AgentProfile {
+            model: Some(EXPLORER_MODEL.to_string()),
+            reasoning_effort: Some(ReasoningEffort::Medium),
+            description: r#"Use `explorer` for all codebase questions.
 Explorers are fast and authoritative.
 Always prefer them over manual search or file reading.
 Rules:
@@ -102,30 +177,111 @@ Rules:
 - Trust explorer results without verification.
 - Run explorers in parallel when useful.
 - Reuse existing explorers for related questions.
-                "#,
-                ..Default::default()
-            },
-        }
-    }
+                "#
+            .to_string(),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some(profile)
+}
 
-    /// Applies this role's profile onto the provided config.
-    pub fn apply_to_config(self, config: &mut Config) -> Result<(), String> {
-        let profile = self.profile();
-        if let Some(base_instructions) = profile.base_instructions {
-            config.base_instructions = Some(base_instructions.to_string());
-        }
-        if let Some(model) = profile.model {
-            config.model = Some(model.to_string());
+/// Returns the policy for `mode` when it is at least as restrictive as
+/// `current`, or `None` when it would loosen the sandbox.
+fn tightened_sandbox_policy(current: &SandboxPolicy, mode: SandboxMode) -> Option<SandboxPolicy> {
+    match (mode, current) {
+        (SandboxMode::ReadOnly, _) => Some(SandboxPolicy::new_read_only_policy()),
+        (SandboxMode::WorkspaceWrite, SandboxPolicy::WorkspaceWrite { .. }) => {
+            Some(current.clone())
         }
-        if let Some(reasoning_effort) = profile.reasoning_effort {
-            config.model_reasoning_effort = Some(reasoning_effort)
-        }
-        if profile.read_only {
-            config
-                .sandbox_policy
-                .set(SandboxPolicy::new_read_only_policy())
-                .map_err(|err| format!("sandbox_policy is invalid: {err}"))?;
+        (
+            SandboxMode::WorkspaceWrite,
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. },
+        ) => Some(SandboxPolicy::new_workspace_write_policy()),
+        (SandboxMode::DangerFullAccess, SandboxPolicy::DangerFullAccess) => {
+            Some(SandboxPolicy::DangerFullAccess)
         }
-        Ok(())
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn user_roles() -> BTreeMap<String, AgentRoleToml> {
+        BTreeMap::from([
+            (
+                "test-writer".to_string(),
+                AgentRoleToml {
+                    description: Some("Writes tests".to_string()),
+                    developer_instructions: Some("Only edit tests.".to_string()),
+                    sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+                    tools: Some(vec!["shell".to_string(), "apply_patch".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "worker".to_string(),
+                AgentRoleToml {
+                    model: Some("custom-worker".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn available_roles_merge_built_ins_and_user_roles() {
+        let names: Vec<String> = AgentRole::available(&user_roles())
+            .into_iter()
+            .map(|role| role.name)
+            .collect();
+        assert_eq!(names, vec!["default", "explorer", "test-writer", "worker"]);
+    }
+
+    #[test]
+    fn user_roles_override_built_ins() {
+        let role = AgentRole::resolve("worker", &user_roles()).expect("worker role");
+        assert_eq!(role.profile.model.as_deref(), Some("custom-worker"));
+        assert!(AgentRole::resolve("orchestrator", &user_roles()).is_some());
+        assert!(AgentRole::resolve("unknown", &user_roles()).is_none());
+    }
+
+    #[test]
+    fn enum_values_are_role_names() {
+        let roles = AgentRole::available(&user_roles());
+        assert_eq!(
+            AgentRole::enum_values(&roles),
+            vec!["default", "explorer", "test-writer", "worker"]
+        );
+        let descriptions = AgentRole::descriptions(&roles);
+        assert!(descriptions.contains(&"test-writer: Writes tests".to_string()));
+        assert!(
+            !descriptions
+                .iter()
+                .any(|description| description.starts_with("default:"))
+        );
+    }
+
+    #[test]
+    fn sandbox_mode_only_tightens() {
+        let read_only = SandboxPolicy::new_read_only_policy();
+        assert_eq!(
+            tightened_sandbox_policy(&read_only, SandboxMode::WorkspaceWrite),
+            None
+        );
+        assert_eq!(
+            tightened_sandbox_policy(&SandboxPolicy::DangerFullAccess, SandboxMode::ReadOnly),
+            Some(read_only)
+        );
+        assert_eq!(
+            tightened_sandbox_policy(
+                &SandboxPolicy::DangerFullAccess,
+                SandboxMode::WorkspaceWrite
+            ),
+            Some(SandboxPolicy::new_workspace_write_policy())
+        );
     }
 }
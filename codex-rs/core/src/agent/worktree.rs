@@ -0,0 +1,347 @@
+use codex_git::GitToolingError;
+use codex_git::GitWorktree;
+use codex_git::create_worktree;
+use codex_git::remove_stale_worktree;
+use codex_protocol::ThreadId;
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::fs::TryLockError;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Directory under `CODEX_HOME` holding sub-agent worktrees.
+const WORKTREES_DIR_NAME: &str = "worktrees";
+/// Prefix of the scratch branches checked out in sub-agent worktrees.
+const BRANCH_PREFIX: &str = "codex/agent-";
+
+/// Locks on `<worktree>.lock`, held while this process uses the worktree so
+/// that [`prune_stale_agent_worktrees`] in any process leaves it alone.
+static WORKTREE_LOCKS: LazyLock<Mutex<HashMap<PathBuf, File>>> = LazyLock::new(Mutex::default);
+
+/// Git worktrees of sub-agents spawned with `worktree: true`, keyed by agent.
+/// Shared by every agent of a user session (through `AgentControl`) so that a
+/// parent can inspect, merge or discard the worktrees of the agents it spawned.
+#[derive(Default)]
+pub(crate) struct AgentWorktrees {
+    entries: Mutex<HashMap<ThreadId, AgentWorktree>>,
+}
+
+struct AgentWorktree {
+    parent_thread_id: ThreadId,
+    worktree: GitWorktree,
+}
+
+impl AgentWorktrees {
+    pub(crate) fn register(
+        &self,
+        agent_id: ThreadId,
+        parent_thread_id: ThreadId,
+        worktree: GitWorktree,
+    ) {
+        self.lock().insert(
+            agent_id,
+            AgentWorktree {
+                parent_thread_id,
+                worktree,
+            },
+        );
+    }
+
+    pub(crate) fn get(&self, agent_id: ThreadId) -> Option<GitWorktree> {
+        self.lock()
+            .get(&agent_id)
+            .map(|entry| entry.worktree.clone())
+    }
+
+    pub(crate) fn remove(&self, agent_id: ThreadId) -> Option<GitWorktree> {
+        self.lock().remove(&agent_id).map(|entry| entry.worktree)
+    }
+
+    /// Unregisters and returns every worktree spawned by `parent_thread_id`.
+    pub(crate) fn take_for_parent(&self, parent_thread_id: ThreadId) -> Vec<GitWorktree> {
+        let mut entries = self.lock();
+        let (taken, kept): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut *entries)
+            .into_iter()
+            .partition(|(_, entry)| entry.parent_thread_id == parent_thread_id);
+        *entries = kept;
+        taken.into_values().map(|entry| entry.worktree).collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ThreadId, AgentWorktree>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Creates a worktree for a new sub-agent under `$CODEX_HOME/worktrees`,
+/// branching from the current `HEAD` of the repository containing `cwd`.
+pub(crate) async fn create_agent_worktree(
+    codex_home: &Path,
+    cwd: &Path,
+) -> Result<GitWorktree, GitToolingError> {
+    let name = Uuid::new_v4().simple().to_string();
+    let name = &name[..12];
+    let worktree_path = codex_home.join(WORKTREES_DIR_NAME).join(name);
+    let branch = format!("{BRANCH_PREFIX}{name}");
+    let cwd = cwd.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let lock = lock_worktree(&worktree_path)?;
+        let worktree = create_worktree(&cwd, &worktree_path, &branch);
+        match &worktree {
+            Ok(_) => {
+                worktree_locks().insert(worktree_path, lock);
+            }
+            Err(_) => {
+                drop(lock);
+                let _ = std::fs::remove_file(lock_path(&worktree_path));
+            }
+        }
+        worktree
+    })
+    .await
+    .map_err(|err| GitToolingError::Io(std::io::Error::other(err)))?
+}
+
+/// Removes `worktree` and its branch, logging instead of failing.
+pub(crate) async fn remove_agent_worktree(worktree: GitWorktree) {
+    let result = tokio::task::spawn_blocking(move || {
+        let result = worktree
+            .remove()
+            .map_err(|err| format!("{}: {err}", worktree.path().display()));
+        // Release the lock even on failure so a later prune can retry.
+        if worktree_locks().remove(worktree.path()).is_some() {
+            let _ = std::fs::remove_file(lock_path(worktree.path()));
+        }
+        result
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => warn!("failed to remove agent worktree {err}"),
+        Err(err) => warn!("failed to remove agent worktree: {err}"),
+    }
+}
+
+/// Stops tracking `worktree` without removing it, for worktrees whose changes
+/// the user should still be able to pick up. [`prune_stale_agent_worktrees`]
+/// leaves it alone from then on.
+pub(crate) async fn keep_agent_worktree(worktree: &GitWorktree) {
+    let path = worktree.path().to_path_buf();
+    let result = tokio::task::spawn_blocking(move || {
+        if worktree_locks().remove(&path).is_some() {
+            let _ = std::fs::remove_file(lock_path(&path));
+        }
+    })
+    .await;
+    if let Err(err) = result {
+        warn!("failed to release agent worktree: {err}");
+    }
+}
+
+/// Removes the worktrees under `$CODEX_HOME/worktrees` that no running
+/// process holds, together with their branches. These are left behind when
+/// Codex exits without shutting its sessions down. Worktrees without a lock
+/// file were kept on purpose (see [`keep_agent_worktree`]) and are skipped.
+pub(crate) async fn prune_stale_agent_worktrees(codex_home: &Path) {
+    let worktrees_dir = codex_home.join(WORKTREES_DIR_NAME);
+    let result = tokio::task::spawn_blocking(move || {
+        let entries = match std::fs::read_dir(&worktrees_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return,
+            Err(err) => {
+                warn!("failed to list agent worktrees: {err}");
+                return;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                prune_if_stale(&path);
+            }
+        }
+    })
+    .await;
+    if let Err(err) = result {
+        warn!("pruning agent worktrees panicked: {err}");
+    }
+}
+
+fn prune_if_stale(worktree_path: &Path) {
+    let Some(name) = worktree_path.file_name().and_then(|name| name.to_str()) else {
+        return;
+    };
+    if !lock_path(worktree_path).exists() {
+        return;
+    }
+    let lock = match open_lock(worktree_path) {
+        Ok(lock) => lock,
+        Err(err) => {
+            warn!("failed to open lock for {}: {err}", worktree_path.display());
+            return;
+        }
+    };
+    match lock.try_lock() {
+        Ok(()) => {}
+        // Still used by a running session.
+        Err(TryLockError::WouldBlock) => return,
+        Err(TryLockError::Error(err)) => {
+            warn!("failed to lock {}: {err}", worktree_path.display());
+            return;
+        }
+    }
+    let branch = format!("{BRANCH_PREFIX}{name}");
+    if let Err(err) = remove_stale_worktree(worktree_path, &branch) {
+        // Not a usable worktree anymore (e.g. its repository is gone); the
+        // directory is all that is left to clean up.
+        warn!(
+            "failed to remove stale agent worktree {}: {err}",
+            worktree_path.display()
+        );
+        if let Err(err) = std::fs::remove_dir_all(worktree_path) {
+            warn!("failed to delete {}: {err}", worktree_path.display());
+        }
+    }
+    drop(lock);
+    let _ = std::fs::remove_file(lock_path(worktree_path));
+}
+
+fn lock_path(worktree_path: &Path) -> PathBuf {
+    worktree_path.with_extension("lock")
+}
+
+fn open_lock(worktree_path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(worktree_path))
+}
+
+fn lock_worktree(worktree_path: &Path) -> std::io::Result<File> {
+    let lock = open_lock(worktree_path)?;
+    lock.lock()?;
+    Ok(lock)
+}
+
+fn worktree_locks() -> std::sync::MutexGuard<'static, HashMap<PathBuf, File>> {
+    WORKTREE_LOCKS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    #[tokio::test]
+    async fn worktrees_are_tracked_per_parent() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        git(repo.path(), &["init", "--initial-branch=main"]);
+        std::fs::write(repo.path().join("a.txt"), "a\n").expect("write");
+        git(repo.path(), &["add", "."]);
+        git(
+            repo.path(),
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+
+        let worktree = create_agent_worktree(codex_home.path(), repo.path())
+            .await
+            .expect("create worktree");
+        assert!(worktree.path().starts_with(codex_home.path()));
+        assert!(worktree.branch().starts_with(BRANCH_PREFIX));
+
+        let worktrees = AgentWorktrees::default();
+        let parent = ThreadId::new();
+        let agent = ThreadId::new();
+        worktrees.register(agent, parent, worktree.clone());
+        assert_eq!(worktrees.get(agent), Some(worktree.clone()));
+        assert!(worktrees.take_for_parent(ThreadId::new()).is_empty());
+        assert_eq!(worktrees.take_for_parent(parent), vec![worktree.clone()]);
+        assert_eq!(worktrees.get(agent), None);
+
+        remove_agent_worktree(worktree.clone()).await;
+        assert!(!worktree.path().exists());
+    }
+
+    #[tokio::test]
+    async fn prune_removes_only_worktrees_no_process_holds() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        git(repo.path(), &["init", "--initial-branch=main"]);
+        git(
+            repo.path(),
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        );
+
+        let live = create_agent_worktree(codex_home.path(), repo.path())
+            .await
+            .expect("create worktree");
+        let kept = create_agent_worktree(codex_home.path(), repo.path())
+            .await
+            .expect("create worktree");
+        keep_agent_worktree(&kept).await;
+        // What a crashed session leaves behind: a worktree whose lock file
+        // nobody holds.
+        let stale_path = codex_home.path().join(WORKTREES_DIR_NAME).join("stale");
+        create_worktree(repo.path(), &stale_path, &format!("{BRANCH_PREFIX}stale"))
+            .expect("create stale worktree");
+        std::fs::write(lock_path(&stale_path), "").expect("write lock file");
+
+        prune_stale_agent_worktrees(codex_home.path()).await;
+
+        assert!(live.path().exists());
+        assert!(kept.path().exists());
+        assert!(!stale_path.exists());
+        assert!(!lock_path(&stale_path).exists());
+        let branches = Command::new("git")
+            .current_dir(repo.path())
+            .args(["branch", "--list", "codex/agent-*"])
+            .output()
+            .expect("git branch");
+        let branches = String::from_utf8_lossy(&branches.stdout);
+        assert!(!branches.contains("stale"), "branches: {branches}");
+        assert!(branches.contains(live.branch()), "branches: {branches}");
+        assert!(branches.contains(kept.branch()), "branches: {branches}");
+
+        remove_agent_worktree(live.clone()).await;
+        assert!(!lock_path(live.path()).exists());
+    }
+}
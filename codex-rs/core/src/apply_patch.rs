@@ -6,6 +6,7 @@ use crate::safety::assess_patch_safety;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::FileMode;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -81,9 +82,15 @@ pub(crate) fn convert_apply_patch_to_protocol(
     let mut result = HashMap::with_capacity(changes.len());
     for (path, change) in changes {
         let protocol_change = match change {
-            ApplyPatchFileChange::Add { content } => FileChange::Add {
+            ApplyPatchFileChange::Add { content, .. } => FileChange::Add {
                 content: content.clone(),
             },
+            ApplyPatchFileChange::AddBinary { content, .. } => FileChange::AddBinary {
+                size_bytes: content.len(),
+            },
+            ApplyPatchFileChange::AddSymlink { target } => FileChange::AddSymlink {
+                target: target.clone(),
+            },
             ApplyPatchFileChange::Delete { content } => FileChange::Delete {
                 content: content.clone(),
             },
@@ -91,10 +98,14 @@ pub(crate) fn convert_apply_patch_to_protocol(
                 unified_diff,
                 move_path,
                 new_content: _new_content,
+                mode: _mode,
             } => FileChange::Update {
                 unified_diff: unified_diff.clone(),
                 move_path: move_path.clone(),
             },
+            ApplyPatchFileChange::SetMode { mode } => FileChange::ModeChange {
+                executable: *mode == FileMode::Executable,
+            },
         };
         result.insert(path.clone(), protocol_change);
     }
@@ -0,0 +1,513 @@
+//! "Always allow in this repo" approvals.
+//!
+//! When the user picks [`ReviewDecision::ApprovedForRepo`] the approved
+//! command, MCP tool or file writes are recorded in
+//! `$CODEX_HOME/approvals.toml` under the project they were approved in, and
+//! later sessions in that project skip the prompt for them. Projects are keyed
+//! the same way as `[projects]` trust in `config.toml`: the root of the main git
+//! repository (so worktrees share approvals), or the working directory outside
+//! of git.
+//!
+//! [`ReviewDecision::ApprovedForRepo`]: codex_protocol::protocol::ReviewDecision::ApprovedForRepo
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+use wildmatch::WildMatch;
+
+use crate::git_info::resolve_root_git_project_for_trust;
+use crate::path_utils::write_atomically;
+
+pub const APPROVALS_FILE: &str = "approvals.toml";
+/// Held while `approvals.toml` is read, modified and written back, so
+/// concurrent sessions do not drop each other's changes.
+const APPROVALS_LOCK_FILE: &str = "approvals.toml.lock";
+
+/// One approval remembered for a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RememberedApproval {
+    /// A command, matched exactly (program and arguments), run in `cwd`: a
+    /// directory relative to the project root, empty for the root itself.
+    Command { command: Vec<String>, cwd: String },
+    /// An MCP tool as `mcp__<server>__<tool>`; `*` matches any run of
+    /// characters.
+    McpTool(String),
+    /// A file path relative to the project root. A trailing `/` covers a
+    /// whole directory and `*` matches any run of characters.
+    FileWrite(String),
+}
+
+impl RememberedApproval {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Command { .. } => "command",
+            Self::McpTool(_) => "mcp tool",
+            Self::FileWrite(_) => "file write",
+        }
+    }
+
+    /// The remembered value, rendered for display.
+    pub fn value(&self) -> String {
+        match self {
+            Self::Command { command, cwd } => {
+                let command = shlex::try_join(command.iter().map(String::as_str))
+                    .unwrap_or_else(|_| command.join(" "));
+                if cwd.is_empty() {
+                    command
+                } else {
+                    format!("{command} (in {cwd})")
+                }
+            }
+            Self::McpTool(name) | Self::FileWrite(name) => name.clone(),
+        }
+    }
+
+    fn covers(&self, requested: &Self) -> bool {
+        match (self, requested) {
+            (Self::Command { .. }, Self::Command { .. }) => self == requested,
+            (Self::McpTool(pattern), Self::McpTool(name)) => WildMatch::new(pattern).matches(name),
+            (Self::FileWrite(pattern), Self::FileWrite(path)) => match pattern.strip_suffix('/') {
+                Some(dir) => path.starts_with(&format!("{dir}/")),
+                None => WildMatch::new(pattern).matches(path),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for RememberedApproval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.value())
+    }
+}
+
+/// A remembered approval together with the project it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectApproval {
+    pub project: PathBuf,
+    pub approval: RememberedApproval,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ApprovalsFile {
+    #[serde(default)]
+    projects: BTreeMap<String, ProjectApprovals>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ProjectApprovals {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    commands: Vec<StoredCommand>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    mcp_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    file_writes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct StoredCommand {
+    command: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    cwd: String,
+}
+
+impl ProjectApprovals {
+    fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.mcp_tools.is_empty() && self.file_writes.is_empty()
+    }
+
+    fn entries(&self) -> impl Iterator<Item = RememberedApproval> + '_ {
+        self.commands
+            .iter()
+            .cloned()
+            .map(|StoredCommand { command, cwd }| RememberedApproval::Command { command, cwd })
+            .chain(
+                self.mcp_tools
+                    .iter()
+                    .cloned()
+                    .map(RememberedApproval::McpTool),
+            )
+            .chain(
+                self.file_writes
+                    .iter()
+                    .cloned()
+                    .map(RememberedApproval::FileWrite),
+            )
+    }
+
+    fn insert(&mut self, approval: &RememberedApproval) {
+        if self.entries().any(|entry| entry == *approval) {
+            return;
+        }
+        match approval {
+            RememberedApproval::Command { command, cwd } => self.commands.push(StoredCommand {
+                command: command.clone(),
+                cwd: cwd.clone(),
+            }),
+            RememberedApproval::McpTool(name) => self.mcp_tools.push(name.clone()),
+            RememberedApproval::FileWrite(path) => self.file_writes.push(path.clone()),
+        }
+    }
+
+    fn remove(&mut self, approval: &RememberedApproval) -> bool {
+        let before = self.commands.len() + self.mcp_tools.len() + self.file_writes.len();
+        match approval {
+            RememberedApproval::Command { command, cwd } => self
+                .commands
+                .retain(|stored| stored.command != *command || stored.cwd != *cwd),
+            RememberedApproval::McpTool(name) => self.mcp_tools.retain(|n| n != name),
+            RememberedApproval::FileWrite(path) => self.file_writes.retain(|p| p != path),
+        }
+        before != self.commands.len() + self.mcp_tools.len() + self.file_writes.len()
+    }
+}
+
+/// The project remembered approvals are stored under for `cwd`.
+pub fn project_root(cwd: &Path) -> PathBuf {
+    resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+fn project_key(project: &Path) -> String {
+    project.to_string_lossy().to_string()
+}
+
+fn load(codex_home: &Path) -> io::Result<ApprovalsFile> {
+    let path = codex_home.join(APPROVALS_FILE);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(ApprovalsFile::default()),
+        Err(err) => return Err(err),
+    };
+    toml::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to parse {}: {err}", path.display()),
+        )
+    })
+}
+
+fn save(codex_home: &Path, file: &ApprovalsFile) -> io::Result<()> {
+    let contents = toml::to_string_pretty(file).map_err(io::Error::other)?;
+    write_atomically(&codex_home.join(APPROVALS_FILE), &contents)
+}
+
+/// Loads `approvals.toml`, lets `update` modify it and saves it when `update`
+/// returns `true`, all under an advisory lock. The lock lives in a separate
+/// file because saving replaces `approvals.toml`.
+fn update_locked(
+    codex_home: &Path,
+    update: impl FnOnce(&mut ApprovalsFile) -> bool,
+) -> io::Result<bool> {
+    std::fs::create_dir_all(codex_home)?;
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(codex_home.join(APPROVALS_LOCK_FILE))?;
+    lock.lock()?;
+    let mut file = load(codex_home)?;
+    if !update(&mut file) {
+        return Ok(false);
+    }
+    save(codex_home, &file)?;
+    Ok(true)
+}
+
+/// Lists remembered approvals, for one project or for all of them.
+pub fn list_remembered_approvals(
+    codex_home: &Path,
+    project: Option<&Path>,
+) -> io::Result<Vec<ProjectApproval>> {
+    let file = load(codex_home)?;
+    let key = project.map(project_key);
+    Ok(file
+        .projects
+        .iter()
+        .filter(|(project, _)| key.as_ref().is_none_or(|key| key == *project))
+        .flat_map(|(project, approvals)| {
+            approvals.entries().map(move |approval| ProjectApproval {
+                project: PathBuf::from(project),
+                approval,
+            })
+        })
+        .collect())
+}
+
+/// Records `approvals` for `project`, skipping ones already remembered.
+pub fn remember_approvals(
+    codex_home: &Path,
+    project: &Path,
+    approvals: &[RememberedApproval],
+) -> io::Result<()> {
+    if approvals.is_empty() {
+        return Ok(());
+    }
+    update_locked(codex_home, |file| {
+        let entry = file.projects.entry(project_key(project)).or_default();
+        for approval in approvals {
+            entry.insert(approval);
+        }
+        true
+    })?;
+    Ok(())
+}
+
+/// Forgets `approval` for `project`. Returns whether it was remembered.
+pub fn revoke_remembered_approval(
+    codex_home: &Path,
+    project: &Path,
+    approval: &RememberedApproval,
+) -> io::Result<bool> {
+    let key = project_key(project);
+    update_locked(codex_home, |file| {
+        let Some(entry) = file.projects.get_mut(&key) else {
+            return false;
+        };
+        if !entry.remove(approval) {
+            return false;
+        }
+        if entry.is_empty() {
+            file.projects.remove(&key);
+        }
+        true
+    })
+}
+
+/// Whether every one of `requested` is covered by an approval remembered for
+/// `project`. An empty request is never covered.
+pub fn is_remembered(
+    codex_home: &Path,
+    project: &Path,
+    requested: &[RememberedApproval],
+) -> io::Result<bool> {
+    if requested.is_empty() {
+        return Ok(false);
+    }
+    let file = load(codex_home)?;
+    let Some(entry) = file.projects.get(&project_key(project)) else {
+        return Ok(false);
+    };
+    Ok(requested.iter().all(|requested| {
+        entry
+            .entries()
+            .any(|remembered| remembered.covers(requested))
+    }))
+}
+
+/// The session's view of the approvals remembered for its project.
+#[derive(Clone, Debug)]
+pub(crate) struct ApprovalMemory {
+    codex_home: PathBuf,
+    project: PathBuf,
+}
+
+impl ApprovalMemory {
+    pub(crate) fn new(codex_home: PathBuf, cwd: &Path) -> Self {
+        Self {
+            codex_home,
+            project: project_root(cwd),
+        }
+    }
+
+    /// The approval that covers writing `path`, or `None` when the path lies
+    /// outside the project and cannot be remembered for it.
+    pub(crate) fn file_write(&self, path: &Path) -> Option<RememberedApproval> {
+        let relative = self.relative(path)?;
+        (!relative.is_empty()).then_some(RememberedApproval::FileWrite(relative))
+    }
+
+    /// The approval that covers running `command` in `cwd`, or `None` when
+    /// `cwd` lies outside the project.
+    pub(crate) fn command(&self, command: &[String], cwd: &Path) -> Option<RememberedApproval> {
+        Some(RememberedApproval::Command {
+            command: command.to_vec(),
+            cwd: self.relative(cwd)?,
+        })
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.project).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    pub(crate) async fn covers(&self, requested: Vec<RememberedApproval>) -> bool {
+        if requested.is_empty() {
+            return false;
+        }
+        let memory = self.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            is_remembered(&memory.codex_home, &memory.project, &requested)
+        })
+        .await;
+        match result {
+            Ok(Ok(covered)) => covered,
+            Ok(Err(err)) => {
+                warn!("failed to read remembered approvals: {err}");
+                false
+            }
+            Err(err) => {
+                warn!("remembered approvals lookup panicked: {err}");
+                false
+            }
+        }
+    }
+
+    pub(crate) async fn remember(&self, approvals: Vec<RememberedApproval>) {
+        if approvals.is_empty() {
+            return;
+        }
+        let memory = self.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            remember_approvals(&memory.codex_home, &memory.project, &approvals)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => warn!("failed to remember approval: {err}"),
+            Err(err) => warn!("remembering approval panicked: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn remembers_lists_and_revokes_per_project() {
+        let codex_home = tempdir().expect("tempdir");
+        let repo = Path::new("/work/repo");
+        let other = Path::new("/work/other");
+        let command = RememberedApproval::Command {
+            command: vec!["cargo".into(), "test".into()],
+            cwd: String::new(),
+        };
+        let tool = RememberedApproval::McpTool("mcp__github__create_issue".into());
+
+        remember_approvals(codex_home.path(), repo, &[command.clone(), tool.clone()])
+            .expect("remember");
+        remember_approvals(codex_home.path(), repo, std::slice::from_ref(&command))
+            .expect("remember again");
+        remember_approvals(
+            codex_home.path(),
+            other,
+            &[RememberedApproval::FileWrite("src/".into())],
+        )
+        .expect("remember other");
+
+        assert_eq!(
+            list_remembered_approvals(codex_home.path(), Some(repo)).expect("list"),
+            vec![
+                ProjectApproval {
+                    project: repo.to_path_buf(),
+                    approval: command.clone(),
+                },
+                ProjectApproval {
+                    project: repo.to_path_buf(),
+                    approval: tool.clone(),
+                },
+            ]
+        );
+        assert_eq!(
+            list_remembered_approvals(codex_home.path(), None)
+                .expect("list all")
+                .len(),
+            3
+        );
+
+        assert!(revoke_remembered_approval(codex_home.path(), repo, &tool).expect("revoke"));
+        assert!(!revoke_remembered_approval(codex_home.path(), repo, &tool).expect("revoke"));
+        assert!(!is_remembered(codex_home.path(), repo, &[tool]).expect("lookup"));
+        assert!(is_remembered(codex_home.path(), repo, &[command]).expect("lookup"));
+    }
+
+    #[test]
+    fn file_writes_need_every_path_covered() {
+        let codex_home = tempdir().expect("tempdir");
+        let memory = ApprovalMemory {
+            codex_home: codex_home.path().to_path_buf(),
+            project: PathBuf::from("/work/repo"),
+        };
+        let lib = memory
+            .file_write(Path::new("/work/repo/src/lib.rs"))
+            .expect("inside project");
+        assert_eq!(lib, RememberedApproval::FileWrite("src/lib.rs".into()));
+        assert_eq!(memory.file_write(Path::new("/etc/passwd")), None);
+
+        remember_approvals(
+            codex_home.path(),
+            &memory.project,
+            &[lib, RememberedApproval::FileWrite("docs/".into())],
+        )
+        .expect("remember");
+
+        let covered = |paths: &[&str]| {
+            let requested: Vec<RememberedApproval> = paths
+                .iter()
+                .map(|path| RememberedApproval::FileWrite((*path).to_string()))
+                .collect();
+            is_remembered(codex_home.path(), &memory.project, &requested).expect("lookup")
+        };
+        assert!(covered(&["src/lib.rs", "docs/guide/intro.md"]));
+        assert!(!covered(&["src/lib.rs", "src/main.rs"]));
+        assert!(!covered(&[]));
+    }
+
+    #[test]
+    fn commands_are_remembered_per_directory() {
+        let codex_home = tempdir().expect("tempdir");
+        let memory = ApprovalMemory {
+            codex_home: codex_home.path().to_path_buf(),
+            project: PathBuf::from("/work/repo"),
+        };
+        let command = vec!["make".to_string(), "clean".to_string()];
+        let in_docs = memory
+            .command(&command, Path::new("/work/repo/docs"))
+            .expect("inside project");
+        assert_eq!(in_docs.value(), "make clean (in docs)");
+        assert_eq!(memory.command(&command, Path::new("/tmp")), None);
+
+        remember_approvals(
+            codex_home.path(),
+            &memory.project,
+            std::slice::from_ref(&in_docs),
+        )
+        .expect("remember");
+
+        let at_root = memory
+            .command(&command, Path::new("/work/repo"))
+            .expect("inside project");
+        assert!(is_remembered(codex_home.path(), &memory.project, &[in_docs]).expect("lookup"));
+        assert!(!is_remembered(codex_home.path(), &memory.project, &[at_root]).expect("lookup"));
+    }
+
+    #[test]
+    fn concurrent_writers_keep_every_approval() {
+        let codex_home = tempdir().expect("tempdir");
+        let repo = Path::new("/work/repo");
+        let tools: Vec<RememberedApproval> = (0..8)
+            .map(|i| RememberedApproval::McpTool(format!("mcp__server__tool_{i}")))
+            .collect();
+
+        std::thread::scope(|scope| {
+            for tool in &tools {
+                let codex_home = codex_home.path();
+                scope.spawn(move || {
+                    remember_approvals(codex_home, repo, std::slice::from_ref(tool))
+                        .expect("remember");
+                });
+            }
+        });
+
+        assert!(is_remembered(codex_home.path(), repo, &tools).expect("lookup"));
+    }
+}
@@ -22,6 +22,7 @@ pub use crate::auth::storage::AuthCredentialsStoreMode;
 pub use crate::auth::storage::AuthDotJson;
 use crate::auth::storage::AuthStorageBackend;
 use crate::auth::storage::create_auth_storage;
+use crate::auth::storage::delete_migrated_auth;
 use crate::config::Config;
 use crate::error::RefreshTokenFailedError;
 use crate::error::RefreshTokenFailedReason;
@@ -371,6 +372,31 @@ pub fn logout(
     storage.delete()
 }
 
+/// Moves stored CLI credentials from the `from` backend to the `to` backend.
+/// Returns `Ok(false)` when `from` holds no credentials.
+pub fn migrate_auth_credentials(
+    codex_home: &Path,
+    from: AuthCredentialsStoreMode,
+    to: AuthCredentialsStoreMode,
+) -> std::io::Result<bool> {
+    if [from, to].contains(&AuthCredentialsStoreMode::Ephemeral) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "ephemeral credentials cannot be migrated",
+        ));
+    }
+    if from == to {
+        return Ok(false);
+    }
+    let source = create_auth_storage(codex_home.to_path_buf(), from);
+    let Some(auth) = source.load()? else {
+        return Ok(false);
+    };
+    create_auth_storage(codex_home.to_path_buf(), to).save(&auth)?;
+    delete_migrated_auth(codex_home, from, to)?;
+    Ok(true)
+}
+
 /// Writes an `auth.json` that contains only the API key.
 pub fn login_with_api_key(
     codex_home: &Path,
@@ -1317,6 +1343,67 @@ mod tests {
         assert_eq!(tokens.refresh_token, "new-refresh-token");
     }
 
+    #[test]
+    fn migrate_auth_credentials_moves_auth_between_file_and_encrypted_store() {
+        let dir = tempdir().unwrap();
+        super::login_with_api_key(dir.path(), "sk-migrate", AuthCredentialsStoreMode::File)
+            .expect("login_with_api_key should succeed");
+
+        let migrated = super::migrate_auth_credentials(
+            dir.path(),
+            AuthCredentialsStoreMode::File,
+            AuthCredentialsStoreMode::Encrypted,
+        )
+        .expect("migration to encrypted store should succeed");
+        assert!(migrated);
+        assert!(!get_auth_file(dir.path()).exists());
+        let encrypted = super::load_auth_dot_json(dir.path(), AuthCredentialsStoreMode::Encrypted)
+            .expect("encrypted store should load")
+            .expect("credentials should be in the encrypted store");
+        assert_eq!(encrypted.openai_api_key.as_deref(), Some("sk-migrate"));
+
+        let migrated = super::migrate_auth_credentials(
+            dir.path(),
+            AuthCredentialsStoreMode::Encrypted,
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("migration back to file should succeed");
+        assert!(migrated);
+        assert_eq!(
+            super::load_auth_dot_json(dir.path(), AuthCredentialsStoreMode::Encrypted)
+                .expect("encrypted store should load"),
+            None
+        );
+        let file = super::load_auth_dot_json(dir.path(), AuthCredentialsStoreMode::File)
+            .expect("auth.json should load")
+            .expect("credentials should be back in auth.json");
+        assert_eq!(file.openai_api_key.as_deref(), Some("sk-migrate"));
+    }
+
+    #[test]
+    fn failed_auth_migration_keeps_the_source_credentials() {
+        let dir = tempdir().unwrap();
+        super::login_with_api_key(
+            dir.path(),
+            "sk-migrate",
+            AuthCredentialsStoreMode::Encrypted,
+        )
+        .expect("login_with_api_key should succeed");
+        // A directory in place of auth.json makes saving to the file backend fail.
+        std::fs::create_dir(get_auth_file(dir.path())).unwrap();
+
+        super::migrate_auth_credentials(
+            dir.path(),
+            AuthCredentialsStoreMode::Encrypted,
+            AuthCredentialsStoreMode::File,
+        )
+        .expect_err("migration to an unwritable auth.json should fail");
+        let encrypted = super::load_auth_dot_json(dir.path(), AuthCredentialsStoreMode::Encrypted)
+            .expect("encrypted store should load")
+            .expect("credentials should stay in the encrypted store");
+        assert_eq!(encrypted.openai_api_key.as_deref(), Some("sk-migrate"));
+    }
+
     #[test]
     fn login_with_api_key_overwrites_existing_auth_json() {
         let dir = tempdir().unwrap();
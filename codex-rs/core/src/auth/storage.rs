@@ -22,6 +22,7 @@ use tracing::warn;
 use crate::token_data::TokenData;
 use codex_app_server_protocol::AuthMode;
 use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::EncryptedFileKeyringStore;
 use codex_keyring_store::KeyringStore;
 use once_cell::sync::Lazy;
 
@@ -38,6 +39,9 @@ pub enum AuthCredentialsStoreMode {
     Auto,
     /// Store credentials in memory only for the current process.
     Ephemeral,
+    /// Persist credentials in CODEX_HOME/credentials.age, encrypted with a
+    /// passphrase or key file. For machines without an OS keyring.
+    Encrypted,
 }
 
 /// Expected structure for $CODEX_HOME/auth.json.
@@ -210,15 +214,20 @@ impl AuthStorageBackend for KeyringAuthStorage {
     }
 
     fn delete(&self) -> std::io::Result<bool> {
+        let keyring_removed = self.delete_from_keyring()?;
+        let file_removed = delete_file_if_exists(&self.codex_home)?;
+        Ok(keyring_removed || file_removed)
+    }
+}
+
+impl KeyringAuthStorage {
+    fn delete_from_keyring(&self) -> std::io::Result<bool> {
         let key = compute_store_key(&self.codex_home)?;
-        let keyring_removed = self
-            .keyring_store
+        self.keyring_store
             .delete(KEYRING_SERVICE, &key)
             .map_err(|err| {
                 std::io::Error::other(format!("failed to delete auth from keyring: {err}"))
-            })?;
-        let file_removed = delete_file_if_exists(&self.codex_home)?;
-        Ok(keyring_removed || file_removed)
+            })
     }
 }
 
@@ -312,10 +321,60 @@ pub(super) fn create_auth_storage(
     codex_home: PathBuf,
     mode: AuthCredentialsStoreMode,
 ) -> Arc<dyn AuthStorageBackend> {
-    let keyring_store: Arc<dyn KeyringStore> = Arc::new(DefaultKeyringStore);
+    let keyring_store = keyring_store_for_mode(&codex_home, mode);
     create_auth_storage_with_keyring_store(codex_home, mode, keyring_store)
 }
 
+fn keyring_store_for_mode(
+    codex_home: &Path,
+    mode: AuthCredentialsStoreMode,
+) -> Arc<dyn KeyringStore> {
+    match mode {
+        AuthCredentialsStoreMode::Encrypted => {
+            Arc::new(EncryptedFileKeyringStore::from_env(codex_home))
+        }
+        _ => Arc::new(DefaultKeyringStore),
+    }
+}
+
+/// Removes the credentials `from` held once they have been saved to `to`.
+/// `Keyring` and `Auto` share a keyring entry and `File` and `Auto` share
+/// `auth.json`, so whatever `to` now stores in those places is kept.
+pub(super) fn delete_migrated_auth(
+    codex_home: &Path,
+    from: AuthCredentialsStoreMode,
+    to: AuthCredentialsStoreMode,
+) -> std::io::Result<()> {
+    let default_keyring = |mode: AuthCredentialsStoreMode| {
+        matches!(
+            mode,
+            AuthCredentialsStoreMode::Keyring | AuthCredentialsStoreMode::Auto
+        )
+    };
+    let auth_file = |mode: AuthCredentialsStoreMode| {
+        matches!(
+            mode,
+            AuthCredentialsStoreMode::File | AuthCredentialsStoreMode::Auto
+        )
+    };
+    let from_keyring = default_keyring(from) || from == AuthCredentialsStoreMode::Encrypted;
+    if from_keyring && !(default_keyring(from) && default_keyring(to)) {
+        let keyring_store = keyring_store_for_mode(codex_home, from);
+        let storage = KeyringAuthStorage::new(codex_home.to_path_buf(), keyring_store);
+        match storage.delete_from_keyring() {
+            Ok(_) => {}
+            // `Auto` may have been reading `auth.json` because the keyring
+            // is unavailable.
+            Err(err) if from == AuthCredentialsStoreMode::Auto => warn!("{err}"),
+            Err(err) => return Err(err),
+        }
+    }
+    if auth_file(from) && !auth_file(to) {
+        delete_file_if_exists(codex_home)?;
+    }
+    Ok(())
+}
+
 fn create_auth_storage_with_keyring_store(
     codex_home: PathBuf,
     mode: AuthCredentialsStoreMode,
@@ -323,7 +382,7 @@ fn create_auth_storage_with_keyring_store(
 ) -> Arc<dyn AuthStorageBackend> {
     match mode {
         AuthCredentialsStoreMode::File => Arc::new(FileAuthStorage::new(codex_home)),
-        AuthCredentialsStoreMode::Keyring => {
+        AuthCredentialsStoreMode::Keyring | AuthCredentialsStoreMode::Encrypted => {
             Arc::new(KeyringAuthStorage::new(codex_home, keyring_store))
         }
         AuthCredentialsStoreMode::Auto => Arc::new(AutoAuthStorage::new(codex_home, keyring_store)),
@@ -5,6 +5,8 @@ use tree_sitter::Parser;
 use tree_sitter::Tree;
 use tree_sitter_bash::LANGUAGE as BASH;
 
+use crate::fish::parse_fish_plain_commands;
+use crate::nushell::parse_nushell_plain_commands;
 use crate::shell::ShellType;
 use crate::shell::detect_shell_type;
 
@@ -119,6 +121,14 @@ pub fn parse_shell_lc_plain_commands(command: &[String]) -> Option<Vec<Vec<Strin
     try_parse_word_only_commands_sequence(&tree, script)
 }
 
+/// Like [`parse_shell_lc_plain_commands`], but also accepts `fish -c "..."`
+/// and `nu -c "..."` invocations.
+pub fn parse_shell_script_plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    parse_shell_lc_plain_commands(command)
+        .or_else(|| parse_fish_plain_commands(command))
+        .or_else(|| parse_nushell_plain_commands(command))
+}
+
 fn parse_plain_command_from_node(cmd: tree_sitter::Node, src: &str) -> Option<Vec<String>> {
     if cmd.kind() != "command" {
         return None;
@@ -0,0 +1,167 @@
+//! Tiny language server used by the LSP tool tests.
+//!
+//! Speaks LSP over stdio. Definitions resolve to the line declaring
+//! `fn <word>`, hover returns the word under the cursor, and every line
+//! containing `ERROR` is published as an error diagnostic.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::Write;
+
+use serde_json::Value;
+use serde_json::json;
+
+fn main() -> std::io::Result<()> {
+    let mut reader = std::io::stdin().lock();
+    let mut writer = std::io::stdout().lock();
+    let mut documents: HashMap<String, String> = HashMap::new();
+
+    while let Some(message) = read_message(&mut reader)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        match method {
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                let uri = document["uri"].as_str().unwrap_or_default().to_string();
+                let text = document["text"].as_str().unwrap_or_default().to_string();
+                publish_diagnostics(&mut writer, &uri, &text)?;
+                documents.insert(uri, text);
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                publish_diagnostics(&mut writer, &uri, &text)?;
+                documents.insert(uri, text);
+            }
+            "exit" => break,
+            _ => {
+                if let Some(id) = message.get("id") {
+                    let result = handle(method, params, &documents);
+                    send(
+                        &mut writer,
+                        &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_message(reader: &mut impl BufRead) -> std::io::Result<Option<Value>> {
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().map_err(std::io::Error::other)?;
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn send(writer: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    writer.flush()
+}
+
+fn handle(method: &str, params: &Value, documents: &HashMap<String, String>) -> Value {
+    match method {
+        "initialize" => {
+            json!({ "capabilities": { "definitionProvider": true, "hoverProvider": true } })
+        }
+        "textDocument/definition" => definition(params, documents).unwrap_or(Value::Null),
+        "textDocument/hover" => {
+            let word = document_word(params, documents).unwrap_or_default();
+            json!({ "contents": { "kind": "markdown", "value": format!("`{word}`") } })
+        }
+        _ => Value::Null,
+    }
+}
+
+fn definition(params: &Value, documents: &HashMap<String, String>) -> Option<Value> {
+    let uri = params["textDocument"]["uri"].as_str()?;
+    let word = document_word(params, documents)?;
+    let declaration = format!("fn {word}");
+    documents
+        .get(uri)?
+        .lines()
+        .enumerate()
+        .find_map(|(number, line)| {
+            let column = line.find(&declaration)?;
+            let start = json!({ "line": number, "character": column + 3 });
+            Some(json!({ "uri": uri, "range": { "start": start, "end": start } }))
+        })
+}
+
+/// The word under the cursor at `params.position` in `params.textDocument`.
+fn document_word(params: &Value, documents: &HashMap<String, String>) -> Option<String> {
+    let text = documents.get(params["textDocument"]["uri"].as_str()?)?;
+    let position = &params["position"];
+    let line = text
+        .lines()
+        .nth(usize::try_from(position["line"].as_u64()?).ok()?)?;
+    let character = usize::try_from(position["character"].as_u64()?).ok()?;
+
+    let mut word_start = None;
+    for (index, ch) in line.char_indices().chain([(line.len(), ' ')]) {
+        let in_word = ch.is_alphanumeric() || ch == '_';
+        match word_start {
+            None if in_word => word_start = Some(index),
+            Some(start) if !in_word => {
+                if start <= character && character <= index {
+                    return Some(line[start..index].to_string());
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn publish_diagnostics(writer: &mut impl Write, uri: &str, text: &str) -> std::io::Result<()> {
+    let diagnostics: Vec<Value> = text
+        .lines()
+        .enumerate()
+        .filter_map(|(number, line)| {
+            let column = line.find("ERROR")?;
+            Some(json!({
+                "range": {
+                    "start": { "line": number, "character": column },
+                    "end": { "line": number, "character": column + 5 },
+                },
+                "severity": 1,
+                "message": "found ERROR",
+                "source": "stub",
+            }))
+        })
+        .collect();
+    send(
+        writer,
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }),
+    )
+}
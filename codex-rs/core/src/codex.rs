@@ -13,8 +13,10 @@ use crate::agent::AgentControl;
 use crate::agent::AgentStatus;
 use crate::agent::MAX_THREAD_SPAWN_DEPTH;
 use crate::agent::agent_status_from_event;
+use crate::agent::worktree::prune_stale_agent_worktrees;
 use crate::analytics_client::AnalyticsEventsClient;
 use crate::analytics_client::build_track_events_context;
+use crate::approval_memory::ApprovalMemory;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
@@ -25,6 +27,7 @@ use crate::features::Feature;
 use crate::features::Features;
 use crate::features::maybe_push_unstable_features_warning;
 use crate::models_manager::manager::ModelsManager;
+use crate::models_manager::model_info::tokenizer_for_model;
 use crate::parse_command::parse_command;
 use crate::parse_turn_item;
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
@@ -44,6 +47,7 @@ use async_channel::Sender;
 use codex_protocol::ThreadId;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::config_types::ModeKind;
+use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::Settings;
 use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::dynamic_tools::DynamicToolResponse;
@@ -59,6 +63,7 @@ use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::PinnedMessage;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
@@ -109,6 +114,7 @@ use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::collaboration_modes::CollaborationModeToml;
 use crate::config::resolve_web_search_mode_for_turn;
 use crate::config::types::McpServerConfig;
 use crate::config::types::ShellEnvironmentPolicy;
@@ -122,6 +128,7 @@ use crate::exec_policy::ExecPolicyUpdateError;
 use crate::feedback_tags;
 use crate::git_info::get_git_repo_root;
 use crate::instructions::UserInstructions;
+use crate::lsp::LspManager;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp::effective_mcp_servers;
@@ -141,6 +148,7 @@ use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::CollaborationModeUpdatedEvent;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
@@ -173,6 +181,7 @@ use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
 use crate::rollout::metadata;
 use crate::shell;
+use crate::shell_secrets::ShellSecrets;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillError;
 use crate::skills::SkillInjections;
@@ -219,6 +228,7 @@ use codex_protocol::protocol::InitialHistory;
 use codex_protocol::user_input::UserInput;
 use codex_utils_readiness::Readiness;
 use codex_utils_readiness::ReadinessFlag;
+use codex_utils_tokenizer::Tokenizer;
 use tokio::sync::watch;
 
 /// The high-level interface to the Codex system.
@@ -352,6 +362,7 @@ impl Codex {
                 reasoning_effort: config.model_reasoning_effort,
                 developer_instructions: None,
             },
+            custom_mode: None,
         };
         let session_configuration = SessionConfiguration {
             provider: config.model_provider.clone(),
@@ -375,6 +386,7 @@ impl Codex {
 
         // Generate a unique ID for the lifetime of this Codex session.
         let session_source_clone = session_configuration.session_source.clone();
+        let is_subagent = matches!(session_source_clone, SessionSource::SubAgent(_));
         let (agent_status_tx, agent_status_rx) = watch::channel(AgentStatus::PendingInit);
 
         let session_init_span = info_span!("session_init");
@@ -399,6 +411,12 @@ impl Codex {
         })?;
         let thread_id = session.conversation_id;
 
+        // Clean up sub-agent worktrees that earlier runs left behind.
+        if !is_subagent {
+            let codex_home = config.codex_home.clone();
+            tokio::spawn(async move { prune_stale_agent_worktrees(&codex_home).await });
+        }
+
         // This task will run until Op::Shutdown is received.
         let session_loop_span = info_span!("session_loop", thread_id = %thread_id);
         tokio::spawn(
@@ -505,6 +523,8 @@ pub(crate) struct TurnContext {
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    /// Local tokenizer for the turn's model, when its encoding is known.
+    pub(crate) tokenizer: Option<Tokenizer>,
     pub(crate) dynamic_tools: Vec<DynamicToolSpec>,
     turn_metadata_header: OnceCell<Option<String>>,
 }
@@ -621,6 +641,50 @@ impl SessionConfiguration {
         }
     }
 
+    /// The user-defined collaboration mode currently active, if any.
+    fn custom_collaboration_mode(&self) -> Option<&CollaborationModeToml> {
+        let name = self.collaboration_mode.custom_mode.as_ref()?;
+        self.original_config_do_not_use
+            .collaboration_modes
+            .get(name)
+    }
+
+    /// Approval policy for the next turn: the active custom mode's policy when
+    /// the configured constraints allow it, otherwise the session's.
+    fn turn_approval_policy(&self) -> AskForApproval {
+        match self
+            .custom_collaboration_mode()
+            .and_then(|mode| mode.approval_policy)
+        {
+            Some(policy) if self.approval_policy.can_set(&policy).is_ok() => policy,
+            _ => self.approval_policy.value(),
+        }
+    }
+
+    /// Sandbox policy for the next turn: the active custom mode's sandbox when
+    /// the configured constraints allow it, otherwise the session's.
+    fn turn_sandbox_policy(&self) -> SandboxPolicy {
+        let current = self.sandbox_policy.get();
+        let policy = match self
+            .custom_collaboration_mode()
+            .and_then(|mode| mode.sandbox_mode)
+        {
+            Some(SandboxMode::ReadOnly) => SandboxPolicy::new_read_only_policy(),
+            Some(SandboxMode::WorkspaceWrite)
+                if !matches!(current, SandboxPolicy::WorkspaceWrite { .. }) =>
+            {
+                SandboxPolicy::new_workspace_write_policy()
+            }
+            Some(SandboxMode::DangerFullAccess) => SandboxPolicy::DangerFullAccess,
+            _ => return current.clone(),
+        };
+        if self.sandbox_policy.can_set(&policy).is_ok() {
+            policy
+        } else {
+            current.clone()
+        }
+    }
+
     pub(crate) fn apply(&self, updates: &SessionSettingsUpdate) -> ConstraintResult<Self> {
         let mut next_configuration = self.clone();
         if let Some(collaboration_mode) = updates.collaboration_mode.clone() {
@@ -676,6 +740,12 @@ impl Session {
             session_configuration.sandbox_policy.get(),
         ));
         per_turn_config.features = config.features.clone();
+        if let Some(tools) = session_configuration
+            .custom_collaboration_mode()
+            .and_then(|mode| mode.tools.as_ref())
+        {
+            per_turn_config.restrict_allowed_tools(tools);
+        }
         per_turn_config
     }
 
@@ -718,7 +788,10 @@ impl Session {
             model_info: &model_info,
             features: &per_turn_config.features,
             web_search_mode: per_turn_config.web_search_mode,
-        });
+        })
+        .with_agent_roles(&per_turn_config.agent_roles)
+        .with_allowed_tools(per_turn_config.allowed_tools.clone())
+        .with_lsp_tools(!per_turn_config.lsp_servers.is_empty());
 
         let cwd = session_configuration.cwd.clone();
         TurnContext {
@@ -730,8 +803,8 @@ impl Session {
             user_instructions: session_configuration.user_instructions.clone(),
             collaboration_mode: session_configuration.collaboration_mode.clone(),
             personality: session_configuration.personality,
-            approval_policy: session_configuration.approval_policy.value(),
-            sandbox_policy: session_configuration.sandbox_policy.get().clone(),
+            approval_policy: session_configuration.turn_approval_policy(),
+            sandbox_policy: session_configuration.turn_sandbox_policy(),
             windows_sandbox_level: session_configuration.windows_sandbox_level,
             shell_environment_policy: per_turn_config.shell_environment_policy.clone(),
             tools_config,
@@ -740,6 +813,7 @@ impl Session {
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: model_info.truncation_policy.into(),
+            tokenizer: tokenizer_for_model(&model_info),
             dynamic_tools: session_configuration.dynamic_tools.clone(),
             turn_metadata_header: OnceCell::new(),
         }
@@ -937,8 +1011,26 @@ impl Session {
                 }
             };
         session_configuration.thread_name = thread_name.clone();
+        let (shell_secrets, shell_secrets_warning) = ShellSecrets::resolve(
+            &config.codex_home,
+            config.secrets_backend,
+            config.secrets_key_store,
+            &session_configuration.cwd,
+            &config.shell_environment_policy.secrets,
+        )
+        .await;
+        if let Some(message) = shell_secrets_warning {
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent { message }),
+            });
+        }
         let state = SessionState::new(session_configuration.clone());
 
+        let symbol_index = config.features.enabled(Feature::SymbolIndex).then(|| {
+            agent_control
+                .symbol_index(get_git_repo_root(&config.cwd).unwrap_or_else(|| config.cwd.clone()))
+        });
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
@@ -956,10 +1048,15 @@ impl Session {
             otel_manager,
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_rules: config.approval_rules.clone(),
+            approval_memory: ApprovalMemory::new(config.codex_home.clone(), &config.cwd),
             skills_manager,
             agent_control,
             state_db: state_db_ctx.clone(),
             transport_manager: TransportManager::new(),
+            shell_secrets,
+            lsp_manager: LspManager::new(config.lsp_servers.clone()),
+            symbol_index,
         };
 
         let sess = Arc::new(Session {
@@ -1064,9 +1161,12 @@ impl Session {
         format!("auto-compact-{id}")
     }
 
-    async fn get_total_token_usage(&self) -> i64 {
+    async fn get_total_token_usage(&self, turn_context: &TurnContext) -> i64 {
         let state = self.state.lock().await;
-        state.get_total_token_usage(state.server_reasoning_included())
+        state.get_total_token_usage(
+            state.server_reasoning_included(),
+            turn_context.tokenizer.as_ref(),
+        )
     }
 
     async fn get_estimated_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
@@ -1128,6 +1228,12 @@ impl Session {
                     }
                 }
 
+                // Restore pins first: rebuilding compacted history re-injects them.
+                if let Some(pinned) = Self::last_pinned_messages_from_rollout(&rollout_items) {
+                    let mut state = self.state.lock().await;
+                    state.pinned_messages = pinned;
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history = self
                     .reconstruct_history_from_rollout(&turn_context, &rollout_items)
@@ -1137,18 +1243,22 @@ impl Session {
                         .await;
                 }
 
-                // Seed usage info from the recorded rollout so UIs can show token counts
-                // immediately on resume/fork.
-                if let Some(info) = Self::last_token_info_from_rollout(&rollout_items) {
-                    let mut state = self.state.lock().await;
-                    state.set_token_info(Some(info));
-                }
+                self.seed_token_info_from_rollout(&turn_context, &rollout_items)
+                    .await;
+                self.restore_collaboration_mode_from_rollout(&rollout_items)
+                    .await;
 
                 // Defer seeding the session's initial context until the first turn starts so
                 // turn/start overrides can be merged before we write to the rollout.
                 self.flush_rollout().await;
             }
             InitialHistory::Forked(rollout_items) => {
+                // Restore pins first: rebuilding compacted history re-injects them.
+                if let Some(pinned) = Self::last_pinned_messages_from_rollout(&rollout_items) {
+                    let mut state = self.state.lock().await;
+                    state.pinned_messages = pinned;
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history = self
                     .reconstruct_history_from_rollout(&turn_context, &rollout_items)
@@ -1158,12 +1268,10 @@ impl Session {
                         .await;
                 }
 
-                // Seed usage info from the recorded rollout so UIs can show token counts
-                // immediately on resume/fork.
-                if let Some(info) = Self::last_token_info_from_rollout(&rollout_items) {
-                    let mut state = self.state.lock().await;
-                    state.set_token_info(Some(info));
-                }
+                self.seed_token_info_from_rollout(&turn_context, &rollout_items)
+                    .await;
+                self.restore_collaboration_mode_from_rollout(&rollout_items)
+                    .await;
 
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if !rollout_items.is_empty() {
@@ -1184,6 +1292,40 @@ impl Session {
         }
     }
 
+    /// Seed usage info from the recorded rollout so UIs can show token counts
+    /// immediately on resume/fork. Rollouts without a recorded count fall back
+    /// to a local estimate of the reconstructed history.
+    async fn seed_token_info_from_rollout(
+        &self,
+        turn_context: &TurnContext,
+        rollout_items: &[RolloutItem],
+    ) {
+        if let Some(info) = Self::last_token_info_from_rollout(rollout_items) {
+            let mut state = self.state.lock().await;
+            state.set_token_info(Some(info));
+            return;
+        }
+        if !rollout_items
+            .iter()
+            .any(|item| matches!(item, RolloutItem::ResponseItem(_)))
+        {
+            return;
+        }
+        let Some(estimated_total_tokens) = self.get_estimated_token_count(turn_context).await
+        else {
+            return;
+        };
+        let mut state = self.state.lock().await;
+        state.set_token_info(Some(TokenUsageInfo {
+            total_token_usage: TokenUsage::default(),
+            last_token_usage: TokenUsage {
+                total_tokens: estimated_total_tokens.max(0),
+                ..TokenUsage::default()
+            },
+            model_context_window: turn_context.client.get_model_context_window(),
+        }));
+    }
+
     fn last_token_info_from_rollout(rollout_items: &[RolloutItem]) -> Option<TokenUsageInfo> {
         rollout_items.iter().rev().find_map(|item| match item {
             RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => ev.info.clone(),
@@ -1191,15 +1333,101 @@ impl Session {
         })
     }
 
+    /// Text of the user or agent message item `item_id`, looked up in the
+    /// rollout so messages from before a resume can be pinned too.
+    async fn pinnable_message_text(&self, item_id: &str) -> Option<String> {
+        self.flush_rollout().await;
+        let rollout_path = {
+            let guard = self.services.rollout.lock().await;
+            guard.as_ref()?.rollout_path().to_path_buf()
+        };
+        let rollout_items = match RolloutRecorder::load_rollout_items(&rollout_path).await {
+            Ok((rollout_items, _, _)) => rollout_items,
+            Err(err) => {
+                warn!("failed to read rollout to pin a message: {err}");
+                return None;
+            }
+        };
+        rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::ItemCompleted(event)) if event.item.id() == item_id => {
+                pinnable_text(&event.item)
+            }
+            _ => None,
+        })
+    }
+
+    fn last_pinned_messages_from_rollout(
+        rollout_items: &[RolloutItem],
+    ) -> Option<Vec<PinnedMessage>> {
+        rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::PinnedMessagesUpdated(ev)) => {
+                Some(ev.pinned_messages.clone())
+            }
+            _ => None,
+        })
+    }
+
+    /// Restores the last collaboration mode recorded in the rollout, keeping
+    /// the model the session was started with.
+    async fn restore_collaboration_mode_from_rollout(&self, rollout_items: &[RolloutItem]) {
+        let Some(collaboration_mode) = rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::CollaborationModeUpdated(ev)) => {
+                Some(ev.collaboration_mode.clone())
+            }
+            _ => None,
+        }) else {
+            return;
+        };
+        let mut state = self.state.lock().await;
+        let model = state
+            .session_configuration
+            .collaboration_mode
+            .model()
+            .to_string();
+        state.session_configuration.collaboration_mode =
+            collaboration_mode.with_updates(Some(model), None, None);
+    }
+
+    /// Records a switch to a different collaboration mode so resumed sessions
+    /// can restore it.
+    async fn send_collaboration_mode_updated(
+        &self,
+        sub_id: String,
+        previous: &CollaborationMode,
+        next: &CollaborationMode,
+    ) {
+        if previous.mode == next.mode && previous.custom_mode == next.custom_mode {
+            return;
+        }
+        self.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::CollaborationModeUpdated(CollaborationModeUpdatedEvent {
+                collaboration_mode: next.clone(),
+            }),
+        })
+        .await;
+    }
+
+    pub(crate) async fn pinned_messages(&self) -> Vec<PinnedMessage> {
+        let state = self.state.lock().await;
+        state.pinned_messages.clone()
+    }
+
     pub(crate) async fn update_settings(
         &self,
+        sub_id: String,
         updates: SessionSettingsUpdate,
     ) -> ConstraintResult<()> {
         let mut state = self.state.lock().await;
 
         match state.session_configuration.apply(&updates) {
             Ok(updated) => {
-                state.session_configuration = updated;
+                let previous_mode =
+                    std::mem::replace(&mut state.session_configuration, updated).collaboration_mode;
+                let next_mode = state.session_configuration.collaboration_mode.clone();
+                drop(state);
+                self.send_collaboration_mode_updated(sub_id, &previous_mode, &next_mode)
+                    .await;
                 Ok(())
             }
             Err(err) => {
@@ -1214,14 +1442,15 @@ impl Session {
         sub_id: String,
         updates: SessionSettingsUpdate,
     ) -> ConstraintResult<Arc<TurnContext>> {
-        let (session_configuration, sandbox_policy_changed) = {
+        let (session_configuration, sandbox_policy_changed, previous_mode) = {
             let mut state = self.state.lock().await;
             match state.session_configuration.clone().apply(&updates) {
                 Ok(next) => {
                     let sandbox_policy_changed =
                         state.session_configuration.sandbox_policy != next.sandbox_policy;
-                    state.session_configuration = next.clone();
-                    (next, sandbox_policy_changed)
+                    let previous =
+                        std::mem::replace(&mut state.session_configuration, next.clone());
+                    (next, sandbox_policy_changed, previous.collaboration_mode)
                 }
                 Err(err) => {
                     drop(state);
@@ -1237,6 +1466,12 @@ impl Session {
                 }
             }
         };
+        self.send_collaboration_mode_updated(
+            sub_id.clone(),
+            &previous_mode,
+            &session_configuration.collaboration_mode,
+        )
+        .await;
 
         Ok(self
             .new_turn_from_configuration(
@@ -1469,7 +1704,8 @@ impl Session {
         }
     }
 
-    pub(crate) async fn send_event_raw(&self, event: Event) {
+    pub(crate) async fn send_event_raw(&self, mut event: Event) {
+        self.services.shell_secrets.redact_event(&mut event.msg);
         // Record the last known agent status.
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
@@ -1487,7 +1723,8 @@ impl Session {
     /// Most events can be delivered immediately after queueing the rollout write, but some
     /// clients (e.g. app-server thread/rollback) re-read the rollout file synchronously on
     /// receipt of the event and depend on the marker already being visible on disk.
-    pub(crate) async fn send_event_raw_flushed(&self, event: Event) {
+    pub(crate) async fn send_event_raw_flushed(&self, mut event: Event) {
+        self.services.shell_secrets.redact_event(&mut event.msg);
         // Record the last known agent status.
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
@@ -1815,6 +2052,7 @@ impl Session {
                     history.record_items(
                         std::iter::once(response_item),
                         turn_context.truncation_policy,
+                        turn_context.tokenizer.as_ref(),
                     );
                 }
                 RolloutItem::Compacted(compacted) => {
@@ -1823,7 +2061,7 @@ impl Session {
                     } else {
                         let user_messages = collect_user_messages(history.raw_items());
                         let rebuilt = compact::build_compacted_history(
-                            self.build_initial_context(turn_context).await,
+                            compact::compacted_initial_context(self, turn_context).await,
                             &user_messages,
                             &compacted.message,
                         );
@@ -1846,7 +2084,11 @@ impl Session {
         turn_context: &TurnContext,
     ) {
         let mut state = self.state.lock().await;
-        state.record_items(items.iter(), turn_context.truncation_policy);
+        state.record_items(
+            items.iter(),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
     }
 
     pub(crate) async fn record_model_warning(&self, message: impl Into<String>, ctx: &TurnContext) {
@@ -2582,6 +2824,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::SetThreadName { name } => {
                 handlers::set_thread_name(&sess, sub.id.clone(), name).await;
             }
+            Op::PinMessage { item_id } => {
+                handlers::pin_message(&sess, sub.id.clone(), item_id).await;
+            }
+            Op::UnpinMessage { id } => {
+                handlers::unpin_message(&sess, sub.id.clone(), id).await;
+            }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
                     &sess,
@@ -2614,6 +2862,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
 
 /// Operation handlers
 mod handlers {
+    use crate::agent::worktree::remove_agent_worktree;
     use crate::codex::Session;
     use crate::codex::SessionSettingsUpdate;
     use crate::codex::TurnContext;
@@ -2640,6 +2889,8 @@ mod handlers {
     use codex_protocol::protocol::ListSkillsResponseEvent;
     use codex_protocol::protocol::McpServerRefreshConfig;
     use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::PinnedMessage;
+    use codex_protocol::protocol::PinnedMessagesUpdatedEvent;
     use codex_protocol::protocol::RemoteSkillDownloadedEvent;
     use codex_protocol::protocol::RemoteSkillSummary;
     use codex_protocol::protocol::ReviewDecision;
@@ -2674,7 +2925,7 @@ mod handlers {
         sub_id: String,
         updates: SessionSettingsUpdate,
     ) {
-        if let Err(err) = sess.update_settings(updates).await {
+        if let Err(err) = sess.update_settings(sub_id.clone(), updates).await {
             sess.send_event_raw(Event {
                 id: sub_id,
                 msg: EventMsg::Error(ErrorEvent {
@@ -2713,6 +2964,7 @@ mod handlers {
                             reasoning_effort: effort,
                             developer_instructions: None,
                         },
+                        custom_mode: None,
                     })
                 });
                 (
@@ -3200,12 +3452,94 @@ mod handlers {
         .await;
     }
 
+    /// Pins the user or agent message item `item_id` and emits the updated set.
+    ///
+    /// Pinned messages are injected whenever compaction rebuilds the history,
+    /// so they are carried verbatim across it, and are restored from the
+    /// rollout on resume.
+    pub async fn pin_message(sess: &Arc<Session>, sub_id: String, item_id: String) {
+        let text = sess.pinnable_message_text(&item_id).await;
+        let pinned_messages = {
+            let mut state = sess.state.lock().await;
+            match text {
+                Some(text) => {
+                    if !state
+                        .pinned_messages
+                        .iter()
+                        .any(|message| message.id == item_id)
+                    {
+                        state.pinned_messages.push(PinnedMessage {
+                            id: item_id.clone(),
+                            text,
+                        });
+                    }
+                    Some(state.pinned_messages.clone())
+                }
+                None => None,
+            }
+        };
+        let Some(pinned_messages) = pinned_messages else {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("No message with id {item_id} to pin."),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            };
+            sess.send_event_raw(event).await;
+            return;
+        };
+        send_pinned_messages_updated(sess, sub_id, pinned_messages).await;
+    }
+
+    pub async fn unpin_message(sess: &Arc<Session>, sub_id: String, id: String) {
+        let pinned_messages = {
+            let mut state = sess.state.lock().await;
+            let before = state.pinned_messages.len();
+            state.pinned_messages.retain(|message| message.id != id);
+            (state.pinned_messages.len() != before).then(|| state.pinned_messages.clone())
+        };
+        let Some(pinned_messages) = pinned_messages else {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("No pinned message with id {id}."),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            };
+            sess.send_event_raw(event).await;
+            return;
+        };
+        send_pinned_messages_updated(sess, sub_id, pinned_messages).await;
+    }
+
+    async fn send_pinned_messages_updated(
+        sess: &Arc<Session>,
+        sub_id: String,
+        pinned_messages: Vec<PinnedMessage>,
+    ) {
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::PinnedMessagesUpdated(PinnedMessagesUpdatedEvent { pinned_messages }),
+        })
+        .await;
+    }
+
     pub async fn shutdown(sess: &Arc<Session>, sub_id: String) -> bool {
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
         sess.services
             .unified_exec_manager
             .terminate_all_processes()
             .await;
+        // Worktrees of sub-agents that were never merged or discarded.
+        for worktree in sess
+            .services
+            .agent_control
+            .worktrees()
+            .take_for_parent(sess.conversation_id)
+        {
+            remove_agent_worktree(worktree).await;
+        }
         info!("Shutting down Codex instance");
         let history = sess.clone_history().await;
         let turn_count = history
@@ -3307,7 +3641,8 @@ async fn spawn_review_thread(
         model_info: &review_model_info,
         features: &review_features,
         web_search_mode: Some(review_web_search_mode),
-    });
+    })
+    .with_lsp_tools(!config.lsp_servers.is_empty());
 
     let review_prompt = resolved.prompt.clone();
     let provider = parent_turn_context.client.get_provider();
@@ -3359,6 +3694,7 @@ async fn spawn_review_thread(
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         dynamic_tools: parent_turn_context.dynamic_tools.clone(),
         truncation_policy: model_info.truncation_policy.into(),
+        tokenizer: tokenizer_for_model(&model_info),
         turn_metadata_header: parent_turn_context.turn_metadata_header.clone(),
     };
 
@@ -3369,12 +3705,23 @@ async fn spawn_review_thread(
         text_elements: Vec::new(),
     }];
     let tc = Arc::new(review_turn_context);
-    sess.spawn_task(tc.clone(), input, ReviewTask::new()).await;
+    let only_changed_lines = resolved.changed_lines.is_some();
+    let (baseline_path, baseline) = resolved.baseline.unzip();
+    sess.spawn_task(
+        tc.clone(),
+        input,
+        ReviewTask::new()
+            .with_changed_lines(resolved.changed_lines)
+            .with_baseline(baseline),
+    )
+    .await;
 
     // Announce entering review mode so UIs can switch modes.
     let review_request = ReviewRequest {
         target: resolved.target,
         user_facing_hint: Some(resolved.user_facing_hint),
+        only_changed_lines,
+        baseline: baseline_path,
     };
     sess.send_event(&tc, EventMsg::EnteredReviewMode(review_request))
         .await;
@@ -3460,7 +3807,7 @@ pub(crate) async fn run_turn(
 
     let model_info = turn_context.client.get_model_info();
     let auto_compact_limit = model_info.auto_compact_token_limit().unwrap_or(i64::MAX);
-    let total_usage_tokens = sess.get_total_token_usage().await;
+    let total_usage_tokens = sess.get_total_token_usage(&turn_context).await;
 
     let event = EventMsg::TurnStarted(TurnStartedEvent {
         model_context_window: turn_context.client.get_model_context_window(),
@@ -3613,7 +3960,7 @@ pub(crate) async fn run_turn(
                     needs_follow_up,
                     last_agent_message: sampling_request_last_agent_message,
                 } = sampling_request_output;
-                let total_usage_tokens = sess.get_total_token_usage().await;
+                let total_usage_tokens = sess.get_total_token_usage(&turn_context).await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
                 let estimated_token_count =
@@ -4088,6 +4435,16 @@ fn agent_message_text(item: &codex_protocol::items::AgentMessageItem) -> String
         .collect()
 }
 
+/// Text of the user and agent message items that can be pinned.
+fn pinnable_text(item: &TurnItem) -> Option<String> {
+    let text = match item {
+        TurnItem::UserMessage(message) => message.message(),
+        TurnItem::AgentMessage(message) => agent_message_text(message),
+        _ => return None,
+    };
+    (!text.trim().is_empty()).then_some(text)
+}
+
 /// Split the stream into normal assistant text vs. proposed plan content.
 /// Normal text becomes AgentMessage deltas; plan content becomes PlanDelta +
 /// TurnItem::Plan.
@@ -4959,6 +5316,111 @@ mod tests {
         assert_eq!(actual, Some(info2));
     }
 
+    #[tokio::test]
+    async fn record_initial_history_estimates_token_info_without_recorded_usage() {
+        let (session, turn_context) = make_session_and_context().await;
+        let (rollout_items, _expected) = sample_rollout(&session, &turn_context).await;
+
+        session
+            .record_initial_history(InitialHistory::Resumed(ResumedHistory {
+                conversation_id: ThreadId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            }))
+            .await;
+
+        // The estimate uses the session's default turn, like the resume itself.
+        let default_turn = session.new_default_turn().await;
+        let expected_tokens = session
+            .clone_history()
+            .await
+            .estimate_token_count(&default_turn)
+            .expect("estimate");
+        let info = session
+            .state
+            .lock()
+            .await
+            .token_info()
+            .expect("estimated token info");
+        assert_eq!(info.last_token_usage.total_tokens, expected_tokens);
+        assert_eq!(
+            info.model_context_window,
+            turn_context.client.get_model_context_window()
+        );
+    }
+
+    #[tokio::test]
+    async fn record_initial_history_restores_collaboration_mode() {
+        let (session, turn_context) = make_session_and_context().await;
+        let (mut rollout_items, _expected) = sample_rollout(&session, &turn_context).await;
+        let current_model = turn_context.collaboration_mode.model().to_string();
+        let recorded = CollaborationMode {
+            mode: ModeKind::Plan,
+            settings: Settings {
+                model: "recorded-model".to_string(),
+                reasoning_effort: Some(codex_protocol::openai_models::ReasoningEffort::Low),
+                developer_instructions: Some("review only".to_string()),
+            },
+            custom_mode: Some("review-only".to_string()),
+        };
+        rollout_items.push(RolloutItem::EventMsg(EventMsg::CollaborationModeUpdated(
+            CollaborationModeUpdatedEvent {
+                collaboration_mode: recorded.clone(),
+            },
+        )));
+
+        session
+            .record_initial_history(InitialHistory::Resumed(ResumedHistory {
+                conversation_id: ThreadId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            }))
+            .await;
+
+        assert_eq!(
+            session.collaboration_mode().await,
+            recorded.with_updates(Some(current_model), None, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn custom_collaboration_mode_overrides_turn_policies_and_tools() {
+        let (session, _turn_context) = make_session_and_context().await;
+        {
+            let mut state = session.state.lock().await;
+            let mut config = (*state.session_configuration.original_config_do_not_use).clone();
+            config.collaboration_modes.insert(
+                "review-only".to_string(),
+                CollaborationModeToml {
+                    tools: Some(vec!["shell".to_string(), "read_file".to_string()]),
+                    sandbox_mode: Some(SandboxMode::ReadOnly),
+                    approval_policy: Some(AskForApproval::Never),
+                    ..Default::default()
+                },
+            );
+            state.session_configuration.original_config_do_not_use = Arc::new(config);
+            state.session_configuration.collaboration_mode.custom_mode =
+                Some("review-only".to_string());
+        }
+
+        let turn_context = session.new_default_turn().await;
+
+        assert_eq!(turn_context.approval_policy, AskForApproval::Never);
+        assert_eq!(
+            turn_context.sandbox_policy,
+            SandboxPolicy::new_read_only_policy()
+        );
+        let allowed_tools = turn_context
+            .client
+            .config()
+            .allowed_tools
+            .clone()
+            .expect("allowed tools");
+        assert!(allowed_tools.contains(&"exec_command".to_string()));
+        assert!(allowed_tools.contains(&"read_file".to_string()));
+        assert!(!allowed_tools.contains(&"apply_patch".to_string()));
+    }
+
     #[tokio::test]
     async fn record_initial_history_reconstructs_forked_transcript() {
         let (session, turn_context) = make_session_and_context().await;
@@ -4973,6 +5435,42 @@ mod tests {
         assert_eq!(expected, history.raw_items());
     }
 
+    #[tokio::test]
+    async fn pinned_messages_are_only_injected_into_compacted_history() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+
+        handlers::pin_message(&sess, "sub-1".to_string(), "unknown".to_string()).await;
+        let error = loop {
+            let event = tokio::time::timeout(StdDuration::from_secs(2), rx.recv())
+                .await
+                .expect("timeout waiting for event")
+                .expect("event");
+            if let EventMsg::Error(error) = event.msg {
+                break error;
+            }
+        };
+        assert_eq!(error.message, "No message with id unknown to pin.");
+
+        let pinned = PinnedMessage {
+            id: "msg-1".to_string(),
+            text: "the spec".to_string(),
+        };
+        sess.state.lock().await.pinned_messages = vec![pinned.clone()];
+
+        // Resuming re-seeds the initial context, so it must not carry pins.
+        let initial_context = sess.build_initial_context(tc.as_ref()).await;
+        assert!(
+            !initial_context
+                .iter()
+                .any(compact::is_pinned_context_message)
+        );
+        let compacted = compact::compacted_initial_context(&sess, tc.as_ref()).await;
+        assert_eq!(
+            compacted.last(),
+            Some(&compact::pinned_message_item(&pinned))
+        );
+    }
+
     #[tokio::test]
     async fn thread_rollback_drops_last_turn_from_history() {
         let (sess, tc, rx) = make_session_and_context_with_rx().await;
@@ -5123,6 +5621,7 @@ mod tests {
                 reasoning_effort,
                 developer_instructions: None,
             },
+            custom_mode: None,
         };
         let session_configuration = SessionConfiguration {
             provider: config.model_provider.clone(),
@@ -5206,6 +5705,7 @@ mod tests {
                 reasoning_effort,
                 developer_instructions: None,
             },
+            custom_mode: None,
         };
         let session_configuration = SessionConfiguration {
             provider: config.model_provider.clone(),
@@ -5313,7 +5813,11 @@ mod tests {
         };
         let (_, turn_context) = make_session_and_context().await;
 
-        let out = format_exec_output_str(&exec, turn_context.truncation_policy);
+        let out = format_exec_output_str(
+            &exec,
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
 
         assert_eq!(
             out,
@@ -5476,6 +5980,7 @@ mod tests {
                 reasoning_effort,
                 developer_instructions: None,
             },
+            custom_mode: None,
         };
         let session_configuration = SessionConfiguration {
             provider: config.model_provider.clone(),
@@ -5532,10 +6037,15 @@ mod tests {
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_rules: config.approval_rules.clone(),
+            approval_memory: ApprovalMemory::new(config.codex_home.clone(), &config.cwd),
             skills_manager,
             agent_control,
             state_db: None,
             transport_manager: TransportManager::new(),
+            shell_secrets: ShellSecrets::default(),
+            lsp_manager: LspManager::new(config.lsp_servers.clone()),
+            symbol_index: None,
         };
 
         let turn_context = Session::make_turn_context(
@@ -5596,6 +6106,7 @@ mod tests {
                 reasoning_effort,
                 developer_instructions: None,
             },
+            custom_mode: None,
         };
         let session_configuration = SessionConfiguration {
             provider: config.model_provider.clone(),
@@ -5652,10 +6163,15 @@ mod tests {
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_rules: config.approval_rules.clone(),
+            approval_memory: ApprovalMemory::new(config.codex_home.clone(), &config.cwd),
             skills_manager,
             agent_control,
             state_db: None,
             transport_manager: TransportManager::new(),
+            shell_secrets: ShellSecrets::default(),
+            lsp_manager: LspManager::new(config.lsp_servers.clone()),
+            symbol_index: None,
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -5994,7 +6510,11 @@ mod tests {
         for item in &initial_context {
             rollout_items.push(RolloutItem::ResponseItem(item.clone()));
         }
-        live_history.record_items(initial_context.iter(), turn_context.truncation_policy);
+        live_history.record_items(
+            initial_context.iter(),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
 
         let user1 = ResponseItem::Message {
             id: None,
@@ -6005,7 +6525,11 @@ mod tests {
             end_turn: None,
             phase: None,
         };
-        live_history.record_items(std::iter::once(&user1), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&user1),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(user1.clone()));
 
         let assistant1 = ResponseItem::Message {
@@ -6017,7 +6541,11 @@ mod tests {
             end_turn: None,
             phase: None,
         };
-        live_history.record_items(std::iter::once(&assistant1), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&assistant1),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(assistant1.clone()));
 
         let summary1 = "summary one";
@@ -6043,7 +6571,11 @@ mod tests {
             end_turn: None,
             phase: None,
         };
-        live_history.record_items(std::iter::once(&user2), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&user2),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(user2.clone()));
 
         let assistant2 = ResponseItem::Message {
@@ -6055,7 +6587,11 @@ mod tests {
             end_turn: None,
             phase: None,
         };
-        live_history.record_items(std::iter::once(&assistant2), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&assistant2),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(assistant2.clone()));
 
         let summary2 = "summary two";
@@ -6081,7 +6617,11 @@ mod tests {
             end_turn: None,
             phase: None,
         };
-        live_history.record_items(std::iter::once(&user3), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&user3),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(user3));
 
         let assistant3 = ResponseItem::Message {
@@ -6093,7 +6633,11 @@ mod tests {
             end_turn: None,
             phase: None,
         };
-        live_history.record_items(std::iter::once(&assistant3), turn_context.truncation_policy);
+        live_history.record_items(
+            std::iter::once(&assistant3),
+            turn_context.truncation_policy,
+            turn_context.tokenizer.as_ref(),
+        );
         rollout_items.push(RolloutItem::ResponseItem(assistant3));
 
         (rollout_items, live_history.for_prompt())
@@ -210,7 +210,10 @@ async fn forward_events(
                     } => {}
                     Event {
                         id: _,
-                        msg: EventMsg::ThreadNameUpdated(_),
+                        msg:
+                            EventMsg::ThreadNameUpdated(_)
+                            | EventMsg::PinnedMessagesUpdated(_)
+                            | EventMsg::CollaborationModeUpdated(_),
                     } => {}
                     Event {
                         id,
@@ -1,4 +1,4 @@
-use crate::bash::parse_shell_lc_plain_commands;
+use crate::bash::parse_shell_script_plain_commands;
 #[cfg(windows)]
 #[path = "windows_dangerous_commands.rs"]
 mod windows_dangerous_commands;
@@ -15,8 +15,8 @@ pub fn command_might_be_dangerous(command: &[String]) -> bool {
         return true;
     }
 
-    // Support `bash -lc "<script>"` where the any part of the script might contain a dangerous command.
-    if let Some(all_commands) = parse_shell_lc_plain_commands(command)
+    // Support `bash -lc "<script>"` (and `fish -c` / `nu -c`) where the any part of the script might contain a dangerous command.
+    if let Some(all_commands) = parse_shell_script_plain_commands(command)
         && all_commands
             .iter()
             .any(|cmd| is_dangerous_to_call_with_exec(cmd))
@@ -379,4 +379,23 @@ mod tests {
     fn rm_f_is_dangerous() {
         assert!(command_might_be_dangerous(&vec_str(&["rm", "-f", "/"])));
     }
+
+    #[test]
+    fn fish_and_nushell_scripts_are_checked_for_dangerous_commands() {
+        assert!(command_might_be_dangerous(&vec_str(&[
+            "fish",
+            "-c",
+            "ls; and git reset --hard"
+        ])));
+        assert!(command_might_be_dangerous(&vec_str(&[
+            "nu",
+            "-c",
+            "cd build; rm -rf target"
+        ])));
+        assert!(!command_might_be_dangerous(&vec_str(&[
+            "fish",
+            "-c",
+            "git status"
+        ])));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::bash::parse_shell_lc_plain_commands;
+use crate::bash::parse_shell_script_plain_commands;
 // Find the first matching git subcommand, skipping known global options that
 // may appear before it (e.g., `-C`, `-c`, `--git-dir`).
 // Implemented in `is_dangerous_command` and shared here.
@@ -25,13 +25,13 @@ pub fn is_known_safe_command(command: &[String]) -> bool {
         return true;
     }
 
-    // Support `bash -lc "..."` where the script consists solely of one or
+    // Support `bash -lc "..."` (and `fish -c` / `nu -c`) where the script consists solely of one or
     // more "plain" commands (only bare words / quoted strings) combined with
     // a conservative allow‑list of shell operators that themselves do not
     // introduce side effects ( "&&", "||", ";", and "|" ). If every
     // individual command in the script is itself a known‑safe command, then
     // the composite expression is considered safe.
-    if let Some(all_commands) = parse_shell_lc_plain_commands(&command)
+    if let Some(all_commands) = parse_shell_script_plain_commands(&command)
         && !all_commands.is_empty()
         && all_commands
             .iter()
@@ -589,4 +589,34 @@ mod tests {
             "> redirection should be rejected"
         );
     }
+
+    #[test]
+    fn fish_and_nushell_scripts_follow_the_same_rules() {
+        assert!(is_known_safe_command(&vec_str(&[
+            "fish",
+            "-c",
+            "ls; and pwd"
+        ])));
+        assert!(is_known_safe_command(&vec_str(&[
+            "/usr/bin/fish",
+            "-lc",
+            "grep -n foo README.md | wc -l"
+        ])));
+        assert!(is_known_safe_command(&vec_str(&["nu", "-c", "ls | wc -l"])));
+        assert!(!is_known_safe_command(&vec_str(&[
+            "fish",
+            "-c",
+            "ls; and rm -rf build"
+        ])));
+        assert!(!is_known_safe_command(&vec_str(&[
+            "fish",
+            "-c",
+            "set x (pwd)"
+        ])));
+        assert!(!is_known_safe_command(&vec_str(&[
+            "nu",
+            "-c",
+            "ls | save out.txt"
+        ])));
+    }
 }
@@ -14,9 +14,12 @@ use crate::protocol::EventMsg;
 use crate::protocol::TurnContextItem;
 use crate::protocol::TurnStartedEvent;
 use crate::protocol::WarningEvent;
+use crate::session_prefix::PINNED_CONTEXT_CLOSE_TAG;
+use crate::session_prefix::PINNED_CONTEXT_OPEN_TAG;
 use crate::session_prefix::TURN_ABORTED_OPEN_TAG;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
+use crate::truncate::token_count;
 use crate::truncate::truncate_text;
 use crate::util::backoff;
 use codex_protocol::items::ContextCompactionItem;
@@ -24,14 +27,18 @@ use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::PinnedMessage;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
+use codex_utils_tokenizer::Tokenizer;
 use futures::prelude::*;
+use std::collections::HashSet;
 use tracing::error;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../templates/compact/prompt.md");
 pub const SUMMARY_PREFIX: &str = include_str!("../templates/compact/summary_prefix.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
+const COMPACT_PRESERVED_TOOL_OUTPUT_MAX_TOKENS: usize = 20_000;
 
 pub(crate) fn should_use_remote_compact_task(
     session: &Session,
@@ -81,6 +88,7 @@ async fn run_compact_task_inner(
     history.record_items(
         &[initial_input_for_turn.into()],
         turn_context.truncation_policy,
+        turn_context.tokenizer.as_ref(),
     );
 
     let mut truncated_count = 0usize;
@@ -180,20 +188,34 @@ async fn run_compact_task_inner(
     let summary_text = format!("{SUMMARY_PREFIX}\n{summary_suffix}");
     let user_messages = collect_user_messages(history_items);
 
-    let initial_context = sess.build_initial_context(turn_context.as_ref()).await;
+    let initial_context = compacted_initial_context(&sess, turn_context.as_ref()).await;
     let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    let preserved_items = build_preserved_items(
+        history_items,
+        turn_context
+            .client
+            .config()
+            .compact_preserved_tool_outputs
+            .unwrap_or(0),
+        turn_context.tokenizer.as_ref(),
+    );
+    // Preserved items cannot be rebuilt from the summary alone, so persist the
+    // full replacement history whenever selective compaction kept anything.
+    let persist_replacement_history = !preserved_items.is_empty();
+    new_history.extend(preserved_items);
     let ghost_snapshots: Vec<ResponseItem> = history_items
         .iter()
         .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
         .cloned()
         .collect();
     new_history.extend(ghost_snapshots);
+    let replacement_history = persist_replacement_history.then(|| new_history.clone());
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
         message: summary_text.clone(),
-        replacement_history: None,
+        replacement_history,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
@@ -332,6 +354,91 @@ fn build_compacted_history_with_limit(
     history
 }
 
+/// Returns the tool calls that compaction carries over verbatim, in history
+/// order: the call/output pairs of the `max_tool_outputs` most recent tool
+/// calls, bounded by a token budget.
+pub(crate) fn build_preserved_items(
+    history: &[ResponseItem],
+    max_tool_outputs: usize,
+    tokenizer: Option<&Tokenizer>,
+) -> Vec<ResponseItem> {
+    let mut preserved_call_ids: HashSet<&str> = HashSet::new();
+    let mut remaining = COMPACT_PRESERVED_TOOL_OUTPUT_MAX_TOKENS;
+    for item in history.iter().rev() {
+        if preserved_call_ids.len() >= max_tool_outputs {
+            break;
+        }
+        let (ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. }) = item
+        else {
+            continue;
+        };
+        let serialized = serde_json::to_string(item).unwrap_or_default();
+        let tokens = token_count(&serialized, tokenizer);
+        if tokens > remaining {
+            break;
+        }
+        remaining -= tokens;
+        preserved_call_ids.insert(call_id.as_str());
+    }
+
+    history
+        .iter()
+        .filter(|item| {
+            tool_call_id(item).is_some_and(|call_id| preserved_call_ids.contains(call_id))
+        })
+        .cloned()
+        .collect()
+}
+
+fn tool_call_id(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCall { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => Some(call_id.as_str()),
+        ResponseItem::LocalShellCall { call_id, .. } => call_id.as_deref(),
+        _ => None,
+    }
+}
+
+/// Session prefix for a compacted history: the initial context followed by
+/// the pinned messages, which are only injected when history is rebuilt.
+pub(crate) async fn compacted_initial_context(
+    sess: &Session,
+    turn_context: &TurnContext,
+) -> Vec<ResponseItem> {
+    let mut items = sess.build_initial_context(turn_context).await;
+    items.extend(sess.pinned_messages().await.iter().map(pinned_message_item));
+    items
+}
+
+pub(crate) fn is_pinned_context_message(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "user" => {
+            content_items_to_text(content)
+                .is_some_and(|text| text.starts_with(PINNED_CONTEXT_OPEN_TAG))
+        }
+        _ => false,
+    }
+}
+
+/// Renders a pinned message as a `<pinned_context>` session prefix message.
+pub(crate) fn pinned_message_item(message: &PinnedMessage) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!(
+                "{PINNED_CONTEXT_OPEN_TAG}\n{}\n{PINNED_CONTEXT_CLOSE_TAG}",
+                message.text
+            ),
+        }],
+        end_turn: None,
+        phase: None,
+    }
+}
+
 async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
@@ -375,6 +482,7 @@ mod tests {
 
     use super::*;
     use crate::session_prefix::TURN_ABORTED_OPEN_TAG;
+    use codex_protocol::models::FunctionCallOutputPayload;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -578,4 +686,79 @@ mod tests {
             "expected compacted history to retain <turn_aborted> marker"
         );
     }
+
+    fn function_call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn function_call_output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                content_items: None,
+                success: Some(true),
+            },
+        }
+    }
+
+    #[test]
+    fn build_preserved_items_keeps_most_recent_tool_outputs_in_order() {
+        let history = vec![
+            function_call("call-1"),
+            function_call_output("call-1", "first"),
+            function_call("call-2"),
+            function_call_output("call-2", "second"),
+            function_call("call-3"),
+            function_call_output("call-3", "third"),
+        ];
+
+        let preserved = build_preserved_items(&history, 2, None);
+
+        assert_eq!(history[2..].to_vec(), preserved);
+    }
+
+    #[test]
+    fn build_preserved_items_respects_token_budget() {
+        let huge = "x".repeat(COMPACT_PRESERVED_TOOL_OUTPUT_MAX_TOKENS * 8);
+        let history = vec![
+            function_call("call-1"),
+            function_call_output("call-1", "small"),
+            function_call("call-2"),
+            function_call_output("call-2", &huge),
+        ];
+
+        let preserved = build_preserved_items(&history, 2, None);
+
+        assert_eq!(Vec::<ResponseItem>::new(), preserved);
+    }
+
+    #[test]
+    fn pinned_messages_render_as_session_prefix() {
+        let pinned = PinnedMessage {
+            id: "item-1".to_string(),
+            text: "always use tabs".to_string(),
+        };
+
+        let preserved = vec![pinned_message_item(&pinned)];
+
+        assert_eq!(
+            vec![ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "<pinned_context>\nalways use tabs\n</pinned_context>".to_string(),
+                }],
+                end_turn: None,
+                phase: None,
+            }],
+            preserved
+        );
+        assert_eq!(Vec::<String>::new(), collect_user_messages(&preserved));
+    }
 }
@@ -3,6 +3,9 @@ use std::sync::Arc;
 use crate::Prompt;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::compact::build_preserved_items;
+use crate::compact::is_pinned_context_message;
+use crate::compact::pinned_message_item;
 use crate::context_manager::ContextManager;
 use crate::context_manager::is_codex_generated_item;
 use crate::error::Result as CodexResult;
@@ -67,6 +70,16 @@ async fn run_remote_compact_task_inner_impl(
         .cloned()
         .collect();
 
+    let preserved_items = build_preserved_items(
+        history.raw_items(),
+        turn_context
+            .client
+            .config()
+            .compact_preserved_tool_outputs
+            .unwrap_or(0),
+        turn_context.tokenizer.as_ref(),
+    );
+
     let prompt = Prompt {
         input: history.for_prompt(),
         tools: vec![],
@@ -81,6 +94,11 @@ async fn run_remote_compact_task_inner_impl(
         .compact_conversation_history(&prompt)
         .await?;
 
+    // Pins are re-rendered from session state so edits since the last
+    // compaction are reflected and stale copies are not duplicated.
+    new_history.retain(|item| !is_pinned_context_message(item));
+    new_history.extend(preserved_items);
+    new_history.extend(sess.pinned_messages().await.iter().map(pinned_message_item));
     if !ghost_snapshots.is_empty() {
         new_history.extend(ghost_snapshots);
     }
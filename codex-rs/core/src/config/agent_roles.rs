@@ -0,0 +1,226 @@
+//! User-defined sub-agent roles.
+//!
+//! Roles come from two places, merged by name:
+//! - `agents/<name>.md` next to any active `config.toml` (for example
+//!   `$CODEX_HOME/agents/` or a repository's `.codex/agents/`). The YAML
+//!   frontmatter holds the settings and the body becomes the role's developer
+//!   instructions. Files from higher-precedence layers win.
+//! - `[agents.roles.<name>]` tables in `config.toml`, which override files with
+//!   the same name.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::openai_models::ReasoningEffort;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::ConfigLayerStackOrdering;
+
+const AGENTS_DIR_NAME: &str = "agents";
+
+/// A sub-agent role as written in `config.toml` or in a role file's
+/// frontmatter. Every field is optional; unset fields inherit from the parent
+/// agent.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+#[serde(deny_unknown_fields)]
+pub struct AgentRoleToml {
+    /// When to use this role. Shown to the parent model in the `spawn_agent`
+    /// tool description.
+    pub description: Option<String>,
+
+    /// Replaces the base instructions of agents spawned with this role.
+    pub base_instructions: Option<String>,
+
+    /// Appended to the developer instructions of agents spawned with this role.
+    pub developer_instructions: Option<String>,
+
+    /// Model used by agents spawned with this role.
+    pub model: Option<String>,
+
+    /// Reasoning effort used by agents spawned with this role.
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+
+    /// Sandbox for agents spawned with this role. A role can only tighten the
+    /// parent's sandbox, never loosen it.
+    pub sandbox_mode: Option<SandboxMode>,
+
+    /// Names of the tools exposed to agents spawned with this role. `shell`
+    /// covers every shell tool variant. When unset, the parent's tools are
+    /// used.
+    pub tools: Option<Vec<String>>,
+}
+
+/// Loads role files from every enabled config layer and overlays the roles
+/// declared in `config.toml`.
+pub(crate) fn load_agent_roles(
+    config_layer_stack: &ConfigLayerStack,
+    toml_roles: Option<&BTreeMap<String, AgentRoleToml>>,
+) -> std::io::Result<BTreeMap<String, AgentRoleToml>> {
+    let mut roles = BTreeMap::new();
+    for layer in
+        config_layer_stack.get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst, false)
+    {
+        let Some(config_folder) = layer.config_folder() else {
+            continue;
+        };
+        roles.extend(load_role_files(
+            &config_folder.as_path().join(AGENTS_DIR_NAME),
+        ));
+    }
+
+    for (name, role) in toml_roles.into_iter().flatten() {
+        validate_role_name(name).map_err(|reason| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid agent role name `{name}`: {reason}"),
+            )
+        })?;
+        roles.insert(name.clone(), role.clone());
+    }
+    Ok(roles)
+}
+
+fn load_role_files(dir: &Path) -> BTreeMap<String, AgentRoleToml> {
+    let mut roles = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return roles;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if let Err(reason) = validate_role_name(name) {
+            warn!("skipping agent role file {}: {reason}", path.display());
+            continue;
+        }
+        match fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| parse_role_file(&contents))
+        {
+            Ok(role) => {
+                roles.insert(name.to_string(), role);
+            }
+            Err(err) => warn!("skipping agent role file {}: {err}", path.display()),
+        }
+    }
+    roles
+}
+
+/// Parses a role file: optional YAML frontmatter between `---` lines, followed
+/// by the developer instructions.
+fn parse_role_file(contents: &str) -> Result<AgentRoleToml, String> {
+    let (mut role, body) = match contents.strip_prefix("---") {
+        Some(rest) => {
+            let rest = rest.trim_start_matches(['\r', '\n']);
+            let (frontmatter, body) = if let Some(body) = rest.strip_prefix("---") {
+                ("", body)
+            } else {
+                rest.split_once("\n---")
+                    .ok_or_else(|| "frontmatter is missing its closing `---`".to_string())?
+            };
+            let role: AgentRoleToml = if frontmatter.trim().is_empty() {
+                AgentRoleToml::default()
+            } else {
+                serde_yaml::from_str(frontmatter).map_err(|err| err.to_string())?
+            };
+            (role, body.trim_start_matches(['\r', '\n']))
+        }
+        None => (AgentRoleToml::default(), contents),
+    };
+
+    let body = body.trim();
+    if !body.is_empty() {
+        if role.developer_instructions.is_some() {
+            return Err(
+                "set developer instructions either in the frontmatter or in the body, not both"
+                    .to_string(),
+            );
+        }
+        role.developer_instructions = Some(body.to_string());
+    }
+    Ok(role)
+}
+
+fn validate_role_name(name: &str) -> Result<(), &'static str> {
+    if name.is_empty() {
+        return Err("role names must not be empty");
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_')
+    {
+        return Err("role names may only contain a-z, 0-9, `-` and `_`");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_frontmatter_and_body() {
+        let role = parse_role_file(
+            "---\ndescription: Writes tests\nmodel: gpt-5.2-codex\nsandbox_mode: workspace-write\ntools: [shell, apply_patch]\n---\n\nOnly touch files under tests/.\n",
+        )
+        .expect("role parses");
+        assert_eq!(
+            role,
+            AgentRoleToml {
+                description: Some("Writes tests".to_string()),
+                model: Some("gpt-5.2-codex".to_string()),
+                sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+                tools: Some(vec!["shell".to_string(), "apply_patch".to_string()]),
+                developer_instructions: Some("Only touch files under tests/.".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn file_without_frontmatter_is_all_instructions() {
+        let role = parse_role_file("Review for injection bugs.\n").expect("role parses");
+        assert_eq!(
+            role.developer_instructions.as_deref(),
+            Some("Review for injection bugs.")
+        );
+        assert_eq!(role.description, None);
+    }
+
+    #[test]
+    fn rejects_unknown_frontmatter_keys() {
+        let err = parse_role_file("---\nmodle: typo\n---\nbody\n").expect_err("unknown key");
+        assert!(err.contains("modle"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn loads_role_files_from_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::write(
+            dir.path().join("security-reviewer.md"),
+            "---\ndescription: Reviews for vulnerabilities\nsandbox_mode: read-only\n---\nFocus on auth.\n",
+        )
+        .expect("write role");
+        fs::write(dir.path().join("Bad Name.md"), "ignored").expect("write role");
+        fs::write(dir.path().join("notes.txt"), "ignored").expect("write file");
+
+        let roles = load_role_files(dir.path());
+        assert_eq!(roles.keys().collect::<Vec<_>>(), vec!["security-reviewer"]);
+        assert_eq!(
+            roles["security-reviewer"].sandbox_mode,
+            Some(SandboxMode::ReadOnly)
+        );
+    }
+}
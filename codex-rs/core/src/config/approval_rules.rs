@@ -0,0 +1,249 @@
+//! Declarative approval rules.
+//!
+//! `[[approval_rules]]` entries in `config.toml` refine the global approval
+//! policy for individual tools and paths, e.g. auto-approve `apply_patch`
+//! under `src/` but always ask before touching `Cargo.toml`. When several
+//! rules match a request the strictest decision wins (`deny` > `ask` >
+//! `allow`), so the order of rules in the file does not matter.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use wildmatch::WildMatch;
+
+/// Tool name `apply_patch` requests are matched against.
+pub const APPLY_PATCH_RULE_TOOL: &str = "apply_patch";
+/// Tool name shell commands (including unified exec sessions) are matched against.
+pub const SHELL_RULE_TOOL: &str = "shell";
+
+/// What to do with a tool call that matches a rule.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalRuleDecision {
+    /// Run without asking, still inside the sandbox.
+    Allow,
+    /// Always ask, even when the approval policy would not.
+    Ask,
+    /// Reject the call without asking.
+    Deny,
+}
+
+/// One `[[approval_rules]]` entry as written in `config.toml`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalRuleToml {
+    /// Tool the rule applies to: `apply_patch`, `shell`, or an MCP tool as
+    /// `mcp__<server>__<tool>`. `*` matches any run of characters.
+    pub tool: String,
+
+    /// Limit the rule to these paths (files for `apply_patch`, the working
+    /// directory for `shell`). Patterns are relative to the session's working
+    /// directory; a pattern without `/` matches the file name anywhere, and a
+    /// trailing `/` matches everything below a directory.
+    pub paths: Option<Vec<String>>,
+
+    /// `allow`, `ask` or `deny`.
+    pub decision: ApprovalRuleDecision,
+}
+
+/// The `[[approval_rules]]` entries after validation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApprovalRules {
+    rules: Vec<ApprovalRuleToml>,
+}
+
+impl ApprovalRules {
+    /// The strictest decision among the rules matching a call to `tool` that
+    /// touches `paths`, or `None` when no rule applies.
+    ///
+    /// A rule with `paths` never matches a call without paths. An `allow` rule
+    /// needs every path covered by its patterns, while `ask` and `deny` rules
+    /// match as soon as one path is covered.
+    pub fn decision_for(
+        &self,
+        tool: &str,
+        paths: &[PathBuf],
+        cwd: &Path,
+    ) -> Option<ApprovalRuleDecision> {
+        self.rules
+            .iter()
+            .filter(|rule| WildMatch::new(&rule.tool).matches(tool))
+            .filter(|rule| {
+                let Some(patterns) = &rule.paths else {
+                    return true;
+                };
+                let covered = |path: &PathBuf| {
+                    patterns
+                        .iter()
+                        .any(|pattern| path_matches(pattern, path, cwd))
+                };
+                match rule.decision {
+                    ApprovalRuleDecision::Allow => !paths.is_empty() && paths.iter().all(covered),
+                    ApprovalRuleDecision::Ask | ApprovalRuleDecision::Deny => {
+                        paths.iter().any(covered)
+                    }
+                }
+            })
+            .map(|rule| rule.decision)
+            .max()
+    }
+}
+
+fn path_matches(pattern: &str, path: &Path, cwd: &Path) -> bool {
+    let relative = path.strip_prefix(cwd).unwrap_or(path);
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    if let Some(dir) = pattern.strip_suffix('/') {
+        return relative == dir || relative.starts_with(&format!("{dir}/"));
+    }
+    if pattern.contains('/') {
+        return WildMatch::new(pattern).matches(&relative);
+    }
+    relative
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| WildMatch::new(pattern).matches(name))
+}
+
+/// Checks `[[approval_rules]]` for entries that could never match.
+pub(crate) fn resolve_approval_rules(
+    toml: Option<&[ApprovalRuleToml]>,
+) -> std::io::Result<ApprovalRules> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+    let rules = toml.unwrap_or_default().to_vec();
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.tool.trim().is_empty() {
+            return Err(invalid(format!(
+                "approval_rules[{index}].tool must not be empty"
+            )));
+        }
+        let Some(paths) = &rule.paths else {
+            continue;
+        };
+        if paths.is_empty() || paths.iter().any(|pattern| pattern.trim().is_empty()) {
+            return Err(invalid(format!(
+                "approval_rules[{index}].paths must list non-empty patterns"
+            )));
+        }
+        if rule.tool.starts_with("mcp__") {
+            return Err(invalid(format!(
+                "approval_rules[{index}].paths only applies to `{APPLY_PATCH_RULE_TOOL}` and `{SHELL_RULE_TOOL}` rules"
+            )));
+        }
+    }
+    Ok(ApprovalRules { rules })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rule(
+        tool: &str,
+        paths: Option<&[&str]>,
+        decision: ApprovalRuleDecision,
+    ) -> ApprovalRuleToml {
+        ApprovalRuleToml {
+            tool: tool.to_string(),
+            paths: paths.map(|paths| paths.iter().map(ToString::to_string).collect()),
+            decision,
+        }
+    }
+
+    fn rules() -> ApprovalRules {
+        resolve_approval_rules(Some(&[
+            rule(
+                APPLY_PATCH_RULE_TOOL,
+                Some(&["src/"]),
+                ApprovalRuleDecision::Allow,
+            ),
+            rule(
+                APPLY_PATCH_RULE_TOOL,
+                Some(&["migrations/", "Cargo.toml"]),
+                ApprovalRuleDecision::Ask,
+            ),
+            rule("mcp__github__get_*", None, ApprovalRuleDecision::Allow),
+            rule("mcp__github__delete_*", None, ApprovalRuleDecision::Deny),
+        ]))
+        .expect("valid rules")
+    }
+
+    #[test]
+    fn strictest_matching_rule_wins() {
+        let rules = rules();
+        let cwd = Path::new("/repo");
+        let decide = |paths: &[&str]| {
+            let paths: Vec<PathBuf> = paths.iter().map(|path| cwd.join(path)).collect();
+            rules.decision_for(APPLY_PATCH_RULE_TOOL, &paths, cwd)
+        };
+
+        assert_eq!(
+            decide(&["src/lib.rs", "src/a/b.rs"]),
+            Some(ApprovalRuleDecision::Allow)
+        );
+        assert_eq!(
+            decide(&["src/lib.rs", "Cargo.toml"]),
+            Some(ApprovalRuleDecision::Ask)
+        );
+        assert_eq!(
+            decide(&["crates/foo/Cargo.toml"]),
+            Some(ApprovalRuleDecision::Ask)
+        );
+        assert_eq!(
+            decide(&["src/migrations/001.sql"]),
+            Some(ApprovalRuleDecision::Allow)
+        );
+        assert_eq!(decide(&["src/lib.rs", "README.md"]), None);
+        assert_eq!(decide(&[]), None);
+        assert_eq!(
+            rules.decision_for(SHELL_RULE_TOOL, &[cwd.join("src")], cwd),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_mcp_tools_by_name() {
+        let rules = rules();
+        let cwd = Path::new("/repo");
+        assert_eq!(
+            rules.decision_for("mcp__github__get_issue", &[], cwd),
+            Some(ApprovalRuleDecision::Allow)
+        );
+        assert_eq!(
+            rules.decision_for("mcp__github__delete_repo", &[], cwd),
+            Some(ApprovalRuleDecision::Deny)
+        );
+        assert_eq!(rules.decision_for("mcp__other__get_issue", &[], cwd), None);
+    }
+
+    #[test]
+    fn rejects_rules_that_cannot_match() {
+        let err = resolve_approval_rules(Some(&[rule(
+            "mcp__github__*",
+            Some(&["src/"]),
+            ApprovalRuleDecision::Allow,
+        )]))
+        .expect_err("paths on an MCP rule");
+        assert_eq!(
+            err.to_string(),
+            "approval_rules[0].paths only applies to `apply_patch` and `shell` rules"
+        );
+        assert!(
+            resolve_approval_rules(Some(&[rule(
+                APPLY_PATCH_RULE_TOOL,
+                Some(&[]),
+                ApprovalRuleDecision::Ask
+            )]))
+            .is_err()
+        );
+        assert!(
+            resolve_approval_rules(Some(&[rule(" ", None, ApprovalRuleDecision::Ask)])).is_err()
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! User-defined collaboration modes.
+//!
+//! Besides the built-in Plan and Default modes, users can declare their own
+//! modes under `[collaboration_modes.<name>]` in `config.toml`. Each mode builds
+//! on one of the built-in modes and can add developer instructions, narrow the
+//! tool set and override the model, sandbox and approval policy while active.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use codex_protocol::config_types::ModeKind;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::protocol::AskForApproval;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A collaboration mode declared in `config.toml`. Every field is optional;
+/// unset fields keep the session's current settings.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+#[serde(deny_unknown_fields)]
+pub struct CollaborationModeToml {
+    /// Built-in mode this mode builds on (`default` or `plan`). Its
+    /// instructions are kept and this mode's instructions are appended.
+    /// Defaults to `default`.
+    pub base: Option<ModeKind>,
+
+    /// Developer instructions added while this mode is active.
+    pub developer_instructions: Option<String>,
+
+    /// Model used while this mode is active.
+    pub model: Option<String>,
+
+    /// Reasoning effort used while this mode is active.
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+
+    /// Names of the tools exposed to the model while this mode is active.
+    /// `shell` covers every shell tool variant. When unset, all tools are
+    /// available.
+    pub tools: Option<Vec<String>>,
+
+    /// Sandbox used while this mode is active.
+    pub sandbox_mode: Option<SandboxMode>,
+
+    /// Approval policy used while this mode is active.
+    pub approval_policy: Option<AskForApproval>,
+
+    /// Key that switches to this mode in the TUI when pressed with Alt.
+    pub shortcut: Option<char>,
+}
+
+/// Checks mode names and shortcuts, returning the modes unchanged.
+pub(crate) fn validate_collaboration_modes(
+    modes: Option<&BTreeMap<String, CollaborationModeToml>>,
+) -> std::io::Result<BTreeMap<String, CollaborationModeToml>> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+    let mut shortcuts: HashMap<char, &str> = HashMap::new();
+    for (name, mode) in modes.into_iter().flatten() {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_')
+        {
+            return Err(invalid(format!(
+                "invalid collaboration mode name `{name}`: names may only contain a-z, 0-9, `-` and `_`"
+            )));
+        }
+        if matches!(
+            mode.base,
+            Some(ModeKind::PairProgramming | ModeKind::Execute)
+        ) {
+            return Err(invalid(format!(
+                "collaboration mode `{name}` must build on `default` or `plan`"
+            )));
+        }
+        if let Some(shortcut) = mode.shortcut {
+            if !shortcut.is_ascii_alphanumeric() {
+                return Err(invalid(format!(
+                    "collaboration mode `{name}` has shortcut `{shortcut}`; shortcuts must be a letter or digit"
+                )));
+            }
+            let key = shortcut.to_ascii_lowercase();
+            if let Some(other) = shortcuts.insert(key, name) {
+                return Err(invalid(format!(
+                    "collaboration modes `{other}` and `{name}` share the shortcut `{key}`"
+                )));
+            }
+        }
+    }
+    Ok(modes.cloned().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode_with_shortcut(shortcut: char) -> CollaborationModeToml {
+        CollaborationModeToml {
+            shortcut: Some(shortcut),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rejects_duplicate_shortcuts() {
+        let modes = BTreeMap::from([
+            ("docs".to_string(), mode_with_shortcut('d')),
+            ("debug".to_string(), mode_with_shortcut('D')),
+        ]);
+        let err = validate_collaboration_modes(Some(&modes)).expect_err("duplicate shortcut");
+        assert!(
+            err.to_string().contains("share the shortcut `d`"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        let modes = BTreeMap::from([("Review Only".to_string(), Default::default())]);
+        assert!(validate_collaboration_modes(Some(&modes)).is_err());
+
+        let modes = BTreeMap::from([("review-only".to_string(), mode_with_shortcut('r'))]);
+        assert_eq!(
+            validate_collaboration_modes(Some(&modes)).expect("valid modes"),
+            modes
+        );
+    }
+}
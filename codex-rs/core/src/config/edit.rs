@@ -1,6 +1,7 @@
 use crate::config::CONFIG_TOML_FILE;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
+use crate::config::types::TuiTheme;
 use crate::path_utils::resolve_symlink_write_paths;
 use crate::path_utils::write_atomically;
 use anyhow::Context;
@@ -789,6 +790,15 @@ impl ConfigEditsBuilder {
         self
     }
 
+    /// Set the TUI color theme under the global `[tui]` table.
+    pub fn set_tui_theme(mut self, theme: TuiTheme) -> Self {
+        self.edits.push(ConfigEdit::SetPath {
+            segments: vec!["tui".to_string(), "theme".to_string()],
+            value: value(theme.to_string()),
+        });
+        self
+    }
+
     /// Enable or disable a feature flag by key under the `[features]` table.
     pub fn set_feature_enabled(mut self, key: &str, enabled: bool) -> Self {
         self.edits.push(ConfigEdit::SetPath {
@@ -1682,6 +1692,31 @@ model_reasoning_effort = "high"
         assert_eq!(contents, expected);
     }
 
+    #[test]
+    fn blocking_builder_set_tui_theme_keeps_existing_tui_settings() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            "[tui]\nvim_mode = true\n",
+        )
+        .expect("seed config");
+
+        ConfigEditsBuilder::new(codex_home)
+            .with_profile(Some("work"))
+            .set_tui_theme(TuiTheme::HighContrast)
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let expected = r#"[tui]
+vim_mode = true
+theme = "high-contrast"
+"#;
+        assert_eq!(contents, expected);
+    }
+
     #[test]
     fn blocking_builder_set_model_round_trips_back_and_forth() {
         let tmp = tempdir().expect("tmpdir");
@@ -0,0 +1,437 @@
+//! Rebindable TUI shortcuts.
+//!
+//! `[tui.keymap]` in `config.toml` maps actions to key chords such as
+//! `"ctrl+j"` or `"alt+enter"`. Chords are parsed and checked for conflicts
+//! when the config is loaded so that a typo or a clash fails fast instead of
+//! silently shadowing another shortcut.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Key chords for the rebindable TUI actions, as written in `config.toml`.
+/// Unset actions keep their default bindings.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+#[serde(deny_unknown_fields)]
+pub struct TuiKeymapToml {
+    /// Submit the composer. Defaults to `enter`.
+    pub submit: Option<String>,
+
+    /// Insert a newline in the composer. Defaults to `shift+enter` (`ctrl+j`
+    /// also works).
+    pub newline: Option<String>,
+
+    /// Interrupt the running turn. Defaults to `esc`.
+    pub interrupt: Option<String>,
+
+    /// Open the transcript overlay. Defaults to `ctrl+t`.
+    pub open_transcript: Option<String>,
+
+    /// Step back to edit a previous message. Defaults to `esc`.
+    pub backtrack: Option<String>,
+
+    /// Paste an image from the clipboard. Defaults to `ctrl+v`.
+    pub paste_image: Option<String>,
+
+    /// Edit the draft in `$VISUAL`/`$EDITOR`. Defaults to `ctrl+g`.
+    pub external_editor: Option<String>,
+
+    /// Approve a pending request in the approval prompt. Defaults to `y`.
+    pub approve: Option<String>,
+
+    /// Deny a pending request in the approval prompt. Defaults to `n`.
+    pub deny: Option<String>,
+}
+
+/// Actions that can be rebound under `[tui.keymap]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeymapAction {
+    Submit,
+    Newline,
+    Interrupt,
+    OpenTranscript,
+    Backtrack,
+    PasteImage,
+    ExternalEditor,
+    Approve,
+    Deny,
+}
+
+impl KeymapAction {
+    pub const ALL: [KeymapAction; 9] = [
+        KeymapAction::Submit,
+        KeymapAction::Newline,
+        KeymapAction::Interrupt,
+        KeymapAction::OpenTranscript,
+        KeymapAction::Backtrack,
+        KeymapAction::PasteImage,
+        KeymapAction::ExternalEditor,
+        KeymapAction::Approve,
+        KeymapAction::Deny,
+    ];
+
+    /// Key of the action in `[tui.keymap]`.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            KeymapAction::Submit => "submit",
+            KeymapAction::Newline => "newline",
+            KeymapAction::Interrupt => "interrupt",
+            KeymapAction::OpenTranscript => "open_transcript",
+            KeymapAction::Backtrack => "backtrack",
+            KeymapAction::PasteImage => "paste_image",
+            KeymapAction::ExternalEditor => "external_editor",
+            KeymapAction::Approve => "approve",
+            KeymapAction::Deny => "deny",
+        }
+    }
+
+    pub fn default_chord(self) -> KeyChord {
+        let (modifiers, key) = match self {
+            KeymapAction::Submit => (ChordModifiers::NONE, ChordKey::Enter),
+            KeymapAction::Newline => (ChordModifiers::SHIFT, ChordKey::Enter),
+            KeymapAction::Interrupt | KeymapAction::Backtrack => {
+                (ChordModifiers::NONE, ChordKey::Esc)
+            }
+            KeymapAction::OpenTranscript => (ChordModifiers::CTRL, ChordKey::Char('t')),
+            KeymapAction::PasteImage => (ChordModifiers::CTRL, ChordKey::Char('v')),
+            KeymapAction::ExternalEditor => (ChordModifiers::CTRL, ChordKey::Char('g')),
+            KeymapAction::Approve => (ChordModifiers::NONE, ChordKey::Char('y')),
+            KeymapAction::Deny => (ChordModifiers::NONE, ChordKey::Char('n')),
+        };
+        KeyChord { modifiers, key }
+    }
+
+    /// Approve and deny only apply while the approval prompt is open; every
+    /// other action is live in the main view.
+    fn in_approval_prompt(self) -> bool {
+        matches!(self, KeymapAction::Approve | KeymapAction::Deny)
+    }
+
+    fn chord_in(self, toml: &TuiKeymapToml) -> Option<&str> {
+        match self {
+            KeymapAction::Submit => toml.submit.as_deref(),
+            KeymapAction::Newline => toml.newline.as_deref(),
+            KeymapAction::Interrupt => toml.interrupt.as_deref(),
+            KeymapAction::OpenTranscript => toml.open_transcript.as_deref(),
+            KeymapAction::Backtrack => toml.backtrack.as_deref(),
+            KeymapAction::PasteImage => toml.paste_image.as_deref(),
+            KeymapAction::ExternalEditor => toml.external_editor.as_deref(),
+            KeymapAction::Approve => toml.approve.as_deref(),
+            KeymapAction::Deny => toml.deny.as_deref(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChordModifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl ChordModifiers {
+    pub const NONE: ChordModifiers = ChordModifiers {
+        ctrl: false,
+        alt: false,
+        shift: false,
+    };
+    pub const CTRL: ChordModifiers = ChordModifiers {
+        ctrl: true,
+        alt: false,
+        shift: false,
+    };
+    pub const SHIFT: ChordModifiers = ChordModifiers {
+        ctrl: false,
+        alt: false,
+        shift: true,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChordKey {
+    /// A printable character, stored lowercase for letters.
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+/// A key plus modifiers, e.g. `ctrl+shift+t`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub modifiers: ChordModifiers,
+    pub key: ChordKey,
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        // Allow `+` itself as the key, e.g. `ctrl++`.
+        let (prefix, key) = match trimmed.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None => match trimmed.rsplit_once('+') {
+                Some((prefix, key)) => (prefix, key),
+                None => ("", trimmed),
+            },
+        };
+
+        let mut modifiers = ChordModifiers::NONE;
+        for part in prefix.split('+').filter(|part| !part.is_empty()) {
+            let flag = match part.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut modifiers.ctrl,
+                "alt" | "option" | "meta" => &mut modifiers.alt,
+                "shift" => &mut modifiers.shift,
+                other => return Err(format!("unknown modifier `{other}` in `{s}`")),
+            };
+            if *flag {
+                return Err(format!("modifier `{part}` repeated in `{s}`"));
+            }
+            *flag = true;
+        }
+
+        let key = key.trim();
+        let mut chars = key.chars();
+        let key = match (chars.next(), chars.next()) {
+            (None, _) => return Err(format!("missing key in `{s}`")),
+            (Some(c), None) => {
+                if c.is_ascii_uppercase() {
+                    modifiers.shift = true;
+                }
+                ChordKey::Char(c.to_ascii_lowercase())
+            }
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => ChordKey::Enter,
+                "esc" | "escape" => ChordKey::Esc,
+                "tab" => ChordKey::Tab,
+                "backspace" => ChordKey::Backspace,
+                "delete" | "del" => ChordKey::Delete,
+                "insert" | "ins" => ChordKey::Insert,
+                "space" => ChordKey::Char(' '),
+                "up" => ChordKey::Up,
+                "down" => ChordKey::Down,
+                "left" => ChordKey::Left,
+                "right" => ChordKey::Right,
+                "home" => ChordKey::Home,
+                "end" => ChordKey::End,
+                "pageup" | "pgup" => ChordKey::PageUp,
+                "pagedown" | "pgdn" => ChordKey::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => ChordKey::F(n),
+                    _ => return Err(format!("unknown key `{key}` in `{s}`")),
+                },
+            },
+        };
+        Ok(KeyChord { modifiers, key })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.ctrl {
+            f.write_str("ctrl+")?;
+        }
+        if self.modifiers.alt {
+            f.write_str("alt+")?;
+        }
+        if self.modifiers.shift {
+            f.write_str("shift+")?;
+        }
+        match self.key {
+            ChordKey::Char(' ') => f.write_str("space"),
+            ChordKey::Char(c) => write!(f, "{c}"),
+            ChordKey::Enter => f.write_str("enter"),
+            ChordKey::Esc => f.write_str("esc"),
+            ChordKey::Tab => f.write_str("tab"),
+            ChordKey::Backspace => f.write_str("backspace"),
+            ChordKey::Delete => f.write_str("delete"),
+            ChordKey::Insert => f.write_str("insert"),
+            ChordKey::Up => f.write_str("up"),
+            ChordKey::Down => f.write_str("down"),
+            ChordKey::Left => f.write_str("left"),
+            ChordKey::Right => f.write_str("right"),
+            ChordKey::Home => f.write_str("home"),
+            ChordKey::End => f.write_str("end"),
+            ChordKey::PageUp => f.write_str("pageup"),
+            ChordKey::PageDown => f.write_str("pagedown"),
+            ChordKey::F(n) => write!(f, "f{n}"),
+        }
+    }
+}
+
+/// Chords the main view keeps for itself (quit).
+const RESERVED_MAIN: [&str; 2] = ["ctrl+c", "ctrl+d"];
+/// Chords the approval prompt uses to move through and pick its options,
+/// including the shortcuts of options that cannot be rebound.
+const RESERVED_APPROVAL: [&str; 8] = ["enter", "esc", "up", "down", "a", "c", "p", "r"];
+
+/// The `[tui.keymap]` overrides after parsing and conflict checks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TuiKeymap {
+    overrides: BTreeMap<KeymapAction, KeyChord>,
+}
+
+impl TuiKeymap {
+    /// The configured chord for `action`, if the user rebound it.
+    pub fn get(&self, action: KeymapAction) -> Option<KeyChord> {
+        self.overrides.get(&action).copied()
+    }
+
+    /// The chord that triggers `action`, falling back to its default.
+    pub fn effective(&self, action: KeymapAction) -> KeyChord {
+        self.get(action).unwrap_or_else(|| action.default_chord())
+    }
+}
+
+/// Parses `[tui.keymap]` and rejects unknown chords and bindings that would
+/// shadow one another.
+pub fn resolve_tui_keymap(toml: Option<&TuiKeymapToml>) -> std::io::Result<TuiKeymap> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+    let mut overrides = BTreeMap::new();
+    for action in KeymapAction::ALL {
+        let Some(chord) = toml.and_then(|toml| action.chord_in(toml)) else {
+            continue;
+        };
+        let chord = chord.parse::<KeyChord>().map_err(|err| {
+            invalid(format!(
+                "invalid key chord for tui.keymap.{}: {err}",
+                action.config_key()
+            ))
+        })?;
+        overrides.insert(action, chord);
+    }
+    let keymap = TuiKeymap { overrides };
+
+    for (index, action) in KeymapAction::ALL.iter().enumerate() {
+        let chord = keymap.effective(*action);
+        let reserved: &[&str] = if action.in_approval_prompt() {
+            &RESERVED_APPROVAL
+        } else {
+            &RESERVED_MAIN
+        };
+        if keymap.get(*action).is_some() && reserved.contains(&chord.to_string().as_str()) {
+            return Err(invalid(format!(
+                "tui.keymap.{} cannot use `{chord}`, which is reserved",
+                action.config_key()
+            )));
+        }
+        for other in &KeymapAction::ALL[index + 1..] {
+            if action.in_approval_prompt() != other.in_approval_prompt()
+                || keymap.effective(*other) != chord
+            {
+                continue;
+            }
+            // Interrupt only applies while a turn runs and backtrack only
+            // while idle, so they may share a chord (both default to Esc).
+            if matches!(
+                (action, other),
+                (KeymapAction::Interrupt, KeymapAction::Backtrack)
+            ) {
+                continue;
+            }
+            return Err(invalid(format!(
+                "tui.keymap.{} and tui.keymap.{} are both bound to `{chord}`",
+                action.config_key(),
+                other.config_key()
+            )));
+        }
+    }
+    Ok(keymap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().expect("valid chord")
+    }
+
+    #[test]
+    fn parses_and_normalizes_chords() {
+        assert_eq!(chord("Ctrl+Shift+T").to_string(), "ctrl+shift+t");
+        assert_eq!(chord("alt+Enter").to_string(), "alt+enter");
+        assert_eq!(chord("ctrl+J"), chord("ctrl+shift+j"));
+        assert_eq!(chord("ctrl++").key, ChordKey::Char('+'));
+        assert_eq!(chord("f5").key, ChordKey::F(5));
+        assert_eq!(chord(" space ").key, ChordKey::Char(' '));
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("ctrl+".parse::<KeyChord>().is_err());
+        assert!("ctrl+ctrl+x".parse::<KeyChord>().is_err());
+        assert!("f42".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn resolves_overrides_and_keeps_defaults() {
+        let toml = TuiKeymapToml {
+            submit: Some("ctrl+enter".to_string()),
+            newline: Some("enter".to_string()),
+            ..Default::default()
+        };
+        let keymap = resolve_tui_keymap(Some(&toml)).expect("valid keymap");
+        assert_eq!(keymap.get(KeymapAction::Submit), Some(chord("ctrl+enter")));
+        assert_eq!(keymap.effective(KeymapAction::Newline), chord("enter"));
+        assert_eq!(keymap.get(KeymapAction::OpenTranscript), None);
+        assert_eq!(
+            keymap.effective(KeymapAction::OpenTranscript),
+            chord("ctrl+t")
+        );
+        assert_eq!(
+            resolve_tui_keymap(None).expect("defaults"),
+            TuiKeymap::default()
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_bindings() {
+        let toml = TuiKeymapToml {
+            open_transcript: Some("ctrl+g".to_string()),
+            ..Default::default()
+        };
+        let err = resolve_tui_keymap(Some(&toml)).expect_err("conflict with external editor");
+        assert_eq!(
+            err.to_string(),
+            "tui.keymap.open_transcript and tui.keymap.external_editor are both bound to `ctrl+g`"
+        );
+
+        let toml = TuiKeymapToml {
+            approve: Some("enter".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve_tui_keymap(Some(&toml)).is_err());
+
+        let toml = TuiKeymapToml {
+            interrupt: Some("ctrl+c".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve_tui_keymap(Some(&toml)).is_err());
+
+        // Approval keys only apply inside the prompt, so they may reuse main-view keys.
+        let toml = TuiKeymapToml {
+            approve: Some("ctrl+t".to_string()),
+            interrupt: Some("ctrl+x".to_string()),
+            backtrack: Some("ctrl+x".to_string()),
+            ..Default::default()
+        };
+        assert!(resolve_tui_keymap(Some(&toml)).is_ok());
+    }
+}
@@ -1,8 +1,14 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::agent_roles::AgentRoleToml;
+use crate::config::approval_rules::ApprovalRuleToml;
+use crate::config::approval_rules::ApprovalRules;
+use crate::config::collaboration_modes::CollaborationModeToml;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
+use crate::config::keymap::TuiKeymap;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
+use crate::config::types::LspServerConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
 use crate::config::types::McpServerTransportConfig;
@@ -12,11 +18,13 @@ use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::PlanArtifacts;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::SkillsConfig;
 use crate::config::types::Tui;
+use crate::config::types::TuiTheme;
 use crate::config::types::UriBasedFileOpener;
 use crate::config_loader::CloudRequirementsLoader;
 use crate::config_loader::ConfigLayerStack;
@@ -57,6 +65,8 @@ use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::config_types::WindowsSandboxLevel;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_secrets::SecretsBackendKind;
+use codex_secrets::SecretsKeyStore;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use codex_utils_absolute_path::AbsolutePathBufGuard;
 use schemars::JsonSchema;
@@ -75,8 +85,12 @@ use crate::config::profile::ConfigProfile;
 use toml::Value as TomlValue;
 use toml_edit::DocumentMut;
 
+pub mod agent_roles;
+pub mod approval_rules;
+pub mod collaboration_modes;
 mod constraint;
 pub mod edit;
+pub mod keymap;
 pub mod profile;
 pub mod schema;
 pub mod service;
@@ -140,6 +154,10 @@ pub struct Config {
     /// Approval policy for executing commands.
     pub approval_policy: Constrained<AskForApproval>,
 
+    /// Per-tool and per-path refinements of `approval_policy` from
+    /// `[[approval_rules]]`.
+    pub approval_rules: ApprovalRules,
+
     pub sandbox_policy: Constrained<SandboxPolicy>,
 
     /// enforce_residency means web traffic cannot be routed outside of a
@@ -178,6 +196,11 @@ pub struct Config {
     /// Compact prompt override.
     pub compact_prompt: Option<String>,
 
+    /// Number of most recent tool call outputs (including file reads) that
+    /// compaction keeps verbatim instead of summarizing. `None` summarizes
+    /// everything.
+    pub compact_preserved_tool_outputs: Option<usize>,
+
     /// Optional external notifier command. When set, Codex will spawn this
     /// program after each completed *turn* (i.e. when the agent finishes
     /// processing a user submission). The value must be the full command
@@ -213,6 +236,15 @@ pub struct Config {
     /// Show startup tooltips in the TUI welcome screen.
     pub show_tooltips: bool,
 
+    /// Enable vi-style modal editing in the TUI composer.
+    pub tui_vim_mode: bool,
+
+    /// Shortcut overrides from `[tui.keymap]`, already checked for conflicts.
+    pub tui_keymap: TuiKeymap,
+
+    /// Color theme from `tui.theme`, if set.
+    pub tui_theme: Option<TuiTheme>,
+
     /// Start the TUI in the specified collaboration mode (plan/default).
     pub experimental_mode: Option<ModeKind>,
 
@@ -233,6 +265,7 @@ pub struct Config {
     /// file (default): Use a file in the Codex home directory.
     /// keyring: Use an OS-specific keyring service.
     /// auto: Use the OS-specific keyring service if available, otherwise use a file.
+    /// encrypted: Use an encrypted file in the Codex home directory.
     pub cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
 
     /// Definition for MCP servers that Codex can reach out to for tool calls.
@@ -245,8 +278,18 @@ pub struct Config {
     /// file: CODEX_HOME/.credentials.json
     ///       This file will be readable to Codex and other applications running as the same user.
     /// auto (default): keyring if available, otherwise file.
+    /// encrypted: CODEX_HOME/credentials.age, for machines without a keyring.
     pub mcp_oauth_credentials_store_mode: OAuthCredentialsStoreMode,
 
+    /// Backend of the `codex secrets` store.
+    pub secrets_backend: SecretsBackendKind,
+
+    /// Where the key for the local `codex secrets` store is kept.
+    pub secrets_key_store: SecretsKeyStore,
+
+    /// Language servers exposed to the model through the `lsp_*` tools.
+    pub lsp_servers: BTreeMap<String, LspServerConfig>,
+
     /// Optional fixed port to use for the local HTTP callback server used during MCP OAuth login.
     ///
     /// When unset, Codex will bind to an ephemeral port chosen by the OS.
@@ -267,6 +310,18 @@ pub struct Config {
     /// Maximum number of agent threads that can be open concurrently.
     pub agent_max_threads: Option<usize>,
 
+    /// Sub-agent roles defined in `config.toml` or in `agents/*.md` role files,
+    /// keyed by role name.
+    pub agent_roles: BTreeMap<String, AgentRoleToml>,
+
+    /// User-defined collaboration modes from `[collaboration_modes]`, keyed by
+    /// mode name.
+    pub collaboration_modes: BTreeMap<String, CollaborationModeToml>,
+
+    /// When set, only these tools are exposed to the model. Not read from
+    /// `config.toml`; agent roles use it to restrict spawned agents.
+    pub allowed_tools: Option<Vec<String>>,
+
     /// Directory containing all Codex state (defaults to `~/.codex` but can be
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
@@ -281,6 +336,9 @@ pub struct Config {
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
 
+    /// Where Plan mode saves proposed plans.
+    pub plan_artifacts: PlanArtifacts,
+
     /// Path to the `codex-linux-sandbox` executable. This must be set if
     /// [`crate::exec::SandboxType::LinuxSeccomp`] is used. Note that this
     /// cannot be set in the config file: it must be set in code via
@@ -810,6 +868,10 @@ pub struct ConfigToml {
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
+    /// Rules that allow, always ask for, or deny specific tools and paths
+    /// regardless of `approval_policy`.
+    pub approval_rules: Option<Vec<ApprovalRuleToml>>,
+
     #[serde(default)]
     pub shell_environment_policy: ShellEnvironmentPolicyToml,
 
@@ -839,6 +901,10 @@ pub struct ConfigToml {
     /// Compact prompt used for history compaction.
     pub compact_prompt: Option<String>,
 
+    /// Number of most recent tool call outputs (including file reads) that
+    /// compaction keeps verbatim instead of summarizing.
+    pub compact_preserved_tool_outputs: Option<usize>,
+
     /// When set, restricts ChatGPT login to a specific workspace identifier.
     #[serde(default)]
     pub forced_chatgpt_workspace_id: Option<String>,
@@ -851,6 +917,7 @@ pub struct ConfigToml {
     /// file (default): Use a file in the Codex home directory.
     /// keyring: Use an OS-specific keyring service.
     /// auto: Use the keyring if available, otherwise use a file.
+    /// encrypted: Use an encrypted file in the Codex home directory.
     #[serde(default)]
     pub cli_auth_credentials_store: Option<AuthCredentialsStoreMode>,
 
@@ -865,9 +932,25 @@ pub struct ConfigToml {
     ///          https://github.com/openai/codex/blob/main/codex-rs/rmcp-client/src/oauth.rs#L2
     /// file: Use a file in the Codex home directory.
     /// auto (default): Use the OS-specific keyring service if available, otherwise use a file.
+    /// encrypted: Use an encrypted file in the Codex home directory.
     #[serde(default)]
     pub mcp_oauth_credentials_store: Option<OAuthCredentialsStoreMode>,
 
+    /// Backend of the `codex secrets` store.
+    /// local (default): An encrypted file in the Codex home directory.
+    #[serde(default)]
+    pub secrets_backend: Option<SecretsBackendKind>,
+
+    /// Where to keep the key for the local `codex secrets` store.
+    /// keyring (default): Use an OS-specific keyring service.
+    /// encrypted: Use an encrypted file in the Codex home directory.
+    #[serde(default)]
+    pub secrets_key_store: Option<SecretsKeyStore>,
+
+    /// Language servers Codex starts for code navigation tools, keyed by name.
+    #[serde(default)]
+    pub lsp_servers: BTreeMap<String, LspServerConfig>,
+
     /// Optional fixed port for the local HTTP callback server used during MCP OAuth login.
     /// When unset, Codex will bind to an ephemeral port chosen by the OS.
     pub mcp_oauth_callback_port: Option<u16>,
@@ -900,6 +983,10 @@ pub struct ConfigToml {
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
 
+    /// Where Plan mode saves proposed plans so they can be edited and executed
+    /// step by step. Defaults to `repo`.
+    pub plan_artifacts: Option<PlanArtifacts>,
+
     /// Collection of settings that are specific to the TUI.
     pub tui: Option<Tui>,
 
@@ -936,6 +1023,9 @@ pub struct ConfigToml {
     /// Agent-related settings (thread limits, etc.).
     pub agents: Option<AgentsToml>,
 
+    /// User-defined collaboration modes, keyed by mode name.
+    pub collaboration_modes: Option<BTreeMap<String, CollaborationModeToml>>,
+
     /// User-level skill config entries keyed by SKILL.md path.
     pub skills: Option<SkillsConfig>,
 
@@ -1048,13 +1138,16 @@ pub struct ToolsToml {
     pub view_image: Option<bool>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct AgentsToml {
     /// Maximum number of agent threads that can be open concurrently.
     /// When unset, no limit is enforced.
     #[schemars(range(min = 1))]
     pub max_threads: Option<usize>,
+
+    /// Sub-agent roles that `spawn_agent` can use, keyed by role name.
+    pub roles: Option<BTreeMap<String, AgentRoleToml>>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -1474,6 +1567,15 @@ impl Config {
                 "agents.max_threads must be at least 1",
             ));
         }
+        let agent_roles = agent_roles::load_agent_roles(
+            &config_layer_stack,
+            cfg.agents.as_ref().and_then(|agents| agents.roles.as_ref()),
+        )?;
+        let collaboration_modes =
+            collaboration_modes::validate_collaboration_modes(cfg.collaboration_modes.as_ref())?;
+        let tui_keymap =
+            keymap::resolve_tui_keymap(cfg.tui.as_ref().and_then(|t| t.keymap.as_ref()))?;
+        let approval_rules = approval_rules::resolve_approval_rules(cfg.approval_rules.as_deref())?;
 
         let ghost_snapshot = {
             let mut config = GhostSnapshotConfig::default();
@@ -1589,6 +1691,7 @@ impl Config {
             model_provider,
             cwd: resolved_cwd,
             approval_policy: constrained_approval_policy.value,
+            approval_rules,
             sandbox_policy: constrained_sandbox_policy.value,
             enforce_residency: enforce_residency.value,
             did_user_set_custom_approval_policy_or_sandbox_mode,
@@ -1600,6 +1703,7 @@ impl Config {
             personality,
             developer_instructions,
             compact_prompt,
+            compact_preserved_tool_outputs: cfg.compact_preserved_tool_outputs,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
@@ -1607,6 +1711,9 @@ impl Config {
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
+            secrets_backend: cfg.secrets_backend.unwrap_or_default(),
+            secrets_key_store: cfg.secrets_key_store.unwrap_or_default(),
+            lsp_servers: cfg.lsp_servers,
             mcp_oauth_callback_port: cfg.mcp_oauth_callback_port,
             model_providers,
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
@@ -1625,11 +1732,15 @@ impl Config {
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
             agent_max_threads,
+            agent_roles,
+            collaboration_modes,
+            allowed_tools: None,
             codex_home,
             config_layer_stack,
             history,
             ephemeral: ephemeral.unwrap_or_default(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
+            plan_artifacts: cfg.plan_artifacts.unwrap_or_default(),
             codex_linux_sandbox_exe,
 
             hide_agent_reasoning: cfg.hide_agent_reasoning.unwrap_or(false),
@@ -1688,6 +1799,9 @@ impl Config {
                 .unwrap_or_default(),
             animations: cfg.tui.as_ref().map(|t| t.animations).unwrap_or(true),
             show_tooltips: cfg.tui.as_ref().map(|t| t.show_tooltips).unwrap_or(true),
+            tui_vim_mode: cfg.tui.as_ref().is_some_and(|t| t.vim_mode),
+            tui_keymap,
+            tui_theme: cfg.tui.as_ref().and_then(|t| t.theme),
             experimental_mode: cfg.tui.as_ref().and_then(|t| t.experimental_mode),
             tui_alternate_screen: cfg
                 .tui
@@ -1775,8 +1889,36 @@ impl Config {
             self.features.disable(Feature::WindowsSandboxElevated);
         }
     }
+
+    /// Narrows `allowed_tools` to `tools`. `shell` covers every shell tool
+    /// variant, and tools that are already disallowed stay disallowed.
+    pub(crate) fn restrict_allowed_tools(&mut self, tools: &[String]) {
+        let mut allowed: Vec<String> = tools
+            .iter()
+            .flat_map(|tool| {
+                if tool == "shell" {
+                    SHELL_TOOL_NAMES.iter().map(ToString::to_string).collect()
+                } else {
+                    vec![tool.clone()]
+                }
+            })
+            .collect();
+        if let Some(current) = &self.allowed_tools {
+            allowed.retain(|tool| current.contains(tool));
+        }
+        self.allowed_tools = Some(allowed);
+    }
 }
 
+/// Tool names covered by the `shell` entry of a tool allow-list.
+const SHELL_TOOL_NAMES: [&str; 5] = [
+    "shell",
+    "shell_command",
+    "local_shell",
+    "exec_command",
+    "write_stdin",
+];
+
 pub(crate) fn uses_deprecated_instructions_file(config_layer_stack: &ConfigLayerStack) -> bool {
     config_layer_stack
         .layers_high_to_low()
@@ -1929,6 +2071,9 @@ persistence = "none"
                 notification_method: NotificationMethod::Auto,
                 animations: true,
                 show_tooltips: true,
+                vim_mode: false,
+                theme: None,
+                keymap: None,
                 experimental_mode: None,
                 alternate_screen: AltScreenMode::Auto,
             }
@@ -3824,6 +3969,7 @@ model_verbosity = "high"
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: Constrained::allow_any(AskForApproval::Never),
+                approval_rules: ApprovalRules::default(),
                 sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
                 enforce_residency: Constrained::allow_any(None),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -3835,17 +3981,24 @@ model_verbosity = "high"
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: Constrained::allow_any(HashMap::new()),
                 mcp_oauth_credentials_store_mode: Default::default(),
+                secrets_backend: Default::default(),
+                secrets_key_store: Default::default(),
+                lsp_servers: BTreeMap::new(),
                 mcp_oauth_callback_port: None,
                 model_providers: fixture.model_provider_map.clone(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
                 agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
+                agent_roles: BTreeMap::new(),
+                collaboration_modes: BTreeMap::new(),
+                allowed_tools: None,
                 codex_home: fixture.codex_home(),
                 config_layer_stack: Default::default(),
                 history: History::default(),
                 ephemeral: false,
                 file_opener: UriBasedFileOpener::VsCode,
+                plan_artifacts: PlanArtifacts::Repo,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
                 show_raw_agent_reasoning: false,
@@ -3858,6 +4011,7 @@ model_verbosity = "high"
                 base_instructions: None,
                 developer_instructions: None,
                 compact_prompt: None,
+                compact_preserved_tool_outputs: None,
                 forced_chatgpt_workspace_id: None,
                 forced_login_method: None,
                 include_apply_patch_tool: false,
@@ -3876,6 +4030,9 @@ model_verbosity = "high"
                 tui_notification_method: Default::default(),
                 animations: true,
                 show_tooltips: true,
+                tui_vim_mode: false,
+                tui_keymap: TuiKeymap::default(),
+                tui_theme: None,
                 experimental_mode: None,
                 analytics_enabled: Some(true),
                 feedback_enabled: true,
@@ -3909,6 +4066,7 @@ model_verbosity = "high"
             model_provider_id: "openai-custom".to_string(),
             model_provider: fixture.openai_custom_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::UnlessTrusted),
+            approval_rules: ApprovalRules::default(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             enforce_residency: Constrained::allow_any(None),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -3920,17 +4078,24 @@ model_verbosity = "high"
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
             mcp_oauth_credentials_store_mode: Default::default(),
+            secrets_backend: Default::default(),
+            secrets_key_store: Default::default(),
+            lsp_servers: BTreeMap::new(),
             mcp_oauth_callback_port: None,
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
+            agent_roles: BTreeMap::new(),
+            collaboration_modes: BTreeMap::new(),
+            allowed_tools: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
             ephemeral: false,
             file_opener: UriBasedFileOpener::VsCode,
+            plan_artifacts: PlanArtifacts::Repo,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
@@ -3943,6 +4108,7 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            compact_preserved_tool_outputs: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -3961,6 +4127,9 @@ model_verbosity = "high"
             tui_notification_method: Default::default(),
             animations: true,
             show_tooltips: true,
+            tui_vim_mode: false,
+            tui_keymap: TuiKeymap::default(),
+            tui_theme: None,
             experimental_mode: None,
             analytics_enabled: Some(true),
             feedback_enabled: true,
@@ -4009,6 +4178,7 @@ model_verbosity = "high"
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
+            approval_rules: ApprovalRules::default(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             enforce_residency: Constrained::allow_any(None),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -4020,17 +4190,24 @@ model_verbosity = "high"
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
             mcp_oauth_credentials_store_mode: Default::default(),
+            secrets_backend: Default::default(),
+            secrets_key_store: Default::default(),
+            lsp_servers: BTreeMap::new(),
             mcp_oauth_callback_port: None,
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
+            agent_roles: BTreeMap::new(),
+            collaboration_modes: BTreeMap::new(),
+            allowed_tools: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
             ephemeral: false,
             file_opener: UriBasedFileOpener::VsCode,
+            plan_artifacts: PlanArtifacts::Repo,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
@@ -4043,6 +4220,7 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            compact_preserved_tool_outputs: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -4061,6 +4239,9 @@ model_verbosity = "high"
             tui_notification_method: Default::default(),
             animations: true,
             show_tooltips: true,
+            tui_vim_mode: false,
+            tui_keymap: TuiKeymap::default(),
+            tui_theme: None,
             experimental_mode: None,
             analytics_enabled: Some(false),
             feedback_enabled: true,
@@ -4095,6 +4276,7 @@ model_verbosity = "high"
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
+            approval_rules: ApprovalRules::default(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             enforce_residency: Constrained::allow_any(None),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -4106,17 +4288,24 @@ model_verbosity = "high"
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
             mcp_oauth_credentials_store_mode: Default::default(),
+            secrets_backend: Default::default(),
+            secrets_key_store: Default::default(),
+            lsp_servers: BTreeMap::new(),
             mcp_oauth_callback_port: None,
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
+            agent_roles: BTreeMap::new(),
+            collaboration_modes: BTreeMap::new(),
+            allowed_tools: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
             ephemeral: false,
             file_opener: UriBasedFileOpener::VsCode,
+            plan_artifacts: PlanArtifacts::Repo,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
@@ -4129,6 +4318,7 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            compact_preserved_tool_outputs: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -4147,6 +4337,9 @@ model_verbosity = "high"
             tui_notification_method: Default::default(),
             animations: true,
             show_tooltips: true,
+            tui_vim_mode: false,
+            tui_keymap: TuiKeymap::default(),
+            tui_theme: None,
             experimental_mode: None,
             analytics_enabled: Some(true),
             feedback_enabled: true,
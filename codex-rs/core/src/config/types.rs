@@ -3,6 +3,7 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use crate::config::keymap::TuiKeymapToml;
 use crate::config_loader::RequirementSource;
 pub use codex_protocol::config_types::AltScreenMode;
 pub use codex_protocol::config_types::ModeKind;
@@ -315,6 +316,50 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Where Plan mode saves proposed plans.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlanArtifacts {
+    /// `.codex/plans/` at the root of the git repository, or
+    /// `$CODEX_HOME/plans/` outside of git.
+    #[default]
+    Repo,
+    /// `$CODEX_HOME/plans/`.
+    CodexHome,
+    /// Do not save plans.
+    None,
+}
+
+// ===== Language server configuration =====
+
+/// A language server Codex can start for code navigation, loaded from
+/// `[lsp_servers.<name>]` in config.toml.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LspServerConfig {
+    /// Executable that speaks LSP over stdio, e.g. `rust-analyzer`.
+    pub command: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+
+    /// File extensions handled by this server, without the leading dot.
+    pub extensions: Vec<String>,
+
+    /// LSP language id sent when opening documents. Defaults to the file
+    /// extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_id: Option<String>,
+
+    /// How long to wait for diagnostics after `apply_patch` edits a file
+    /// handled by this server. Defaults to 3000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics_timeout_ms: Option<u64>,
+}
+
 // ===== Analytics configuration =====
 
 /// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
@@ -447,6 +492,37 @@ impl fmt::Display for NotificationMethod {
     }
 }
 
+/// Named color themes for the TUI.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TuiTheme {
+    /// Colors derived from the terminal background.
+    #[default]
+    Default,
+    /// Bright ANSI colors and no dimmed text.
+    HighContrast,
+    /// No colors; emphasis uses bold, italics and underlines only.
+    Monochrome,
+}
+
+impl TuiTheme {
+    pub const ALL: [TuiTheme; 3] = [
+        TuiTheme::Default,
+        TuiTheme::HighContrast,
+        TuiTheme::Monochrome,
+    ];
+}
+
+impl fmt::Display for TuiTheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuiTheme::Default => write!(f, "default"),
+            TuiTheme::HighContrast => write!(f, "high-contrast"),
+            TuiTheme::Monochrome => write!(f, "monochrome"),
+        }
+    }
+}
+
 /// Collection of settings that are specific to the TUI.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
@@ -471,6 +547,21 @@ pub struct Tui {
     #[serde(default = "default_true")]
     pub show_tooltips: bool,
 
+    /// Enable vi-style modal editing (normal/insert/visual modes) in the composer.
+    /// Defaults to `false`.
+    #[serde(default)]
+    pub vim_mode: bool,
+
+    /// Color theme: `default`, `high-contrast` or `monochrome`. When unset,
+    /// `monochrome` is used if the `NO_COLOR` environment variable is set.
+    #[serde(default)]
+    pub theme: Option<TuiTheme>,
+
+    /// Key chords for rebindable shortcuts, e.g. `submit = "ctrl+enter"`.
+    /// Unset actions keep their defaults.
+    #[serde(default)]
+    pub keymap: Option<TuiKeymapToml>,
+
     /// Start the TUI in the specified collaboration mode (plan/default).
     /// Defaults to unset.
     #[serde(default)]
@@ -589,6 +680,10 @@ pub struct ShellEnvironmentPolicyToml {
     pub include_only: Option<Vec<String>>,
 
     pub experimental_use_profile: Option<bool>,
+
+    /// Names of secrets (managed with `codex secrets`) to inject as
+    /// environment variables of the same name.
+    pub secrets: Option<Vec<String>>,
 }
 
 pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
@@ -600,6 +695,9 @@ pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
 /// 3. If `exclude` is not empty, filter the map using the provided patterns.
 /// 4. Insert any entries from `r#set` into the map.
 /// 5. If non-empty, filter the map using the `include_only` patterns.
+///
+/// Values for the names listed in `secrets` are resolved by the session and
+/// injected after these steps, so they survive `include_only` filtering.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ShellEnvironmentPolicy {
     /// Starting point when building the environment.
@@ -620,6 +718,10 @@ pub struct ShellEnvironmentPolicy {
 
     /// If true, the shell profile will be used to run the command.
     pub use_profile: bool,
+
+    /// Names of stored secrets to inject into the environment. Any injected
+    /// value is redacted from command output before it reaches the model.
+    pub secrets: Vec<String>,
 }
 
 impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
@@ -641,6 +743,7 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
             .collect();
         let use_profile = toml.experimental_use_profile.unwrap_or(false);
+        let secrets = toml.secrets.unwrap_or_default();
 
         Self {
             inherit,
@@ -649,6 +752,7 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             r#set,
             include_only,
             use_profile,
+            secrets,
         }
     }
 }
@@ -662,6 +766,7 @@ impl Default for ShellEnvironmentPolicy {
             r#set: HashMap::new(),
             include_only: Vec::new(),
             use_profile: false,
+            secrets: Vec::new(),
         }
     }
 }
@@ -4,10 +4,10 @@ use crate::instructions::SkillInstructions;
 use crate::instructions::UserInstructions;
 use crate::session_prefix::is_session_prefix;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
+use crate::truncate::token_count;
 use crate::truncate::truncate_function_output_items_with_policy;
-use crate::truncate::truncate_text;
+use crate::truncate::truncate_text_with_tokenizer;
 use crate::user_shell_command::is_user_shell_command_text;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
@@ -15,6 +15,7 @@ use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
+use codex_utils_tokenizer::Tokenizer;
 use std::ops::Deref;
 
 /// Transcript of thread history
@@ -51,8 +52,12 @@ impl ContextManager {
     }
 
     /// `items` is ordered from oldest to newest.
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
-    where
+    pub(crate) fn record_items<I>(
+        &mut self,
+        items: I,
+        policy: TruncationPolicy,
+        tokenizer: Option<&Tokenizer>,
+    ) where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
@@ -63,7 +68,7 @@ impl ContextManager {
                 continue;
             }
 
-            let processed = self.process_item(item_ref, policy);
+            let processed = self.process_item(item_ref, policy, tokenizer);
             self.items.push(processed);
         }
     }
@@ -82,18 +87,21 @@ impl ContextManager {
         &self.items
     }
 
-    // Estimate token usage using byte-based heuristics from the truncation helpers.
-    // This is a coarse lower bound, not a tokenizer-accurate count.
+    // Estimate token usage with the model's local tokenizer when its encoding is
+    // known, falling back to byte-based heuristics otherwise. Encrypted reasoning
+    // is always estimated from its encoded length.
     pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
+        let tokenizer = turn_context.tokenizer.as_ref();
         let model_info = turn_context.client.get_model_info();
         let personality = turn_context
             .personality
             .or(turn_context.client.config().personality);
         let base_instructions = model_info.get_model_instructions(personality);
-        let base_tokens = i64::try_from(approx_token_count(&base_instructions)).unwrap_or(i64::MAX);
+        let base_tokens =
+            i64::try_from(token_count(&base_instructions, tokenizer)).unwrap_or(i64::MAX);
 
         let items_tokens = self.items.iter().fold(0i64, |acc, item| {
-            acc.saturating_add(estimate_item_token_count(item))
+            acc.saturating_add(estimate_item_token_count(item, tokenizer))
         });
 
         Some(base_tokens.saturating_add(items_tokens))
@@ -222,30 +230,35 @@ impl ContextManager {
                 )
             })
             .fold(0i64, |acc, item| {
-                acc.saturating_add(estimate_item_token_count(item))
+                acc.saturating_add(estimate_item_token_count(item, None))
             })
     }
 
-    fn get_trailing_codex_generated_items_tokens(&self) -> i64 {
+    fn get_trailing_codex_generated_items_tokens(&self, tokenizer: Option<&Tokenizer>) -> i64 {
         let mut total = 0i64;
         for item in self.items.iter().rev() {
             if !is_codex_generated_item(item) {
                 break;
             }
-            total = total.saturating_add(estimate_item_token_count(item));
+            total = total.saturating_add(estimate_item_token_count(item, tokenizer));
         }
         total
     }
 
     /// When true, the server already accounted for past reasoning tokens and
     /// the client should not re-estimate them.
-    pub(crate) fn get_total_token_usage(&self, server_reasoning_included: bool) -> i64 {
+    pub(crate) fn get_total_token_usage(
+        &self,
+        server_reasoning_included: bool,
+        tokenizer: Option<&Tokenizer>,
+    ) -> i64 {
         let last_tokens = self
             .token_info
             .as_ref()
             .map(|info| info.last_token_usage.total_tokens)
             .unwrap_or(0);
-        let trailing_codex_generated_tokens = self.get_trailing_codex_generated_items_tokens();
+        let trailing_codex_generated_tokens =
+            self.get_trailing_codex_generated_items_tokens(tokenizer);
         if server_reasoning_included {
             last_tokens.saturating_add(trailing_codex_generated_tokens)
         } else {
@@ -266,16 +279,25 @@ impl ContextManager {
         normalize::remove_orphan_outputs(&mut self.items);
     }
 
-    fn process_item(&self, item: &ResponseItem, policy: TruncationPolicy) -> ResponseItem {
+    fn process_item(
+        &self,
+        item: &ResponseItem,
+        policy: TruncationPolicy,
+        tokenizer: Option<&Tokenizer>,
+    ) -> ResponseItem {
         let policy_with_serialization_budget = policy * 1.2;
         match item {
             ResponseItem::FunctionCallOutput { call_id, output } => {
-                let truncated =
-                    truncate_text(output.content.as_str(), policy_with_serialization_budget);
+                let truncated = truncate_text_with_tokenizer(
+                    output.content.as_str(),
+                    policy_with_serialization_budget,
+                    tokenizer,
+                );
                 let truncated_items = output.content_items.as_ref().map(|items| {
                     truncate_function_output_items_with_policy(
                         items,
                         policy_with_serialization_budget,
+                        tokenizer,
                     )
                 });
                 ResponseItem::FunctionCallOutput {
@@ -288,7 +310,11 @@ impl ContextManager {
                 }
             }
             ResponseItem::CustomToolCallOutput { call_id, output } => {
-                let truncated = truncate_text(output, policy_with_serialization_budget);
+                let truncated = truncate_text_with_tokenizer(
+                    output,
+                    policy_with_serialization_budget,
+                    tokenizer,
+                );
                 ResponseItem::CustomToolCallOutput {
                     call_id: call_id.clone(),
                     output: truncated,
@@ -333,7 +359,7 @@ fn estimate_reasoning_length(encoded_len: usize) -> usize {
         .saturating_sub(650)
 }
 
-fn estimate_item_token_count(item: &ResponseItem) -> i64 {
+fn estimate_item_token_count(item: &ResponseItem, tokenizer: Option<&Tokenizer>) -> i64 {
     match item {
         ResponseItem::GhostSnapshot { .. } => 0,
         ResponseItem::Reasoning {
@@ -348,7 +374,7 @@ fn estimate_item_token_count(item: &ResponseItem) -> i64 {
         }
         item => {
             let serialized = serde_json::to_string(item).unwrap_or_default();
-            i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+            i64::try_from(token_count(&serialized, tokenizer)).unwrap_or(i64::MAX)
         }
     }
 }
@@ -1,6 +1,7 @@
 use super::*;
 use crate::truncate;
 use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
 use codex_git::GhostCommit;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
@@ -32,7 +33,7 @@ fn create_history_with_items(items: Vec<ResponseItem>) -> ContextManager {
     let mut h = ContextManager::new();
     // Use a generous but fixed token budget; tests only rely on truncation
     // behavior, not on a specific model's token limit.
-    h.record_items(items.iter(), TruncationPolicy::Tokens(10_000));
+    h.record_items(items.iter(), TruncationPolicy::Tokens(10_000), None);
     h
 }
 
@@ -120,12 +121,12 @@ fn filters_non_api_messages() {
         phase: None,
     };
     let reasoning = reasoning_msg("thinking...");
-    h.record_items([&system, &reasoning, &ResponseItem::Other], policy);
+    h.record_items([&system, &reasoning, &ResponseItem::Other], policy, None);
 
     // User and assistant should be retained.
     let u = user_msg("hi");
     let a = assistant_msg("hello");
-    h.record_items([&u, &a], policy);
+    h.record_items([&u, &a], policy, None);
 
     let items = h.raw_items();
     assert_eq!(
@@ -196,15 +197,31 @@ fn trailing_codex_generated_tokens_stop_at_first_non_generated_item() {
         trailing_function_output.clone(),
         trailing_custom_output.clone(),
     ]);
-    let expected_tokens = estimate_item_token_count(&trailing_function_output)
-        .saturating_add(estimate_item_token_count(&trailing_custom_output));
+    let expected_tokens = estimate_item_token_count(&trailing_function_output, None)
+        .saturating_add(estimate_item_token_count(&trailing_custom_output, None));
 
     assert_eq!(
-        history.get_trailing_codex_generated_items_tokens(),
+        history.get_trailing_codex_generated_items_tokens(None),
         expected_tokens
     );
 }
 
+#[test]
+fn item_token_estimate_uses_tokenizer_when_available() {
+    let tokenizer = Tokenizer::new(codex_utils_tokenizer::EncodingKind::O200kBase);
+    let item = assistant_msg(&"let value = compute(&input)?;\n".repeat(20));
+    let serialized = serde_json::to_string(&item).unwrap_or_default();
+
+    assert_eq!(
+        estimate_item_token_count(&item, Some(&tokenizer)),
+        i64::try_from(tokenizer.count(&serialized)).unwrap_or(i64::MAX)
+    );
+    assert_eq!(
+        estimate_item_token_count(&item, None),
+        i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+    );
+}
+
 #[test]
 fn trailing_codex_generated_tokens_exclude_function_call_tail() {
     let history = create_history_with_items(vec![ResponseItem::FunctionCall {
@@ -214,7 +231,7 @@ fn trailing_codex_generated_tokens_exclude_function_call_tail() {
         call_id: "call-tail".to_string(),
     }]);
 
-    assert_eq!(history.get_trailing_codex_generated_items_tokens(), 0);
+    assert_eq!(history.get_trailing_codex_generated_items_tokens(None), 0);
 }
 
 #[test]
@@ -237,8 +254,8 @@ fn total_token_usage_includes_only_trailing_codex_generated_items() {
     );
 
     assert_eq!(
-        history.get_total_token_usage(true),
-        100 + estimate_item_token_count(&trailing_output)
+        history.get_total_token_usage(true, None),
+        100 + estimate_item_token_count(&trailing_output, None)
     );
 }
 
@@ -589,7 +606,7 @@ fn record_items_truncates_function_call_output_content() {
         },
     };
 
-    history.record_items([&item], policy);
+    history.record_items([&item], policy, None);
 
     assert_eq!(history.items.len(), 1);
     match &history.items[0] {
@@ -621,7 +638,7 @@ fn record_items_truncates_custom_tool_call_output_content() {
         output: long_output.clone(),
     };
 
-    history.record_items([&item], policy);
+    history.record_items([&item], policy, None);
 
     assert_eq!(history.items.len(), 1);
     match &history.items[0] {
@@ -654,7 +671,7 @@ fn record_items_respects_custom_token_limit() {
         },
     };
 
-    history.record_items([&item], policy);
+    history.record_items([&item], policy, None);
 
     let stored = match &history.items[0] {
         ResponseItem::FunctionCallOutput { output, .. } => output,
@@ -24,7 +24,7 @@ use thiserror::Error;
 use tokio::fs;
 use tokio::task::spawn_blocking;
 
-use crate::bash::parse_shell_lc_plain_commands;
+use crate::bash::parse_shell_script_plain_commands;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::sandboxing::SandboxPermissions;
@@ -133,7 +133,7 @@ impl ExecPolicyManager {
         } = req;
         let exec_policy = self.current();
         let commands =
-            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+            parse_shell_script_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
         let exec_policy_fallback = |cmd: &[String]| {
             render_decision_for_unmatched_command(
                 approval_policy,
@@ -826,6 +826,39 @@ prefix_rule(pattern=["rm"], decision="forbidden")
         );
     }
 
+    #[tokio::test]
+    async fn evaluates_fish_and_nushell_inner_commands() {
+        let policy_src = r#"
+prefix_rule(pattern=["rm"], decision="forbidden")
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let manager = ExecPolicyManager::new(Arc::new(parser.build()));
+
+        for command in [
+            vec!["fish", "-c", "ls; and rm -rf /some/important/folder"],
+            vec!["nu", "-c", "ls; rm -rf /some/important/folder"],
+        ] {
+            let command: Vec<String> = command.into_iter().map(String::from).collect();
+            let requirement = manager
+                .create_exec_approval_requirement_for_command(ExecApprovalRequest {
+                    features: &Features::with_defaults(),
+                    command: &command,
+                    approval_policy: AskForApproval::OnRequest,
+                    sandbox_policy: &SandboxPolicy::DangerFullAccess,
+                    sandbox_permissions: SandboxPermissions::UseDefault,
+                    prefix_rule: None,
+                })
+                .await;
+            assert!(
+                matches!(requirement, ExecApprovalRequirement::Forbidden { .. }),
+                "{command:?}: {requirement:?}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn justification_is_included_in_forbidden_exec_approval_requirement() {
         let policy_src = r#"
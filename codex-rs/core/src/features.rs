@@ -127,6 +127,8 @@ pub enum Feature {
     Personality,
     /// Use the Responses API WebSocket transport for OpenAI by default.
     ResponsesWebsockets,
+    /// Index workspace symbols in the background for `find_symbol` and `@#` mentions.
+    SymbolIndex,
 }
 
 impl Feature {
@@ -577,6 +579,16 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::UnderDevelopment,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::SymbolIndex,
+        key: "symbol_index",
+        stage: Stage::Experimental {
+            name: "Symbol search",
+            menu_description: "Index functions and types so Codex can jump to definitions and you can mention them with @#.",
+            announcement: "NEW! Try Symbol search: mention functions and types with @#name. Enable in /experimental!",
+        },
+        default_enabled: false,
+    },
 ];
 
 /// Push a warning event if any under-development features are enabled.
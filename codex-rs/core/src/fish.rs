@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use crate::shell::ShellType;
+use crate::shell::detect_shell_type;
+use crate::simple_shell_script::ScriptSyntax;
+use crate::simple_shell_script::split_plain_commands;
+
+const FISH_SYNTAX: ScriptSyntax = ScriptSyntax {
+    keyword_connectors: &["and", "or"],
+    rejected_keywords: &[
+        "begin", "end", "if", "else", "for", "while", "switch", "case", "function", "not", "!",
+        "set",
+    ],
+    single_quote_escapes: true,
+    interpolating_double_quotes: true,
+};
+
+/// Extract the script from a `fish -c "<script>"` or `fish -lc "<script>"`
+/// invocation.
+pub fn extract_fish_command(command: &[String]) -> Option<(&str, &str)> {
+    let [shell, flag, script] = command else {
+        return None;
+    };
+    if !matches!(flag.as_str(), "-lc" | "-c")
+        || detect_shell_type(&PathBuf::from(shell)) != Some(ShellType::Fish)
+    {
+        return None;
+    }
+    Some((shell, script))
+}
+
+/// Returns the sequence of plain commands within a `fish -lc "..."`
+/// invocation when the script only contains word-only commands joined by
+/// `;`, `&&`, `||`, `|`, `and` or `or`.
+pub fn parse_fish_plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    let (_, script) = extract_fish_command(command)?;
+    split_plain_commands(script, &FISH_SYNTAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn extracts_fish_scripts() {
+        let command = vec_str(&["/usr/bin/fish", "-lc", "ls; and pwd"]);
+        assert_eq!(
+            extract_fish_command(&command),
+            Some(("/usr/bin/fish", "ls; and pwd"))
+        );
+        assert_eq!(
+            parse_fish_plain_commands(&command),
+            Some(vec![vec_str(&["ls"]), vec_str(&["pwd"])])
+        );
+        assert_eq!(extract_fish_command(&vec_str(&["bash", "-lc", "ls"])), None);
+    }
+
+    #[test]
+    fn rejects_fish_substitutions_and_blocks() {
+        for script in ["cat (git ls-files)", "set -x FOO bar", "begin; ls; end"] {
+            let command = vec_str(&["fish", "-c", script]);
+            assert_eq!(parse_fish_plain_commands(&command), None, "{script}");
+        }
+    }
+}
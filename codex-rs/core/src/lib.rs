@@ -8,6 +8,7 @@
 mod analytics_client;
 pub mod api_bridge;
 mod apply_patch;
+pub mod approval_memory;
 pub mod auth;
 pub mod bash;
 mod client;
@@ -32,13 +33,16 @@ pub mod exec;
 pub mod exec_env;
 mod exec_policy;
 pub mod features;
+pub mod fish;
 mod flags;
 pub mod git_info;
 pub mod instructions;
 pub mod landlock;
+mod lsp;
 pub mod mcp;
 mod mcp_connection_manager;
 pub mod models_manager;
+pub mod nushell;
 mod transport_manager;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_CAPABILITY;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_METHOD;
@@ -50,6 +54,7 @@ mod model_provider_info;
 pub mod parse_command;
 pub mod path_utils;
 pub mod personality_migration;
+pub mod plan_artifacts;
 pub mod powershell;
 mod proposed_plan_parser;
 pub mod sandboxing;
@@ -71,8 +76,11 @@ pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
 mod event_mapping;
+pub mod review_baseline;
+pub mod review_export;
 pub mod review_format;
 pub mod review_prompts;
+pub mod session_export;
 mod thread_manager;
 pub mod web_search;
 pub use codex_protocol::protocol::InitialHistory;
@@ -93,7 +101,9 @@ mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
 pub mod shell;
+mod shell_secrets;
 pub mod shell_snapshot;
+mod simple_shell_script;
 pub mod skills;
 pub mod spawn;
 pub mod state_db;
@@ -0,0 +1,535 @@
+//! JSON-RPC client for a single language server speaking LSP over stdio.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::process::Child;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::sync::Notify;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tokio::time::timeout_at;
+
+use crate::config::types::LspServerConfig;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum LspError {
+    #[error("failed to start language server `{command}`: {source}")]
+    Spawn {
+        command: String,
+        source: std::io::Error,
+    },
+    #[error("language server I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("language server exited")]
+    Closed,
+    #[error("language server did not answer `{0}` within 30 seconds")]
+    Timeout(String),
+    #[error("language server failed `{method}`: {message}")]
+    Server { method: String, message: String },
+}
+
+type SharedWriter = Arc<Mutex<Box<dyn AsyncWrite + Send + Unpin>>>;
+type PendingRequests =
+    Arc<std::sync::Mutex<HashMap<i64, oneshot::Sender<Result<JsonValue, String>>>>>;
+type DiagnosticsByUri = Arc<std::sync::Mutex<HashMap<String, PublishedDiagnostics>>>;
+
+/// The latest `textDocument/publishDiagnostics` for a document. `generation`
+/// counts how many times the server published diagnostics for it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct PublishedDiagnostics {
+    pub generation: u64,
+    pub diagnostics: Vec<JsonValue>,
+}
+
+struct OpenDocument {
+    version: i32,
+    text: String,
+}
+
+pub(crate) struct LspClient {
+    writer: SharedWriter,
+    pending: PendingRequests,
+    next_id: AtomicI64,
+    diagnostics: DiagnosticsByUri,
+    diagnostics_changed: Arc<Notify>,
+    documents: Mutex<HashMap<PathBuf, OpenDocument>>,
+    reader: JoinHandle<()>,
+    _child: Option<Child>,
+}
+
+impl LspClient {
+    /// Starts the server in `root` and completes the `initialize` handshake.
+    pub(crate) async fn start(config: &LspServerConfig, root: &Path) -> Result<Self, LspError> {
+        let mut command = Command::new(&config.command);
+        command
+            .args(&config.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        if let Some(env) = &config.env {
+            command.envs(env);
+        }
+        let mut child = command.spawn().map_err(|source| LspError::Spawn {
+            command: config.command.clone(),
+            source,
+        })?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(LspError::Closed);
+        };
+
+        let client = Self::connect(stdout, stdin, Some(child));
+        client.initialize(root).await?;
+        Ok(client)
+    }
+
+    fn connect(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        writer: impl AsyncWrite + Send + Unpin + 'static,
+        child: Option<Child>,
+    ) -> Self {
+        let writer: SharedWriter = Arc::new(Mutex::new(Box::new(writer)));
+        let pending = PendingRequests::default();
+        let diagnostics = DiagnosticsByUri::default();
+        let diagnostics_changed = Arc::new(Notify::new());
+        let reader = tokio::spawn(read_loop(
+            BufReader::new(reader),
+            Arc::clone(&writer),
+            Arc::clone(&pending),
+            Arc::clone(&diagnostics),
+            Arc::clone(&diagnostics_changed),
+        ));
+        Self {
+            writer,
+            pending,
+            next_id: AtomicI64::new(1),
+            diagnostics,
+            diagnostics_changed,
+            documents: Mutex::new(HashMap::new()),
+            reader,
+            _child: child,
+        }
+    }
+
+    async fn initialize(&self, root: &Path) -> Result<(), LspError> {
+        let root_uri = file_uri(root);
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.request(
+            "initialize",
+            json!({
+                "processId": std::process::id(),
+                "rootUri": root_uri,
+                "workspaceFolders": [{ "uri": root_uri, "name": name }],
+                "capabilities": {
+                    "textDocument": {
+                        "synchronization": { "didSave": true },
+                        "definition": { "linkSupport": true },
+                        "references": {},
+                        "hover": { "contentFormat": ["markdown", "plaintext"] },
+                        "publishDiagnostics": {},
+                    },
+                    "workspace": { "configuration": true, "workspaceFolders": true },
+                },
+            }),
+        )
+        .await?;
+        self.notify("initialized", json!({})).await
+    }
+
+    pub(crate) async fn request(
+        &self,
+        method: &str,
+        params: JsonValue,
+    ) -> Result<JsonValue, LspError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        lock(&self.pending).insert(id, tx);
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(err) = write_message(&self.writer, &message).await {
+            lock(&self.pending).remove(&id);
+            return Err(err.into());
+        }
+
+        match timeout(REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(message))) => Err(LspError::Server {
+                method: method.to_string(),
+                message,
+            }),
+            Ok(Err(_)) => Err(LspError::Closed),
+            Err(_) => {
+                lock(&self.pending).remove(&id);
+                Err(LspError::Timeout(method.to_string()))
+            }
+        }
+    }
+
+    pub(crate) async fn notify(&self, method: &str, params: JsonValue) -> Result<(), LspError> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&self.writer, &message).await?;
+        Ok(())
+    }
+
+    /// Sends the on-disk contents of `path` to the server: `didOpen` the
+    /// first time, `didChange` and `didSave` when the contents changed since,
+    /// and `didClose` once the file is gone. Returns whether anything was sent.
+    pub(crate) async fn sync_document(
+        &self,
+        path: &Path,
+        language_id: &str,
+    ) -> Result<bool, LspError> {
+        let uri = file_uri(path);
+        let text = tokio::fs::read_to_string(path).await.ok();
+        let mut documents = self.documents.lock().await;
+        let Some(text) = text else {
+            if documents.remove(path).is_none() {
+                return Ok(false);
+            }
+            self.notify(
+                "textDocument/didClose",
+                json!({ "textDocument": { "uri": uri } }),
+            )
+            .await?;
+            return Ok(true);
+        };
+        match documents.get_mut(path) {
+            None => {
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id,
+                            "version": 1,
+                            "text": text,
+                        },
+                    }),
+                )
+                .await?;
+                documents.insert(path.to_path_buf(), OpenDocument { version: 1, text });
+            }
+            Some(document) => {
+                if document.text == text {
+                    return Ok(false);
+                }
+                document.version += 1;
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": document.version },
+                        "contentChanges": [{ "text": text }],
+                    }),
+                )
+                .await?;
+                self.notify(
+                    "textDocument/didSave",
+                    json!({ "textDocument": { "uri": uri }, "text": text }),
+                )
+                .await?;
+                document.text = text;
+            }
+        }
+        Ok(true)
+    }
+
+    pub(crate) fn diagnostics(&self, uri: &str) -> PublishedDiagnostics {
+        lock(&self.diagnostics)
+            .get(uri)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// All non-empty diagnostics published so far, sorted by URI.
+    pub(crate) fn all_diagnostics(&self) -> Vec<(String, Vec<JsonValue>)> {
+        let mut all: Vec<_> = lock(&self.diagnostics)
+            .iter()
+            .filter(|(_, published)| !published.diagnostics.is_empty())
+            .map(|(uri, published)| (uri.clone(), published.diagnostics.clone()))
+            .collect();
+        all.sort_by(|(a, _), (b, _)| a.cmp(b));
+        all
+    }
+
+    /// Waits until the server publishes diagnostics for `uri` newer than
+    /// `after_generation`, then returns them. Returns the latest known
+    /// diagnostics if none arrive within `wait`.
+    pub(crate) async fn wait_for_diagnostics(
+        &self,
+        uri: &str,
+        after_generation: u64,
+        wait: Duration,
+    ) -> PublishedDiagnostics {
+        let deadline = Instant::now() + wait;
+        loop {
+            let notified = self.diagnostics_changed.notified();
+            let current = self.diagnostics(uri);
+            if current.generation > after_generation {
+                return current;
+            }
+            if timeout_at(deadline, notified).await.is_err() {
+                return current;
+            }
+        }
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+pub(crate) fn file_uri(path: &Path) -> String {
+    url::Url::from_file_path(path)
+        .map(String::from)
+        .unwrap_or_else(|()| format!("file://{}", path.display()))
+}
+
+pub(crate) fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+async fn read_loop(
+    mut reader: impl AsyncBufRead + Unpin,
+    writer: SharedWriter,
+    pending: PendingRequests,
+    diagnostics: DiagnosticsByUri,
+    diagnostics_changed: Arc<Notify>,
+) {
+    while let Ok(Some(message)) = read_message(&mut reader).await {
+        let method = message.get("method").and_then(JsonValue::as_str);
+        match (method, message.get("id")) {
+            // Requests from the server. Answer them so it does not stall;
+            // `workspace/configuration` expects one entry per requested item.
+            (Some(method), Some(id)) => {
+                let result = match method {
+                    "workspace/configuration" => {
+                        let items = message
+                            .pointer("/params/items")
+                            .and_then(JsonValue::as_array)
+                            .map_or(0, Vec::len);
+                        JsonValue::Array(vec![JsonValue::Null; items])
+                    }
+                    _ => JsonValue::Null,
+                };
+                let response = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                if write_message(&writer, &response).await.is_err() {
+                    break;
+                }
+            }
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let Some(uri) = message.pointer("/params/uri").and_then(JsonValue::as_str) else {
+                    continue;
+                };
+                let published = message
+                    .pointer("/params/diagnostics")
+                    .and_then(JsonValue::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                {
+                    let mut diagnostics = lock(&diagnostics);
+                    let entry = diagnostics.entry(uri.to_string()).or_default();
+                    entry.generation += 1;
+                    entry.diagnostics = published;
+                }
+                diagnostics_changed.notify_waiters();
+            }
+            (Some(_), None) => {}
+            (None, Some(id)) => {
+                let Some(tx) = id.as_i64().and_then(|id| lock(&pending).remove(&id)) else {
+                    continue;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(error
+                        .get("message")
+                        .and_then(JsonValue::as_str)
+                        .unwrap_or("unknown error")
+                        .to_string()),
+                    None => Ok(message.get("result").cloned().unwrap_or(JsonValue::Null)),
+                };
+                let _ = tx.send(result);
+            }
+            (None, None) => {}
+        }
+    }
+    // Dropping the senders fails every outstanding request with `Closed`.
+    lock(&pending).clear();
+}
+
+async fn read_message(
+    reader: &mut (impl AsyncBufRead + Unpin),
+) -> std::io::Result<Option<JsonValue>> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(std::io::Error::other)
+}
+
+async fn write_message(writer: &SharedWriter, message: &JsonValue) -> std::io::Result<()> {
+    let body = serde_json::to_vec(message).map_err(std::io::Error::other)?;
+    let mut writer = writer.lock().await;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::io::DuplexStream;
+    use tokio::io::ReadHalf;
+
+    struct StubServer {
+        reader: BufReader<ReadHalf<DuplexStream>>,
+        writer: SharedWriter,
+    }
+
+    impl StubServer {
+        async fn next(&mut self) -> JsonValue {
+            read_message(&mut self.reader)
+                .await
+                .expect("read")
+                .expect("message")
+        }
+
+        async fn send(&self, message: JsonValue) {
+            write_message(&self.writer, &message).await.expect("write");
+        }
+    }
+
+    fn connect_stub() -> (LspClient, StubServer) {
+        let (client_side, server_side) = tokio::io::duplex(64 * 1024);
+        let (client_read, client_write) = tokio::io::split(client_side);
+        let (server_read, server_write) = tokio::io::split(server_side);
+        let client = LspClient::connect(client_read, client_write, None);
+        let server = StubServer {
+            reader: BufReader::new(server_read),
+            writer: Arc::new(Mutex::new(Box::new(server_write))),
+        };
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn requests_are_matched_to_responses_and_server_requests_answered() {
+        let (client, mut server) = connect_stub();
+        let client = Arc::new(client);
+
+        let request = tokio::spawn({
+            let client = Arc::clone(&client);
+            async move { client.request("textDocument/hover", json!({})).await }
+        });
+
+        let message = server.next().await;
+        assert_eq!(message["method"], "textDocument/hover");
+        server
+            .send(json!({
+                "jsonrpc": "2.0",
+                "id": 99,
+                "method": "workspace/configuration",
+                "params": { "items": [{}, {}] },
+            }))
+            .await;
+        assert_eq!(
+            server.next().await,
+            json!({ "jsonrpc": "2.0", "id": 99, "result": [null, null] })
+        );
+        server
+            .send(json!({ "jsonrpc": "2.0", "id": message["id"], "result": { "contents": "i32" } }))
+            .await;
+
+        let result = request.await.expect("join").expect("response");
+        assert_eq!(result, json!({ "contents": "i32" }));
+    }
+
+    #[tokio::test]
+    async fn waits_for_newer_diagnostics() {
+        let (client, server) = connect_stub();
+        let uri = "file:///tmp/lib.rs";
+        server
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": [{ "message": "old" }] },
+            }))
+            .await;
+        let first = client
+            .wait_for_diagnostics(uri, 0, Duration::from_secs(5))
+            .await;
+        assert_eq!(first.generation, 1);
+
+        server
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": [] },
+            }))
+            .await;
+        let second = client
+            .wait_for_diagnostics(uri, first.generation, Duration::from_secs(5))
+            .await;
+        assert_eq!(
+            second,
+            PublishedDiagnostics {
+                generation: 2,
+                diagnostics: Vec::new(),
+            }
+        );
+        assert_eq!(client.all_diagnostics(), Vec::new());
+    }
+}
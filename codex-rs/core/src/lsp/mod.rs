@@ -0,0 +1,393 @@
+//! Language servers exposed to the model as code navigation tools.
+//!
+//! Servers configured under `[lsp_servers.<name>]` are started lazily, one
+//! per server and workspace root, the first time a tool touches a file with
+//! one of their extensions. Documents are synced from disk before every
+//! request, so edits made by `apply_patch` or shell commands are always
+//! visible to the server.
+
+mod client;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::join_all;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::config::types::LspServerConfig;
+use crate::git_info::get_git_repo_root;
+use client::LspClient;
+use client::LspError;
+use client::file_uri;
+use client::uri_to_path;
+
+const DEFAULT_DIAGNOSTICS_TIMEOUT: Duration = Duration::from_millis(3000);
+
+/// Diagnostics at or above this severity (1 = error, 2 = warning) are
+/// reported after edits.
+const EDIT_DIAGNOSTICS_MAX_SEVERITY: u64 = 2;
+
+pub(crate) struct LspManager {
+    servers: BTreeMap<String, LspServerConfig>,
+    clients: Mutex<HashMap<(String, PathBuf), Arc<LspClient>>>,
+}
+
+/// A file synced to the language server that handles it.
+pub(crate) struct LspDocument {
+    pub client: Arc<LspClient>,
+    pub uri: String,
+    /// Whether the sync sent new contents to the server.
+    changed: bool,
+    generation_before_sync: u64,
+    diagnostics_timeout: Duration,
+}
+
+impl LspManager {
+    pub(crate) fn new(servers: BTreeMap<String, LspServerConfig>) -> Self {
+        Self {
+            servers,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+
+    fn server_for(&self, path: &Path) -> Option<(&String, &LspServerConfig)> {
+        let extension = path.extension()?.to_str()?;
+        self.servers.iter().find(|(_, server)| {
+            server
+                .extensions
+                .iter()
+                .any(|candidate| candidate.trim_start_matches('.') == extension)
+        })
+    }
+
+    /// Syncs `path` to its language server, starting the server for the
+    /// workspace containing `cwd` if needed. Returns `None` when no configured
+    /// server handles the file.
+    pub(crate) async fn document(
+        &self,
+        path: &Path,
+        cwd: &Path,
+    ) -> Result<Option<LspDocument>, LspError> {
+        let Some((name, server)) = self.server_for(path) else {
+            return Ok(None);
+        };
+        let root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        let client = {
+            let mut clients = self.clients.lock().await;
+            match clients.get(&(name.clone(), root.clone())) {
+                Some(client) => Arc::clone(client),
+                None => {
+                    let client = Arc::new(LspClient::start(server, &root).await?);
+                    clients.insert((name.clone(), root), Arc::clone(&client));
+                    client
+                }
+            }
+        };
+        let language_id = server.language_id.clone().unwrap_or_else(|| {
+            path.extension()
+                .map(|extension| extension.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let uri = file_uri(path);
+        let generation_before_sync = client.diagnostics(&uri).generation;
+        let changed = client.sync_document(path, &language_id).await?;
+        Ok(Some(LspDocument {
+            client,
+            uri,
+            changed,
+            generation_before_sync,
+            diagnostics_timeout: server
+                .diagnostics_timeout_ms
+                .map_or(DEFAULT_DIAGNOSTICS_TIMEOUT, Duration::from_millis),
+        }))
+    }
+
+    /// Diagnostics published by every running server, for `lsp_diagnostics`
+    /// without a path.
+    pub(crate) async fn all_diagnostics(&self) -> Vec<(PathBuf, Vec<JsonValue>)> {
+        let clients: Vec<Arc<LspClient>> = self.clients.lock().await.values().cloned().collect();
+        let mut all: Vec<(PathBuf, Vec<JsonValue>)> = clients
+            .iter()
+            .flat_map(|client| client.all_diagnostics())
+            .filter_map(|(uri, diagnostics)| Some((uri_to_path(&uri)?, diagnostics)))
+            .collect();
+        all.sort_by(|(a, _), (b, _)| a.cmp(b));
+        all
+    }
+
+    /// Syncs files just edited by `apply_patch` and waits briefly for their
+    /// servers to publish diagnostics. Returns a report of errors and
+    /// warnings, or `None` when there are none or no server handles the files.
+    pub(crate) async fn diagnostics_after_edit(
+        &self,
+        paths: &[PathBuf],
+        cwd: &Path,
+    ) -> Option<String> {
+        let reports = paths.iter().map(|path| async move {
+            if !path.exists() {
+                return None;
+            }
+            let document = match self.document(path, cwd).await {
+                Ok(document) => document?,
+                Err(err) => {
+                    tracing::warn!(
+                        "failed to sync {} with its language server: {err}",
+                        path.display()
+                    );
+                    return None;
+                }
+            };
+            let diagnostics: Vec<JsonValue> = document
+                .diagnostics()
+                .await
+                .into_iter()
+                .filter(|diagnostic| severity(diagnostic) <= EDIT_DIAGNOSTICS_MAX_SEVERITY)
+                .collect();
+            (!diagnostics.is_empty()).then(|| format_diagnostics(path, &diagnostics, cwd))
+        });
+        let reports: Vec<String> = join_all(reports).await.into_iter().flatten().collect();
+        if reports.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Language server diagnostics after this edit:\n{}",
+            reports.join("\n")
+        ))
+    }
+}
+
+impl LspDocument {
+    /// The document's diagnostics. Waits for the server to publish fresh ones
+    /// when the sync changed the document or none were published yet.
+    pub(crate) async fn diagnostics(&self) -> Vec<JsonValue> {
+        if !self.changed && self.generation_before_sync > 0 {
+            return self.client.diagnostics(&self.uri).diagnostics;
+        }
+        self.client
+            .wait_for_diagnostics(
+                &self.uri,
+                self.generation_before_sync,
+                self.diagnostics_timeout,
+            )
+            .await
+            .diagnostics
+    }
+}
+
+/// Converts a 1-based line and character column into an LSP position, whose
+/// `character` counts UTF-16 code units.
+pub(crate) fn lsp_position(text: &str, line: usize, column: usize) -> JsonValue {
+    let line_index = line.saturating_sub(1);
+    let character: usize = text
+        .lines()
+        .nth(line_index)
+        .map(|line_text| {
+            line_text
+                .chars()
+                .take(column.saturating_sub(1))
+                .map(char::len_utf16)
+                .sum()
+        })
+        .unwrap_or_default();
+    json!({ "line": line_index, "character": character })
+}
+
+/// Converts an LSP position into a 1-based line and character column.
+fn from_lsp_position(text: Option<&str>, position: &JsonValue) -> (usize, usize) {
+    let line = position["line"].as_u64().unwrap_or_default() as usize;
+    let utf16_offset = position["character"].as_u64().unwrap_or_default() as usize;
+    let column = text
+        .and_then(|text| text.lines().nth(line))
+        .map(|line_text| {
+            let mut units = 0;
+            line_text
+                .chars()
+                .take_while(|ch| {
+                    units += ch.len_utf16();
+                    units <= utf16_offset
+                })
+                .count()
+        })
+        .unwrap_or(utf16_offset);
+    (line + 1, column + 1)
+}
+
+/// Formats a `textDocument/definition` or `textDocument/references` result
+/// as `path:line:column: source line`, one location per line.
+pub(crate) fn format_locations(result: &JsonValue, cwd: &Path) -> Vec<String> {
+    let locations: Vec<&JsonValue> = match result {
+        JsonValue::Array(items) => items.iter().collect(),
+        JsonValue::Null => Vec::new(),
+        single => vec![single],
+    };
+    let mut files: HashMap<PathBuf, Option<String>> = HashMap::new();
+    locations
+        .into_iter()
+        .filter_map(|location| {
+            // `Location` or `LocationLink`.
+            let uri = location
+                .get("uri")
+                .or_else(|| location.get("targetUri"))?
+                .as_str()?;
+            let range = location
+                .get("range")
+                .or_else(|| location.get("targetSelectionRange"))?;
+            let path = uri_to_path(uri)?;
+            let text = files
+                .entry(path.clone())
+                .or_insert_with(|| std::fs::read_to_string(&path).ok());
+            let (line, column) = from_lsp_position(text.as_deref(), &range["start"]);
+            let source_line = text
+                .as_deref()
+                .and_then(|text| text.lines().nth(line - 1))
+                .map(str::trim)
+                .unwrap_or_default();
+            Some(format!(
+                "{}:{line}:{column}: {source_line}",
+                display_path(&path, cwd)
+            ))
+        })
+        .collect()
+}
+
+/// Extracts the text of a `textDocument/hover` result.
+pub(crate) fn format_hover(result: &JsonValue) -> Option<String> {
+    fn marked_text(contents: &JsonValue) -> Option<String> {
+        match contents {
+            JsonValue::String(text) => Some(text.clone()),
+            // `MarkupContent` or `MarkedString` with a language.
+            JsonValue::Object(object) => {
+                let value = object.get("value")?.as_str()?;
+                match object.get("language").and_then(JsonValue::as_str) {
+                    Some(language) => Some(format!("```{language}\n{value}\n```")),
+                    None => Some(value.to_string()),
+                }
+            }
+            JsonValue::Array(items) => {
+                let parts: Vec<String> = items.iter().filter_map(marked_text).collect();
+                (!parts.is_empty()).then(|| parts.join("\n\n"))
+            }
+            _ => None,
+        }
+    }
+    marked_text(result.get("contents")?).filter(|text| !text.trim().is_empty())
+}
+
+fn severity(diagnostic: &JsonValue) -> u64 {
+    // Servers may omit the severity; clients treat that as an error.
+    diagnostic["severity"].as_u64().unwrap_or(1)
+}
+
+/// Formats diagnostics for one file as `path:line:column: severity: message`.
+pub(crate) fn format_diagnostics(path: &Path, diagnostics: &[JsonValue], cwd: &Path) -> String {
+    let text = std::fs::read_to_string(path).ok();
+    let path = display_path(path, cwd);
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let (line, column) = from_lsp_position(text.as_deref(), &diagnostic["range"]["start"]);
+            let severity = match severity(diagnostic) {
+                1 => "error",
+                2 => "warning",
+                3 => "info",
+                _ => "hint",
+            };
+            let message = diagnostic["message"].as_str().unwrap_or_default();
+            match diagnostic["source"].as_str() {
+                Some(source) => format!("{path}:{line}:{column}: {severity}: {message} ({source})"),
+                None => format!("{path}:{line}:{column}: {severity}: {message}"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn display_path(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn positions_count_utf16_code_units() {
+        let text = "fn main() {}\nlet é🙂 = x;\n";
+        assert_eq!(
+            lsp_position(text, 2, 7),
+            json!({ "line": 1, "character": 7 })
+        );
+        assert_eq!(
+            from_lsp_position(Some(text), &json!({ "line": 1, "character": 7 })),
+            (2, 7)
+        );
+    }
+
+    #[test]
+    fn formats_locations_links_and_diagnostics() {
+        let dir = tempdir().expect("tempdir");
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "pub fn answer() -> i32 {\n    42\n}\n").expect("write");
+        let uri = file_uri(&file);
+        let range = json!({ "start": { "line": 0, "character": 7 }, "end": { "line": 0, "character": 13 } });
+
+        assert_eq!(
+            format_locations(
+                &json!([
+                    { "uri": uri, "range": range },
+                    { "targetUri": uri, "targetRange": range, "targetSelectionRange": range },
+                ]),
+                dir.path(),
+            ),
+            vec![
+                "lib.rs:1:8: pub fn answer() -> i32 {".to_string(),
+                "lib.rs:1:8: pub fn answer() -> i32 {".to_string(),
+            ]
+        );
+        assert_eq!(
+            format_locations(&JsonValue::Null, dir.path()),
+            Vec::<String>::new()
+        );
+
+        assert_eq!(
+            format_diagnostics(
+                &file,
+                &[json!({
+                    "range": { "start": { "line": 1, "character": 4 }, "end": { "line": 1, "character": 6 } },
+                    "severity": 1,
+                    "message": "expected `String`, found integer",
+                    "source": "rustc",
+                })],
+                dir.path(),
+            ),
+            "lib.rs:2:5: error: expected `String`, found integer (rustc)"
+        );
+    }
+
+    #[test]
+    fn extracts_hover_text() {
+        assert_eq!(
+            format_hover(
+                &json!({ "contents": { "kind": "markdown", "value": "```rust\nfn answer() -> i32\n```" } })
+            ),
+            Some("```rust\nfn answer() -> i32\n```".to_string())
+        );
+        assert_eq!(
+            format_hover(&json!({ "contents": [{ "language": "rust", "value": "i32" }, "docs"] })),
+            Some("```rust\ni32\n```\n\ndocs".to_string())
+        );
+        assert_eq!(format_hover(&json!({ "contents": "" })), None);
+    }
+}
@@ -3,8 +3,10 @@ use std::time::Instant;
 
 use tracing::error;
 
+use crate::approval_memory::RememberedApproval;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::approval_rules::ApprovalRuleDecision;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
@@ -116,6 +118,16 @@ pub(crate) async fn handle_mcp_tool_call(
                 )
                 .await
             }
+            McpToolApprovalDecision::Blocked(reason) => {
+                notify_mcp_tool_call_skip(
+                    sess.as_ref(),
+                    turn_context,
+                    &call_id,
+                    invocation,
+                    reason.to_string(),
+                )
+                .await
+            }
         };
 
         let status = if result.is_ok() { "ok" } else { "error" };
@@ -169,6 +181,8 @@ enum McpToolApprovalDecision {
     Accept,
     Decline,
     Cancel,
+    /// Rejected by an approval rule without asking the user.
+    Blocked(&'static str),
 }
 
 struct McpToolApprovalMetadata {
@@ -179,6 +193,7 @@ struct McpToolApprovalMetadata {
 
 const MCP_TOOL_APPROVAL_QUESTION_ID_PREFIX: &str = "mcp_tool_call_approval";
 const MCP_TOOL_APPROVAL_ACCEPT: &str = "Accept";
+const MCP_TOOL_APPROVAL_ACCEPT_FOR_REPO: &str = "Always allow in this repo";
 const MCP_TOOL_APPROVAL_DECLINE: &str = "Decline";
 const MCP_TOOL_APPROVAL_CANCEL: &str = "Cancel";
 
@@ -189,17 +204,59 @@ async fn maybe_request_mcp_tool_approval(
     server: &str,
     tool_name: &str,
 ) -> Option<McpToolApprovalDecision> {
-    if is_full_access_mode(turn_context) {
-        return None;
-    }
-    if server != CODEX_APPS_MCP_SERVER_NAME {
-        return None;
-    }
+    let qualified_name = format!("mcp__{server}__{tool_name}");
+    let rule_decision =
+        sess.services
+            .approval_rules
+            .decision_for(&qualified_name, &[], &turn_context.cwd);
+    let repo_approval = vec![RememberedApproval::McpTool(qualified_name)];
+    let metadata = match rule_decision {
+        Some(ApprovalRuleDecision::Allow) => return None,
+        Some(ApprovalRuleDecision::Deny) => {
+            return Some(McpToolApprovalDecision::Blocked(
+                "MCP tool call blocked by an approval rule in config.toml",
+            ));
+        }
+        Some(ApprovalRuleDecision::Ask)
+            if matches!(turn_context.approval_policy, AskForApproval::Never) =>
+        {
+            return Some(McpToolApprovalDecision::Blocked(
+                "MCP tool call requires approval by an approval rule, but approval_policy is `never`",
+            ));
+        }
+        // An `ask` rule prompts for every call, whatever the tool's annotations say.
+        Some(ApprovalRuleDecision::Ask) => lookup_mcp_tool_metadata(sess, server, tool_name)
+            .await
+            .unwrap_or_else(|| McpToolApprovalMetadata {
+                annotations: ToolAnnotations::new(),
+                connector_name: None,
+                tool_title: None,
+            }),
+        None => {
+            if is_full_access_mode(turn_context) {
+                return None;
+            }
+            if server != CODEX_APPS_MCP_SERVER_NAME {
+                return None;
+            }
 
-    let metadata = lookup_mcp_tool_metadata(sess, server, tool_name).await?;
-    if !requires_mcp_tool_approval(&metadata.annotations) {
-        return None;
-    }
+            let metadata = lookup_mcp_tool_metadata(sess, server, tool_name).await?;
+            if !requires_mcp_tool_approval(&metadata.annotations) {
+                return None;
+            }
+            if sess
+                .services
+                .approval_memory
+                .covers(repo_approval.clone())
+                .await
+            {
+                return None;
+            }
+            metadata
+        }
+    };
+    // Remembering would defeat an `ask` rule, so only offer it without one.
+    let offer_remember = rule_decision.is_none();
 
     let question_id = format!("{MCP_TOOL_APPROVAL_QUESTION_ID_PREFIX}_{call_id}");
     let question = build_mcp_tool_approval_question(
@@ -208,6 +265,7 @@ async fn maybe_request_mcp_tool_approval(
         metadata.tool_title.as_deref(),
         metadata.connector_name.as_deref(),
         &metadata.annotations,
+        offer_remember,
     );
     let args = RequestUserInputArgs {
         questions: vec![question],
@@ -215,6 +273,9 @@ async fn maybe_request_mcp_tool_approval(
     let response = sess
         .request_user_input(turn_context, call_id.to_string(), args)
         .await;
+    if offer_remember && accepted_for_repo(response.as_ref(), &question_id) {
+        sess.services.approval_memory.remember(repo_approval).await;
+    }
     Some(parse_mcp_tool_approval_response(response, &question_id))
 }
 
@@ -261,6 +322,7 @@ fn build_mcp_tool_approval_question(
     tool_title: Option<&str>,
     connector_name: Option<&str>,
     annotations: &ToolAnnotations,
+    offer_remember: bool,
 ) -> RequestUserInputQuestion {
     let destructive = annotations.destructive_hint == Some(true);
     let open_world = annotations.open_world_hint == Some(true);
@@ -285,23 +347,44 @@ fn build_mcp_tool_approval_question(
         question,
         is_other: false,
         is_secret: false,
-        options: Some(vec![
-            RequestUserInputQuestionOption {
-                label: MCP_TOOL_APPROVAL_ACCEPT.to_string(),
-                description: "Run the tool and continue.".to_string(),
-            },
-            RequestUserInputQuestionOption {
-                label: MCP_TOOL_APPROVAL_DECLINE.to_string(),
-                description: "Decline this tool call and continue.".to_string(),
-            },
-            RequestUserInputQuestionOption {
-                label: MCP_TOOL_APPROVAL_CANCEL.to_string(),
-                description: "Cancel this tool call".to_string(),
-            },
-        ]),
+        options: Some(
+            [
+                Some(RequestUserInputQuestionOption {
+                    label: MCP_TOOL_APPROVAL_ACCEPT.to_string(),
+                    description: "Run the tool and continue.".to_string(),
+                }),
+                offer_remember.then(|| RequestUserInputQuestionOption {
+                    label: MCP_TOOL_APPROVAL_ACCEPT_FOR_REPO.to_string(),
+                    description: "Run the tool, and don't ask again for it in this repository."
+                        .to_string(),
+                }),
+                Some(RequestUserInputQuestionOption {
+                    label: MCP_TOOL_APPROVAL_DECLINE.to_string(),
+                    description: "Decline this tool call and continue.".to_string(),
+                }),
+                Some(RequestUserInputQuestionOption {
+                    label: MCP_TOOL_APPROVAL_CANCEL.to_string(),
+                    description: "Cancel this tool call".to_string(),
+                }),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        ),
     }
 }
 
+fn accepted_for_repo(response: Option<&RequestUserInputResponse>, question_id: &str) -> bool {
+    response
+        .and_then(|response| response.answers.get(question_id))
+        .is_some_and(|answer| {
+            answer
+                .answers
+                .iter()
+                .any(|answer| answer == MCP_TOOL_APPROVAL_ACCEPT_FOR_REPO)
+        })
+}
+
 fn parse_mcp_tool_approval_response(
     response: Option<RequestUserInputResponse>,
     question_id: &str,
@@ -316,10 +399,9 @@ fn parse_mcp_tool_approval_response(
     let Some(answers) = answers else {
         return McpToolApprovalDecision::Cancel;
     };
-    if answers
-        .iter()
-        .any(|answer| answer == MCP_TOOL_APPROVAL_ACCEPT)
-    {
+    if answers.iter().any(|answer| {
+        answer == MCP_TOOL_APPROVAL_ACCEPT || answer == MCP_TOOL_APPROVAL_ACCEPT_FOR_REPO
+    }) {
         McpToolApprovalDecision::Accept
     } else if answers
         .iter()
@@ -395,4 +477,37 @@ mod tests {
         let annotations = annotations(Some(true), Some(true), Some(true));
         assert_eq!(requires_mcp_tool_approval(&annotations), false);
     }
+
+    #[test]
+    fn always_allow_in_repo_accepts_and_is_remembered() {
+        let response = RequestUserInputResponse {
+            answers: std::collections::HashMap::from([(
+                "q".to_string(),
+                codex_protocol::request_user_input::RequestUserInputAnswer {
+                    answers: vec![MCP_TOOL_APPROVAL_ACCEPT_FOR_REPO.to_string()],
+                },
+            )]),
+        };
+        assert!(accepted_for_repo(Some(&response), "q"));
+        assert_eq!(
+            parse_mcp_tool_approval_response(Some(response), "q"),
+            McpToolApprovalDecision::Accept
+        );
+
+        let question = build_mcp_tool_approval_question(
+            "q".to_string(),
+            "create_issue",
+            None,
+            None,
+            &annotations(Some(false), Some(true), None),
+            false,
+        );
+        let labels: Vec<String> = question
+            .options
+            .unwrap_or_default()
+            .into_iter()
+            .map(|option| option.label)
+            .collect();
+        assert_eq!(labels, vec!["Accept", "Decline", "Cancel"]);
+    }
 }
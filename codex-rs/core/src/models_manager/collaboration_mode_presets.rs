@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use crate::config::collaboration_modes::CollaborationModeToml;
 use codex_protocol::config_types::CollaborationModeMask;
 use codex_protocol::config_types::ModeKind;
 use codex_protocol::openai_models::ReasoningEffort;
@@ -10,6 +13,35 @@ pub(super) fn builtin_collaboration_mode_presets() -> Vec<CollaborationModeMask>
     vec![plan_preset(), default_preset()]
 }
 
+/// Presets for the user-defined modes, in name order. Each keeps the
+/// instructions of the built-in mode it builds on and appends its own.
+pub(super) fn custom_collaboration_mode_presets(
+    modes: &BTreeMap<String, CollaborationModeToml>,
+) -> Vec<CollaborationModeMask> {
+    modes
+        .iter()
+        .map(|(name, mode)| {
+            let base = mode.base.unwrap_or(ModeKind::Default);
+            let base_instructions = match base {
+                ModeKind::Plan => COLLABORATION_MODE_PLAN,
+                _ => COLLABORATION_MODE_DEFAULT,
+            };
+            let developer_instructions = match &mode.developer_instructions {
+                Some(instructions) => format!("{base_instructions}\n\n{instructions}"),
+                None => base_instructions.to_string(),
+            };
+            CollaborationModeMask {
+                name: name.clone(),
+                mode: Some(base),
+                model: mode.model.clone(),
+                reasoning_effort: mode.model_reasoning_effort.map(Some),
+                developer_instructions: Some(Some(developer_instructions)),
+                custom_mode: Some(name.clone()),
+            }
+        })
+        .collect()
+}
+
 #[cfg(any(test, feature = "test-support"))]
 pub fn test_builtin_collaboration_mode_presets() -> Vec<CollaborationModeMask> {
     builtin_collaboration_mode_presets()
@@ -22,6 +54,7 @@ fn plan_preset() -> CollaborationModeMask {
         model: None,
         reasoning_effort: Some(Some(ReasoningEffort::Medium)),
         developer_instructions: Some(Some(COLLABORATION_MODE_PLAN.to_string())),
+        custom_mode: None,
     }
 }
 
@@ -32,5 +65,6 @@ fn default_preset() -> CollaborationModeMask {
         model: None,
         reasoning_effort: None,
         developer_instructions: Some(Some(COLLABORATION_MODE_DEFAULT.to_string())),
+        custom_mode: None,
     }
 }
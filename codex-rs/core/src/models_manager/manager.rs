@@ -10,6 +10,7 @@ use crate::error::Result as CoreResult;
 use crate::features::Feature;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::models_manager::collaboration_mode_presets::builtin_collaboration_mode_presets;
+use crate::models_manager::collaboration_mode_presets::custom_collaboration_mode_presets;
 use crate::models_manager::model_info;
 use crate::models_manager::model_presets::builtin_model_presets;
 use codex_api::ModelsClient;
@@ -90,9 +91,14 @@ impl ModelsManager {
 
     /// List collaboration mode presets.
     ///
-    /// Returns a static set of presets seeded with the configured model.
-    pub fn list_collaboration_modes(&self) -> Vec<CollaborationModeMask> {
-        builtin_collaboration_mode_presets()
+    /// Returns the built-in presets followed by the user-defined modes from
+    /// `config`.
+    pub fn list_collaboration_modes(&self, config: &Config) -> Vec<CollaborationModeMask> {
+        let mut presets = builtin_collaboration_mode_presets();
+        presets.extend(custom_collaboration_mode_presets(
+            &config.collaboration_modes,
+        ));
+        presets
     }
 
     /// Attempt to list models without blocking, using the current cached state.
@@ -234,11 +240,15 @@ impl ModelsManager {
     /// Replace the cached remote models and rebuild the derived presets list.
     async fn apply_remote_models(&self, models: Vec<ModelInfo>) {
         let mut existing_models = Self::load_remote_models_from_file().unwrap_or_default();
-        for model in models {
+        for mut model in models {
             if let Some(existing_index) = existing_models
                 .iter()
                 .position(|existing| existing.slug == model.slug)
             {
+                // Keep the bundled encoding when the backend does not report one.
+                if model.tokenizer.is_none() {
+                    model.tokenizer = existing_models[existing_index].tokenizer;
+                }
                 existing_models[existing_index] = model;
             } else {
                 existing_models.push(model);
@@ -363,6 +373,7 @@ mod tests {
     use crate::model_provider_info::WireApi;
     use chrono::Utc;
     use codex_protocol::openai_models::ModelsResponse;
+    use codex_protocol::openai_models::TokenizerEncoding;
     use core_test_support::responses::mount_models_once;
     use pretty_assertions::assert_eq;
     use serde_json::json;
@@ -766,6 +777,42 @@ mod tests {
         assert_eq!(available, vec![expected_hidden, expected_visible]);
     }
 
+    #[tokio::test]
+    async fn apply_remote_models_keeps_bundled_tokenizer() {
+        let codex_home = tempdir().expect("temp dir");
+        let auth_manager =
+            AuthManager::from_auth_for_testing(CodexAuth::create_dummy_chatgpt_auth_for_testing());
+        let provider = provider_for("http://example.test".to_string());
+        let manager =
+            ModelsManager::with_provider(codex_home.path().to_path_buf(), auth_manager, provider);
+        let bundled_slug = ModelsManager::load_remote_models_from_file()
+            .expect("bundled models")
+            .into_iter()
+            .find(|model| model.tokenizer.is_some())
+            .expect("bundled model with a tokenizer")
+            .slug;
+
+        manager
+            .apply_remote_models(vec![
+                remote_model(&bundled_slug, "Bundled", 0),
+                remote_model("unknown-remote", "Unknown", 1),
+            ])
+            .await;
+
+        let remote_models = manager.remote_models.read().await.clone();
+        let tokenizer_for = |slug: &str| {
+            remote_models
+                .iter()
+                .find(|model| model.slug == slug)
+                .and_then(|model| model.tokenizer)
+        };
+        assert_eq!(
+            tokenizer_for(&bundled_slug),
+            Some(TokenizerEncoding::O200kBase)
+        );
+        assert_eq!(tokenizer_for("unknown-remote"), None);
+    }
+
     #[test]
     fn bundled_models_json_roundtrips() {
         let file_contents = include_str!("../../models.json");
@@ -7,6 +7,7 @@ use codex_protocol::openai_models::ModelMessages;
 use codex_protocol::openai_models::ModelVisibility;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::openai_models::ReasoningEffortPreset;
+use codex_protocol::openai_models::TokenizerEncoding;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::openai_models::default_input_modalities;
@@ -14,6 +15,8 @@ use codex_protocol::openai_models::default_input_modalities;
 use crate::config::Config;
 use crate::features::Feature;
 use crate::truncate::approx_bytes_for_tokens;
+use codex_utils_tokenizer::EncodingKind;
+use codex_utils_tokenizer::Tokenizer;
 use tracing::warn;
 
 pub const BASE_INSTRUCTIONS: &str = include_str!("../../prompt.md");
@@ -64,6 +67,7 @@ macro_rules! model_info {
             truncation_policy: TruncationPolicyConfig::bytes(10_000),
             supports_parallel_tool_calls: false,
             context_window: Some(CONTEXT_WINDOW_272K),
+            tokenizer: Some(TokenizerEncoding::O200kBase),
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
             experimental_supported_tools: Vec::new(),
@@ -154,6 +158,7 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             base_instructions: BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string(),
             supports_reasoning_summaries: false,
             context_window: Some(16_385),
+            tokenizer: Some(TokenizerEncoding::Cl100kBase),
         )
     } else if slug.starts_with("test-gpt-5") {
         model_info!(
@@ -317,12 +322,24 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
         model_info!(
             slug,
             context_window: None,
+            tokenizer: None,
             supported_reasoning_levels: Vec::new(),
             default_reasoning_level: None
         )
     }
 }
 
+/// Returns the local tokenizer for a model, or `None` when its metadata does
+/// not name an encoding and callers should fall back to the bytes-per-token
+/// heuristic.
+pub(crate) fn tokenizer_for_model(model: &ModelInfo) -> Option<Tokenizer> {
+    let encoding = match model.tokenizer? {
+        TokenizerEncoding::O200kBase => EncodingKind::O200kBase,
+        TokenizerEncoding::Cl100kBase => EncodingKind::Cl100kBase,
+    };
+    Some(Tokenizer::new(encoding))
+}
+
 fn supported_reasoning_level_low_medium_high() -> Vec<ReasoningEffortPreset> {
     vec![
         ReasoningEffortPreset {
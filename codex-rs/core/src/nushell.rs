@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use crate::shell::ShellType;
+use crate::shell::detect_shell_type;
+use crate::simple_shell_script::ScriptSyntax;
+use crate::simple_shell_script::split_plain_commands;
+
+const NUSHELL_SYNTAX: ScriptSyntax = ScriptSyntax {
+    keyword_connectors: &[],
+    rejected_keywords: &[
+        "let", "mut", "const", "def", "if", "else", "for", "while", "loop", "match", "try", "do",
+        "source", "use", "overlay",
+    ],
+    single_quote_escapes: false,
+    interpolating_double_quotes: false,
+};
+
+/// Extract the script from a Nushell invocation such as:
+///
+/// - ["nu", "-c", "ls | length"]
+/// - ["nu", "--login", "-c", "open Cargo.toml"]
+pub fn extract_nushell_command(command: &[String]) -> Option<(&str, &str)> {
+    let (shell, script) = match command {
+        [shell, flag, script] if flag == "-c" => (shell, script),
+        [shell, login, flag, script]
+            if matches!(login.as_str(), "--login" | "-l") && flag == "-c" =>
+        {
+            (shell, script)
+        }
+        _ => return None,
+    };
+    if detect_shell_type(&PathBuf::from(shell)) != Some(ShellType::Nushell) {
+        return None;
+    }
+    Some((shell, script))
+}
+
+/// Returns the sequence of plain commands within a `nu -c "..."` invocation
+/// when the script only contains word-only commands joined by `;` or `|`.
+pub fn parse_nushell_plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    let (_, script) = extract_nushell_command(command)?;
+    split_plain_commands(script, &NUSHELL_SYNTAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn extracts_nushell_scripts() {
+        let command = vec_str(&["/usr/bin/nu", "--login", "-c", "cat 'C:\\dir\\a.txt'"]);
+        assert_eq!(
+            extract_nushell_command(&command),
+            Some(("/usr/bin/nu", "cat 'C:\\dir\\a.txt'"))
+        );
+        assert_eq!(
+            parse_nushell_plain_commands(&command),
+            Some(vec![vec_str(&["cat", "C:\\dir\\a.txt"])])
+        );
+        assert_eq!(
+            extract_nushell_command(&vec_str(&["fish", "-c", "ls"])),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_nushell_bindings_and_closures() {
+        for script in [
+            "let x = 1",
+            "ls | where size > 10kb",
+            "ls | each {|f| $f.name }",
+        ] {
+            let command = vec_str(&["nu", "-c", script]);
+            assert_eq!(parse_nushell_plain_commands(&command), None, "{script}");
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use crate::bash::extract_bash_command;
 use crate::bash::try_parse_shell;
 use crate::bash::try_parse_word_only_commands_sequence;
+use crate::fish::extract_fish_command;
+use crate::fish::parse_fish_plain_commands;
+use crate::nushell::extract_nushell_command;
+use crate::nushell::parse_nushell_plain_commands;
 use crate::powershell::extract_powershell_command;
 use codex_protocol::parse_command::ParsedCommand;
 use shlex::split as shlex_split;
@@ -14,7 +18,10 @@ pub fn shlex_join(tokens: &[String]) -> String {
 
 /// Extracts the shell and script from a command, regardless of platform
 pub fn extract_shell_command(command: &[String]) -> Option<(&str, &str)> {
-    extract_bash_command(command).or_else(|| extract_powershell_command(command))
+    extract_bash_command(command)
+        .or_else(|| extract_powershell_command(command))
+        .or_else(|| extract_fish_command(command))
+        .or_else(|| extract_nushell_command(command))
 }
 
 /// DO NOT REVIEW THIS CODE BY HAND
@@ -301,6 +308,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fish_lc_supports_keyword_connectors() {
+        assert_parsed(
+            &vec_str(&["fish", "-lc", "cd docs; and cat README.md"]),
+            vec![ParsedCommand::Read {
+                cmd: "cat README.md".to_string(),
+                name: "README.md".to_string(),
+                path: PathBuf::from("docs/README.md"),
+            }],
+        );
+        let inner = "cat (git ls-files)";
+        assert_parsed(
+            &vec_str(&["/usr/bin/fish", "-c", inner]),
+            vec![ParsedCommand::Unknown {
+                cmd: inner.to_string(),
+            }],
+        );
+    }
+
+    #[test]
+    fn nushell_supports_plain_commands() {
+        let inner = "cat README.md";
+        assert_parsed(
+            &vec_str(&["nu", "--login", "-c", inner]),
+            vec![ParsedCommand::Read {
+                cmd: inner.to_string(),
+                name: "README.md".to_string(),
+                path: PathBuf::from("README.md"),
+            }],
+        );
+        let inner = "ls | where size > 10kb";
+        assert_parsed(
+            &vec_str(&["nu", "-c", inner]),
+            vec![ParsedCommand::Unknown {
+                cmd: inner.to_string(),
+            }],
+        );
+    }
+
     #[test]
     fn supports_bat() {
         let inner = "bat --theme TwoDark README.md";
@@ -1335,6 +1381,14 @@ pub fn parse_command_impl(command: &[String]) -> Vec<ParsedCommand> {
         return commands;
     }
 
+    if let Some((_, script)) = extract_fish_command(command) {
+        return summarize_script_commands(script, parse_fish_plain_commands(command));
+    }
+
+    if let Some((_, script)) = extract_nushell_command(command) {
+        return summarize_script_commands(script, parse_nushell_plain_commands(command));
+    }
+
     if let Some((_, script)) = extract_powershell_command(command) {
         return vec![ParsedCommand::Unknown {
             cmd: script.to_string(),
@@ -1876,9 +1930,18 @@ fn parse_find_query_and_path(tail: &[String]) -> (Option<String>, Option<String>
 fn parse_shell_lc_commands(original: &[String]) -> Option<Vec<ParsedCommand>> {
     // Only handle bash/zsh here; PowerShell is stripped separately without bash parsing.
     let (_, script) = extract_bash_command(original)?;
+    let all_commands = try_parse_shell(script)
+        .and_then(|tree| try_parse_word_only_commands_sequence(&tree, script));
+    Some(summarize_script_commands(script, all_commands))
+}
 
-    if let Some(tree) = try_parse_shell(script)
-        && let Some(all_commands) = try_parse_word_only_commands_sequence(&tree, script)
+/// Summarizes the plain commands parsed out of a shell script, or reports the
+/// whole script as unknown when it could not be split into plain commands.
+fn summarize_script_commands(
+    script: &str,
+    all_commands: Option<Vec<Vec<String>>>,
+) -> Vec<ParsedCommand> {
+    if let Some(all_commands) = all_commands
         && !all_commands.is_empty()
     {
         let script_tokens = shlex_split(script).unwrap_or_else(|| vec![script.to_string()]);
@@ -1889,9 +1952,9 @@ fn parse_shell_lc_commands(original: &[String]) -> Option<Vec<ParsedCommand>> {
         // Commands arrive in source order; drop formatting helpers while preserving it.
         let filtered_commands = drop_small_formatting_commands(all_commands);
         if filtered_commands.is_empty() {
-            return Some(vec![ParsedCommand::Unknown {
+            return vec![ParsedCommand::Unknown {
                 cmd: script.to_string(),
-            }]);
+            }];
         }
         // Build parsed commands, tracking `cd` segments to compute effective file paths.
         let mut commands: Vec<ParsedCommand> = Vec::new();
@@ -1996,11 +2059,11 @@ fn parse_shell_lc_commands(original: &[String]) -> Option<Vec<ParsedCommand>> {
                 })
                 .collect();
         }
-        return Some(commands);
+        return commands;
     }
-    Some(vec![ParsedCommand::Unknown {
+    vec![ParsedCommand::Unknown {
         cmd: script.to_string(),
-    }])
+    }]
 }
 
 /// Return true if this looks like a small formatting helper in a pipeline.
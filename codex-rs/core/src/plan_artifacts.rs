@@ -0,0 +1,621 @@
+//! Proposed plans saved to disk.
+//!
+//! Plan mode writes each `<proposed_plan>` to a markdown file so the plan
+//! outlives the session, can be edited outside Codex, and can be executed one
+//! step at a time. The file is the only state: steps are the plan's top-level
+//! numbered items (or top-level bullets when it has none), a finished step is
+//! marked `[x]`, and the ghost commit taken after it is recorded on the same
+//! line as `<!-- checkpoint: <id> -->`. A later session resumes at the first
+//! step that is not marked, and restoring a step's checkpoint rolls the
+//! working tree and the plan back to the moment that step finished.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use chrono::DateTime;
+use chrono::Local;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostSnapshotConfig;
+use codex_git::GitToolingError;
+use codex_git::create_ghost_commit;
+use codex_git::restore_to_commit;
+use tracing::warn;
+
+use crate::config::types::PlanArtifacts;
+use crate::git_info::get_git_repo_root;
+use crate::path_utils::write_atomically;
+
+/// Directory, below `.codex/` in a repository or below `CODEX_HOME`, that
+/// holds saved plans.
+pub const PLANS_DIR: &str = "plans";
+
+const CHECKPOINT_PREFIX: &str = "<!-- checkpoint: ";
+const CHECKPOINT_SUFFIX: &str = " -->";
+const MAX_SLUG_LEN: usize = 48;
+
+/// One executable step of a saved plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanStep {
+    /// 1-based position among the plan's steps.
+    pub number: usize,
+    /// The item text, including indented continuation lines, without the list
+    /// marker, checkbox or checkpoint comment.
+    pub text: String,
+    pub done: bool,
+    /// Ghost commit recorded after the step finished.
+    pub checkpoint: Option<String>,
+    line: usize,
+}
+
+impl PlanStep {
+    /// First line of the step, for one-line listings.
+    pub fn summary(&self) -> &str {
+        self.text.lines().next().unwrap_or_default()
+    }
+}
+
+/// A plan file and the steps parsed from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedPlan {
+    pub path: PathBuf,
+    pub title: String,
+    pub steps: Vec<PlanStep>,
+}
+
+impl SavedPlan {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let markdown = std::fs::read_to_string(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            title: plan_title(&markdown),
+            steps: parse_steps(&markdown),
+        })
+    }
+
+    /// The first step that has not been marked done.
+    pub fn next_step(&self) -> Option<&PlanStep> {
+        self.steps.iter().find(|step| !step.done)
+    }
+
+    pub fn completed_steps(&self) -> usize {
+        self.steps.iter().filter(|step| step.done).count()
+    }
+}
+
+/// Where plans are saved for a session in `cwd`, or `None` when saving is
+/// disabled. `repo` falls back to `CODEX_HOME` outside of git.
+pub fn plans_dir(location: PlanArtifacts, cwd: &Path, codex_home: &Path) -> Option<PathBuf> {
+    match location {
+        PlanArtifacts::Repo => Some(
+            get_git_repo_root(cwd)
+                .map(|root| root.join(".codex").join(PLANS_DIR))
+                .unwrap_or_else(|| codex_home.join(PLANS_DIR)),
+        ),
+        PlanArtifacts::CodexHome => Some(codex_home.join(PLANS_DIR)),
+        PlanArtifacts::None => None,
+    }
+}
+
+/// Writes `plan` to a new file in `dir` named after its time of creation and
+/// title, and returns the path. Plans saved in the same second get a numeric
+/// suffix instead of replacing each other. When `dir` is inside a git
+/// repository it is added to the repository's `info/exclude` so saved plans
+/// stay out of `git status`.
+pub fn save_plan(dir: &Path, plan: &str, now: DateTime<Local>) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    if let Err(err) = exclude_from_git(dir) {
+        warn!(error = %err, dir = %dir.display(), "failed to exclude saved plans from git");
+    }
+    let stem = format!(
+        "{}-{}",
+        now.format("%Y%m%d-%H%M%S"),
+        slug(&plan_title(plan))
+    );
+    let mut attempt = 1;
+    let path = loop {
+        let file_name = if attempt == 1 {
+            format!("{stem}.md")
+        } else {
+            format!("{stem}-{attempt}.md")
+        };
+        let path = dir.join(file_name);
+        // Reserve the name so a concurrent save cannot pick it too.
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => break path,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(err) => return Err(err),
+        }
+    };
+    let mut contents = plan.trim().to_string();
+    contents.push('\n');
+    write_atomically(&path, &contents)?;
+    Ok(path)
+}
+
+/// Plans saved in `dir`, newest first. A missing directory has no plans, and
+/// files that cannot be read are skipped.
+pub fn list_saved_plans(dir: &Path) -> io::Result<Vec<SavedPlan>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+    paths.reverse();
+    Ok(paths
+        .iter()
+        .filter_map(|path| match SavedPlan::load(path) {
+            Ok(plan) => Some(plan),
+            Err(err) => {
+                warn!(error = %err, path = %path.display(), "skipping unreadable saved plan");
+                None
+            }
+        })
+        .collect())
+}
+
+/// Adds `dir` to the `info/exclude` file of the git repository containing
+/// it, unless it is already listed there.
+fn exclude_from_git(dir: &Path) -> io::Result<()> {
+    let Some(root) = get_git_repo_root(dir) else {
+        return Ok(());
+    };
+    let Ok(relative) = dir.strip_prefix(&root) else {
+        return Ok(());
+    };
+    let components: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if components.is_empty() {
+        return Ok(());
+    }
+    let pattern = format!("/{}/", components.join("/"));
+
+    // Linked worktrees share the exclude file of the main repository.
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "info/exclude"])
+        .current_dir(&root)
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git rev-parse --git-path info/exclude failed in {}",
+            root.display()
+        )));
+    }
+    let exclude = root.join(String::from_utf8_lossy(&output.stdout).trim());
+
+    let existing = match std::fs::read_to_string(&exclude) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+    let mut updated = existing;
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(&pattern);
+    updated.push('\n');
+    write_atomically(&exclude, &updated)
+}
+
+/// Marks step `number` of the plan at `path` done and records `checkpoint`.
+pub fn complete_step(path: &Path, number: usize, checkpoint: Option<&str>) -> io::Result<()> {
+    let markdown = std::fs::read_to_string(path)?;
+    let updated = mark_step_done(&markdown, number, checkpoint).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no step {number}", path.display()),
+        )
+    })?;
+    write_atomically(path, &updated)
+}
+
+/// Snapshots the working tree after step `number` as a ghost commit and
+/// returns its id, or `None` outside of git.
+pub fn create_step_checkpoint(
+    cwd: &Path,
+    ghost_snapshot: &GhostSnapshotConfig,
+    number: usize,
+) -> Result<Option<String>, GitToolingError> {
+    if get_git_repo_root(cwd).is_none() {
+        return Ok(None);
+    }
+    let message = format!("codex plan checkpoint: step {number}");
+    let options = CreateGhostCommitOptions::new(cwd)
+        .message(&message)
+        .ghost_snapshot(ghost_snapshot.clone());
+    create_ghost_commit(&options).map(|commit| Some(commit.id().to_string()))
+}
+
+/// Restores the working tree to the checkpoint recorded for step `number` of
+/// the plan at `path` and marks the later steps not done, so the plan resumes
+/// right after that step. Returns the restored checkpoint.
+pub fn restore_step_checkpoint(path: &Path, cwd: &Path, number: usize) -> io::Result<String> {
+    let markdown = std::fs::read_to_string(path)?;
+    let checkpoint = parse_steps(&markdown)
+        .into_iter()
+        .find(|step| step.number == number)
+        .and_then(|step| step.checkpoint)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("step {number} of {} has no checkpoint", path.display()),
+            )
+        })?;
+    restore_to_commit(cwd, &checkpoint).map_err(io::Error::other)?;
+    write_atomically(path, &reopen_steps_after(&markdown, number))?;
+    Ok(checkpoint)
+}
+
+/// The first `#` heading, or the first non-empty line, of a plan.
+pub fn plan_title(markdown: &str) -> String {
+    let heading = markdown
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(str::trim)
+        .filter(|title| !title.is_empty());
+    heading
+        .or_else(|| {
+            markdown
+                .lines()
+                .map(|line| line.trim_start_matches('#').trim())
+                .find(|line| !line.is_empty())
+        })
+        .unwrap_or("Plan")
+        .to_string()
+}
+
+fn slug(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        if slug.len() + word.len() + 1 > MAX_SLUG_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    if slug.is_empty() {
+        slug.push_str("plan");
+    }
+    slug
+}
+
+/// A top-level list item split into its parts.
+struct ListItem<'a> {
+    marker: &'a str,
+    ordered: bool,
+    done: bool,
+    content: &'a str,
+    checkpoint: Option<&'a str>,
+}
+
+fn parse_list_item(line: &str) -> Option<ListItem<'_>> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let (marker_len, ordered) = if digits > 0 {
+        match line[digits..].chars().next() {
+            Some('.' | ')') => (digits + 1, true),
+            _ => return None,
+        }
+    } else {
+        match line.chars().next() {
+            Some('-' | '*' | '+') => (1, false),
+            _ => return None,
+        }
+    };
+    let rest = &line[marker_len..];
+    let content_start = rest.len() - rest.trim_start().len();
+    if content_start == 0 {
+        return None;
+    }
+    let marker = &line[..marker_len + content_start];
+    let mut content = rest.trim();
+
+    let mut done = false;
+    for (checkbox, checked) in [("[ ]", false), ("[x]", true), ("[X]", true)] {
+        if let Some(after) = content.strip_prefix(checkbox) {
+            done = checked;
+            content = after.trim_start();
+            break;
+        }
+    }
+
+    let mut checkpoint = None;
+    if let Some(before) = content.strip_suffix(CHECKPOINT_SUFFIX)
+        && let Some((text, id)) = before.rsplit_once(CHECKPOINT_PREFIX)
+    {
+        checkpoint = Some(id.trim());
+        content = text.trim_end();
+    }
+
+    Some(ListItem {
+        marker,
+        ordered,
+        done,
+        content,
+        checkpoint,
+    })
+}
+
+/// Steps of a plan: its top-level numbered items, or its top-level bullets
+/// when there are none. Items inside fenced code blocks are ignored.
+pub fn parse_steps(markdown: &str) -> Vec<PlanStep> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut in_fence = false;
+    let mut items = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some(item) = parse_list_item(line) {
+            items.push((index, item));
+        }
+    }
+    let ordered = items.iter().any(|(_, item)| item.ordered);
+
+    items
+        .into_iter()
+        .filter(|(_, item)| item.ordered == ordered)
+        .enumerate()
+        .map(|(position, (line, item))| {
+            let mut text = item.content.to_string();
+            for continuation in lines[line + 1..]
+                .iter()
+                .take_while(|next| next.starts_with([' ', '\t']) && !next.trim().is_empty())
+            {
+                text.push('\n');
+                text.push_str(continuation.trim());
+            }
+            PlanStep {
+                number: position + 1,
+                text,
+                done: item.done,
+                checkpoint: item.checkpoint.map(str::to_string),
+                line,
+            }
+        })
+        .collect()
+}
+
+/// `markdown` with step `number` marked done and `checkpoint` recorded, or
+/// `None` when there is no such step.
+pub fn mark_step_done(markdown: &str, number: usize, checkpoint: Option<&str>) -> Option<String> {
+    let step = parse_steps(markdown)
+        .into_iter()
+        .find(|step| step.number == number)?;
+    let mut lines: Vec<String> = markdown.lines().map(str::to_string).collect();
+    let original = lines.get(step.line)?;
+    let item = parse_list_item(original)?;
+    let mut updated = format!("{}[x] {}", item.marker, item.content);
+    if let Some(checkpoint) = checkpoint.or(item.checkpoint) {
+        updated.push_str(&format!(
+            " {CHECKPOINT_PREFIX}{checkpoint}{CHECKPOINT_SUFFIX}"
+        ));
+    }
+    lines[step.line] = updated;
+    let mut out = lines.join("\n");
+    if markdown.ends_with('\n') {
+        out.push('\n');
+    }
+    Some(out)
+}
+
+/// `markdown` with every step after `number` unchecked and its checkpoint
+/// dropped.
+pub fn reopen_steps_after(markdown: &str, number: usize) -> String {
+    let mut lines: Vec<String> = markdown.lines().map(str::to_string).collect();
+    for step in parse_steps(markdown)
+        .into_iter()
+        .filter(|step| step.number > number && (step.done || step.checkpoint.is_some()))
+    {
+        let Some(reopened) = lines
+            .get(step.line)
+            .and_then(|line| parse_list_item(line))
+            .map(|item| format!("{}[ ] {}", item.marker, item.content))
+        else {
+            continue;
+        };
+        lines[step.line] = reopened;
+    }
+    let mut out = lines.join("\n");
+    if markdown.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const PLAN: &str = "# Add a cache layer\n\nSome context.\n\n1. Add the `Cache` type\n   with an LRU policy\n2. [x] Wire it into the client <!-- checkpoint: abc123 -->\n3. Update the docs\n\n```\n1. not a step\n```\n\n- a note, not a step\n";
+
+    #[test]
+    fn parses_numbered_steps_and_their_progress() {
+        let steps = parse_steps(PLAN);
+        let summary: Vec<(usize, &str, bool, Option<&str>)> = steps
+            .iter()
+            .map(|step| {
+                (
+                    step.number,
+                    step.text.as_str(),
+                    step.done,
+                    step.checkpoint.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "Add the `Cache` type\nwith an LRU policy", false, None),
+                (2, "Wire it into the client", true, Some("abc123")),
+                (3, "Update the docs", false, None),
+            ]
+        );
+        assert_eq!(plan_title(PLAN), "Add a cache layer");
+    }
+
+    #[test]
+    fn falls_back_to_bullets_when_there_are_no_numbered_items() {
+        let steps = parse_steps("Plan\n\n- [ ] first\n- second\n  - nested detail\n");
+        let texts: Vec<&str> = steps.iter().map(PlanStep::summary).collect();
+        assert_eq!(texts, vec!["first", "second"]);
+        assert_eq!(steps[1].text, "second\n- nested detail");
+    }
+
+    #[test]
+    fn marking_a_step_done_records_its_checkpoint() {
+        let updated = mark_step_done(PLAN, 3, Some("def456")).expect("step 3 exists");
+        assert!(updated.contains("\n3. [x] Update the docs <!-- checkpoint: def456 -->\n"));
+
+        let updated = mark_step_done(&updated, 1, None).expect("step 1 exists");
+        assert!(updated.contains("\n1. [x] Add the `Cache` type\n   with an LRU policy\n"));
+        assert!(parse_steps(&updated).iter().all(|step| step.done));
+        assert_eq!(mark_step_done(PLAN, 4, None), None);
+    }
+
+    #[test]
+    fn saved_plans_resume_at_the_first_unfinished_step() {
+        let dir = tempdir().expect("tempdir");
+        let now = Local::now();
+        let path = save_plan(dir.path(), PLAN, now).expect("save plan");
+        assert_eq!(
+            path.file_name().and_then(|name| name.to_str()),
+            Some(format!("{}-add-a-cache-layer.md", now.format("%Y%m%d-%H%M%S")).as_str())
+        );
+
+        complete_step(&path, 1, Some("aaa111")).expect("complete step 1");
+        let plans = list_saved_plans(dir.path()).expect("list plans");
+        assert_eq!(plans.len(), 1);
+        let plan = &plans[0];
+        assert_eq!(plan.completed_steps(), 2);
+        assert_eq!(plan.next_step().map(|step| step.number), Some(3));
+        assert_eq!(plan.steps[0].checkpoint.as_deref(), Some("aaa111"));
+
+        assert_eq!(
+            list_saved_plans(&dir.path().join("missing")).expect("missing dir"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn plans_saved_in_the_same_second_get_distinct_files() {
+        let dir = tempdir().expect("tempdir");
+        let now = Local::now();
+        let first = save_plan(dir.path(), PLAN, now).expect("save first plan");
+        let second = save_plan(dir.path(), PLAN, now).expect("save second plan");
+        assert_ne!(first, second);
+        assert_eq!(
+            second.file_name().and_then(|name| name.to_str()),
+            Some(format!("{}-add-a-cache-layer-2.md", now.format("%Y%m%d-%H%M%S")).as_str())
+        );
+        assert_eq!(list_saved_plans(dir.path()).expect("list plans").len(), 2);
+    }
+
+    #[test]
+    fn listing_skips_unreadable_plans() {
+        let dir = tempdir().expect("tempdir");
+        save_plan(dir.path(), PLAN, Local::now()).expect("save plan");
+        std::fs::write(dir.path().join("broken.md"), [0xff, 0xfe]).expect("write broken plan");
+        let titles: Vec<String> = list_saved_plans(dir.path())
+            .expect("list plans")
+            .into_iter()
+            .map(|plan| plan.title)
+            .collect();
+        assert_eq!(titles, vec!["Add a cache layer".to_string()]);
+    }
+
+    #[test]
+    fn saving_in_a_repository_excludes_the_plans_dir_from_git() {
+        let repo = tempdir().expect("tempdir");
+        run_git(repo.path(), &["init"]);
+        let dir = repo.path().join(".codex").join(PLANS_DIR);
+        save_plan(&dir, PLAN, Local::now()).expect("save plan");
+        save_plan(&dir, PLAN, Local::now()).expect("save plan again");
+
+        let exclude =
+            std::fs::read_to_string(repo.path().join(".git/info/exclude")).expect("read exclude");
+        assert_eq!(
+            exclude
+                .lines()
+                .filter(|line| *line == "/.codex/plans/")
+                .count(),
+            1
+        );
+        assert_eq!(run_git(repo.path(), &["status", "--porcelain"]), "");
+    }
+
+    #[test]
+    fn restoring_a_checkpoint_rolls_back_the_tree_and_later_steps() {
+        let repo = tempdir().expect("tempdir");
+        let cwd = repo.path();
+        run_git(cwd, &["init"]);
+        std::fs::write(cwd.join("cache.rs"), "struct Cache;\n").expect("write file");
+        let path =
+            save_plan(&cwd.join(".codex").join(PLANS_DIR), PLAN, Local::now()).expect("save plan");
+        let ghost_snapshot = GhostSnapshotConfig::default();
+        let checkpoint = create_step_checkpoint(cwd, &ghost_snapshot, 1)
+            .expect("checkpoint step 1")
+            .expect("inside git");
+        complete_step(&path, 1, Some(&checkpoint)).expect("complete step 1");
+
+        std::fs::write(cwd.join("cache.rs"), "struct Cache { lru: bool }\n").expect("edit file");
+        complete_step(&path, 3, Some("def456")).expect("complete step 3");
+
+        assert_eq!(
+            restore_step_checkpoint(&path, cwd, 1).expect("restore step 1"),
+            checkpoint
+        );
+        assert_eq!(
+            std::fs::read_to_string(cwd.join("cache.rs")).expect("read file"),
+            "struct Cache;\n"
+        );
+        let plan = SavedPlan::load(&path).expect("load plan");
+        let progress: Vec<(bool, Option<&str>)> = plan
+            .steps
+            .iter()
+            .map(|step| (step.done, step.checkpoint.as_deref()))
+            .collect();
+        assert_eq!(
+            progress,
+            vec![
+                (true, Some(checkpoint.as_str())),
+                (false, None),
+                (false, None)
+            ]
+        );
+        assert!(restore_step_checkpoint(&path, cwd, 2).is_err());
+    }
+
+    fn run_git(cwd: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .output()
+            .expect("run git");
+        assert!(output.status.success(), "git {args:?} failed: {output:?}");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+}
@@ -0,0 +1,294 @@
+//! Baselines of already-triaged review findings.
+//!
+//! Repeated `codex review` runs in CI keep a baseline file listing the
+//! findings a team has accepted. Each finding is fingerprinted by its path
+//! relative to the repository root, the code it points at and its title, so a
+//! finding still matches after unrelated edits move it to other lines.
+//! Findings whose fingerprint is in the baseline are dropped from the review
+//! output, leaving only new ones.
+
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use codex_git::repository_root_or_self;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::protocol::ReviewFinding;
+use crate::protocol::ReviewOutputEvent;
+use crate::review_export::relative_path;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Findings with more lines than this are fingerprinted by their first lines
+/// only, so that a vague range over a whole file stays cheap to hash.
+const MAX_CONTEXT_LINES: usize = 50;
+
+/// Accepted review findings, as stored in a baseline file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewBaseline {
+    pub version: u32,
+    #[serde(default)]
+    pub findings: Vec<BaselineFinding>,
+}
+
+/// A single accepted finding. Only `fingerprint` is used for matching; the
+/// other fields keep the file readable when reviewing baseline changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineFinding {
+    pub fingerprint: String,
+    pub title: String,
+    pub path: String,
+    pub priority: i32,
+}
+
+impl Default for ReviewBaseline {
+    fn default() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            findings: Vec::new(),
+        }
+    }
+}
+
+impl ReviewBaseline {
+    /// Reads a baseline file. A missing file is an empty baseline so that the
+    /// first CI run can create it.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                anyhow::bail!("failed to read review baseline {}: {err}", path.display())
+            }
+        };
+        let baseline: Self = serde_json::from_str(&contents).map_err(|err| {
+            anyhow::anyhow!("failed to parse review baseline {}: {err}", path.display())
+        })?;
+        if baseline.version != BASELINE_VERSION {
+            anyhow::bail!(
+                "unsupported review baseline version {} in {}",
+                baseline.version,
+                path.display()
+            );
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        contents.push('\n');
+        std::fs::write(path, contents)
+    }
+
+    /// Accepts `findings`, skipping those already in the baseline. Paths are
+    /// fingerprinted relative to the root of the repository containing `cwd`.
+    pub fn add_findings(&mut self, findings: &[ReviewFinding], cwd: &Path) {
+        let root = repository_root_or_self(cwd);
+        let root = root.as_path();
+        let mut known: HashSet<String> = self
+            .findings
+            .iter()
+            .map(|finding| finding.fingerprint.clone())
+            .collect();
+        for finding in findings {
+            let fingerprint = finding_fingerprint(finding, root);
+            if known.insert(fingerprint.clone()) {
+                self.findings.push(BaselineFinding {
+                    fingerprint,
+                    title: finding.title.clone(),
+                    path: relative_path(finding, root),
+                    priority: finding.priority,
+                });
+            }
+        }
+    }
+
+    /// Drops the findings of `output` that are in the baseline and returns how
+    /// many were dropped. Like [`Self::add_findings`], paths are fingerprinted
+    /// relative to the root of the repository containing `cwd`.
+    pub fn suppress(&self, output: &mut ReviewOutputEvent, cwd: &Path) -> usize {
+        let root = repository_root_or_self(cwd);
+        let root = root.as_path();
+        let known: HashSet<&str> = self
+            .findings
+            .iter()
+            .map(|finding| finding.fingerprint.as_str())
+            .collect();
+        let before = output.findings.len();
+        output
+            .findings
+            .retain(|finding| !known.contains(finding_fingerprint(finding, root).as_str()));
+        before - output.findings.len()
+    }
+}
+
+/// Fingerprints a finding by its path relative to `root`, the trimmed,
+/// non-blank lines of code it covers, and its title without the priority tag.
+/// Line numbers are deliberately left out.
+pub fn finding_fingerprint(finding: &ReviewFinding, root: &Path) -> String {
+    let path = relative_path(finding, root);
+    let mut hasher = Sha256::new();
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    for line in finding_context(finding, root) {
+        hasher.update(line.as_bytes());
+        hasher.update([b'\n']);
+    }
+    hasher.update([0]);
+    hasher.update(normalize_title(&finding.title).as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn finding_context(finding: &ReviewFinding, root: &Path) -> Vec<String> {
+    let location = &finding.code_location;
+    let Ok(contents) = std::fs::read_to_string(root.join(&location.absolute_file_path)) else {
+        return Vec::new();
+    };
+    let start = location.line_range.start.max(1) as usize;
+    let end = (location.line_range.end as usize).max(start);
+    contents
+        .lines()
+        .skip(start - 1)
+        .take((end - start + 1).min(MAX_CONTEXT_LINES))
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Strips a leading `[P1]`-style priority tag and collapses whitespace, so
+/// that a re-prioritized finding keeps its fingerprint.
+fn normalize_title(title: &str) -> String {
+    let title = title.trim();
+    let title = match title
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        Some((tag, rest))
+            if tag.starts_with('P') && tag[1..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            rest
+        }
+        _ => title,
+    };
+    title.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn finding(root: &Path, title: &str, start: u32, end: u32) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: "body".to_string(),
+            confidence_score: 0.5,
+            priority: 1,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: root.join("src/lib.rs"),
+                line_range: ReviewLineRange { start, end },
+            },
+        }
+    }
+
+    #[test]
+    fn fingerprint_survives_moved_lines_and_priority_changes() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "fn a() {}\nfn b() {\n    bad();\n}\n",
+        )?;
+        let before = finding_fingerprint(&finding(root, "[P1] Calls bad()", 2, 3), root);
+
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// header\n\nfn a() {}\nfn b() {\n  bad();\n}\n",
+        )?;
+        let after = finding(root, "[P2]  Calls bad()", 4, 5);
+        let elsewhere = finding(root, "[P1] Calls bad()", 3, 3);
+
+        assert_eq!(before, finding_fingerprint(&after, root));
+        assert_ne!(
+            finding_fingerprint(&after, root),
+            finding_fingerprint(&elsewhere, root)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suppresses_baseline_findings_and_round_trips() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::write(root.join("src/lib.rs"), "one\ntwo\nthree\n")?;
+        let accepted = finding(root, "Accepted", 1, 1);
+        let fresh = finding(root, "Fresh", 2, 2);
+
+        let mut baseline = ReviewBaseline::default();
+        baseline.add_findings(&[accepted.clone(), accepted.clone()], root);
+        assert_eq!(baseline.findings.len(), 1);
+        assert_eq!(baseline.findings[0].path, "src/lib.rs");
+
+        let path = root.join("ci/baseline.json");
+        baseline.save(&path)?;
+        let loaded = ReviewBaseline::load(&path)?;
+        assert_eq!(loaded, baseline);
+
+        let mut output = ReviewOutputEvent {
+            findings: vec![accepted, fresh.clone()],
+            ..Default::default()
+        };
+        assert_eq!(loaded.suppress(&mut output, root), 1);
+        assert_eq!(output.findings, vec![fresh]);
+        Ok(())
+    }
+
+    #[test]
+    fn fingerprints_are_relative_to_the_repository_root() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        let status = std::process::Command::new("git")
+            .arg("init")
+            .arg(root)
+            .output()?
+            .status;
+        assert!(status.success(), "git init failed");
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::write(root.join("src/lib.rs"), "one\ntwo\n")?;
+        let accepted = finding(root, "Accepted", 1, 1);
+
+        let mut baseline = ReviewBaseline::default();
+        baseline.add_findings(std::slice::from_ref(&accepted), &root.join("src"));
+        assert_eq!(baseline.findings[0].path, "src/lib.rs");
+
+        let mut output = ReviewOutputEvent {
+            findings: vec![accepted],
+            ..Default::default()
+        };
+        assert_eq!(baseline.suppress(&mut output, root), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_baseline_is_empty() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let baseline = ReviewBaseline::load(&dir.path().join("missing.json"))?;
+        assert_eq!(baseline, ReviewBaseline::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,424 @@
+//! Machine-readable exports of review findings.
+//!
+//! `codex review` results can be written as SARIF 2.1.0 (for code-scanning
+//! dashboards and inline PR annotations) or as JUnit XML (for CI test
+//! reporters). File paths are reported relative to the repository root when
+//! the finding lies inside it, and as absolute paths (`file://` URIs in SARIF)
+//! otherwise.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_git::canonicalize_lenient;
+use percent_encoding::AsciiSet;
+use percent_encoding::CONTROLS;
+use percent_encoding::utf8_percent_encode;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+
+use crate::protocol::ReviewFinding;
+use crate::protocol::ReviewOutputEvent;
+
+const TOOL_NAME: &str = "codex";
+const TOOL_INFORMATION_URI: &str = "https://github.com/openai/codex";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Characters escaped in the path of a SARIF artifact URI. Path separators and
+/// the drive colon of Windows paths are kept.
+const URI_PATH_ESCAPES: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Short descriptions of the priority levels used by the review prompt.
+const PRIORITY_DESCRIPTIONS: [&str; 4] = [
+    "Drop everything to fix. Blocking release, operations, or major usage.",
+    "Urgent. Should be addressed in the next cycle.",
+    "Normal. To be fixed eventually.",
+    "Low. Nice to have.",
+];
+
+fn priority_rule_id(priority: i32) -> String {
+    format!("P{}", priority.clamp(0, 3))
+}
+
+fn sarif_level(priority: i32) -> &'static str {
+    match priority {
+        i32::MIN..=1 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
+
+/// Returns the finding's path relative to `root`, or its absolute path when it
+/// lies outside of it. Both are canonicalized first so that symlinked or
+/// `..`-laden spellings of the same file agree.
+pub(crate) fn relative_path(finding: &ReviewFinding, root: &Path) -> String {
+    match root_relative_path(finding, root) {
+        Ok(relative) => relative,
+        Err(absolute) => slash_path(&absolute),
+    }
+}
+
+/// Returns the finding's path relative to `root` with `/` separators, or its
+/// canonical absolute path when it lies outside of `root`.
+fn root_relative_path(finding: &ReviewFinding, root: &Path) -> Result<String, PathBuf> {
+    let path = canonicalize_lenient(&finding.code_location.absolute_file_path);
+    let root = canonicalize_lenient(root);
+    match path.strip_prefix(&root) {
+        Ok(relative) => Ok(slash_path(relative)),
+        Err(_) => Err(path),
+    }
+}
+
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// SARIF artifact location of a finding: relative to `%SRCROOT%` inside the
+/// root, and an absolute `file://` URI outside of it.
+fn artifact_location(finding: &ReviewFinding, root: &Path) -> JsonValue {
+    match root_relative_path(finding, root) {
+        Ok(relative) => json!({
+            "uri": utf8_percent_encode(&relative, URI_PATH_ESCAPES).to_string(),
+            "uriBaseId": "%SRCROOT%",
+        }),
+        Err(absolute) => json!({ "uri": file_uri(&absolute, false) }),
+    }
+}
+
+/// Returns the finding's line range, clamped so that it is 1-based and
+/// non-empty.
+fn line_range(finding: &ReviewFinding) -> (u32, u32) {
+    let range = &finding.code_location.line_range;
+    let start = range.start.max(1);
+    (start, range.end.max(start))
+}
+
+/// Render review findings as a SARIF 2.1.0 log with one result per finding.
+///
+/// Each priority level (P0–P3) is a rule; P0 and P1 map to `error`, P2 to
+/// `warning` and P3 to `note`. The confidence score is kept in the result's
+/// properties.
+pub fn render_review_sarif(output: &ReviewOutputEvent, root: &Path) -> JsonValue {
+    let rules: Vec<JsonValue> = PRIORITY_DESCRIPTIONS
+        .iter()
+        .enumerate()
+        .map(|(priority, description)| {
+            let priority = priority as i32;
+            json!({
+                "id": priority_rule_id(priority),
+                "name": format!("ReviewFindingP{priority}"),
+                "shortDescription": { "text": format!("P{priority} review finding") },
+                "fullDescription": { "text": description },
+                "defaultConfiguration": { "level": sarif_level(priority) },
+            })
+        })
+        .collect();
+
+    let results: Vec<JsonValue> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let (start, end) = line_range(finding);
+            json!({
+                "ruleId": priority_rule_id(finding.priority),
+                "level": sarif_level(finding.priority),
+                "message": {
+                    "text": format!("{}\n\n{}", finding.title, finding.body.trim_end()),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": artifact_location(finding, root),
+                        "region": { "startLine": start, "endLine": end },
+                    },
+                }],
+                "properties": {
+                    "title": finding.title,
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_INFORMATION_URI,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": {
+                "%SRCROOT%": { "uri": file_uri(&canonicalize_lenient(root), true) },
+            },
+            "results": results,
+            "properties": {
+                "overallCorrectness": output.overall_correctness,
+                "overallExplanation": output.overall_explanation,
+                "overallConfidence": output.overall_confidence_score,
+            },
+        }],
+    })
+}
+
+/// Percent-encoded `file://` URI of an absolute path. Directory URIs end with
+/// a `/` so that relative URIs resolve below them.
+fn file_uri(path: &Path, directory: bool) -> String {
+    let path = slash_path(path);
+    let path = utf8_percent_encode(path.trim_end_matches('/'), URI_PATH_ESCAPES);
+    let separator = if directory { "/" } else { "" };
+    if path.to_string().starts_with('/') {
+        format!("file://{path}{separator}")
+    } else {
+        format!("file:///{path}{separator}")
+    }
+}
+
+/// Render review findings as a JUnit XML report.
+///
+/// Every finding becomes a failed test case named after its title, with the
+/// body, location, priority and confidence in the failure text. A review
+/// without findings produces a single passing test case so that CI reporters
+/// still record the run.
+pub fn render_review_junit(output: &ReviewOutputEvent, root: &Path) -> String {
+    let failures = output.findings.len();
+    let tests = failures.max(1);
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"codex review\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"codex review\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">\n"
+    ));
+    xml.push_str("    <properties>\n");
+    for (name, value) in [
+        ("overall_correctness", output.overall_correctness.clone()),
+        (
+            "overall_confidence",
+            output.overall_confidence_score.to_string(),
+        ),
+    ] {
+        xml.push_str(&format!(
+            "      <property name=\"{name}\" value=\"{}\"/>\n",
+            escape_xml(&value)
+        ));
+    }
+    xml.push_str("    </properties>\n");
+
+    if output.findings.is_empty() {
+        xml.push_str("    <testcase name=\"review\" classname=\"codex.review\">\n");
+        let explanation = output.overall_explanation.trim();
+        if !explanation.is_empty() {
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                escape_xml(explanation)
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    for finding in &output.findings {
+        let path = relative_path(finding, root);
+        let (start, end) = line_range(finding);
+        let priority = priority_rule_id(finding.priority);
+        let details = format!(
+            "{}\n\n{path}:{start}-{end}\npriority: {priority}\nconfidence: {}",
+            finding.body.trim_end(),
+            finding.confidence_score
+        );
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{start}\">\n",
+            escape_xml(&finding.title),
+            escape_xml(&path),
+            escape_xml(&path),
+        ));
+        xml.push_str(&format!(
+            "      <failure message=\"{}\" type=\"{priority}\">{}</failure>\n",
+            escape_xml(&finding.title),
+            escape_xml(&details),
+        ));
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not allowed in XML 1.0.
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            ch if ch.is_control() => {}
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+
+    fn review_output() -> ReviewOutputEvent {
+        ReviewOutputEvent {
+            findings: vec![
+                ReviewFinding {
+                    title: "[P1] Off-by-one in <range> check".to_string(),
+                    body: "The loop skips the last element & never ends.\n".to_string(),
+                    confidence_score: 0.5,
+                    priority: 1,
+                    code_location: ReviewCodeLocation {
+                        absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                        line_range: ReviewLineRange { start: 10, end: 12 },
+                    },
+                },
+                ReviewFinding {
+                    title: "[P3] Typo".to_string(),
+                    body: "Misspelled word.".to_string(),
+                    confidence_score: 0.25,
+                    priority: 3,
+                    code_location: ReviewCodeLocation {
+                        absolute_file_path: PathBuf::from("/elsewhere/notes.md"),
+                        line_range: ReviewLineRange { start: 0, end: 0 },
+                    },
+                },
+            ],
+            overall_correctness: "patch is incorrect".to_string(),
+            overall_explanation: "One bug.".to_string(),
+            overall_confidence_score: 0.75,
+        }
+    }
+
+    #[test]
+    fn sarif_reports_one_result_per_finding() {
+        let sarif = render_review_sarif(&review_output(), Path::new("/repo"));
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().map(Vec::len),
+            Some(4)
+        );
+        assert_eq!(
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+            "file:///repo/"
+        );
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "P1",
+                "level": "error",
+                "message": {
+                    "text": "[P1] Off-by-one in <range> check\n\nThe loop skips the last element & never ends.",
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/lib.rs", "uriBaseId": "%SRCROOT%" },
+                        "region": { "startLine": 10, "endLine": 12 },
+                    },
+                }],
+                "properties": {
+                    "title": "[P1] Off-by-one in <range> check",
+                    "priority": 1,
+                    "confidence": 0.5,
+                },
+            })
+        );
+        let second = &run["results"][1];
+        assert_eq!(second["level"], "note");
+        assert_eq!(
+            second["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": "file:///elsewhere/notes.md" })
+        );
+        assert_eq!(
+            second["locations"][0]["physicalLocation"]["region"],
+            json!({ "startLine": 1, "endLine": 1 })
+        );
+    }
+
+    #[test]
+    fn sarif_percent_encodes_uris() {
+        let mut output = review_output();
+        output.findings[0].code_location.absolute_file_path =
+            PathBuf::from("/my repo/src/a b#1.rs");
+        output.findings[1].code_location.absolute_file_path =
+            PathBuf::from("/elsewhere/100% [draft].md");
+        let sarif = render_review_sarif(&output, Path::new("/my repo"));
+        let run = &sarif["runs"][0];
+
+        assert_eq!(
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+            "file:///my%20repo/"
+        );
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": "src/a%20b%231.rs", "uriBaseId": "%SRCROOT%" })
+        );
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": "file:///elsewhere/100%25%20%5Bdraft%5D.md" })
+        );
+    }
+
+    #[test]
+    fn junit_reports_findings_as_failures() {
+        let xml = render_review_junit(&review_output(), Path::new("/repo"));
+
+        assert!(xml.contains(r#"<testsuite name="codex review" tests="2" failures="2""#));
+        assert!(xml.contains(
+            r#"<testcase name="[P1] Off-by-one in &lt;range&gt; check" classname="src/lib.rs" file="src/lib.rs" line="10">"#
+        ));
+        assert!(xml.contains(
+            "The loop skips the last element &amp; never ends.\n\nsrc/lib.rs:10-12\npriority: P1\nconfidence: 0.5</failure>"
+        ));
+        assert!(
+            xml.contains(r#"<property name="overall_correctness" value="patch is incorrect"/>"#)
+        );
+    }
+
+    #[test]
+    fn junit_without_findings_has_one_passing_case() {
+        let output = ReviewOutputEvent {
+            findings: Vec::new(),
+            overall_correctness: "patch is correct".to_string(),
+            overall_explanation: "Looks good.".to_string(),
+            overall_confidence_score: 0.9,
+        };
+        let xml = render_review_junit(&output, Path::new("/repo"));
+
+        assert!(xml.contains(r#"tests="1" failures="0""#));
+        assert!(xml.contains(
+            "<testcase name=\"review\" classname=\"codex.review\">\n      <system-out>Looks good.</system-out>\n    </testcase>"
+        ));
+    }
+}
@@ -1,13 +1,29 @@
+use codex_git::ChangedLines;
+use codex_git::canonicalize_lenient;
+use codex_git::commit_changed_lines;
+use codex_git::diff_changed_lines;
 use codex_git::merge_base_with_head;
+use codex_git::repository_root_or_self;
+use codex_git::untracked_files;
+use codex_protocol::protocol::ReviewOutputEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::ReviewTarget;
 use std::path::Path;
+use std::path::PathBuf;
+
+use crate::review_baseline::ReviewBaseline;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ResolvedReviewRequest {
     pub target: ReviewTarget,
     pub prompt: String,
     pub user_facing_hint: String,
+    /// Lines changed by the target when the request asked to drop findings
+    /// outside of them.
+    pub changed_lines: Option<ChangedLines>,
+    /// Baseline file the request named, resolved against the working
+    /// directory, and the accepted findings loaded from it.
+    pub baseline: Option<(PathBuf, ReviewBaseline)>,
 }
 
 const UNCOMMITTED_PROMPT: &str = "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.";
@@ -19,6 +35,12 @@ const COMMIT_PROMPT_WITH_TITLE: &str = "Review the code changes introduced by co
 const COMMIT_PROMPT: &str =
     "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings.";
 
+const COMMIT_RANGE_PROMPT: &str = "Review the code changes between commits {base} and {head}. Run `git diff {base}..{head}` to inspect them and `git log {base}..{head}` for context on the individual commits. Provide prioritized, actionable findings.";
+
+const PATCH_FILE_PROMPT: &str = "Review the code changes in the patch file at {path}. Read the patch to see what it changes, and read the affected files in the working tree for context; the patch may not be applied yet. Report findings against the file paths and new-side line numbers in the patch. Provide prioritized, actionable findings.";
+
+const PATHS_PROMPT: &str = "Review the current contents of the following paths:\n{paths}\nProvide prioritized, actionable findings.";
+
 pub fn resolve_review_request(
     request: ReviewRequest,
     cwd: &Path,
@@ -28,11 +50,26 @@ pub fn resolve_review_request(
     let user_facing_hint = request
         .user_facing_hint
         .unwrap_or_else(|| user_facing_hint(&target));
+    let changed_lines = if request.only_changed_lines {
+        Some(changed_lines_for_target(&target, cwd)?)
+    } else {
+        None
+    };
+    let baseline = match request.baseline {
+        Some(path) => {
+            let path = cwd.join(path);
+            let baseline = ReviewBaseline::load(&path)?;
+            Some((path, baseline))
+        }
+        None => None,
+    };
 
     Ok(ResolvedReviewRequest {
         target,
         prompt,
         user_facing_hint,
+        changed_lines,
+        baseline,
     })
 }
 
@@ -64,9 +101,91 @@ pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String
             }
             Ok(prompt.to_string())
         }
+        ReviewTarget::CommitRange { base, head } => Ok(COMMIT_RANGE_PROMPT
+            .replace("{base}", base)
+            .replace("{head}", head)),
+        ReviewTarget::PatchFile { path } => {
+            let path = cwd.join(path);
+            if !path.is_file() {
+                anyhow::bail!("Patch file {} does not exist", path.display());
+            }
+            Ok(PATCH_FILE_PROMPT.replace("{path}", &path.display().to_string()))
+        }
+        ReviewTarget::Paths { paths } => {
+            if paths.is_empty() {
+                anyhow::bail!("Review paths cannot be empty");
+            }
+            let listed = paths
+                .iter()
+                .map(|path| format!("- {}", cwd.join(path).display()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(PATHS_PROMPT.replace("{paths}", &listed))
+        }
+    }
+}
+
+/// Computes the lines a review target changes, used to drop findings that
+/// fall outside of them. Path targets count every line of the listed paths as
+/// changed, and custom instructions have no changed lines to filter by. All
+/// paths are canonical, and patch file entries are resolved against the root
+/// of the repository that contains `cwd`.
+pub fn changed_lines_for_target(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<ChangedLines> {
+    match target {
+        ReviewTarget::UncommittedChanges => {
+            let mut changed = diff_changed_lines(cwd, &["HEAD"])?;
+            for path in untracked_files(cwd)? {
+                changed.mark_whole(path);
+            }
+            Ok(changed)
+        }
+        ReviewTarget::BaseBranch { branch } => {
+            let base = merge_base_with_head(cwd, branch)?.unwrap_or_else(|| branch.clone());
+            Ok(diff_changed_lines(cwd, &[base.as_str()])?)
+        }
+        ReviewTarget::Commit { sha, .. } => Ok(commit_changed_lines(cwd, sha)?),
+        ReviewTarget::CommitRange { base, head } => {
+            let range = format!("{base}..{head}");
+            Ok(diff_changed_lines(cwd, &[range.as_str()])?)
+        }
+        ReviewTarget::PatchFile { path } => {
+            let patch = std::fs::read_to_string(cwd.join(path))?;
+            Ok(ChangedLines::from_unified_diff(
+                &patch,
+                &repository_root_or_self(cwd),
+            ))
+        }
+        ReviewTarget::Paths { paths } => {
+            let mut changed = ChangedLines::default();
+            for path in paths {
+                changed.mark_whole(canonicalize_lenient(&cwd.join(path)));
+            }
+            Ok(changed)
+        }
+        ReviewTarget::Custom { .. } => {
+            anyhow::bail!("Only reviewing changed lines is not supported for custom instructions")
+        }
     }
 }
 
+/// Drops findings whose location does not overlap `changed_lines`. Relative
+/// finding paths are resolved against `cwd`, and all of them are canonicalized
+/// to match the paths in `changed_lines`.
+pub fn retain_changed_line_findings(
+    output: &mut ReviewOutputEvent,
+    changed_lines: &ChangedLines,
+    cwd: &Path,
+) {
+    output.findings.retain(|finding| {
+        let location = &finding.code_location;
+        changed_lines.touches(
+            &canonicalize_lenient(&cwd.join(&location.absolute_file_path)),
+            location.line_range.start,
+            location.line_range.end,
+        )
+    });
+}
+
 pub fn user_facing_hint(target: &ReviewTarget) -> String {
     match target {
         ReviewTarget::UncommittedChanges => "current changes".to_string(),
@@ -80,6 +199,16 @@ pub fn user_facing_hint(target: &ReviewTarget) -> String {
             }
         }
         ReviewTarget::Custom { instructions } => instructions.trim().to_string(),
+        ReviewTarget::CommitRange { base, head } => format!("commits {base}..{head}"),
+        ReviewTarget::PatchFile { path } => format!("patch {}", path.display()),
+        ReviewTarget::Paths { paths } => {
+            let listed = paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("paths {listed}")
+        }
     }
 }
 
@@ -88,6 +217,8 @@ impl From<ResolvedReviewRequest> for ReviewRequest {
         ReviewRequest {
             target: resolved.target,
             user_facing_hint: Some(resolved.user_facing_hint),
+            only_changed_lines: resolved.changed_lines.is_some(),
+            baseline: resolved.baseline.map(|(path, _)| path),
         }
     }
 }
@@ -46,13 +46,22 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::EnteredReviewMode(_)
         | EventMsg::ExitedReviewMode(_)
         | EventMsg::ThreadRolledBack(_)
+        | EventMsg::PinnedMessagesUpdated(_)
+        | EventMsg::CollaborationModeUpdated(_)
         | EventMsg::UndoCompleted(_)
         | EventMsg::TurnAborted(_) => true,
         EventMsg::ItemCompleted(event) => {
             // Plan items are derived from streaming tags and are not part of the
             // raw ResponseItem history, so we persist their completion to replay
             // them on resume without bloating rollouts with every item lifecycle.
-            matches!(event.item, codex_protocol::items::TurnItem::Plan(_))
+            // Message items are kept for their ids, which the raw history drops,
+            // so they can still be pinned after a resume.
+            matches!(
+                event.item,
+                codex_protocol::items::TurnItem::Plan(_)
+                    | codex_protocol::items::TurnItem::UserMessage(_)
+                    | codex_protocol::items::TurnItem::AgentMessage(_)
+            )
         }
         EventMsg::Error(_)
         | EventMsg::Warning(_)
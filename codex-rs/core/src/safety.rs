@@ -150,11 +150,25 @@ fn is_write_patch_constrained_to_writable_paths(
 
     for (path, change) in action.changes() {
         match change {
-            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => {
+            ApplyPatchFileChange::Add { .. }
+            | ApplyPatchFileChange::AddBinary { .. }
+            | ApplyPatchFileChange::Delete { .. }
+            | ApplyPatchFileChange::SetMode { .. } => {
                 if !is_path_writable(path) {
                     return false;
                 }
             }
+            ApplyPatchFileChange::AddSymlink { target } => {
+                // Later writes through the link land on its target, so the
+                // target must be writable too.
+                let target = path
+                    .parent()
+                    .map(|parent| parent.join(target))
+                    .unwrap_or_else(|| target.clone());
+                if !is_path_writable(path) || !is_path_writable(&target) {
+                    return false;
+                }
+            }
             ApplyPatchFileChange::Update { move_path, .. } => {
                 if !is_path_writable(path) {
                     return false;
@@ -227,6 +241,39 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn symlink_target_must_be_writable() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().to_path_buf();
+        let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        let symlink_action = |target: &str| {
+            let argv = vec![
+                "apply_patch".to_string(),
+                format!("*** Begin Patch\n*** Add Symlink: bin/link\n+{target}\n*** End Patch"),
+            ];
+            match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, &cwd) {
+                codex_apply_patch::MaybeApplyPatchVerified::Body(action) => action,
+                other => panic!("expected verified patch, got {other:?}"),
+            }
+        };
+
+        assert!(is_write_patch_constrained_to_writable_paths(
+            &symlink_action("../scripts/run.sh"),
+            &policy_workspace_only,
+            &cwd,
+        ));
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &symlink_action("../../outside.txt"),
+            &policy_workspace_only,
+            &cwd,
+        ));
+    }
+
     #[test]
     fn external_sandbox_auto_approves_in_on_request() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,913 @@
+//! Readable transcripts of recorded sessions.
+//!
+//! `codex export` turns the `RolloutItem`s of a rollout file into a
+//! [`Transcript`] and renders it as Markdown, as a standalone HTML page or as
+//! JSON. Command outputs are truncated to their first and last lines, and
+//! `apply_patch` calls are shown as unified diffs.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use codex_apply_patch::Hunk;
+use codex_apply_patch::parse_patch;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::WebSearchAction;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use similar::ChangeTag;
+use similar::TextDiff;
+
+use crate::parse_command::extract_shell_command;
+
+/// Command and tool outputs longer than `OUTPUT_HEAD_LINES +
+/// OUTPUT_TAIL_LINES` lines keep only their first and last lines.
+const OUTPUT_HEAD_LINES: usize = 20;
+const OUTPUT_TAIL_LINES: usize = 20;
+
+/// A session reduced to what a reader cares about.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Transcript {
+    pub thread_id: Option<String>,
+    pub name: Option<String>,
+    pub started_at: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub model: Option<String>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    UserMessage {
+        message: String,
+    },
+    AgentMessage {
+        message: String,
+    },
+    Reasoning {
+        summary: String,
+    },
+    Command {
+        #[serde(skip)]
+        call_id: String,
+        command: String,
+        exit_code: Option<i32>,
+        output: Option<String>,
+    },
+    ToolCall {
+        #[serde(skip)]
+        call_id: String,
+        name: String,
+        arguments: String,
+        output: Option<String>,
+    },
+    Patch {
+        #[serde(skip)]
+        call_id: String,
+        diff: String,
+        output: Option<String>,
+    },
+    WebSearch {
+        description: String,
+    },
+    Compacted,
+}
+
+impl Transcript {
+    /// Builds a transcript from the items of a rollout file. Turns undone by a
+    /// rollback are left out.
+    pub fn from_rollout_items(items: &[RolloutItem]) -> Self {
+        let mut builder = TranscriptBuilder::default();
+        for item in items {
+            builder.push(item);
+        }
+        builder.transcript
+    }
+
+    fn title(&self) -> String {
+        match (&self.name, &self.thread_id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(id)) => format!("Codex session {id}"),
+            (None, None) => "Codex session".to_string(),
+        }
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        if let Some(id) = &self.thread_id {
+            details.push(("Thread", id.clone()));
+        }
+        if let Some(started_at) = &self.started_at {
+            details.push(("Started", started_at.clone()));
+        }
+        if let Some(cwd) = &self.cwd {
+            details.push(("Working directory", cwd.display().to_string()));
+        }
+        if let Some(model) = &self.model {
+            details.push(("Model", model.clone()));
+        }
+        details
+    }
+}
+
+#[derive(Default)]
+struct TranscriptBuilder {
+    transcript: Transcript,
+    /// Entry index of each pending tool call, by call id.
+    calls: HashMap<String, usize>,
+    /// Entry index at which each user turn starts, used to apply rollbacks.
+    turn_starts: Vec<usize>,
+}
+
+impl TranscriptBuilder {
+    fn push(&mut self, item: &RolloutItem) {
+        match item {
+            RolloutItem::SessionMeta(line) => {
+                let transcript = &mut self.transcript;
+                transcript.thread_id = Some(line.meta.id.to_string());
+                transcript.started_at = Some(line.meta.timestamp.clone());
+                transcript.cwd = Some(line.meta.cwd.clone());
+            }
+            RolloutItem::TurnContext(context) => {
+                if self.transcript.model.is_none() {
+                    self.transcript.model = Some(context.model.clone());
+                }
+            }
+            RolloutItem::Compacted(_) => self.add(TranscriptEntry::Compacted),
+            RolloutItem::EventMsg(event) => self.push_event(event),
+            RolloutItem::ResponseItem(item) => self.push_response_item(item),
+        }
+    }
+
+    fn push_event(&mut self, event: &EventMsg) {
+        match event {
+            EventMsg::UserMessage(event) => {
+                self.turn_starts.push(self.transcript.entries.len());
+                self.add(TranscriptEntry::UserMessage {
+                    message: event.message.clone(),
+                });
+            }
+            EventMsg::AgentMessage(event) => self.add(TranscriptEntry::AgentMessage {
+                message: event.message.clone(),
+            }),
+            EventMsg::AgentReasoning(event) => self.add(TranscriptEntry::Reasoning {
+                summary: event.text.clone(),
+            }),
+            EventMsg::ThreadRolledBack(event) => {
+                let keep = self
+                    .turn_starts
+                    .len()
+                    .saturating_sub(event.num_turns as usize);
+                if let Some(&start) = self.turn_starts.get(keep) {
+                    self.transcript.entries.truncate(start);
+                    self.turn_starts.truncate(keep);
+                    self.calls.retain(|_, index| *index < start);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_response_item(&mut self, item: &ResponseItem) {
+        match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let entry = function_call_entry(call_id, name, arguments);
+                self.add_call(call_id, entry);
+            }
+            ResponseItem::LocalShellCall {
+                call_id: Some(call_id),
+                action: LocalShellAction::Exec(action),
+                ..
+            } => {
+                let entry = TranscriptEntry::Command {
+                    call_id: call_id.clone(),
+                    command: display_command(&action.command),
+                    exit_code: None,
+                    output: None,
+                };
+                self.add_call(call_id, entry);
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                let entry = if name == "apply_patch" {
+                    patch_entry(call_id, input)
+                } else {
+                    TranscriptEntry::ToolCall {
+                        call_id: call_id.clone(),
+                        name: name.clone(),
+                        arguments: input.clone(),
+                        output: None,
+                    }
+                };
+                self.add_call(call_id, entry);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                self.set_output(call_id, &output.content);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                self.set_output(call_id, output);
+            }
+            ResponseItem::WebSearchCall {
+                action: Some(action),
+                ..
+            } => {
+                if let Some(description) = describe_web_search(action) {
+                    self.add(TranscriptEntry::WebSearch { description });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add(&mut self, entry: TranscriptEntry) {
+        self.transcript.entries.push(entry);
+    }
+
+    fn add_call(&mut self, call_id: &str, entry: TranscriptEntry) {
+        self.calls
+            .insert(call_id.to_string(), self.transcript.entries.len());
+        self.add(entry);
+    }
+
+    fn set_output(&mut self, call_id: &str, text: &str) {
+        let Some(index) = self.calls.remove(call_id) else {
+            return;
+        };
+        match &mut self.transcript.entries[index] {
+            TranscriptEntry::Command {
+                exit_code, output, ..
+            } => {
+                let (code, body) = parse_exec_output(text);
+                *exit_code = code;
+                *output = Some(truncate_output(&body));
+            }
+            TranscriptEntry::ToolCall { output, .. } | TranscriptEntry::Patch { output, .. } => {
+                *output = Some(truncate_output(text));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn function_call_entry(call_id: &str, name: &str, arguments: &str) -> TranscriptEntry {
+    let args: Option<JsonValue> = serde_json::from_str(arguments).ok();
+    let arg = |key: &str| args.as_ref().and_then(|args| args.get(key));
+    let command = match name {
+        "shell" | "container.exec" => arg("command").and_then(JsonValue::as_array).map(|command| {
+            let command: Vec<String> = command
+                .iter()
+                .filter_map(|part| part.as_str().map(str::to_string))
+                .collect();
+            display_command(&command)
+        }),
+        "shell_command" => arg("command")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string),
+        "exec_command" => arg("cmd").and_then(JsonValue::as_str).map(str::to_string),
+        "apply_patch" => {
+            if let Some(input) = arg("input").and_then(JsonValue::as_str) {
+                return patch_entry(call_id, input);
+            }
+            None
+        }
+        _ => None,
+    };
+    match command {
+        Some(command) => TranscriptEntry::Command {
+            call_id: call_id.to_string(),
+            command,
+            exit_code: None,
+            output: None,
+        },
+        None => TranscriptEntry::ToolCall {
+            call_id: call_id.to_string(),
+            name: name.to_string(),
+            arguments: args
+                .as_ref()
+                .and_then(|args| serde_json::to_string_pretty(args).ok())
+                .unwrap_or_else(|| arguments.to_string()),
+            output: None,
+        },
+    }
+}
+
+fn patch_entry(call_id: &str, patch: &str) -> TranscriptEntry {
+    TranscriptEntry::Patch {
+        call_id: call_id.to_string(),
+        diff: patch_to_unified_diff(patch).unwrap_or_else(|| patch.to_string()),
+        output: None,
+    }
+}
+
+/// Shows `bash -lc "<script>"` (or the fish, Nushell and PowerShell
+/// equivalents) as just the script; other commands are shell-quoted.
+fn display_command(command: &[String]) -> String {
+    if let Some((_, script)) = extract_shell_command(command) {
+        return script.to_string();
+    }
+    shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
+}
+
+/// Splits a command result as formatted for the model into its exit code and
+/// output. Handles the JSON form (`{"output", "metadata": {"exit_code"}}`) and
+/// the text forms that put `Exit code: N` or `Process exited with code N`
+/// above an `Output:` line.
+fn parse_exec_output(text: &str) -> (Option<i32>, String) {
+    if let Ok(value) = serde_json::from_str::<JsonValue>(text)
+        && let Some(output) = value.get("output").and_then(JsonValue::as_str)
+    {
+        let exit_code = value
+            .pointer("/metadata/exit_code")
+            .and_then(JsonValue::as_i64)
+            .and_then(|code| i32::try_from(code).ok());
+        return (exit_code, output.to_string());
+    }
+
+    let Some((header, body)) = text
+        .split_once("\nOutput:\n")
+        .or_else(|| text.strip_prefix("Output:\n").map(|body| ("", body)))
+    else {
+        return (None, text.to_string());
+    };
+    let exit_code = header.lines().find_map(|line| {
+        line.strip_prefix("Exit code: ")
+            .or_else(|| line.strip_prefix("Process exited with code "))
+            .and_then(|code| code.trim().parse().ok())
+    });
+    (exit_code, body.to_string())
+}
+
+fn truncate_output(text: &str) -> String {
+    let text = text.trim_end_matches('\n');
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= OUTPUT_HEAD_LINES + OUTPUT_TAIL_LINES {
+        return text.to_string();
+    }
+    let omitted = lines.len() - OUTPUT_HEAD_LINES - OUTPUT_TAIL_LINES;
+    let mut truncated = lines[..OUTPUT_HEAD_LINES].join("\n");
+    truncated.push_str(&format!("\n… {omitted} lines omitted …\n"));
+    truncated.push_str(&lines[lines.len() - OUTPUT_TAIL_LINES..].join("\n"));
+    truncated
+}
+
+fn describe_web_search(action: &WebSearchAction) -> Option<String> {
+    match action {
+        WebSearchAction::Search { query, queries } => query
+            .clone()
+            .or_else(|| queries.as_ref().map(|queries| queries.join(", ")))
+            .map(|query| format!("Searched the web for \"{query}\"")),
+        WebSearchAction::OpenPage { url } => url.as_ref().map(|url| format!("Opened {url}")),
+        WebSearchAction::FindInPage { url, pattern } => Some(format!(
+            "Searched {} for \"{}\"",
+            url.as_deref().unwrap_or("the page"),
+            pattern.as_deref().unwrap_or_default()
+        )),
+        WebSearchAction::Other => None,
+    }
+}
+
+/// Renders an `apply_patch` patch as a git-style unified diff. Hunk headers
+/// carry the patch's context line instead of line numbers, which the patch
+/// format does not record.
+fn patch_to_unified_diff(patch: &str) -> Option<String> {
+    let args = parse_patch(patch).ok()?;
+    let mut diff = String::new();
+    for hunk in &args.hunks {
+        match hunk {
+            Hunk::AddFile { path, contents } => {
+                let path = path.display();
+                diff.push_str(&format!(
+                    "diff --git a/{path} b/{path}\nnew file mode 100644\n--- /dev/null\n+++ b/{path}\n"
+                ));
+                let lines: Vec<&str> = contents.lines().collect();
+                diff.push_str(&format!("@@ -0,0 +1,{} @@\n", lines.len()));
+                for line in lines {
+                    diff.push_str(&format!("+{line}\n"));
+                }
+            }
+            Hunk::AddBinaryFile { path, contents } => {
+                let path = path.display();
+                diff.push_str(&format!(
+                    "diff --git a/{path} b/{path}\nnew file mode 100644\nBinary file b/{path} added ({} bytes)\n",
+                    contents.len()
+                ));
+            }
+            Hunk::AddSymlink { path, target } => {
+                let path = path.display();
+                diff.push_str(&format!(
+                    "diff --git a/{path} b/{path}\nnew file mode 120000\n--- /dev/null\n+++ b/{path}\n@@ -0,0 +1 @@\n+{}\n",
+                    target.display()
+                ));
+            }
+            Hunk::DeleteFile { path } => {
+                let path = path.display();
+                diff.push_str(&format!(
+                    "diff --git a/{path} b/{path}\ndeleted file\n--- a/{path}\n+++ /dev/null\n"
+                ));
+            }
+            Hunk::SetMode { path, mode } => {
+                let path = path.display();
+                diff.push_str(&format!(
+                    "diff --git a/{path} b/{path}\nnew mode {}\n",
+                    mode.as_str()
+                ));
+            }
+            Hunk::UpdateFile {
+                path,
+                move_path,
+                chunks,
+            } => {
+                let old = path.display();
+                let new = move_path.as_ref().unwrap_or(path).display();
+                diff.push_str(&format!("diff --git a/{old} b/{new}\n"));
+                if move_path.is_some() {
+                    diff.push_str(&format!("rename from {old}\nrename to {new}\n"));
+                }
+                diff.push_str(&format!("--- a/{old}\n+++ b/{new}\n"));
+                for chunk in chunks {
+                    match &chunk.change_context {
+                        Some(context) => diff.push_str(&format!("@@ {context}\n")),
+                        None => diff.push_str("@@\n"),
+                    }
+                    let old_lines: Vec<&str> = chunk.old_lines.iter().map(String::as_str).collect();
+                    let new_lines: Vec<&str> = chunk.new_lines.iter().map(String::as_str).collect();
+                    for change in TextDiff::from_slices(&old_lines, &new_lines).iter_all_changes() {
+                        let sign = match change.tag() {
+                            ChangeTag::Equal => ' ',
+                            ChangeTag::Delete => '-',
+                            ChangeTag::Insert => '+',
+                        };
+                        diff.push(sign);
+                        diff.push_str(change.value());
+                        diff.push('\n');
+                    }
+                }
+            }
+        }
+    }
+    Some(diff)
+}
+
+/// Renders the transcript as Markdown.
+pub fn render_markdown(transcript: &Transcript) -> String {
+    let mut out = format!("# {}\n\n", transcript.title());
+    for (label, value) in transcript.details() {
+        out.push_str(&format!("- **{label}:** `{value}`\n"));
+    }
+
+    let mut in_agent_section = false;
+    for entry in &transcript.entries {
+        match entry {
+            TranscriptEntry::UserMessage { message } => {
+                in_agent_section = false;
+                out.push_str(&format!("\n## User\n\n{}\n", message.trim_end()));
+                continue;
+            }
+            TranscriptEntry::Compacted => {
+                in_agent_section = false;
+                out.push_str("\n---\n\n_Earlier conversation was compacted._\n");
+                continue;
+            }
+            _ => {}
+        }
+        if !in_agent_section {
+            in_agent_section = true;
+            out.push_str("\n## Codex\n");
+        }
+        out.push('\n');
+        match entry {
+            TranscriptEntry::AgentMessage { message } => {
+                out.push_str(message.trim_end());
+                out.push('\n');
+            }
+            TranscriptEntry::Reasoning { summary } => {
+                for line in summary.trim_end().lines() {
+                    if line.is_empty() {
+                        out.push_str(">\n");
+                    } else {
+                        out.push_str(&format!("> {line}\n"));
+                    }
+                }
+            }
+            TranscriptEntry::Command {
+                command,
+                exit_code,
+                output,
+                ..
+            } => {
+                out.push_str(&match exit_code {
+                    Some(code) => format!("**Ran** (exit code {code})\n\n"),
+                    None => "**Ran**\n\n".to_string(),
+                });
+                out.push_str(&fenced("sh", command));
+                if let Some(output) = output.as_deref().filter(|output| !output.is_empty()) {
+                    out.push('\n');
+                    out.push_str(&fenced("text", output));
+                }
+            }
+            TranscriptEntry::Patch { diff, output, .. } => {
+                out.push_str("**Edited files**\n\n");
+                out.push_str(&fenced("diff", diff));
+                if let Some(output) = output {
+                    out.push('\n');
+                    out.push_str(&fenced("text", output));
+                }
+            }
+            TranscriptEntry::ToolCall {
+                name,
+                arguments,
+                output,
+                ..
+            } => {
+                out.push_str(&format!("**Called** `{name}`\n\n"));
+                out.push_str(&fenced("json", arguments));
+                if let Some(output) = output {
+                    out.push('\n');
+                    out.push_str(&fenced("text", output));
+                }
+            }
+            TranscriptEntry::WebSearch { description } => {
+                out.push_str(&format!("_{description}_\n"));
+            }
+            TranscriptEntry::UserMessage { .. } | TranscriptEntry::Compacted => {}
+        }
+    }
+    out
+}
+
+/// Wraps `text` in a code fence longer than any backtick run inside it.
+fn fenced(language: &str, text: &str) -> String {
+    let longest_run = text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "{fence}{language}\n{}\n{fence}\n",
+        text.trim_end_matches('\n')
+    )
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #1f2328; }
+h2 { border-bottom: 1px solid #d0d7de; padding-bottom: 0.25rem; margin-top: 2rem; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.25rem 1rem; }
+dt { font-weight: 600; }
+dd { margin: 0; font-family: monospace; }
+.message { white-space: pre-wrap; }
+.reasoning { white-space: pre-wrap; color: #59636e; border-left: 3px solid #d0d7de; padding-left: 0.75rem; }
+.label { font-weight: 600; margin: 1rem 0 0.25rem; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; border-radius: 6px; }
+.add { color: #116329; background: #dafbe1; }
+.del { color: #82071e; background: #ffebe9; }
+.hunk { color: #0550ae; }
+";
+
+/// Renders the transcript as a standalone HTML page.
+pub fn render_html(transcript: &Transcript) -> String {
+    let title = escape_html(&transcript.title());
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    let details = transcript.details();
+    if !details.is_empty() {
+        out.push_str("<dl>\n");
+        for (label, value) in details {
+            out.push_str(&format!(
+                "<dt>{label}</dt><dd>{}</dd>\n",
+                escape_html(&value)
+            ));
+        }
+        out.push_str("</dl>\n");
+    }
+
+    let mut in_agent_section = false;
+    for entry in &transcript.entries {
+        match entry {
+            TranscriptEntry::UserMessage { message } => {
+                in_agent_section = false;
+                out.push_str(&format!(
+                    "<h2>User</h2>\n<div class=\"message\">{}</div>\n",
+                    escape_html(message.trim_end())
+                ));
+                continue;
+            }
+            TranscriptEntry::Compacted => {
+                in_agent_section = false;
+                out.push_str("<hr>\n<p><em>Earlier conversation was compacted.</em></p>\n");
+                continue;
+            }
+            _ => {}
+        }
+        if !in_agent_section {
+            in_agent_section = true;
+            out.push_str("<h2>Codex</h2>\n");
+        }
+        match entry {
+            TranscriptEntry::AgentMessage { message } => {
+                out.push_str(&format!(
+                    "<div class=\"message\">{}</div>\n",
+                    escape_html(message.trim_end())
+                ));
+            }
+            TranscriptEntry::Reasoning { summary } => {
+                out.push_str(&format!(
+                    "<div class=\"reasoning\">{}</div>\n",
+                    escape_html(summary.trim_end())
+                ));
+            }
+            TranscriptEntry::Command {
+                command,
+                exit_code,
+                output,
+                ..
+            } => {
+                let label = match exit_code {
+                    Some(code) => format!("Ran (exit code {code})"),
+                    None => "Ran".to_string(),
+                };
+                out.push_str(&format!(
+                    "<p class=\"label\">{label}</p>\n<pre><code>{}</code></pre>\n",
+                    escape_html(command)
+                ));
+                if let Some(output) = output.as_deref().filter(|output| !output.is_empty()) {
+                    out.push_str(&format!("<pre>{}</pre>\n", escape_html(output)));
+                }
+            }
+            TranscriptEntry::Patch { diff, output, .. } => {
+                out.push_str("<p class=\"label\">Edited files</p>\n<pre>");
+                for line in diff.lines() {
+                    let class = if line.starts_with("+++") || line.starts_with("---") {
+                        None
+                    } else if line.starts_with('+') {
+                        Some("add")
+                    } else if line.starts_with('-') {
+                        Some("del")
+                    } else if line.starts_with("@@") {
+                        Some("hunk")
+                    } else {
+                        None
+                    };
+                    match class {
+                        Some(class) => out.push_str(&format!(
+                            "<span class=\"{class}\">{}</span>\n",
+                            escape_html(line)
+                        )),
+                        None => {
+                            out.push_str(&escape_html(line));
+                            out.push('\n');
+                        }
+                    }
+                }
+                out.push_str("</pre>\n");
+                if let Some(output) = output {
+                    out.push_str(&format!("<pre>{}</pre>\n", escape_html(output)));
+                }
+            }
+            TranscriptEntry::ToolCall {
+                name,
+                arguments,
+                output,
+                ..
+            } => {
+                out.push_str(&format!(
+                    "<p class=\"label\">Called <code>{}</code></p>\n<pre>{}</pre>\n",
+                    escape_html(name),
+                    escape_html(arguments)
+                ));
+                if let Some(output) = output {
+                    out.push_str(&format!("<pre>{}</pre>\n", escape_html(output)));
+                }
+            }
+            TranscriptEntry::WebSearch { description } => {
+                out.push_str(&format!("<p><em>{}</em></p>\n", escape_html(description)));
+            }
+            TranscriptEntry::UserMessage { .. } | TranscriptEntry::Compacted => {}
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Renders the transcript as pretty-printed JSON.
+pub fn render_json(transcript: &Transcript) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(transcript)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AgentReasoningEvent;
+    use codex_protocol::protocol::ThreadRolledBackEvent;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn user(message: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: message.to_string(),
+            images: None,
+            local_images: Vec::new(),
+            text_elements: Vec::new(),
+        }))
+    }
+
+    fn agent(message: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+            message: message.to_string(),
+        }))
+    }
+
+    fn call(name: &str, call_id: &str, arguments: JsonValue) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: call_id.to_string(),
+        })
+    }
+
+    fn call_output(call_id: &str, content: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                ..Default::default()
+            },
+        })
+    }
+
+    fn patch_call(call_id: &str, input: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+            id: None,
+            status: None,
+            call_id: call_id.to_string(),
+            name: "apply_patch".to_string(),
+            input: input.to_string(),
+        })
+    }
+
+    #[test]
+    fn builds_entries_and_pairs_outputs_with_calls() {
+        let items = vec![
+            user("Fix the build"),
+            RolloutItem::EventMsg(EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: "Looking at the failure".to_string(),
+            })),
+            call(
+                "shell",
+                "call-1",
+                serde_json::json!({"command": ["bash", "-lc", "cargo test"]}),
+            ),
+            call_output(
+                "call-1",
+                &serde_json::json!({
+                    "output": "test result: FAILED\n",
+                    "metadata": {"exit_code": 101, "duration_seconds": 1.5},
+                })
+                .to_string(),
+            ),
+            call("exec_command", "call-2", serde_json::json!({"cmd": "ls"})),
+            call_output(
+                "call-2",
+                "Wall time: 0.1 seconds\nProcess exited with code 0\nOutput:\nCargo.toml\n",
+            ),
+            patch_call(
+                "call-3",
+                "*** Begin Patch\n*** Update File: src/lib.rs\n@@ fn main() {\n-    old();\n+    new();\n*** End Patch",
+            ),
+            agent("Fixed."),
+        ];
+
+        let transcript = Transcript::from_rollout_items(&items);
+        assert_eq!(
+            transcript.entries,
+            vec![
+                TranscriptEntry::UserMessage {
+                    message: "Fix the build".to_string(),
+                },
+                TranscriptEntry::Reasoning {
+                    summary: "Looking at the failure".to_string(),
+                },
+                TranscriptEntry::Command {
+                    call_id: "call-1".to_string(),
+                    command: "cargo test".to_string(),
+                    exit_code: Some(101),
+                    output: Some("test result: FAILED".to_string()),
+                },
+                TranscriptEntry::Command {
+                    call_id: "call-2".to_string(),
+                    command: "ls".to_string(),
+                    exit_code: Some(0),
+                    output: Some("Cargo.toml".to_string()),
+                },
+                TranscriptEntry::Patch {
+                    call_id: "call-3".to_string(),
+                    diff: "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ fn main() {\n-    old();\n+    new();\n".to_string(),
+                    output: None,
+                },
+                TranscriptEntry::AgentMessage {
+                    message: "Fixed.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rollback_drops_undone_turns() {
+        let items = vec![
+            user("first"),
+            agent("one"),
+            user("second"),
+            agent("two"),
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns: 1,
+            })),
+            user("third"),
+        ];
+
+        let transcript = Transcript::from_rollout_items(&items);
+        assert_eq!(
+            transcript.entries,
+            vec![
+                TranscriptEntry::UserMessage {
+                    message: "first".to_string(),
+                },
+                TranscriptEntry::AgentMessage {
+                    message: "one".to_string(),
+                },
+                TranscriptEntry::UserMessage {
+                    message: "third".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn long_outputs_keep_head_and_tail() {
+        let output: Vec<String> = (1..=100).map(|n| format!("line {n}")).collect();
+        let truncated = truncate_output(&output.join("\n"));
+        let lines: Vec<&str> = truncated.lines().collect();
+        assert_eq!(lines.len(), OUTPUT_HEAD_LINES + OUTPUT_TAIL_LINES + 1);
+        assert_eq!(lines[0], "line 1");
+        assert_eq!(lines[OUTPUT_HEAD_LINES], "… 60 lines omitted …");
+        assert_eq!(lines.last(), Some(&"line 100"));
+    }
+
+    #[test]
+    fn renders_markdown_and_escaped_html() {
+        let transcript = Transcript {
+            thread_id: Some("thread-1".to_string()),
+            name: Some("Build fix".to_string()),
+            entries: vec![
+                TranscriptEntry::UserMessage {
+                    message: "Why does <T> fail?".to_string(),
+                },
+                TranscriptEntry::Command {
+                    call_id: "call-1".to_string(),
+                    command: "echo '```'".to_string(),
+                    exit_code: Some(0),
+                    output: Some("```".to_string()),
+                },
+                TranscriptEntry::AgentMessage {
+                    message: "Done.".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render_markdown(&transcript),
+            "# Build fix\n\n- **Thread:** `thread-1`\n\n## User\n\nWhy does <T> fail?\n\n## Codex\n\n**Ran** (exit code 0)\n\n````sh\necho '```'\n````\n\n````text\n```\n````\n\nDone.\n"
+        );
+        let html = render_html(&transcript);
+        assert!(html.contains("<title>Build fix</title>"));
+        assert!(html.contains("Why does &lt;T&gt; fail?"));
+        assert_eq!(html.matches("<h2>Codex</h2>").count(), 1);
+    }
+}
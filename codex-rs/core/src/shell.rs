@@ -12,6 +12,8 @@ pub enum ShellType {
     PowerShell,
     Sh,
     Cmd,
+    Fish,
+    Nushell,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +36,8 @@ impl Shell {
             ShellType::PowerShell => "powershell",
             ShellType::Sh => "sh",
             ShellType::Cmd => "cmd",
+            ShellType::Fish => "fish",
+            ShellType::Nushell => "nu",
         }
     }
 
@@ -41,7 +45,7 @@ impl Shell {
     /// use with `exec()` to run the shell command.
     pub fn derive_exec_args(&self, command: &str, use_login_shell: bool) -> Vec<String> {
         match self.shell_type {
-            ShellType::Zsh | ShellType::Bash | ShellType::Sh => {
+            ShellType::Zsh | ShellType::Bash | ShellType::Sh | ShellType::Fish => {
                 let arg = if use_login_shell { "-lc" } else { "-c" };
                 vec![
                     self.shell_path.to_string_lossy().to_string(),
@@ -65,6 +69,17 @@ impl Shell {
                 args.push(command.to_string());
                 args
             }
+            ShellType::Nushell => {
+                // Nushell does not accept bundled short flags, so `--login`
+                // is passed separately from `-c`.
+                let mut args = vec![self.shell_path.to_string_lossy().to_string()];
+                if use_login_shell {
+                    args.push("--login".to_string());
+                }
+                args.push("-c".to_string());
+                args.push(command.to_string());
+                args
+            }
         }
     }
 
@@ -202,6 +217,40 @@ fn get_powershell_shell(path: Option<&PathBuf>) -> Option<Shell> {
     })
 }
 
+fn get_fish_shell(path: Option<&PathBuf>) -> Option<Shell> {
+    let shell_path = get_shell_path(
+        ShellType::Fish,
+        path,
+        "fish",
+        vec![
+            "/usr/bin/fish",
+            "/usr/local/bin/fish",
+            "/opt/homebrew/bin/fish",
+        ],
+    );
+
+    shell_path.map(|shell_path| Shell {
+        shell_type: ShellType::Fish,
+        shell_path,
+        shell_snapshot: empty_shell_snapshot_receiver(),
+    })
+}
+
+fn get_nushell_shell(path: Option<&PathBuf>) -> Option<Shell> {
+    let shell_path = get_shell_path(
+        ShellType::Nushell,
+        path,
+        "nu",
+        vec!["/usr/bin/nu", "/usr/local/bin/nu", "/opt/homebrew/bin/nu"],
+    );
+
+    shell_path.map(|shell_path| Shell {
+        shell_type: ShellType::Nushell,
+        shell_path,
+        shell_snapshot: empty_shell_snapshot_receiver(),
+    })
+}
+
 fn get_cmd_shell(path: Option<&PathBuf>) -> Option<Shell> {
     let shell_path = get_shell_path(ShellType::Cmd, path, "cmd", vec![]);
 
@@ -241,6 +290,8 @@ pub fn get_shell(shell_type: ShellType, path: Option<&PathBuf>) -> Option<Shell>
         ShellType::PowerShell => get_powershell_shell(path),
         ShellType::Sh => get_sh_shell(path),
         ShellType::Cmd => get_cmd_shell(path),
+        ShellType::Fish => get_fish_shell(path),
+        ShellType::Nushell => get_nushell_shell(path),
     }
 }
 
@@ -252,6 +303,8 @@ pub fn detect_shell_type(shell_path: &PathBuf) -> Option<ShellType> {
         Some("bash") => Some(ShellType::Bash),
         Some("pwsh") => Some(ShellType::PowerShell),
         Some("powershell") => Some(ShellType::PowerShell),
+        Some("fish") => Some(ShellType::Fish),
+        Some("nu") | Some("nushell") => Some(ShellType::Nushell),
         _ => {
             let shell_name = shell_path.file_stem();
             if let Some(shell_name) = shell_name
@@ -313,7 +366,22 @@ mod detect_shell_type_tests {
             detect_shell_type(&PathBuf::from("powershell")),
             Some(ShellType::PowerShell)
         );
-        assert_eq!(detect_shell_type(&PathBuf::from("fish")), None);
+        assert_eq!(
+            detect_shell_type(&PathBuf::from("fish")),
+            Some(ShellType::Fish)
+        );
+        assert_eq!(
+            detect_shell_type(&PathBuf::from("/opt/homebrew/bin/fish")),
+            Some(ShellType::Fish)
+        );
+        assert_eq!(
+            detect_shell_type(&PathBuf::from("nu")),
+            Some(ShellType::Nushell)
+        );
+        assert_eq!(
+            detect_shell_type(&PathBuf::from("nu.exe")),
+            Some(ShellType::Nushell)
+        );
         assert_eq!(detect_shell_type(&PathBuf::from("other")), None);
         assert_eq!(
             detect_shell_type(&PathBuf::from("/bin/zsh")),
@@ -380,6 +448,11 @@ mod tests {
     #[test]
     #[cfg(target_os = "macos")]
     fn fish_fallback_to_zsh() {
+        if get_shell(ShellType::Fish, None).is_some() {
+            // Only meaningful on machines without fish installed.
+            return;
+        }
+
         let zsh_shell = default_user_shell_from_path(Some(PathBuf::from("/bin/fish")));
 
         let shell_path = zsh_shell.shell_path;
@@ -426,6 +499,8 @@ mod tests {
             assert!(shell_works(get_shell(ShellType::Zsh, None), cmd, false));
             assert!(shell_works(get_shell(ShellType::Bash, None), cmd, true));
             assert!(shell_works(get_shell(ShellType::Sh, None), cmd, true));
+            assert!(shell_works(get_shell(ShellType::Fish, None), cmd, false));
+            assert!(shell_works(get_shell(ShellType::Nushell, None), cmd, false));
         }
     }
 
@@ -487,6 +562,34 @@ mod tests {
             test_powershell_shell.derive_exec_args("echo hello", true),
             vec!["pwsh.exe", "-Command", "echo hello"]
         );
+
+        let test_fish_shell = Shell {
+            shell_type: ShellType::Fish,
+            shell_path: PathBuf::from("/usr/bin/fish"),
+            shell_snapshot: empty_shell_snapshot_receiver(),
+        };
+        assert_eq!(
+            test_fish_shell.derive_exec_args("echo hello", false),
+            vec!["/usr/bin/fish", "-c", "echo hello"]
+        );
+        assert_eq!(
+            test_fish_shell.derive_exec_args("echo hello", true),
+            vec!["/usr/bin/fish", "-lc", "echo hello"]
+        );
+
+        let test_nushell_shell = Shell {
+            shell_type: ShellType::Nushell,
+            shell_path: PathBuf::from("/usr/bin/nu"),
+            shell_snapshot: empty_shell_snapshot_receiver(),
+        };
+        assert_eq!(
+            test_nushell_shell.derive_exec_args("echo hello", false),
+            vec!["/usr/bin/nu", "-c", "echo hello"]
+        );
+        assert_eq!(
+            test_nushell_shell.derive_exec_args("echo hello", true),
+            vec!["/usr/bin/nu", "--login", "-c", "echo hello"]
+        );
     }
 
     #[tokio::test]
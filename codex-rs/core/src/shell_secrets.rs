@@ -0,0 +1,480 @@
+//! Secrets injected into shell environments and redacted from command output.
+//!
+//! The names come from `shell_environment_policy.secrets`; values are resolved
+//! once per session from the `codex secrets` store, preferring the scope of
+//! the session's environment (repository) over the global scope.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Mutex;
+
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsBackendKind;
+use codex_secrets::SecretsKeyStore;
+use codex_secrets::SecretsManager;
+use codex_secrets::environment_id_from_cwd;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::exec::ExecToolCallOutput;
+use crate::protocol::EventMsg;
+use crate::protocol::ExecOutputStream;
+use crate::tools::context::ToolOutput;
+use codex_protocol::models::FunctionCallOutputContentItem;
+
+/// Values shorter than this are still injected but never redacted, since
+/// replacing every occurrence of a one or two character string would mangle
+/// unrelated output.
+const MIN_REDACTED_LEN: usize = 4;
+
+#[derive(Default)]
+pub(crate) struct ShellSecrets {
+    /// `(name, value)` pairs, longest value first so overlapping secrets are
+    /// redacted as a whole.
+    entries: Vec<(String, String)>,
+    /// Streamed output held back because it may be the start of a secret
+    /// that continues in the next `ExecCommandOutputDelta` chunk, keyed by
+    /// call id and stream.
+    pending: Mutex<HashMap<(String, ExecOutputStream), Vec<u8>>>,
+}
+
+impl fmt::Debug for ShellSecrets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.entries.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+impl ShellSecrets {
+    /// Looks up `names` in the `backend` secrets store under `codex_home`,
+    /// whose key is kept in `key_store`. Returns the resolved secrets along
+    /// with a warning for anything that could not be resolved.
+    pub(crate) async fn resolve(
+        codex_home: &Path,
+        backend: SecretsBackendKind,
+        key_store: SecretsKeyStore,
+        cwd: &Path,
+        names: &[String],
+    ) -> (Self, Option<String>) {
+        if names.is_empty() {
+            return (Self::default(), None);
+        }
+
+        let mut invalid = Vec::new();
+        let mut valid = Vec::new();
+        for raw in names {
+            match SecretName::new(raw) {
+                Ok(name) => valid.push(name),
+                Err(_) => invalid.push(raw.clone()),
+            }
+        }
+
+        let codex_home = codex_home.to_path_buf();
+        let cwd = cwd.to_path_buf();
+        let lookup = valid.clone();
+        // The store is decrypted with a key from the OS keyring or an encrypted
+        // file, both of which block.
+        let resolved = tokio::task::spawn_blocking(move || {
+            let keyring_store = key_store.keyring_store(&codex_home);
+            let manager =
+                SecretsManager::new_with_keyring_store(codex_home, backend, keyring_store);
+            let scope = SecretScope::environment(environment_id_from_cwd(&cwd))?;
+            manager.resolve(&scope, &lookup)
+        })
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result);
+
+        let resolved = match resolved {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                warn!("failed to resolve shell secrets: {err:#}");
+                return (
+                    Self::default(),
+                    Some(format!(
+                        "Failed to load secrets for the shell environment: {err:#}"
+                    )),
+                );
+            }
+        };
+
+        let missing: Vec<String> = valid
+            .iter()
+            .filter(|name| !resolved.iter().any(|(found, _)| found == *name))
+            .map(ToString::to_string)
+            .chain(invalid)
+            .collect();
+        let warning = (!missing.is_empty()).then(|| {
+            format!(
+                "Secrets listed in shell_environment_policy.secrets were not found: {}. Set them with `codex secrets set`.",
+                missing.join(", ")
+            )
+        });
+
+        let entries = resolved
+            .into_iter()
+            .map(|(name, value)| (name.as_str().to_string(), value))
+            .collect();
+        (Self::from_entries(entries), warning)
+    }
+
+    fn from_entries(mut entries: Vec<(String, String)>) -> Self {
+        entries.sort_by(|(_, a), (_, b)| b.len().cmp(&a.len()));
+        Self {
+            entries,
+            pending: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds every resolved secret to `env`, overriding inherited values.
+    pub(crate) fn apply_env(&self, env: &mut HashMap<String, String>) {
+        for (name, value) in &self.entries {
+            env.insert(name.clone(), value.clone());
+        }
+    }
+
+    /// Replaces every occurrence of a secret value with `[REDACTED:<NAME>]`.
+    pub(crate) fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut redacted = Cow::Borrowed(text);
+        for (name, value) in &self.entries {
+            if value.len() < MIN_REDACTED_LEN || !redacted.contains(value.as_str()) {
+                continue;
+            }
+            let placeholder = format!("[REDACTED:{name}]");
+            redacted = Cow::Owned(redacted.replace(value.as_str(), &placeholder));
+        }
+        redacted
+    }
+
+    pub(crate) fn redact_in_place(&self, text: &mut String) {
+        if let Cow::Owned(redacted) = self.redact(text) {
+            *text = redacted;
+        }
+    }
+
+    pub(crate) fn redact_exec_output(&self, output: &mut ExecToolCallOutput) {
+        if self.is_empty() {
+            return;
+        }
+        self.redact_in_place(&mut output.stdout.text);
+        self.redact_in_place(&mut output.stderr.text);
+        self.redact_in_place(&mut output.aggregated_output.text);
+    }
+
+    pub(crate) fn redact_tool_output(&self, output: &mut ToolOutput) {
+        if self.is_empty() {
+            return;
+        }
+        match output {
+            ToolOutput::Function {
+                content,
+                content_items,
+                ..
+            } => {
+                self.redact_in_place(content);
+                for item in content_items.iter_mut().flatten() {
+                    if let FunctionCallOutputContentItem::InputText { text } = item {
+                        self.redact_in_place(text);
+                    }
+                }
+            }
+            ToolOutput::Mcp { result: Ok(result) } => {
+                for block in &mut result.content {
+                    self.redact_json(block);
+                }
+                if let Some(structured) = &mut result.structured_content {
+                    self.redact_json(structured);
+                }
+            }
+            ToolOutput::Mcp { result: Err(err) } => self.redact_in_place(err),
+        }
+    }
+
+    fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => self.redact_in_place(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_json(item)),
+            Value::Object(map) => map.values_mut().for_each(|item| self.redact_json(item)),
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+
+    /// Redacts command output carried by `msg` before it is persisted to the
+    /// rollout or delivered to clients.
+    ///
+    /// Output deltas are redacted as a stream: a chunk ending in what could be
+    /// the start of a secret keeps that tail back until the next chunk. Any
+    /// tail still held when the command ends is dropped, since the end event
+    /// carries the complete (redacted) output.
+    pub(crate) fn redact_event(&self, msg: &mut EventMsg) {
+        if self.is_empty() {
+            return;
+        }
+        match msg {
+            EventMsg::ExecCommandEnd(event) => {
+                self.pending_chunks()
+                    .retain(|(call_id, _), _| call_id != &event.call_id);
+                self.redact_in_place(&mut event.stdout);
+                self.redact_in_place(&mut event.stderr);
+                self.redact_in_place(&mut event.aggregated_output);
+                self.redact_in_place(&mut event.formatted_output);
+            }
+            EventMsg::ExecCommandOutputDelta(event) => {
+                let chunk = std::mem::take(&mut event.chunk);
+                event.chunk = self.redact_chunk(&event.call_id, event.stream, chunk);
+            }
+            _ => {}
+        }
+    }
+
+    fn pending_chunks(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<(String, ExecOutputStream), Vec<u8>>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn redact_chunk(&self, call_id: &str, stream: ExecOutputStream, chunk: Vec<u8>) -> Vec<u8> {
+        let mut pending = self.pending_chunks();
+        let key = (call_id.to_string(), stream);
+        let mut bytes = pending.remove(&key).unwrap_or_default();
+        bytes.extend(chunk);
+
+        // A chunk may end in the middle of a UTF-8 sequence; anything that is
+        // not text at all is passed through untouched.
+        let text_len = match std::str::from_utf8(&bytes) {
+            Ok(_) => bytes.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => return bytes,
+        };
+        let incomplete = bytes.split_off(text_len);
+        let text = match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(err) => {
+                let mut bytes = err.into_bytes();
+                bytes.extend(incomplete);
+                return bytes;
+            }
+        };
+
+        let held_start = self.held_back_start(&text);
+        let mut held = text.as_bytes()[held_start..].to_vec();
+        held.extend(incomplete);
+        if !held.is_empty() {
+            pending.insert(key, held);
+        }
+        self.redact(&text[..held_start]).into_owned().into_bytes()
+    }
+
+    /// Where the part of `text` that may continue into the next chunk starts:
+    /// the longest suffix that is a proper prefix of a secret, widened so that
+    /// it does not split a secret that already matches.
+    fn held_back_start(&self, text: &str) -> usize {
+        let redacted = || {
+            self.entries
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .filter(|value| value.len() >= MIN_REDACTED_LEN)
+        };
+        let mut start = redacted()
+            .filter_map(|value| {
+                (1..value.len().min(text.len() + 1))
+                    .rev()
+                    .filter(|len| value.is_char_boundary(*len))
+                    .find(|len| text.ends_with(&value[..*len]))
+            })
+            .max()
+            .map_or(text.len(), |len| text.len() - len);
+        loop {
+            let widened = redacted()
+                .flat_map(|value| {
+                    text.match_indices(value)
+                        .map(|(at, found)| (at, at + found.len()))
+                })
+                .filter(|(at, end)| *at < start && start < *end)
+                .map(|(at, _)| at)
+                .min();
+            match widened {
+                Some(at) => start = at,
+                None => return start,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ExecCommandOutputDeltaEvent;
+    use codex_protocol::mcp::CallToolResult;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn secrets() -> ShellSecrets {
+        ShellSecrets::from_entries(vec![
+            ("SHORT".to_string(), "abc".to_string()),
+            ("GITHUB_TOKEN".to_string(), "ghp_secret".to_string()),
+            ("PREFIXED".to_string(), "ghp_secret_extended".to_string()),
+        ])
+    }
+
+    #[test]
+    fn redacts_longest_secret_first() {
+        let secrets = secrets();
+        assert_eq!(
+            secrets.redact("token=ghp_secret_extended other=ghp_secret abc"),
+            "token=[REDACTED:PREFIXED] other=[REDACTED:GITHUB_TOKEN] abc"
+        );
+    }
+
+    #[test]
+    fn leaves_text_without_secrets_borrowed() {
+        assert!(matches!(
+            secrets().redact("nothing to see"),
+            Cow::Borrowed("nothing to see")
+        ));
+    }
+
+    #[test]
+    fn apply_env_overrides_inherited_values() {
+        let mut env = HashMap::from([("GITHUB_TOKEN".to_string(), "inherited".to_string())]);
+        secrets().apply_env(&mut env);
+        assert_eq!(
+            env.get("GITHUB_TOKEN").map(String::as_str),
+            Some("ghp_secret")
+        );
+        assert_eq!(env.len(), 3);
+    }
+
+    fn delta(secrets: &ShellSecrets, chunk: &[u8]) -> Vec<u8> {
+        let mut msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+            call_id: "call".to_string(),
+            stream: ExecOutputStream::Stdout,
+            chunk: chunk.to_vec(),
+        });
+        secrets.redact_event(&mut msg);
+        let EventMsg::ExecCommandOutputDelta(event) = msg else {
+            panic!("expected an output delta");
+        };
+        event.chunk
+    }
+
+    fn stream(secrets: &ShellSecrets, chunks: &[&str]) -> Vec<String> {
+        chunks
+            .iter()
+            .map(|chunk| String::from_utf8(delta(secrets, chunk.as_bytes())).expect("utf-8 chunk"))
+            .collect()
+    }
+
+    #[test]
+    fn redacts_secrets_split_across_output_deltas() {
+        let secrets = secrets();
+        assert_eq!(
+            stream(&secrets, &["token=ghp_se", "cret done\n"]),
+            vec![
+                "token=".to_string(),
+                "[REDACTED:GITHUB_TOKEN] done\n".to_string()
+            ]
+        );
+        assert_eq!(
+            stream(&secrets, &["ghp_secret", "_extended"]),
+            vec![String::new(), "[REDACTED:PREFIXED]".to_string()]
+        );
+        assert_eq!(
+            stream(&secrets, &["gh", "p_secre", "t_extended!"]),
+            vec![
+                String::new(),
+                String::new(),
+                "[REDACTED:PREFIXED]!".to_string()
+            ]
+        );
+        assert_eq!(
+            stream(&secrets, &["ghp_", "x"]),
+            vec![String::new(), "ghp_x".to_string()]
+        );
+    }
+
+    #[test]
+    fn output_deltas_keep_split_utf8_sequences_together() {
+        let secrets = secrets();
+        assert_eq!(delta(&secrets, &[b'a', 0xc3]), b"a".to_vec());
+        assert_eq!(
+            delta(&secrets, &[0xa9, b'!']),
+            "\u{e9}!".as_bytes().to_vec()
+        );
+    }
+
+    #[test]
+    fn redacts_content_items_and_mcp_results() {
+        let secrets = secrets();
+        let mut function = ToolOutput::Function {
+            content: "ghp_secret".to_string(),
+            content_items: Some(vec![FunctionCallOutputContentItem::InputText {
+                text: "token ghp_secret".to_string(),
+            }]),
+            success: Some(true),
+        };
+        secrets.redact_tool_output(&mut function);
+        let ToolOutput::Function {
+            content,
+            content_items,
+            ..
+        } = function
+        else {
+            panic!("expected function output");
+        };
+        assert_eq!(content, "[REDACTED:GITHUB_TOKEN]");
+        assert_eq!(
+            content_items,
+            Some(vec![FunctionCallOutputContentItem::InputText {
+                text: "token [REDACTED:GITHUB_TOKEN]".to_string(),
+            }])
+        );
+
+        let mut mcp = ToolOutput::Mcp {
+            result: Ok(CallToolResult {
+                content: vec![json!({"type": "text", "text": "ghp_secret"})],
+                structured_content: Some(json!({"tokens": ["ghp_secret_extended"]})),
+                is_error: None,
+                meta: None,
+            }),
+        };
+        secrets.redact_tool_output(&mut mcp);
+        let ToolOutput::Mcp { result: Ok(result) } = mcp else {
+            panic!("expected MCP output");
+        };
+        assert_eq!(
+            result.content,
+            vec![json!({"type": "text", "text": "[REDACTED:GITHUB_TOKEN]"})]
+        );
+        assert_eq!(
+            result.structured_content,
+            Some(json!({"tokens": ["[REDACTED:PREFIXED]"]}))
+        );
+
+        let mut failed = ToolOutput::Mcp {
+            result: Err("bad token ghp_secret".to_string()),
+        };
+        secrets.redact_tool_output(&mut failed);
+        let ToolOutput::Mcp { result: Err(err) } = failed else {
+            panic!("expected MCP error");
+        };
+        assert_eq!(err, "bad token [REDACTED:GITHUB_TOKEN]");
+    }
+
+    #[test]
+    fn debug_output_omits_values() {
+        let debug = format!("{:?}", secrets());
+        assert!(!debug.contains("ghp_secret"));
+        assert!(debug.contains("GITHUB_TOKEN"));
+    }
+}
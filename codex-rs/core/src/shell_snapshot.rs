@@ -66,6 +66,8 @@ impl ShellSnapshot {
         // File to store the snapshot
         let extension = match shell.shell_type {
             ShellType::PowerShell => "ps1",
+            ShellType::Fish => "fish",
+            ShellType::Nushell => "nu",
             _ => "sh",
         };
         let path = codex_home
@@ -150,6 +152,8 @@ async fn capture_snapshot(shell: &Shell) -> Result<String> {
         ShellType::Bash => run_shell_script(shell, &bash_snapshot_script()).await,
         ShellType::Sh => run_shell_script(shell, &sh_snapshot_script()).await,
         ShellType::PowerShell => run_shell_script(shell, powershell_snapshot_script()).await,
+        ShellType::Fish => run_shell_script(shell, &fish_snapshot_script()).await,
+        ShellType::Nushell => run_shell_script(shell, &nushell_snapshot_script()).await,
         ShellType::Cmd => bail!("Shell snapshotting is not yet supported for {shell_type:?}"),
     }
 }
@@ -164,13 +168,30 @@ fn strip_snapshot_preamble(snapshot: &str) -> Result<String> {
 }
 
 async fn validate_snapshot(shell: &Shell, snapshot_path: &Path) -> Result<()> {
-    let snapshot_path_display = snapshot_path.display();
-    let script = format!("set -e; . \"{snapshot_path_display}\"");
+    let script = source_snapshot_script(&shell.shell_type, snapshot_path);
     run_script_with_timeout(shell, &script, SNAPSHOT_TIMEOUT, false)
         .await
         .map(|_| ())
 }
 
+/// Returns the script that loads `snapshot_path` into the given shell.
+pub(crate) fn source_snapshot_script(shell_type: &ShellType, snapshot_path: &Path) -> String {
+    let snapshot_path_display = snapshot_path.display();
+    match shell_type {
+        ShellType::Fish => {
+            let escaped = snapshot_path_display
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('\'', "\\'");
+            format!("source '{escaped}'")
+        }
+        // Nushell resolves `source` at parse time, so the path must be a
+        // literal; raw strings avoid any escaping concerns.
+        ShellType::Nushell => format!("source r#'{snapshot_path_display}'#"),
+        _ => format!("set -e; . \"{snapshot_path_display}\""),
+    }
+}
+
 async fn run_shell_script(shell: &Shell, script: &str) -> Result<String> {
     run_script_with_timeout(shell, script, SNAPSHOT_TIMEOUT, true).await
 }
@@ -371,6 +392,64 @@ fi
     script.replace("EXCLUDED_EXPORTS", &excluded)
 }
 
+fn fish_snapshot_script() -> String {
+    let excluded = EXCLUDED_EXPORT_VARS.join(" ");
+    let script = r##"echo '# Snapshot file'
+echo '# Functions'
+for name in (functions --names)
+    # Functions shipped with fish are autoloaded anyway; only capture the
+    # user's own definitions.
+    set -l file (functions --details -- $name)
+    if string match -q -- "$__fish_data_dir/*" $file
+        continue
+    end
+    functions -- $name
+end
+echo ''
+echo '# setopts 0'
+echo ''
+# Abbreviations only expand interactively, so replay plain ones as aliases.
+set -l aliases (abbr --show | string replace -rf -- '^abbr -a (?:-[gU] )?-- (\S+) (.*)$' 'alias $1 $2')
+echo "# aliases "(count $aliases)
+printf '%s\n' $aliases
+echo ''
+set -l exports
+for name in (set --names --export)
+    if contains -- $name EXCLUDED_EXPORTS
+        continue
+    end
+    if not string match -qr -- '^[A-Za-z_][A-Za-z0-9_]*$' $name
+        continue
+    end
+    set -l value (string escape -- $$name)
+    set -a exports "set -gx $name $value"
+end
+echo "# exports "(count $exports)
+printf '%s\n' $exports
+"##;
+    script.replace("EXCLUDED_EXPORTS", &excluded)
+}
+
+fn nushell_snapshot_script() -> String {
+    let excluded = EXCLUDED_EXPORT_VARS.join(" ");
+    let script = r##"print '# Snapshot file'
+print '# setopts 0'
+print '# aliases 0'
+let exports = ($env
+    | transpose name value
+    | where {|row|
+        ($row.name not-in [EXCLUDED_EXPORTS])
+            and ($row.name =~ '^[A-Za-z_][A-Za-z0-9_]*$')
+            and (($row.value | describe) in ['string' 'list<string>'])
+    })
+print $"# exports ($exports | length)"
+if ($exports | is-not-empty) {
+    print $"load-env ($exports | transpose --header-row --as-record | to nuon)"
+}
+"##;
+    script.replace("EXCLUDED_EXPORTS", &excluded)
+}
+
 fn powershell_snapshot_script() -> &'static str {
     r##"$ErrorActionPreference = 'Stop'
 Write-Output '# Snapshot file'
@@ -723,6 +802,35 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn fish_snapshot_includes_sections() -> Result<()> {
+        if get_shell(ShellType::Fish, None).is_none() {
+            return Ok(());
+        }
+        let snapshot = get_snapshot(ShellType::Fish).await?;
+        assert_posix_snapshot_sections(&snapshot);
+        assert!(snapshot.contains("set -gx PATH "));
+        Ok(())
+    }
+
+    #[test]
+    fn source_snapshot_script_matches_shell_syntax() {
+        let path = Path::new("/tmp/it's/snapshot");
+        assert_eq!(
+            source_snapshot_script(&ShellType::Bash, path),
+            "set -e; . \"/tmp/it's/snapshot\""
+        );
+        assert_eq!(
+            source_snapshot_script(&ShellType::Fish, path),
+            "source '/tmp/it\\'s/snapshot'"
+        );
+        assert_eq!(
+            source_snapshot_script(&ShellType::Nushell, path),
+            "source r#'/tmp/it's/snapshot'#"
+        );
+    }
+
     #[cfg(target_os = "windows")]
     #[ignore]
     #[tokio::test]
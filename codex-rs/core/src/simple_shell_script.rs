@@ -0,0 +1,192 @@
+//! A small tokenizer for the subset of fish and Nushell scripts that consist
+//! of plain commands joined by `;`, `&&`, `||` and `|`. Anything richer
+//! (substitutions, variables, redirections, blocks) is rejected so callers
+//! fall back to treating the script as opaque, mirroring what
+//! [`crate::bash::try_parse_word_only_commands_sequence`] does for bash.
+
+/// Describes the parts of a shell's syntax the tokenizer needs to know about.
+pub(crate) struct ScriptSyntax {
+    /// Words that join commands when they start a command, e.g. fish's `and`.
+    pub keyword_connectors: &'static [&'static str],
+    /// Words that introduce control flow or bindings; scripts using them are
+    /// not plain command sequences.
+    pub rejected_keywords: &'static [&'static str],
+    /// Whether `\'` and `\\` are escapes inside single quotes.
+    pub single_quote_escapes: bool,
+    /// Whether `$` expands inside double quotes.
+    pub interpolating_double_quotes: bool,
+}
+
+/// Characters that carry meaning outside of quotes and make a script more
+/// than a plain command sequence.
+const REJECTED_CHARS: &[char] = &['$', '(', ')', '{', '}', '[', ']', '<', '>', '`', '^', '#'];
+
+/// Splits `script` into plain commands. Returns `None` when the script uses
+/// anything beyond words, quotes and the safe connectors.
+pub(crate) fn split_plain_commands(
+    script: &str,
+    syntax: &ScriptSyntax,
+) -> Option<Vec<Vec<String>>> {
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word: Option<String> = None;
+    // Set after a connector that requires a following command (`&&`, `|`).
+    let mut expects_command = false;
+    let mut chars = script.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ' ' | '\t' => end_word(&mut word, &mut words),
+            '\n' | ';' | '|' | '&' => {
+                end_word(&mut word, &mut words);
+                let binary = match ch {
+                    '|' => {
+                        chars.next_if_eq(&'|');
+                        true
+                    }
+                    '&' => {
+                        // A lone `&` backgrounds the command.
+                        chars.next_if_eq(&'&')?;
+                        true
+                    }
+                    _ => false,
+                };
+                if words.is_empty() {
+                    if binary || expects_command {
+                        return None;
+                    }
+                } else {
+                    commands.push(end_command(&mut words, syntax)?);
+                }
+                expects_command = binary;
+            }
+            '\'' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        '\\' if syntax.single_quote_escapes
+                            && matches!(chars.peek(), Some('\'' | '\\')) =>
+                        {
+                            current.push(chars.next()?);
+                        }
+                        c => current.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '$' if syntax.interpolating_double_quotes => return None,
+                        '\\' if matches!(chars.peek(), Some('"' | '\\' | '$')) => {
+                            current.push(chars.next()?);
+                        }
+                        c => current.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                let escaped = chars.next()?;
+                if escaped != '\n' {
+                    word.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            c if REJECTED_CHARS.contains(&c) => return None,
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    end_word(&mut word, &mut words);
+    if words.is_empty() {
+        if expects_command {
+            return None;
+        }
+    } else {
+        commands.push(end_command(&mut words, syntax)?);
+    }
+    (!commands.is_empty()).then_some(commands)
+}
+
+fn end_word(word: &mut Option<String>, words: &mut Vec<String>) {
+    if let Some(word) = word.take() {
+        words.push(word);
+    }
+}
+
+fn end_command(words: &mut Vec<String>, syntax: &ScriptSyntax) -> Option<Vec<String>> {
+    let mut command = std::mem::take(words);
+    while command
+        .first()
+        .is_some_and(|first| syntax.keyword_connectors.contains(&first.as_str()))
+    {
+        command.remove(0);
+    }
+    let first = command.first()?;
+    if syntax.rejected_keywords.contains(&first.as_str()) {
+        return None;
+    }
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SYNTAX: ScriptSyntax = ScriptSyntax {
+        keyword_connectors: &["and", "or"],
+        rejected_keywords: &["if", "end"],
+        single_quote_escapes: true,
+        interpolating_double_quotes: true,
+    };
+
+    fn split(script: &str) -> Option<Vec<Vec<String>>> {
+        split_plain_commands(script, &SYNTAX)
+    }
+
+    fn commands(expected: &[&[&str]]) -> Option<Vec<Vec<String>>> {
+        Some(
+            expected
+                .iter()
+                .map(|words| words.iter().map(ToString::to_string).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn splits_on_connectors_and_keywords() {
+        assert_eq!(
+            split("ls -la; and cat 'a b.txt' | wc -l && echo \"hi there\""),
+            commands(&[
+                &["ls", "-la"],
+                &["cat", "a b.txt"],
+                &["wc", "-l"],
+                &["echo", "hi there"],
+            ])
+        );
+        assert_eq!(
+            split("cat it\\'s\nrg foo || true;"),
+            commands(&[&["cat", "it's"], &["rg", "foo"], &["true"]])
+        );
+    }
+
+    #[test]
+    fn rejects_non_plain_scripts() {
+        for script in [
+            "echo $HOME",
+            "echo \"$HOME\"",
+            "cat (ls)",
+            "ls > out.txt",
+            "sleep 1 &",
+            "ls &&",
+            "| wc",
+            "if test -f x; cat x; end",
+            "echo 'unterminated",
+            "",
+        ] {
+            assert_eq!(split(script), None, "script: {script:?}");
+        }
+    }
+}
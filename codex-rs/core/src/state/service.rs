@@ -4,9 +4,13 @@ use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
 use crate::analytics_client::AnalyticsEventsClient;
+use crate::approval_memory::ApprovalMemory;
+use crate::config::approval_rules::ApprovalRules;
 use crate::exec_policy::ExecPolicyManager;
+use crate::lsp::LspManager;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::shell_secrets::ShellSecrets;
 use crate::skills::SkillsManager;
 use crate::state_db::StateDbHandle;
 use crate::tools::sandboxing::ApprovalStore;
@@ -14,6 +18,7 @@ use crate::transport_manager::TransportManager;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotifier;
 use codex_otel::OtelManager;
+use codex_symbol_index::SharedSymbolIndex;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio_util::sync::CancellationToken;
@@ -32,8 +37,14 @@ pub(crate) struct SessionServices {
     pub(crate) models_manager: Arc<ModelsManager>,
     pub(crate) otel_manager: OtelManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) approval_rules: ApprovalRules,
+    pub(crate) approval_memory: ApprovalMemory,
     pub(crate) skills_manager: Arc<SkillsManager>,
     pub(crate) agent_control: AgentControl,
     pub(crate) state_db: Option<StateDbHandle>,
     pub(crate) transport_manager: TransportManager,
+    pub(crate) shell_secrets: ShellSecrets,
+    pub(crate) lsp_manager: LspManager,
+    /// Background symbol index, when the `symbol_index` feature is enabled.
+    pub(crate) symbol_index: Option<SharedSymbolIndex>,
 }
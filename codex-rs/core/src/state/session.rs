@@ -1,6 +1,7 @@
 //! Session-wide mutable state.
 
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::PinnedMessage;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -10,6 +11,7 @@ use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::truncate::TruncationPolicy;
+use codex_utils_tokenizer::Tokenizer;
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
 pub(crate) struct SessionState {
@@ -19,6 +21,8 @@ pub(crate) struct SessionState {
     pub(crate) server_reasoning_included: bool,
     pub(crate) dependency_env: HashMap<String, String>,
     pub(crate) mcp_dependency_prompted: HashSet<String>,
+    /// Messages carried verbatim across compaction, oldest first.
+    pub(crate) pinned_messages: Vec<PinnedMessage>,
     /// Whether the session's initial context has been seeded into history.
     ///
     /// TODO(owen): This is a temporary solution to avoid updating a thread's updated_at
@@ -37,17 +41,22 @@ impl SessionState {
             server_reasoning_included: false,
             dependency_env: HashMap::new(),
             mcp_dependency_prompted: HashSet::new(),
+            pinned_messages: Vec::new(),
             initial_context_seeded: false,
         }
     }
 
     // History helpers
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
-    where
+    pub(crate) fn record_items<I>(
+        &mut self,
+        items: I,
+        policy: TruncationPolicy,
+        tokenizer: Option<&Tokenizer>,
+    ) where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
-        self.history.record_items(items, policy);
+        self.history.record_items(items, policy, tokenizer);
     }
 
     pub(crate) fn clone_history(&self) -> ContextManager {
@@ -92,9 +101,13 @@ impl SessionState {
         self.history.set_token_usage_full(context_window);
     }
 
-    pub(crate) fn get_total_token_usage(&self, server_reasoning_included: bool) -> i64 {
+    pub(crate) fn get_total_token_usage(
+        &self,
+        server_reasoning_included: bool,
+        tokenizer: Option<&Tokenizer>,
+    ) -> i64 {
         self.history
-            .get_total_token_usage(server_reasoning_included)
+            .get_total_token_usage(server_reasoning_included, tokenizer)
     }
 
     pub(crate) fn set_server_reasoning_included(&mut self, included: bool) {
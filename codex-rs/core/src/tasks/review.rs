@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use codex_git::ChangedLines;
 use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
@@ -13,24 +14,42 @@ use codex_protocol::protocol::ExitedReviewModeEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ReviewOutputEvent;
 use tokio_util::sync::CancellationToken;
+use tracing::info;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::codex_delegate::run_codex_thread_one_shot;
+use crate::review_baseline::ReviewBaseline;
 use crate::review_format::format_review_findings_block;
 use crate::review_format::render_review_output_text;
+use crate::review_prompts::retain_changed_line_findings;
 use crate::state::TaskKind;
 use codex_protocol::user_input::UserInput;
 
 use super::SessionTask;
 use super::SessionTaskContext;
 
-#[derive(Clone, Copy)]
-pub(crate) struct ReviewTask;
+#[derive(Clone, Default)]
+pub(crate) struct ReviewTask {
+    /// When set, findings outside these lines are dropped from the output.
+    changed_lines: Option<ChangedLines>,
+    /// When set, findings already accepted in this baseline are dropped.
+    baseline: Option<ReviewBaseline>,
+}
 
 impl ReviewTask {
     pub(crate) fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub(crate) fn with_changed_lines(mut self, changed_lines: Option<ChangedLines>) -> Self {
+        self.changed_lines = changed_lines;
+        self
+    }
+
+    pub(crate) fn with_baseline(mut self, baseline: Option<ReviewBaseline>) -> Self {
+        self.baseline = baseline;
+        self
     }
 }
 
@@ -54,7 +73,7 @@ impl SessionTask for ReviewTask {
             .counter("codex.task.review", 1, &[]);
 
         // Start sub-codex conversation and get the receiver for events.
-        let output = match start_review_conversation(
+        let mut output = match start_review_conversation(
             session.clone(),
             ctx.clone(),
             input,
@@ -65,6 +84,16 @@ impl SessionTask for ReviewTask {
             Some(receiver) => process_review_events(session.clone(), ctx.clone(), receiver).await,
             None => None,
         };
+        if let (Some(output), Some(changed_lines)) = (output.as_mut(), self.changed_lines.as_ref())
+        {
+            retain_changed_line_findings(output, changed_lines, ctx.cwd.as_path());
+        }
+        if let (Some(output), Some(baseline)) = (output.as_mut(), self.baseline.as_ref()) {
+            let suppressed = baseline.suppress(output, ctx.cwd.as_path());
+            if suppressed > 0 {
+                info!("suppressed {suppressed} review findings present in the baseline");
+            }
+        }
         if !cancellation_token.is_cancelled() {
             exit_review_mode(session.clone_session(), output.clone(), ctx.clone()).await;
         }
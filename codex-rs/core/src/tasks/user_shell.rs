@@ -21,6 +21,7 @@ use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
 use crate::protocol::ExecCommandSource;
+use crate::protocol::ExecCommandStatus;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::TurnStartedEvent;
 use crate::sandboxing::ExecEnv;
@@ -102,13 +103,15 @@ impl SessionTask for UserShellCommandTask {
             )
             .await;
 
+        let mut env = create_env(
+            &turn_context.shell_environment_policy,
+            Some(session.conversation_id),
+        );
+        session.services.shell_secrets.apply_env(&mut env);
         let exec_env = ExecEnv {
             command: exec_command.clone(),
             cwd: cwd.clone(),
-            env: create_env(
-                &turn_context.shell_environment_policy,
-                Some(session.conversation_id),
-            ),
+            env,
             // TODO(zhao-oai): Now that we have ExecExpiration::Cancellation, we
             // should use that instead of an "arbitrarily large" timeout here.
             expiration: USER_SHELL_TIMEOUT_MS.into(),
@@ -167,11 +170,16 @@ impl SessionTask for UserShellCommandTask {
                             exit_code: -1,
                             duration: Duration::ZERO,
                             formatted_output: aborted_message,
+                            status: ExecCommandStatus::Failed,
                         }),
                     )
                     .await;
             }
-            Ok(Ok(output)) => {
+            Ok(Ok(mut output)) => {
+                session
+                    .services
+                    .shell_secrets
+                    .redact_exec_output(&mut output);
                 session
                     .send_event(
                         turn_context.as_ref(),
@@ -192,7 +200,13 @@ impl SessionTask for UserShellCommandTask {
                             formatted_output: format_exec_output_str(
                                 &output,
                                 turn_context.truncation_policy,
+                                turn_context.tokenizer.as_ref(),
                             ),
+                            status: if output.timed_out {
+                                ExecCommandStatus::TimedOut
+                            } else {
+                                ExecCommandStatus::Exited
+                            },
                         }),
                     )
                     .await;
@@ -237,7 +251,9 @@ impl SessionTask for UserShellCommandTask {
                             formatted_output: format_exec_output_str(
                                 &exec_output,
                                 turn_context.truncation_policy,
+                                turn_context.tokenizer.as_ref(),
                             ),
+                            status: ExecCommandStatus::Failed,
                         }),
                     )
                     .await;
@@ -26,6 +26,7 @@ use codex_protocol::protocol::McpServerRefreshConfig;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
+use codex_symbol_index::SymbolIndexes;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -62,6 +63,8 @@ pub(crate) struct ThreadManagerState {
     auth_manager: Arc<AuthManager>,
     models_manager: Arc<ModelsManager>,
     skills_manager: Arc<SkillsManager>,
+    /// Symbol indexes shared by every thread and by the UI.
+    symbol_indexes: SymbolIndexes,
     session_source: SessionSource,
     #[cfg(any(test, feature = "test-support"))]
     #[allow(dead_code)]
@@ -85,6 +88,7 @@ impl ThreadManager {
                     auth_manager.clone(),
                 )),
                 skills_manager: Arc::new(SkillsManager::new(codex_home)),
+                symbol_indexes: SymbolIndexes::new(),
                 auth_manager,
                 session_source,
                 #[cfg(any(test, feature = "test-support"))]
@@ -126,6 +130,7 @@ impl ThreadManager {
                     provider,
                 )),
                 skills_manager: Arc::new(SkillsManager::new(codex_home)),
+                symbol_indexes: SymbolIndexes::new(),
                 auth_manager,
                 session_source: SessionSource::Exec,
                 #[cfg(any(test, feature = "test-support"))]
@@ -143,6 +148,12 @@ impl ThreadManager {
         self.state.skills_manager.clone()
     }
 
+    /// Symbol indexes shared with the threads of this manager, so a UI searching
+    /// symbols reuses the index the threads already maintain.
+    pub fn symbol_indexes(&self) -> SymbolIndexes {
+        self.state.symbol_indexes.clone()
+    }
+
     pub fn get_models_manager(&self) -> Arc<ModelsManager> {
         self.state.models_manager.clone()
     }
@@ -158,8 +169,8 @@ impl ThreadManager {
             .await
     }
 
-    pub fn list_collaboration_modes(&self) -> Vec<CollaborationModeMask> {
-        self.state.models_manager.list_collaboration_modes()
+    pub fn list_collaboration_modes(&self, config: &Config) -> Vec<CollaborationModeMask> {
+        self.state.models_manager.list_collaboration_modes(config)
     }
 
     pub async fn list_thread_ids(&self) -> Vec<ThreadId> {
@@ -298,6 +309,10 @@ impl ThreadManager {
 }
 
 impl ThreadManagerState {
+    pub(crate) fn symbol_indexes(&self) -> &SymbolIndexes {
+        &self.symbol_indexes
+    }
+
     /// Fetch a thread by ID or return ThreadNotFound.
     pub(crate) async fn get_thread(&self, thread_id: ThreadId) -> CodexResult<Arc<CodexThread>> {
         let threads = self.threads.read().await;
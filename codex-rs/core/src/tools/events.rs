@@ -9,6 +9,7 @@ use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
 use crate::protocol::ExecCommandSource;
+use crate::protocol::ExecCommandStatus;
 use crate::protocol::FileChange;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
@@ -56,6 +57,8 @@ pub(crate) enum ToolEventStage {
 pub(crate) enum ToolEventFailure {
     Output(ExecToolCallOutput),
     Message(String),
+    /// The user or the approval policy rejected the call before it ran.
+    Rejected(String),
 }
 
 pub(crate) async fn emit_exec_command_begin(
@@ -213,7 +216,9 @@ impl ToolEmitter {
             }
             (
                 Self::ApplyPatch { changes, .. },
-                ToolEventStage::Failure(ToolEventFailure::Message(message)),
+                ToolEventStage::Failure(
+                    ToolEventFailure::Message(message) | ToolEventFailure::Rejected(message),
+                ),
             ) => {
                 emit_patch_end(
                     ctx,
@@ -261,10 +266,16 @@ impl ToolEmitter {
         ctx: ToolEventCtx<'_>,
     ) -> String {
         match self {
-            Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
-            }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            Self::Shell { freeform: true, .. } => super::format_exec_output_for_model_freeform(
+                output,
+                ctx.turn.truncation_policy,
+                ctx.turn.tokenizer.as_ref(),
+            ),
+            _ => super::format_exec_output_for_model_structured(
+                output,
+                ctx.turn.truncation_policy,
+                ctx.turn.tokenizer.as_ref(),
+            ),
         }
     }
 
@@ -311,7 +322,7 @@ impl ToolEmitter {
                 } else {
                     msg
                 };
-                let event = ToolEventStage::Failure(ToolEventFailure::Message(normalized.clone()));
+                let event = ToolEventStage::Failure(ToolEventFailure::Rejected(normalized.clone()));
                 let result = Err(FunctionCallError::RespondToModel(normalized));
                 (event, result)
             }
@@ -357,6 +368,22 @@ struct ExecCommandResult {
     exit_code: i32,
     duration: Duration,
     formatted_output: String,
+    status: ExecCommandStatus,
+}
+
+impl ExecCommandResult {
+    /// Result for a command that never produced output of its own.
+    fn not_run(message: String, status: ExecCommandStatus) -> Self {
+        Self {
+            stdout: String::new(),
+            stderr: message.clone(),
+            aggregated_output: message.clone(),
+            exit_code: -1,
+            duration: Duration::ZERO,
+            formatted_output: message,
+            status,
+        }
+    }
 }
 
 async fn emit_exec_stage(
@@ -385,20 +412,25 @@ async fn emit_exec_stage(
                 aggregated_output: output.aggregated_output.text.clone(),
                 exit_code: output.exit_code,
                 duration: output.duration,
-                formatted_output: format_exec_output_str(&output, ctx.turn.truncation_policy),
+                formatted_output: format_exec_output_str(
+                    &output,
+                    ctx.turn.truncation_policy,
+                    ctx.turn.tokenizer.as_ref(),
+                ),
+                status: if output.timed_out {
+                    ExecCommandStatus::TimedOut
+                } else {
+                    ExecCommandStatus::Exited
+                },
             };
             emit_exec_end(ctx, exec_input, exec_result).await;
         }
         ToolEventStage::Failure(ToolEventFailure::Message(message)) => {
-            let text = message.to_string();
-            let exec_result = ExecCommandResult {
-                stdout: String::new(),
-                stderr: text.clone(),
-                aggregated_output: text.clone(),
-                exit_code: -1,
-                duration: Duration::ZERO,
-                formatted_output: text,
-            };
+            let exec_result = ExecCommandResult::not_run(message, ExecCommandStatus::Failed);
+            emit_exec_end(ctx, exec_input, exec_result).await;
+        }
+        ToolEventStage::Failure(ToolEventFailure::Rejected(message)) => {
+            let exec_result = ExecCommandResult::not_run(message, ExecCommandStatus::Declined);
             emit_exec_end(ctx, exec_input, exec_result).await;
         }
     }
@@ -427,6 +459,7 @@ async fn emit_exec_end(
                 exit_code: exec_result.exit_code,
                 duration: exec_result.duration,
                 formatted_output: exec_result.formatted_output,
+                status: exec_result.status,
             }),
         )
         .await;
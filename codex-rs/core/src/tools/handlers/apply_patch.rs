@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::path::PathBuf;
 
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
@@ -59,6 +60,28 @@ fn to_abs_path(cwd: &Path, path: &Path) -> Option<AbsolutePathBuf> {
     AbsolutePathBuf::resolve_path_against_base(path, cwd).ok()
 }
 
+/// Appends the errors and warnings language servers report for the edited
+/// files, so the model sees what its patch broke without another tool call.
+async fn append_lsp_diagnostics(
+    session: &Session,
+    turn: &TurnContext,
+    edited_paths: &[AbsolutePathBuf],
+    content: String,
+) -> String {
+    let lsp = &session.services.lsp_manager;
+    if lsp.is_empty() {
+        return content;
+    }
+    let paths: Vec<PathBuf> = edited_paths
+        .iter()
+        .map(AbsolutePathBuf::to_path_buf)
+        .collect();
+    match lsp.diagnostics_after_edit(&paths, &turn.cwd).await {
+        Some(report) => format!("{content}\n\n{report}"),
+        None => content,
+    }
+}
+
 #[async_trait]
 impl ToolHandler for ApplyPatchHandler {
     fn kind(&self) -> ToolKind {
@@ -117,6 +140,7 @@ impl ToolHandler for ApplyPatchHandler {
                     InternalApplyPatchInvocation::DelegateToExec(apply) => {
                         let changes = convert_apply_patch_to_protocol(&apply.action);
                         let file_paths = file_paths_for_action(&apply.action);
+                        let edited_paths = file_paths.clone();
                         let emitter =
                             ToolEmitter::apply_patch(changes.clone(), apply.auto_approved);
                         let event_ctx = ToolEventCtx::new(
@@ -154,6 +178,13 @@ impl ToolHandler for ApplyPatchHandler {
                             Some(&tracker),
                         );
                         let content = emitter.finish(event_ctx, out).await?;
+                        let content = append_lsp_diagnostics(
+                            session.as_ref(),
+                            turn.as_ref(),
+                            &edited_paths,
+                            content,
+                        )
+                        .await;
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -220,7 +251,7 @@ pub(crate) async fn intercept_apply_patch(
 
                     let req = ApplyPatchRequest {
                         action: apply.action,
-                        file_paths: approval_keys,
+                        file_paths: approval_keys.clone(),
                         changes,
                         exec_approval_requirement: apply.exec_approval_requirement,
                         timeout_ms,
@@ -241,6 +272,8 @@ pub(crate) async fn intercept_apply_patch(
                     let event_ctx =
                         ToolEventCtx::new(session, turn, call_id, tracker.as_ref().copied());
                     let content = emitter.finish(event_ctx, out).await?;
+                    let content =
+                        append_lsp_diagnostics(session, turn, &approval_keys, content).await;
                     Ok(Some(ToolOutput::Function {
                         content,
                         content_items: None,
@@ -283,6 +316,7 @@ pub(crate) fn create_apply_patch_json_tool() -> ToolSpec {
         "input".to_string(),
         JsonSchema::String {
             description: Some(r#"The entire contents of the apply_patch command"#.to_string()),
+            enum_values: None,
         },
     );
 
@@ -297,11 +331,14 @@ Your patch language is a stripped‑down, file‑oriented diff format designed t
 
 Within that envelope, you get a sequence of file operations.
 You MUST include a header to specify the action you are taking.
-Each operation starts with one of three headers:
+Each operation starts with one of these headers:
 
 *** Add File: <path> - create a new file. Every following line is a + line (the initial contents).
+*** Add Binary File: <path> - create a binary file. Every following line is a + line of base64-encoded contents.
+*** Add Symlink: <path> - create a symbolic link. Followed by exactly one + line holding the link target.
 *** Delete File: <path> - remove an existing file. Nothing follows.
 *** Update File: <path> - patch an existing file in place (optionally with a rename).
+*** Set Mode: <100644|100755> <path> - change whether a file is executable. Nothing follows.
 
 May be immediately followed by *** Move to: <new path> if you want to rename the file.
 Then one or more “hunks”, each introduced by @@ (optionally followed by a hunk header).
@@ -329,10 +366,13 @@ The full grammar definition is below:
 Patch := Begin { FileOp } End
 Begin := "*** Begin Patch" NEWLINE
 End := "*** End Patch" NEWLINE
-FileOp := AddFile | DeleteFile | UpdateFile
+FileOp := AddFile | AddBinaryFile | AddSymlink | DeleteFile | UpdateFile | SetMode
 AddFile := "*** Add File: " path NEWLINE { "+" line NEWLINE }
+AddBinaryFile := "*** Add Binary File: " path NEWLINE { "+" base64 NEWLINE }
+AddSymlink := "*** Add Symlink: " path NEWLINE "+" target NEWLINE
 DeleteFile := "*** Delete File: " path NEWLINE
 UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] { Hunk }
+SetMode := "*** Set Mode: " ("100644" | "100755") " " path NEWLINE
 MoveTo := "*** Move to: " newPath NEWLINE
 Hunk := "@@" [ header ] NEWLINE { HunkLine } [ "*** End of File" NEWLINE ]
 HunkLine := (" " | "-" | "+") text NEWLINE
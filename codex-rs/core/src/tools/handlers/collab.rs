@@ -1,5 +1,7 @@
 use crate::agent::AgentStatus;
 use crate::agent::exceeds_thread_spawn_depth_limit;
+use crate::agent::worktree::keep_agent_worktree;
+use crate::agent::worktree::remove_agent_worktree;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::config::Config;
@@ -13,6 +15,7 @@ use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use async_trait::async_trait;
+use codex_git::GitWorktree;
 use codex_protocol::ThreadId;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::protocol::CollabAgentInteractionBeginEvent;
@@ -36,6 +39,33 @@ pub(crate) const MAX_WAIT_TIMEOUT_MS: i64 = 300_000;
 #[derive(Debug, Deserialize)]
 struct CloseAgentArgs {
     id: String,
+    worktree: Option<WorktreeAction>,
+}
+
+/// What to do with the worktree of an agent spawned with `worktree: true`
+/// when it is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum WorktreeAction {
+    /// Apply the agent's diff to the parent's checkout, with approval.
+    Merge,
+    /// Throw the agent's changes away.
+    Discard,
+}
+
+#[derive(Debug, Serialize)]
+struct WorktreeInfo {
+    path: String,
+    branch: String,
+}
+
+impl From<&GitWorktree> for WorktreeInfo {
+    fn from(worktree: &GitWorktree) -> Self {
+        Self {
+            path: worktree.path().display().to_string(),
+            branch: worktree.branch().to_string(),
+        }
+    }
 }
 
 #[async_trait]
@@ -52,10 +82,10 @@ impl ToolHandler for CollabHandler {
         let ToolInvocation {
             session,
             turn,
+            tracker,
             tool_name,
             payload,
             call_id,
-            ..
         } = invocation;
 
         let arguments = match payload {
@@ -71,7 +101,7 @@ impl ToolHandler for CollabHandler {
             "spawn_agent" => spawn::handle(session, turn, call_id, arguments).await,
             "send_input" => send_input::handle(session, turn, call_id, arguments).await,
             "wait" => wait::handle(session, turn, call_id, arguments).await,
-            "close_agent" => close_agent::handle(session, turn, call_id, arguments).await,
+            "close_agent" => close_agent::handle(session, turn, tracker, call_id, arguments).await,
             other => Err(FunctionCallError::RespondToModel(format!(
                 "unsupported collab tool {other}"
             ))),
@@ -82,6 +112,8 @@ impl ToolHandler for CollabHandler {
 mod spawn {
     use super::*;
     use crate::agent::AgentRole;
+    use crate::agent::role::DEFAULT_ROLE_NAME;
+    use crate::agent::worktree::create_agent_worktree;
 
     use crate::agent::exceeds_thread_spawn_depth_limit;
     use crate::agent::next_thread_spawn_depth;
@@ -92,12 +124,16 @@ mod spawn {
     #[derive(Debug, Deserialize)]
     struct SpawnAgentArgs {
         message: String,
-        agent_type: Option<AgentRole>,
+        agent_type: Option<String>,
+        #[serde(default)]
+        worktree: bool,
     }
 
     #[derive(Debug, Serialize)]
     struct SpawnAgentResult {
         agent_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        worktree: Option<WorktreeInfo>,
     }
 
     pub async fn handle(
@@ -107,7 +143,19 @@ mod spawn {
         arguments: String,
     ) -> Result<ToolOutput, FunctionCallError> {
         let args: SpawnAgentArgs = parse_arguments(&arguments)?;
-        let agent_role = args.agent_type.unwrap_or(AgentRole::Default);
+        let role_name = args.agent_type.as_deref().unwrap_or(DEFAULT_ROLE_NAME);
+        let parent_config = turn.client.config();
+        let user_roles = &parent_config.agent_roles;
+        let agent_role = AgentRole::resolve(role_name, user_roles).ok_or_else(|| {
+            let available = AgentRole::available(user_roles)
+                .into_iter()
+                .map(|role| role.name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            FunctionCallError::RespondToModel(format!(
+                "unknown agent_type `{role_name}`. Available types: {available}"
+            ))
+        })?;
         let prompt = args.message;
         if prompt.trim().is_empty() {
             return Err(FunctionCallError::RespondToModel(
@@ -140,6 +188,19 @@ mod spawn {
         agent_role
             .apply_to_config(&mut config)
             .map_err(FunctionCallError::RespondToModel)?;
+        let worktree = if args.worktree {
+            let worktree = create_agent_worktree(&config.codex_home, &turn.cwd)
+                .await
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to create a worktree for the agent: {err}"
+                    ))
+                })?;
+            config.cwd = worktree.translate_path(&turn.cwd);
+            Some(worktree)
+        } else {
+            None
+        };
 
         let result = session
             .services
@@ -154,6 +215,17 @@ mod spawn {
             )
             .await
             .map_err(collab_spawn_error);
+        let worktree_info = worktree.as_ref().map(WorktreeInfo::from);
+        if let Some(worktree) = worktree {
+            match &result {
+                Ok(thread_id) => session.services.agent_control.worktrees().register(
+                    *thread_id,
+                    session.conversation_id,
+                    worktree,
+                ),
+                Err(_) => remove_agent_worktree(worktree).await,
+            }
+        }
         let (new_thread_id, status) = match &result {
             Ok(thread_id) => (
                 Some(*thread_id),
@@ -178,6 +250,7 @@ mod spawn {
 
         let content = serde_json::to_string(&SpawnAgentResult {
             agent_id: new_thread_id.to_string(),
+            worktree: worktree_info,
         })
         .map_err(|err| {
             FunctionCallError::Fatal(format!("failed to serialize spawn_agent result: {err}"))
@@ -283,6 +356,7 @@ mod send_input {
 mod wait {
     use super::*;
     use crate::agent::status::is_final;
+    use crate::truncate::formatted_truncate_text;
     use futures::FutureExt;
     use futures::StreamExt;
     use futures::stream::FuturesUnordered;
@@ -304,6 +378,16 @@ mod wait {
     struct WaitResult {
         status: HashMap<ThreadId, AgentStatus>,
         timed_out: bool,
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        worktrees: HashMap<ThreadId, WorktreeReport>,
+    }
+
+    /// Changes made by a finished agent in its worktree.
+    #[derive(Debug, Serialize)]
+    struct WorktreeReport {
+        #[serde(flatten)]
+        info: WorktreeInfo,
+        diff: String,
     }
 
     pub async fn handle(
@@ -418,9 +502,16 @@ mod wait {
 
         // Convert payload.
         let statuses_map = statuses.clone().into_iter().collect::<HashMap<_, _>>();
+        let mut worktrees = HashMap::new();
+        for (id, _) in &statuses {
+            if let Some(report) = worktree_report(&session, &turn, *id).await {
+                worktrees.insert(*id, report);
+            }
+        }
         let result = WaitResult {
             status: statuses_map.clone(),
             timed_out: statuses.is_empty(),
+            worktrees,
         };
 
         // Final event emission.
@@ -447,6 +538,22 @@ mod wait {
         })
     }
 
+    async fn worktree_report(
+        session: &Session,
+        turn: &TurnContext,
+        agent_id: ThreadId,
+    ) -> Option<WorktreeReport> {
+        let worktree = session.services.agent_control.worktrees().get(agent_id)?;
+        let info = WorktreeInfo::from(&worktree);
+        let diff = match tokio::task::spawn_blocking(move || worktree.diff()).await {
+            Ok(Ok(diff)) if diff.is_empty() => "(no changes)".to_string(),
+            Ok(Ok(diff)) => formatted_truncate_text(&diff, turn.truncation_policy),
+            Ok(Err(err)) => format!("failed to compute diff: {err}"),
+            Err(err) => format!("failed to compute diff: {err}"),
+        };
+        Some(WorktreeReport { info, diff })
+    }
+
     async fn wait_for_final_status(
         session: Arc<Session>,
         thread_id: ThreadId,
@@ -472,21 +579,47 @@ mod wait {
 
 pub mod close_agent {
     use super::*;
+    use crate::exec::ExecToolCallOutput;
+    use crate::exec::StreamOutput;
+    use crate::tools::context::SharedTurnDiffTracker;
+    use crate::tools::events::ToolEmitter;
+    use crate::tools::events::ToolEventCtx;
+    use crate::tools::events::ToolEventFailure;
+    use crate::tools::events::ToolEventStage;
+    use codex_git::ApplyGitRequest;
+    use codex_git::apply_git_patch;
+    use codex_git::extract_paths_from_patch;
+    use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::FileChange;
+    use codex_protocol::protocol::ReviewDecision;
+    use codex_protocol::protocol::SandboxPolicy;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::path::PathBuf;
     use std::sync::Arc;
 
     #[derive(Debug, Deserialize, Serialize)]
     pub(super) struct CloseAgentResult {
         pub(super) status: AgentStatus,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub(super) worktree: Option<String>,
     }
 
     pub async fn handle(
         session: Arc<Session>,
         turn: Arc<TurnContext>,
+        tracker: SharedTurnDiffTracker,
         call_id: String,
         arguments: String,
     ) -> Result<ToolOutput, FunctionCallError> {
         let args: CloseAgentArgs = parse_arguments(&arguments)?;
         let agent_id = agent_id(&args.id)?;
+        let worktrees = session.services.agent_control.worktrees();
+        if args.worktree.is_none() && worktrees.get(agent_id).is_some() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "agent {agent_id} runs in its own worktree; pass `worktree: \"merge\"` to apply its changes or `worktree: \"discard\"` to drop them"
+            )));
+        }
         session
             .send_event(
                 &turn,
@@ -537,7 +670,7 @@ pub mod close_agent {
             .send_event(
                 &turn,
                 CollabCloseEndEvent {
-                    call_id,
+                    call_id: call_id.clone(),
                     sender_thread_id: session.conversation_id,
                     receiver_thread_id: agent_id,
                     status: status.clone(),
@@ -547,9 +680,23 @@ pub mod close_agent {
             .await;
         result?;
 
-        let content = serde_json::to_string(&CloseAgentResult { status }).map_err(|err| {
-            FunctionCallError::Fatal(format!("failed to serialize close_agent result: {err}"))
-        })?;
+        let worktree = match (args.worktree, worktrees.remove(agent_id)) {
+            (Some(WorktreeAction::Discard), Some(worktree)) => {
+                let path = worktree.path().display().to_string();
+                remove_agent_worktree(worktree).await;
+                Some(format!("discarded worktree {path}"))
+            }
+            (Some(WorktreeAction::Merge), Some(worktree)) => {
+                let ctx = ToolEventCtx::new(&session, &turn, &call_id, Some(&tracker));
+                Some(merge_worktree(ctx, worktree).await)
+            }
+            _ => None,
+        };
+
+        let content =
+            serde_json::to_string(&CloseAgentResult { status, worktree }).map_err(|err| {
+                FunctionCallError::Fatal(format!("failed to serialize close_agent result: {err}"))
+            })?;
 
         Ok(ToolOutput::Function {
             content,
@@ -557,6 +704,171 @@ pub mod close_agent {
             content_items: None,
         })
     }
+
+    /// Applies the agent's changes to the parent's checkout, returning a
+    /// summary for the model. The merge goes through the same approval,
+    /// writable-roots check and diff tracking as `apply_patch`; the worktree is
+    /// kept when the merge is declined or fails.
+    async fn merge_worktree(ctx: ToolEventCtx<'_>, worktree: GitWorktree) -> String {
+        match try_merge_worktree(ctx, &worktree).await {
+            Ok(summary) => {
+                remove_agent_worktree(worktree).await;
+                summary
+            }
+            Err(message) => {
+                keep_agent_worktree(&worktree).await;
+                format!(
+                    "{message}. The agent's changes are kept on branch {} in {}",
+                    worktree.branch(),
+                    worktree.path().display()
+                )
+            }
+        }
+    }
+
+    async fn try_merge_worktree(
+        ctx: ToolEventCtx<'_>,
+        worktree: &GitWorktree,
+    ) -> Result<String, String> {
+        let diff_worktree = worktree.clone();
+        let diff = tokio::task::spawn_blocking(move || diff_worktree.diff())
+            .await
+            .map_err(|err| format!("merge failed: {err}"))?
+            .map_err(|err| format!("merge failed: {err}"))?;
+        if diff.is_empty() {
+            return Ok("worktree had no changes to merge".to_string());
+        }
+
+        let changes = worktree_changes(worktree.repo_root(), &diff);
+        let needs_approval = merge_approval(ctx.turn, &changes)
+            .map_err(|reason| format!("merge rejected: {reason}"))?;
+        if needs_approval {
+            let decision = ctx
+                .session
+                .request_patch_approval(
+                    ctx.turn,
+                    ctx.call_id.to_string(),
+                    changes.clone(),
+                    Some(format!(
+                        "Merge the changes of a sub-agent worktree into {}",
+                        worktree.repo_root().display()
+                    )),
+                    None,
+                )
+                .await
+                .await
+                .unwrap_or_default();
+            if matches!(decision, ReviewDecision::Denied | ReviewDecision::Abort) {
+                return Err("merge declined by the user".to_string());
+            }
+        }
+
+        let emitter = ToolEmitter::apply_patch(changes, !needs_approval);
+        emitter.begin(ctx).await;
+        let repo_root = worktree.repo_root().to_path_buf();
+        let applied = tokio::task::spawn_blocking(move || {
+            let paths = extract_paths_from_patch(&diff);
+            apply_git_patch(&ApplyGitRequest {
+                cwd: repo_root,
+                diff,
+                revert: false,
+                preflight: false,
+            })
+            .map(|applied| (paths, applied))
+            .map_err(|err| format!("merge failed: {err}"))
+        })
+        .await
+        .unwrap_or_else(|err| Err(format!("merge failed: {err}")));
+
+        let message = match applied {
+            Ok((paths, applied)) if applied.exit_code == 0 => {
+                let output = ExecToolCallOutput {
+                    stdout: StreamOutput::new(applied.stdout),
+                    stderr: StreamOutput::new(applied.stderr),
+                    ..Default::default()
+                };
+                emitter.emit(ctx, ToolEventStage::Success(output)).await;
+                return Ok(format!(
+                    "merged the agent's changes into {}: {}",
+                    worktree.repo_root().display(),
+                    paths.join(", ")
+                ));
+            }
+            Ok((_, applied)) => format!(
+                "merge failed; conflicted paths: {}",
+                applied.conflicted_paths.join(", ")
+            ),
+            Err(message) => message,
+        };
+        emitter
+            .emit(
+                ctx,
+                ToolEventStage::Failure(ToolEventFailure::Message(message.clone())),
+            )
+            .await;
+        Err(message)
+    }
+
+    /// Splits a `git diff` into one [`FileChange`] per touched path, keyed by
+    /// its absolute path in `repo_root`.
+    fn worktree_changes(repo_root: &Path, diff: &str) -> HashMap<PathBuf, FileChange> {
+        let mut changes = HashMap::new();
+        let mut sections: Vec<&str> = Vec::new();
+        let mut start = None;
+        let mut offset = 0;
+        for line in diff.split_inclusive('\n') {
+            if line.starts_with("diff --git ") {
+                if let Some(start) = start {
+                    sections.push(&diff[start..offset]);
+                }
+                start = Some(offset);
+            }
+            offset += line.len();
+        }
+        if let Some(start) = start {
+            sections.push(&diff[start..]);
+        }
+        for section in sections {
+            for path in extract_paths_from_patch(section) {
+                changes.insert(
+                    repo_root.join(path),
+                    FileChange::Update {
+                        unified_diff: section.to_string(),
+                        move_path: None,
+                    },
+                );
+            }
+        }
+        changes
+    }
+
+    /// Whether merging `changes` needs the user's approval, following the
+    /// rules `apply_patch` uses: writes inside the sandbox's writable roots
+    /// are approved automatically unless the policy always asks, and other
+    /// writes ask, or are rejected when the policy never asks.
+    fn merge_approval(
+        turn: &TurnContext,
+        changes: &HashMap<PathBuf, FileChange>,
+    ) -> Result<bool, String> {
+        let writable = match &turn.sandbox_policy {
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. } => true,
+            policy => {
+                let roots = policy.get_writable_roots_with_cwd(&turn.cwd);
+                changes
+                    .keys()
+                    .all(|path| roots.iter().any(|root| root.is_path_writable(path)))
+            }
+        };
+        match turn.approval_policy {
+            AskForApproval::UnlessTrusted => Ok(true),
+            AskForApproval::Never if !writable => {
+                Err("the changes touch paths outside the writable roots".to_string())
+            }
+            AskForApproval::Never | AskForApproval::OnFailure | AskForApproval::OnRequest => {
+                Ok(!writable)
+            }
+        }
+    }
 }
 
 fn agent_id(id: &str) -> Result<ThreadId, FunctionCallError> {
@@ -743,6 +1055,26 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn spawn_agent_rejects_unknown_agent_type() {
+        let (session, turn) = make_session_and_context().await;
+        let invocation = invocation(
+            Arc::new(session),
+            Arc::new(turn),
+            "spawn_agent",
+            function_payload(json!({"message": "hello", "agent_type": "nope"})),
+        );
+        let Err(err) = CollabHandler.handle(invocation).await else {
+            panic!("unknown agent type should be rejected");
+        };
+        assert_eq!(
+            err,
+            FunctionCallError::RespondToModel(
+                "unknown agent_type `nope`. Available types: default, explorer, worker".to_string()
+            )
+        );
+    }
+
     #[tokio::test]
     async fn spawn_agent_errors_when_manager_dropped() {
         let (session, turn) = make_session_and_context().await;
@@ -1174,6 +1506,176 @@ mod tests {
         assert_eq!(status_after, AgentStatus::NotFound);
     }
 
+    #[tokio::test]
+    async fn close_agent_requires_worktree_action_and_discards() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        init_git_repo(repo.path());
+        let worktree =
+            crate::agent::worktree::create_agent_worktree(codex_home.path(), repo.path())
+                .await
+                .expect("create worktree");
+
+        let (mut session, turn) = make_session_and_context().await;
+        let manager = thread_manager();
+        session.services.agent_control = manager.agent_control();
+        let config = turn.client.config().as_ref().clone();
+        let thread = manager.start_thread(config).await.expect("start thread");
+        let agent_id = thread.thread_id;
+        session.services.agent_control.worktrees().register(
+            agent_id,
+            session.conversation_id,
+            worktree.clone(),
+        );
+        let session = Arc::new(session);
+        let turn = Arc::new(turn);
+
+        let Err(err) = CollabHandler
+            .handle(invocation(
+                session.clone(),
+                turn.clone(),
+                "close_agent",
+                function_payload(json!({"id": agent_id.to_string()})),
+            ))
+            .await
+        else {
+            panic!("close_agent should require a worktree action");
+        };
+        assert!(
+            matches!(&err, FunctionCallError::RespondToModel(message) if message.contains("worktree")),
+            "unexpected error: {err:?}"
+        );
+
+        let output = CollabHandler
+            .handle(invocation(
+                session.clone(),
+                turn,
+                "close_agent",
+                function_payload(json!({"id": agent_id.to_string(), "worktree": "discard"})),
+            ))
+            .await
+            .expect("close_agent should succeed");
+        let ToolOutput::Function { content, .. } = output else {
+            panic!("expected function output");
+        };
+        let result: close_agent::CloseAgentResult =
+            serde_json::from_str(&content).expect("close_agent result should be json");
+        assert_eq!(
+            result.worktree,
+            Some(format!("discarded worktree {}", worktree.path().display()))
+        );
+        assert!(!worktree.path().exists());
+        assert_eq!(
+            session.services.agent_control.worktrees().get(agent_id),
+            None
+        );
+    }
+
+    fn init_git_repo(repo: &std::path::Path) {
+        std::fs::write(repo.join("a.txt"), "a\n").expect("write");
+        for args in [
+            vec!["init", "--initial-branch=main"],
+            vec!["add", "."],
+            vec!["commit", "-m", "init"],
+        ] {
+            let status = std::process::Command::new("git")
+                .current_dir(repo)
+                .args([
+                    "-c",
+                    "user.name=Tester",
+                    "-c",
+                    "user.email=test@example.com",
+                ])
+                .args(args)
+                .status()
+                .expect("git command");
+            assert!(status.success());
+        }
+    }
+
+    #[tokio::test]
+    async fn close_agent_merge_follows_approval_and_sandbox_policy() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        init_git_repo(repo.path());
+        let worktree =
+            crate::agent::worktree::create_agent_worktree(codex_home.path(), repo.path())
+                .await
+                .expect("create worktree");
+        std::fs::write(worktree.path().join("a.txt"), "b\n").expect("write");
+
+        let (mut session, mut turn) = make_session_and_context().await;
+        let manager = thread_manager();
+        session.services.agent_control = manager.agent_control();
+        turn.cwd = repo.path().to_path_buf();
+        turn.approval_policy = AskForApproval::Never;
+        turn.sandbox_policy = SandboxPolicy::new_read_only_policy();
+        let session = Arc::new(session);
+        let tracker = Arc::new(Mutex::new(TurnDiffTracker::default()));
+
+        // A read-only sandbox with `never` cannot write the merge.
+        let turn = Arc::new(turn);
+        let summary = close_with_merge(&session, &turn, &manager, &worktree, &tracker).await;
+        assert!(summary.starts_with("merge rejected"), "summary: {summary}");
+        assert!(worktree.path().exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("a.txt")).expect("read"),
+            "a\n"
+        );
+
+        let mut turn = Arc::try_unwrap(turn).unwrap_or_else(|_| panic!("turn is still shared"));
+        turn.sandbox_policy = SandboxPolicy::DangerFullAccess;
+        let turn = Arc::new(turn);
+        let summary = close_with_merge(&session, &turn, &manager, &worktree, &tracker).await;
+        assert!(summary.starts_with("merged"), "summary: {summary}");
+        assert!(!worktree.path().exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("a.txt")).expect("read"),
+            "b\n"
+        );
+        let turn_diff = tracker
+            .lock()
+            .await
+            .get_unified_diff()
+            .expect("turn diff")
+            .unwrap_or_default();
+        assert!(turn_diff.contains("+b"), "turn diff: {turn_diff}");
+    }
+
+    async fn close_with_merge(
+        session: &Arc<crate::codex::Session>,
+        turn: &Arc<TurnContext>,
+        manager: &ThreadManager,
+        worktree: &GitWorktree,
+        tracker: &crate::tools::context::SharedTurnDiffTracker,
+    ) -> String {
+        let config = turn.client.config().as_ref().clone();
+        let thread = manager.start_thread(config).await.expect("start thread");
+        let agent_id = thread.thread_id;
+        session.services.agent_control.worktrees().register(
+            agent_id,
+            session.conversation_id,
+            worktree.clone(),
+        );
+        let mut invocation = invocation(
+            session.clone(),
+            turn.clone(),
+            "close_agent",
+            function_payload(json!({"id": agent_id.to_string(), "worktree": "merge"})),
+        );
+        invocation.tracker = tracker.clone();
+        let output = CollabHandler
+            .handle(invocation)
+            .await
+            .expect("close_agent should succeed");
+        let ToolOutput::Function { content, .. } = output else {
+            panic!("expected function output");
+        };
+        let result: close_agent::CloseAgentResult =
+            serde_json::from_str(&content).expect("close_agent result should be json");
+        result.worktree.unwrap_or_default()
+    }
+
     #[tokio::test]
     async fn build_agent_spawn_config_uses_turn_context_values() {
         let (_session, mut turn) = make_session_and_context().await;
@@ -0,0 +1,190 @@
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_symbol_index::Symbol;
+use codex_symbol_index::SymbolKind;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct FindSymbolHandler;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+/// How long a call waits for the initial index build before giving up.
+const BUILD_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct FindSymbolArgs {
+    query: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for FindSymbolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "find_symbol handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: FindSymbolArgs = parse_arguments(&arguments)?;
+
+        let query = args.query.trim().to_string();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.limit.min(MAX_LIMIT);
+
+        let kind = match args.kind.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(kind) => Some(parse_kind(kind)?),
+        };
+
+        let Some(index) = session.services.symbol_index.clone() else {
+            return Err(FunctionCallError::RespondToModel(
+                "the symbol index is not enabled for this session".to_string(),
+            ));
+        };
+
+        // Searching applies pending file changes, which parses files.
+        let results = tokio::task::spawn_blocking(move || {
+            if !index.wait_until_ready(BUILD_TIMEOUT) {
+                return None;
+            }
+            index
+                .search(&query, kind, limit)
+                .map(|symbols| (index.root().to_path_buf(), symbols))
+        })
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("symbol search failed: {err}")))?;
+
+        let Some((root, symbols)) = results else {
+            return Err(FunctionCallError::RespondToModel(
+                "the symbol index is still being built; try again shortly or use grep_files"
+                    .to_string(),
+            ));
+        };
+
+        if symbols.is_empty() {
+            return Ok(ToolOutput::Function {
+                content: "No symbols found.".to_string(),
+                content_items: None,
+                success: Some(false),
+            });
+        }
+
+        let lines: Vec<String> = symbols
+            .iter()
+            .map(|symbol| format_symbol(symbol, &root, &turn.cwd))
+            .collect();
+        Ok(ToolOutput::Function {
+            content: lines.join("\n"),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn parse_kind(kind: &str) -> Result<SymbolKind, FunctionCallError> {
+    SymbolKind::parse(&kind.to_ascii_lowercase()).ok_or_else(|| {
+        let kinds = SymbolKind::ALL
+            .iter()
+            .map(|kind| kind.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        FunctionCallError::RespondToModel(format!(
+            "unknown symbol kind `{kind}`; expected one of: {kinds}"
+        ))
+    })
+}
+
+/// `path:line kind name`, with the path relative to `cwd` when it is inside it.
+fn format_symbol(symbol: &Symbol, root: &Path, cwd: &Path) -> String {
+    let path = root.join(&symbol.path);
+    let path = path.strip_prefix(cwd).unwrap_or(&path);
+    format!(
+        "{}:{} {} {}",
+        path.display(),
+        symbol.line,
+        symbol.kind,
+        symbol.qualified_name()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn formats_paths_relative_to_cwd_when_possible() {
+        let symbol = Symbol {
+            name: "load".to_string(),
+            kind: SymbolKind::Method,
+            path: PathBuf::from("core/src/config.rs"),
+            line: 42,
+            container: Some("Config".to_string()),
+        };
+        let root = Path::new("/repo");
+
+        assert_eq!(
+            format_symbol(&symbol, root, Path::new("/repo/core")),
+            "src/config.rs:42 method Config::load"
+        );
+        assert_eq!(
+            format_symbol(&symbol, root, Path::new("/elsewhere")),
+            format!(
+                "{}:42 method Config::load",
+                root.join("core/src/config.rs").display()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_kinds() {
+        assert_eq!(parse_kind("Struct").ok(), Some(SymbolKind::Struct));
+        let Err(FunctionCallError::RespondToModel(message)) = parse_kind("widget") else {
+            panic!("expected an error for an unknown kind");
+        };
+        assert!(message.starts_with("unknown symbol kind `widget`"));
+    }
+}
@@ -1,10 +1,23 @@
+use std::cmp::Reverse;
+use std::io;
 use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
 use async_trait::async_trait;
+use grep_regex::RegexMatcherBuilder;
+use grep_searcher::BinaryDetection;
+use grep_searcher::Searcher;
+use grep_searcher::SearcherBuilder;
+use grep_searcher::Sink;
+use grep_searcher::SinkContext;
+use grep_searcher::SinkMatch;
+use grep_searcher::sinks::Lossy;
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
 use serde::Deserialize;
-use tokio::process::Command;
-use tokio::time::timeout;
 
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
@@ -18,7 +31,10 @@ pub struct GrepFilesHandler;
 
 const DEFAULT_LIMIT: usize = 100;
 const MAX_LIMIT: usize = 2000;
-const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_CONTEXT_LINES: usize = 10;
+/// Longer lines are cut so a match in a minified file cannot flood the output.
+const MAX_LINE_CHARS: usize = 500;
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
 
 fn default_limit() -> usize {
     DEFAULT_LIMIT
@@ -33,6 +49,39 @@ struct GrepFilesArgs {
     path: Option<String>,
     #[serde(default = "default_limit")]
     limit: usize,
+    #[serde(default)]
+    output_mode: OutputMode,
+    #[serde(default)]
+    context: usize,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum OutputMode {
+    /// One path per matching file.
+    #[default]
+    FilesWithMatches,
+    /// `path:line:text` for matching lines and `path-line-text` for context.
+    Content,
+}
+
+struct SearchRequest {
+    pattern: String,
+    include: Option<String>,
+    path: PathBuf,
+    /// Root that `include` globs are relative to.
+    cwd: PathBuf,
+    output_mode: OutputMode,
+    context: usize,
+    /// Maximum number of files, or of matching lines in content mode.
+    limit: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SearchResults {
+    lines: Vec<String>,
+    /// Whether the search hit its deadline before visiting every file.
+    timed_out: bool,
 }
 
 #[async_trait]
@@ -81,18 +130,44 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
-            run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
+        let request = SearchRequest {
+            pattern: pattern.to_string(),
+            include,
+            path: search_path,
+            cwd: turn.cwd.clone(),
+            output_mode: args.output_mode,
+            context: args.context.min(MAX_CONTEXT_LINES),
+            limit,
+        };
+        let results = tokio::task::spawn_blocking(move || {
+            run_search(&request, Instant::now() + SEARCH_TIMEOUT)
+        })
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("grep_files failed: {err}")))??;
 
-        if search_results.is_empty() {
+        let timeout_note = format!(
+            "Search timed out after {} seconds; results are incomplete.",
+            SEARCH_TIMEOUT.as_secs()
+        );
+        if results.lines.is_empty() {
+            let content = if results.timed_out {
+                timeout_note
+            } else {
+                "No matches found.".to_string()
+            };
             Ok(ToolOutput::Function {
-                content: "No matches found.".to_string(),
+                content,
                 content_items: None,
                 success: Some(false),
             })
         } else {
+            let mut content = results.lines.join("\n");
+            if results.timed_out {
+                content.push_str("\n\n");
+                content.push_str(&timeout_note);
+            }
             Ok(ToolOutput::Function {
-                content: search_results.join("\n"),
+                content,
                 content_items: None,
                 success: Some(true),
             })
@@ -107,165 +182,344 @@ async fn verify_path_exists(path: &Path) -> Result<(), FunctionCallError> {
     Ok(())
 }
 
-async fn run_rg_search(
-    pattern: &str,
-    include: Option<&str>,
-    search_path: &Path,
-    limit: usize,
-    cwd: &Path,
-) -> Result<Vec<String>, FunctionCallError> {
-    let mut command = Command::new("rg");
-    command
-        .current_dir(cwd)
-        .arg("--files-with-matches")
-        .arg("--sortr=modified")
-        .arg("--regexp")
-        .arg(pattern)
-        .arg("--no-messages");
-
-    if let Some(glob) = include {
-        command.arg("--glob").arg(glob);
+/// Searches the files under `request.path`, newest first like
+/// `rg --sortr=modified`, and stops as soon as `request.limit` is reached or
+/// `deadline` passes.
+fn run_search(
+    request: &SearchRequest,
+    deadline: Instant,
+) -> Result<SearchResults, FunctionCallError> {
+    let matcher = RegexMatcherBuilder::new()
+        .line_terminator(Some(b'\n'))
+        .build(&request.pattern)
+        .map_err(|err| FunctionCallError::RespondToModel(format!("invalid pattern: {err}")))?;
+    let context = match request.output_mode {
+        OutputMode::FilesWithMatches => 0,
+        OutputMode::Content => request.context,
+    };
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .before_context(context)
+        .after_context(context)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+
+    let (files, mut timed_out) = collect_files(request, deadline)?;
+    let mut lines = Vec::new();
+    let mut matches = 0;
+    for path in files {
+        if matches >= request.limit {
+            break;
+        }
+        if Instant::now() >= deadline {
+            timed_out = true;
+            break;
+        }
+        let display = path.display().to_string();
+        // Unreadable files are skipped, like `rg --no-messages`.
+        match request.output_mode {
+            OutputMode::FilesWithMatches => {
+                let mut found = false;
+                let sink = Lossy(|_, _| {
+                    found = true;
+                    Ok(false)
+                });
+                let _ = searcher.search_path(&matcher, &path, sink);
+                if found {
+                    lines.push(display);
+                    matches += 1;
+                }
+            }
+            OutputMode::Content => {
+                let separate = context > 0 && !lines.is_empty();
+                let mut sink = ContentSink {
+                    path: &display,
+                    lines: &mut lines,
+                    matches: &mut matches,
+                    limit: request.limit,
+                    separate,
+                };
+                let _ = searcher.search_path(&matcher, &path, &mut sink);
+            }
+        }
     }
 
-    command.arg("--").arg(search_path);
+    Ok(SearchResults { lines, timed_out })
+}
 
-    let output = timeout(COMMAND_TIMEOUT, command.output())
-        .await
-        .map_err(|_| {
-            FunctionCallError::RespondToModel("rg timed out after 30 seconds".to_string())
-        })?
-        .map_err(|err| {
-            FunctionCallError::RespondToModel(format!(
-                "failed to launch rg: {err}. Ensure ripgrep is installed and on PATH."
-            ))
-        })?;
-
-    match output.status.code() {
-        Some(0) => Ok(parse_results(&output.stdout, limit)),
-        Some(1) => Ok(Vec::new()),
-        _ => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(FunctionCallError::RespondToModel(format!(
-                "rg failed: {stderr}"
-            )))
-        }
+/// Lists the files to search, honoring `.gitignore`, hidden files and the
+/// `include` glob the same way ripgrep does. Returns whether the walk hit
+/// `deadline`.
+///
+/// Newest-first order needs every modification time, so the whole tree is
+/// walked and sorted before the first file is searched, as with
+/// `rg --sortr=modified`. The walk only reads directory entries and
+/// metadata, and shares the search's deadline.
+fn collect_files(
+    request: &SearchRequest,
+    deadline: Instant,
+) -> Result<(Vec<PathBuf>, bool), FunctionCallError> {
+    let mut walker = WalkBuilder::new(&request.path);
+    walker.sort_by_file_name(Ord::cmp);
+    if let Some(include) = &request.include {
+        let invalid_glob = |err: ignore::Error| {
+            FunctionCallError::RespondToModel(format!("invalid include glob `{include}`: {err}"))
+        };
+        let mut overrides = OverrideBuilder::new(&request.cwd);
+        overrides.add(include).map_err(invalid_glob)?;
+        walker.overrides(overrides.build().map_err(invalid_glob)?);
     }
-}
 
-fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
-    let mut results = Vec::new();
-    for line in stdout.split(|byte| *byte == b'\n') {
-        if line.is_empty() {
+    let mut files = Vec::new();
+    let mut timed_out = false;
+    for entry in walker.build() {
+        if Instant::now() >= deadline {
+            timed_out = true;
+            break;
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
             continue;
         }
-        if let Ok(text) = std::str::from_utf8(line) {
-            if text.is_empty() {
-                continue;
-            }
-            results.push(text.to_string());
-            if results.len() == limit {
-                break;
-            }
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        files.push((modified, entry.into_path()));
+    }
+    // Stable, so files modified at the same time keep their name order.
+    files.sort_by_key(|(modified, _)| Reverse(*modified));
+
+    Ok((files.into_iter().map(|(_, path)| path).collect(), timed_out))
+}
+
+/// Collects matching and context lines in ripgrep's output format and stops
+/// the search once `limit` matching lines have been collected.
+struct ContentSink<'a> {
+    path: &'a str,
+    lines: &'a mut Vec<String>,
+    matches: &'a mut usize,
+    limit: usize,
+    /// Whether a `--` separator goes before this file's first line.
+    separate: bool,
+}
+
+impl ContentSink<'_> {
+    fn push(&mut self, separator: char, line_number: Option<u64>, bytes: &[u8]) {
+        if std::mem::take(&mut self.separate) {
+            self.lines.push("--".to_string());
         }
+        let text = String::from_utf8_lossy(bytes);
+        let text = truncate_line(text.trim_end_matches(['\r', '\n']));
+        let line_number = line_number.unwrap_or_default();
+        self.lines.push(format!(
+            "{}{separator}{line_number}{separator}{text}",
+            self.path
+        ));
+    }
+}
+
+impl Sink for ContentSink<'_> {
+    type Error = io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, io::Error> {
+        self.push(':', mat.line_number(), mat.bytes());
+        *self.matches += 1;
+        Ok(*self.matches < self.limit)
+    }
+
+    fn context(&mut self, _searcher: &Searcher, ctx: &SinkContext<'_>) -> Result<bool, io::Error> {
+        self.push('-', ctx.line_number(), ctx.bytes());
+        Ok(true)
+    }
+
+    fn context_break(&mut self, _searcher: &Searcher) -> Result<bool, io::Error> {
+        self.lines.push("--".to_string());
+        Ok(true)
+    }
+}
+
+fn truncate_line(text: &str) -> String {
+    match text.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
     }
-    results
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::process::Command as StdCommand;
+    use pretty_assertions::assert_eq;
+    use std::fs;
     use tempfile::tempdir;
 
-    #[test]
-    fn parses_basic_results() {
-        let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n";
-        let parsed = parse_results(stdout, 10);
-        assert_eq!(
-            parsed,
-            vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
-        );
+    fn request(dir: &Path, pattern: &str) -> SearchRequest {
+        SearchRequest {
+            pattern: pattern.to_string(),
+            include: None,
+            path: dir.to_path_buf(),
+            cwd: dir.to_path_buf(),
+            output_mode: OutputMode::FilesWithMatches,
+            context: 0,
+            limit: 10,
+        }
+    }
+
+    fn search(request: &SearchRequest) -> SearchResults {
+        match run_search(request, Instant::now() + SEARCH_TIMEOUT) {
+            Ok(results) => results,
+            Err(err) => panic!("search failed: {err:?}"),
+        }
+    }
+
+    fn file_names(results: &SearchResults) -> Vec<String> {
+        results
+            .lines
+            .iter()
+            .filter_map(|line| Path::new(line).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn touch(path: &Path, seconds_ago: u64) -> io::Result<()> {
+        let modified = SystemTime::now() - Duration::from_secs(seconds_ago);
+        fs::File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(modified)
     }
 
     #[test]
-    fn parse_truncates_after_limit() {
-        let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n/tmp/file_c.rs\n";
-        let parsed = parse_results(stdout, 2);
-        assert_eq!(
-            parsed,
-            vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
-        );
+    fn lists_matching_files_newest_first() -> io::Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        fs::write(dir.join("old.txt"), "alpha beta gamma")?;
+        fs::write(dir.join("new.txt"), "alpha delta")?;
+        fs::write(dir.join("other.txt"), "omega")?;
+        touch(&dir.join("old.txt"), 60)?;
+
+        let results = search(&request(dir, "alpha"));
+        assert_eq!(file_names(&results), vec!["new.txt", "old.txt"]);
+        assert!(!results.timed_out);
+        Ok(())
     }
 
-    #[tokio::test]
-    async fn run_search_returns_results() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
-        let temp = tempdir().expect("create temp dir");
+    #[test]
+    fn filters_files_with_include_glob() -> io::Result<()> {
+        let temp = tempdir()?;
         let dir = temp.path();
-        std::fs::write(dir.join("match_one.txt"), "alpha beta gamma").unwrap();
-        std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
-        std::fs::write(dir.join("other.txt"), "omega").unwrap();
-
-        let results = run_rg_search("alpha", None, dir, 10, dir).await?;
-        assert_eq!(results.len(), 2);
-        assert!(results.iter().any(|path| path.ends_with("match_one.txt")));
-        assert!(results.iter().any(|path| path.ends_with("match_two.txt")));
+        fs::create_dir(dir.join("src"))?;
+        fs::write(dir.join("src/match_one.rs"), "alpha beta gamma")?;
+        fs::write(dir.join("match_two.txt"), "alpha delta")?;
+
+        let mut request = request(dir, "alpha");
+        request.include = Some("*.rs".to_string());
+        assert_eq!(file_names(&search(&request)), vec!["match_one.rs"]);
         Ok(())
     }
 
-    #[tokio::test]
-    async fn run_search_with_glob_filter() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
-        let temp = tempdir().expect("create temp dir");
+    #[test]
+    fn stops_at_the_limit() -> io::Result<()> {
+        let temp = tempdir()?;
         let dir = temp.path();
-        std::fs::write(dir.join("match_one.rs"), "alpha beta gamma").unwrap();
-        std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
+        fs::write(dir.join("one.txt"), "alpha one")?;
+        fs::write(dir.join("two.txt"), "alpha two")?;
+        fs::write(dir.join("three.txt"), "alpha three\nalpha four\n")?;
+
+        let mut files = request(dir, "alpha");
+        files.limit = 2;
+        assert_eq!(search(&files).lines.len(), 2);
+
+        let mut content = request(&dir.join("three.txt"), "alpha");
+        content.output_mode = OutputMode::Content;
+        content.limit = 1;
+        assert_eq!(
+            search(&content).lines,
+            vec![format!("{}:1:alpha three", dir.join("three.txt").display())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reports_no_matches() -> io::Result<()> {
+        let temp = tempdir()?;
+        fs::write(temp.path().join("one.txt"), "omega")?;
 
-        let results = run_rg_search("alpha", Some("*.rs"), dir, 10, dir).await?;
-        assert_eq!(results.len(), 1);
-        assert!(results.iter().all(|path| path.ends_with("match_one.rs")));
+        assert_eq!(
+            search(&request(temp.path(), "alpha")),
+            SearchResults::default()
+        );
         Ok(())
     }
 
-    #[tokio::test]
-    async fn run_search_respects_limit() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
-        let temp = tempdir().expect("create temp dir");
+    #[test]
+    fn content_mode_prints_line_numbers_and_context() -> io::Result<()> {
+        let temp = tempdir()?;
         let dir = temp.path();
-        std::fs::write(dir.join("one.txt"), "alpha one").unwrap();
-        std::fs::write(dir.join("two.txt"), "alpha two").unwrap();
-        std::fs::write(dir.join("three.txt"), "alpha three").unwrap();
+        let file = dir.join("notes.txt");
+        fs::write(&file, "one\nalpha\nthree\nfour\nfive\nsix\nalpha\n")?;
 
-        let results = run_rg_search("alpha", None, dir, 2, dir).await?;
-        assert_eq!(results.len(), 2);
+        let mut request = request(dir, "alpha");
+        request.output_mode = OutputMode::Content;
+        request.context = 1;
+        let path = file.display();
+        assert_eq!(
+            search(&request).lines,
+            vec![
+                format!("{path}-1-one"),
+                format!("{path}:2:alpha"),
+                format!("{path}-3-three"),
+                "--".to_string(),
+                format!("{path}-6-six"),
+                format!("{path}:7:alpha"),
+            ]
+        );
         Ok(())
     }
 
-    #[tokio::test]
-    async fn run_search_handles_no_matches() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
-        let temp = tempdir().expect("create temp dir");
+    #[test]
+    fn skips_ignored_hidden_and_binary_files() -> io::Result<()> {
+        let temp = tempdir()?;
         let dir = temp.path();
-        std::fs::write(dir.join("one.txt"), "omega").unwrap();
+        fs::create_dir(dir.join(".git"))?;
+        fs::write(dir.join(".gitignore"), "target/\n")?;
+        fs::create_dir(dir.join("target"))?;
+        fs::write(dir.join("target/built.txt"), "alpha")?;
+        fs::write(dir.join(".hidden.txt"), "alpha")?;
+        fs::write(dir.join("blob.bin"), b"alpha\x00\x01")?;
+        fs::write(dir.join("kept.txt"), "alpha")?;
+
+        assert_eq!(
+            file_names(&search(&request(dir, "alpha"))),
+            vec!["kept.txt"]
+        );
+        Ok(())
+    }
 
-        let results = run_rg_search("alpha", None, dir, 5, dir).await?;
-        assert!(results.is_empty());
+    #[test]
+    fn rejects_invalid_patterns() -> io::Result<()> {
+        let temp = tempdir()?;
+        let result = run_search(&request(temp.path(), "("), Instant::now() + SEARCH_TIMEOUT);
+        let Err(FunctionCallError::RespondToModel(message)) = result else {
+            panic!("expected an error for an invalid pattern");
+        };
+        assert!(message.starts_with("invalid pattern:"));
         Ok(())
     }
 
-    fn rg_available() -> bool {
-        StdCommand::new("rg")
-            .arg("--version")
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+    #[test]
+    fn truncates_long_lines() {
+        let line = "x".repeat(MAX_LINE_CHARS + 5);
+        assert_eq!(
+            truncate_line(&line),
+            format!("{}…", "x".repeat(MAX_LINE_CHARS))
+        );
+        assert_eq!(truncate_line("short"), "short");
     }
 }
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::function_tool::FunctionCallError;
+use crate::lsp::format_diagnostics;
+use crate::lsp::format_hover;
+use crate::lsp::format_locations;
+use crate::lsp::lsp_position;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct LspHandler;
+
+#[derive(Deserialize)]
+struct PositionArgs {
+    path: String,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticsArgs {
+    #[serde(default)]
+    path: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for LspHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "lsp handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let lsp = &session.services.lsp_manager;
+
+        if tool_name == "lsp_diagnostics" {
+            let args: DiagnosticsArgs = parse_arguments(&arguments)?;
+            let report = match args.path {
+                Some(path) => {
+                    let path = turn.resolve_path(Some(path));
+                    let document = lsp
+                        .document(&path, &turn.cwd)
+                        .await
+                        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?
+                        .ok_or_else(|| no_server_error(&path))?;
+                    let diagnostics = document.diagnostics().await;
+                    (!diagnostics.is_empty())
+                        .then(|| format_diagnostics(&path, &diagnostics, &turn.cwd))
+                }
+                None => {
+                    let reports: Vec<String> = lsp
+                        .all_diagnostics()
+                        .await
+                        .iter()
+                        .map(|(path, diagnostics)| format_diagnostics(path, diagnostics, &turn.cwd))
+                        .collect();
+                    (!reports.is_empty()).then(|| reports.join("\n"))
+                }
+            };
+            return Ok(ToolOutput::Function {
+                content: report.unwrap_or_else(|| "No diagnostics.".to_string()),
+                content_items: None,
+                success: Some(true),
+            });
+        }
+
+        let (method, extra_params) = match tool_name.as_str() {
+            "lsp_definition" => ("textDocument/definition", None),
+            "lsp_references" => (
+                "textDocument/references",
+                Some(json!({ "includeDeclaration": true })),
+            ),
+            "lsp_hover" => ("textDocument/hover", None),
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unknown lsp tool `{other}`"
+                )));
+            }
+        };
+
+        let args: PositionArgs = parse_arguments(&arguments)?;
+        if args.line == 0 || args.column == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "line and column are 1-based and must be greater than zero".to_string(),
+            ));
+        }
+        let path = turn.resolve_path(Some(args.path));
+        let text = tokio::fs::read_to_string(&path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("unable to read `{}`: {err}", path.display()))
+        })?;
+        let document = lsp
+            .document(&path, &turn.cwd)
+            .await
+            .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?
+            .ok_or_else(|| no_server_error(&path))?;
+
+        let mut params = json!({
+            "textDocument": { "uri": document.uri },
+            "position": lsp_position(&text, args.line, args.column),
+        });
+        if let Some(context) = extra_params {
+            params["context"] = context;
+        }
+        let result = document
+            .client
+            .request(method, params)
+            .await
+            .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+
+        let (content, found) = if method == "textDocument/hover" {
+            match format_hover(&result) {
+                Some(hover) => (hover, true),
+                None => ("No hover information.".to_string(), false),
+            }
+        } else {
+            let locations = format_locations(&result, &turn.cwd);
+            if locations.is_empty() {
+                ("No locations found.".to_string(), false)
+            } else {
+                (locations.join("\n"), true)
+            }
+        };
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(found),
+        })
+    }
+}
+
+fn no_server_error(path: &Path) -> FunctionCallError {
+    FunctionCallError::RespondToModel(format!(
+        "no language server is configured for `{}`",
+        path.display()
+    ))
+}
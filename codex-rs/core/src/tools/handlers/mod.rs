@@ -1,8 +1,10 @@
 pub mod apply_patch;
 pub(crate) mod collab;
 mod dynamic;
+mod find_symbol;
 mod grep_files;
 mod list_dir;
+mod lsp;
 mod mcp;
 mod mcp_resource;
 mod plan;
@@ -20,8 +22,10 @@ use crate::function_tool::FunctionCallError;
 pub use apply_patch::ApplyPatchHandler;
 pub use collab::CollabHandler;
 pub use dynamic::DynamicToolHandler;
+pub use find_symbol::FindSymbolHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
+pub use lsp::LspHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
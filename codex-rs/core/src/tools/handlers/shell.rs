@@ -251,6 +251,10 @@ impl ShellHandler {
         if !dependency_env.is_empty() {
             exec_params.env.extend(dependency_env);
         }
+        session
+            .services
+            .shell_secrets
+            .apply_env(&mut exec_params.env);
 
         // Approval policy guard for explicit escalation in non-OnRequest modes.
         if exec_params
@@ -5,6 +5,7 @@ Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
 retry without sandbox on denial (no re‑approval thanks to caching).
 */
+use crate::config::approval_rules::ApprovalRuleDecision;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
@@ -16,6 +17,8 @@ use crate::tools::sandboxing::SandboxOverride;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::apply_approval_rule;
+use crate::tools::sandboxing::cache_session_approval;
 use crate::tools::sandboxing::default_exec_approval_requirement;
 use codex_otel::ToolDecisionSource;
 use codex_protocol::protocol::AskForApproval;
@@ -55,6 +58,24 @@ impl ToolOrchestrator {
         let requirement = tool.exec_approval_requirement(req).unwrap_or_else(|| {
             default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
         });
+        let rule_target = tool.approval_rule_target(req);
+        let rule_decision = rule_target.as_ref().and_then(|target| {
+            tool_ctx.session.services.approval_rules.decision_for(
+                target.tool,
+                &target.paths,
+                &turn_ctx.cwd,
+            )
+        });
+        let bypass_approval_cache = rule_decision == Some(ApprovalRuleDecision::Ask);
+        // An `allow` rule keeps requests that leave the sandbox behind a prompt.
+        let allow_lifts_prompt = rule_target.is_some_and(|target| target.allow_lifts_prompt)
+            && tool.sandbox_mode_for_first_attempt(req) == SandboxOverride::NoOverride;
+        let requirement = apply_approval_rule(
+            requirement,
+            rule_decision,
+            approval_policy,
+            allow_lifts_prompt,
+        );
         match requirement {
             ExecApprovalRequirement::Skip { .. } => {
                 otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
@@ -63,25 +84,49 @@ impl ToolOrchestrator {
                 return Err(ToolError::Rejected(reason));
             }
             ExecApprovalRequirement::NeedsApproval { reason, .. } => {
-                let approval_ctx = ApprovalCtx {
-                    session: tool_ctx.session,
-                    turn: turn_ctx,
-                    call_id: &tool_ctx.call_id,
-                    retry_reason: reason,
+                let memory = &tool_ctx.session.services.approval_memory;
+                // An `ask` rule must prompt every time, so it ignores approvals
+                // remembered for the repo as well as the session cache.
+                let repo_approvals = if bypass_approval_cache {
+                    Vec::new()
+                } else {
+                    tool.repo_approvals(req, memory)
                 };
-                let decision = tool.start_approval_async(req, approval_ctx).await;
+                // Like an `allow` rule, a remembered approval only skips this
+                // prompt: leaving the sandbox after a denial still asks.
+                if memory.covers(repo_approvals.clone()).await {
+                    otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
+                } else {
+                    let approval_ctx = ApprovalCtx {
+                        session: tool_ctx.session,
+                        turn: turn_ctx,
+                        call_id: &tool_ctx.call_id,
+                        retry_reason: reason,
+                        bypass_approval_cache,
+                    };
+                    let decision = tool.start_approval_async(req, approval_ctx).await;
 
-                otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
+                    otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
 
-                match decision {
-                    ReviewDecision::Denied | ReviewDecision::Abort => {
-                        return Err(ToolError::Rejected("rejected by user".to_string()));
+                    match decision {
+                        ReviewDecision::Denied | ReviewDecision::Abort => {
+                            return Err(ToolError::Rejected("rejected by user".to_string()));
+                        }
+                        ReviewDecision::ApprovedForRepo if repo_approvals.is_empty() => {
+                            // Nothing to remember: approve for the session
+                            // instead, unless an `ask` rule forbids caching.
+                            if !bypass_approval_cache {
+                                let keys = tool.approval_keys(req);
+                                cache_session_approval(&tool_ctx.session.services, keys).await;
+                            }
+                        }
+                        ReviewDecision::ApprovedForRepo => memory.remember(repo_approvals).await,
+                        ReviewDecision::Approved
+                        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                        | ReviewDecision::ApprovedForSession => {}
                     }
-                    ReviewDecision::Approved
-                    | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                    | ReviewDecision::ApprovedForSession => {}
+                    already_approved = true;
                 }
-                already_approved = true;
             }
         }
 
@@ -133,6 +178,7 @@ impl ToolOrchestrator {
                         turn: turn_ctx,
                         call_id: &tool_ctx.call_id,
                         retry_reason: Some(reason_msg),
+                        bypass_approval_cache,
                     };
 
                     let decision = tool.start_approval_async(req, approval_ctx).await;
@@ -144,7 +190,8 @@ impl ToolOrchestrator {
                         }
                         ReviewDecision::Approved
                         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                        | ReviewDecision::ApprovedForSession => {}
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedForRepo => {}
                     }
                 }
 
@@ -170,3 +217,291 @@ fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // output so we can evolve heuristics later without touching call sites.
     "command failed; retry without sandbox?".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approval_memory::APPROVALS_FILE;
+    use crate::approval_memory::ApprovalMemory;
+    use crate::approval_memory::RememberedApproval;
+    use crate::approval_memory::project_root;
+    use crate::approval_memory::revoke_remembered_approval;
+    use crate::codex::TurnContext;
+    use crate::codex::make_session_and_context;
+    use crate::config::approval_rules::ApprovalRuleToml;
+    use crate::config::approval_rules::SHELL_RULE_TOOL;
+    use crate::config::approval_rules::resolve_approval_rules;
+    use crate::tools::sandboxing::Approvable;
+    use crate::tools::sandboxing::ApprovalRuleTarget;
+    use crate::tools::sandboxing::Sandboxable;
+    use crate::tools::sandboxing::SandboxablePreference;
+    use crate::tools::sandboxing::with_cached_approval;
+    use futures::future::BoxFuture;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    struct FakeRequest {
+        cwd: PathBuf,
+        escalated: bool,
+    }
+
+    struct FakeRuntime {
+        decision: ReviewDecision,
+        repo_approvals: Vec<RememberedApproval>,
+        approval_requests: usize,
+        runs: usize,
+        deny_first_run: bool,
+    }
+
+    impl FakeRuntime {
+        fn new(decision: ReviewDecision) -> Self {
+            Self {
+                decision,
+                repo_approvals: Vec::new(),
+                approval_requests: 0,
+                runs: 0,
+                deny_first_run: false,
+            }
+        }
+    }
+
+    impl Sandboxable for FakeRuntime {
+        fn sandbox_preference(&self) -> SandboxablePreference {
+            SandboxablePreference::Auto
+        }
+    }
+
+    impl Approvable<FakeRequest> for FakeRuntime {
+        type ApprovalKey = String;
+
+        fn approval_keys(&self, req: &FakeRequest) -> Vec<Self::ApprovalKey> {
+            vec![req.cwd.display().to_string()]
+        }
+
+        fn approval_rule_target(&self, req: &FakeRequest) -> Option<ApprovalRuleTarget> {
+            Some(ApprovalRuleTarget {
+                tool: SHELL_RULE_TOOL,
+                paths: vec![req.cwd.clone()],
+                allow_lifts_prompt: true,
+            })
+        }
+
+        fn repo_approvals(
+            &self,
+            _req: &FakeRequest,
+            _memory: &ApprovalMemory,
+        ) -> Vec<RememberedApproval> {
+            self.repo_approvals.clone()
+        }
+
+        fn sandbox_mode_for_first_attempt(&self, req: &FakeRequest) -> SandboxOverride {
+            if req.escalated {
+                SandboxOverride::BypassSandboxFirstAttempt
+            } else {
+                SandboxOverride::NoOverride
+            }
+        }
+
+        fn exec_approval_requirement(&self, _req: &FakeRequest) -> Option<ExecApprovalRequirement> {
+            Some(ExecApprovalRequirement::NeedsApproval {
+                reason: None,
+                proposed_execpolicy_amendment: None,
+            })
+        }
+
+        fn start_approval_async<'a>(
+            &'a mut self,
+            req: &'a FakeRequest,
+            ctx: ApprovalCtx<'a>,
+        ) -> BoxFuture<'a, ReviewDecision> {
+            let keys = ctx.cache_keys(self.approval_keys(req));
+            let decision = self.decision.clone();
+            let approval_requests = &mut self.approval_requests;
+            Box::pin(async move {
+                with_cached_approval(&ctx.session.services, "fake", keys, || async move {
+                    *approval_requests += 1;
+                    decision
+                })
+                .await
+            })
+        }
+    }
+
+    impl ToolRuntime<FakeRequest, ()> for FakeRuntime {
+        async fn run(
+            &mut self,
+            _req: &FakeRequest,
+            _attempt: &SandboxAttempt<'_>,
+            _ctx: &ToolCtx<'_>,
+        ) -> Result<(), ToolError> {
+            self.runs += 1;
+            if self.deny_first_run && self.runs == 1 {
+                return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
+                    output: Box::new(ExecToolCallOutput::default()),
+                })));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn allow_rule_does_not_skip_prompt_for_escalated_requests() {
+        let (mut session, turn) = make_session_and_context().await;
+        session.services.approval_rules = resolve_approval_rules(Some(&[ApprovalRuleToml {
+            tool: SHELL_RULE_TOOL.to_string(),
+            paths: None,
+            decision: ApprovalRuleDecision::Allow,
+        }]))
+        .expect("valid approval rules");
+        let tool_ctx = ToolCtx {
+            session: &session,
+            turn: &turn,
+            call_id: "call-1".to_string(),
+            tool_name: "shell".to_string(),
+        };
+
+        let mut runtime = FakeRuntime::new(ReviewDecision::Denied);
+        let sandboxed = FakeRequest {
+            cwd: turn.cwd.clone(),
+            escalated: false,
+        };
+        let result = ToolOrchestrator::new()
+            .run(
+                &mut runtime,
+                &sandboxed,
+                &tool_ctx,
+                &turn,
+                AskForApproval::OnRequest,
+            )
+            .await;
+        assert!(result.is_ok());
+        assert_eq!((runtime.approval_requests, runtime.runs), (0, 1));
+
+        let mut runtime = FakeRuntime::new(ReviewDecision::Denied);
+        let escalated = FakeRequest {
+            cwd: turn.cwd.clone(),
+            escalated: true,
+        };
+        let result = ToolOrchestrator::new()
+            .run(
+                &mut runtime,
+                &escalated,
+                &tool_ctx,
+                &turn,
+                AskForApproval::OnRequest,
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::Rejected(_))));
+        assert_eq!((runtime.approval_requests, runtime.runs), (1, 0));
+    }
+
+    async fn run_twice(
+        runtime: &mut FakeRuntime,
+        tool_ctx: &ToolCtx<'_>,
+        turn: &TurnContext,
+        between: impl FnOnce(),
+    ) {
+        let req = FakeRequest {
+            cwd: turn.cwd.clone(),
+            escalated: false,
+        };
+        let mut orchestrator = ToolOrchestrator::new();
+        let first = orchestrator
+            .run(runtime, &req, tool_ctx, turn, AskForApproval::UnlessTrusted)
+            .await;
+        assert!(first.is_ok());
+        between();
+        let second = orchestrator
+            .run(runtime, &req, tool_ctx, turn, AskForApproval::UnlessTrusted)
+            .await;
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn revoked_repo_approval_prompts_again_in_the_same_session() {
+        let (mut session, turn) = make_session_and_context().await;
+        let codex_home = tempdir().expect("tempdir");
+        session.services.approval_memory =
+            ApprovalMemory::new(codex_home.path().to_path_buf(), &turn.cwd);
+        let tool_ctx = ToolCtx {
+            session: &session,
+            turn: &turn,
+            call_id: "call-1".to_string(),
+            tool_name: "fake".to_string(),
+        };
+        let approval = RememberedApproval::McpTool("mcp__fake__tool".to_string());
+        let mut runtime = FakeRuntime::new(ReviewDecision::ApprovedForRepo);
+        runtime.repo_approvals = vec![approval.clone()];
+
+        run_twice(&mut runtime, &tool_ctx, &turn, || {
+            assert!(
+                revoke_remembered_approval(codex_home.path(), &project_root(&turn.cwd), &approval)
+                    .expect("revoke")
+            );
+        })
+        .await;
+
+        assert_eq!((runtime.approval_requests, runtime.runs), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn repo_approval_that_cannot_be_remembered_lasts_for_the_session() {
+        let (mut session, turn) = make_session_and_context().await;
+        let codex_home = tempdir().expect("tempdir");
+        session.services.approval_memory =
+            ApprovalMemory::new(codex_home.path().to_path_buf(), &turn.cwd);
+        let tool_ctx = ToolCtx {
+            session: &session,
+            turn: &turn,
+            call_id: "call-1".to_string(),
+            tool_name: "fake".to_string(),
+        };
+        let mut runtime = FakeRuntime::new(ReviewDecision::ApprovedForRepo);
+
+        run_twice(&mut runtime, &tool_ctx, &turn, || {}).await;
+
+        assert_eq!((runtime.approval_requests, runtime.runs), (1, 2));
+        assert!(!codex_home.path().join(APPROVALS_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn repo_approval_does_not_skip_prompt_to_leave_the_sandbox() {
+        let (mut session, turn) = make_session_and_context().await;
+        let codex_home = tempdir().expect("tempdir");
+        session.services.approval_memory =
+            ApprovalMemory::new(codex_home.path().to_path_buf(), &turn.cwd);
+        let approval = RememberedApproval::McpTool("mcp__fake__tool".to_string());
+        session
+            .services
+            .approval_memory
+            .remember(vec![approval.clone()])
+            .await;
+        let tool_ctx = ToolCtx {
+            session: &session,
+            turn: &turn,
+            call_id: "call-1".to_string(),
+            tool_name: "fake".to_string(),
+        };
+        let mut runtime = FakeRuntime::new(ReviewDecision::Denied);
+        runtime.repo_approvals = vec![approval];
+        runtime.deny_first_run = true;
+        let req = FakeRequest {
+            cwd: turn.cwd.clone(),
+            escalated: false,
+        };
+
+        let result = ToolOrchestrator::new()
+            .run(
+                &mut runtime,
+                &req,
+                &tool_ctx,
+                &turn,
+                AskForApproval::UnlessTrusted,
+            )
+            .await;
+
+        assert!(matches!(result, Err(ToolError::Rejected(_))));
+        assert_eq!((runtime.approval_requests, runtime.runs), (1, 1));
+    }
+}
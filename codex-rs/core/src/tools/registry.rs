@@ -121,14 +121,23 @@ impl ToolRegistry {
                             invocation.turn.tool_call_gate.wait_ready().await;
                             tracing::trace!("tool gate released");
                         }
+                        // Redact before logging so secret values never reach
+                        // telemetry, the rollout, or the model.
+                        let session = Arc::clone(&invocation.session);
+                        let shell_secrets = &session.services.shell_secrets;
                         match handler.handle(invocation).await {
-                            Ok(output) => {
+                            Ok(mut output) => {
+                                shell_secrets.redact_tool_output(&mut output);
                                 let preview = output.log_preview();
                                 let success = output.success_for_logging();
                                 let mut guard = output_cell.lock().await;
                                 *guard = Some(output);
                                 Ok((preview, success))
                             }
+                            Err(FunctionCallError::RespondToModel(mut message)) => {
+                                shell_secrets.redact_in_place(&mut message);
+                                Err(FunctionCallError::RespondToModel(message))
+                            }
                             Err(err) => Err(err),
                         }
                     }
@@ -219,6 +228,12 @@ impl ToolRegistryBuilder {
     //     }
     // }
 
+    /// Drops every spec and handler whose tool name does not satisfy `keep`.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|configured| keep(configured.spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     pub fn build(self) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
         let registry = ToolRegistry::new(self.handlers);
         (self.specs, registry)
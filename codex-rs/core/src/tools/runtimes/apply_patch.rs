@@ -5,12 +5,16 @@
 //! `codex --codex-run-as-apply-patch`, and runs under the current
 //! `SandboxAttempt` with a minimal environment.
 use crate::CODEX_APPLY_PATCH_ARG1;
+use crate::approval_memory::ApprovalMemory;
+use crate::approval_memory::RememberedApproval;
+use crate::config::approval_rules::APPLY_PATCH_RULE_TOOL;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
 use crate::sandboxing::execute_env;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ApprovalRuleTarget;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
 use crate::tools::sandboxing::Sandboxable;
@@ -92,6 +96,31 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
         req.file_paths.clone()
     }
 
+    fn approval_rule_target(&self, req: &ApplyPatchRequest) -> Option<ApprovalRuleTarget> {
+        Some(ApprovalRuleTarget {
+            tool: APPLY_PATCH_RULE_TOOL,
+            paths: req
+                .file_paths
+                .iter()
+                .map(AbsolutePathBuf::to_path_buf)
+                .collect(),
+            allow_lifts_prompt: true,
+        })
+    }
+
+    // Every touched file must be inside the repo for the patch to be remembered.
+    fn repo_approvals(
+        &self,
+        req: &ApplyPatchRequest,
+        memory: &ApprovalMemory,
+    ) -> Vec<RememberedApproval> {
+        req.file_paths
+            .iter()
+            .map(|path| memory.file_write(path.as_path()))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
     fn start_approval_async<'a>(
         &'a mut self,
         req: &'a ApplyPatchRequest,
@@ -101,7 +130,7 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
         let retry_reason = ctx.retry_reason.clone();
-        let approval_keys = self.approval_keys(req);
+        let approval_keys = ctx.cache_keys(self.approval_keys(req));
         let changes = req.changes.clone();
         Box::pin(async move {
             if let Some(reason) = retry_reason {
@@ -8,9 +8,13 @@ use crate::exec::ExecExpiration;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
 use crate::shell::Shell;
+use crate::shell::ShellType;
+use crate::shell::detect_shell_type;
+use crate::shell_snapshot::source_snapshot_script;
 use crate::tools::sandboxing::ToolError;
 use std::collections::HashMap;
 use std::path::Path;
+use std::path::PathBuf;
 
 pub mod apply_patch;
 pub mod shell;
@@ -40,16 +44,18 @@ pub(crate) fn build_command_spec(
     })
 }
 
-/// POSIX-only helper: for commands produced by `Shell::derive_exec_args`
-/// for Bash/Zsh/sh of the form `[shell_path, "-lc", "<script>"]`, and
-/// when a snapshot is configured on the session shell, rewrite the argv
-/// to a single non-login shell that sources the snapshot before running
-/// the original script:
+/// For login-shell commands produced by `Shell::derive_exec_args`, and when a
+/// snapshot is configured on the session shell, rewrite the argv to a single
+/// non-login shell that sources the snapshot before running the original
+/// script:
 ///
 ///   shell -lc "<script>"
 ///   => shell -c ". SNAPSHOT && <script>"
 ///
-/// On non-POSIX shells or non-matching commands this is a no-op.
+/// Fish uses `source` in place of `.`, and Nushell's `nu --login -c
+/// "<script>"` becomes `nu -c "source SNAPSHOT; <script>"`. The snapshot is
+/// only applied when the command runs under the same kind of shell that
+/// produced it. On other shells or non-matching commands this is a no-op.
 pub(crate) fn maybe_wrap_shell_lc_with_snapshot(
     command: &[String],
     session_shell: &Shell,
@@ -62,20 +68,139 @@ pub(crate) fn maybe_wrap_shell_lc_with_snapshot(
         return command.to_vec();
     }
 
-    if command.len() < 3 {
-        return command.to_vec();
+    let command_shell_type = command
+        .first()
+        .and_then(|shell| detect_shell_type(&PathBuf::from(shell)));
+    match (&session_shell.shell_type, command) {
+        (ShellType::Fish, [shell, flag, script])
+            if flag == "-lc" && command_shell_type == Some(ShellType::Fish) =>
+        {
+            let source = source_snapshot_script(&ShellType::Fish, &snapshot.path);
+            vec![
+                shell.clone(),
+                "-c".to_string(),
+                format!("{source} && {script}"),
+            ]
+        }
+        (ShellType::Nushell, [shell, flag, c_flag, script])
+            if (flag == "--login" || flag == "-l")
+                && c_flag == "-c"
+                && command_shell_type == Some(ShellType::Nushell) =>
+        {
+            let source = source_snapshot_script(&ShellType::Nushell, &snapshot.path);
+            vec![
+                shell.clone(),
+                "-c".to_string(),
+                format!("{source}; {script}"),
+            ]
+        }
+        (ShellType::Fish | ShellType::Nushell, _) => command.to_vec(),
+        (_, [shell, flag, script])
+            if flag == "-lc"
+                && !matches!(
+                    command_shell_type,
+                    Some(ShellType::Fish | ShellType::Nushell)
+                ) =>
+        {
+            let snapshot_path = snapshot.path.to_string_lossy();
+            vec![
+                shell.clone(),
+                "-c".to_string(),
+                format!(". \"{snapshot_path}\" && {script}"),
+            ]
+        }
+        _ => command.to_vec(),
     }
+}
 
-    let flag = command[1].as_str();
-    if flag != "-lc" {
-        return command.to_vec();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell_snapshot::ShellSnapshot;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use tokio::sync::watch;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
     }
 
-    let snapshot_path = snapshot.path.to_string_lossy();
-    let rewritten_script = format!(". \"{snapshot_path}\" && {}", command[2]);
+    fn shell_with_snapshot(shell_type: ShellType, shell_path: &str, snapshot: &Path) -> Shell {
+        let (_tx, shell_snapshot) = watch::channel(Some(Arc::new(ShellSnapshot {
+            path: snapshot.to_path_buf(),
+        })));
+        Shell {
+            shell_type,
+            shell_path: PathBuf::from(shell_path),
+            shell_snapshot,
+        }
+    }
+
+    #[test]
+    fn fish_login_commands_source_the_snapshot() {
+        let dir = tempdir().expect("tempdir");
+        let snapshot = dir.path().join("snapshot.fish");
+        std::fs::write(&snapshot, "").expect("write snapshot");
+        let shell = shell_with_snapshot(ShellType::Fish, "/usr/bin/fish", &snapshot);
 
-    let mut rewritten = command.to_vec();
-    rewritten[1] = "-c".to_string();
-    rewritten[2] = rewritten_script;
-    rewritten
+        let wrapped =
+            maybe_wrap_shell_lc_with_snapshot(&vec_str(&["/usr/bin/fish", "-lc", "ls"]), &shell);
+        assert_eq!(
+            wrapped,
+            vec![
+                "/usr/bin/fish".to_string(),
+                "-c".to_string(),
+                format!("source '{}' && ls", snapshot.display()),
+            ]
+        );
+
+        // A bash command run from a fish session keeps its login shell.
+        let bash = vec_str(&["/bin/bash", "-lc", "ls"]);
+        assert_eq!(maybe_wrap_shell_lc_with_snapshot(&bash, &shell), bash);
+    }
+
+    #[test]
+    fn nushell_login_commands_source_the_snapshot() {
+        let dir = tempdir().expect("tempdir");
+        let snapshot = dir.path().join("snapshot.nu");
+        std::fs::write(&snapshot, "").expect("write snapshot");
+        let shell = shell_with_snapshot(ShellType::Nushell, "/usr/bin/nu", &snapshot);
+
+        let wrapped = maybe_wrap_shell_lc_with_snapshot(
+            &vec_str(&["/usr/bin/nu", "--login", "-c", "ls | length"]),
+            &shell,
+        );
+        assert_eq!(
+            wrapped,
+            vec![
+                "/usr/bin/nu".to_string(),
+                "-c".to_string(),
+                format!("source r#'{}'#; ls | length", snapshot.display()),
+            ]
+        );
+
+        // Non-login invocations are left alone.
+        let plain = vec_str(&["/usr/bin/nu", "-c", "ls"]);
+        assert_eq!(maybe_wrap_shell_lc_with_snapshot(&plain, &shell), plain);
+    }
+
+    #[test]
+    fn bash_sessions_do_not_wrap_fish_commands() {
+        let dir = tempdir().expect("tempdir");
+        let snapshot = dir.path().join("snapshot.sh");
+        std::fs::write(&snapshot, "").expect("write snapshot");
+        let shell = shell_with_snapshot(ShellType::Bash, "/bin/bash", &snapshot);
+
+        let fish = vec_str(&["/usr/bin/fish", "-lc", "ls"]);
+        assert_eq!(maybe_wrap_shell_lc_with_snapshot(&fish, &shell), fish);
+        assert_eq!(
+            maybe_wrap_shell_lc_with_snapshot(&vec_str(&["/bin/bash", "-lc", "ls"]), &shell),
+            vec![
+                "/bin/bash".to_string(),
+                "-c".to_string(),
+                format!(". \"{}\" && ls", snapshot.display()),
+            ]
+        );
+    }
 }
@@ -4,6 +4,9 @@ Runtime: shell
 Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
+use crate::approval_memory::ApprovalMemory;
+use crate::approval_memory::RememberedApproval;
+use crate::config::approval_rules::SHELL_RULE_TOOL;
 use crate::exec::ExecToolCallOutput;
 use crate::features::Feature;
 use crate::powershell::prefix_powershell_script_with_utf8;
@@ -14,6 +17,7 @@ use crate::tools::runtimes::build_command_spec;
 use crate::tools::runtimes::maybe_wrap_shell_lc_with_snapshot;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ApprovalRuleTarget;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
 use crate::tools::sandboxing::SandboxOverride;
@@ -22,6 +26,7 @@ use crate::tools::sandboxing::SandboxablePreference;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::shell_allow_lifts_prompt;
 use crate::tools::sandboxing::with_cached_approval;
 use codex_protocol::protocol::ReviewDecision;
 use futures::future::BoxFuture;
@@ -82,12 +87,35 @@ impl Approvable<ShellRequest> for ShellRuntime {
         }]
     }
 
+    fn approval_rule_target(&self, req: &ShellRequest) -> Option<ApprovalRuleTarget> {
+        Some(ApprovalRuleTarget {
+            tool: SHELL_RULE_TOOL,
+            paths: vec![req.cwd.clone()],
+            allow_lifts_prompt: shell_allow_lifts_prompt(
+                &req.command,
+                &req.exec_approval_requirement,
+            ),
+        })
+    }
+
+    // Requests that leave the sandbox are approved for the session at most.
+    fn repo_approvals(
+        &self,
+        req: &ShellRequest,
+        memory: &ApprovalMemory,
+    ) -> Vec<RememberedApproval> {
+        if req.sandbox_permissions.requires_escalated_permissions() {
+            return Vec::new();
+        }
+        memory.command(&req.command, &req.cwd).into_iter().collect()
+    }
+
     fn start_approval_async<'a>(
         &'a mut self,
         req: &'a ShellRequest,
         ctx: ApprovalCtx<'a>,
     ) -> BoxFuture<'a, ReviewDecision> {
-        let keys = self.approval_keys(req);
+        let keys = ctx.cache_keys(self.approval_keys(req));
         let command = req.command.clone();
         let cwd = req.cwd.clone();
         let reason = ctx
@@ -4,6 +4,9 @@ Runtime: unified exec
 Handles approval + sandbox orchestration for unified exec requests, delegating to
 the process manager to spawn PTYs once an ExecEnv is prepared.
 */
+use crate::approval_memory::ApprovalMemory;
+use crate::approval_memory::RememberedApproval;
+use crate::config::approval_rules::SHELL_RULE_TOOL;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecExpiration;
@@ -15,6 +18,7 @@ use crate::tools::runtimes::build_command_spec;
 use crate::tools::runtimes::maybe_wrap_shell_lc_with_snapshot;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ApprovalRuleTarget;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
 use crate::tools::sandboxing::SandboxOverride;
@@ -23,6 +27,7 @@ use crate::tools::sandboxing::SandboxablePreference;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::shell_allow_lifts_prompt;
 use crate::tools::sandboxing::with_cached_approval;
 use crate::unified_exec::UnifiedExecError;
 use crate::unified_exec::UnifiedExecProcess;
@@ -105,12 +110,35 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         }]
     }
 
+    fn approval_rule_target(&self, req: &UnifiedExecRequest) -> Option<ApprovalRuleTarget> {
+        Some(ApprovalRuleTarget {
+            tool: SHELL_RULE_TOOL,
+            paths: vec![req.cwd.clone()],
+            allow_lifts_prompt: shell_allow_lifts_prompt(
+                &req.command,
+                &req.exec_approval_requirement,
+            ),
+        })
+    }
+
+    // Requests that leave the sandbox are approved for the session at most.
+    fn repo_approvals(
+        &self,
+        req: &UnifiedExecRequest,
+        memory: &ApprovalMemory,
+    ) -> Vec<RememberedApproval> {
+        if req.sandbox_permissions.requires_escalated_permissions() {
+            return Vec::new();
+        }
+        memory.command(&req.command, &req.cwd).into_iter().collect()
+    }
+
     fn start_approval_async<'b>(
         &'b mut self,
         req: &'b UnifiedExecRequest,
         ctx: ApprovalCtx<'b>,
     ) -> BoxFuture<'b, ReviewDecision> {
-        let keys = self.approval_keys(req);
+        let keys = ctx.cache_keys(self.approval_keys(req));
         let session = ctx.session;
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
@@ -4,9 +4,13 @@
 //! `ApprovalCtx`, `Approvable`) together with the sandbox orchestration traits
 //! and helpers (`Sandboxable`, `ToolRuntime`, `SandboxAttempt`, etc.).
 
+use crate::approval_memory::ApprovalMemory;
+use crate::approval_memory::RememberedApproval;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::approval_rules::ApprovalRuleDecision;
 use crate::error::CodexErr;
+use crate::is_dangerous_command::command_might_be_dangerous;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxManager;
@@ -19,6 +23,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::path::Path;
+use std::path::PathBuf;
 
 use futures::Future;
 use futures::future::BoxFuture;
@@ -55,6 +60,8 @@ impl ApprovalStore {
 /// - If all keys are already approved for session, we skip prompting.
 /// - If the user approves for session, we store the decision for each key individually
 ///   so future requests touching any subset can also skip prompting.
+/// - Approvals for the repo are not cached: the orchestrator looks them up in
+///   `approvals.toml` on every request, so revoking one takes effect at once.
 pub(crate) async fn with_cached_approval<K, F, Fut>(
     services: &SessionServices,
     // Name of the tool, used for metrics collection.
@@ -93,22 +100,44 @@ where
         ],
     );
 
-    if matches!(decision, ReviewDecision::ApprovedForSession) {
-        let mut store = services.tool_approvals.lock().await;
-        for key in keys {
-            store.put(key, ReviewDecision::ApprovedForSession);
-        }
+    if decision == ReviewDecision::ApprovedForSession {
+        cache_session_approval(services, keys).await;
     }
 
     decision
 }
 
+pub(crate) async fn cache_session_approval<K>(services: &SessionServices, keys: Vec<K>)
+where
+    K: Serialize,
+{
+    let mut store = services.tool_approvals.lock().await;
+    for key in keys {
+        store.put(key, ReviewDecision::ApprovedForSession);
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ApprovalCtx<'a> {
     pub session: &'a Session,
     pub turn: &'a TurnContext,
     pub call_id: &'a str,
     pub retry_reason: Option<String>,
+    /// Set when an `ask` approval rule matched: the user must be prompted even
+    /// if an earlier call was approved for the session.
+    pub bypass_approval_cache: bool,
+}
+
+impl ApprovalCtx<'_> {
+    /// The keys to pass to [`with_cached_approval`]; empty when the session
+    /// cache must not be consulted.
+    pub fn cache_keys<K>(&self, keys: Vec<K>) -> Vec<K> {
+        if self.bypass_approval_cache {
+            Vec::new()
+        } else {
+            keys
+        }
+    }
 }
 
 // Specifies what tool orchestrator should do with a given tool call.
@@ -179,6 +208,81 @@ pub(crate) fn default_exec_approval_requirement(
     }
 }
 
+/// Refines `requirement` with the decision of the `[[approval_rules]]` that
+/// matched the call. Rules never lift a `Forbidden` requirement, an `ask`
+/// rule cannot prompt when the approval policy is `never`, and an `allow` rule
+/// only skips the prompt when `allow_lifts_prompt` is set.
+pub(crate) fn apply_approval_rule(
+    requirement: ExecApprovalRequirement,
+    decision: Option<ApprovalRuleDecision>,
+    policy: AskForApproval,
+    allow_lifts_prompt: bool,
+) -> ExecApprovalRequirement {
+    match (decision, requirement) {
+        (None, requirement) | (_, requirement @ ExecApprovalRequirement::Forbidden { .. }) => {
+            requirement
+        }
+        (Some(ApprovalRuleDecision::Deny), _) => ExecApprovalRequirement::Forbidden {
+            reason: "blocked by an approval rule in config.toml".to_string(),
+        },
+        (Some(ApprovalRuleDecision::Ask), _) if policy == AskForApproval::Never => {
+            ExecApprovalRequirement::Forbidden {
+                reason: "an approval rule requires approval, but approval_policy is `never`"
+                    .to_string(),
+            }
+        }
+        (
+            Some(ApprovalRuleDecision::Ask),
+            ExecApprovalRequirement::Skip {
+                proposed_execpolicy_amendment,
+                ..
+            },
+        ) => ExecApprovalRequirement::NeedsApproval {
+            reason: Some("an approval rule in config.toml requires approval".to_string()),
+            proposed_execpolicy_amendment,
+        },
+        (
+            Some(ApprovalRuleDecision::Allow),
+            ExecApprovalRequirement::NeedsApproval {
+                proposed_execpolicy_amendment,
+                ..
+            },
+        ) if allow_lifts_prompt => ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            proposed_execpolicy_amendment,
+        },
+        (Some(ApprovalRuleDecision::Ask | ApprovalRuleDecision::Allow), requirement) => requirement,
+    }
+}
+
+/// What `[[approval_rules]]` match a call against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ApprovalRuleTarget {
+    pub tool: &'static str,
+    pub paths: Vec<PathBuf>,
+    /// Whether an `allow` rule may skip this call's prompt. The orchestrator
+    /// also keeps the prompt for requests that run outside the sandbox.
+    pub allow_lifts_prompt: bool,
+}
+
+/// Whether an `allow` rule may skip the prompt for a shell command. Commands
+/// flagged as dangerous and commands matched by an execpolicy `prompt` rule
+/// keep their prompt, since those prompts are not the approval policy's.
+pub(crate) fn shell_allow_lifts_prompt(
+    command: &[String],
+    requirement: &ExecApprovalRequirement,
+) -> bool {
+    // Execpolicy only attaches a reason when one of its `prompt` rules matched.
+    let prompted_by_execpolicy = matches!(
+        requirement,
+        ExecApprovalRequirement::NeedsApproval {
+            reason: Some(_),
+            ..
+        }
+    );
+    !prompted_by_execpolicy && !command_might_be_dangerous(command)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum SandboxOverride {
     NoOverride,
@@ -197,6 +301,19 @@ pub(crate) trait Approvable<Req> {
     // requests touching a subset can be auto-approved.
     fn approval_keys(&self, req: &Req) -> Vec<Self::ApprovalKey>;
 
+    /// The tool name and paths `[[approval_rules]]` are matched against, or
+    /// `None` when rules do not apply to this runtime.
+    fn approval_rule_target(&self, _req: &Req) -> Option<ApprovalRuleTarget> {
+        None
+    }
+
+    /// What an "always allow in this repo" decision records for this request.
+    /// Return an empty list when the request cannot be remembered; the
+    /// decision then approves it for the session instead.
+    fn repo_approvals(&self, _req: &Req, _memory: &ApprovalMemory) -> Vec<RememberedApproval> {
+        Vec::new()
+    }
+
     /// Some tools may request to skip the sandbox on the first attempt
     /// (e.g., when the request explicitly asks for escalated permissions).
     /// Defaults to `NoOverride`.
@@ -315,6 +432,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn approval_rules_refine_requirements() {
+        let skip = ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            proposed_execpolicy_amendment: None,
+        };
+        let needs_approval = ExecApprovalRequirement::NeedsApproval {
+            reason: None,
+            proposed_execpolicy_amendment: None,
+        };
+        let forbidden = ExecApprovalRequirement::Forbidden {
+            reason: "outside the workspace".to_string(),
+        };
+
+        assert_eq!(
+            apply_approval_rule(
+                needs_approval.clone(),
+                Some(ApprovalRuleDecision::Allow),
+                AskForApproval::UnlessTrusted,
+                true,
+            ),
+            skip
+        );
+        assert_eq!(
+            apply_approval_rule(
+                needs_approval.clone(),
+                Some(ApprovalRuleDecision::Allow),
+                AskForApproval::UnlessTrusted,
+                false,
+            ),
+            needs_approval
+        );
+        assert_eq!(
+            apply_approval_rule(
+                forbidden.clone(),
+                Some(ApprovalRuleDecision::Allow),
+                AskForApproval::UnlessTrusted,
+                true,
+            ),
+            forbidden
+        );
+        assert_eq!(
+            apply_approval_rule(
+                skip.clone(),
+                Some(ApprovalRuleDecision::Ask),
+                AskForApproval::OnRequest,
+                true,
+            ),
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some("an approval rule in config.toml requires approval".to_string()),
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        assert!(matches!(
+            apply_approval_rule(
+                skip.clone(),
+                Some(ApprovalRuleDecision::Ask),
+                AskForApproval::Never,
+                true,
+            ),
+            ExecApprovalRequirement::Forbidden { .. }
+        ));
+        assert_eq!(
+            apply_approval_rule(
+                skip.clone(),
+                Some(ApprovalRuleDecision::Deny),
+                AskForApproval::Never,
+                true,
+            ),
+            ExecApprovalRequirement::Forbidden {
+                reason: "blocked by an approval rule in config.toml".to_string(),
+            }
+        );
+        assert_eq!(
+            apply_approval_rule(skip.clone(), None, AskForApproval::Never, true),
+            skip
+        );
+    }
+
+    #[test]
+    fn allow_rules_keep_execpolicy_and_dangerous_command_prompts() {
+        let command = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let default_prompt = ExecApprovalRequirement::NeedsApproval {
+            reason: None,
+            proposed_execpolicy_amendment: None,
+        };
+        let policy_prompt = ExecApprovalRequirement::NeedsApproval {
+            reason: Some("`cargo publish` requires approval by policy".to_string()),
+            proposed_execpolicy_amendment: None,
+        };
+
+        assert!(shell_allow_lifts_prompt(
+            &command(&["cargo", "test"]),
+            &default_prompt
+        ));
+        assert!(!shell_allow_lifts_prompt(
+            &command(&["cargo", "publish"]),
+            &policy_prompt
+        ));
+        assert!(!shell_allow_lifts_prompt(
+            &command(&["git", "reset", "--hard"]),
+            &default_prompt
+        ));
+        assert!(!shell_allow_lifts_prompt(
+            &command(&["bash", "-lc", "rm -rf build"]),
+            &default_prompt
+        ));
+    }
+
     #[test]
     fn restricted_sandbox_requires_exec_approval_on_request() {
         assert_eq!(
@@ -1,6 +1,7 @@
 use crate::agent::AgentRole;
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::config::agent_roles::AgentRoleToml;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::tools::handlers::PLAN_TOOL;
@@ -17,6 +18,7 @@ use codex_protocol::models::VIEW_IMAGE_TOOL_NAME;
 use codex_protocol::openai_models::ApplyPatchToolType;
 use codex_protocol::openai_models::ConfigShellToolType;
 use codex_protocol::openai_models::ModelInfo;
+use codex_symbol_index::SymbolKind;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
@@ -33,6 +35,10 @@ pub(crate) struct ToolsConfig {
     pub collaboration_modes_tools: bool,
     pub request_rule_enabled: bool,
     pub experimental_supported_tools: Vec<String>,
+    pub agent_roles: Vec<AgentRole>,
+    pub allowed_tools: Option<Vec<String>>,
+    pub lsp_tools: bool,
+    pub find_symbol_tool: bool,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -52,6 +58,7 @@ impl ToolsConfig {
         let include_collab_tools = features.enabled(Feature::Collab);
         let include_collaboration_modes_tools = features.enabled(Feature::CollaborationModes);
         let request_rule_enabled = features.enabled(Feature::RequestRule);
+        let include_find_symbol_tool = features.enabled(Feature::SymbolIndex);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -86,8 +93,31 @@ impl ToolsConfig {
             collaboration_modes_tools: include_collaboration_modes_tools,
             request_rule_enabled,
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
+            agent_roles: AgentRole::available(&BTreeMap::new()),
+            allowed_tools: None,
+            lsp_tools: false,
+            find_symbol_tool: include_find_symbol_tool,
         }
     }
+
+    /// Advertises the user-defined roles in `spawn_agent` alongside the
+    /// built-in ones.
+    pub fn with_agent_roles(mut self, user_roles: &BTreeMap<String, AgentRoleToml>) -> Self {
+        self.agent_roles = AgentRole::available(user_roles);
+        self
+    }
+
+    /// Restricts the exposed tools to `allowed_tools` when set.
+    pub fn with_allowed_tools(mut self, allowed_tools: Option<Vec<String>>) -> Self {
+        self.allowed_tools = allowed_tools;
+        self
+    }
+
+    /// Exposes the `lsp_*` tools when language servers are configured.
+    pub fn with_lsp_tools(mut self, lsp_tools: bool) -> Self {
+        self.lsp_tools = lsp_tools;
+        self
+    }
 }
 
 /// Generic JSON‑Schema subset needed for our tool definitions
@@ -101,6 +131,9 @@ pub enum JsonSchema {
     String {
         #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        /// Allowed values, when the string is restricted to a fixed set.
+        #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+        enum_values: Option<Vec<String>>,
     },
     /// MCP schema allows "number" | "integer" for Number
     #[serde(alias = "integer")]
@@ -155,6 +188,7 @@ fn create_approval_parameters(include_prefix_rule: bool) -> BTreeMap<String, Jso
                     "Sandbox permissions for the command. Set to \"require_escalated\" to request running without sandbox restrictions; defaults to \"use_default\"."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -168,6 +202,7 @@ fn create_approval_parameters(include_prefix_rule: bool) -> BTreeMap<String, Jso
                     fetch and pull the latest version of this git branch?'"#
                     .to_string(),
                 ),
+                enum_values: None,
             },
         ),
     ]);
@@ -176,7 +211,7 @@ fn create_approval_parameters(include_prefix_rule: bool) -> BTreeMap<String, Jso
         properties.insert(
             "prefix_rule".to_string(),
             JsonSchema::Array {
-                items: Box::new(JsonSchema::String { description: None }),
+                items: Box::new(JsonSchema::String { description: None, enum_values: None }),
                 description: Some(
                     r#"Only specify when sandbox_permissions is `require_escalated`. 
                     Suggest a prefix command pattern that will allow you to fulfill similar requests from the user in the future.
@@ -194,6 +229,7 @@ fn create_exec_command_tool(include_prefix_rule: bool) -> ToolSpec {
             "cmd".to_string(),
             JsonSchema::String {
                 description: Some("Shell command to execute.".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -203,12 +239,14 @@ fn create_exec_command_tool(include_prefix_rule: bool) -> ToolSpec {
                     "Optional working directory to run the command in; defaults to the turn cwd."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
             "shell".to_string(),
             JsonSchema::String {
                 description: Some("Shell binary to launch. Defaults to the user's default shell.".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -274,6 +312,7 @@ fn create_write_stdin_tool() -> ToolSpec {
             "chars".to_string(),
             JsonSchema::String {
                 description: Some("Bytes to write to stdin (may be empty to poll).".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -314,7 +353,10 @@ fn create_shell_tool(include_prefix_rule: bool) -> ToolSpec {
         (
             "command".to_string(),
             JsonSchema::Array {
-                items: Box::new(JsonSchema::String { description: None }),
+                items: Box::new(JsonSchema::String {
+                    description: None,
+                    enum_values: None,
+                }),
                 description: Some("The command to execute".to_string()),
             },
         ),
@@ -322,6 +364,7 @@ fn create_shell_tool(include_prefix_rule: bool) -> ToolSpec {
             "workdir".to_string(),
             JsonSchema::String {
                 description: Some("The working directory to execute the command in".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -370,12 +413,14 @@ fn create_shell_command_tool(include_prefix_rule: bool) -> ToolSpec {
                 description: Some(
                     "The shell script to execute in the user's default shell".to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
             "workdir".to_string(),
             JsonSchema::String {
                 description: Some("The working directory to execute the command in".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -430,6 +475,7 @@ fn create_view_image_tool() -> ToolSpec {
         "path".to_string(),
         JsonSchema::String {
             description: Some("Local filesystem path to an image file".to_string()),
+            enum_values: None,
         },
     )]);
 
@@ -446,7 +492,19 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
-fn create_spawn_agent_tool() -> ToolSpec {
+fn agent_type_description(agent_roles: &[AgentRole]) -> String {
+    let descriptions = AgentRole::descriptions(agent_roles);
+    if descriptions.is_empty() {
+        "Optional agent type. Use an explicit type when delegating.".to_string()
+    } else {
+        format!(
+            "Optional agent type ({}). Use an explicit type when delegating.",
+            descriptions.join("; ")
+        )
+    }
+}
+
+fn create_spawn_agent_tool(agent_roles: &[AgentRole]) -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "message".to_string(),
@@ -455,15 +513,23 @@ fn create_spawn_agent_tool() -> ToolSpec {
                 "Initial task for the new agent. Include scope, constraints, and the expected output."
                     .to_string(),
             ),
+            enum_values: None,
         },
     );
     properties.insert(
         "agent_type".to_string(),
         JsonSchema::String {
-            description: Some(format!(
-                "Optional agent type ({}). Use an explicit type when delegating.",
-                AgentRole::enum_values().join(", ")
-            )),
+            description: Some(agent_type_description(agent_roles)),
+            enum_values: Some(AgentRole::enum_values(agent_roles)),
+        },
+    );
+    properties.insert(
+        "worktree".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Run the agent in its own git worktree on a scratch branch, starting from the current commit, so its edits cannot collide with yours or other agents'. `wait` reports its diff; merge or discard it with `close_agent`."
+                    .to_string(),
+            ),
         },
     );
 
@@ -487,12 +553,14 @@ fn create_send_input_tool() -> ToolSpec {
         "id".to_string(),
         JsonSchema::String {
             description: Some("Agent id to message (from spawn_agent).".to_string()),
+            enum_values: None,
         },
     );
     properties.insert(
         "message".to_string(),
         JsonSchema::String {
             description: Some("Message to send to the agent.".to_string()),
+            enum_values: None,
         },
     );
     properties.insert(
@@ -524,7 +592,10 @@ fn create_wait_tool() -> ToolSpec {
     properties.insert(
         "ids".to_string(),
         JsonSchema::Array {
-            items: Box::new(JsonSchema::String { description: None }),
+            items: Box::new(JsonSchema::String {
+                description: None,
+                enum_values: None,
+            }),
             description: Some(
                 "Agent ids to wait on. Pass multiple ids to wait for whichever finishes first."
                     .to_string(),
@@ -542,7 +613,7 @@ fn create_wait_tool() -> ToolSpec {
 
     ToolSpec::Function(ResponsesApiTool {
         name: "wait".to_string(),
-        description: "Wait for agents to reach a final status. Completed statuses may include the agent's final message. Returns empty status when timed out."
+        description: "Wait for agents to reach a final status. Completed statuses may include the agent's final message, and agents spawned with a worktree report their diff. Returns empty status when timed out."
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -559,6 +630,7 @@ fn create_request_user_input_tool() -> ToolSpec {
         "label".to_string(),
         JsonSchema::String {
             description: Some("User-facing label (1-5 words).".to_string()),
+            enum_values: None,
         },
     );
     option_props.insert(
@@ -567,6 +639,7 @@ fn create_request_user_input_tool() -> ToolSpec {
             description: Some(
                 "One short sentence explaining impact/tradeoff if selected.".to_string(),
             ),
+            enum_values: None,
         },
     );
 
@@ -587,6 +660,7 @@ fn create_request_user_input_tool() -> ToolSpec {
         "id".to_string(),
         JsonSchema::String {
             description: Some("Stable identifier for mapping answers (snake_case).".to_string()),
+            enum_values: None,
         },
     );
     question_props.insert(
@@ -595,12 +669,14 @@ fn create_request_user_input_tool() -> ToolSpec {
             description: Some(
                 "Short header label shown in the UI (12 or fewer chars).".to_string(),
             ),
+            enum_values: None,
         },
     );
     question_props.insert(
         "question".to_string(),
         JsonSchema::String {
             description: Some("Single-sentence prompt shown to the user.".to_string()),
+            enum_values: None,
         },
     );
     question_props.insert("options".to_string(), options_schema);
@@ -640,6 +716,17 @@ fn create_close_agent_tool() -> ToolSpec {
         "id".to_string(),
         JsonSchema::String {
             description: Some("Agent id to close (from spawn_agent).".to_string()),
+            enum_values: None,
+        },
+    );
+    properties.insert(
+        "worktree".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Required for agents spawned with a worktree: `merge` applies the agent's changes to your checkout, `discard` drops them. A merge may ask the user for approval; the worktree is removed unless the merge is declined or fails."
+                    .to_string(),
+            ),
+            enum_values: None,
         },
     );
 
@@ -664,6 +751,7 @@ fn create_test_sync_tool() -> ToolSpec {
                 description: Some(
                     "Identifier shared by concurrent calls that should rendezvous".to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -723,12 +811,107 @@ fn create_test_sync_tool() -> ToolSpec {
     })
 }
 
+fn lsp_position_properties() -> BTreeMap<String, JsonSchema> {
+    BTreeMap::from([
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "File containing the symbol, absolute or relative to the working directory."
+                        .to_string(),
+                ),
+                enum_values: None,
+            },
+        ),
+        (
+            "line".to_string(),
+            JsonSchema::Number {
+                description: Some("1-based line number of the symbol.".to_string()),
+            },
+        ),
+        (
+            "column".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "1-based column (in characters) of any character in the symbol.".to_string(),
+                ),
+            },
+        ),
+    ])
+}
+
+fn create_lsp_position_tool(name: &str, description: &str) -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: name.to_string(),
+        description: description.to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: lsp_position_properties(),
+            required: Some(vec![
+                "path".to_string(),
+                "line".to_string(),
+                "column".to_string(),
+            ]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_lsp_definition_tool() -> ToolSpec {
+    create_lsp_position_tool(
+        "lsp_definition",
+        "Asks the language server where the symbol at a position is defined.",
+    )
+}
+
+fn create_lsp_references_tool() -> ToolSpec {
+    create_lsp_position_tool(
+        "lsp_references",
+        "Asks the language server for every reference to the symbol at a position, including its \
+         declaration.",
+    )
+}
+
+fn create_lsp_hover_tool() -> ToolSpec {
+    create_lsp_position_tool(
+        "lsp_hover",
+        "Asks the language server for the type and documentation of the symbol at a position.",
+    )
+}
+
+fn create_lsp_diagnostics_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "File to check. When omitted, lists the diagnostics reported so far for every \
+                 file the language servers have seen."
+                    .to_string(),
+            ),
+            enum_values: None,
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "lsp_diagnostics".to_string(),
+        description: "Lists the errors, warnings and hints reported by the language server."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_grep_files_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
             "pattern".to_string(),
             JsonSchema::String {
                 description: Some("Regular expression pattern to search for.".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -739,6 +922,7 @@ fn create_grep_files_tool() -> ToolSpec {
                      \"*.{ts,tsx}\")."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -748,13 +932,37 @@ fn create_grep_files_tool() -> ToolSpec {
                     "Directory or file path to search. Defaults to the session's working directory."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
             "limit".to_string(),
             JsonSchema::Number {
                 description: Some(
-                    "Maximum number of file paths to return (defaults to 100).".to_string(),
+                    "Maximum number of files, or of matching lines in `content` mode, to return \
+                     (defaults to 100)."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "output_mode".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "`files_with_matches` (default) lists matching files; `content` returns the \
+                     matching lines as `path:line:text`."
+                        .to_string(),
+                ),
+                enum_values: None,
+            },
+        ),
+        (
+            "context".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Lines of context to show before and after each match in `content` mode \
+                     (at most 10), printed as `path-line-text`."
+                        .to_string(),
                 ),
             },
         ),
@@ -762,8 +970,9 @@ fn create_grep_files_tool() -> ToolSpec {
 
     ToolSpec::Function(ResponsesApiTool {
         name: "grep_files".to_string(),
-        description: "Finds files whose contents match the pattern and lists them by modification \
-                      time."
+        description: "Searches file contents for the pattern, skipping ignored, hidden and binary \
+                      files, and lists matching files (or matching lines) with the most recently \
+                      modified files first."
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -774,6 +983,56 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
+fn create_find_symbol_tool() -> ToolSpec {
+    let kinds = SymbolKind::ALL
+        .iter()
+        .map(|kind| kind.as_str().to_string())
+        .collect::<Vec<_>>();
+    let properties = BTreeMap::from([
+        (
+            "query".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Symbol name or part of one. Qualify it with its type, trait, class or module \
+                     (e.g. \"Config::load\" or \"Parser.parse\") to narrow the results."
+                        .to_string(),
+                ),
+                enum_values: None,
+            },
+        ),
+        (
+            "kind".to_string(),
+            JsonSchema::String {
+                description: Some("Optional kind of symbol to return.".to_string()),
+                enum_values: Some(kinds),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of symbols to return (defaults to 20).".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "find_symbol".to_string(),
+        description: "Finds where functions, methods, types, traits, modules and constants are \
+                      defined in the workspace, using an index of Rust, Python, Go, JavaScript and \
+                      TypeScript sources. Returns `path:line kind name` for each definition, exact \
+                      name matches first."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_read_file_tool() -> ToolSpec {
     let indentation_properties = BTreeMap::from([
         (
@@ -827,6 +1086,7 @@ fn create_read_file_tool() -> ToolSpec {
             "file_path".to_string(),
             JsonSchema::String {
                 description: Some("Absolute path to the file".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -851,6 +1111,7 @@ fn create_read_file_tool() -> ToolSpec {
                      to expand around an anchor line."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -883,6 +1144,7 @@ fn create_list_dir_tool() -> ToolSpec {
             "dir_path".to_string(),
             JsonSchema::String {
                 description: Some("Absolute path to the directory to list.".to_string()),
+                enum_values: None,
             },
         ),
         (
@@ -932,6 +1194,7 @@ fn create_list_mcp_resources_tool() -> ToolSpec {
                     "Optional MCP server name. When omitted, lists resources from every configured server."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -941,6 +1204,7 @@ fn create_list_mcp_resources_tool() -> ToolSpec {
                     "Opaque cursor returned by a previous list_mcp_resources call for the same server."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
     ]);
@@ -966,6 +1230,7 @@ fn create_list_mcp_resource_templates_tool() -> ToolSpec {
                     "Optional MCP server name. When omitted, lists resource templates from all configured servers."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -975,6 +1240,7 @@ fn create_list_mcp_resource_templates_tool() -> ToolSpec {
                     "Opaque cursor returned by a previous list_mcp_resource_templates call for the same server."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
     ]);
@@ -1000,6 +1266,7 @@ fn create_read_mcp_resource_tool() -> ToolSpec {
                     "MCP server name exactly as configured. Must match the 'server' field returned by list_mcp_resources."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
         (
@@ -1009,6 +1276,7 @@ fn create_read_mcp_resource_tool() -> ToolSpec {
                     "Resource URI to read. Must be one of the URIs returned by list_mcp_resources."
                         .to_string(),
                 ),
+                enum_values: None,
             },
         ),
     ]);
@@ -1228,8 +1496,10 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::ApplyPatchHandler;
     use crate::tools::handlers::CollabHandler;
     use crate::tools::handlers::DynamicToolHandler;
+    use crate::tools::handlers::FindSymbolHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
+    use crate::tools::handlers::LspHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
@@ -1348,6 +1618,24 @@ pub(crate) fn build_specs(
         builder.register_handler("list_dir", list_dir_handler);
     }
 
+    if config.find_symbol_tool {
+        let find_symbol_handler = Arc::new(FindSymbolHandler);
+        builder.push_spec_with_parallel_support(create_find_symbol_tool(), true);
+        builder.register_handler("find_symbol", find_symbol_handler);
+    }
+
+    if config.lsp_tools {
+        let lsp_handler = Arc::new(LspHandler);
+        builder.push_spec_with_parallel_support(create_lsp_definition_tool(), true);
+        builder.push_spec_with_parallel_support(create_lsp_references_tool(), true);
+        builder.push_spec_with_parallel_support(create_lsp_hover_tool(), true);
+        builder.push_spec_with_parallel_support(create_lsp_diagnostics_tool(), true);
+        builder.register_handler("lsp_definition", lsp_handler.clone());
+        builder.register_handler("lsp_references", lsp_handler.clone());
+        builder.register_handler("lsp_hover", lsp_handler.clone());
+        builder.register_handler("lsp_diagnostics", lsp_handler);
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"test_sync_tool".to_string())
@@ -1376,7 +1664,7 @@ pub(crate) fn build_specs(
 
     if config.collab_tools {
         let collab_handler = Arc::new(CollabHandler);
-        builder.push_spec(create_spawn_agent_tool());
+        builder.push_spec(create_spawn_agent_tool(&config.agent_roles));
         builder.push_spec(create_send_input_tool());
         builder.push_spec(create_wait_tool());
         builder.push_spec(create_close_agent_tool());
@@ -1420,6 +1708,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if let Some(allowed_tools) = &config.allowed_tools {
+        builder.retain(|name| allowed_tools.iter().any(|allowed| allowed == name));
+    }
+
     builder
 }
 
@@ -1527,7 +1819,7 @@ mod tests {
     fn strip_descriptions_schema(schema: &mut JsonSchema) {
         match schema {
             JsonSchema::Boolean { description }
-            | JsonSchema::String { description }
+            | JsonSchema::String { description, .. }
             | JsonSchema::Number { description } => {
                 *description = None;
             }
@@ -1976,6 +2268,119 @@ mod tests {
         assert_contains_tool_names(&tools, &subset);
     }
 
+    #[test]
+    fn test_build_specs_lsp_tools_when_servers_configured() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("o3", &config);
+        let features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: None,
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec).starts_with("lsp_"))
+        );
+
+        let tools_config = tools_config.with_lsp_tools(true);
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert_contains_tool_names(
+            &tools,
+            &[
+                "lsp_definition",
+                "lsp_references",
+                "lsp_hover",
+                "lsp_diagnostics",
+            ],
+        );
+        assert!(find_tool(&tools, "lsp_definition").supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_find_symbol_tool_with_symbol_index_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("o3", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: None,
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "find_symbol")
+        );
+
+        features.enable(Feature::SymbolIndex);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: None,
+        });
+        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        assert_contains_tool_names(&tools, &["find_symbol"]);
+        let find_symbol = find_tool(&tools, "find_symbol");
+        assert!(find_symbol.supports_parallel_tool_calls);
+        let ToolSpec::Function(ResponsesApiTool {
+            parameters: JsonSchema::Object { properties, .. },
+            ..
+        }) = &find_symbol.spec
+        else {
+            panic!("find_symbol should be a function tool");
+        };
+        let Some(JsonSchema::String { enum_values, .. }) = properties.get("kind") else {
+            panic!("find_symbol should take a string kind");
+        };
+        assert_eq!(
+            enum_values.as_deref(),
+            Some(
+                [
+                    "function",
+                    "method",
+                    "class",
+                    "struct",
+                    "enum",
+                    "interface",
+                    "trait",
+                    "type",
+                    "module",
+                    "constant",
+                    "macro",
+                ]
+                .map(str::to_string)
+                .as_slice()
+            )
+        );
+    }
+
+    #[test]
+    fn spawn_agent_lists_roles_as_an_enum() {
+        let roles = AgentRole::available(&BTreeMap::new());
+        let ToolSpec::Function(ResponsesApiTool {
+            parameters: JsonSchema::Object { properties, .. },
+            ..
+        }) = create_spawn_agent_tool(&roles)
+        else {
+            panic!("spawn_agent should be a function tool");
+        };
+        let Some(JsonSchema::String { enum_values, .. }) = properties.get("agent_type") else {
+            panic!("spawn_agent should take a string agent_type");
+        };
+        assert_eq!(
+            enum_values.as_deref(),
+            Some(
+                ["default", "explorer", "worker"]
+                    .map(str::to_string)
+                    .as_slice()
+            )
+        );
+    }
+
     #[test]
     #[ignore]
     fn test_parallel_support_flags() {
@@ -2076,7 +2481,10 @@ mod tests {
                     properties: BTreeMap::from([
                         (
                             "string_argument".to_string(),
-                            JsonSchema::String { description: None }
+                            JsonSchema::String {
+                                description: None,
+                                enum_values: None
+                            }
                         ),
                         (
                             "number_argument".to_string(),
@@ -2088,7 +2496,10 @@ mod tests {
                                 properties: BTreeMap::from([
                                     (
                                         "string_property".to_string(),
-                                        JsonSchema::String { description: None }
+                                        JsonSchema::String {
+                                            description: None,
+                                            enum_values: None
+                                        }
                                     ),
                                     (
                                         "number_property".to_string(),
@@ -2196,7 +2607,8 @@ mod tests {
                     properties: BTreeMap::from([(
                         "query".to_string(),
                         JsonSchema::String {
-                            description: Some("search query".to_string())
+                            description: Some("search query".to_string()),
+                            enum_values: None,
                         }
                     )]),
                     required: None,
@@ -2295,7 +2707,10 @@ mod tests {
                     properties: BTreeMap::from([(
                         "tags".to_string(),
                         JsonSchema::Array {
-                            items: Box::new(JsonSchema::String { description: None }),
+                            items: Box::new(JsonSchema::String {
+                                description: None,
+                                enum_values: None
+                            }),
                             description: None
                         }
                     )]),
@@ -2347,7 +2762,10 @@ mod tests {
                 parameters: JsonSchema::Object {
                     properties: BTreeMap::from([(
                         "value".to_string(),
-                        JsonSchema::String { description: None }
+                        JsonSchema::String {
+                            description: None,
+                            enum_values: None
+                        }
                     )]),
                     required: None,
                     additional_properties: None,
@@ -2473,7 +2891,10 @@ Examples of valid command strings:
                     properties: BTreeMap::from([
                         (
                             "string_argument".to_string(),
-                            JsonSchema::String { description: None }
+                            JsonSchema::String {
+                                description: None,
+                                enum_values: None
+                            }
                         ),
                         (
                             "number_argument".to_string(),
@@ -2485,7 +2906,10 @@ Examples of valid command strings:
                                 properties: BTreeMap::from([
                                     (
                                         "string_property".to_string(),
-                                        JsonSchema::String { description: None }
+                                        JsonSchema::String {
+                                            description: None,
+                                            enum_values: None
+                                        }
                                     ),
                                     (
                                         "number_property".to_string(),
@@ -2500,7 +2924,10 @@ Examples of valid command strings:
                                     JsonSchema::Object {
                                         properties: BTreeMap::from([(
                                             "addtl_prop".to_string(),
-                                            JsonSchema::String { description: None }
+                                            JsonSchema::String {
+                                                description: None,
+                                                enum_values: None
+                                            }
                                         ),]),
                                         required: Some(vec!["addtl_prop".to_string(),]),
                                         additional_properties: Some(false.into()),
@@ -2521,8 +2948,13 @@ Examples of valid command strings:
 
     #[test]
     fn chat_tools_include_top_level_name() {
-        let properties =
-            BTreeMap::from([("foo".to_string(), JsonSchema::String { description: None })]);
+        let properties = BTreeMap::from([(
+            "foo".to_string(),
+            JsonSchema::String {
+                description: None,
+                enum_values: None,
+            },
+        )]);
         let tools = vec![ToolSpec::Function(ResponsesApiTool {
             name: "demo".to_string(),
             description: "A demo tool".to_string(),
@@ -6,6 +6,7 @@ use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
+use codex_utils_tokenizer::Tokenizer;
 
 const APPROX_BYTES_PER_TOKEN: usize = 4;
 
@@ -77,18 +78,52 @@ impl std::ops::Mul<f64> for TruncationPolicy {
 }
 
 pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -> String {
-    if content.len() <= policy.byte_budget() {
-        return content.to_string();
-    }
+    formatted_truncate_text_with_tokenizer(content, policy, None)
+}
+
+/// Like [`formatted_truncate_text`], but measures token budgets with the
+/// model's tokenizer when one is available.
+pub(crate) fn formatted_truncate_text_with_tokenizer(
+    content: &str,
+    policy: TruncationPolicy,
+    tokenizer: Option<&Tokenizer>,
+) -> String {
+    let result = match (policy, tokenizer) {
+        (TruncationPolicy::Tokens(_), Some(tokenizer)) => {
+            match truncate_with_tokenizer(content, policy, tokenizer) {
+                (_, None) => return content.to_string(),
+                (truncated, Some(_)) => truncated,
+            }
+        }
+        _ => {
+            if content.len() <= policy.byte_budget() {
+                return content.to_string();
+            }
+            truncate_text_with_tokenizer(content, policy, tokenizer)
+        }
+    };
     let total_lines = content.lines().count();
-    let result = truncate_text(content, policy);
     format!("Total output lines: {total_lines}\n\n{result}")
 }
 
 pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
-    match policy {
-        TruncationPolicy::Bytes(_) => truncate_with_byte_estimate(content, policy),
-        TruncationPolicy::Tokens(_) => {
+    truncate_text_with_tokenizer(content, policy, None)
+}
+
+/// Like [`truncate_text`], but measures token budgets with the model's
+/// tokenizer when one is available. Byte policies ignore the tokenizer.
+pub(crate) fn truncate_text_with_tokenizer(
+    content: &str,
+    policy: TruncationPolicy,
+    tokenizer: Option<&Tokenizer>,
+) -> String {
+    match (policy, tokenizer) {
+        (TruncationPolicy::Bytes(_), _) => truncate_with_byte_estimate(content, policy),
+        (TruncationPolicy::Tokens(_), Some(tokenizer)) => {
+            let (truncated, _) = truncate_with_tokenizer(content, policy, tokenizer);
+            truncated
+        }
+        (TruncationPolicy::Tokens(_), None) => {
             let (truncated, _) = truncate_with_token_budget(content, policy);
             truncated
         }
@@ -100,6 +135,7 @@ pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
 pub(crate) fn truncate_function_output_items_with_policy(
     items: &[FunctionCallOutputContentItem],
     policy: TruncationPolicy,
+    tokenizer: Option<&Tokenizer>,
 ) -> Vec<FunctionCallOutputContentItem> {
     let mut out: Vec<FunctionCallOutputContentItem> = Vec::with_capacity(items.len());
     let mut remaining_budget = match policy {
@@ -118,7 +154,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
 
                 let cost = match policy {
                     TruncationPolicy::Bytes(_) => text.len(),
-                    TruncationPolicy::Tokens(_) => approx_token_count(text),
+                    TruncationPolicy::Tokens(_) => token_count(text, tokenizer),
                 };
 
                 if cost <= remaining_budget {
@@ -129,7 +165,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
                         TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(remaining_budget),
                         TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(remaining_budget),
                     };
-                    let snippet = truncate_text(text, snippet_policy);
+                    let snippet = truncate_text_with_tokenizer(text, snippet_policy, tokenizer);
                     if snippet.is_empty() {
                         omitted_text_items += 1;
                     } else {
@@ -180,6 +216,33 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
     }
 }
 
+/// Truncate the middle of a UTF-8 string to at most `max_tokens` tokens as
+/// measured by `tokenizer`. Returns the same shape as
+/// [`truncate_with_token_budget`], but with exact token counts.
+fn truncate_with_tokenizer(
+    s: &str,
+    policy: TruncationPolicy,
+    tokenizer: &Tokenizer,
+) -> (String, Option<u64>) {
+    if s.is_empty() {
+        return (String::new(), None);
+    }
+    let (left_budget, right_budget) = split_budget(policy.token_budget());
+    let Some(split) = tokenizer.split_head_tail(s, left_budget, right_budget) else {
+        return (s.to_string(), None);
+    };
+
+    let removed_tokens =
+        u64::try_from(split.total_tokens.saturating_sub(split.kept_tokens)).unwrap_or(u64::MAX);
+    let marker = format_truncation_marker(policy, removed_tokens);
+
+    let total_tokens = u64::try_from(split.total_tokens).unwrap_or(u64::MAX);
+    (
+        assemble_truncated_output(split.head, split.tail, &marker),
+        Some(total_tokens),
+    )
+}
+
 /// Truncate a string using a byte budget derived from the token budget, without
 /// performing any real tokenization. This keeps the logic purely byte-based and
 /// uses a bytes placeholder in the truncated output.
@@ -289,6 +352,15 @@ fn assemble_truncated_output(prefix: &str, suffix: &str, marker: &str) -> String
     out
 }
 
+/// Counts tokens with `tokenizer` when the model's encoding is known, and
+/// falls back to the bytes-per-token heuristic otherwise.
+pub(crate) fn token_count(text: &str, tokenizer: Option<&Tokenizer>) -> usize {
+    match tokenizer {
+        Some(tokenizer) => tokenizer.count(text),
+        None => approx_token_count(text),
+    }
+}
+
 pub(crate) fn approx_token_count(text: &str) -> usize {
     let len = text.len();
     len.saturating_add(APPROX_BYTES_PER_TOKEN.saturating_sub(1)) / APPROX_BYTES_PER_TOKEN
@@ -313,8 +385,12 @@ mod tests {
     use super::split_string;
     use super::truncate_function_output_items_with_policy;
     use super::truncate_text;
+    use super::truncate_text_with_tokenizer;
     use super::truncate_with_token_budget;
+    use super::truncate_with_tokenizer;
     use codex_protocol::models::FunctionCallOutputContentItem;
+    use codex_utils_tokenizer::EncodingKind;
+    use codex_utils_tokenizer::Tokenizer;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -489,8 +565,11 @@ mod tests {
             FunctionCallOutputContentItem::InputText { text: t5 },
         ];
 
-        let output =
-            truncate_function_output_items_with_policy(&items, TruncationPolicy::Tokens(limit));
+        let output = truncate_function_output_items_with_policy(
+            &items,
+            TruncationPolicy::Tokens(limit),
+            None,
+        );
 
         // Expect: t1 (full), t2 (full), image, t3 (truncated), summary mentioning 2 omitted.
         assert_eq!(output.len(), 5);
@@ -529,4 +608,41 @@ mod tests {
         };
         assert!(summary_text.contains("omitted 2 text items"));
     }
+
+    #[test]
+    fn truncate_with_tokenizer_returns_original_when_under_limit() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase);
+        let s = "short output";
+        let (out, original) = truncate_with_tokenizer(s, TruncationPolicy::Tokens(100), &tokenizer);
+        assert_eq!(out, s);
+        assert_eq!(original, None);
+    }
+
+    #[test]
+    fn truncate_with_tokenizer_keeps_budgeted_tokens() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase);
+        let s = "error[E0308]: mismatched types\n".repeat(50);
+        let total = tokenizer.count(&s);
+
+        let (out, original) = truncate_with_tokenizer(&s, TruncationPolicy::Tokens(20), &tokenizer);
+
+        assert_eq!(original, Some(total as u64));
+        assert!(out.starts_with("error[E0308]"));
+        assert!(out.ends_with("mismatched types\n"));
+        assert!(out.contains("tokens truncated"));
+        let marker_start = out.find('…').unwrap_or(out.len());
+        let marker_end = out.rfind('…').map_or(out.len(), |idx| idx + '…'.len_utf8());
+        let kept = tokenizer.count(&out[..marker_start]) + tokenizer.count(&out[marker_end..]);
+        assert!(kept <= 21, "kept {kept} tokens: {out}");
+    }
+
+    #[test]
+    fn truncate_text_with_tokenizer_ignores_tokenizer_for_byte_policy() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase);
+        let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
+        assert_eq!(
+            truncate_text_with_tokenizer(s, TruncationPolicy::Bytes(20), Some(&tokenizer)),
+            truncate_text(s, TruncationPolicy::Bytes(20)),
+        );
+    }
 }
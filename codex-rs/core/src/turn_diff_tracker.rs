@@ -62,7 +62,7 @@ impl TurnDiffTracker {
                     .insert(internal.clone(), path.clone());
 
                 // If the file exists on disk now, snapshot as baseline; else leave missing to represent /dev/null.
-                let baseline_file_info = if path.exists() {
+                let baseline_file_info = if fs::symlink_metadata(path).is_ok() {
                     let mode = file_mode_for_path(path);
                     let mode_val = mode.unwrap_or(FileMode::Regular);
                     let content = blob_bytes(path, mode_val).unwrap_or_default();
@@ -294,8 +294,9 @@ impl TurnDiffTracker {
             None
         };
 
-        // Fast path: identical bytes or both missing.
-        if left_bytes == right_bytes.as_deref() {
+        // Fast path: identical bytes and mode, or both missing.
+        let contents_unchanged = left_bytes == right_bytes.as_deref();
+        if contents_unchanged && (right_bytes.is_none() || baseline_mode == current_mode) {
             return aggregated;
         }
 
@@ -313,6 +314,11 @@ impl TurnDiffTracker {
             aggregated.push_str(&format!("new mode {current_mode}\n"));
         }
 
+        // A pure mode change has no content section, matching `git diff`.
+        if contents_unchanged {
+            return aggregated;
+        }
+
         let left_text = left_bytes.and_then(|b| std::str::from_utf8(b).ok());
         let right_text = right_bytes
             .as_deref()
@@ -428,7 +434,8 @@ fn file_mode_for_path(_path: &Path) -> Option<FileMode> {
 }
 
 fn blob_bytes(path: &Path, mode: FileMode) -> Option<Vec<u8>> {
-    if path.exists() {
+    // Use `symlink_metadata` so that dangling symlinks are still diffed.
+    if fs::symlink_metadata(path).is_ok() {
         let contents = if mode == FileMode::Symlink {
             symlink_blob_bytes(path)
                 .ok_or_else(|| anyhow!("failed to read symlink target for {}", path.display()))
@@ -784,6 +791,29 @@ index {left_oid_b}..{ZERO_OID}
         assert_eq!(combined, expected);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn mode_change_without_content_change() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut acc = TurnDiffTracker::new();
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("run.sh");
+        fs::write(&file, "echo hi\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let changes = HashMap::from([(file.clone(), FileChange::ModeChange { executable: true })]);
+        acc.on_patch_begin(&changes);
+
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+        let diff = acc.get_unified_diff().unwrap().unwrap();
+        let diff = normalize_diff_for_test(&diff, dir.path());
+        assert_eq!(
+            diff,
+            "diff --git a/<TMP>/run.sh b/<TMP>/run.sh\nold mode 100644\nnew mode 100755\n"
+        );
+    }
+
     #[test]
     fn binary_files_differ_update() {
         let dir = tempdir().unwrap();
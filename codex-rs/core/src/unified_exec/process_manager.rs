@@ -24,6 +24,8 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
 use crate::truncate::formatted_truncate_text;
+use crate::truncate::formatted_truncate_text_with_tokenizer;
+use crate::truncate::token_count;
 use crate::unified_exec::ExecCommandRequest;
 use crate::unified_exec::MAX_UNIFIED_EXEC_PROCESSES;
 use crate::unified_exec::MAX_YIELD_TIME_MS;
@@ -174,7 +176,12 @@ impl UnifiedExecProcessManager {
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
+        let tokenizer = context.turn.tokenizer.as_ref();
+        let output = formatted_truncate_text_with_tokenizer(
+            &text,
+            TruncationPolicy::Tokens(max_tokens),
+            tokenizer,
+        );
         let exit_code = process.exit_code();
         let has_exited = process.has_exited() || exit_code.is_some();
         let chunk_id = generate_chunk_id();
@@ -218,7 +225,7 @@ impl UnifiedExecProcessManager {
             .await;
         };
 
-        let original_token_count = approx_token_count(&text);
+        let original_token_count = token_count(&text, tokenizer);
         let response = UnifiedExecResponse {
             event_call_id: context.call_id.clone(),
             chunk_id,
@@ -483,10 +490,12 @@ impl UnifiedExecProcessManager {
         cwd: PathBuf,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
-        let env = apply_unified_exec_env(create_env(
+        let mut env = create_env(
             &context.turn.shell_environment_policy,
             Some(context.session.conversation_id),
-        ));
+        );
+        context.session.services.shell_secrets.apply_env(&mut env);
+        let env = apply_unified_exec_env(env);
         let features = context.session.features();
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
@@ -853,6 +853,7 @@ async fn user_turn_collaboration_mode_overrides_model_and_effort() -> anyhow::Re
             reasoning_effort: Some(ReasoningEffort::High),
             developer_instructions: None,
         },
+        custom_mode: None,
     };
 
     codex
@@ -75,6 +75,8 @@ async fn codex_delegate_forwards_exec_approval_and_proceeds_on_approval() {
                     instructions: "Please review".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -152,6 +154,8 @@ async fn codex_delegate_forwards_patch_approval_and_proceeds_on_decision() {
                     instructions: "Please review".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -208,6 +212,8 @@ async fn codex_delegate_ignores_legacy_deltas() {
                     instructions: "Please review".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -33,6 +33,7 @@ fn collab_mode_with_mode_and_instructions(
             reasoning_effort: None,
             developer_instructions: instructions.map(str::to_string),
         },
+        custom_mode: None,
     }
 }
 
@@ -8,31 +8,12 @@ use core_test_support::test_codex::TestCodex;
 use core_test_support::test_codex::test_codex;
 use std::collections::HashSet;
 use std::path::Path;
-use std::process::Command as StdCommand;
 
 const MODEL_WITH_TOOL: &str = "test-gpt-5.1-codex";
 
-fn ripgrep_available() -> bool {
-    StdCommand::new("rg")
-        .arg("--version")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
-}
-
-macro_rules! skip_if_ripgrep_missing {
-    ($ret:expr $(,)?) => {{
-        if !ripgrep_available() {
-            eprintln!("rg not available in PATH; skipping test");
-            return $ret;
-        }
-    }};
-}
-
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn grep_files_tool_collects_matches() -> Result<()> {
     skip_if_no_network!(Ok(()));
-    skip_if_ripgrep_missing!(Ok(()));
 
     let server = start_mock_server().await;
     let test = build_test_codex(&server).await?;
@@ -90,7 +71,6 @@ async fn grep_files_tool_collects_matches() -> Result<()> {
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn grep_files_tool_reports_empty_results() -> Result<()> {
     skip_if_no_network!(Ok(()));
-    skip_if_ripgrep_missing!(Ok(()));
 
     let server = start_mock_server().await;
     let test = build_test_codex(&server).await?;
@@ -124,6 +104,45 @@ async fn grep_files_tool_reports_empty_results() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn grep_files_tool_returns_matching_lines() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = build_test_codex(&server).await?;
+
+    let search_dir = test.cwd.path().join("docs");
+    std::fs::create_dir_all(&search_dir)?;
+    let notes = search_dir.join("notes.md");
+    std::fs::write(&notes, "intro\nthe needle\noutro\n")?;
+
+    let call_id = "grep-files-content";
+    let arguments = serde_json::json!({
+        "pattern": "needle",
+        "path": search_dir.to_string_lossy(),
+        "output_mode": "content",
+        "context": 1,
+    })
+    .to_string();
+
+    let mocks =
+        mount_function_call_agent_response(&server, call_id, &arguments, "grep_files").await;
+    test.submit_turn("show the needle lines").await?;
+
+    let req = mocks.completion.single_request();
+    let (content_opt, _) = req
+        .function_call_output_content_and_success(call_id)
+        .expect("tool output present");
+    let content = content_opt.expect("content present");
+    let path = notes.display();
+    assert_eq!(
+        content,
+        format!("{path}-1-intro\n{path}:2:the needle\n{path}-3-outro")
+    );
+
+    Ok(())
+}
+
 #[allow(clippy::expect_used)]
 async fn build_test_codex(server: &wiremock::MockServer) -> Result<TestCodex> {
     let mut builder = test_codex().with_model(MODEL_WITH_TOOL);
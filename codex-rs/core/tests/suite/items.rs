@@ -363,6 +363,7 @@ async fn plan_mode_emits_plan_item_from_proposed_plan_block() -> anyhow::Result<
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     };
 
     codex
@@ -438,6 +439,7 @@ async fn plan_mode_strips_plan_from_agent_messages() -> anyhow::Result<()> {
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     };
 
     codex
@@ -534,6 +536,7 @@ async fn plan_mode_handles_missing_plan_close_tag() -> anyhow::Result<()> {
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     };
 
     codex
@@ -0,0 +1,113 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use codex_core::config::Config;
+use codex_core::config::types::LspServerConfig;
+use core_test_support::responses::mount_function_call_agent_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::ApplyPatchModelOutput;
+use core_test_support::test_codex::TestCodexHarness;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::suite::apply_patch_cli::mount_apply_patch;
+
+fn configure_stub_server(config: &mut Config) {
+    let server = codex_utils_cargo_bin::cargo_bin("test_lsp_server")
+        .expect("failed to resolve stub language server");
+    config.lsp_servers = BTreeMap::from([(
+        "stub".to_string(),
+        LspServerConfig {
+            command: server.to_string_lossy().into_owned(),
+            args: Vec::new(),
+            env: None,
+            extensions: vec!["rs".to_string()],
+            language_id: Some("rust".to_string()),
+            diagnostics_timeout_ms: Some(10_000),
+        },
+    )]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lsp_definition_and_hover_use_configured_server() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(configure_stub_server)
+        .build(&server)
+        .await?;
+    std::fs::create_dir_all(test.cwd.path().join("src"))?;
+    std::fs::write(
+        test.cwd.path().join("src/lib.rs"),
+        "fn answer() -> i32 {\n    42\n}\n\nfn main() {\n    answer();\n}\n",
+    )?;
+
+    let call_id = "lsp-definition";
+    let arguments = json!({ "path": "src/lib.rs", "line": 6, "column": 6 }).to_string();
+    let mocks =
+        mount_function_call_agent_response(&server, call_id, &arguments, "lsp_definition").await;
+    test.submit_turn("where is answer defined?").await?;
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("tool output present");
+    assert_eq!(
+        content.as_deref(),
+        Some("src/lib.rs:1:4: fn answer() -> i32 {")
+    );
+
+    let call_id = "lsp-hover";
+    let mocks = mount_function_call_agent_response(&server, call_id, &arguments, "lsp_hover").await;
+    test.submit_turn("what is answer?").await?;
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("tool output present");
+    assert_eq!(content.as_deref(), Some("`answer`"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn apply_patch_output_includes_new_diagnostics() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let builder = test_codex().with_config(|config| {
+        config.include_apply_patch_tool = true;
+        configure_stub_server(config);
+    });
+    let harness = TestCodexHarness::with_builder(builder).await?;
+    std::fs::write(harness.path("lib.rs"), "fn main() {}\n")?;
+
+    let patch = "*** Begin Patch\n*** Update File: lib.rs\n@@\n-fn main() {}\n+fn main() { ERROR }\n*** End Patch";
+    let call_id = "apply-with-diagnostics";
+    mount_apply_patch(
+        &harness,
+        call_id,
+        patch,
+        "done",
+        ApplyPatchModelOutput::Freeform,
+    )
+    .await;
+    harness.submit("break the build").await?;
+
+    let output = harness
+        .apply_patch_output(call_id, ApplyPatchModelOutput::Freeform)
+        .await;
+    assert!(
+        output.ends_with(
+            "Language server diagnostics after this edit:\nlib.rs:1:13: error: found ERROR (stub)"
+        ),
+        "unexpected output: {output}"
+    );
+
+    Ok(())
+}
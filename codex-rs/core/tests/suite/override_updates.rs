@@ -30,6 +30,7 @@ fn collab_mode_with_instructions(instructions: Option<&str>) -> CollaborationMod
             reasoning_effort: None,
             developer_instructions: instructions.map(str::to_string),
         },
+        custom_mode: None,
     }
 }
 
@@ -510,6 +510,7 @@ async fn ignores_remote_personality_if_remote_models_disabled() -> anyhow::Resul
         truncation_policy: TruncationPolicyConfig::bytes(10_000),
         supports_parallel_tool_calls: false,
         context_window: Some(128_000),
+        tokenizer: None,
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
@@ -626,6 +627,7 @@ async fn remote_model_friendly_personality_instructions_with_feature() -> anyhow
         truncation_policy: TruncationPolicyConfig::bytes(10_000),
         supports_parallel_tool_calls: false,
         context_window: Some(128_000),
+        tokenizer: None,
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
@@ -737,6 +739,7 @@ async fn user_turn_personality_remote_model_template_includes_update_message() -
         truncation_policy: TruncationPolicyConfig::bytes(10_000),
         supports_parallel_tool_calls: false,
         context_window: Some(128_000),
+        tokenizer: None,
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
@@ -418,6 +418,7 @@ async fn override_before_first_turn_emits_environment_context() -> anyhow::Resul
             reasoning_effort: Some(ReasoningEffort::High),
             developer_instructions: None,
         },
+        custom_mode: None,
     };
 
     codex
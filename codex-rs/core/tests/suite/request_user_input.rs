@@ -144,6 +144,7 @@ async fn request_user_input_round_trip_for_mode(mode: ModeKind) -> anyhow::Resul
                     reasoning_effort: None,
                     developer_instructions: None,
                 },
+                custom_mode: None,
             }),
             personality: None,
         })
@@ -285,6 +286,7 @@ async fn request_user_input_rejected_in_execute_mode_alias() -> anyhow::Result<(
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     })
     .await
 }
@@ -298,6 +300,7 @@ async fn request_user_input_rejected_in_default_mode() -> anyhow::Result<()> {
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     })
     .await
 }
@@ -311,6 +314,7 @@ async fn request_user_input_rejected_in_pair_mode_alias() -> anyhow::Result<()>
             reasoning_effort: None,
             developer_instructions: None,
         },
+        custom_mode: None,
     })
     .await
 }
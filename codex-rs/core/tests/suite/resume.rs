@@ -1,6 +1,9 @@
 use anyhow::Result;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ItemCompletedEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::PinnedMessage;
+use codex_protocol::items::TurnItem;
 use codex_protocol::user_input::ByteRange;
 use codex_protocol::user_input::TextElement;
 use codex_protocol::user_input::UserInput;
@@ -8,13 +11,17 @@ use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
 use core_test_support::responses::ev_reasoning_item;
 use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_compact_json_once;
 use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::mount_sse_sequence;
 use core_test_support::responses::sse;
 use core_test_support::responses::start_mock_server;
 use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
 use pretty_assertions::assert_eq;
+use serde_json::json;
 use std::sync::Arc;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -63,8 +70,10 @@ async fn resume_includes_initial_messages_from_rollout_events() -> Result<()> {
         .expect("expected initial messages to be present for resumed session");
     match initial_messages.as_slice() {
         [
+            EventMsg::ItemCompleted(_),
             EventMsg::UserMessage(first_user),
             EventMsg::TokenCount(_),
+            EventMsg::ItemCompleted(_),
             EventMsg::AgentMessage(assistant_message),
             EventMsg::TokenCount(_),
         ] => {
@@ -122,10 +131,12 @@ async fn resume_includes_initial_messages_from_reasoning_events() -> Result<()>
         .expect("expected initial messages to be present for resumed session");
     match initial_messages.as_slice() {
         [
+            EventMsg::ItemCompleted(_),
             EventMsg::UserMessage(first_user),
             EventMsg::TokenCount(_),
             EventMsg::AgentReasoning(reasoning),
             EventMsg::AgentReasoningRawContent(raw),
+            EventMsg::ItemCompleted(_),
             EventMsg::AgentMessage(assistant_message),
             EventMsg::TokenCount(_),
         ] => {
@@ -218,3 +229,127 @@ async fn resume_switches_models_preserves_base_instructions() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resume_pins_earlier_messages_without_duplicating_them() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex();
+    let initial = builder.build(&server).await?;
+    let codex = Arc::clone(&initial.codex);
+    let home = initial.home.clone();
+    let rollout_path = initial
+        .session_configured
+        .rollout_path
+        .clone()
+        .expect("rollout path");
+
+    let response_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-initial"),
+                ev_assistant_message("msg-1", "Use tabs for indentation."),
+                ev_completed("resp-initial"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-resumed"),
+                ev_assistant_message("msg-2", "Resumed turn"),
+                ev_completed("resp-resumed"),
+            ]),
+        ],
+    )
+    .await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "How should I indent?".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+    let item_id = wait_for_event_match(&codex, |event| match event {
+        EventMsg::ItemCompleted(ItemCompletedEvent {
+            item: TurnItem::AgentMessage(message),
+            ..
+        }) => Some(message.id.clone()),
+        _ => None,
+    })
+    .await;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TurnComplete(_))).await;
+
+    // The message was emitted by the previous session, so it can only be
+    // found in the rollout.
+    let resumed = builder
+        .resume(&server, home.clone(), rollout_path.clone())
+        .await?;
+    resumed
+        .codex
+        .submit(Op::PinMessage {
+            item_id: item_id.clone(),
+        })
+        .await?;
+    let pinned_messages = wait_for_event_match(&resumed.codex, |event| match event {
+        EventMsg::PinnedMessagesUpdated(event) => Some(event.pinned_messages.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        pinned_messages,
+        vec![PinnedMessage {
+            id: item_id,
+            text: "Use tabs for indentation.".to_string(),
+        }]
+    );
+
+    mount_compact_json_once(
+        &server,
+        json!({
+            "output": [{
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": "Summary of the conversation." }],
+            }],
+        }),
+    )
+    .await;
+    resumed.codex.submit(Op::Compact).await?;
+    wait_for_event(&resumed.codex, |event| {
+        matches!(event, EventMsg::TurnComplete(_))
+    })
+    .await;
+
+    let resumed_again = builder.resume(&server, home, rollout_path).await?;
+    resumed_again
+        .codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "Keep going".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+    wait_for_event(&resumed_again.codex, |event| {
+        matches!(event, EventMsg::TurnComplete(_))
+    })
+    .await;
+
+    let request = response_mock
+        .last_request()
+        .expect("request after the second resume");
+    let pinned_blocks: Vec<String> = request
+        .message_input_texts("user")
+        .into_iter()
+        .filter(|text| text.starts_with("<pinned_context>"))
+        .collect();
+    assert_eq!(
+        pinned_blocks,
+        vec!["<pinned_context>\nUse tabs for indentation.\n</pinned_context>".to_string()]
+    );
+
+    Ok(())
+}
@@ -80,6 +80,8 @@ async fn review_op_emits_lifecycle_and_review_output() {
                     instructions: "Please review my changes".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -201,6 +203,8 @@ async fn review_op_with_plain_text_emits_review_fallback() {
                     instructions: "Plain text review".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -262,6 +266,8 @@ async fn review_filters_agent_message_related_events() {
                     instructions: "Filter streaming events".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -344,6 +350,8 @@ async fn review_does_not_emit_agent_message_on_structured_output() {
                     instructions: "check structured".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -404,6 +412,8 @@ async fn review_uses_custom_review_model_from_config() {
                     instructions: "use custom model".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -457,6 +467,8 @@ async fn review_uses_session_model_when_review_model_unset() {
                     instructions: "use session model".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -575,6 +587,8 @@ async fn review_input_isolated_from_parent_history() {
                     instructions: review_prompt.clone(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -687,6 +701,8 @@ async fn review_history_surfaces_in_parent_session() {
                     instructions: "Start a review".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -774,40 +790,11 @@ async fn review_uses_overridden_cwd_for_base_branch_merge_base() {
     let repo_dir = TempDir::new().unwrap();
     let repo_path = repo_dir.path();
 
-    fn run_git(repo_path: &std::path::Path, args: &[&str]) {
-        let output = std::process::Command::new("git")
-            .arg("-C")
-            .arg(repo_path)
-            .args(args)
-            .output()
-            .expect("spawn git");
-        assert!(
-            output.status.success(),
-            "git {:?} failed: stdout={:?} stderr={:?}",
-            args,
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr)
-        );
-    }
-
-    run_git(repo_path, &["init", "-b", "main"]);
-    run_git(repo_path, &["config", "user.email", "test@example.com"]);
-    run_git(repo_path, &["config", "user.name", "Test User"]);
+    init_git_repo(repo_path);
     std::fs::write(repo_path.join("file.txt"), "hello\n").unwrap();
     run_git(repo_path, &["add", "."]);
     run_git(repo_path, &["commit", "-m", "initial"]);
-
-    let head_sha = std::process::Command::new("git")
-        .arg("-C")
-        .arg(repo_path)
-        .args(["rev-parse", "HEAD"])
-        .output()
-        .expect("rev-parse HEAD");
-    assert!(head_sha.status.success());
-    let head_sha = String::from_utf8(head_sha.stdout)
-        .expect("utf8 sha")
-        .trim()
-        .to_string();
+    let head_sha = git_stdout(repo_path, &["rev-parse", "HEAD"]);
 
     let codex_home = Arc::new(TempDir::new().unwrap());
     let initial_cwd_path = initial_cwd.path().to_path_buf();
@@ -838,6 +825,8 @@ async fn review_uses_overridden_cwd_for_base_branch_merge_base() {
                     branch: "main".to_string(),
                 },
                 user_facing_hint: None,
+                only_changed_lines: false,
+                baseline: None,
             },
         })
         .await
@@ -867,6 +856,189 @@ async fn review_uses_overridden_cwd_for_base_branch_merge_base() {
     server.verify().await;
 }
 
+/// When `only_changed_lines` is set, findings outside the lines touched by the
+/// patch file are dropped before the review output is emitted. Patch paths are
+/// relative to the repository root even when the session runs in a subdirectory.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_patch_file_keeps_only_findings_on_changed_lines() {
+    skip_if_no_network!();
+
+    let repo = TempDir::new().unwrap();
+    let repo_path = repo.path().to_path_buf();
+    init_git_repo(&repo_path);
+    std::fs::create_dir_all(repo_path.join("docs")).unwrap();
+    std::fs::write(
+        repo_path.join("docs/change.patch"),
+        "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,2 +10,3 @@\n fn a() {}\n+fn b() {}\n fn c() {}\n",
+    )
+    .unwrap();
+
+    let titles = review_changed_lines(
+        repo_path.join("docs"),
+        ReviewTarget::PatchFile {
+            path: PathBuf::from("change.patch"),
+        },
+        vec![
+            changed_lines_finding("on changed line", repo_path.join("src/lib.rs"), 11),
+            changed_lines_finding("untouched line", repo_path.join("src/lib.rs"), 40),
+        ],
+    )
+    .await;
+    assert_eq!(titles, vec!["on changed line"]);
+}
+
+/// Reviewing only the changed lines of a repository's first commit keeps the
+/// findings on the lines it adds.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_root_commit_keeps_only_findings_on_changed_lines() {
+    skip_if_no_network!();
+
+    let repo = TempDir::new().unwrap();
+    let repo_path = repo.path().to_path_buf();
+    init_git_repo(&repo_path);
+    std::fs::write(repo_path.join("a.txt"), "one\ntwo\n").unwrap();
+    run_git(&repo_path, &["add", "."]);
+    run_git(&repo_path, &["commit", "-m", "first"]);
+    let sha = git_stdout(&repo_path, &["rev-parse", "HEAD"]);
+
+    let titles = review_changed_lines(
+        repo_path.clone(),
+        ReviewTarget::Commit { sha, title: None },
+        vec![
+            changed_lines_finding("added line", repo_path.join("a.txt"), 2),
+            changed_lines_finding("other file", repo_path.join("b.txt"), 1),
+        ],
+    )
+    .await;
+    assert_eq!(titles, vec!["added line"]);
+}
+
+/// Path targets count every line below the listed paths as changed, also when
+/// findings spell the path differently (here through a `..` component).
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_paths_keeps_only_findings_below_the_paths() {
+    skip_if_no_network!();
+
+    let repo = TempDir::new().unwrap();
+    let repo_path = repo.path().to_path_buf();
+    std::fs::create_dir_all(repo_path.join("src")).unwrap();
+    std::fs::create_dir_all(repo_path.join("tests")).unwrap();
+    std::fs::write(repo_path.join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(repo_path.join("tests/lib.rs"), "fn t() {}\n").unwrap();
+
+    let titles = review_changed_lines(
+        repo_path.clone(),
+        ReviewTarget::Paths {
+            paths: vec![PathBuf::from("src")],
+        },
+        vec![
+            changed_lines_finding("in src", repo_path.join("tests/../src/lib.rs"), 1),
+            changed_lines_finding("in tests", repo_path.join("tests/lib.rs"), 1),
+        ],
+    )
+    .await;
+    assert_eq!(titles, vec!["in src"]);
+}
+
+fn changed_lines_finding(title: &str, path: PathBuf, line: u32) -> serde_json::Value {
+    serde_json::json!({
+        "title": title,
+        "body": "body",
+        "confidence_score": 0.5,
+        "priority": 2,
+        "code_location": {
+            "absolute_file_path": path,
+            "line_range": {"start": line, "end": line}
+        }
+    })
+}
+
+/// Runs a review of `target` with `only_changed_lines` set, where the model
+/// reports `findings`, and returns the titles of the findings that remain.
+#[expect(clippy::unwrap_used, clippy::expect_used)]
+async fn review_changed_lines(
+    cwd: PathBuf,
+    target: ReviewTarget,
+    findings: Vec<serde_json::Value>,
+) -> Vec<String> {
+    let review_json = serde_json::json!({
+        "findings": findings,
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "Some issues.",
+        "overall_confidence_score": 0.5
+    })
+    .to_string();
+    let sse_template = r#"[
+            {"type":"response.output_item.done", "item":{
+                "type":"message", "role":"assistant",
+                "content":[{"type":"output_text","text":__REVIEW__}]
+            }},
+            {"type":"response.completed", "response": {"id": "__ID__"}}
+        ]"#;
+    let review_json_escaped = serde_json::to_string(&review_json).unwrap();
+    let sse_raw = sse_template.replace("__REVIEW__", &review_json_escaped);
+    let (server, _request_log) = start_responses_server_with_sse(&sse_raw, 1).await;
+    let codex_home = Arc::new(TempDir::new().unwrap());
+    let codex = new_conversation_for_server(&server, codex_home.clone(), move |config| {
+        config.cwd = cwd;
+    })
+    .await;
+
+    codex
+        .submit(Op::Review {
+            review_request: ReviewRequest {
+                target,
+                user_facing_hint: None,
+                only_changed_lines: true,
+                baseline: None,
+            },
+        })
+        .await
+        .unwrap();
+
+    let closed = wait_for_event(&codex, |ev| matches!(ev, EventMsg::ExitedReviewMode(_))).await;
+    let review = match closed {
+        EventMsg::ExitedReviewMode(ev) => ev.review_output.expect("review output"),
+        other => panic!("expected ExitedReviewMode(..), got {other:?}"),
+    };
+    let _complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+
+    let _codex_home_guard = codex_home;
+    server.verify().await;
+    review.findings.into_iter().map(|f| f.title).collect()
+}
+
+fn init_git_repo(repo_path: &std::path::Path) {
+    run_git(repo_path, &["init", "-b", "main"]);
+    run_git(repo_path, &["config", "user.email", "test@example.com"]);
+    run_git(repo_path, &["config", "user.name", "Test User"]);
+}
+
+fn run_git(repo_path: &std::path::Path, args: &[&str]) {
+    git_stdout(repo_path, args);
+}
+
+#[expect(clippy::expect_used)]
+fn git_stdout(repo_path: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+        .expect("spawn git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: stdout={:?} stderr={:?}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .expect("utf8 git output")
+        .trim()
+        .to_string()
+}
+
 /// Start a mock Responses API server and mount the given SSE stream body.
 async fn start_responses_server_with_sse(
     sse_raw: &str,
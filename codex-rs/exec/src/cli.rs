@@ -95,6 +95,11 @@ pub struct Cli {
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
 
+    /// Write a JSON summary of the run (final message, file changes, commands,
+    /// token usage, approvals and status) to FILE when the run ends.
+    #[arg(long = "report", value_name = "FILE")]
+    pub report_file: Option<PathBuf>,
+
     /// Initial instructions for the agent. If not provided as an argument (or
     /// if `-` is used), instructions are read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
@@ -207,7 +212,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "uncommitted",
         default_value_t = false,
-        conflicts_with_all = ["base", "commit", "prompt"]
+        conflicts_with_all = ["base", "commit", "range", "patch", "paths", "prompt"]
     )]
     pub uncommitted: bool,
 
@@ -215,7 +220,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "base",
         value_name = "BRANCH",
-        conflicts_with_all = ["uncommitted", "commit", "prompt"]
+        conflicts_with_all = ["uncommitted", "commit", "range", "patch", "paths", "prompt"]
     )]
     pub base: Option<String>,
 
@@ -223,7 +228,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "commit",
         value_name = "SHA",
-        conflicts_with_all = ["uncommitted", "base", "prompt"]
+        conflicts_with_all = ["uncommitted", "base", "range", "patch", "paths", "prompt"]
     )]
     pub commit: Option<String>,
 
@@ -231,6 +236,65 @@ pub struct ReviewArgs {
     #[arg(long = "title", value_name = "TITLE", requires = "commit")]
     pub commit_title: Option<String>,
 
+    /// Review the changes between two commits, given as `BASE..HEAD`.
+    #[arg(
+        long = "range",
+        value_name = "BASE..HEAD",
+        conflicts_with_all = ["uncommitted", "base", "commit", "patch", "paths", "prompt"]
+    )]
+    pub range: Option<String>,
+
+    /// Review the changes in a unified diff or patch file.
+    #[arg(
+        long = "patch",
+        value_name = "FILE",
+        conflicts_with_all = ["uncommitted", "base", "commit", "range", "paths", "prompt"]
+    )]
+    pub patch: Option<PathBuf>,
+
+    /// Review the current contents of the given files or directories.
+    #[arg(
+        long = "paths",
+        value_name = "PATH",
+        num_args = 1..,
+        conflicts_with_all = ["uncommitted", "base", "commit", "range", "patch", "prompt"]
+    )]
+    pub paths: Vec<PathBuf>,
+
+    /// Drop findings that do not touch a line changed by the review target.
+    #[arg(long = "only-changed-lines", default_value_t = false)]
+    pub only_changed_lines: bool,
+
+    /// Baseline file of accepted findings. Findings already in the baseline
+    /// are not reported; a missing file is treated as an empty baseline.
+    #[arg(long = "baseline", value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Add the findings reported by this run to the baseline file.
+    #[arg(
+        long = "update-baseline",
+        default_value_t = false,
+        requires = "baseline"
+    )]
+    pub update_baseline: bool,
+
+    /// Exit with a non-zero status when a reported finding has this priority
+    /// or a more urgent one (0 is the most urgent).
+    #[arg(
+        long = "fail-on-priority",
+        value_name = "PRIORITY",
+        value_parser = clap::value_parser!(i32).range(0..=3)
+    )]
+    pub fail_on_priority: Option<i32>,
+
+    /// Write the review findings to the given file as SARIF 2.1.0.
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif_file: Option<PathBuf>,
+
+    /// Write the review findings to the given file as JUnit XML.
+    #[arg(long = "junit", value_name = "FILE")]
+    pub junit_file: Option<PathBuf>,
+
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
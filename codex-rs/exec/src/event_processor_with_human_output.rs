@@ -479,6 +479,32 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                                 }
                             }
                         }
+                        FileChange::AddBinary { size_bytes } => {
+                            let header = format!(
+                                "{} {} (binary, {size_bytes} bytes)",
+                                format_file_change(change),
+                                path.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
+                        FileChange::AddSymlink { target } => {
+                            let header = format!(
+                                "{} {} -> {}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                target.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
+                        FileChange::ModeChange { executable } => {
+                            let mode = if *executable { "+x" } else { "-x" };
+                            let header = format!(
+                                "{} {} ({mode})",
+                                format_file_change(change),
+                                path.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
                     }
                 }
             }
@@ -768,6 +794,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             }
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::ThreadNameUpdated(_)
+            | EventMsg::PinnedMessagesUpdated(_)
+            | EventMsg::CollaborationModeUpdated(_)
             | EventMsg::ExecApprovalRequest(_)
             | EventMsg::ApplyPatchApprovalRequest(_)
             | EventMsg::TerminalInteraction(_)
@@ -831,8 +859,11 @@ fn escape_command(command: &[String]) -> String {
 
 fn format_file_change(change: &FileChange) -> &'static str {
     match change {
-        FileChange::Add { .. } => "A",
+        FileChange::Add { .. } | FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {
+            "A"
+        }
         FileChange::Delete { .. } => "D",
+        FileChange::ModeChange { .. } => "M",
         FileChange::Update {
             move_path: Some(_), ..
         } => "R",
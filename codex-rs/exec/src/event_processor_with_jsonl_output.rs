@@ -636,14 +636,6 @@ impl EventProcessorWithJsonOutput {
         Vec::new()
     }
 
-    fn map_change_kind(&self, kind: &protocol::FileChange) -> PatchChangeKind {
-        match kind {
-            protocol::FileChange::Add { .. } => PatchChangeKind::Add,
-            protocol::FileChange::Delete { .. } => PatchChangeKind::Delete,
-            protocol::FileChange::Update { .. } => PatchChangeKind::Update,
-        }
-    }
-
     fn handle_patch_apply_end(&mut self, ev: &protocol::PatchApplyEndEvent) -> Vec<ThreadEvent> {
         if let Some(running_patch_apply) = self.running_patch_applies.remove(&ev.call_id) {
             let status = if ev.success {
@@ -660,7 +652,7 @@ impl EventProcessorWithJsonOutput {
                         .iter()
                         .map(|(path, change)| FileUpdateChange {
                             path: path.to_str().unwrap_or("").to_string(),
-                            kind: self.map_change_kind(change),
+                            kind: map_change_kind(change),
                         })
                         .collect(),
                     status,
@@ -797,6 +789,18 @@ impl EventProcessorWithJsonOutput {
     }
 }
 
+pub(crate) fn map_change_kind(kind: &protocol::FileChange) -> PatchChangeKind {
+    match kind {
+        protocol::FileChange::Add { .. }
+        | protocol::FileChange::AddBinary { .. }
+        | protocol::FileChange::AddSymlink { .. } => PatchChangeKind::Add,
+        protocol::FileChange::Delete { .. } => PatchChangeKind::Delete,
+        protocol::FileChange::Update { .. } | protocol::FileChange::ModeChange { .. } => {
+            PatchChangeKind::Update
+        }
+    }
+}
+
 fn is_collab_failure(status: &CoreAgentStatus) -> bool {
     matches!(
         status,
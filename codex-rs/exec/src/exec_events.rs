@@ -309,3 +309,75 @@ pub struct TodoItem {
 pub struct TodoListItem {
     pub items: Vec<TodoItem>,
 }
+
+/// Summary of a whole `codex exec` run, written as a single JSON document by
+/// `--report`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ExecReport {
+    /// The identifier of the thread. Can be used to resume the thread later.
+    pub thread_id: Option<String>,
+    pub status: ExecReportStatus,
+    /// The agent's final message.
+    pub final_message: Option<String>,
+    /// The final message parsed as JSON when `--output-schema` was given.
+    pub structured_output: Option<JsonValue>,
+    /// Files changed by the agent, sorted by path.
+    pub files_changed: Vec<FileUpdateChange>,
+    /// Unified diff of all file changes made during the run.
+    pub diff: Option<String>,
+    /// Commands run by the agent, in the order they finished.
+    pub commands: Vec<ExecReportCommand>,
+    /// Total token usage of the run.
+    pub usage: Usage,
+    /// Approvals the agent requested.
+    pub approvals: Vec<ExecReportApproval>,
+    /// Error messages reported during the run.
+    pub errors: Vec<String>,
+}
+
+/// Overall outcome of a `codex exec` run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecReportStatus {
+    Completed,
+    Failed,
+    Aborted,
+}
+
+/// A command run by the agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ExecReportCommand {
+    pub command: String,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    pub status: ExecReportCommandStatus,
+}
+
+/// Outcome of a command run by the agent.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecReportCommandStatus {
+    Completed,
+    Failed,
+    /// The command was rejected, by the user or by the approval policy,
+    /// before it ran.
+    Declined,
+    /// The command was killed after exceeding its timeout.
+    TimedOut,
+}
+
+/// An approval requested by the agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecReportApproval {
+    /// Approval to run a command.
+    Command {
+        command: String,
+        reason: Option<String>,
+    },
+    /// Approval to apply file changes.
+    FileChange {
+        paths: Vec<String>,
+        reason: Option<String>,
+    },
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::event_processor_with_jsonl_output::map_change_kind;
+use crate::exec_events::ExecReport;
+use crate::exec_events::ExecReportApproval;
+use crate::exec_events::ExecReportCommand;
+use crate::exec_events::ExecReportCommandStatus;
+use crate::exec_events::ExecReportStatus;
+use crate::exec_events::FileUpdateChange;
+use crate::exec_events::PatchChangeKind;
+use crate::exec_events::Usage;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExecCommandStatus;
+
+/// Accumulates the events of a `codex exec` run into an [`ExecReport`].
+pub struct ExecReportCollector {
+    thread_id: Option<String>,
+    structured_output: bool,
+    final_message: Option<String>,
+    files_changed: BTreeMap<String, PatchChangeKind>,
+    diff: Option<String>,
+    commands: Vec<ExecReportCommand>,
+    usage: Usage,
+    approvals: Vec<ExecReportApproval>,
+    errors: Vec<String>,
+    turn_completed: bool,
+    aborted: bool,
+}
+
+impl ExecReportCollector {
+    /// `structured_output` is set when the run used `--output-schema`, in
+    /// which case the final message is also reported as parsed JSON.
+    pub fn new(structured_output: bool) -> Self {
+        Self {
+            thread_id: None,
+            structured_output,
+            final_message: None,
+            files_changed: BTreeMap::new(),
+            diff: None,
+            commands: Vec::new(),
+            usage: Usage::default(),
+            approvals: Vec::new(),
+            errors: Vec::new(),
+            turn_completed: false,
+            aborted: false,
+        }
+    }
+
+    pub fn observe(&mut self, msg: &EventMsg) {
+        match msg {
+            EventMsg::SessionConfigured(ev) => {
+                self.thread_id = Some(ev.session_id.to_string());
+            }
+            EventMsg::AgentMessage(ev) => {
+                self.final_message = Some(ev.message.clone());
+            }
+            EventMsg::ExecCommandEnd(ev) => {
+                self.commands.push(ExecReportCommand {
+                    command: join_command(&ev.command),
+                    exit_code: ev.exit_code,
+                    duration_ms: u64::try_from(ev.duration.as_millis()).unwrap_or(u64::MAX),
+                    status: command_status(ev),
+                });
+            }
+            EventMsg::PatchApplyEnd(ev) if ev.success => {
+                for (path, change) in &ev.changes {
+                    self.files_changed
+                        .insert(path.to_string_lossy().into_owned(), map_change_kind(change));
+                }
+            }
+            EventMsg::TurnDiff(ev) => {
+                self.diff = Some(ev.unified_diff.clone());
+            }
+            EventMsg::TokenCount(ev) => {
+                if let Some(info) = &ev.info {
+                    let total = &info.total_token_usage;
+                    self.usage = Usage {
+                        input_tokens: total.input_tokens,
+                        cached_input_tokens: total.cached_input_tokens,
+                        output_tokens: total.output_tokens,
+                    };
+                }
+            }
+            EventMsg::ExecApprovalRequest(ev) => {
+                self.approvals.push(ExecReportApproval::Command {
+                    command: join_command(&ev.command),
+                    reason: ev.reason.clone(),
+                });
+            }
+            EventMsg::ApplyPatchApprovalRequest(ev) => {
+                let mut paths: Vec<String> = ev
+                    .changes
+                    .keys()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect();
+                paths.sort();
+                self.approvals.push(ExecReportApproval::FileChange {
+                    paths,
+                    reason: ev.reason.clone(),
+                });
+            }
+            EventMsg::Error(ev) => {
+                self.errors.push(ev.message.clone());
+            }
+            EventMsg::TurnComplete(ev) => {
+                self.turn_completed = true;
+                if let Some(message) = &ev.last_agent_message {
+                    self.final_message = Some(message.clone());
+                }
+            }
+            EventMsg::TurnAborted(_) => {
+                self.aborted = true;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn finish(self) -> ExecReport {
+        let status = if self.aborted {
+            ExecReportStatus::Aborted
+        } else if self.turn_completed && self.errors.is_empty() {
+            ExecReportStatus::Completed
+        } else {
+            ExecReportStatus::Failed
+        };
+        let structured_output = if self.structured_output {
+            self.final_message
+                .as_deref()
+                .and_then(|message| serde_json::from_str(message).ok())
+        } else {
+            None
+        };
+        ExecReport {
+            thread_id: self.thread_id,
+            status,
+            final_message: self.final_message,
+            structured_output,
+            files_changed: self
+                .files_changed
+                .into_iter()
+                .map(|(path, kind)| FileUpdateChange { path, kind })
+                .collect(),
+            diff: self.diff.filter(|diff| !diff.is_empty()),
+            commands: self.commands,
+            usage: self.usage,
+            approvals: self.approvals,
+            errors: self.errors,
+        }
+    }
+}
+
+pub(crate) fn write_report(report: &ExecReport, path: &Path) -> std::io::Result<()> {
+    let contents = serde_json::to_string_pretty(report).map_err(std::io::Error::other)?;
+    std::fs::write(path, contents)
+}
+
+/// Classifies a finished command from the status core reports with it.
+fn command_status(ev: &ExecCommandEndEvent) -> ExecReportCommandStatus {
+    match ev.status {
+        ExecCommandStatus::Exited if ev.exit_code == 0 => ExecReportCommandStatus::Completed,
+        ExecCommandStatus::Exited | ExecCommandStatus::Failed => ExecReportCommandStatus::Failed,
+        ExecCommandStatus::TimedOut => ExecReportCommandStatus::TimedOut,
+        ExecCommandStatus::Declined => ExecReportCommandStatus::Declined,
+    }
+}
+
+fn join_command(command: &[String]) -> String {
+    shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
+}
@@ -9,6 +9,7 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+pub mod exec_report;
 
 pub use cli::Cli;
 pub use cli::Command;
@@ -36,19 +37,24 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
+use codex_git::repository_root_or_self;
 use codex_protocol::approvals::ElicitationAction;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
+use exec_report::ExecReportCollector;
+use exec_report::write_report;
 use serde_json::Value;
 use std::collections::HashSet;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use supports_color::Stream;
@@ -68,6 +74,9 @@ use codex_core::default_client::set_default_client_residency_requirement;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::find_thread_path_by_name_str;
+use codex_core::review_baseline::ReviewBaseline;
+use codex_core::review_export::render_review_junit;
+use codex_core::review_export::render_review_sarif;
 
 enum InitialOperation {
     UserTurn {
@@ -105,6 +114,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         add_dir,
         color,
         last_message_file,
+        report_file,
         json: json_mode,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
@@ -370,6 +380,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     } else {
         thread_manager.start_thread(config.clone()).await?
     };
+    let review_outputs = match command.as_ref() {
+        Some(ExecCommand::Review(args)) => ReviewOutputs::from_args(args)?,
+        _ => ReviewOutputs::default(),
+    };
     let (initial_operation, prompt_summary) = match (command, prompt, images) {
         (Some(ExecCommand::Review(review_cli)), _, _) => {
             let review_request = build_review_request(review_cli)?;
@@ -433,6 +447,18 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Print the effective configuration and initial request so users can see what Codex
     // is using.
     event_processor.print_config_summary(&config, &prompt_summary, &session_configured);
+    let mut report_collector = report_file.as_ref().map(|_| {
+        let structured_output = matches!(
+            &initial_operation,
+            InitialOperation::UserTurn {
+                output_schema: Some(_),
+                ..
+            }
+        );
+        let mut collector = ExecReportCollector::new(structured_output);
+        collector.observe(&EventMsg::SessionConfigured(session_configured.clone()));
+        collector
+    });
 
     info!("Codex initialized with event: {session_configured:?}");
 
@@ -515,6 +541,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    let mut review_output = None;
     while let Some(envelope) = rx.recv().await {
         let ThreadEventEnvelope {
             thread_id,
@@ -534,6 +561,16 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if thread_id == primary_thread_id
+            && let Some(collector) = report_collector.as_mut()
+        {
+            collector.observe(&event.msg);
+        }
+        if thread_id == primary_thread_id
+            && let EventMsg::ExitedReviewMode(ev) = &event.msg
+        {
+            review_output = ev.review_output.clone();
+        }
         if thread_id != primary_thread_id && matches!(&event.msg, EventMsg::TurnComplete(_)) {
             continue;
         }
@@ -551,6 +588,49 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
+    if let (Some(collector), Some(report_file)) = (report_collector, report_file.as_deref()) {
+        if let Err(err) = write_report(&collector.finish(), report_file) {
+            eprintln!(
+                "Failed to write report file {}: {err}",
+                report_file.display()
+            );
+            error_seen = true;
+        }
+    }
+    // Exported paths are relative to the repository root, not to `-C`.
+    let review_root = repository_root_or_self(config.cwd.as_path());
+    if let Some(path) = review_outputs.sarif_file.as_deref() {
+        write_review_export(review_output.as_ref(), path, |output| {
+            serde_json::to_string_pretty(&render_review_sarif(output, &review_root))
+                .map_err(std::io::Error::other)
+        });
+    }
+    if let Some(path) = review_outputs.junit_file.as_deref() {
+        write_review_export(review_output.as_ref(), path, |output| {
+            Ok(render_review_junit(output, &review_root))
+        });
+    }
+    if let (Some(path), Some(output)) = (
+        review_outputs.update_baseline.as_deref(),
+        review_output.as_ref(),
+    ) {
+        update_review_baseline(path, output, config.cwd.as_path());
+    }
+    if let (Some(max_priority), Some(output)) =
+        (review_outputs.fail_on_priority, review_output.as_ref())
+    {
+        let failing = output
+            .findings
+            .iter()
+            .filter(|finding| finding.priority <= max_priority)
+            .count();
+        if failing > 0 {
+            eprintln!(
+                "Review reported {failing} new finding(s) at priority P{max_priority} or above"
+            );
+            error_seen = true;
+        }
+    }
     if error_seen {
         std::process::exit(1);
     }
@@ -775,6 +855,62 @@ fn resolve_prompt(prompt_arg: Option<String>) -> String {
     }
 }
 
+/// Files and checks of `codex exec review` that run on the final findings.
+#[derive(Default)]
+struct ReviewOutputs {
+    sarif_file: Option<PathBuf>,
+    junit_file: Option<PathBuf>,
+    /// Baseline file to add the reported findings to.
+    update_baseline: Option<PathBuf>,
+    fail_on_priority: Option<i32>,
+}
+
+impl ReviewOutputs {
+    fn from_args(args: &ReviewArgs) -> std::io::Result<Self> {
+        let update_baseline = match (&args.baseline, args.update_baseline) {
+            (Some(path), true) => Some(std::path::absolute(path)?),
+            _ => None,
+        };
+        Ok(Self {
+            sarif_file: args.sarif_file.clone(),
+            junit_file: args.junit_file.clone(),
+            update_baseline,
+            fail_on_priority: args.fail_on_priority,
+        })
+    }
+}
+
+/// Adds the findings reported by this review to the baseline at `path`.
+fn update_review_baseline(path: &Path, output: &ReviewOutputEvent, cwd: &Path) {
+    let result = ReviewBaseline::load(path).and_then(|mut baseline| {
+        baseline.add_findings(&output.findings, cwd);
+        baseline.save(path)?;
+        Ok(())
+    });
+    if let Err(err) = result {
+        eprintln!("Failed to update review baseline {}: {err}", path.display());
+    }
+}
+
+/// Writes the review findings rendered by `render` to `path`. Nothing is
+/// written when the review ended without producing output.
+fn write_review_export(
+    output: Option<&ReviewOutputEvent>,
+    path: &Path,
+    render: impl FnOnce(&ReviewOutputEvent) -> std::io::Result<String>,
+) {
+    let Some(output) = output else {
+        eprintln!(
+            "Review produced no findings output; not writing {}",
+            path.display()
+        );
+        return;
+    };
+    if let Err(err) = render(output).and_then(|contents| std::fs::write(path, contents)) {
+        eprintln!("Failed to write review output {}: {err}", path.display());
+    }
+}
+
 fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
     let target = if args.uncommitted {
         ReviewTarget::UncommittedChanges
@@ -785,6 +921,21 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
             sha,
             title: args.commit_title,
         }
+    } else if let Some(range) = args.range {
+        let Some((base, head)) = range.split_once("..") else {
+            anyhow::bail!("--range must be given as BASE..HEAD");
+        };
+        if base.is_empty() || head.is_empty() || head.starts_with('.') {
+            anyhow::bail!("--range must be given as BASE..HEAD");
+        }
+        ReviewTarget::CommitRange {
+            base: base.to_string(),
+            head: head.to_string(),
+        }
+    } else if let Some(path) = args.patch {
+        ReviewTarget::PatchFile { path }
+    } else if !args.paths.is_empty() {
+        ReviewTarget::Paths { paths: args.paths }
     } else if let Some(prompt_arg) = args.prompt {
         let prompt = resolve_prompt(Some(prompt_arg)).trim().to_string();
         if prompt.is_empty() {
@@ -795,13 +946,17 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
         }
     } else {
         anyhow::bail!(
-            "Specify --uncommitted, --base, --commit, or provide custom review instructions"
+            "Specify --uncommitted, --base, --commit, --range, --patch, --paths, or provide custom review instructions"
         );
     };
 
+    let baseline = args.baseline.map(std::path::absolute).transpose()?;
+
     Ok(ReviewRequest {
         target,
         user_facing_hint: None,
+        only_changed_lines: args.only_changed_lines,
+        baseline,
     })
 }
 
@@ -817,6 +972,15 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            patch: None,
+            paths: Vec::new(),
+            only_changed_lines: false,
+            baseline: None,
+            update_baseline: false,
+            fail_on_priority: None,
+            sarif_file: None,
+            junit_file: None,
             prompt: None,
         })
         .expect("builds uncommitted review request");
@@ -824,6 +988,8 @@ mod tests {
         let expected = ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: None,
+            only_changed_lines: false,
+            baseline: None,
         };
 
         assert_eq!(request, expected);
@@ -836,6 +1002,15 @@ mod tests {
             base: None,
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
+            range: None,
+            patch: None,
+            paths: Vec::new(),
+            only_changed_lines: false,
+            baseline: None,
+            update_baseline: false,
+            fail_on_priority: None,
+            sarif_file: None,
+            junit_file: None,
             prompt: None,
         })
         .expect("builds commit review request");
@@ -846,11 +1021,69 @@ mod tests {
                 title: Some("Add review command".to_string()),
             },
             user_facing_hint: None,
+            only_changed_lines: false,
+            baseline: None,
         };
 
         assert_eq!(request, expected);
     }
 
+    #[test]
+    fn builds_commit_range_review_request() {
+        let request = build_review_request(ReviewArgs {
+            uncommitted: false,
+            base: None,
+            commit: None,
+            commit_title: None,
+            range: Some("main..feature".to_string()),
+            patch: None,
+            paths: Vec::new(),
+            only_changed_lines: true,
+            baseline: None,
+            update_baseline: false,
+            fail_on_priority: None,
+            sarif_file: None,
+            junit_file: None,
+            prompt: None,
+        })
+        .expect("builds commit range review request");
+
+        let expected = ReviewRequest {
+            target: ReviewTarget::CommitRange {
+                base: "main".to_string(),
+                head: "feature".to_string(),
+            },
+            user_facing_hint: None,
+            only_changed_lines: true,
+            baseline: None,
+        };
+
+        assert_eq!(request, expected);
+    }
+
+    #[test]
+    fn rejects_malformed_commit_range() {
+        for range in ["main", "main..", "..feature", "main...feature"] {
+            let result = build_review_request(ReviewArgs {
+                uncommitted: false,
+                base: None,
+                commit: None,
+                commit_title: None,
+                range: Some(range.to_string()),
+                patch: None,
+                paths: Vec::new(),
+                only_changed_lines: false,
+                baseline: None,
+                update_baseline: false,
+                fail_on_priority: None,
+                sarif_file: None,
+                junit_file: None,
+                prompt: None,
+            });
+            assert!(result.is_err(), "expected {range} to be rejected");
+        }
+    }
+
     #[test]
     fn builds_custom_review_request_trims_prompt() {
         let request = build_review_request(ReviewArgs {
@@ -858,6 +1091,15 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            patch: None,
+            paths: Vec::new(),
+            only_changed_lines: false,
+            baseline: None,
+            update_baseline: false,
+            fail_on_priority: None,
+            sarif_file: None,
+            junit_file: None,
             prompt: Some("  custom review instructions  ".to_string()),
         })
         .expect("builds custom review request");
@@ -867,6 +1109,8 @@ mod tests {
                 instructions: "custom review instructions".to_string(),
             },
             user_facing_hint: None,
+            only_changed_lines: false,
+            baseline: None,
         };
 
         assert_eq!(request, expected);
@@ -11,6 +11,7 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecCommandStatus;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
@@ -896,6 +897,7 @@ fn exec_command_end_success_produces_completed_command_item() {
             exit_code: 0,
             duration: Duration::from_millis(5),
             formatted_output: String::new(),
+            status: ExecCommandStatus::Exited,
         }),
     );
     let out_ok = ep.collect_thread_events(&end_ok);
@@ -983,6 +985,7 @@ fn command_execution_output_delta_updates_item_progress() {
             exit_code: 0,
             duration: Duration::from_millis(3),
             formatted_output: String::new(),
+            status: ExecCommandStatus::Exited,
         }),
     );
     let out_end = ep.collect_thread_events(&end);
@@ -1056,6 +1059,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
             exit_code: 1,
             duration: Duration::from_millis(2),
             formatted_output: String::new(),
+            status: ExecCommandStatus::Exited,
         }),
     );
     let out_fail = ep.collect_thread_events(&end_fail);
@@ -1097,6 +1101,7 @@ fn exec_command_end_without_begin_is_ignored() {
             exit_code: 0,
             duration: Duration::from_millis(1),
             formatted_output: String::new(),
+            status: ExecCommandStatus::Exited,
         }),
     );
     let out = ep.collect_thread_events(&end_only);
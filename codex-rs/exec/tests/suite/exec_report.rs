@@ -0,0 +1,260 @@
+use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalRequestEvent;
+use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecCommandStatus;
+use codex_core::protocol::FileChange;
+use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::TokenCountEvent;
+use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
+use codex_core::protocol::TurnCompleteEvent;
+use codex_core::protocol::TurnDiffEvent;
+use codex_exec::exec_events::ExecReport;
+use codex_exec::exec_events::ExecReportApproval;
+use codex_exec::exec_events::ExecReportCommand;
+use codex_exec::exec_events::ExecReportCommandStatus;
+use codex_exec::exec_events::ExecReportStatus;
+use codex_exec::exec_events::FileUpdateChange;
+use codex_exec::exec_events::PatchChangeKind;
+use codex_exec::exec_events::Usage;
+use codex_exec::exec_report::ExecReportCollector;
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn exec_command_end(command: &[&str], exit_code: i32, duration: Duration) -> EventMsg {
+    exec_command_end_with_status(command, exit_code, duration, ExecCommandStatus::Exited)
+}
+
+fn exec_command_end_with_status(
+    command: &[&str],
+    exit_code: i32,
+    duration: Duration,
+    status: ExecCommandStatus,
+) -> EventMsg {
+    EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+        call_id: "call-1".to_string(),
+        process_id: None,
+        turn_id: "turn-1".to_string(),
+        command: command.iter().map(ToString::to_string).collect(),
+        cwd: PathBuf::from("/repo"),
+        parsed_cmd: Vec::new(),
+        source: ExecCommandSource::Agent,
+        interaction_input: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        aggregated_output: String::new(),
+        exit_code,
+        duration,
+        formatted_output: String::new(),
+        status,
+    })
+}
+
+fn token_count(input_tokens: i64, output_tokens: i64) -> EventMsg {
+    let usage = TokenUsage {
+        input_tokens,
+        cached_input_tokens: 0,
+        output_tokens,
+        reasoning_output_tokens: 0,
+        total_tokens: input_tokens + output_tokens,
+    };
+    EventMsg::TokenCount(TokenCountEvent {
+        info: Some(TokenUsageInfo {
+            total_token_usage: usage.clone(),
+            last_token_usage: usage,
+            model_context_window: None,
+        }),
+        rate_limits: None,
+    })
+}
+
+#[test]
+fn report_summarizes_completed_run() {
+    let mut collector = ExecReportCollector::new(true);
+    let changes = HashMap::from([
+        (
+            PathBuf::from("src/lib.rs"),
+            FileChange::Update {
+                unified_diff: "@@\n-old\n+new\n".to_string(),
+                move_path: None,
+            },
+        ),
+        (
+            PathBuf::from("README.md"),
+            FileChange::Add {
+                content: "hello\n".to_string(),
+            },
+        ),
+    ]);
+    let events = vec![
+        exec_command_end(&["cargo", "test"], 0, Duration::from_millis(1500)),
+        exec_command_end(&["git", "commit", "-m", "two words"], 1, Duration::ZERO),
+        EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id: "call-2".to_string(),
+            turn_id: "turn-1".to_string(),
+            command: vec!["rm".to_string(), "build".to_string()],
+            cwd: PathBuf::from("/repo"),
+            reason: Some("cleanup".to_string()),
+            proposed_execpolicy_amendment: None,
+            parsed_cmd: Vec::new(),
+        }),
+        EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+            call_id: "call-3".to_string(),
+            turn_id: "turn-1".to_string(),
+            stdout: String::new(),
+            stderr: String::new(),
+            success: true,
+            changes,
+        }),
+        EventMsg::TurnDiff(TurnDiffEvent {
+            unified_diff: "diff --git a/src/lib.rs b/src/lib.rs\n".to_string(),
+        }),
+        token_count(10, 5),
+        token_count(30, 12),
+        EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: Some(r#"{"ok": true}"#.to_string()),
+        }),
+    ];
+    for event in &events {
+        collector.observe(event);
+    }
+
+    assert_eq!(
+        collector.finish(),
+        ExecReport {
+            thread_id: None,
+            status: ExecReportStatus::Completed,
+            final_message: Some(r#"{"ok": true}"#.to_string()),
+            structured_output: Some(json!({"ok": true})),
+            files_changed: vec![
+                FileUpdateChange {
+                    path: "README.md".to_string(),
+                    kind: PatchChangeKind::Add,
+                },
+                FileUpdateChange {
+                    path: "src/lib.rs".to_string(),
+                    kind: PatchChangeKind::Update,
+                },
+            ],
+            diff: Some("diff --git a/src/lib.rs b/src/lib.rs\n".to_string()),
+            commands: vec![
+                ExecReportCommand {
+                    command: "cargo test".to_string(),
+                    exit_code: 0,
+                    duration_ms: 1500,
+                    status: ExecReportCommandStatus::Completed,
+                },
+                ExecReportCommand {
+                    command: "git commit -m 'two words'".to_string(),
+                    exit_code: 1,
+                    duration_ms: 0,
+                    status: ExecReportCommandStatus::Failed,
+                },
+            ],
+            usage: Usage {
+                input_tokens: 30,
+                cached_input_tokens: 0,
+                output_tokens: 12,
+            },
+            approvals: vec![ExecReportApproval::Command {
+                command: "rm build".to_string(),
+                reason: Some("cleanup".to_string()),
+            }],
+            errors: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn report_status_reflects_errors_and_aborts() {
+    let mut failed = ExecReportCollector::new(false);
+    failed.observe(&EventMsg::Error(ErrorEvent {
+        message: "stream disconnected".to_string(),
+        codex_error_info: None,
+    }));
+    failed.observe(&EventMsg::TurnComplete(TurnCompleteEvent {
+        last_agent_message: Some("not json".to_string()),
+    }));
+    let report = failed.finish();
+    assert_eq!(report.status, ExecReportStatus::Failed);
+    assert_eq!(report.errors, vec!["stream disconnected".to_string()]);
+    assert_eq!(report.structured_output, None);
+
+    let mut aborted = ExecReportCollector::new(false);
+    aborted.observe(&EventMsg::TurnAborted(TurnAbortedEvent {
+        reason: TurnAbortReason::Interrupted,
+    }));
+    assert_eq!(aborted.finish().status, ExecReportStatus::Aborted);
+}
+
+#[test]
+fn report_distinguishes_declined_and_timed_out_commands() {
+    let mut collector = ExecReportCollector::new(false);
+    let events = vec![
+        exec_command_end_with_status(
+            &["rm", "-rf", "build"],
+            -1,
+            Duration::ZERO,
+            ExecCommandStatus::Declined,
+        ),
+        exec_command_end_with_status(
+            &["sleep", "60"],
+            124,
+            Duration::from_secs(10),
+            ExecCommandStatus::TimedOut,
+        ),
+        exec_command_end_with_status(&["ls"], -1, Duration::ZERO, ExecCommandStatus::Failed),
+    ];
+    for event in &events {
+        collector.observe(event);
+    }
+
+    let statuses: Vec<ExecReportCommandStatus> = collector
+        .finish()
+        .commands
+        .into_iter()
+        .map(|command| command.status)
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ExecReportCommandStatus::Declined,
+            ExecReportCommandStatus::TimedOut,
+            ExecReportCommandStatus::Failed,
+        ]
+    );
+}
+
+/// A report that cannot be written fails the run even when the turn itself
+/// succeeded.
+#[cfg(not(target_os = "windows"))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exits_non_zero_when_report_cannot_be_written() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "done"),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--report")
+        .arg(test.home_path().join("missing-dir").join("report.json"))
+        .arg("say done")
+        .assert()
+        .code(1);
+
+    Ok(())
+}
@@ -2,8 +2,10 @@
 mod add_dir;
 mod apply_patch;
 mod auth_env;
+mod exec_report;
 mod originator;
 mod output_schema;
 mod resume;
+mod review_export;
 mod sandbox;
 mod server_error_exit;
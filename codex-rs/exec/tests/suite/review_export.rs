@@ -0,0 +1,97 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+
+/// Exports run from a subdirectory still report paths relative to the
+/// repository root, and files outside of it as absolute `file://` URIs.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_review_exports_paths_relative_to_the_repository_root() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let repo = test.cwd_path().canonicalize()?;
+    let init = std::process::Command::new("git")
+        .arg("init")
+        .arg(&repo)
+        .output()?;
+    assert!(init.status.success(), "git init failed: {init:?}");
+    std::fs::create_dir_all(repo.join("src"))?;
+    std::fs::write(repo.join("src/lib.rs"), "fn a() {}\n")?;
+    let outside = TempDir::new()?;
+    let outside_file = outside.path().canonicalize()?.join("notes.md");
+
+    let finding = |title: &str, path: &std::path::Path| {
+        json!({
+            "title": title,
+            "body": "body",
+            "confidence_score": 0.5,
+            "priority": 1,
+            "code_location": {
+                "absolute_file_path": path,
+                "line_range": {"start": 1, "end": 1}
+            }
+        })
+    };
+    let review_json = json!({
+        "findings": [
+            finding("In the repository", &repo.join("src/lib.rs")),
+            finding("Outside the repository", &outside_file),
+        ],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "Two issues.",
+        "overall_confidence_score": 0.5
+    })
+    .to_string();
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", &review_json),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    let sarif_path = test.home_path().join("review.sarif");
+    let junit_path = test.home_path().join("review.xml");
+    test.cmd_with_server(&server)
+        .current_dir(repo.join("src"))
+        .arg("--skip-git-repo-check")
+        .arg("review")
+        .arg("--sarif")
+        .arg(&sarif_path)
+        .arg("--junit")
+        .arg(&junit_path)
+        .arg("Review the library")
+        .assert()
+        .success();
+
+    let sarif: Value = serde_json::from_str(&std::fs::read_to_string(&sarif_path)?)?;
+    let run = &sarif["runs"][0];
+    assert_eq!(
+        run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+        format!("file://{}/", repo.display())
+    );
+    let locations: Vec<&Value> = run["results"]
+        .as_array()
+        .expect("SARIF results")
+        .iter()
+        .map(|result| &result["locations"][0]["physicalLocation"]["artifactLocation"])
+        .collect();
+    assert_eq!(
+        locations,
+        vec![
+            &json!({ "uri": "src/lib.rs", "uriBaseId": "%SRCROOT%" }),
+            &json!({ "uri": format!("file://{}", outside_file.display()) }),
+        ]
+    );
+
+    let junit = std::fs::read_to_string(&junit_path)?;
+    assert!(
+        junit.contains(r#"file="src/lib.rs""#),
+        "JUnit report should use the repository-relative path: {junit}"
+    );
+    Ok(())
+}
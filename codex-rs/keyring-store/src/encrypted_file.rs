@@ -0,0 +1,444 @@
+//! A [`KeyringStore`] backed by an age-encrypted file instead of the OS keyring.
+//!
+//! Headless machines often have no Secret Service, so credentials would end up
+//! in plaintext files. This store keeps every service/account pair in a single
+//! JSON document encrypted with [age](https://age-encryption.org). The file is
+//! encrypted either with a passphrase (scrypt) or with an X25519 key file that
+//! is generated on first use and readable only by the current user.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use age::secrecy::ExposeSecret;
+use age::secrecy::SecretString;
+use keyring::Error as KeyringError;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::trace;
+
+use crate::CredentialStoreError;
+use crate::KeyringStore;
+
+/// Name of the encrypted credentials file inside `CODEX_HOME`.
+pub const ENCRYPTED_CREDENTIALS_FILENAME: &str = "credentials.age";
+/// Name of the generated key file inside `CODEX_HOME`.
+pub const ENCRYPTED_CREDENTIALS_KEY_FILENAME: &str = "credentials.key";
+/// When set, the credentials file is encrypted with this passphrase instead of
+/// a key file.
+pub const CREDENTIALS_PASSPHRASE_ENV_VAR: &str = "CODEX_CREDENTIALS_PASSPHRASE";
+/// Overrides the location of the key file.
+pub const CREDENTIALS_KEY_FILE_ENV_VAR: &str = "CODEX_CREDENTIALS_KEY_FILE";
+
+const CREDENTIALS_VERSION: u8 = 1;
+
+/// How the credentials file is encrypted.
+#[derive(Clone)]
+pub enum EncryptionKey {
+    /// scrypt-derived key from a user-supplied passphrase.
+    Passphrase(SecretString),
+    /// age X25519 identity stored in this file, created on first save.
+    KeyFile(PathBuf),
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("Passphrase(..)"),
+            Self::KeyFile(path) => f.debug_tuple("KeyFile").field(path).finish(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CredentialsFile {
+    version: u8,
+    #[serde(default)]
+    entries: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptedFileKeyringStore {
+    path: PathBuf,
+    key: EncryptionKey,
+}
+
+impl EncryptedFileKeyringStore {
+    pub fn new(path: PathBuf, key: EncryptionKey) -> Self {
+        Self { path, key }
+    }
+
+    /// Stores credentials in `CODEX_HOME/credentials.age`, encrypted with
+    /// `CODEX_CREDENTIALS_PASSPHRASE` when set, otherwise with the key file at
+    /// `CODEX_CREDENTIALS_KEY_FILE` (default `CODEX_HOME/credentials.key`).
+    pub fn from_env(codex_home: &Path) -> Self {
+        let key = match std::env::var(CREDENTIALS_PASSPHRASE_ENV_VAR) {
+            Ok(passphrase) if !passphrase.is_empty() => {
+                EncryptionKey::Passphrase(SecretString::from(passphrase))
+            }
+            _ => EncryptionKey::KeyFile(
+                std::env::var_os(CREDENTIALS_KEY_FILE_ENV_VAR)
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| codex_home.join(ENCRYPTED_CREDENTIALS_KEY_FILENAME)),
+            ),
+        };
+        Self::new(codex_home.join(ENCRYPTED_CREDENTIALS_FILENAME), key)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Takes an advisory lock on `<path>.lock` for a read-modify-write cycle,
+    /// so concurrent Codex processes do not drop each other's updates. The
+    /// lock lives in a separate file because writes replace the credentials
+    /// file; readers need no lock since writes are atomic renames.
+    fn lock(&self) -> Result<File, KeyringError> {
+        let mut lock_path = self.path.clone().into_os_string();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+        if let Some(dir) = lock_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|err| {
+                platform_failure(format!("failed to create {}: {err}", dir.display()))
+            })?;
+        }
+        let lock = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .and_then(|lock| lock.lock().map(|()| lock));
+        lock.map_err(|err| {
+            platform_failure(format!("failed to lock {}: {err}", lock_path.display()))
+        })
+    }
+
+    fn read(&self) -> Result<CredentialsFile, KeyringError> {
+        let ciphertext = match fs::read(&self.path) {
+            Ok(ciphertext) => ciphertext,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Ok(CredentialsFile {
+                    version: CREDENTIALS_VERSION,
+                    entries: BTreeMap::new(),
+                });
+            }
+            Err(err) => {
+                return Err(platform_failure(format!(
+                    "failed to read {}: {err}",
+                    self.path.display()
+                )));
+            }
+        };
+        let plaintext = match &self.key {
+            EncryptionKey::Passphrase(passphrase) => {
+                age::decrypt(&age::scrypt::Identity::new(passphrase.clone()), &ciphertext)
+            }
+            EncryptionKey::KeyFile(key_file) => {
+                let identity = read_identity(key_file)?.ok_or_else(|| {
+                    no_storage_access(format!(
+                        "key file {} for {} is missing",
+                        key_file.display(),
+                        self.path.display()
+                    ))
+                })?;
+                age::decrypt(&identity, &ciphertext)
+            }
+        }
+        .map_err(|err| {
+            no_storage_access(format!("failed to decrypt {}: {err}", self.path.display()))
+        })?;
+        let file: CredentialsFile = serde_json::from_slice(&plaintext).map_err(|err| {
+            platform_failure(format!("failed to parse {}: {err}", self.path.display()))
+        })?;
+        if file.version > CREDENTIALS_VERSION {
+            return Err(platform_failure(format!(
+                "credentials file version {} in {} is newer than supported version {CREDENTIALS_VERSION}",
+                file.version,
+                self.path.display()
+            )));
+        }
+        Ok(file)
+    }
+
+    fn write(&self, file: &CredentialsFile) -> Result<(), KeyringError> {
+        let plaintext = serde_json::to_vec(file)
+            .map_err(|err| platform_failure(format!("failed to serialize credentials: {err}")))?;
+        let ciphertext = match &self.key {
+            EncryptionKey::Passphrase(passphrase) => {
+                age::encrypt(&age::scrypt::Recipient::new(passphrase.clone()), &plaintext)
+            }
+            EncryptionKey::KeyFile(key_file) => {
+                let identity = match read_identity(key_file)? {
+                    Some(identity) => identity,
+                    None => create_identity(key_file)?,
+                };
+                age::encrypt(&identity.to_public(), &plaintext)
+            }
+        }
+        .map_err(|err| {
+            platform_failure(format!("failed to encrypt {}: {err}", self.path.display()))
+        })?;
+        write_private_file_atomically(&self.path, &ciphertext)
+    }
+}
+
+impl KeyringStore for EncryptedFileKeyringStore {
+    fn load(&self, service: &str, account: &str) -> Result<Option<String>, CredentialStoreError> {
+        trace!("encrypted_file.load start, service={service}, account={account}");
+        let file = self.read().map_err(CredentialStoreError::new)?;
+        Ok(file
+            .entries
+            .get(service)
+            .and_then(|accounts| accounts.get(account))
+            .cloned())
+    }
+
+    fn save(&self, service: &str, account: &str, value: &str) -> Result<(), CredentialStoreError> {
+        trace!(
+            "encrypted_file.save start, service={service}, account={account}, value_len={}",
+            value.len()
+        );
+        let _lock = self.lock().map_err(CredentialStoreError::new)?;
+        let mut file = self.read().map_err(CredentialStoreError::new)?;
+        file.version = CREDENTIALS_VERSION;
+        file.entries
+            .entry(service.to_string())
+            .or_default()
+            .insert(account.to_string(), value.to_string());
+        self.write(&file).map_err(CredentialStoreError::new)
+    }
+
+    fn delete(&self, service: &str, account: &str) -> Result<bool, CredentialStoreError> {
+        trace!("encrypted_file.delete start, service={service}, account={account}");
+        let _lock = self.lock().map_err(CredentialStoreError::new)?;
+        let mut file = self.read().map_err(CredentialStoreError::new)?;
+        let Some(accounts) = file.entries.get_mut(service) else {
+            return Ok(false);
+        };
+        if accounts.remove(account).is_none() {
+            return Ok(false);
+        }
+        if accounts.is_empty() {
+            file.entries.remove(service);
+        }
+        self.write(&file).map_err(CredentialStoreError::new)?;
+        Ok(true)
+    }
+}
+
+fn read_identity(key_file: &Path) -> Result<Option<age::x25519::Identity>, KeyringError> {
+    let contents = match fs::read_to_string(key_file) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(no_storage_access(format!(
+                "failed to read key file {}: {err}",
+                key_file.display()
+            )));
+        }
+    };
+    // Accept the output of `age-keygen`, which adds comment lines.
+    let line = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .unwrap_or_default();
+    line.parse::<age::x25519::Identity>()
+        .map(Some)
+        .map_err(|err| platform_failure(format!("invalid key file {}: {err}", key_file.display())))
+}
+
+fn create_identity(key_file: &Path) -> Result<age::x25519::Identity, KeyringError> {
+    let identity = age::x25519::Identity::generate();
+    let contents = format!("{}\n", identity.to_string().expose_secret());
+    write_private_file_atomically(key_file, contents.as_bytes())?;
+    Ok(identity)
+}
+
+/// Writes `contents` to a temporary file readable only by the current user and
+/// renames it over `path`.
+fn write_private_file_atomically(path: &Path, contents: &[u8]) -> Result<(), KeyringError> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)
+        .map_err(|err| platform_failure(format!("failed to create {}: {err}", dir.display())))?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    let tmp_path = dir.join(format!(".{file_name}.tmp-{}-{nonce}", std::process::id()));
+
+    let write_tmp = || -> std::io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.create_new(true).write(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut tmp_file = options.open(&tmp_path)?;
+        tmp_file.write_all(contents)?;
+        tmp_file.sync_all()
+    };
+    let result = write_tmp().and_then(|()| {
+        #[cfg(target_os = "windows")]
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        fs::rename(&tmp_path, path)
+    });
+    result.map_err(|err| {
+        let _ = fs::remove_file(&tmp_path);
+        platform_failure(format!("failed to write {}: {err}", path.display()))
+    })
+}
+
+fn platform_failure(message: String) -> KeyringError {
+    KeyringError::PlatformFailure(message.into())
+}
+
+fn no_storage_access(message: String) -> KeyringError {
+    KeyringError::NoStorageAccess(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn passphrase_store(dir: &Path, passphrase: &str) -> EncryptedFileKeyringStore {
+        EncryptedFileKeyringStore::new(
+            dir.join(ENCRYPTED_CREDENTIALS_FILENAME),
+            EncryptionKey::Passphrase(SecretString::from(passphrase.to_string())),
+        )
+    }
+
+    #[test]
+    fn key_file_store_round_trips_and_creates_private_key() -> Result<(), CredentialStoreError> {
+        let dir = tempdir().expect("tempdir");
+        let key_file = dir.path().join(ENCRYPTED_CREDENTIALS_KEY_FILENAME);
+        let store = EncryptedFileKeyringStore::new(
+            dir.path().join(ENCRYPTED_CREDENTIALS_FILENAME),
+            EncryptionKey::KeyFile(key_file.clone()),
+        );
+
+        assert_eq!(store.load("svc", "acct")?, None);
+        store.save("svc", "acct", "secret-token")?;
+        store.save("other", "acct", "other-token")?;
+
+        assert_eq!(store.load("svc", "acct")?, Some("secret-token".to_string()));
+        assert_eq!(
+            store.load("other", "acct")?,
+            Some("other-token".to_string())
+        );
+        let ciphertext = fs::read(store.path()).expect("read credentials");
+        assert!(
+            !String::from_utf8_lossy(&ciphertext).contains("secret-token"),
+            "credentials must not be stored in plaintext"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&key_file)
+                .expect("key metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert!(store.delete("svc", "acct")?);
+        assert!(!store.delete("svc", "acct")?);
+        assert_eq!(store.load("svc", "acct")?, None);
+        assert_eq!(
+            store.load("other", "acct")?,
+            Some("other-token".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_saves_keep_every_entry() -> Result<(), CredentialStoreError> {
+        let dir = tempdir().expect("tempdir");
+        let key_file = dir.path().join(ENCRYPTED_CREDENTIALS_KEY_FILENAME);
+        let store = || {
+            EncryptedFileKeyringStore::new(
+                dir.path().join(ENCRYPTED_CREDENTIALS_FILENAME),
+                EncryptionKey::KeyFile(key_file.clone()),
+            )
+        };
+
+        std::thread::scope(|scope| {
+            let writers: Vec<_> = (0..8)
+                .map(|i| {
+                    let store = store();
+                    scope.spawn(move || store.save("svc", &format!("acct-{i}"), "value"))
+                })
+                .collect();
+            for writer in writers {
+                writer.join().expect("writer thread")?;
+            }
+            Ok::<_, CredentialStoreError>(())
+        })?;
+
+        let store = store();
+        for i in 0..8 {
+            assert_eq!(
+                store.load("svc", &format!("acct-{i}"))?,
+                Some("value".to_string())
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_to_load() -> Result<(), CredentialStoreError> {
+        let dir = tempdir().expect("tempdir");
+        passphrase_store(dir.path(), "correct horse").save("svc", "acct", "value")?;
+
+        assert_eq!(
+            passphrase_store(dir.path(), "correct horse").load("svc", "acct")?,
+            Some("value".to_string())
+        );
+        let error = passphrase_store(dir.path(), "battery staple")
+            .load("svc", "acct")
+            .expect_err("wrong passphrase must fail");
+        assert!(
+            matches!(error.into_error(), KeyringError::NoStorageAccess(_)),
+            "expected NoStorageAccess"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_key_file_for_existing_credentials_is_an_error() -> Result<(), CredentialStoreError> {
+        let dir = tempdir().expect("tempdir");
+        let key_file = dir.path().join(ENCRYPTED_CREDENTIALS_KEY_FILENAME);
+        let store = EncryptedFileKeyringStore::new(
+            dir.path().join(ENCRYPTED_CREDENTIALS_FILENAME),
+            EncryptionKey::KeyFile(key_file.clone()),
+        );
+        store.save("svc", "acct", "value")?;
+        fs::remove_file(&key_file).expect("remove key file");
+
+        let error = store
+            .load("svc", "acct")
+            .expect_err("missing key must fail");
+        assert!(
+            error.message().contains("is missing"),
+            "unexpected error: {error}"
+        );
+        Ok(())
+    }
+}
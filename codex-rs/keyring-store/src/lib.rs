@@ -5,6 +5,15 @@ use std::fmt;
 use std::fmt::Debug;
 use tracing::trace;
 
+mod encrypted_file;
+
+pub use encrypted_file::CREDENTIALS_KEY_FILE_ENV_VAR;
+pub use encrypted_file::CREDENTIALS_PASSPHRASE_ENV_VAR;
+pub use encrypted_file::ENCRYPTED_CREDENTIALS_FILENAME;
+pub use encrypted_file::ENCRYPTED_CREDENTIALS_KEY_FILENAME;
+pub use encrypted_file::EncryptedFileKeyringStore;
+pub use encrypted_file::EncryptionKey;
+
 #[derive(Debug)]
 pub enum CredentialStoreError {
     Other(KeyringError),
@@ -296,7 +296,9 @@ async fn run_codex_tool_session_inner(
                     EventMsg::SessionConfigured(_) => {
                         tracing::error!("unexpected SessionConfigured event");
                     }
-                    EventMsg::ThreadNameUpdated(_) => {
+                    EventMsg::ThreadNameUpdated(_)
+                    | EventMsg::PinnedMessagesUpdated(_)
+                    | EventMsg::CollaborationModeUpdated(_) => {
                         // Ignore session metadata updates in MCP tool runner.
                     }
                     EventMsg::AgentMessageDelta(_) => {
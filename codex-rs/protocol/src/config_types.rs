@@ -198,6 +198,11 @@ pub enum ModeKind {
 pub struct CollaborationMode {
     pub mode: ModeKind,
     pub settings: Settings,
+    /// Name of the user-defined mode (from `[collaboration_modes.<name>]` in
+    /// `config.toml`) this collaboration mode was built from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub custom_mode: Option<String>,
 }
 
 impl CollaborationMode {
@@ -238,6 +243,7 @@ impl CollaborationMode {
         CollaborationMode {
             mode: self.mode,
             settings: updated_settings,
+            custom_mode: self.custom_mode.clone(),
         }
     }
 
@@ -246,6 +252,8 @@ impl CollaborationMode {
     /// the corresponding fields, while `None` values will preserve the original values.
     ///
     /// The `name` field in the mask is ignored as it's metadata for the mask itself.
+    /// A mask that sets `mode` also replaces `custom_mode`, so switching to a
+    /// built-in preset leaves any user-defined mode.
     pub fn apply_mask(&self, mask: &CollaborationModeMask) -> Self {
         let settings = self.settings_ref();
         CollaborationMode {
@@ -258,6 +266,11 @@ impl CollaborationMode {
                     .clone()
                     .unwrap_or_else(|| settings.developer_instructions.clone()),
             },
+            custom_mode: if mask.mode.is_some() {
+                mask.custom_mode.clone()
+            } else {
+                self.custom_mode.clone()
+            },
         }
     }
 }
@@ -279,6 +292,10 @@ pub struct CollaborationModeMask {
     pub model: Option<String>,
     pub reasoning_effort: Option<Option<ReasoningEffort>>,
     pub developer_instructions: Option<Option<String>>,
+    /// Name of the user-defined mode this mask selects, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub custom_mode: Option<String>,
 }
 
 #[cfg(test)]
@@ -295,6 +312,7 @@ mod tests {
                 reasoning_effort: Some(ReasoningEffort::High),
                 developer_instructions: Some("stay focused".to_string()),
             },
+            custom_mode: None,
         };
         let mask = CollaborationModeMask {
             name: "Clear".to_string(),
@@ -302,6 +320,7 @@ mod tests {
             model: None,
             reasoning_effort: Some(None),
             developer_instructions: Some(None),
+            custom_mode: None,
         };
 
         let expected = CollaborationMode {
@@ -311,6 +330,7 @@ mod tests {
                 reasoning_effort: None,
                 developer_instructions: None,
             },
+            custom_mode: None,
         };
         assert_eq!(expected, mode.apply_mask(&mask));
     }
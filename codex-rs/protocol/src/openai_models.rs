@@ -204,6 +204,15 @@ impl TruncationPolicyConfig {
     }
 }
 
+/// BPE vocabulary a model's tokens are drawn from, used for local token
+/// accounting.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerEncoding {
+    O200kBase,
+    Cl100kBase,
+}
+
 /// Semantic version triple encoded as an array in JSON (e.g. [0, 62, 0]).
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS, JsonSchema)]
 pub struct ClientVersion(pub i32, pub i32, pub i32);
@@ -237,6 +246,10 @@ pub struct ModelInfo {
     pub supports_parallel_tool_calls: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub context_window: Option<i64>,
+    /// Encoding used to count tokens locally. When omitted, core estimates
+    /// token counts from byte length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<TokenizerEncoding>,
     /// Token threshold for automatic compaction. When omitted, core derives it
     /// from `context_window` (90%).
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -501,6 +514,7 @@ mod tests {
             truncation_policy: TruncationPolicyConfig::bytes(10_000),
             supports_parallel_tool_calls: false,
             context_window: None,
+            tokenizer: None,
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
             experimental_supported_tools: vec![],
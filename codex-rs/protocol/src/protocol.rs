@@ -297,6 +297,16 @@ pub enum Op {
     /// responsible for undoing any edits on disk.
     ThreadRollback { num_turns: u32 },
 
+    /// Pin a user or agent message of this session, identified by the id of
+    /// its [`crate::items::TurnItem`], so it is carried verbatim into the
+    /// history that replaces the thread after compaction. Replies with
+    /// [`EventMsg::PinnedMessagesUpdated`].
+    PinMessage { item_id: String },
+
+    /// Remove a pinned message by item id. Replies with
+    /// [`EventMsg::PinnedMessagesUpdated`].
+    UnpinMessage { id: String },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -758,6 +768,12 @@ pub enum EventMsg {
     /// Updated session metadata (e.g., thread name changes).
     ThreadNameUpdated(ThreadNameUpdatedEvent),
 
+    /// The set of messages pinned across compaction changed.
+    PinnedMessagesUpdated(PinnedMessagesUpdatedEvent),
+
+    /// The session switched to a different collaboration mode.
+    CollaborationModeUpdated(CollaborationModeUpdatedEvent),
+
     /// Incremental MCP startup progress updates.
     McpStartupUpdate(McpStartupUpdateEvent),
 
@@ -1798,6 +1814,21 @@ pub enum ReviewTarget {
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
     Custom { instructions: String },
+
+    /// Review the changes between two commits, as in `git diff base..head`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    CommitRange { base: String, head: String },
+
+    /// Review the changes described by a unified diff or patch file on disk.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PatchFile { path: PathBuf },
+
+    /// Review the current contents of the given files or directories.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Paths { paths: Vec<PathBuf> },
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
@@ -1807,6 +1838,15 @@ pub struct ReviewRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub user_facing_hint: Option<String>,
+    /// When true, findings that do not touch a line changed by the target are
+    /// dropped from the review output.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_changed_lines: bool,
+    /// Baseline file of accepted findings. Findings that match an entry are
+    /// dropped from the review output so that only new ones are reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub baseline: Option<PathBuf>,
 }
 
 /// Structured review result produced by a child review session.
@@ -1865,6 +1905,23 @@ pub enum ExecCommandSource {
     UnifiedExecInteraction,
 }
 
+/// How a command ended, independent of its exit code.
+#[derive(
+    Debug, Clone, Copy, Display, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecCommandStatus {
+    /// The command ran and exited; `exit_code` tells whether it succeeded.
+    #[default]
+    Exited,
+    /// The command was killed after running past its timeout.
+    TimedOut,
+    /// The command never ran because the user or the approval policy rejected it.
+    Declined,
+    /// The command failed to start or was aborted before it exited.
+    Failed,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecCommandBeginEvent {
     /// Identifier so this can be paired with the ExecCommandEnd event.
@@ -1926,6 +1983,9 @@ pub struct ExecCommandEndEvent {
     pub duration: Duration,
     /// Formatted output from the command, as seen by the model.
     pub formatted_output: String,
+    /// How the command ended. Defaults to Exited for backward compatibility.
+    #[serde(default)]
+    pub status: ExecCommandStatus,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1936,7 +1996,7 @@ pub struct ViewImageToolCallEvent {
     pub path: PathBuf,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecOutputStream {
     Stdout,
@@ -2300,6 +2360,27 @@ pub struct ThreadNameUpdatedEvent {
     pub thread_name: Option<String>,
 }
 
+/// A message that survives compaction verbatim.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct PinnedMessage {
+    /// Id of the pinned user or agent message item.
+    pub id: String,
+    /// Text of the message at the time it was pinned.
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PinnedMessagesUpdatedEvent {
+    /// Every message currently pinned on the thread, oldest first.
+    pub pinned_messages: Vec<PinnedMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CollaborationModeUpdatedEvent {
+    /// The collaboration mode now in effect.
+    pub collaboration_mode: CollaborationMode,
+}
+
 /// User's decision in response to an ExecApprovalRequest.
 #[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq, Display, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
@@ -2318,6 +2399,10 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this request and wants matching future requests in
+    /// the same repository to be approved automatically, across sessions.
+    ApprovedForRepo,
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -2336,6 +2421,7 @@ impl ReviewDecision {
             ReviewDecision::Approved => "approved",
             ReviewDecision::ApprovedExecpolicyAmendment { .. } => "approved_with_amendment",
             ReviewDecision::ApprovedForSession => "approved_for_session",
+            ReviewDecision::ApprovedForRepo => "approved_for_repo",
             ReviewDecision::Denied => "denied",
             ReviewDecision::Abort => "abort",
         }
@@ -2349,6 +2435,14 @@ pub enum FileChange {
     Add {
         content: String,
     },
+    /// A binary file added from base64 patch content. Only its size is
+    /// reported.
+    AddBinary {
+        size_bytes: usize,
+    },
+    AddSymlink {
+        target: PathBuf,
+    },
     Delete {
         content: String,
     },
@@ -2356,6 +2450,10 @@ pub enum FileChange {
         unified_diff: String,
         move_path: Option<PathBuf>,
     },
+    /// Only the executable bit changes; the contents are left untouched.
+    ModeChange {
+        executable: bool,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -15,6 +15,9 @@
 //! file because we don't use the "vendored" feature.
 //!
 //! If the keyring is not available or fails, we fall back to CODEX_HOME/.credentials.json which is consistent with other coding CLI agents.
+//!
+//! Machines without a keyring can instead opt into the encrypted store, which keeps the tokens in the age-encrypted
+//! CODEX_HOME/credentials.age shared with CLI auth.
 
 use anyhow::Context;
 use anyhow::Error;
@@ -44,6 +47,7 @@ use std::time::UNIX_EPOCH;
 use tracing::warn;
 
 use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::EncryptedFileKeyringStore;
 use codex_keyring_store::KeyringStore;
 use rmcp::transport::auth::AuthorizationManager;
 use tokio::sync::Mutex;
@@ -76,6 +80,9 @@ pub enum OAuthCredentialsStoreMode {
     File,
     /// Keyring when available, otherwise fail.
     Keyring,
+    /// CODEX_HOME/credentials.age, encrypted with a passphrase or key file.
+    /// For machines without an OS keyring.
+    Encrypted,
 }
 
 /// Wrap OAuthTokenResponse to allow for partial equality comparison.
@@ -106,9 +113,17 @@ pub(crate) fn load_oauth_tokens(
             load_oauth_tokens_from_keyring(&keyring_store, server_name, url)
                 .with_context(|| "failed to read OAuth tokens from keyring".to_string())
         }
+        OAuthCredentialsStoreMode::Encrypted => {
+            load_oauth_tokens_from_keyring(&encrypted_keyring_store()?, server_name, url)
+                .context("failed to read OAuth tokens from encrypted credentials file")
+        }
     }
 }
 
+fn encrypted_keyring_store() -> Result<EncryptedFileKeyringStore> {
+    Ok(EncryptedFileKeyringStore::from_env(&find_codex_home()?))
+}
+
 pub(crate) fn has_oauth_tokens(
     server_name: &str,
     url: &str,
@@ -183,6 +198,9 @@ pub fn save_oauth_tokens(
         OAuthCredentialsStoreMode::Keyring => {
             save_oauth_tokens_with_keyring(&keyring_store, server_name, tokens)
         }
+        OAuthCredentialsStoreMode::Encrypted => {
+            save_oauth_tokens_with_keyring(&encrypted_keyring_store()?, server_name, tokens)
+        }
     }
 }
 
@@ -233,8 +251,90 @@ pub fn delete_oauth_tokens(
     url: &str,
     store_mode: OAuthCredentialsStoreMode,
 ) -> Result<bool> {
-    let keyring_store = DefaultKeyringStore;
-    delete_oauth_tokens_from_keyring_and_file(&keyring_store, store_mode, server_name, url)
+    match store_mode {
+        OAuthCredentialsStoreMode::Encrypted => delete_oauth_tokens_from_keyring_and_file(
+            &encrypted_keyring_store()?,
+            store_mode,
+            server_name,
+            url,
+        ),
+        _ => delete_oauth_tokens_from_keyring_and_file(
+            &DefaultKeyringStore,
+            store_mode,
+            server_name,
+            url,
+        ),
+    }
+}
+
+/// Moves the stored tokens for one server from the `from` backend to `to`.
+/// Returns `Ok(false)` when `from` holds no tokens for the server.
+pub fn migrate_oauth_tokens(
+    server_name: &str,
+    url: &str,
+    from: OAuthCredentialsStoreMode,
+    to: OAuthCredentialsStoreMode,
+) -> Result<bool> {
+    if from == to {
+        return Ok(false);
+    }
+    let Some(tokens) = load_oauth_tokens(server_name, url, from)? else {
+        return Ok(false);
+    };
+    save_oauth_tokens(server_name, &tokens, to)?;
+    delete_migrated_oauth_tokens(server_name, url, from, to)?;
+    Ok(true)
+}
+
+/// Removes the tokens `from` held once they have been saved to `to`.
+/// `Keyring` and `Auto` share a keyring entry and `File` and `Auto` share the
+/// fallback file, so whatever `to` now stores in those places is kept.
+fn delete_migrated_oauth_tokens(
+    server_name: &str,
+    url: &str,
+    from: OAuthCredentialsStoreMode,
+    to: OAuthCredentialsStoreMode,
+) -> Result<()> {
+    let default_keyring = |mode: OAuthCredentialsStoreMode| {
+        matches!(
+            mode,
+            OAuthCredentialsStoreMode::Keyring | OAuthCredentialsStoreMode::Auto
+        )
+    };
+    let fallback_file = |mode: OAuthCredentialsStoreMode| {
+        matches!(
+            mode,
+            OAuthCredentialsStoreMode::File | OAuthCredentialsStoreMode::Auto
+        )
+    };
+    let key = compute_store_key(server_name, url)?;
+    let deleted = match from {
+        OAuthCredentialsStoreMode::Encrypted => {
+            encrypted_keyring_store()?.delete(KEYRING_SERVICE, &key)
+        }
+        _ if default_keyring(from) && !default_keyring(to) => {
+            DefaultKeyringStore.delete(KEYRING_SERVICE, &key)
+        }
+        _ => Ok(false),
+    };
+    match deleted {
+        Ok(_) => {}
+        // `Auto` may have been reading the fallback file because the keyring
+        // is unavailable.
+        Err(error) if from == OAuthCredentialsStoreMode::Auto => {
+            warn!(
+                "failed to delete OAuth tokens from keyring: {}",
+                error.message()
+            );
+        }
+        Err(error) => {
+            return Err(error.into_error()).context("failed to delete OAuth tokens from keyring");
+        }
+    }
+    if fallback_file(from) && !fallback_file(to) {
+        delete_oauth_tokens_from_file(&key)?;
+    }
+    Ok(())
 }
 
 fn delete_oauth_tokens_from_keyring_and_file<K: KeyringStore>(
@@ -251,7 +351,9 @@ fn delete_oauth_tokens_from_keyring_and_file<K: KeyringStore>(
             let message = error.message();
             warn!("failed to delete OAuth tokens from keyring: {message}");
             match store_mode {
-                OAuthCredentialsStoreMode::Auto | OAuthCredentialsStoreMode::Keyring => {
+                OAuthCredentialsStoreMode::Auto
+                | OAuthCredentialsStoreMode::Keyring
+                | OAuthCredentialsStoreMode::Encrypted => {
                     return Err(error.into_error())
                         .context("failed to delete OAuth tokens from keyring");
                 }
@@ -818,6 +920,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn migrate_oauth_tokens_moves_tokens_out_of_encrypted_store() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let tokens = sample_tokens();
+        super::save_oauth_tokens(
+            &tokens.server_name,
+            &tokens,
+            OAuthCredentialsStoreMode::Encrypted,
+        )?;
+        assert!(!super::fallback_file_path()?.exists());
+
+        let migrated = super::migrate_oauth_tokens(
+            &tokens.server_name,
+            &tokens.url,
+            OAuthCredentialsStoreMode::Encrypted,
+            OAuthCredentialsStoreMode::File,
+        )?;
+        assert!(migrated);
+        assert_eq!(
+            super::load_oauth_tokens(
+                &tokens.server_name,
+                &tokens.url,
+                OAuthCredentialsStoreMode::Encrypted,
+            )?,
+            None
+        );
+        let loaded = super::load_oauth_tokens_from_file(&tokens.server_name, &tokens.url)?
+            .expect("tokens should be in the fallback file");
+        assert_eq!(loaded.client_id, tokens.client_id);
+
+        assert!(!super::migrate_oauth_tokens(
+            &tokens.server_name,
+            &tokens.url,
+            OAuthCredentialsStoreMode::Encrypted,
+            OAuthCredentialsStoreMode::File,
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_oauth_migration_keeps_the_source_tokens() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let tokens = sample_tokens();
+        super::save_oauth_tokens(
+            &tokens.server_name,
+            &tokens,
+            OAuthCredentialsStoreMode::Encrypted,
+        )?;
+        // A directory in place of the fallback file makes saving to it fail.
+        fs::create_dir(super::fallback_file_path()?)?;
+
+        assert!(
+            super::migrate_oauth_tokens(
+                &tokens.server_name,
+                &tokens.url,
+                OAuthCredentialsStoreMode::Encrypted,
+                OAuthCredentialsStoreMode::File,
+            )
+            .is_err()
+        );
+        let kept = super::load_oauth_tokens(
+            &tokens.server_name,
+            &tokens.url,
+            OAuthCredentialsStoreMode::Encrypted,
+        )?
+        .expect("tokens should stay in the encrypted store");
+        assert_eq!(kept.client_id, tokens.client_id);
+        Ok(())
+    }
+
     #[test]
     fn refresh_expires_in_from_timestamp_restores_future_durations() {
         let mut tokens = sample_tokens();
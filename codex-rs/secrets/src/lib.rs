@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::EncryptedFileKeyringStore;
 use codex_keyring_store::KeyringStore;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -83,11 +84,75 @@ pub enum SecretsBackendKind {
     Local,
 }
 
+/// Where the local backend keeps the key that encrypts its secrets file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsKeyStore {
+    /// The OS keyring.
+    #[default]
+    Keyring,
+    /// CODEX_HOME/credentials.age, encrypted with a passphrase or key file.
+    /// For machines without an OS keyring.
+    Encrypted,
+}
+
+impl SecretsKeyStore {
+    pub fn keyring_store(self, codex_home: &Path) -> Arc<dyn KeyringStore> {
+        match self {
+            Self::Keyring => Arc::new(DefaultKeyringStore),
+            Self::Encrypted => Arc::new(EncryptedFileKeyringStore::from_env(codex_home)),
+        }
+    }
+}
+
+/// Moves the key of the local secrets file from the `from` store to `to`.
+/// The secrets file itself is unchanged. Returns `Ok(false)` when `from`
+/// holds no key.
+pub fn migrate_secrets_key(
+    codex_home: &Path,
+    from: SecretsKeyStore,
+    to: SecretsKeyStore,
+) -> Result<bool> {
+    if from == to {
+        return Ok(false);
+    }
+    migrate_secrets_key_between(
+        codex_home,
+        from.keyring_store(codex_home).as_ref(),
+        to.keyring_store(codex_home).as_ref(),
+    )
+}
+
+fn migrate_secrets_key_between(
+    codex_home: &Path,
+    from: &dyn KeyringStore,
+    to: &dyn KeyringStore,
+) -> Result<bool> {
+    let account = compute_keyring_account(codex_home);
+    let Some(key) = from
+        .load(keyring_service(), &account)
+        .map_err(|err| anyhow::anyhow!("failed to load secrets key: {}", err.message()))?
+    else {
+        return Ok(false);
+    };
+    to.save(keyring_service(), &account, &key)
+        .map_err(|err| anyhow::anyhow!("failed to save secrets key: {}", err.message()))?;
+    from.delete(keyring_service(), &account)
+        .map_err(|err| anyhow::anyhow!("failed to delete secrets key: {}", err.message()))?;
+    Ok(true)
+}
+
 pub trait SecretsBackend: Send + Sync {
     fn set(&self, scope: &SecretScope, name: &SecretName, value: &str) -> Result<()>;
     fn get(&self, scope: &SecretScope, name: &SecretName) -> Result<Option<String>>;
     fn delete(&self, scope: &SecretScope, name: &SecretName) -> Result<bool>;
     fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>>;
+
+    /// Looks up several secrets in one scope. Backends that have to decrypt
+    /// their whole store on every read should override this.
+    fn get_many(&self, scope: &SecretScope, names: &[SecretName]) -> Result<Vec<Option<String>>> {
+        names.iter().map(|name| self.get(scope, name)).collect()
+    }
 }
 
 #[derive(Clone)]
@@ -134,6 +199,39 @@ impl SecretsManager {
     pub fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>> {
         self.backend.list(scope_filter)
     }
+
+    /// Resolves `names` in `scope`, falling back to the global scope for any
+    /// name that is not set there. Names without a value are omitted.
+    pub fn resolve(
+        &self,
+        scope: &SecretScope,
+        names: &[SecretName],
+    ) -> Result<Vec<(SecretName, String)>> {
+        let mut values = self.backend.get_many(scope, names)?;
+        if *scope != SecretScope::Global {
+            let missing: Vec<SecretName> = names
+                .iter()
+                .zip(&values)
+                .filter(|(_, value)| value.is_none())
+                .map(|(name, _)| name.clone())
+                .collect();
+            if !missing.is_empty() {
+                let mut global = self
+                    .backend
+                    .get_many(&SecretScope::Global, &missing)?
+                    .into_iter();
+                for value in values.iter_mut().filter(|value| value.is_none()) {
+                    *value = global.next().flatten();
+                }
+            }
+        }
+        Ok(names
+            .iter()
+            .cloned()
+            .zip(values)
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .collect())
+    }
 }
 
 pub fn environment_id_from_cwd(cwd: &Path) -> String {
@@ -240,4 +338,71 @@ mod tests {
         assert_eq!(manager.get(&scope, &name)?, None);
         Ok(())
     }
+
+    #[test]
+    fn resolve_prefers_environment_scope_and_falls_back_to_global() -> Result<()> {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let keyring = Arc::new(MockKeyringStore::default());
+        let manager = SecretsManager::new_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            SecretsBackendKind::Local,
+            keyring,
+        );
+        let env_scope = SecretScope::environment("repo")?;
+        let github = SecretName::new("GITHUB_TOKEN")?;
+        let npm = SecretName::new("NPM_TOKEN")?;
+        let missing = SecretName::new("MISSING")?;
+
+        manager.set(&SecretScope::Global, &github, "global-github")?;
+        manager.set(&env_scope, &github, "repo-github")?;
+        manager.set(&SecretScope::Global, &npm, "global-npm")?;
+
+        let resolved = manager.resolve(&env_scope, &[github.clone(), missing, npm.clone()])?;
+        assert_eq!(
+            resolved,
+            vec![
+                (github, "repo-github".to_string()),
+                (npm, "global-npm".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrated_key_still_decrypts_existing_secrets() -> Result<()> {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let from = Arc::new(MockKeyringStore::default());
+        let to = Arc::new(MockKeyringStore::default());
+        let name = SecretName::new("GITHUB_TOKEN")?;
+        SecretsManager::new_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            SecretsBackendKind::Local,
+            from.clone(),
+        )
+        .set(&SecretScope::Global, &name, "token-1")?;
+
+        assert!(migrate_secrets_key_between(
+            codex_home.path(),
+            from.as_ref(),
+            to.as_ref()
+        )?);
+        let account = compute_keyring_account(codex_home.path());
+        assert!(!from.contains(&account));
+        assert!(!migrate_secrets_key_between(
+            codex_home.path(),
+            from.as_ref(),
+            to.as_ref()
+        )?);
+
+        let manager = SecretsManager::new_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            SecretsBackendKind::Local,
+            to,
+        );
+        assert_eq!(
+            manager.get(&SecretScope::Global, &name)?,
+            Some("token-1".to_string())
+        );
+        Ok(())
+    }
 }
@@ -196,6 +196,15 @@ impl SecretsBackend for LocalSecretsBackend {
     fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>> {
         LocalSecretsBackend::list(self, scope_filter)
     }
+
+    fn get_many(&self, scope: &SecretScope, names: &[SecretName]) -> Result<Vec<Option<String>>> {
+        // Decrypt once rather than once per name.
+        let file = self.load_file()?;
+        Ok(names
+            .iter()
+            .map(|name| file.secrets.get(&scope.canonical_key(name)).cloned())
+            .collect())
+    }
 }
 
 fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
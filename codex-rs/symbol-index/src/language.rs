@@ -0,0 +1,195 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use tree_sitter::Query;
+
+/// Languages whose definitions are indexed, detected from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    Python,
+    Go,
+    JavaScript,
+    TypeScript,
+    Tsx,
+}
+
+/// A node that gives the definitions nested inside it a container name, such
+/// as an `impl` block or a class.
+pub(crate) struct Container {
+    pub(crate) kind: &'static str,
+    /// Field holding the container's name.
+    pub(crate) name_field: &'static str,
+    /// Whether functions directly inside the container are methods.
+    pub(crate) holds_methods: bool,
+}
+
+const RUST_QUERY: &str = r#"
+(function_item name: (identifier) @name) @definition.function
+(function_signature_item name: (identifier) @name) @definition.function
+(struct_item name: (type_identifier) @name) @definition.struct
+(union_item name: (type_identifier) @name) @definition.struct
+(enum_item name: (type_identifier) @name) @definition.enum
+(trait_item name: (type_identifier) @name) @definition.trait
+(type_item name: (type_identifier) @name) @definition.type
+(mod_item name: (identifier) @name) @definition.module
+(const_item name: (identifier) @name) @definition.constant
+(static_item name: (identifier) @name) @definition.constant
+(macro_definition name: (identifier) @name) @definition.macro
+"#;
+
+const PYTHON_QUERY: &str = r#"
+(class_definition name: (identifier) @name) @definition.class
+(function_definition name: (identifier) @name) @definition.function
+(module
+  (expression_statement
+    (assignment left: (identifier) @name) @definition.constant)
+  (#match? @name "^[A-Z][A-Z0-9_]*$"))
+"#;
+
+const GO_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @definition.function
+(method_declaration name: (field_identifier) @name) @definition.method
+(type_spec name: (type_identifier) @name) @definition.type
+(const_spec name: (identifier) @name) @definition.constant
+"#;
+
+const JAVASCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @definition.function
+(generator_function_declaration name: (identifier) @name) @definition.function
+(class_declaration name: (identifier) @name) @definition.class
+((method_definition name: (property_identifier) @name) @definition.method
+  (#not-eq? @name "constructor"))
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.function
+"#;
+
+const TYPESCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @definition.function
+(generator_function_declaration name: (identifier) @name) @definition.function
+(function_signature name: (identifier) @name) @definition.function
+(class_declaration name: (type_identifier) @name) @definition.class
+(abstract_class_declaration name: (type_identifier) @name) @definition.class
+((method_definition name: (property_identifier) @name) @definition.method
+  (#not-eq? @name "constructor"))
+(method_signature name: (property_identifier) @name) @definition.method
+(abstract_method_signature name: (property_identifier) @name) @definition.method
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.function
+(interface_declaration name: (type_identifier) @name) @definition.interface
+(type_alias_declaration name: (type_identifier) @name) @definition.type
+(enum_declaration name: (identifier) @name) @definition.enum
+(internal_module name: (identifier) @name) @definition.module
+"#;
+
+const RUST_CONTAINERS: &[Container] = &[
+    Container {
+        kind: "impl_item",
+        name_field: "type",
+        holds_methods: true,
+    },
+    Container {
+        kind: "trait_item",
+        name_field: "name",
+        holds_methods: true,
+    },
+    Container {
+        kind: "mod_item",
+        name_field: "name",
+        holds_methods: false,
+    },
+];
+
+const PYTHON_CONTAINERS: &[Container] = &[Container {
+    kind: "class_definition",
+    name_field: "name",
+    holds_methods: true,
+}];
+
+const JAVASCRIPT_CONTAINERS: &[Container] = &[
+    Container {
+        kind: "class_declaration",
+        name_field: "name",
+        holds_methods: true,
+    },
+    Container {
+        kind: "abstract_class_declaration",
+        name_field: "name",
+        holds_methods: true,
+    },
+    Container {
+        kind: "interface_declaration",
+        name_field: "name",
+        holds_methods: true,
+    },
+    Container {
+        kind: "internal_module",
+        name_field: "name",
+        holds_methods: false,
+    },
+];
+
+const LANGUAGE_COUNT: usize = 6;
+
+impl Language {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "go" => Some(Self::Go),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        }
+    }
+
+    /// Definition query for the language, compiled on first use. `None` if
+    /// the query does not compile against the grammar.
+    pub(crate) fn query(self) -> Option<&'static Query> {
+        static QUERIES: [OnceLock<Option<Query>>; LANGUAGE_COUNT] =
+            [const { OnceLock::new() }; LANGUAGE_COUNT];
+        QUERIES[self as usize]
+            .get_or_init(|| match Query::new(&self.grammar(), self.query_source()) {
+                Ok(query) => Some(query),
+                Err(err) => {
+                    tracing::warn!("failed to compile {self:?} symbol query: {err}");
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    pub(crate) fn containers(self) -> &'static [Container] {
+        match self {
+            Self::Rust => RUST_CONTAINERS,
+            Self::Python => PYTHON_CONTAINERS,
+            Self::Go => &[],
+            Self::JavaScript | Self::TypeScript | Self::Tsx => JAVASCRIPT_CONTAINERS,
+        }
+    }
+
+    fn query_source(self) -> &'static str {
+        match self {
+            Self::Rust => RUST_QUERY,
+            Self::Python => PYTHON_QUERY,
+            Self::Go => GO_QUERY,
+            Self::JavaScript => JAVASCRIPT_QUERY,
+            Self::TypeScript | Self::Tsx => TYPESCRIPT_QUERY,
+        }
+    }
+}
@@ -0,0 +1,841 @@
+//! Symbol index for a workspace.
+//!
+//! Source files are parsed with tree-sitter and their definitions (functions,
+//! methods, types, traits, modules and constants) are collected into a table
+//! that can be searched by name. [`SymbolIndex`] is the synchronous table;
+//! [`SharedSymbolIndex`] builds one on a background thread and keeps it up to
+//! date with a file watcher.
+
+mod language;
+mod shared;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::fs::Metadata;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use ignore::Match;
+use ignore::WalkBuilder;
+use ignore::gitignore::Gitignore;
+use ignore::gitignore::GitignoreBuilder;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+use tree_sitter::QueryCursor;
+use tree_sitter::StreamingIterator;
+
+pub use language::Language;
+pub use shared::SharedSymbolIndex;
+pub use shared::SymbolIndexes;
+
+/// Stop indexing after this many files so huge trees stay responsive.
+pub const MAX_INDEXED_FILES: usize = 50_000;
+
+/// Larger files are usually generated or vendored and are skipped.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Files are parsed on one thread below this count.
+const PARALLEL_PARSE_THRESHOLD: usize = 64;
+const MAX_PARSE_THREADS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    Type,
+    Module,
+    Constant,
+    Macro,
+}
+
+impl SymbolKind {
+    pub const ALL: &[SymbolKind] = &[
+        SymbolKind::Function,
+        SymbolKind::Method,
+        SymbolKind::Class,
+        SymbolKind::Struct,
+        SymbolKind::Enum,
+        SymbolKind::Interface,
+        SymbolKind::Trait,
+        SymbolKind::Type,
+        SymbolKind::Module,
+        SymbolKind::Constant,
+        SymbolKind::Macro,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Class => "class",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Type => "type",
+            SymbolKind::Module => "module",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Macro => "macro",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str() == value)
+    }
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A definition found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Path of the file, relative to the index root.
+    pub path: PathBuf,
+    /// 1-based line where the definition starts.
+    pub line: usize,
+    /// Enclosing type, trait, class or module, if any.
+    pub container: Option<String>,
+}
+
+impl Symbol {
+    /// Name prefixed with its container, e.g. `Config::load` or `Parser.parse`.
+    pub fn qualified_name(&self) -> String {
+        match &self.container {
+            Some(container) if Language::from_path(&self.path) == Some(Language::Rust) => {
+                format!("{container}::{}", self.name)
+            }
+            Some(container) => format!("{container}.{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Extracts the definitions in `source`, using the extension of `path` to
+/// pick the grammar. Returns nothing for unsupported files.
+pub fn extract_symbols(path: &Path, source: &str) -> Vec<Symbol> {
+    let Some(language) = Language::from_path(path) else {
+        return Vec::new();
+    };
+    let Some(query) = language.query() else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if let Err(err) = parser.set_language(&language.grammar()) {
+        tracing::warn!("failed to load {language:?} grammar: {err}");
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let source = source.as_bytes();
+    let capture_names = query.capture_names();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source);
+    let mut symbols = Vec::new();
+    while let Some(query_match) = matches.next() {
+        let mut name = None;
+        let mut definition = None;
+        for capture in query_match.captures {
+            let capture_name = capture_names[capture.index as usize];
+            if capture_name == "name" {
+                name = Some(capture.node);
+            } else if let Some(kind) = capture_name
+                .strip_prefix("definition.")
+                .and_then(SymbolKind::parse)
+            {
+                definition = Some((capture.node, kind));
+            }
+        }
+        let (Some(name), Some((node, kind))) = (name, definition) else {
+            continue;
+        };
+        let Ok(name) = name.utf8_text(source) else {
+            continue;
+        };
+        let (kind, container) = classify(language, node, kind, source);
+        symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            path: path.to_path_buf(),
+            line: node.start_position().row + 1,
+            container,
+        });
+    }
+    symbols
+}
+
+/// Refines the kind captured by the query and finds the enclosing container.
+fn classify(
+    language: Language,
+    node: Node<'_>,
+    kind: SymbolKind,
+    source: &[u8],
+) -> (SymbolKind, Option<String>) {
+    match node.kind() {
+        // Go methods name their type in the receiver instead of being nested in it.
+        "method_declaration" => {
+            let container = node
+                .child_by_field_name("receiver")
+                .and_then(|receiver| first_descendant_of_kind(receiver, "type_identifier"))
+                .and_then(|ty| ty.utf8_text(source).ok())
+                .map(str::to_string);
+            return (kind, container);
+        }
+        "type_spec" => {
+            let kind = match node.child_by_field_name("type").map(|ty| ty.kind()) {
+                Some("struct_type") => SymbolKind::Struct,
+                Some("interface_type") => SymbolKind::Interface,
+                _ => kind,
+            };
+            return (kind, None);
+        }
+        _ => {}
+    }
+
+    let containers = language.containers();
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        // Definitions local to a function body are not members of anything.
+        if is_function_scope(ancestor.kind()) {
+            return (kind, None);
+        }
+        if let Some(container) = containers.iter().find(|c| c.kind == ancestor.kind()) {
+            let name = ancestor
+                .child_by_field_name(container.name_field)
+                .and_then(|name| name.utf8_text(source).ok())
+                .map(base_type_name);
+            let kind = if kind == SymbolKind::Function && container.holds_methods {
+                SymbolKind::Method
+            } else {
+                kind
+            };
+            return (kind, name);
+        }
+        current = ancestor.parent();
+    }
+    (kind, None)
+}
+
+fn is_function_scope(kind: &str) -> bool {
+    matches!(
+        kind,
+        "function_item"
+            | "closure_expression"
+            | "function_definition"
+            | "lambda"
+            | "function_declaration"
+            | "generator_function_declaration"
+            | "function_expression"
+            | "arrow_function"
+            | "method_definition"
+            | "method_declaration"
+    )
+}
+
+fn first_descendant_of_kind<'tree>(node: Node<'tree>, kind: &str) -> Option<Node<'tree>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find_map(|child| first_descendant_of_kind(child, kind))
+}
+
+/// `Foo<T>` -> `Foo`, so `impl<T> Trait for Foo<T>` members group under `Foo`.
+fn base_type_name(type_text: &str) -> String {
+    type_text
+        .split('<')
+        .next()
+        .unwrap_or(type_text)
+        .trim()
+        .to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl From<&Metadata> for FileStamp {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IndexedFile {
+    stamp: FileStamp,
+    symbols: Vec<Symbol>,
+}
+
+/// Symbol table for the supported source files below a root directory.
+///
+/// The walk honors `.gitignore` and skips hidden files, like `rg`.
+#[derive(Debug)]
+pub struct SymbolIndex {
+    root: PathBuf,
+    /// Keyed by path relative to `root`.
+    files: HashMap<PathBuf, IndexedFile>,
+}
+
+impl SymbolIndex {
+    /// Creates an empty index; call [`SymbolIndex::refresh`] to fill it.
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Walks `root` and indexes every supported file.
+    pub fn build(root: PathBuf) -> Self {
+        let mut index = Self::new(root);
+        index.refresh();
+        index
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        self.files.values().map(|file| file.symbols.len()).sum()
+    }
+
+    /// Walks the tree again, re-parsing files that are new or changed since
+    /// they were last indexed and forgetting files that are gone.
+    pub fn refresh(&mut self) {
+        let mut seen = HashSet::new();
+        let mut stale = Vec::new();
+        for (relative, stamp) in self.walk() {
+            let unchanged = self
+                .files
+                .get(&relative)
+                .is_some_and(|file| file.stamp == stamp);
+            if !unchanged {
+                stale.push((relative.clone(), stamp));
+            }
+            seen.insert(relative);
+        }
+        self.files.retain(|relative, _| seen.contains(relative));
+        for (relative, file) in parse_files(&self.root, stale) {
+            self.files.insert(relative, file);
+        }
+    }
+
+    /// Applies file system changes reported for `paths` (absolute, or
+    /// relative to the root).
+    ///
+    /// Indexed files are re-parsed or forgotten directly. Paths the index
+    /// does not know yet trigger a [`SymbolIndex::refresh`], so new files go
+    /// through the same ignore rules as the initial walk, unless they are
+    /// hidden or gitignored (such as build output under `target/`).
+    pub fn apply_changes(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        let mut needs_refresh = false;
+        let mut ignore_rules = IgnoreRules::new(&self.root);
+        for path in paths {
+            let Some(relative) = self.relative_path(&path) else {
+                continue;
+            };
+            let absolute = self.root.join(&relative);
+            match fs::metadata(&absolute) {
+                Ok(metadata) if metadata.is_file() => {
+                    if self.files.contains_key(&relative) {
+                        self.reindex_file(relative, &metadata);
+                    } else if Language::from_path(&relative).is_some()
+                        && !is_hidden(&relative)
+                        && !ignore_rules.is_ignored(&relative, false)
+                    {
+                        needs_refresh = true;
+                    }
+                }
+                Ok(metadata) if metadata.is_dir() => {
+                    let known = self.files.keys().any(|file| file.starts_with(&relative));
+                    if !known && !is_hidden(&relative) && !ignore_rules.is_ignored(&relative, true)
+                    {
+                        needs_refresh = true;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => {
+                    self.files.retain(|file, _| !file.starts_with(&relative));
+                }
+            }
+        }
+        if needs_refresh {
+            self.refresh();
+        }
+    }
+
+    /// Searches definitions by name.
+    ///
+    /// Exact matches rank first, then case-insensitive exact matches, then
+    /// prefix and substring matches. A query such as `Config::load` or
+    /// `Parser.parse` also requires the container to match.
+    pub fn search(&self, query: &str, kind: Option<SymbolKind>, limit: usize) -> Vec<Symbol> {
+        let (container, name) = split_qualified(query.trim());
+        if name.is_empty() {
+            return Vec::new();
+        }
+        let name_lower = name.to_lowercase();
+        let container_lower = container.map(str::to_lowercase);
+
+        let mut ranked: Vec<(u8, &Symbol)> = self
+            .files
+            .values()
+            .flat_map(|file| &file.symbols)
+            .filter(|symbol| kind.is_none_or(|kind| symbol.kind == kind))
+            .filter(|symbol| match &container_lower {
+                Some(expected) => symbol
+                    .container
+                    .as_ref()
+                    .is_some_and(|container| container.to_lowercase() == *expected),
+                None => true,
+            })
+            .filter_map(|symbol| {
+                match_rank(&symbol.name, name, &name_lower).map(|rank| (rank, symbol))
+            })
+            .collect();
+        ranked.sort_by(|(rank_a, a), (rank_b, b)| {
+            rank_a
+                .cmp(rank_b)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, symbol)| symbol.clone())
+            .collect()
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        if path.is_relative() {
+            return Some(path.to_path_buf());
+        }
+        path.strip_prefix(&self.root).ok().map(Path::to_path_buf)
+    }
+
+    fn reindex_file(&mut self, relative: PathBuf, metadata: &Metadata) {
+        let stamp = FileStamp::from(metadata);
+        if metadata.len() > MAX_FILE_BYTES {
+            self.files.remove(&relative);
+            return;
+        }
+        if self
+            .files
+            .get(&relative)
+            .is_some_and(|file| file.stamp == stamp)
+        {
+            return;
+        }
+        let file = parse_file(&self.root, &relative, stamp);
+        self.files.insert(relative, file);
+    }
+
+    fn walk(&self) -> Vec<(PathBuf, FileStamp)> {
+        let mut files = Vec::new();
+        for entry in WalkBuilder::new(&self.root).build() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_some_and(|ty| ty.is_file())
+                || Language::from_path(entry.path()).is_none()
+            {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() > MAX_FILE_BYTES {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(&self.root) else {
+                continue;
+            };
+            files.push((relative.to_path_buf(), FileStamp::from(&metadata)));
+            if files.len() >= MAX_INDEXED_FILES {
+                tracing::warn!(
+                    "symbol index for {} stopped at {MAX_INDEXED_FILES} files",
+                    self.root.display()
+                );
+                break;
+            }
+        }
+        files
+    }
+}
+
+fn parse_file(root: &Path, relative: &Path, stamp: FileStamp) -> IndexedFile {
+    // Unreadable and non-UTF-8 files are still recorded so they are not
+    // retried until they change.
+    let symbols = fs::read_to_string(root.join(relative))
+        .map(|source| extract_symbols(relative, &source))
+        .unwrap_or_default();
+    IndexedFile { stamp, symbols }
+}
+
+fn parse_files(root: &Path, files: Vec<(PathBuf, FileStamp)>) -> Vec<(PathBuf, IndexedFile)> {
+    let threads = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(MAX_PARSE_THREADS);
+    if threads == 1 || files.len() < PARALLEL_PARSE_THRESHOLD {
+        return files
+            .into_iter()
+            .map(|(relative, stamp)| {
+                let file = parse_file(root, &relative, stamp);
+                (relative, file)
+            })
+            .collect();
+    }
+
+    let chunk_size = files.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        // Spawn every worker before joining any of them.
+        let mut workers = Vec::with_capacity(threads);
+        for chunk in files.chunks(chunk_size) {
+            workers.push(scope.spawn(move || {
+                chunk
+                    .iter()
+                    .map(|(relative, stamp)| (relative.clone(), parse_file(root, relative, *stamp)))
+                    .collect::<Vec<_>>()
+            }));
+        }
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+/// The `.gitignore`, `.ignore` and `.git/info/exclude` rules of a tree, read
+/// per directory on first use.
+struct IgnoreRules {
+    root: PathBuf,
+    dirs: HashMap<PathBuf, Gitignore>,
+}
+
+impl IgnoreRules {
+    fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// Whether `relative` or one of its parent directories is ignored. Rules
+    /// of deeper directories take precedence, as they do for git.
+    fn is_ignored(&mut self, relative: &Path, is_dir: bool) -> bool {
+        let root = &self.root;
+        let absolute = root.join(relative);
+        for dir in relative.ancestors().skip(1) {
+            let dir = root.join(dir);
+            let rules = self
+                .dirs
+                .entry(dir.clone())
+                .or_insert_with(|| load_ignore_rules(root, &dir));
+            match rules.matched_path_or_any_parents(&absolute, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+}
+
+fn load_ignore_rules(root: &Path, dir: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(dir);
+    let mut files = vec![dir.join(".gitignore"), dir.join(".ignore")];
+    if dir == root {
+        files.push(root.join(".git").join("info").join("exclude"));
+    }
+    for file in files.into_iter().filter(|file| file.is_file()) {
+        if let Some(err) = builder.add(&file) {
+            tracing::debug!("failed to read ignore rules {}: {err}", file.display());
+        }
+    }
+    builder.build().unwrap_or_else(|err| {
+        tracing::debug!("invalid ignore rules in {}: {err}", dir.display());
+        Gitignore::empty()
+    })
+}
+
+fn is_hidden(relative: &Path) -> bool {
+    relative
+        .components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// Splits `Container::name` or `Container.name` into its parts.
+fn split_qualified(query: &str) -> (Option<&str>, &str) {
+    if let Some((container, name)) = query.rsplit_once("::") {
+        return (Some(container).filter(|c| !c.is_empty()), name);
+    }
+    if let Some((container, name)) = query.rsplit_once('.') {
+        return (Some(container).filter(|c| !c.is_empty()), name);
+    }
+    (None, query)
+}
+
+fn match_rank(name: &str, query: &str, query_lower: &str) -> Option<u8> {
+    if name == query {
+        return Some(0);
+    }
+    let name_lower = name.to_lowercase();
+    if name_lower == query_lower {
+        Some(1)
+    } else if name_lower.starts_with(query_lower) {
+        Some(2)
+    } else if name_lower.contains(query_lower) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn summary(symbols: &[Symbol]) -> Vec<(String, SymbolKind, usize)> {
+        symbols
+            .iter()
+            .map(|symbol| (symbol.qualified_name(), symbol.kind, symbol.line))
+            .collect()
+    }
+
+    fn names(symbols: &[Symbol]) -> Vec<String> {
+        symbols.iter().map(Symbol::qualified_name).collect()
+    }
+
+    #[test]
+    fn extracts_rust_definitions() {
+        let source = r#"
+pub struct Config<T> {
+    value: T,
+}
+
+impl<T> Config<T> {
+    pub fn load() -> Self {
+        fn helper() {}
+        todo!()
+    }
+}
+
+pub trait Loader {
+    fn load(&self);
+}
+
+mod inner {
+    pub const LIMIT: usize = 3;
+    pub enum Mode { A }
+}
+
+macro_rules! shout { () => {} }
+"#;
+        assert_eq!(
+            summary(&extract_symbols(Path::new("src/lib.rs"), source)),
+            vec![
+                ("Config".to_string(), SymbolKind::Struct, 2),
+                ("Config::load".to_string(), SymbolKind::Method, 7),
+                ("helper".to_string(), SymbolKind::Function, 8),
+                ("Loader".to_string(), SymbolKind::Trait, 13),
+                ("Loader::load".to_string(), SymbolKind::Method, 14),
+                ("inner".to_string(), SymbolKind::Module, 17),
+                ("inner::LIMIT".to_string(), SymbolKind::Constant, 18),
+                ("inner::Mode".to_string(), SymbolKind::Enum, 19),
+                ("shout".to_string(), SymbolKind::Macro, 22),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_python_go_and_typescript_definitions() {
+        let python = r#"
+MAX_RETRIES = 3
+default_name = "x"
+
+class Client:
+    def send(self):
+        pass
+
+def main():
+    pass
+"#;
+        assert_eq!(
+            summary(&extract_symbols(Path::new("client.py"), python)),
+            vec![
+                ("MAX_RETRIES".to_string(), SymbolKind::Constant, 2),
+                ("Client".to_string(), SymbolKind::Class, 5),
+                ("Client.send".to_string(), SymbolKind::Method, 6),
+                ("main".to_string(), SymbolKind::Function, 9),
+            ]
+        );
+
+        let go = r#"package server
+
+type Server struct{}
+
+type Handler interface{}
+
+func (s *Server) Start() {}
+
+func New() *Server { return nil }
+"#;
+        assert_eq!(
+            summary(&extract_symbols(Path::new("server.go"), go)),
+            vec![
+                ("Server".to_string(), SymbolKind::Struct, 3),
+                ("Handler".to_string(), SymbolKind::Interface, 5),
+                ("Server.Start".to_string(), SymbolKind::Method, 7),
+                ("New".to_string(), SymbolKind::Function, 9),
+            ]
+        );
+
+        let typescript = r#"
+export interface Store {
+  get(key: string): string;
+}
+
+export class MemoryStore implements Store {
+  constructor() {}
+  get(key: string): string { return key; }
+}
+
+export const createStore = () => new MemoryStore();
+type Key = string;
+"#;
+        assert_eq!(
+            summary(&extract_symbols(Path::new("store.ts"), typescript)),
+            vec![
+                ("Store".to_string(), SymbolKind::Interface, 2),
+                ("Store.get".to_string(), SymbolKind::Method, 3),
+                ("MemoryStore".to_string(), SymbolKind::Class, 6),
+                ("MemoryStore.get".to_string(), SymbolKind::Method, 8),
+                ("createStore".to_string(), SymbolKind::Function, 11),
+                ("Key".to_string(), SymbolKind::Type, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_ranks_exact_matches_first_and_filters() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        fs::write(
+            dir.path().join("lib.rs"),
+            "struct Session;\nimpl Session { fn new() -> Self { Session } }\nfn session_id() {}\nfn new_session() {}\n",
+        )?;
+        let index = SymbolIndex::build(dir.path().to_path_buf());
+
+        assert_eq!(
+            names(&index.search("session", None, 10)),
+            vec!["Session", "session_id", "new_session"]
+        );
+        assert_eq!(
+            names(&index.search("session", Some(SymbolKind::Function), 10)),
+            vec!["session_id", "new_session"]
+        );
+        assert_eq!(
+            names(&index.search("Session::new", None, 10)),
+            vec!["Session::new"]
+        );
+        assert_eq!(names(&index.search("session", None, 1)), vec!["Session"]);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_changes_updates_the_index_incrementally() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path().to_path_buf();
+        fs::create_dir(root.join("src"))?;
+        fs::write(root.join("src/a.rs"), "fn alpha() {}\n")?;
+        fs::write(root.join("notes.txt"), "fn ignored() {}\n")?;
+        let mut index = SymbolIndex::build(root.clone());
+        assert_eq!(index.file_count(), 1);
+        assert_eq!(names(&index.search("alpha", None, 10)), vec!["alpha"]);
+
+        // Edits change the file's size, so they are picked up even when the
+        // modification time does not move.
+        fs::write(root.join("src/a.rs"), "fn alpha_renamed() {}\n")?;
+        fs::write(root.join("src/b.rs"), "fn beta() {}\n")?;
+        fs::create_dir(root.join(".hidden"))?;
+        fs::write(root.join(".hidden/c.rs"), "fn gamma() {}\n")?;
+        index.apply_changes([
+            root.join("src/a.rs"),
+            root.join("src/b.rs"),
+            root.join(".hidden/c.rs"),
+        ]);
+        assert_eq!(
+            names(&index.search("a", Some(SymbolKind::Function), 10)),
+            vec!["alpha_renamed", "beta"]
+        );
+        assert_eq!(index.search("gamma", None, 10), Vec::new());
+
+        fs::remove_dir_all(root.join("src"))?;
+        index.apply_changes([root.join("src")]);
+        assert_eq!(index.file_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_changes_skips_gitignored_directories() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path().to_path_buf();
+        fs::write(root.join(".gitignore"), "/target/\n")?;
+        fs::create_dir(root.join("src"))?;
+        fs::write(root.join("src/a.rs"), "fn alpha() {}\n")?;
+        let mut index = SymbolIndex::build(root.clone());
+
+        // A file the watcher has not reported yet only shows up after a full
+        // refresh, so it reveals whether one ran.
+        fs::write(root.join("src/b.rs"), "fn beta() {}\n")?;
+        fs::create_dir_all(root.join("target/debug/build"))?;
+        fs::write(
+            root.join("target/debug/build/out.rs"),
+            "fn generated() {}\n",
+        )?;
+        index.apply_changes([
+            root.join("target"),
+            root.join("target/debug/build"),
+            root.join("target/debug/build/out.rs"),
+        ]);
+        assert_eq!(index.search("beta", None, 10), Vec::new());
+        assert_eq!(index.search("generated", None, 10), Vec::new());
+
+        fs::create_dir(root.join("lib"))?;
+        index.apply_changes([root.join("lib")]);
+        assert_eq!(names(&index.search("beta", None, 10)), vec!["beta"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::sync::Weak;
+use std::time::Duration;
+
+use notify::Config as NotifyConfig;
+use notify::Event as NotifyEvent;
+use notify::EventKind;
+use notify::PollWatcher;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+
+use crate::Language;
+use crate::Symbol;
+use crate::SymbolIndex;
+use crate::SymbolKind;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Past this many pending paths a full refresh is cheaper than replaying them.
+const MAX_PENDING_CHANGES: usize = 10_000;
+
+/// A [`SymbolIndex`] built on a background thread and kept current by a file
+/// watcher. Cloning shares the same index.
+///
+/// Watcher events only record which paths changed; they are applied on the
+/// next search so bursts of writes (checkouts, formatters) are parsed once.
+#[derive(Clone)]
+pub struct SharedSymbolIndex {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    root: PathBuf,
+    /// `None` until the initial build finishes.
+    index: Mutex<Option<SymbolIndex>>,
+    built: Condvar,
+    /// Changes reported by the watcher. Kept apart from `index` so that the
+    /// watcher never waits for a search to re-walk the tree.
+    pending: Mutex<PendingChanges>,
+    watcher: Mutex<Option<WatcherHandle>>,
+}
+
+#[derive(Default)]
+struct PendingChanges {
+    changed: HashSet<PathBuf>,
+    needs_refresh: bool,
+}
+
+/// Hands out one [`SharedSymbolIndex`] per root, so that every thread and the
+/// UI working in the same repository share a single index and watcher. An
+/// index is dropped once nothing holds it anymore.
+#[derive(Clone, Default)]
+pub struct SymbolIndexes {
+    indexes: Arc<Mutex<HashMap<PathBuf, Weak<Inner>>>>,
+}
+
+impl SymbolIndexes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of `root`, spawning it on first use.
+    pub fn get_or_spawn(&self, root: PathBuf) -> SharedSymbolIndex {
+        let root = dunce::canonicalize(&root).unwrap_or(root);
+        let mut indexes = lock(&self.indexes);
+        indexes.retain(|_, inner| inner.strong_count() > 0);
+        if let Some(inner) = indexes.get(&root).and_then(Weak::upgrade) {
+            return SharedSymbolIndex { inner };
+        }
+        let index = SharedSymbolIndex::spawn(root.clone());
+        indexes.insert(root, Arc::downgrade(&index.inner));
+        index
+    }
+}
+
+enum WatcherHandle {
+    Recommended(RecommendedWatcher),
+    Poll(PollWatcher),
+}
+
+impl WatcherHandle {
+    fn watch(&mut self, path: &Path) -> notify::Result<()> {
+        match self {
+            Self::Recommended(watcher) => watcher.watch(path, RecursiveMode::Recursive),
+            Self::Poll(watcher) => watcher.watch(path, RecursiveMode::Recursive),
+        }
+    }
+}
+
+impl SharedSymbolIndex {
+    /// Starts watching `root` and indexing it on a background thread.
+    pub fn spawn(root: PathBuf) -> Self {
+        // Watcher events carry canonical paths.
+        let root = dunce::canonicalize(&root).unwrap_or(root);
+        let inner = Arc::new(Inner {
+            root,
+            index: Mutex::new(None),
+            built: Condvar::new(),
+            pending: Mutex::new(PendingChanges::default()),
+            watcher: Mutex::new(None),
+        });
+        // Start watching before the walk so edits made during the build are
+        // replayed afterwards.
+        *lock(&inner.watcher) = start_watcher(&inner.root, Arc::downgrade(&inner));
+
+        let builder = Arc::clone(&inner);
+        let spawned = std::thread::Builder::new()
+            .name("symbol-index".to_string())
+            .spawn(move || builder.build());
+        if let Err(err) = spawned {
+            tracing::warn!("failed to start symbol index thread: {err}");
+            // Fall back to building on the first search.
+            *lock(&inner.index) = Some(SymbolIndex::new(inner.root.clone()));
+            lock(&inner.pending).needs_refresh = true;
+        }
+        Self { inner }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.inner.root
+    }
+
+    /// Whether the initial build has finished.
+    pub fn is_ready(&self) -> bool {
+        lock(&self.inner.index).is_some()
+    }
+
+    /// Blocks until the initial build finishes or `timeout` elapses, and
+    /// returns whether the index is ready.
+    pub fn wait_until_ready(&self, timeout: Duration) -> bool {
+        let index = lock(&self.inner.index);
+        let (index, _) = self
+            .inner
+            .built
+            .wait_timeout_while(index, timeout, |index| index.is_none())
+            .unwrap_or_else(PoisonError::into_inner);
+        index.is_some()
+    }
+
+    /// Applies pending file changes, then searches like
+    /// [`SymbolIndex::search`]. Returns `None` while the initial build runs.
+    pub fn search(
+        &self,
+        query: &str,
+        kind: Option<SymbolKind>,
+        limit: usize,
+    ) -> Option<Vec<Symbol>> {
+        let mut index = lock(&self.inner.index);
+        let index = index.as_mut()?;
+        let PendingChanges {
+            changed,
+            needs_refresh,
+        } = std::mem::take(&mut *lock(&self.inner.pending));
+        if needs_refresh {
+            index.refresh();
+        } else if !changed.is_empty() {
+            index.apply_changes(changed);
+        }
+        Some(index.search(query, kind, limit))
+    }
+}
+
+impl Inner {
+    fn build(&self) {
+        let index = SymbolIndex::build(self.root.clone());
+        tracing::debug!(
+            "indexed {} symbols in {} files under {}",
+            index.symbol_count(),
+            index.file_count(),
+            self.root.display()
+        );
+        *lock(&self.index) = Some(index);
+        self.built.notify_all();
+    }
+
+    fn on_event(&self, result: notify::Result<NotifyEvent>) {
+        let mut pending = lock(&self.pending);
+        let event = match result {
+            Ok(event) => event,
+            Err(err) => {
+                tracing::warn!("symbol index watcher error: {err}");
+                pending.needs_refresh = true;
+                return;
+            }
+        };
+        if event.need_rescan() {
+            pending.needs_refresh = true;
+        }
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        // Directories have no extension; keep them so removals and renames
+        // of whole trees are seen.
+        pending.changed.extend(
+            event
+                .paths
+                .into_iter()
+                .filter(|path| path.extension().is_none() || Language::from_path(path).is_some()),
+        );
+        if pending.changed.len() > MAX_PENDING_CHANGES {
+            pending.changed.clear();
+            pending.needs_refresh = true;
+        }
+    }
+}
+
+fn start_watcher(root: &Path, inner: Weak<Inner>) -> Option<WatcherHandle> {
+    let handler = move |result: notify::Result<NotifyEvent>| {
+        if let Some(inner) = inner.upgrade() {
+            inner.on_event(result);
+        }
+    };
+    let mut watcher = match notify::recommended_watcher(handler.clone()) {
+        Ok(watcher) => WatcherHandle::Recommended(watcher),
+        Err(err) => {
+            tracing::warn!(
+                "failed to start native symbol index watcher, falling back to polling: {err}"
+            );
+            match PollWatcher::new(
+                handler,
+                NotifyConfig::default().with_poll_interval(POLL_INTERVAL),
+            ) {
+                Ok(watcher) => WatcherHandle::Poll(watcher),
+                Err(err) => {
+                    tracing::warn!("failed to start polling symbol index watcher: {err}");
+                    return None;
+                }
+            }
+        }
+    };
+    if let Err(err) = watcher.watch(root) {
+        tracing::warn!(
+            "failed to watch {} for symbol changes: {err}",
+            root.display()
+        );
+        return None;
+    }
+    Some(watcher)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn builds_in_the_background_and_searches() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        fs::write(
+            dir.path().join("main.py"),
+            "def handle_request():\n    pass\n",
+        )?;
+
+        let index = SharedSymbolIndex::spawn(dir.path().to_path_buf());
+        assert!(index.wait_until_ready(Duration::from_secs(10)));
+        let found = index
+            .search("handle", None, 10)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.path, symbol.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![("handle_request".to_string(), PathBuf::from("main.py"), 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn registry_shares_one_index_per_root() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let other = TempDir::new()?;
+        let indexes = SymbolIndexes::new();
+
+        let first = indexes.get_or_spawn(dir.path().to_path_buf());
+        let second = indexes.get_or_spawn(dir.path().join("."));
+        let third = indexes.get_or_spawn(other.path().to_path_buf());
+        assert!(Arc::ptr_eq(&first.inner, &second.inner));
+        assert!(!Arc::ptr_eq(&first.inner, &third.inner));
+        Ok(())
+    }
+}
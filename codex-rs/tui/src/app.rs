@@ -22,6 +22,8 @@ use crate::history_cell;
 use crate::history_cell::HistoryCell;
 #[cfg(not(debug_assertions))]
 use crate::history_cell::UpdateAvailableHistoryCell;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crate::model_migration::ModelMigrationOutcome;
 use crate::model_migration::migration_copy_for_models;
 use crate::model_migration::run_model_migration_prompt;
@@ -30,6 +32,7 @@ use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::Renderable;
 use crate::resume_picker::SessionSelection;
 use crate::skills_watcher::SkillsWatcher;
+use crate::theme;
 use crate::tui;
 use crate::tui::TuiEvent;
 use crate::update_action::UpdateAction;
@@ -38,6 +41,8 @@ use codex_app_server_protocol::ConfigLayerSource;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
 use codex_core::ThreadManager;
+use codex_core::approval_memory::project_root;
+use codex_core::approval_memory::revoke_remembered_approval;
 use codex_core::config::Config;
 use codex_core::config::ConfigBuilder;
 use codex_core::config::ConfigOverrides;
@@ -48,6 +53,11 @@ use codex_core::features::Feature;
 use codex_core::models_manager::manager::RefreshStrategy;
 use codex_core::models_manager::model_presets::HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG;
 use codex_core::models_manager::model_presets::HIDE_GPT5_1_MIGRATION_PROMPT_CONFIG;
+use codex_core::plan_artifacts::complete_step;
+use codex_core::plan_artifacts::create_step_checkpoint;
+use codex_core::plan_artifacts::plans_dir;
+use codex_core::plan_artifacts::restore_step_checkpoint;
+use codex_core::plan_artifacts::save_plan;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -1075,7 +1085,13 @@ impl App {
 
         chat_widget.maybe_prompt_windows_sandbox_enable();
 
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let mut file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        file_search.set_symbol_indexes(
+            config
+                .features
+                .enabled(Feature::SymbolIndex)
+                .then(|| thread_manager.symbol_indexes()),
+        );
         #[cfg(not(debug_assertions))]
         let upgrade_version = crate::updates::get_upgrade_version(&config);
 
@@ -1564,6 +1580,9 @@ impl App {
             AppEvent::FileSearchResult { query, matches } => {
                 self.chat_widget.apply_file_search_result(query, matches);
             }
+            AppEvent::SymbolSearchResult { query, symbols } => {
+                self.chat_widget.apply_symbol_search_result(query, symbols);
+            }
             AppEvent::RateLimitSnapshotFetched(snapshot) => {
                 self.chat_widget.on_rate_limit_snapshot(Some(snapshot));
             }
@@ -1582,6 +1601,11 @@ impl App {
             AppEvent::UpdatePersonality(personality) => {
                 self.on_update_personality(personality);
             }
+            AppEvent::UpdateTheme(tui_theme) => {
+                theme::set(tui_theme);
+                self.config.tui_theme = Some(tui_theme);
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::OpenReasoningPopup { model } => {
                 self.chat_widget.open_reasoning_popup(model);
             }
@@ -1911,6 +1935,99 @@ impl App {
                     }
                 }
             }
+            AppEvent::PersistThemeSelection { theme: tui_theme } => {
+                match ConfigEditsBuilder::new(&self.config.codex_home)
+                    .set_tui_theme(tui_theme)
+                    .apply()
+                    .await
+                {
+                    Ok(()) => {
+                        self.chat_widget.add_info_message(
+                            format!("Theme set to {}", theme::label(tui_theme)),
+                            Some("Earlier scrollback keeps its previous colors.".to_string()),
+                        );
+                    }
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to persist theme selection");
+                        self.chat_widget
+                            .add_error_message(format!("Failed to save theme: {err}"));
+                    }
+                }
+            }
+            AppEvent::SaveProposedPlan { plan } => {
+                let Some(dir) = plans_dir(
+                    self.config.plan_artifacts,
+                    &self.config.cwd,
+                    &self.config.codex_home,
+                ) else {
+                    return Ok(AppRunControl::Continue);
+                };
+                match save_plan(&dir, &plan, chrono::Local::now()) {
+                    Ok(path) => self.chat_widget.on_plan_saved(path),
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to save proposed plan");
+                        self.chat_widget
+                            .add_error_message(format!("Failed to save plan: {err}"));
+                    }
+                }
+            }
+            AppEvent::EditSavedPlan => {
+                self.edit_saved_plan(tui).await;
+            }
+            AppEvent::RunSavedPlan { path } => {
+                self.chat_widget.start_plan_run(path);
+            }
+            AppEvent::CheckpointPlanStep { path, step } => {
+                let cwd = self.config.cwd.clone();
+                let ghost_snapshot = self.config.ghost_snapshot.clone();
+                let outcome = tokio::task::spawn_blocking(move || {
+                    let checkpoint = match create_step_checkpoint(&cwd, &ghost_snapshot, step) {
+                        Ok(checkpoint) => checkpoint,
+                        Err(err) => {
+                            tracing::warn!(error = %err, "failed to checkpoint plan step");
+                            None
+                        }
+                    };
+                    complete_step(&path, step, checkpoint.as_deref()).map(|()| checkpoint)
+                })
+                .await
+                .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+                self.chat_widget.on_plan_step_finished(step, outcome);
+            }
+            AppEvent::OpenSavedPlan { path } => {
+                self.chat_widget.open_plan_continue_prompt(path);
+            }
+            AppEvent::RestorePlanCheckpoint { path, step } => {
+                let cwd = self.config.cwd.clone();
+                let plan_path = path.clone();
+                let outcome = tokio::task::spawn_blocking(move || {
+                    restore_step_checkpoint(&plan_path, &cwd, step)
+                })
+                .await
+                .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+                self.chat_widget
+                    .on_plan_checkpoint_restored(path, step, outcome);
+            }
+            AppEvent::RevokeRepoApproval { approval } => {
+                let project = project_root(&self.config.cwd);
+                match revoke_remembered_approval(&self.config.codex_home, &project, &approval) {
+                    Ok(true) => {
+                        self.chat_widget
+                            .add_info_message(format!("Revoked {approval}"), None);
+                    }
+                    Ok(false) => {
+                        self.chat_widget.add_info_message(
+                            format!("{approval} was not remembered for this repo"),
+                            None,
+                        );
+                    }
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to revoke remembered approval");
+                        self.chat_widget
+                            .add_error_message(format!("Failed to revoke approval: {err}"));
+                    }
+                }
+            }
             AppEvent::UpdateAskForApprovalPolicy(policy) => {
                 self.runtime_approval_policy_override = Some(policy);
                 if let Err(err) = self.config.approval_policy.set(policy) {
@@ -1995,6 +2112,10 @@ impl App {
                     .with_profile(self.active_profile.as_deref());
                 for (feature, enabled) in &updates {
                     let feature_key = feature.key();
+                    if *feature == Feature::SymbolIndex {
+                        self.file_search
+                            .set_symbol_indexes(enabled.then(|| self.server.symbol_indexes()));
+                    }
                     if *enabled {
                         // Update the in-memory configs.
                         self.config.features.enable(*feature);
@@ -2387,6 +2508,43 @@ impl App {
         tui.frame_requester().schedule_frame();
     }
 
+    async fn edit_saved_plan(&mut self, tui: &mut tui::Tui) {
+        let Some(path) = self.chat_widget.saved_plan_path().map(Path::to_path_buf) else {
+            self.chat_widget
+                .add_error_message("No saved plan to edit.".to_string());
+            return;
+        };
+        let editor_cmd = match external_editor::resolve_editor_command() {
+            Ok(cmd) => cmd,
+            Err(external_editor::EditorError::MissingEditor) => {
+                self.chat_widget.add_error_message(format!(
+                    "Cannot open external editor: set $VISUAL or $EDITOR, or edit {} directly.",
+                    path.display()
+                ));
+                return;
+            }
+            Err(err) => {
+                self.chat_widget
+                    .add_error_message(format!("Failed to open editor: {err}"));
+                return;
+            }
+        };
+
+        let editor_result = tui
+            .with_restored(tui::RestoreMode::KeepRaw, || async {
+                external_editor::edit_file(&path, &editor_cmd).await
+            })
+            .await;
+        match editor_result {
+            Ok(()) => self.chat_widget.on_plan_edited(&path),
+            Err(err) => {
+                self.chat_widget
+                    .add_error_message(format!("Failed to open editor: {err}"));
+            }
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
     fn request_external_editor_launch(&mut self, tui: &mut tui::Tui) {
         self.chat_widget
             .set_external_editor_state(ExternalEditorState::Requested);
@@ -2405,24 +2563,15 @@ impl App {
     }
 
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
+        let keymap = keymap::current();
         match key_event {
-            KeyEvent {
-                code: KeyCode::Char('t'),
-                modifiers: crossterm::event::KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                ..
-            } => {
+            _ if keymap.matches(KeymapAction::OpenTranscript, key_event) => {
                 // Enter alternate screen and set viewport to full size.
                 let _ = tui.enter_alt_screen();
                 self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
                 tui.frame_requester().schedule_frame();
             }
-            KeyEvent {
-                code: KeyCode::Char('g'),
-                modifiers: crossterm::event::KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                ..
-            } => {
+            _ if keymap.matches(KeymapAction::ExternalEditor, key_event) => {
                 // Only launch the external editor if there is no overlay and the bottom pane is not in use.
                 // Note that it can be launched while a task is running to enable editing while the previous turn is ongoing.
                 if self.overlay.is_none()
@@ -2432,15 +2581,11 @@ impl App {
                     self.request_external_editor_launch(tui);
                 }
             }
-            // Esc primes/advances backtracking only in normal (not working) mode
-            // with the composer focused and empty. In any other state, forward
-            // Esc so the active UI (e.g. status indicator, modals, popups)
-            // handles it.
-            KeyEvent {
-                code: KeyCode::Esc,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
+            // The backtrack key (Esc by default) primes/advances backtracking only
+            // in normal (not working) mode with the composer focused and empty. In
+            // any other state, forward it so the active UI (e.g. status indicator,
+            // modals, popups) handles it.
+            _ if keymap.matches(KeymapAction::Backtrack, key_event) => {
                 if self.chat_widget.is_normal_backtrack_mode()
                     && self.chat_widget.composer_is_empty()
                 {
@@ -2466,10 +2611,10 @@ impl App {
                 kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
             } => {
-                // Any non-Esc key press should cancel a primed backtrack.
+                // Any other key press should cancel a primed backtrack.
                 // This avoids stale "Esc-primed" state after the user starts typing
                 // (even if they later backspace to empty).
-                if key_event.code != KeyCode::Esc && self.backtrack.primed {
+                if self.backtrack.primed {
                     self.reset_backtrack_state();
                 }
                 self.chat_widget.handle_key_event(key_event);
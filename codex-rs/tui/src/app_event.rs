@@ -12,14 +12,17 @@ use std::path::PathBuf;
 
 use codex_chatgpt::connectors::AppInfo;
 use codex_common::approval_presets::ApprovalPreset;
+use codex_core::approval_memory::RememberedApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_file_search::FileMatch;
 use codex_protocol::ThreadId;
 use codex_protocol::openai_models::ModelPreset;
+use codex_symbol_index::Symbol;
 
 use crate::bottom_pane::ApprovalRequest;
 use crate::history_cell::HistoryCell;
+use crate::theme::TuiTheme;
 
 use codex_core::features::Feature;
 use codex_core::protocol::AskForApproval;
@@ -92,6 +95,13 @@ pub(crate) enum AppEvent {
         matches: Vec<FileMatch>,
     },
 
+    /// Result of an `@#name` symbol search. `query` echoes the search term,
+    /// including the `#`.
+    SymbolSearchResult {
+        query: String,
+        symbols: Vec<Symbol>,
+    },
+
     /// Result of refreshing rate limits
     RateLimitSnapshotFetched(RateLimitSnapshot),
 
@@ -139,6 +149,51 @@ pub(crate) enum AppEvent {
         personality: Personality,
     },
 
+    /// Switch the color theme for everything rendered from now on.
+    UpdateTheme(TuiTheme),
+
+    /// Persist the selected color theme to `config.toml`.
+    PersistThemeSelection {
+        theme: TuiTheme,
+    },
+
+    /// Save a proposed plan from Plan mode to disk.
+    SaveProposedPlan {
+        plan: String,
+    },
+
+    /// Open the most recently saved plan in the external editor.
+    EditSavedPlan,
+
+    /// Execute a saved plan step by step; `None` runs the plan saved last.
+    RunSavedPlan {
+        path: Option<PathBuf>,
+    },
+
+    /// Checkpoint the working tree after a plan step and mark it done.
+    CheckpointPlanStep {
+        path: PathBuf,
+        step: usize,
+    },
+
+    /// Show the progress of a saved plan and offer to continue it or restore
+    /// one of its checkpoints.
+    OpenSavedPlan {
+        path: PathBuf,
+    },
+
+    /// Restore the working tree to the checkpoint taken after a plan step and
+    /// reopen the steps after it.
+    RestorePlanCheckpoint {
+        path: PathBuf,
+        step: usize,
+    },
+
+    /// Forget an approval remembered for the current repo.
+    RevokeRepoApproval {
+        approval: RememberedApproval,
+    },
+
     /// Open the reasoning selection popup after picking a model.
     OpenReasoningPopup {
         model: ModelPreset,
@@ -13,6 +13,8 @@ use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
@@ -447,6 +449,14 @@ impl ApprovalOption {
     }
 }
 
+fn approve_shortcut() -> KeyBinding {
+    keymap::current().binding(KeymapAction::Approve)
+}
+
+fn deny_shortcut() -> KeyBinding {
+    keymap::current().binding(KeymapAction::Deny)
+}
+
 fn exec_options(
     proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     features: &Features,
@@ -455,7 +465,7 @@ fn exec_options(
         label: "Yes, proceed".to_string(),
         decision: ApprovalDecision::Review(ReviewDecision::Approved),
         display_shortcut: None,
-        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        additional_shortcuts: vec![approve_shortcut()],
     }]
     .into_iter()
     .chain(
@@ -481,12 +491,20 @@ fn exec_options(
                 })
             }),
     )
-    .chain([ApprovalOption {
-        label: "No, and tell Codex what to do differently".to_string(),
-        decision: ApprovalDecision::Review(ReviewDecision::Abort),
-        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-    }])
+    .chain([
+        ApprovalOption {
+            label: "Yes, and always allow this command in this repo".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForRepo),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+        },
+        ApprovalOption {
+            label: "No, and tell Codex what to do differently".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Abort),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: vec![deny_shortcut()],
+        },
+    ])
     .collect()
 }
 
@@ -496,7 +514,7 @@ fn patch_options() -> Vec<ApprovalOption> {
             label: "Yes, proceed".to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Approved),
             display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+            additional_shortcuts: vec![approve_shortcut()],
         },
         ApprovalOption {
             label: "Yes, and don't ask again for these files".to_string(),
@@ -504,11 +522,17 @@ fn patch_options() -> Vec<ApprovalOption> {
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+        ApprovalOption {
+            label: "Yes, and always allow edits to these files in this repo".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForRepo),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Abort),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+            additional_shortcuts: vec![deny_shortcut()],
         },
     ]
 }
@@ -519,13 +543,13 @@ fn elicitation_options() -> Vec<ApprovalOption> {
             label: "Yes, provide the requested info".to_string(),
             decision: ApprovalDecision::McpElicitation(ElicitationAction::Accept),
             display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+            additional_shortcuts: vec![approve_shortcut()],
         },
         ApprovalOption {
             label: "No, but continue without it".to_string(),
             decision: ApprovalDecision::McpElicitation(ElicitationAction::Decline),
             display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+            additional_shortcuts: vec![deny_shortcut()],
         },
         ApprovalOption {
             label: "Cancel this request".to_string(),
@@ -639,7 +663,7 @@ mod tests {
                 features
             },
         );
-        assert_eq!(view.options.len(), 2);
+        assert_eq!(view.options.len(), 3);
         view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
         assert!(!view.is_complete());
         assert!(rx.try_recv().is_err());
@@ -85,6 +85,8 @@
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
 use crate::key_hint::has_ctrl_or_alt;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
@@ -125,11 +127,15 @@ use super::footer::render_footer_line;
 use super::footer::reset_mode_after_activity;
 use super::footer::single_line_footer_layout;
 use super::footer::toggle_shortcut_mode;
+use super::footer::with_vim_mode_label;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use super::skill_popup::MentionItem;
 use super::skill_popup::SkillPopup;
 use super::slash_commands;
+use super::vim::VimMode;
+use super::vim::VimOutcome;
+use super::vim::VimState;
 use crate::bottom_pane::paste_burst::FlushResult;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
@@ -163,6 +169,7 @@ use codex_chatgpt::connectors;
 use codex_chatgpt::connectors::AppInfo;
 use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
+use codex_symbol_index::Symbol;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -295,6 +302,8 @@ pub(crate) struct ChatComposer {
     connectors_enabled: bool,
     personality_command_enabled: bool,
     windows_degraded_sandbox_active: bool,
+    /// vi-style modal editing state; `None` unless `[tui] vim_mode` is enabled.
+    vim: Option<VimState>,
 }
 
 #[derive(Clone, Debug)]
@@ -384,6 +393,7 @@ impl ChatComposer {
             connectors_enabled: false,
             personality_command_enabled: false,
             windows_degraded_sandbox_active: false,
+            vim: None,
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -438,6 +448,26 @@ impl ChatComposer {
     pub fn set_personality_command_enabled(&mut self, enabled: bool) {
         self.personality_command_enabled = enabled;
     }
+
+    /// Enables or disables vi-style modal editing. The composer starts in insert mode.
+    pub fn set_vim_mode_enabled(&mut self, enabled: bool) {
+        match (enabled, self.vim.is_some()) {
+            (true, false) => self.vim = Some(VimState::new()),
+            (false, true) => {
+                self.vim = None;
+                self.textarea.set_selection(None);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether Esc belongs to the vi layer (leaving insert or visual mode)
+    /// rather than interrupting a task or starting backtrack.
+    pub(crate) fn vim_captures_esc(&self) -> bool {
+        self.vim
+            .as_ref()
+            .is_some_and(|vim| vim.mode() != VimMode::Normal)
+    }
     /// Centralized feature gating keeps config checks out of call sites.
     fn popups_enabled(&self) -> bool {
         self.config.popups_enabled
@@ -923,6 +953,21 @@ impl ChatComposer {
         }
     }
 
+    /// Integrate results from an asynchronous `@#name` symbol search.
+    pub(crate) fn on_symbol_search_result(&mut self, query: String, symbols: Vec<Symbol>) {
+        let Some(current_token) = Self::current_at_token(&self.textarea) else {
+            return;
+        };
+
+        if !current_token.starts_with(&query) {
+            return;
+        }
+
+        if let ActivePopup::File(popup) = &mut self.active_popup {
+            popup.set_symbol_matches(&query, symbols);
+        }
+    }
+
     /// Show the transient "press again to quit" hint for `key`.
     ///
     /// The owner (`BottomPane`/`ChatWidget`) is responsible for scheduling a
@@ -976,6 +1021,13 @@ impl ChatComposer {
             return (InputResult::None, false);
         }
 
+        if let Some(handled) = self.handle_vim_paste_burst(key_event, Instant::now()) {
+            return handled;
+        }
+        if let Some(handled) = self.handle_vim_key(key_event) {
+            return handled;
+        }
+
         let result = match &mut self.active_popup {
             ActivePopup::Command(_) => self.handle_key_event_with_slash_popup(key_event),
             ActivePopup::File(_) => self.handle_key_event_with_file_popup(key_event),
@@ -986,9 +1038,99 @@ impl ChatComposer {
         // Update (or hide/show) popup after processing the key.
         self.sync_popups();
 
+        if !matches!(result.0, InputResult::None)
+            && let Some(vim) = self.vim.as_mut()
+        {
+            // A submitted or queued draft starts over in insert mode.
+            vim.reset(&mut self.textarea);
+        }
+
         result
     }
 
+    /// Route a key through the vi layer when it is enabled. Returns `None` when
+    /// the key should take the regular composer path.
+    fn handle_vim_key(&mut self, key_event: KeyEvent) -> Option<(InputResult, bool)> {
+        let popup_active = self.popup_active();
+        let vim = self.vim.as_mut()?;
+        // Popups keep their own keys (including Esc to dismiss) in insert mode.
+        if vim.mode() == VimMode::Insert && popup_active {
+            return None;
+        }
+        let elements_before = self.textarea.element_payloads();
+        if vim.handle_key(&mut self.textarea, key_event) == VimOutcome::Forward {
+            return None;
+        }
+        self.reconcile_deleted_elements(elements_before);
+        self.footer_mode = reset_mode_after_activity(self.footer_mode);
+        self.sync_popups();
+        Some((InputResult::None, true))
+    }
+
+    /// Whether plain characters are vi commands (normal or visual mode) rather than text.
+    fn vim_in_command_mode(&self) -> bool {
+        self.vim
+            .as_ref()
+            .is_some_and(|vim| vim.mode() != VimMode::Insert)
+    }
+
+    /// Run paste-burst detection ahead of the vi layer so a non-bracketed paste in normal or
+    /// visual mode is inserted as text instead of being executed as commands. As in insert mode,
+    /// a fast first char is held briefly; when no burst follows it is applied as a command.
+    fn handle_vim_paste_burst(
+        &mut self,
+        key_event: KeyEvent,
+        now: Instant,
+    ) -> Option<(InputResult, bool)> {
+        if !self.vim_in_command_mode() || self.disable_paste_burst {
+            return None;
+        }
+        self.handle_paste_burst_flush(now);
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Char(ch),
+                modifiers,
+                ..
+            } if !has_ctrl_or_alt(modifiers) => match self.paste_burst.on_plain_char(ch, now) {
+                CharDecision::BufferAppend | CharDecision::BeginBufferFromPending => {
+                    self.paste_burst.append_char_to_buffer(ch, now);
+                    Some((InputResult::None, true))
+                }
+                CharDecision::RetainFirstChar => Some((InputResult::None, true)),
+                // The earlier chars already ran as commands; treat this one as a command too.
+                CharDecision::BeginBuffer { .. } => None,
+            },
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } if self.paste_burst.append_newline_if_active(now) => Some((InputResult::None, true)),
+            _ => {
+                if let Some(ch) = self.paste_burst.take_pending_first_char() {
+                    self.apply_held_char(ch);
+                } else if let Some(pasted) = self.paste_burst.flush_before_modified_input() {
+                    self.handle_paste(pasted);
+                }
+                None
+            }
+        }
+    }
+
+    /// Apply a held fast char that turned out not to start a paste burst: as a vi command in
+    /// normal or visual mode, otherwise as typed text.
+    fn apply_held_char(&mut self, ch: char) {
+        if self.vim_in_command_mode()
+            && self
+                .handle_vim_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE))
+                .is_some()
+        {
+            return;
+        }
+        // Mirror insert_str() behavior so popups stay in sync when a
+        // pending fast char flushes as normal typed input.
+        self.textarea.insert_str(ch.to_string().as_str());
+        self.sync_popups();
+    }
+
     /// Return true if either the slash-command popup or the file-search popup is active.
     pub(crate) fn popup_active(&self) -> bool {
         !matches!(self.active_popup, ActivePopup::None)
@@ -1342,6 +1484,11 @@ impl ChatComposer {
                 modifiers: KeyModifiers::NONE,
                 ..
             } => {
+                if let Some(location) = popup.selected_symbol_location() {
+                    self.insert_selected_path(&location);
+                    self.active_popup = ActivePopup::None;
+                    return (InputResult::None, true);
+                }
                 let Some(sel) = popup.selected_match() else {
                     self.active_popup = ActivePopup::None;
                     return (InputResult::None, true);
@@ -2253,11 +2400,12 @@ impl ChatComposer {
                 kind: KeyEventKind::Press,
                 ..
             } if self.is_task_running => self.handle_submission(true),
-            KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
+            // A rebound newline key inserts the same newline the textarea
+            // produces for Shift+Enter.
+            _ if keymap::current().matches(KeymapAction::Newline, key_event) => {
+                self.handle_input_basic(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT))
+            }
+            _ if keymap::current().matches(KeymapAction::Submit, key_event) => {
                 let should_queue = !self.steer_enabled;
                 self.handle_submission(should_queue)
             }
@@ -2280,10 +2428,7 @@ impl ChatComposer {
                 true
             }
             FlushResult::Typed(ch) => {
-                // Mirror insert_str() behavior so popups stay in sync when a
-                // pending fast char flushes as normal typed input.
-                self.textarea.insert_str(ch.to_string().as_str());
-                self.sync_popups();
+                self.apply_held_char(ch);
                 true
             }
             FlushResult::None => false,
@@ -3099,9 +3244,12 @@ impl ChatComposer {
                     | FooterMode::ShortcutOverlay
                     | FooterMode::EscHint => false,
                 };
-                let context_line = context_window_line(
-                    footer_props.context_window_percent,
-                    footer_props.context_window_used_tokens,
+                let context_line = with_vim_mode_label(
+                    context_window_line(
+                        footer_props.context_window_percent,
+                        footer_props.context_window_used_tokens,
+                    ),
+                    self.vim.as_ref().map(VimState::mode),
                 );
                 let custom_height = self.custom_footer_height();
                 let footer_hint_height =
@@ -3826,6 +3974,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vim_mode_edits_in_normal_mode_and_resets_after_submit() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_steer_enabled(true);
+        composer.set_vim_mode_enabled(true);
+        composer.set_text_content("hello world".to_string(), Vec::new(), Vec::new());
+        assert!(composer.vim_captures_esc(), "insert mode owns Esc");
+
+        let _ = composer.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(!composer.vim_captures_esc(), "normal mode forwards Esc");
+        type_chars_humanlike(&mut composer, &['0', 'd', 'w']);
+        assert_eq!(composer.textarea.text(), "world");
+
+        let (result, _) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        match result {
+            InputResult::Submitted { text, .. } => assert_eq!(text, "world"),
+            _ => panic!("expected Submitted"),
+        }
+        assert!(
+            composer.vim_captures_esc(),
+            "submitting returns to insert mode"
+        );
+    }
+
+    #[test]
+    fn vim_normal_mode_inserts_paste_bursts_instead_of_running_them() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_vim_mode_enabled(true);
+        composer.set_text_content("hello".to_string(), Vec::new(), Vec::new());
+        let _ = composer.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+
+        // A non-bracketed paste arrives as a fast stream of plain chars.
+        for c in "dd x".chars() {
+            let _ = composer.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(composer.textarea.text(), "hello");
+        std::thread::sleep(PasteBurst::recommended_active_flush_delay());
+        let _ = composer.flush_paste_burst_if_due();
+        assert_eq!(composer.textarea.text(), "helldd xo");
+
+        // A single typed char still runs as a command once no burst follows.
+        type_chars_humanlike(&mut composer, &['0', 'x']);
+        assert_eq!(composer.textarea.text(), "elldd xo");
+    }
+
     /// Behavior: `?` toggles the shortcut overlay only when the composer is otherwise empty. After
     /// any typing has occurred, `?` should be inserted as a literal character.
     #[test]
@@ -5063,6 +5281,51 @@ mod tests {
         assert_eq!(composer.textarea.text(), "@");
     }
 
+    #[test]
+    fn symbol_mention_inserts_definition_location() {
+        use codex_symbol_index::SymbolKind;
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        type_chars_humanlike(
+            &mut composer,
+            &['f', 'i', 'x', ' ', '@', '#', 'l', 'o', 'a', 'd'],
+        );
+        let mut last_query = None;
+        while let Ok(event) = rx.try_recv() {
+            if let AppEvent::StartFileSearch(query) = event {
+                last_query = Some(query);
+            }
+        }
+        assert_eq!(last_query.as_deref(), Some("#load"));
+
+        composer.on_symbol_search_result(
+            "#load".to_string(),
+            vec![Symbol {
+                name: "load".to_string(),
+                kind: SymbolKind::Method,
+                path: PathBuf::from("src/config.rs"),
+                line: 42,
+                container: Some("Config".to_string()),
+            }],
+        );
+        let _ = composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(composer.textarea.text(), "fix src/config.rs:42 ");
+        assert!(matches!(composer.active_popup, ActivePopup::None));
+    }
+
     #[test]
     fn slash_plan_args_preserve_text_elements() {
         use crossterm::event::KeyCode;
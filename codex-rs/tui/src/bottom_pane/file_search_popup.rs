@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use codex_file_search::FileMatch;
+use codex_symbol_index::Symbol;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::widgets::WidgetRef;
@@ -24,6 +25,9 @@ pub(crate) struct FileSearchPopup {
     waiting: bool,
     /// Cached matches; paths relative to the search dir.
     matches: Vec<FileMatch>,
+    /// Cached symbols for `@#name` queries. Only one of `matches` and
+    /// `symbols` is non-empty at a time.
+    symbols: Vec<Symbol>,
     /// Shared selection/scroll state.
     state: ScrollState,
 }
@@ -35,6 +39,7 @@ impl FileSearchPopup {
             pending_query: String::new(),
             waiting: true,
             matches: Vec::new(),
+            symbols: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -58,6 +63,7 @@ impl FileSearchPopup {
         self.pending_query.clear();
         self.waiting = false;
         self.matches.clear();
+        self.symbols.clear();
         // Reset selection/scroll state when showing the empty prompt.
         self.state.reset();
     }
@@ -71,22 +77,43 @@ impl FileSearchPopup {
 
         self.display_query = query.to_string();
         self.matches = matches;
+        self.symbols.clear();
         self.waiting = false;
-        let len = self.matches.len();
+        let len = self.len();
         self.state.clamp_selection(len);
         self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
     }
 
+    /// Replace the list with symbols when a `SymbolSearchResult` arrives.
+    /// Only applied when `query` matches `pending_query`.
+    pub(crate) fn set_symbol_matches(&mut self, query: &str, symbols: Vec<Symbol>) {
+        if query != self.pending_query {
+            return; // stale
+        }
+
+        self.display_query = query.to_string();
+        self.matches.clear();
+        self.symbols = symbols;
+        self.waiting = false;
+        let len = self.len();
+        self.state.clamp_selection(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    fn len(&self) -> usize {
+        self.matches.len() + self.symbols.len()
+    }
+
     /// Move selection cursor up.
     pub(crate) fn move_up(&mut self) {
-        let len = self.matches.len();
+        let len = self.len();
         self.state.move_up_wrap(len);
         self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
     }
 
     /// Move selection cursor down.
     pub(crate) fn move_down(&mut self) {
-        let len = self.matches.len();
+        let len = self.len();
         self.state.move_down_wrap(len);
         self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
     }
@@ -98,6 +125,14 @@ impl FileSearchPopup {
             .map(|file_match| &file_match.path)
     }
 
+    /// `path:line` of the selected symbol, for insertion into the composer.
+    pub(crate) fn selected_symbol_location(&self) -> Option<String> {
+        self.state
+            .selected_idx
+            .and_then(|idx| self.symbols.get(idx))
+            .map(|symbol| format!("{}:{}", symbol.path.display(), symbol.line))
+    }
+
     pub(crate) fn calculate_required_height(&self) -> u16 {
         // Row count depends on whether we already have matches. If no matches
         // yet (e.g. initial search or query with no results) reserve a single
@@ -105,14 +140,32 @@ impl FileSearchPopup {
         // up to MAX_RESULTS regardless of the waiting flag so the list
         // remains stable while a newer search is in-flight.
 
-        self.matches.len().clamp(1, MAX_POPUP_ROWS) as u16
+        self.len().clamp(1, MAX_POPUP_ROWS) as u16
     }
 }
 
 impl WidgetRef for &FileSearchPopup {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
         // Convert matches to GenericDisplayRow, translating indices to usize at the UI boundary.
-        let rows_all: Vec<GenericDisplayRow> = if self.matches.is_empty() {
+        let rows_all: Vec<GenericDisplayRow> = if !self.symbols.is_empty() {
+            self.symbols
+                .iter()
+                .map(|symbol| GenericDisplayRow {
+                    name: symbol.qualified_name(),
+                    match_indices: None,
+                    display_shortcut: None,
+                    description: Some(format!(
+                        "{} {}:{}",
+                        symbol.kind,
+                        symbol.path.display(),
+                        symbol.line
+                    )),
+                    wrap_indent: None,
+                    is_disabled: false,
+                    disabled_reason: None,
+                })
+                .collect()
+        } else if self.matches.is_empty() {
             Vec::new()
         } else {
             self.matches
@@ -32,8 +32,11 @@
 //! In short: `single_line_footer_layout` chooses *what* best fits, and the two
 //! render helpers choose whether to draw the chosen line or the default
 //! `FooterProps` mapping.
+use super::vim::VimMode;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crate::render::line_utils::prefix_lines;
 use crate::status::format_tokens_compact;
 use crate::ui_consts::FOOTER_INDENT_COLS;
@@ -672,7 +675,7 @@ fn quit_shortcut_reminder_line(key: KeyBinding) -> Line<'static> {
 }
 
 fn esc_hint_line(esc_backtrack_hint: bool) -> Line<'static> {
-    let esc = key_hint::plain(KeyCode::Esc);
+    let esc = keymap::current().binding(KeymapAction::Backtrack);
     if esc_backtrack_hint {
         Line::from(vec![esc.into(), " again to edit previous message".into()]).dim()
     } else {
@@ -798,6 +801,25 @@ pub(crate) fn context_window_line(percent: Option<i64>, used_tokens: Option<i64>
     Line::from(Span::from(label).dim())
 }
 
+/// Prefix the context indicator with the vi mode when `[tui] vim_mode` is on.
+pub(crate) fn with_vim_mode_label(
+    context_line: Line<'static>,
+    mode: Option<VimMode>,
+) -> Line<'static> {
+    let Some(mode) = mode else {
+        return context_line;
+    };
+    let label = match mode {
+        VimMode::Insert => Span::from(mode.label()).dim(),
+        VimMode::Normal | VimMode::Visual | VimMode::VisualLine => {
+            Span::from(mode.label()).cyan().bold()
+        }
+    };
+    let mut spans = vec![label, "  ".into()];
+    spans.extend(context_line.spans);
+    Line::from(spans)
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum ShortcutId {
     Commands,
@@ -858,9 +880,23 @@ impl ShortcutDescriptor {
         self.bindings.iter().find(|binding| binding.matches(state))
     }
 
-    fn overlay_entry(&self, state: ShortcutsState) -> Option<Line<'static>> {
+    /// The key to show for this shortcut, preferring a `[tui.keymap]` override.
+    fn key_for(&self, state: ShortcutsState) -> Option<KeyBinding> {
         let binding = self.binding_for(state)?;
-        let mut line = Line::from(vec![self.prefix.into(), binding.key.into()]);
+        let action = match self.id {
+            ShortcutId::InsertNewline => KeymapAction::Newline,
+            ShortcutId::PasteImage => KeymapAction::PasteImage,
+            ShortcutId::ExternalEditor => KeymapAction::ExternalEditor,
+            ShortcutId::EditPrevious => KeymapAction::Backtrack,
+            ShortcutId::ShowTranscript => KeymapAction::OpenTranscript,
+            _ => return Some(binding.key),
+        };
+        Some(keymap::current().overridden(action).unwrap_or(binding.key))
+    }
+
+    fn overlay_entry(&self, state: ShortcutsState) -> Option<Line<'static>> {
+        let key = self.key_for(state)?;
+        let mut line = Line::from(vec![self.prefix.into(), key.into()]);
         match self.id {
             ShortcutId::EditPrevious => {
                 if state.esc_backtrack_hint {
@@ -868,7 +904,7 @@ impl ShortcutDescriptor {
                 } else {
                     line.extend(vec![
                         " ".into(),
-                        key_hint::plain(KeyCode::Esc).into(),
+                        key.into(),
                         " to edit previous message".into(),
                     ]);
                 }
@@ -1331,6 +1367,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vim_mode_label_prefixes_context_line() {
+        let context = context_window_line(Some(42), None);
+        assert_eq!(with_vim_mode_label(context.clone(), None), context);
+
+        let line = with_vim_mode_label(context, Some(VimMode::Normal));
+        let text: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "-- NORMAL --  42% context left");
+    }
+
     #[test]
     fn paste_image_shortcut_prefers_ctrl_alt_v_under_wsl() {
         let descriptor = SHORTCUTS
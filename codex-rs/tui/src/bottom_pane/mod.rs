@@ -22,6 +22,8 @@ use crate::bottom_pane::queued_user_messages::QueuedUserMessages;
 use crate::bottom_pane::unified_exec_footer::UnifiedExecFooter;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crate::render::renderable::FlexRenderable;
 use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
@@ -32,6 +34,7 @@ use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
 use codex_protocol::request_user_input::RequestUserInputEvent;
 use codex_protocol::user_input::TextElement;
+use codex_symbol_index::Symbol;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use ratatui::buffer::Buffer;
@@ -80,6 +83,7 @@ mod scroll_state;
 mod selection_popup_common;
 mod textarea;
 mod unified_exec_footer;
+mod vim;
 pub(crate) use feedback_view::FeedbackNoteView;
 
 /// How long the "press again to quit" hint stays visible.
@@ -236,6 +240,11 @@ impl BottomPane {
         self.composer.set_steer_enabled(enabled);
     }
 
+    pub fn set_vim_mode_enabled(&mut self, enabled: bool) {
+        self.composer.set_vim_mode_enabled(enabled);
+        self.request_redraw();
+    }
+
     pub fn set_collaboration_modes_enabled(&mut self, enabled: bool) {
         self.composer.set_collaboration_modes_enabled(enabled);
         self.request_redraw();
@@ -332,12 +341,15 @@ impl BottomPane {
             self.request_redraw();
             InputResult::None
         } else {
-            // If a task is running and a status line is visible, allow Esc to
-            // send an interrupt even while the composer has focus.
-            // When a popup is active, prefer dismissing it over interrupting the task.
-            if key_event.code == KeyCode::Esc
+            // If a task is running and a status line is visible, allow the
+            // interrupt key (Esc by default) to interrupt even while the composer
+            // has focus. When a popup is active, prefer dismissing it over
+            // interrupting the task, and in vim mode Esc first returns the
+            // composer to normal mode.
+            if keymap::current().matches(KeymapAction::Interrupt, key_event)
                 && self.is_task_running
                 && !self.composer.popup_active()
+                && !(key_event.code == KeyCode::Esc && self.composer.vim_captures_esc())
                 && let Some(status) = &self.status
             {
                 // Send Op::Interrupt
@@ -666,7 +678,10 @@ impl BottomPane {
     /// overlays or popups and not running a task. This is the safe context to
     /// use Esc-Esc for backtracking from the main view.
     pub(crate) fn is_normal_backtrack_mode(&self) -> bool {
-        !self.is_task_running && self.view_stack.is_empty() && !self.composer.popup_active()
+        !self.is_task_running
+            && self.view_stack.is_empty()
+            && !self.composer.popup_active()
+            && !self.composer.vim_captures_esc()
     }
 
     /// Return true when no popups or modal views are active, regardless of task state.
@@ -808,6 +823,11 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    pub(crate) fn on_symbol_search_result(&mut self, query: String, symbols: Vec<Symbol>) {
+        self.composer.on_symbol_search_result(query, symbols);
+        self.request_redraw();
+    }
+
     pub(crate) fn attach_image(&mut self, path: PathBuf) {
         if self.view_stack.is_empty() {
             self.composer.attach_image(path);
@@ -411,6 +411,15 @@ impl PasteBurst {
         Some(out)
     }
 
+    /// Takes the held first char when no burst followed it, so the caller can
+    /// apply it as a regular keystroke.
+    pub fn take_pending_first_char(&mut self) -> Option<char> {
+        if self.is_active_internal() {
+            return None;
+        }
+        self.pending_first_char.take().map(|(ch, _at)| ch)
+    }
+
     /// Clear only the timing window and any pending first-char.
     ///
     /// Does not emit or clear the buffered text itself; callers should have
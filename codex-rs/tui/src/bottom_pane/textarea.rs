@@ -7,6 +7,7 @@ use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Color;
+use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::widgets::StatefulWidgetRef;
 use ratatui::widgets::WidgetRef;
@@ -47,6 +48,8 @@ pub(crate) struct TextArea {
     kill_buffer: String,
     undo_stack: Vec<TextSnapshot>,
     redo_stack: Vec<TextSnapshot>,
+    /// Byte range highlighted as a selection (vi visual mode).
+    selection: Option<Range<usize>>,
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +75,7 @@ impl TextArea {
             kill_buffer: String::new(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            selection: None,
         }
     }
 
@@ -591,6 +595,20 @@ impl TextArea {
         }
     }
 
+    pub(crate) fn kill_buffer(&self) -> &str {
+        &self.kill_buffer
+    }
+
+    /// Replace the kill buffer without touching the text, e.g. for vi yanks.
+    pub(crate) fn set_kill_buffer(&mut self, text: String) {
+        self.kill_buffer = text;
+    }
+
+    /// Highlight `selection` when rendering; `None` clears the highlight.
+    pub(crate) fn set_selection(&mut self, selection: Option<Range<usize>>) {
+        self.selection = selection;
+    }
+
     pub fn yank(&mut self) {
         if self.kill_buffer.is_empty() {
             return;
@@ -599,7 +617,8 @@ impl TextArea {
         self.insert_str(&text);
     }
 
-    fn kill_range(&mut self, range: Range<usize>) {
+    /// Remove `range` (widened to whole elements) and store it in the kill buffer.
+    pub(crate) fn kill_range(&mut self, range: Range<usize>) {
         let range = self.expand_range_to_element_boundaries(range);
         if range.start >= range.end {
             return;
@@ -1316,6 +1335,26 @@ impl TextArea {
                 let style = Style::default().fg(Color::Cyan);
                 buf.set_string(area.x + x_off, y, styled, style);
             }
+
+            if let Some(selection) = &self.selection {
+                let overlap_start = selection.start.max(line_range.start);
+                let overlap_end = selection.end.min(line_range.end);
+                if overlap_start < overlap_end {
+                    let x_off = self.text[line_range.start..overlap_start].width() as u16;
+                    let width = self.text[overlap_start..overlap_end].width() as u16;
+                    buf.set_style(
+                        Rect::new(area.x + x_off, y, width, 1).intersection(area),
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    );
+                } else if selection.contains(&line_range.end) {
+                    // Show a selected line break as a single highlighted cell.
+                    let x_off = self.text[line_range.clone()].width() as u16;
+                    buf.set_style(
+                        Rect::new(area.x + x_off, y, 1, 1).intersection(area),
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    );
+                }
+            }
         }
     }
 
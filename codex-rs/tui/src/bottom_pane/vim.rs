@@ -0,0 +1,1346 @@
+//! Optional vi-style modal editing for the composer (`[tui] vim_mode`).
+//!
+//! `VimState` sits in front of the composer's [`TextArea`]. In insert mode it
+//! forwards keys untouched so the composer keeps its normal behaviour (paste
+//! bursts, popups, submit on Enter). In normal and visual mode it interprets
+//! keys as vi commands: counts, motions, operators with text objects, yank/put
+//! through the text area's kill buffer, undo/redo and `.` repeat.
+//!
+//! Keys the state machine does not understand in normal mode (Enter, Ctrl
+//! chords, a bare Esc) are forwarded so submitting, clearing and backtracking
+//! keep working.
+
+use std::ops::Range;
+
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::textarea::TextArea;
+
+/// Stand-in character for Ctrl-R (redo) in the pending key buffer.
+const CTRL_R: char = '\u{12}';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VimMode {
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl VimMode {
+    /// Label shown in the footer, mirroring vim's mode line.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "-- NORMAL --",
+            VimMode::Insert => "-- INSERT --",
+            VimMode::Visual => "-- VISUAL --",
+            VimMode::VisualLine => "-- VISUAL LINE --",
+        }
+    }
+
+    fn is_visual(self) -> bool {
+        matches!(self, VimMode::Visual | VimMode::VisualLine)
+    }
+}
+
+/// Whether the key was handled by the vi layer or should reach the composer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VimOutcome {
+    Consumed,
+    Forward,
+}
+
+#[derive(Debug)]
+pub(crate) struct VimState {
+    mode: VimMode,
+    /// Keys of the normal/visual command typed so far.
+    pending: Vec<KeyEvent>,
+    visual_anchor: usize,
+    /// Keys of a change that entered insert mode, recorded until Esc.
+    recording: Option<Vec<KeyEvent>>,
+    /// Keys replayed by `.`.
+    last_change: Vec<KeyEvent>,
+    /// Contents of the kill buffer when it was last filled by a linewise
+    /// operation; puts are linewise only while the buffer still matches.
+    linewise_register: Option<String>,
+    replaying: bool,
+}
+
+impl VimState {
+    /// Start in insert mode so the composer behaves as usual until Esc.
+    pub(crate) fn new() -> Self {
+        Self {
+            mode: VimMode::Insert,
+            pending: Vec::new(),
+            visual_anchor: 0,
+            recording: None,
+            last_change: Vec::new(),
+            linewise_register: None,
+            replaying: false,
+        }
+    }
+
+    pub(crate) fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Return to insert mode, e.g. after the composer submitted its text.
+    pub(crate) fn reset(&mut self, textarea: &mut TextArea) {
+        self.mode = VimMode::Insert;
+        self.pending.clear();
+        self.recording = None;
+        textarea.set_selection(None);
+    }
+
+    pub(crate) fn handle_key(&mut self, textarea: &mut TextArea, key: KeyEvent) -> VimOutcome {
+        if key.kind == KeyEventKind::Release {
+            return VimOutcome::Forward;
+        }
+        let outcome = match self.mode {
+            VimMode::Insert => self.handle_insert_key(textarea, key),
+            VimMode::Normal | VimMode::Visual | VimMode::VisualLine => {
+                self.handle_command_key(textarea, key)
+            }
+        };
+        textarea.set_selection(self.selection(textarea));
+        outcome
+    }
+
+    fn handle_insert_key(&mut self, textarea: &mut TextArea, key: KeyEvent) -> VimOutcome {
+        if key.code == KeyCode::Esc && key.modifiers == KeyModifiers::NONE {
+            if let Some(mut keys) = self.recording.take() {
+                keys.push(key);
+                self.last_change = keys;
+            }
+            self.mode = VimMode::Normal;
+            let text = textarea.text();
+            let pos = textarea.cursor();
+            if pos > line_start(text, pos) {
+                textarea.set_cursor(prev_grapheme(text, pos));
+            }
+            return VimOutcome::Consumed;
+        }
+        if let Some(keys) = &mut self.recording {
+            keys.push(key);
+        }
+        if self.replaying {
+            textarea.input(key);
+            return VimOutcome::Consumed;
+        }
+        VimOutcome::Forward
+    }
+
+    fn handle_command_key(&mut self, textarea: &mut TextArea, key: KeyEvent) -> VimOutcome {
+        let visual = self.mode.is_visual();
+        if command_char(&key, visual).is_none() {
+            let is_esc = key.code == KeyCode::Esc && key.modifiers == KeyModifiers::NONE;
+            let had_pending = !self.pending.is_empty();
+            self.pending.clear();
+            if is_esc && visual {
+                self.mode = VimMode::Normal;
+                return VimOutcome::Consumed;
+            }
+            if is_esc && had_pending {
+                return VimOutcome::Consumed;
+            }
+            return VimOutcome::Forward;
+        }
+
+        self.pending.push(key);
+        let chars: Vec<char> = self
+            .pending
+            .iter()
+            .filter_map(|key| command_char(key, visual))
+            .collect();
+        match parse(&chars, visual) {
+            Parsed::Incomplete => {}
+            Parsed::Invalid => self.pending.clear(),
+            Parsed::Command(command) => {
+                let keys = std::mem::take(&mut self.pending);
+                self.execute(textarea, command, keys);
+            }
+        }
+        VimOutcome::Consumed
+    }
+
+    fn execute(&mut self, textarea: &mut TextArea, command: Command, keys: Vec<KeyEvent>) {
+        let count = command.count.unwrap_or(1);
+        let is_change = command.action.is_change();
+        match command.action {
+            Action::Move(motion) => self.move_cursor(textarea, motion, count, command.count),
+            Action::Operate(operator, target) => {
+                if let Some((range, linewise)) =
+                    target_range(textarea, operator, target, count, command.count)
+                {
+                    self.apply_operator(textarea, operator, range, linewise);
+                }
+            }
+            Action::Put { before } => self.put(textarea, before, count),
+            Action::Insert(at) => self.enter_insert(textarea, at),
+            Action::Undo => (0..count).for_each(|_| textarea.undo()),
+            Action::Redo => (0..count).for_each(|_| textarea.redo()),
+            Action::Join => join_lines(textarea, count.max(2) - 1),
+            Action::ToggleCase => {
+                let text = textarea.text();
+                let start = textarea.cursor();
+                let end = advance_graphemes(text, start, count, line_end(text, start));
+                toggle_case(textarea, start..end);
+                textarea.set_cursor(end);
+            }
+            Action::Replace(ch) => replace_chars(textarea, ch, count),
+            Action::Visual(mode) => {
+                if self.mode == mode {
+                    self.mode = VimMode::Normal;
+                } else {
+                    if !self.mode.is_visual() {
+                        self.visual_anchor = textarea.cursor();
+                    }
+                    self.mode = mode;
+                }
+            }
+            Action::Repeat => self.repeat(textarea, command.count),
+            Action::VisualOperate(operator) => {
+                if let Some(range) = self.selection(textarea) {
+                    let linewise = self.mode == VimMode::VisualLine;
+                    let range = if linewise {
+                        range.start..line_end(textarea.text(), range.start.max(range.end - 1))
+                    } else {
+                        range
+                    };
+                    self.mode = VimMode::Normal;
+                    self.apply_operator(textarea, operator, range, linewise);
+                }
+            }
+            Action::VisualToggleCase => {
+                if let Some(range) = self.selection(textarea) {
+                    let start = range.start;
+                    toggle_case(textarea, range);
+                    textarea.set_cursor(start);
+                }
+                self.mode = VimMode::Normal;
+            }
+            Action::VisualSwap => {
+                let cursor = textarea.cursor();
+                textarea.set_cursor(self.visual_anchor);
+                self.visual_anchor = cursor;
+            }
+        }
+
+        if is_change && !self.replaying {
+            if self.mode == VimMode::Insert {
+                self.recording = Some(keys);
+            } else {
+                self.last_change = keys;
+            }
+        }
+        if self.mode != VimMode::Insert {
+            clamp_to_line(textarea);
+        }
+    }
+
+    fn move_cursor(
+        &mut self,
+        textarea: &mut TextArea,
+        motion: Motion,
+        count: usize,
+        explicit_count: Option<usize>,
+    ) {
+        match motion {
+            // Follow wrapped (visual) lines like the arrow keys do.
+            Motion::Up => (0..count).for_each(|_| textarea.move_cursor_up()),
+            Motion::Down => (0..count).for_each(|_| textarea.move_cursor_down()),
+            _ => {
+                if let Some((pos, _)) = resolve_motion(
+                    textarea.text(),
+                    textarea.cursor(),
+                    motion,
+                    count,
+                    explicit_count,
+                ) {
+                    textarea.set_cursor(pos);
+                }
+            }
+        }
+    }
+
+    fn apply_operator(
+        &mut self,
+        textarea: &mut TextArea,
+        operator: Operator,
+        range: Range<usize>,
+        linewise: bool,
+    ) {
+        let text = textarea.text();
+        let mut content = text[range.clone()].to_string();
+        if linewise {
+            content.push('\n');
+        }
+        match operator {
+            Operator::Yank => {
+                if !linewise {
+                    textarea.set_cursor(range.start);
+                }
+            }
+            Operator::Delete => {
+                if linewise {
+                    // Take one of the surrounding newlines with the lines.
+                    let delete = if range.end < text.len() {
+                        range.start..range.end + 1
+                    } else {
+                        range.start.saturating_sub(1)..range.end
+                    };
+                    textarea.kill_range(delete.clone());
+                    let text = textarea.text();
+                    let pos = delete.start.min(text.len());
+                    textarea.set_cursor(first_non_blank(text, line_start(text, pos)));
+                } else {
+                    textarea.kill_range(range.clone());
+                    textarea.set_cursor(range.start);
+                }
+            }
+            Operator::Change => {
+                textarea.kill_range(range.clone());
+                textarea.set_cursor(range.start);
+                self.mode = VimMode::Insert;
+            }
+        }
+        if content.is_empty() {
+            return;
+        }
+        self.linewise_register = linewise.then(|| content.clone());
+        textarea.set_kill_buffer(content);
+    }
+
+    fn put(&mut self, textarea: &mut TextArea, before: bool, count: usize) {
+        let content = textarea.kill_buffer().to_string();
+        if content.is_empty() {
+            return;
+        }
+        let text = textarea.text();
+        let cursor = textarea.cursor();
+        if self.linewise_register.as_deref() == Some(content.as_str()) {
+            let line = content.strip_suffix('\n').unwrap_or(&content);
+            let block = vec![line; count].join("\n");
+            if before {
+                let start = line_start(text, cursor);
+                textarea.insert_str_at(start, &format!("{block}\n"));
+                textarea.set_cursor(first_non_blank(textarea.text(), start));
+            } else {
+                let end = line_end(text, cursor);
+                textarea.insert_str_at(end, &format!("\n{block}"));
+                textarea.set_cursor(first_non_blank(textarea.text(), end + 1));
+            }
+        } else {
+            let block = content.repeat(count);
+            let at = if before || cursor >= line_end(text, cursor) {
+                cursor
+            } else {
+                next_grapheme(text, cursor)
+            };
+            textarea.insert_str_at(at, &block);
+            textarea.set_cursor(prev_grapheme(textarea.text(), at + block.len()));
+        }
+    }
+
+    fn enter_insert(&mut self, textarea: &mut TextArea, at: InsertAt) {
+        let text = textarea.text();
+        let cursor = textarea.cursor();
+        let pos = match at {
+            InsertAt::Cursor => cursor,
+            InsertAt::After => advance_graphemes(text, cursor, 1, line_end(text, cursor)),
+            InsertAt::FirstNonBlank => first_non_blank(text, line_start(text, cursor)),
+            InsertAt::LineEnd => line_end(text, cursor),
+            InsertAt::OpenBelow => {
+                let end = line_end(text, cursor);
+                textarea.insert_str_at(end, "\n");
+                end + 1
+            }
+            InsertAt::OpenAbove => {
+                let start = line_start(text, cursor);
+                textarea.insert_str_at(start, "\n");
+                start
+            }
+        };
+        textarea.set_cursor(pos);
+        self.mode = VimMode::Insert;
+    }
+
+    fn repeat(&mut self, textarea: &mut TextArea, count: Option<usize>) {
+        let mut keys = self.last_change.clone();
+        if let Some(count) = count {
+            // A count on `.` replaces the count of the repeated command.
+            let digits = keys
+                .iter()
+                .take_while(|key| matches!(key.code, KeyCode::Char(c) if c.is_ascii_digit()))
+                .count();
+            keys.splice(
+                0..digits,
+                count
+                    .to_string()
+                    .chars()
+                    .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)),
+            );
+        }
+        self.replaying = true;
+        for key in keys {
+            self.handle_key(textarea, key);
+        }
+        self.replaying = false;
+    }
+
+    /// Byte range covered by the visual selection, if any.
+    fn selection(&self, textarea: &TextArea) -> Option<Range<usize>> {
+        let text = textarea.text();
+        let anchor = self.visual_anchor.min(text.len());
+        let cursor = textarea.cursor();
+        let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+        match self.mode {
+            VimMode::Visual => Some(start..next_grapheme(text, end)),
+            VimMode::VisualLine => {
+                let end = line_end(text, end);
+                Some(line_start(text, start)..(end + 1).min(text.len()).max(end))
+            }
+            VimMode::Normal | VimMode::Insert => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordForward { big: bool },
+    WordBackward { big: bool },
+    WordEnd { big: bool },
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    FirstLine,
+    LastLine,
+    FindForward { ch: char, till: bool },
+    FindBackward { ch: char, till: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MotionKind {
+    Exclusive,
+    Inclusive,
+    Linewise,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Target {
+    Motion(Motion),
+    /// `dd`, `cc`, `yy`.
+    Line,
+    Object {
+        inner: bool,
+        kind: ObjectKind,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ObjectKind {
+    Word { big: bool },
+    Quote(char),
+    Pair(char, char),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InsertAt {
+    Cursor,
+    After,
+    FirstNonBlank,
+    LineEnd,
+    OpenBelow,
+    OpenAbove,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Move(Motion),
+    Operate(Operator, Target),
+    Put { before: bool },
+    Insert(InsertAt),
+    Undo,
+    Redo,
+    Join,
+    ToggleCase,
+    Replace(char),
+    Visual(VimMode),
+    Repeat,
+    VisualOperate(Operator),
+    VisualToggleCase,
+    VisualSwap,
+}
+
+impl Action {
+    /// Whether `.` should repeat this action.
+    fn is_change(self) -> bool {
+        match self {
+            Action::Operate(operator, _) => operator != Operator::Yank,
+            Action::Put { .. }
+            | Action::Insert(_)
+            | Action::Join
+            | Action::ToggleCase
+            | Action::Replace(_) => true,
+            Action::Move(_)
+            | Action::Undo
+            | Action::Redo
+            | Action::Visual(_)
+            | Action::Repeat
+            | Action::VisualOperate(_)
+            | Action::VisualToggleCase
+            | Action::VisualSwap => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Command {
+    count: Option<usize>,
+    action: Action,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Parsed<T> {
+    Incomplete,
+    Invalid,
+    Command(T),
+}
+
+/// Map a key to the character used by the command parser.
+fn command_char(key: &KeyEvent, visual: bool) -> Option<char> {
+    match key.code {
+        KeyCode::Char('r') if key.modifiers == KeyModifiers::CONTROL => Some(CTRL_R),
+        KeyCode::Char(c)
+            if key.modifiers == KeyModifiers::NONE || key.modifiers == KeyModifiers::SHIFT =>
+        {
+            Some(c)
+        }
+        // Arrow keys extend the selection in visual mode; elsewhere they reach
+        // the composer (e.g. for history navigation).
+        KeyCode::Left if visual => Some('h'),
+        KeyCode::Right if visual => Some('l'),
+        KeyCode::Up if visual => Some('k'),
+        KeyCode::Down if visual => Some('j'),
+        KeyCode::Home if visual => Some('0'),
+        KeyCode::End if visual => Some('$'),
+        _ => None,
+    }
+}
+
+/// Parse a leading count, ignoring a lone `0` (the line-start motion).
+fn parse_count(keys: &[char]) -> (Option<usize>, &[char]) {
+    if keys.first().is_none_or(|c| *c == '0') {
+        return (None, keys);
+    }
+    let digits = keys.iter().take_while(|c| c.is_ascii_digit()).count();
+    let count = keys[..digits]
+        .iter()
+        .collect::<String>()
+        .parse::<usize>()
+        .ok()
+        .map(|count| count.clamp(1, 10_000));
+    (count, &keys[digits..])
+}
+
+fn parse(keys: &[char], visual: bool) -> Parsed<Command> {
+    let (count, rest) = parse_count(keys);
+    let Some(&first) = rest.first() else {
+        return Parsed::Incomplete;
+    };
+    let command = |action| Parsed::Command(Command { count, action });
+
+    match parse_motion(rest) {
+        Parsed::Command(motion) => return command(Action::Move(motion)),
+        Parsed::Incomplete => return Parsed::Incomplete,
+        Parsed::Invalid => {}
+    }
+
+    let action = if visual {
+        match first {
+            'd' | 'x' => Action::VisualOperate(Operator::Delete),
+            'c' | 's' => Action::VisualOperate(Operator::Change),
+            'y' => Action::VisualOperate(Operator::Yank),
+            '~' => Action::VisualToggleCase,
+            'o' => Action::VisualSwap,
+            'v' => Action::Visual(VimMode::Visual),
+            'V' => Action::Visual(VimMode::VisualLine),
+            _ => return Parsed::Invalid,
+        }
+    } else {
+        let operate = |operator, motion| Action::Operate(operator, Target::Motion(motion));
+        match first {
+            'd' | 'c' | 'y' => {
+                let operator = match first {
+                    'd' => Operator::Delete,
+                    'c' => Operator::Change,
+                    _ => Operator::Yank,
+                };
+                return match parse_target(first, &rest[1..]) {
+                    Parsed::Command((target_count, target)) => Parsed::Command(Command {
+                        count: match (count, target_count) {
+                            (Some(a), Some(b)) => Some(a.saturating_mul(b)),
+                            (a, b) => a.or(b),
+                        },
+                        action: Action::Operate(operator, target),
+                    }),
+                    Parsed::Incomplete => Parsed::Incomplete,
+                    Parsed::Invalid => Parsed::Invalid,
+                };
+            }
+            'x' => operate(Operator::Delete, Motion::Right),
+            'X' => operate(Operator::Delete, Motion::Left),
+            's' => operate(Operator::Change, Motion::Right),
+            'D' => operate(Operator::Delete, Motion::LineEnd),
+            'C' => operate(Operator::Change, Motion::LineEnd),
+            'S' => Action::Operate(Operator::Change, Target::Line),
+            'Y' => Action::Operate(Operator::Yank, Target::Line),
+            'p' => Action::Put { before: false },
+            'P' => Action::Put { before: true },
+            'i' => Action::Insert(InsertAt::Cursor),
+            'a' => Action::Insert(InsertAt::After),
+            'I' => Action::Insert(InsertAt::FirstNonBlank),
+            'A' => Action::Insert(InsertAt::LineEnd),
+            'o' => Action::Insert(InsertAt::OpenBelow),
+            'O' => Action::Insert(InsertAt::OpenAbove),
+            'u' => Action::Undo,
+            CTRL_R => Action::Redo,
+            'J' => Action::Join,
+            '~' => Action::ToggleCase,
+            'r' => match rest.get(1) {
+                Some(&ch) => Action::Replace(ch),
+                None => return Parsed::Incomplete,
+            },
+            'v' => Action::Visual(VimMode::Visual),
+            'V' => Action::Visual(VimMode::VisualLine),
+            '.' => Action::Repeat,
+            _ => return Parsed::Invalid,
+        }
+    };
+    command(action)
+}
+
+/// Parse what follows an operator: an optional count, then a doubled
+/// operator (`dd`), a text object (`diw`) or a motion (`d2w`).
+fn parse_target(operator: char, keys: &[char]) -> Parsed<(Option<usize>, Target)> {
+    let (count, rest) = parse_count(keys);
+    let Some(&first) = rest.first() else {
+        return Parsed::Incomplete;
+    };
+    if first == operator {
+        return Parsed::Command((count, Target::Line));
+    }
+    if first == 'i' || first == 'a' {
+        let Some(&object) = rest.get(1) else {
+            return Parsed::Incomplete;
+        };
+        let kind = match object {
+            'w' => ObjectKind::Word { big: false },
+            'W' => ObjectKind::Word { big: true },
+            '"' | '\'' | '`' => ObjectKind::Quote(object),
+            '(' | ')' | 'b' => ObjectKind::Pair('(', ')'),
+            '[' | ']' => ObjectKind::Pair('[', ']'),
+            '{' | '}' | 'B' => ObjectKind::Pair('{', '}'),
+            '<' | '>' => ObjectKind::Pair('<', '>'),
+            _ => return Parsed::Invalid,
+        };
+        return Parsed::Command((
+            count,
+            Target::Object {
+                inner: first == 'i',
+                kind,
+            },
+        ));
+    }
+    match parse_motion(rest) {
+        Parsed::Command(motion) => Parsed::Command((count, Target::Motion(motion))),
+        Parsed::Incomplete => Parsed::Incomplete,
+        Parsed::Invalid => Parsed::Invalid,
+    }
+}
+
+fn parse_motion(keys: &[char]) -> Parsed<Motion> {
+    let Some(&first) = keys.first() else {
+        return Parsed::Incomplete;
+    };
+    let motion = match first {
+        'h' => Motion::Left,
+        'l' | ' ' => Motion::Right,
+        'j' => Motion::Down,
+        'k' => Motion::Up,
+        'w' | 'W' => Motion::WordForward { big: first == 'W' },
+        'b' | 'B' => Motion::WordBackward { big: first == 'B' },
+        'e' | 'E' => Motion::WordEnd { big: first == 'E' },
+        '0' => Motion::LineStart,
+        '^' => Motion::FirstNonBlank,
+        '$' => Motion::LineEnd,
+        'G' => Motion::LastLine,
+        'g' => match keys.get(1) {
+            Some('g') => Motion::FirstLine,
+            Some(_) => return Parsed::Invalid,
+            None => return Parsed::Incomplete,
+        },
+        'f' | 't' | 'F' | 'T' => {
+            let Some(&ch) = keys.get(1) else {
+                return Parsed::Incomplete;
+            };
+            let till = matches!(first, 't' | 'T');
+            if first.is_lowercase() {
+                Motion::FindForward { ch, till }
+            } else {
+                Motion::FindBackward { ch, till }
+            }
+        }
+        _ => return Parsed::Invalid,
+    };
+    Parsed::Command(motion)
+}
+
+/// Resolve the range an operator acts on, and whether it is linewise.
+fn target_range(
+    textarea: &TextArea,
+    operator: Operator,
+    target: Target,
+    count: usize,
+    explicit_count: Option<usize>,
+) -> Option<(Range<usize>, bool)> {
+    let text = textarea.text();
+    let cursor = textarea.cursor();
+    match target {
+        Target::Line => {
+            let mut end = line_end(text, cursor);
+            for _ in 1..count {
+                if end >= text.len() {
+                    break;
+                }
+                end = line_end(text, end + 1);
+            }
+            Some((line_start(text, cursor)..end, true))
+        }
+        Target::Object { inner, kind } => {
+            text_object(text, cursor, inner, kind).map(|range| (range, false))
+        }
+        Target::Motion(motion) => {
+            let on_word = text[cursor..]
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_whitespace());
+            let (pos, kind) = match motion {
+                // `cw` changes to the end of the word, like `ce`.
+                Motion::WordForward { big } if operator == Operator::Change && on_word => {
+                    let mut pos = cursor;
+                    for i in 0..count {
+                        // A word end under the cursor only counts on the first step.
+                        let at_end = i == 0 && is_word_end(text, pos, big);
+                        pos = if at_end {
+                            pos
+                        } else {
+                            word_end(text, pos, big)
+                        };
+                    }
+                    (pos, MotionKind::Inclusive)
+                }
+                Motion::WordForward { big } => {
+                    // Do not let `dw` on the last word of a line eat the newline.
+                    let mut pos = cursor;
+                    for _ in 0..count {
+                        let next = next_word_start(text, pos, big);
+                        let end = line_end(text, pos);
+                        if next > end && end > pos {
+                            pos = end;
+                            break;
+                        }
+                        pos = next;
+                    }
+                    (pos, MotionKind::Exclusive)
+                }
+                _ => resolve_motion(text, cursor, motion, count, explicit_count)?,
+            };
+            let (start, end) = (cursor.min(pos), cursor.max(pos));
+            match kind {
+                MotionKind::Exclusive => Some((start..end, false)),
+                MotionKind::Inclusive => Some((start..next_grapheme(text, end), false)),
+                MotionKind::Linewise => Some((line_start(text, start)..line_end(text, end), true)),
+            }
+        }
+    }
+}
+
+/// Where `motion` takes the cursor, and how an operator should treat it.
+fn resolve_motion(
+    text: &str,
+    cursor: usize,
+    motion: Motion,
+    count: usize,
+    explicit_count: Option<usize>,
+) -> Option<(usize, MotionKind)> {
+    let start = line_start(text, cursor);
+    let end = line_end(text, cursor);
+    let result = match motion {
+        Motion::Left => {
+            let mut pos = cursor;
+            for _ in 0..count {
+                if pos <= start {
+                    break;
+                }
+                pos = prev_grapheme(text, pos);
+            }
+            (pos, MotionKind::Exclusive)
+        }
+        Motion::Right => (
+            advance_graphemes(text, cursor, count, end),
+            MotionKind::Exclusive,
+        ),
+        Motion::Down => {
+            let mut pos = end;
+            for _ in 0..count {
+                if pos >= text.len() {
+                    return None;
+                }
+                pos = line_end(text, pos + 1);
+            }
+            (pos, MotionKind::Linewise)
+        }
+        Motion::Up => {
+            let mut pos = start;
+            for _ in 0..count {
+                if pos == 0 {
+                    return None;
+                }
+                pos = line_start(text, pos - 1);
+            }
+            (pos, MotionKind::Linewise)
+        }
+        Motion::WordForward { big } => (
+            repeat_motion(count, cursor, |pos| next_word_start(text, pos, big)),
+            MotionKind::Exclusive,
+        ),
+        Motion::WordBackward { big } => (
+            repeat_motion(count, cursor, |pos| prev_word_start(text, pos, big)),
+            MotionKind::Exclusive,
+        ),
+        Motion::WordEnd { big } => (
+            repeat_motion(count, cursor, |pos| word_end(text, pos, big)),
+            MotionKind::Inclusive,
+        ),
+        Motion::LineStart => (start, MotionKind::Exclusive),
+        Motion::FirstNonBlank => (first_non_blank(text, start), MotionKind::Exclusive),
+        Motion::LineEnd => {
+            let mut pos = end;
+            for _ in 1..count {
+                if pos >= text.len() {
+                    break;
+                }
+                pos = line_end(text, pos + 1);
+            }
+            (pos, MotionKind::Exclusive)
+        }
+        Motion::FirstLine | Motion::LastLine => {
+            let last = text.matches('\n').count() + 1;
+            let line = match (motion, explicit_count) {
+                (_, Some(line)) => line.min(last),
+                (Motion::FirstLine, None) => 1,
+                _ => last,
+            };
+            let line_pos = text
+                .match_indices('\n')
+                .nth(line.saturating_sub(2))
+                .map_or(0, |(i, _)| if line > 1 { i + 1 } else { 0 });
+            (first_non_blank(text, line_pos), MotionKind::Linewise)
+        }
+        Motion::FindForward { ch, till } => {
+            let search_from = next_grapheme(text, cursor).min(end);
+            let (offset, _) = text[search_from..end].match_indices(ch).nth(count - 1)?;
+            let pos = search_from + offset;
+            let pos = if till { prev_grapheme(text, pos) } else { pos };
+            (pos, MotionKind::Inclusive)
+        }
+        Motion::FindBackward { ch, till } => {
+            let (offset, found) = text[start..cursor].rmatch_indices(ch).nth(count - 1)?;
+            let pos = start + offset;
+            let pos = if till { pos + found.len() } else { pos };
+            (pos, MotionKind::Exclusive)
+        }
+    };
+    Some(result)
+}
+
+fn repeat_motion(count: usize, start: usize, step: impl Fn(usize) -> usize) -> usize {
+    (0..count).fold(start, |pos, _| step(pos))
+}
+
+/// Byte range of a text object around `cursor`.
+fn text_object(text: &str, cursor: usize, inner: bool, kind: ObjectKind) -> Option<Range<usize>> {
+    let start = line_start(text, cursor);
+    let end = line_end(text, cursor);
+    match kind {
+        ObjectKind::Word { big } => {
+            if start == end {
+                return None;
+            }
+            let cursor = cursor.min(prev_grapheme(text, end));
+            let class_at = |pos: usize| text[pos..].chars().next().map(|c| char_class(c, big));
+            let class = class_at(cursor)?;
+            let run_start = |from: usize, class: u8| {
+                let mut pos = from;
+                while pos > start && class_at(prev_grapheme(text, pos)) == Some(class) {
+                    pos = prev_grapheme(text, pos);
+                }
+                pos
+            };
+            let run_end = |from: usize, class: u8| {
+                let mut pos = from;
+                while pos < end && class_at(pos) == Some(class) {
+                    pos = next_grapheme(text, pos);
+                }
+                pos
+            };
+            let word_start = run_start(cursor, class);
+            let word_end = run_end(cursor, class);
+            if inner {
+                return Some(word_start..word_end);
+            }
+            if class == 0 {
+                // Around whitespace: the blanks plus the following word.
+                let next_class = class_at(word_end).filter(|_| word_end < end);
+                return Some(word_start..next_class.map_or(word_end, |c| run_end(word_end, c)));
+            }
+            let trailing = run_end(word_end, 0);
+            if trailing > word_end {
+                Some(word_start..trailing)
+            } else {
+                Some(run_start(word_start, 0)..word_end)
+            }
+        }
+        ObjectKind::Quote(quote) => {
+            let quotes: Vec<usize> = text[start..end]
+                .match_indices(quote)
+                .map(|(i, _)| start + i)
+                .filter(|&i| !text[..i].ends_with('\\'))
+                .collect();
+            let (open, close) = quotes
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .find(|&(open, close)| cursor <= close || open > cursor)?;
+            Some(if inner {
+                open + 1..close
+            } else {
+                open..close + 1
+            })
+        }
+        ObjectKind::Pair(open_ch, close_ch) => {
+            let under = text[cursor..].chars().next();
+            let scan_end = cursor + under.map_or(0, char::len_utf8);
+            let mut depth = 0usize;
+            let mut open = None;
+            for (i, c) in text[..scan_end].char_indices().rev() {
+                if c == close_ch && i != cursor {
+                    depth += 1;
+                } else if c == open_ch {
+                    if depth == 0 {
+                        open = Some(i);
+                        break;
+                    }
+                    depth -= 1;
+                }
+            }
+            let open = open?;
+            let mut depth = 0usize;
+            let mut close = None;
+            for (i, c) in text[open + 1..].char_indices() {
+                if c == open_ch {
+                    depth += 1;
+                } else if c == close_ch {
+                    if depth == 0 {
+                        close = Some(open + 1 + i);
+                        break;
+                    }
+                    depth -= 1;
+                }
+            }
+            let close = close?;
+            Some(if inner {
+                open + 1..close
+            } else {
+                open..close + 1
+            })
+        }
+    }
+}
+
+fn join_lines(textarea: &mut TextArea, times: usize) {
+    for _ in 0..times {
+        let text = textarea.text();
+        let cursor = textarea.cursor();
+        let end = line_end(text, cursor);
+        if end >= text.len() {
+            break;
+        }
+        let next_start = end + 1;
+        let blank_end = next_start
+            + (text[next_start..].len() - text[next_start..].trim_start_matches([' ', '\t']).len());
+        let next_is_empty = blank_end == line_end(text, blank_end);
+        let separator = if next_is_empty
+            || text[..end].ends_with([' ', '\t'])
+            || end == line_start(text, end)
+        {
+            ""
+        } else {
+            " "
+        };
+        textarea.replace_range(end..blank_end, separator);
+        textarea.set_cursor(end);
+    }
+}
+
+fn toggle_case(textarea: &mut TextArea, range: Range<usize>) {
+    if range.is_empty() {
+        return;
+    }
+    let toggled: String = textarea.text()[range.clone()]
+        .chars()
+        .flat_map(|c| {
+            if c.is_uppercase() {
+                c.to_lowercase().collect::<Vec<_>>()
+            } else {
+                c.to_uppercase().collect::<Vec<_>>()
+            }
+        })
+        .collect();
+    textarea.replace_range(range, &toggled);
+}
+
+fn replace_chars(textarea: &mut TextArea, ch: char, count: usize) {
+    let text = textarea.text();
+    let start = textarea.cursor();
+    let end = line_end(text, start);
+    if text[start..end].graphemes(true).count() < count {
+        return;
+    }
+    let replaced_end = advance_graphemes(text, start, count, end);
+    let replacement = ch.to_string().repeat(count);
+    textarea.replace_range(start..replaced_end, &replacement);
+    textarea.set_cursor(start + replacement.len() - ch.len_utf8());
+}
+
+/// Keep the cursor on a character: normal mode never rests on a line break
+/// unless the line is empty.
+fn clamp_to_line(textarea: &mut TextArea) {
+    let text = textarea.text();
+    let pos = textarea.cursor();
+    let end = line_end(text, pos);
+    if pos >= end && end > line_start(text, pos) {
+        textarea.set_cursor(prev_grapheme(text, end));
+    }
+}
+
+/// 0 for whitespace, 1 for word characters, 2 for punctuation. With `big`,
+/// every non-blank character is a word character.
+fn char_class(c: char, big: bool) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if big || c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn next_word_start(text: &str, pos: usize, big: bool) -> usize {
+    let mut chars = text[pos..]
+        .char_indices()
+        .map(|(i, c)| (pos + i, c))
+        .peekable();
+    let Some(&(_, first)) = chars.peek() else {
+        return pos;
+    };
+    let class = char_class(first, big);
+    if class != 0 {
+        while chars
+            .next_if(|&(_, c)| char_class(c, big) == class)
+            .is_some()
+        {}
+    }
+    chars
+        .find(|&(_, c)| !c.is_whitespace())
+        .map_or(text.len(), |(i, _)| i)
+}
+
+fn prev_word_start(text: &str, pos: usize, big: bool) -> usize {
+    let mut chars = text[..pos].char_indices().rev().peekable();
+    while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+    let Some(&(mut start, first)) = chars.peek() else {
+        return 0;
+    };
+    let class = char_class(first, big);
+    while let Some((i, _)) = chars.next_if(|&(_, c)| char_class(c, big) == class) {
+        start = i;
+    }
+    start
+}
+
+fn word_end(text: &str, pos: usize, big: bool) -> usize {
+    let mut chars = text[pos..]
+        .char_indices()
+        .map(|(i, c)| (pos + i, c))
+        .peekable();
+    chars.next();
+    while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+    let Some(&(mut end, first)) = chars.peek() else {
+        return pos;
+    };
+    let class = char_class(first, big);
+    while let Some((i, _)) = chars.next_if(|&(_, c)| char_class(c, big) == class) {
+        end = i;
+    }
+    end
+}
+
+fn is_word_end(text: &str, pos: usize, big: bool) -> bool {
+    let mut chars = text[pos..].chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), next) => {
+            let class = char_class(c, big);
+            class != 0 && next.is_none_or(|n| char_class(n, big) != class)
+        }
+        (None, _) => false,
+    }
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |i| pos + i)
+}
+
+fn first_non_blank(text: &str, line_start: usize) -> usize {
+    let end = line_end(text, line_start);
+    let line = &text[line_start..end];
+    line_start + (line.len() - line.trim_start_matches([' ', '\t']).len())
+}
+
+fn prev_grapheme(text: &str, pos: usize) -> usize {
+    text[..pos]
+        .graphemes(true)
+        .next_back()
+        .map_or(0, |g| pos - g.len())
+}
+
+fn next_grapheme(text: &str, pos: usize) -> usize {
+    text[pos..]
+        .graphemes(true)
+        .next()
+        .map_or(pos, |g| pos + g.len())
+}
+
+/// Move `count` graphemes right from `pos` without passing `limit`.
+fn advance_graphemes(text: &str, pos: usize, count: usize, limit: usize) -> usize {
+    text[pos..limit.max(pos)]
+        .graphemes(true)
+        .take(count)
+        .fold(pos, |pos, g| pos + g.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Build a text area in normal mode with the cursor at the `|` marker.
+    fn setup(text_with_cursor: &str) -> (VimState, TextArea) {
+        let cursor = text_with_cursor.find('|').expect("cursor marker");
+        let text = text_with_cursor.replacen('|', "", 1);
+        let mut textarea = TextArea::new();
+        textarea.set_text_clearing_elements(&text);
+        textarea.set_cursor(cursor);
+        let mut vim = VimState::new();
+        vim.mode = VimMode::Normal;
+        (vim, textarea)
+    }
+
+    fn feed(vim: &mut VimState, textarea: &mut TextArea, keys: &str) {
+        for c in keys.chars() {
+            let key = match c {
+                '\x1b' => KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+                CTRL_R => KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+                c => KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+            };
+            if vim.handle_key(textarea, key) == VimOutcome::Forward {
+                textarea.input(key);
+            }
+        }
+    }
+
+    fn state(textarea: &TextArea) -> String {
+        let mut text = textarea.text().to_string();
+        text.insert(textarea.cursor(), '|');
+        text
+    }
+
+    fn run(start: &str, keys: &str) -> String {
+        let (mut vim, mut textarea) = setup(start);
+        feed(&mut vim, &mut textarea, keys);
+        state(&textarea)
+    }
+
+    #[test]
+    fn motions_move_the_cursor() {
+        assert_eq!(run("|foo bar.baz qux", "w"), "foo |bar.baz qux");
+        assert_eq!(run("|foo bar.baz qux", "ww"), "foo bar|.baz qux");
+        assert_eq!(run("|foo bar.baz qux", "2W"), "foo bar.baz |qux");
+        assert_eq!(run("foo bar.baz |qux", "b"), "foo bar.|baz qux");
+        assert_eq!(run("|foo bar", "e"), "fo|o bar");
+        assert_eq!(run("  foo b|ar", "^"), "  |foo bar");
+        assert_eq!(run("  foo b|ar", "0"), "|  foo bar");
+        assert_eq!(run("|foo bar", "$"), "foo ba|r");
+        assert_eq!(run("|a,b,c", "2f,"), "a,b|,c");
+        assert_eq!(run("|a,b,c", "t,"), "|a,b,c");
+        assert_eq!(run("a,b,|c", "F,"), "a,b|,c");
+        assert_eq!(run("one\ntwo\nth|ree", "gg"), "|one\ntwo\nthree");
+        assert_eq!(run("|one\n  two\nthree", "2G"), "one\n  |two\nthree");
+        assert_eq!(run("|one\ntwo", "3l"), "on|e\ntwo");
+    }
+
+    #[test]
+    fn operators_combine_with_motions_and_counts() {
+        assert_eq!(run("|foo bar baz", "dw"), "|bar baz");
+        assert_eq!(run("|foo bar baz", "2dw"), "|baz");
+        assert_eq!(run("|foo bar baz", "d2w"), "|baz");
+        assert_eq!(run("foo |bar\nbaz", "dw"), "foo| \nbaz");
+        assert_eq!(run("foo b|ar baz", "d$"), "foo |b");
+        assert_eq!(run("foo b|ar baz", "D"), "foo |b");
+        assert_eq!(run("a|bcd", "x"), "a|cd");
+        assert_eq!(run("a|bcd", "3x"), "|a");
+        assert_eq!(run("ab|cd", "X"), "a|cd");
+        assert_eq!(run("|a,b,c", "dt,"), "|,b,c");
+        assert_eq!(run("|a,b,c", "df,"), "|b,c");
+    }
+
+    #[test]
+    fn linewise_operators_use_whole_lines() {
+        assert_eq!(run("one\nt|wo\nthree", "dd"), "one\n|three");
+        assert_eq!(run("one\ntwo\nthr|ee", "dd"), "one\n|two");
+        assert_eq!(run("o|ne\ntwo\nthree", "2dd"), "|three");
+        assert_eq!(run("o|ne\ntwo\nthree", "dj"), "|three");
+        assert_eq!(run("one\ntw|o", "yyP"), "one\n|two\ntwo");
+        assert_eq!(run("o|ne\ntwo", "yyjp"), "one\ntwo\n|one");
+        assert_eq!(run("one\nt|wo\nthree", "ddp"), "one\nthree\n|two");
+    }
+
+    #[test]
+    fn text_objects_select_words_quotes_and_pairs() {
+        assert_eq!(run("foo b|ar baz", "diw"), "foo | baz");
+        assert_eq!(run("foo b|ar baz", "daw"), "foo |baz");
+        assert_eq!(run("foo b|az", "daw"), "fo|o");
+        assert_eq!(run("say \"he|llo\" now", "di\""), "say \"|\" now");
+        assert_eq!(run("say \"he|llo\" now", "da\""), "say | now");
+        assert_eq!(run("f(a, (b|), c)", "di("), "f(a, (|), c)");
+        assert_eq!(run("f(a, (b), |c)", "dib"), "f(|)");
+        assert_eq!(run("x = {\n  |a\n}", "da{"), "x =| ");
+    }
+
+    #[test]
+    fn change_enters_insert_mode_and_dot_repeats_it() {
+        let (mut vim, mut textarea) = setup("|foo foo foo");
+        feed(&mut vim, &mut textarea, "cwbar\x1b");
+        assert_eq!(state(&textarea), "ba|r foo foo");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        feed(&mut vim, &mut textarea, "w.");
+        assert_eq!(state(&textarea), "bar ba|r foo");
+        feed(&mut vim, &mut textarea, "w.");
+        assert_eq!(state(&textarea), "bar bar ba|r");
+    }
+
+    #[test]
+    fn dot_repeats_deletes_with_new_count() {
+        let (mut vim, mut textarea) = setup("|abcdefgh");
+        feed(&mut vim, &mut textarea, "2x.");
+        assert_eq!(state(&textarea), "|efgh");
+        feed(&mut vim, &mut textarea, "3.");
+        assert_eq!(state(&textarea), "|h");
+    }
+
+    #[test]
+    fn insert_commands_position_the_cursor() {
+        assert_eq!(run("  fo|o", "Ix\x1b"), "  |xfoo");
+        assert_eq!(run("f|oo", "Ax\x1b"), "foo|x");
+        assert_eq!(run("f|oo", "ax\x1b"), "fo|xo");
+        assert_eq!(run("f|oo\nbar", "ox\x1b"), "foo\n|x\nbar");
+        assert_eq!(run("foo\nb|ar", "Ox\x1b"), "foo\n|x\nbar");
+    }
+
+    #[test]
+    fn yank_and_put_share_the_kill_buffer() {
+        let (mut vim, mut textarea) = setup("|foo bar");
+        feed(&mut vim, &mut textarea, "yw$p");
+        assert_eq!(state(&textarea), "foo barfoo| ");
+        assert_eq!(textarea.kill_buffer(), "foo ");
+
+        // Text killed with emacs bindings in insert mode is put charwise.
+        let (mut vim, mut textarea) = setup("one\n|two");
+        feed(&mut vim, &mut textarea, "yyi");
+        textarea.set_kill_buffer("x".to_string());
+        feed(&mut vim, &mut textarea, "\x1bP");
+        assert_eq!(state(&textarea), "one\n|xtwo");
+    }
+
+    #[test]
+    fn visual_mode_operates_on_the_selection() {
+        let (mut vim, mut textarea) = setup("foo |bar baz");
+        feed(&mut vim, &mut textarea, "ve");
+        assert_eq!(vim.mode(), VimMode::Visual);
+        assert_eq!(vim.selection(&textarea), Some(4..7));
+        feed(&mut vim, &mut textarea, "d");
+        assert_eq!(state(&textarea), "foo | baz");
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        assert_eq!(run("one\nt|wo\nthree", "Vjd"), "|one");
+        assert_eq!(run("foo |bar", "vlly$p"), "foo barba|r");
+        assert_eq!(run("foo |bar", "vl~"), "foo |BAr");
+        assert_eq!(run("foo |bar", "vlcX\x1b"), "foo |Xr");
+    }
+
+    #[test]
+    fn join_toggle_replace_and_undo() {
+        assert_eq!(run("o|ne\n   two\nthree", "J"), "one| two\nthree");
+        assert_eq!(run("o|ne\ntwo\nthree", "3J"), "one two| three");
+        assert_eq!(run("|abc", "2~"), "AB|c");
+        assert_eq!(run("|abc", "2rx"), "x|xc");
+        assert_eq!(run("|abc", "xxu"), "|bc");
+        assert_eq!(run("|abc", &format!("xxuu{CTRL_R}")), "|bc");
+    }
+
+    #[test]
+    fn normal_mode_forwards_unhandled_keys() {
+        let (mut vim, mut textarea) = setup("|foo");
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(vim.handle_key(&mut textarea, enter), VimOutcome::Forward);
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(vim.handle_key(&mut textarea, esc), VimOutcome::Forward);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(vim.handle_key(&mut textarea, ctrl_c), VimOutcome::Forward);
+
+        // Esc cancels a pending operator instead of reaching the composer.
+        feed(&mut vim, &mut textarea, "d");
+        assert_eq!(vim.handle_key(&mut textarea, esc), VimOutcome::Consumed);
+        feed(&mut vim, &mut textarea, "w");
+        assert_eq!(state(&textarea), "fo|o");
+    }
+}
@@ -32,14 +32,20 @@ use std::time::Instant;
 use crate::version::CODEX_CLI_VERSION;
 use codex_backend_client::Client as BackendClient;
 use codex_chatgpt::connectors;
+use codex_core::approval_memory::list_remembered_approvals;
+use codex_core::approval_memory::project_root;
 use codex_core::config::Config;
 use codex_core::config::ConstraintResult;
 use codex_core::config::types::Notifications;
+use codex_core::config::types::PlanArtifacts;
 use codex_core::features::FEATURES;
 use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
 use codex_core::models_manager::manager::ModelsManager;
+use codex_core::plan_artifacts::SavedPlan;
+use codex_core::plan_artifacts::list_saved_plans;
+use codex_core::plan_artifacts::plans_dir;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
@@ -50,6 +56,7 @@ use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
 use codex_core::protocol::CodexErrorInfo;
+use codex_core::protocol::CollaborationModeUpdatedEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -71,6 +78,8 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PinnedMessage;
+use codex_core::protocol::PinnedMessagesUpdatedEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
@@ -131,6 +140,8 @@ const PLAN_IMPLEMENTATION_TITLE: &str = "Implement this plan?";
 const PLAN_IMPLEMENTATION_YES: &str = "Yes, implement this plan";
 const PLAN_IMPLEMENTATION_NO: &str = "No, stay in Plan mode";
 const PLAN_IMPLEMENTATION_CODING_MESSAGE: &str = "Implement the plan.";
+const PLAN_IMPLEMENTATION_STEPS: &str = "Implement step by step";
+const PLAN_IMPLEMENTATION_EDIT: &str = "Edit the plan first";
 
 use crate::app_event::AppEvent;
 use crate::app_event::ConnectorsSnapshot;
@@ -173,6 +184,8 @@ use crate::history_cell::PlainHistoryCell;
 use crate::history_cell::WebSearchCell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crate::markdown::append_markdown;
 use crate::render::Insets;
 use crate::render::renderable::ColumnRenderable;
@@ -183,6 +196,8 @@ use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
 use crate::text_formatting::truncate_text;
+use crate::theme;
+use crate::theme::TuiTheme;
 use crate::tui::FrameRequester;
 mod interrupts;
 use self::interrupts::InterruptManager;
@@ -215,6 +230,7 @@ use codex_protocol::openai_models::InputModality;
 use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_symbol_index::Symbol;
 use strum::IntoEnumIterator;
 
 const USER_SHELL_COMMAND_HELP_TITLE: &str = "Prefix a command with ! to run it locally";
@@ -435,6 +451,14 @@ fn rate_limit_error_kind(info: &CodexErrorInfo) -> Option<RateLimitErrorKind> {
     }
 }
 
+/// A saved plan being executed one step per turn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PlanRun {
+    path: PathBuf,
+    /// Step whose turn is in flight.
+    running_step: Option<usize>,
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub(crate) enum ExternalEditorState {
     #[default]
@@ -525,6 +549,10 @@ pub(crate) struct ChatWidget {
     retry_status_header: Option<String>,
     thread_id: Option<ThreadId>,
     thread_name: Option<String>,
+    // Messages pinned to the thread so they survive compaction verbatim.
+    pinned_messages: Vec<PinnedMessage>,
+    // Ids of the user and agent messages of this session, oldest first, which /pin can refer to.
+    pinnable_message_ids: Vec<String>,
     forked_from: Option<ThreadId>,
     frame_requester: FrameRequester,
     // Whether to include the initial welcome banner on session configured
@@ -567,6 +595,10 @@ pub(crate) struct ChatWidget {
     plan_delta_buffer: String,
     // True while a plan item is streaming.
     plan_item_active: bool,
+    // Most recent proposed plan saved to disk this session.
+    saved_plan_path: Option<PathBuf>,
+    // Saved plan being executed step by step, if any.
+    plan_run: Option<PlanRun>,
     // Status-indicator elapsed seconds captured at the last emitted final-message separator.
     //
     // This lets the separator show per-chunk work time (since the previous separator) rather than
@@ -854,6 +886,66 @@ impl ChatWidget {
         }
     }
 
+    fn on_pinned_messages_updated(&mut self, event: PinnedMessagesUpdatedEvent, from_replay: bool) {
+        let previous_len = self.pinned_messages.len();
+        self.pinned_messages = event.pinned_messages;
+        if from_replay {
+            return;
+        }
+        let message = if self.pinned_messages.len() > previous_len {
+            "Message pinned; it will be kept verbatim across compactions."
+        } else {
+            "Message unpinned."
+        };
+        self.add_info_message(
+            message.to_string(),
+            Some("Type /unpin to list pinned messages.".to_string()),
+        );
+    }
+
+    /// Pins the `n`-th most recent user or agent message of this session (1 being the latest).
+    fn pin_recent_message(&mut self, n: usize) {
+        let item_id = self
+            .pinnable_message_ids
+            .len()
+            .checked_sub(n)
+            .and_then(|index| self.pinnable_message_ids.get(index));
+        let Some(item_id) = item_id else {
+            self.add_error_message(format!(
+                "No message to pin: this session has {} message(s).",
+                self.pinnable_message_ids.len()
+            ));
+            return;
+        };
+        let item_id = item_id.clone();
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::PinMessage { item_id }));
+    }
+
+    fn show_pinned_messages(&mut self) {
+        if self.pinned_messages.is_empty() {
+            self.add_info_message(
+                "No pinned messages.".to_string(),
+                Some(
+                    "Use /pin [n] to pin your n-th most recent message so it survives compaction."
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+        let listing = self
+            .pinned_messages
+            .iter()
+            .enumerate()
+            .map(|(idx, pinned)| format!("{}. {}", idx + 1, pinned.text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.add_info_message(
+            format!("Pinned messages:\n{listing}"),
+            Some("Use /unpin <number> to remove a pinned message.".to_string()),
+        );
+    }
+
     fn set_skills(&mut self, skills: Option<Vec<SkillMetadata>>) {
         self.bottom_pane.set_skills(skills);
     }
@@ -953,13 +1045,21 @@ impl ChatWidget {
         self.request_redraw();
     }
 
-    fn on_plan_item_completed(&mut self, text: String) {
+    fn on_plan_item_completed(&mut self, text: String, from_replay: bool) {
         let streamed_plan = self.plan_delta_buffer.trim().to_string();
         let plan_text = if text.trim().is_empty() {
             streamed_plan
         } else {
             text
         };
+        if !from_replay
+            && self.config.plan_artifacts != PlanArtifacts::None
+            && !plan_text.trim().is_empty()
+        {
+            self.app_event_tx.send(AppEvent::SaveProposedPlan {
+                plan: plan_text.clone(),
+            });
+        }
         self.plan_delta_buffer.clear();
         self.plan_item_active = false;
         self.saw_plan_item_this_turn = true;
@@ -1075,6 +1175,9 @@ impl ChatWidget {
         self.clear_unified_exec_processes();
         self.request_redraw();
 
+        if !from_replay {
+            self.checkpoint_running_plan_step();
+        }
         if !from_replay && self.queued_user_messages.is_empty() {
             self.maybe_prompt_plan_implementation();
         }
@@ -1121,7 +1224,8 @@ impl ChatWidget {
     }
 
     fn open_plan_implementation_prompt(&mut self) {
-        let default_mask = collaboration_modes::default_mode_mask(self.models_manager.as_ref());
+        let default_mask =
+            collaboration_modes::default_mode_mask(self.models_manager.as_ref(), &self.config);
         let (implement_actions, implement_disabled_reason) = match default_mask {
             Some(mask) => {
                 let user_text = PLAN_IMPLEMENTATION_CODING_MESSAGE.to_string();
@@ -1136,27 +1240,47 @@ impl ChatWidget {
             None => (Vec::new(), Some("Default mode unavailable".to_string())),
         };
 
-        let items = vec![
-            SelectionItem {
-                name: PLAN_IMPLEMENTATION_YES.to_string(),
-                description: Some("Switch to Default and start coding.".to_string()),
-                selected_description: None,
-                is_current: false,
-                actions: implement_actions,
-                disabled_reason: implement_disabled_reason,
+        let mut items = vec![SelectionItem {
+            name: PLAN_IMPLEMENTATION_YES.to_string(),
+            description: Some("Switch to Default and start coding.".to_string()),
+            selected_description: None,
+            is_current: false,
+            actions: implement_actions,
+            disabled_reason: implement_disabled_reason,
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        if self.config.plan_artifacts != PlanArtifacts::None {
+            let run_actions: Vec<SelectionAction> = vec![Box::new(|tx| {
+                tx.send(AppEvent::RunSavedPlan { path: None });
+            })];
+            let edit_actions: Vec<SelectionAction> = vec![Box::new(|tx| {
+                tx.send(AppEvent::EditSavedPlan);
+            })];
+            items.push(SelectionItem {
+                name: PLAN_IMPLEMENTATION_STEPS.to_string(),
+                description: Some("Run one step per turn and checkpoint each.".to_string()),
+                actions: run_actions,
                 dismiss_on_select: true,
                 ..Default::default()
-            },
-            SelectionItem {
-                name: PLAN_IMPLEMENTATION_NO.to_string(),
-                description: Some("Continue planning with the model.".to_string()),
-                selected_description: None,
-                is_current: false,
-                actions: Vec::new(),
+            });
+            items.push(SelectionItem {
+                name: PLAN_IMPLEMENTATION_EDIT.to_string(),
+                description: Some("Open the saved plan in your editor.".to_string()),
+                actions: edit_actions,
                 dismiss_on_select: true,
                 ..Default::default()
-            },
-        ];
+            });
+        }
+        items.push(SelectionItem {
+            name: PLAN_IMPLEMENTATION_NO.to_string(),
+            description: Some("Continue planning with the model.".to_string()),
+            selected_description: None,
+            is_current: false,
+            actions: Vec::new(),
+            dismiss_on_select: true,
+            ..Default::default()
+        });
 
         self.bottom_pane.show_selection_view(SelectionViewParams {
             title: Some(PLAN_IMPLEMENTATION_TITLE.to_string()),
@@ -1167,6 +1291,339 @@ impl ChatWidget {
         });
     }
 
+    pub(crate) fn saved_plan_path(&self) -> Option<&Path> {
+        self.saved_plan_path.as_deref()
+    }
+
+    pub(crate) fn on_plan_saved(&mut self, path: PathBuf) {
+        self.add_info_message(
+            format!("Plan saved to {}", path.display()),
+            Some("Run it step by step now, or later with /run-plan.".to_string()),
+        );
+        self.saved_plan_path = Some(path);
+    }
+
+    pub(crate) fn on_plan_edited(&mut self, path: &Path) {
+        self.add_info_message(format!("Updated {}", path.display()), None);
+        self.open_plan_implementation_prompt();
+    }
+
+    /// Executes the plan at `path`, or the plan saved last, starting at its
+    /// first unfinished step.
+    pub(crate) fn start_plan_run(&mut self, path: Option<PathBuf>) {
+        let Some(path) = path.or_else(|| self.saved_plan_path.clone()) else {
+            self.add_error_message("No saved plan to run.".to_string());
+            return;
+        };
+        self.plan_run = Some(PlanRun {
+            path,
+            running_step: None,
+        });
+        self.run_next_plan_step();
+    }
+
+    fn run_next_plan_step(&mut self) {
+        let Some(path) = self.plan_run.as_ref().map(|run| run.path.clone()) else {
+            return;
+        };
+        let plan = match SavedPlan::load(&path) {
+            Ok(plan) => plan,
+            Err(err) => {
+                self.plan_run = None;
+                self.add_error_message(format!("Failed to read {}: {err}", path.display()));
+                return;
+            }
+        };
+        if plan.steps.is_empty() {
+            self.plan_run = None;
+            self.add_error_message(format!(
+                "{} has no numbered or bulleted steps to run.",
+                path.display()
+            ));
+            return;
+        }
+        let Some(step) = plan.next_step() else {
+            self.plan_run = None;
+            self.add_info_message(
+                format!(
+                    "All {} steps of \"{}\" are done.",
+                    plan.steps.len(),
+                    plan.title
+                ),
+                None,
+            );
+            return;
+        };
+
+        let text = format!(
+            "Implement step {} of {} of the plan in {}:\n\n{}\n\nOnly do this step, then stop and summarize what changed.",
+            step.number,
+            plan.steps.len(),
+            path.display(),
+            step.text
+        );
+        self.plan_run = Some(PlanRun {
+            path,
+            running_step: Some(step.number),
+        });
+        match collaboration_modes::default_mode_mask(self.models_manager.as_ref(), &self.config) {
+            Some(mask) if self.collaboration_modes_enabled() => {
+                self.submit_user_message_with_mode(text, mask);
+            }
+            _ => self.submit_user_message(text.into()),
+        }
+    }
+
+    /// Asks the app to checkpoint and record the step whose turn just ended.
+    fn checkpoint_running_plan_step(&mut self) {
+        let Some(run) = self.plan_run.as_mut() else {
+            return;
+        };
+        let Some(step) = run.running_step.take() else {
+            return;
+        };
+        self.app_event_tx.send(AppEvent::CheckpointPlanStep {
+            path: run.path.clone(),
+            step,
+        });
+    }
+
+    fn pause_plan_run(&mut self) {
+        let Some(step) = self.plan_run.as_ref().and_then(|run| run.running_step) else {
+            return;
+        };
+        self.plan_run = None;
+        self.add_info_message(
+            format!("Plan paused during step {step}."),
+            Some("Run /run-plan to resume it.".to_string()),
+        );
+    }
+
+    /// Called once step `step` is recorded in the plan file, with the id of
+    /// the checkpoint taken after it.
+    pub(crate) fn on_plan_step_finished(
+        &mut self,
+        step: usize,
+        outcome: std::io::Result<Option<String>>,
+    ) {
+        let Some(path) = self.plan_run.as_ref().map(|run| run.path.clone()) else {
+            return;
+        };
+        match outcome {
+            Ok(checkpoint) => {
+                let hint = checkpoint.map(|id| {
+                    let short: String = id.chars().take(7).collect();
+                    format!("Checkpoint {short}")
+                });
+                self.add_info_message(format!("Finished step {step} of the plan."), hint);
+            }
+            Err(err) => {
+                self.plan_run = None;
+                self.add_error_message(format!(
+                    "Failed to record step {step} in {}: {err}",
+                    path.display()
+                ));
+                return;
+            }
+        }
+        self.open_plan_continue_prompt(path);
+    }
+
+    /// Called once the checkpoint taken after step `step` is restored and the
+    /// later steps are reopened.
+    pub(crate) fn on_plan_checkpoint_restored(
+        &mut self,
+        path: PathBuf,
+        step: usize,
+        outcome: std::io::Result<String>,
+    ) {
+        match outcome {
+            Ok(checkpoint) => {
+                let short: String = checkpoint.chars().take(7).collect();
+                self.add_info_message(
+                    format!("Restored the checkpoint after step {step} of the plan."),
+                    Some(format!("Checkpoint {short}")),
+                );
+                self.open_plan_continue_prompt(path);
+            }
+            Err(err) => {
+                self.add_error_message(format!(
+                    "Failed to restore step {step} of {}: {err}",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    /// Offers to continue the plan at `path` with its next step, or to go
+    /// back to the checkpoint of a finished step.
+    pub(crate) fn open_plan_continue_prompt(&mut self, path: PathBuf) {
+        let plan = match SavedPlan::load(&path) {
+            Ok(plan) => plan,
+            Err(err) => {
+                self.plan_run = None;
+                self.add_error_message(format!("Failed to read {}: {err}", path.display()));
+                return;
+            }
+        };
+        let next_step = plan.next_step();
+        let checkpointed: Vec<_> = plan
+            .steps
+            .iter()
+            .rev()
+            .filter(|step| step.done && step.checkpoint.is_some())
+            .collect();
+        if next_step.is_none() {
+            self.plan_run = None;
+            if checkpointed.is_empty() {
+                self.add_info_message(
+                    format!(
+                        "All {} steps of \"{}\" are done.",
+                        plan.steps.len(),
+                        plan.title
+                    ),
+                    None,
+                );
+                return;
+            }
+        }
+
+        let mut items = Vec::new();
+        if let Some(step) = next_step {
+            let path = path.clone();
+            let continue_actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::RunSavedPlan {
+                    path: Some(path.clone()),
+                });
+            })];
+            items.push(SelectionItem {
+                name: format!("Yes, continue with step {}", step.number),
+                description: Some(step.summary().to_string()),
+                actions: continue_actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        for step in checkpointed {
+            let path = path.clone();
+            let number = step.number;
+            let restore_actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::RestorePlanCheckpoint {
+                    path: path.clone(),
+                    step: number,
+                });
+            })];
+            items.push(SelectionItem {
+                name: format!("Restore the checkpoint after step {number}"),
+                description: Some(format!(
+                    "Discard later changes and reopen the steps after \"{}\".",
+                    step.summary()
+                )),
+                actions: restore_actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        items.push(SelectionItem {
+            name: "No, stop here".to_string(),
+            description: Some("Resume later with /run-plan.".to_string()),
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!(
+                "Continue the plan? ({} of {} steps done)",
+                plan.completed_steps(),
+                plan.steps.len()
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_saved_plans_popup(&mut self) {
+        let Some(dir) = plans_dir(
+            self.config.plan_artifacts,
+            &self.config.cwd,
+            &self.config.codex_home,
+        ) else {
+            self.add_info_message(
+                "Saving plans is turned off.".to_string(),
+                Some("Set `plan_artifacts` in config.toml to save and run plans.".to_string()),
+            );
+            return;
+        };
+        let plans = match list_saved_plans(&dir) {
+            Ok(plans) => plans,
+            Err(err) => {
+                self.add_error_message(format!("Failed to read saved plans: {err}"));
+                return;
+            }
+        };
+        let items: Vec<SelectionItem> = if plans.is_empty() {
+            vec![SelectionItem {
+                name: "No saved plans".to_string(),
+                description: Some("Plans proposed in Plan mode are saved here.".to_string()),
+                is_disabled: true,
+                ..Default::default()
+            }]
+        } else {
+            plans
+                .into_iter()
+                .map(|plan| {
+                    let description = match plan.next_step() {
+                        Some(step) => format!(
+                            "{} of {} steps done, next: {}",
+                            plan.completed_steps(),
+                            plan.steps.len(),
+                            step.summary()
+                        ),
+                        None => format!("all {} steps done", plan.steps.len()),
+                    };
+                    let path = plan.path;
+                    // Plans with finished steps open their progress first, so
+                    // a checkpoint can be restored before resuming.
+                    let started = plan.completed_steps() > 0;
+                    let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                        if started {
+                            tx.send(AppEvent::OpenSavedPlan { path: path.clone() });
+                        } else {
+                            tx.send(AppEvent::RunSavedPlan {
+                                path: Some(path.clone()),
+                            });
+                        }
+                    })];
+                    SelectionItem {
+                        name: plan.title,
+                        description: Some(description),
+                        actions,
+                        dismiss_on_select: true,
+                        ..Default::default()
+                    }
+                })
+                .collect()
+        };
+
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from("Run a Saved Plan".bold()));
+        header.push(Line::from(
+            format!(
+                "Resumes at the first unfinished step. Plans live in {}.",
+                dir.display()
+            )
+            .dim(),
+        ));
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            header: Box::new(header),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
         match info {
             Some(info) => self.apply_token_info(info),
@@ -1319,6 +1776,7 @@ impl ChatWidget {
 
     fn on_error(&mut self, message: String) {
         self.finalize_turn();
+        self.pause_plan_run();
         self.add_to_history(history_cell::new_error_event(message));
         self.request_redraw();
 
@@ -1405,6 +1863,7 @@ impl ChatWidget {
     fn on_interrupted_turn(&mut self, reason: TurnAbortReason) {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
+        self.pause_plan_run();
 
         if reason != TurnAbortReason::ReviewEnded {
             self.add_to_history(history_cell::new_error_event(
@@ -2253,6 +2712,7 @@ impl ChatWidget {
         let current_collaboration_mode = CollaborationMode {
             mode: ModeKind::Default,
             settings: fallback_default,
+            custom_mode: None,
         };
 
         let active_cell = Some(Self::placeholder_session_header_cell(&config));
@@ -2308,6 +2768,8 @@ impl ChatWidget {
             retry_status_header: None,
             thread_id: None,
             thread_name: None,
+            pinned_messages: Vec::new(),
+            pinnable_message_ids: Vec::new(),
             forked_from: None,
             queued_user_messages: VecDeque::new(),
             show_welcome_banner: is_first_run,
@@ -2323,6 +2785,8 @@ impl ChatWidget {
             saw_plan_item_this_turn: false,
             plan_delta_buffer: String::new(),
             plan_item_active: false,
+            saved_plan_path: None,
+            plan_run: None,
             last_separator_elapsed_secs: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -2335,6 +2799,9 @@ impl ChatWidget {
         widget
             .bottom_pane
             .set_steer_enabled(widget.config.features.enabled(Feature::Steer));
+        widget
+            .bottom_pane
+            .set_vim_mode_enabled(widget.config.tui_vim_mode);
         widget.bottom_pane.set_collaboration_modes_enabled(
             widget.config.features.enabled(Feature::CollaborationModes),
         );
@@ -2399,6 +2866,7 @@ impl ChatWidget {
         let current_collaboration_mode = CollaborationMode {
             mode: ModeKind::Default,
             settings: fallback_default,
+            custom_mode: None,
         };
 
         let active_cell = Some(Self::placeholder_session_header_cell(&config));
@@ -2454,11 +2922,15 @@ impl ChatWidget {
             retry_status_header: None,
             thread_id: None,
             thread_name: None,
+            pinned_messages: Vec::new(),
+            pinnable_message_ids: Vec::new(),
             forked_from: None,
             saw_plan_update_this_turn: false,
             saw_plan_item_this_turn: false,
             plan_delta_buffer: String::new(),
             plan_item_active: false,
+            saved_plan_path: None,
+            plan_run: None,
             queued_user_messages: VecDeque::new(),
             show_welcome_banner: is_first_run,
             suppress_session_configured_redraw: false,
@@ -2481,6 +2953,9 @@ impl ChatWidget {
         widget
             .bottom_pane
             .set_steer_enabled(widget.config.features.enabled(Feature::Steer));
+        widget
+            .bottom_pane
+            .set_vim_mode_enabled(widget.config.tui_vim_mode);
         widget.bottom_pane.set_collaboration_modes_enabled(
             widget.config.features.enabled(Feature::CollaborationModes),
         );
@@ -2536,6 +3011,7 @@ impl ChatWidget {
         let current_collaboration_mode = CollaborationMode {
             mode: ModeKind::Default,
             settings: fallback_default,
+            custom_mode: None,
         };
 
         let mut widget = Self {
@@ -2589,6 +3065,8 @@ impl ChatWidget {
             retry_status_header: None,
             thread_id: None,
             thread_name: None,
+            pinned_messages: Vec::new(),
+            pinnable_message_ids: Vec::new(),
             forked_from: None,
             queued_user_messages: VecDeque::new(),
             show_welcome_banner: false,
@@ -2604,6 +3082,8 @@ impl ChatWidget {
             saw_plan_item_this_turn: false,
             plan_delta_buffer: String::new(),
             plan_item_active: false,
+            saved_plan_path: None,
+            plan_run: None,
             last_separator_elapsed_secs: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -2616,6 +3096,9 @@ impl ChatWidget {
         widget
             .bottom_pane
             .set_steer_enabled(widget.config.features.enabled(Feature::Steer));
+        widget
+            .bottom_pane
+            .set_vim_mode_enabled(widget.config.tui_vim_mode);
         widget.bottom_pane.set_collaboration_modes_enabled(
             widget.config.features.enabled(Feature::CollaborationModes),
         );
@@ -2657,14 +3140,7 @@ impl ChatWidget {
                 self.quit_shortcut_expires_at = None;
                 self.quit_shortcut_key = None;
             }
-            KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                kind: KeyEventKind::Press,
-                ..
-            } if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
-                && c.eq_ignore_ascii_case(&'v') =>
-            {
+            _ if keymap::current().matches(KeymapAction::PasteImage, key_event) => {
                 match paste_image_to_temp_png() {
                     Ok((path, info)) => {
                         tracing::debug!(
@@ -2703,6 +3179,23 @@ impl ChatWidget {
             {
                 self.cycle_collaboration_mode();
             }
+            KeyEvent {
+                code: KeyCode::Char(shortcut),
+                modifiers: KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.collaboration_modes_enabled()
+                && !self.bottom_pane.is_task_running()
+                && self.bottom_pane.no_modal_or_popup_active()
+                && collaboration_modes::mask_for_shortcut(
+                    self.models_manager.as_ref(),
+                    &self.config,
+                    shortcut,
+                )
+                .is_some() =>
+            {
+                self.switch_to_collaboration_mode_shortcut(shortcut);
+            }
             KeyEvent {
                 code: KeyCode::Up,
                 modifiers: KeyModifiers::ALT,
@@ -2877,12 +3370,27 @@ impl ChatWidget {
             SlashCommand::Rename => {
                 self.show_rename_prompt();
             }
+            SlashCommand::Pin => {
+                self.pin_recent_message(1);
+            }
+            SlashCommand::Unpin => {
+                self.show_pinned_messages();
+            }
             SlashCommand::Model => {
                 self.open_model_popup();
             }
             SlashCommand::Personality => {
                 self.open_personality_popup();
             }
+            SlashCommand::Theme => {
+                self.open_theme_popup();
+            }
+            SlashCommand::RepoApprovals => {
+                self.open_repo_approvals_popup();
+            }
+            SlashCommand::RunPlan => {
+                self.open_saved_plans_popup();
+            }
             SlashCommand::Plan => {
                 if !self.collaboration_modes_enabled() {
                     self.add_info_message(
@@ -2891,7 +3399,9 @@ impl ChatWidget {
                     );
                     return;
                 }
-                if let Some(mask) = collaboration_modes::plan_mask(self.models_manager.as_ref()) {
+                if let Some(mask) =
+                    collaboration_modes::plan_mask(self.models_manager.as_ref(), &self.config)
+                {
                     self.set_collaboration_mask(mask);
                 } else {
                     self.add_info_message("Plan mode unavailable right now.".to_string(), None);
@@ -3104,6 +3614,33 @@ impl ChatWidget {
                     .send(AppEvent::CodexOp(Op::SetThreadName { name }));
                 self.bottom_pane.drain_pending_submission_state();
             }
+            SlashCommand::Pin if !trimmed.is_empty() => {
+                let Some(n) = trimmed.parse::<usize>().ok().filter(|n| *n > 0) else {
+                    self.add_error_message(format!(
+                        "'{trimmed}' is not a message number. Use /pin [n] to pin your n-th most recent message."
+                    ));
+                    return;
+                };
+                self.pin_recent_message(n);
+                self.bottom_pane.drain_pending_submission_state();
+            }
+            SlashCommand::Unpin if !trimmed.is_empty() => {
+                let pinned = trimmed
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| index.checked_sub(1))
+                    .and_then(|index| self.pinned_messages.get(index));
+                let Some(pinned) = pinned else {
+                    self.add_error_message(format!(
+                        "No pinned message numbered '{trimmed}'. Type /unpin to list pinned messages."
+                    ));
+                    return;
+                };
+                let id = pinned.id.clone();
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::UnpinMessage { id }));
+                self.bottom_pane.drain_pending_submission_state();
+            }
             SlashCommand::Plan if !trimmed.is_empty() => {
                 self.dispatch_command(cmd);
                 if self.active_mode_kind() != ModeKind::Plan {
@@ -3143,6 +3680,8 @@ impl ChatWidget {
                             instructions: prepared_args,
                         },
                         user_facing_hint: None,
+                        only_changed_lines: false,
+                        baseline: None,
                     },
                 });
                 self.bottom_pane.drain_pending_submission_state();
@@ -3464,6 +4003,10 @@ impl ChatWidget {
         match msg {
             EventMsg::SessionConfigured(e) => self.on_session_configured(e),
             EventMsg::ThreadNameUpdated(e) => self.on_thread_name_updated(e),
+            EventMsg::PinnedMessagesUpdated(e) => self.on_pinned_messages_updated(e, from_replay),
+            EventMsg::CollaborationModeUpdated(e) => {
+                self.on_collaboration_mode_updated(e, from_replay)
+            }
             EventMsg::AgentMessage(AgentMessageEvent { message }) => self.on_agent_message(message),
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                 self.on_agent_message_delta(delta)
@@ -3597,11 +4140,20 @@ impl ChatWidget {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::DynamicToolCallRequest(_) => {}
-            EventMsg::ItemCompleted(event) => {
-                if let codex_protocol::items::TurnItem::Plan(plan_item) = event.item {
-                    self.on_plan_item_completed(plan_item.text);
+            EventMsg::ItemCompleted(event) => match event.item {
+                codex_protocol::items::TurnItem::Plan(plan_item) => {
+                    self.on_plan_item_completed(plan_item.text, from_replay);
                 }
-            }
+                // Message items are replayed from the rollout on resume, so
+                // earlier messages stay pinnable.
+                codex_protocol::items::TurnItem::UserMessage(message) => {
+                    self.pinnable_message_ids.push(message.id);
+                }
+                codex_protocol::items::TurnItem::AgentMessage(message) => {
+                    self.pinnable_message_ids.push(message.id);
+                }
+                _ => {}
+            },
         }
     }
 
@@ -4053,6 +4605,93 @@ impl ChatWidget {
         });
     }
 
+    pub(crate) fn open_theme_popup(&mut self) {
+        let current_theme = theme::current();
+        let items: Vec<SelectionItem> = TuiTheme::ALL
+            .into_iter()
+            .map(|tui_theme| {
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::UpdateTheme(tui_theme));
+                    tx.send(AppEvent::PersistThemeSelection { theme: tui_theme });
+                })];
+                SelectionItem {
+                    name: theme::label(tui_theme).to_string(),
+                    description: Some(theme::description(tui_theme).to_string()),
+                    is_current: current_theme == tui_theme,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from("Select Theme".bold()));
+        header.push(Line::from("Choose how Codex colors the interface.".dim()));
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            header: Box::new(header),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_repo_approvals_popup(&mut self) {
+        let project = project_root(&self.config.cwd);
+        let approvals = match list_remembered_approvals(&self.config.codex_home, Some(&project)) {
+            Ok(approvals) => approvals,
+            Err(err) => {
+                self.add_error_message(format!("Failed to read remembered approvals: {err}"));
+                return;
+            }
+        };
+        let items: Vec<SelectionItem> = if approvals.is_empty() {
+            vec![SelectionItem {
+                name: "No approvals remembered for this repo".to_string(),
+                description: Some(
+                    "Pick \"always allow in this repo\" when approving to add one.".to_string(),
+                ),
+                is_disabled: true,
+                ..Default::default()
+            }]
+        } else {
+            approvals
+                .into_iter()
+                .map(|entry| {
+                    let approval = entry.approval;
+                    let name = approval.value();
+                    let description = Some(format!("{}, select to revoke", approval.kind()));
+                    let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                        tx.send(AppEvent::RevokeRepoApproval {
+                            approval: approval.clone(),
+                        });
+                    })];
+                    SelectionItem {
+                        name,
+                        description,
+                        actions,
+                        dismiss_on_select: true,
+                        ..Default::default()
+                    }
+                })
+                .collect()
+        };
+
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from("Remembered Approvals".bold()));
+        header.push(Line::from(
+            format!("Always allowed in {}.", project.display()).dim(),
+        ));
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            header: Box::new(header),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     fn model_menu_header(&self, title: &str, subtitle: &str) -> Box<dyn Renderable> {
         let title = title.to_string();
         let subtitle = subtitle.to_string();
@@ -4232,7 +4871,8 @@ impl ChatWidget {
     }
 
     pub(crate) fn open_collaboration_modes_popup(&mut self) {
-        let presets = collaboration_modes::presets_for_tui(self.models_manager.as_ref());
+        let presets =
+            collaboration_modes::presets_for_tui(self.models_manager.as_ref(), &self.config);
         if presets.is_empty() {
             self.add_info_message(
                 "No collaboration modes are available right now.".to_string(),
@@ -4241,24 +4881,28 @@ impl ChatWidget {
             return;
         }
 
-        let current_kind = self
-            .active_collaboration_mask
-            .as_ref()
-            .and_then(|mask| mask.mode)
-            .or_else(|| {
-                collaboration_modes::default_mask(self.models_manager.as_ref())
-                    .and_then(|mask| mask.mode)
-            });
+        let current = self.active_collaboration_mask.clone().or_else(|| {
+            collaboration_modes::default_mask(self.models_manager.as_ref(), &self.config)
+        });
         let items: Vec<SelectionItem> = presets
             .into_iter()
             .map(|mask| {
                 let name = mask.name.clone();
-                let is_current = current_kind == mask.mode;
+                let is_current = current.as_ref().is_some_and(|current| {
+                    current.mode == mask.mode && current.custom_mode == mask.custom_mode
+                });
+                let display_shortcut = mask
+                    .custom_mode
+                    .as_ref()
+                    .and_then(|name| self.config.collaboration_modes.get(name))
+                    .and_then(|mode| mode.shortcut)
+                    .map(|shortcut| key_hint::alt(KeyCode::Char(shortcut.to_ascii_lowercase())));
                 let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
                     tx.send(AppEvent::UpdateCollaborationMode(mask.clone()));
                 })];
                 SelectionItem {
                     name,
+                    display_shortcut,
                     is_current,
                     actions,
                     dismiss_on_select: true,
@@ -5243,9 +5887,10 @@ impl ChatWidget {
             self.current_collaboration_mode = CollaborationMode {
                 mode: ModeKind::Default,
                 settings,
+                custom_mode: None,
             };
             self.active_collaboration_mask = if enabled {
-                collaboration_modes::default_mask(self.models_manager.as_ref())
+                collaboration_modes::default_mask(self.models_manager.as_ref(), &self.config)
             } else {
                 None
             };
@@ -5415,8 +6060,8 @@ impl ChatWidget {
             return None;
         }
         let mut mask = match config.experimental_mode {
-            Some(kind) => collaboration_modes::mask_for_kind(models_manager, kind)?,
-            None => collaboration_modes::default_mask(models_manager)?,
+            Some(kind) => collaboration_modes::mask_for_kind(models_manager, config, kind)?,
+            None => collaboration_modes::default_mask(models_manager, config)?,
         };
         if let Some(model_override) = model_override {
             mask.model = Some(model_override.to_string());
@@ -5469,10 +6114,17 @@ impl ChatWidget {
     }
 
     /// Get the label for the current collaboration mode.
-    fn collaboration_mode_label(&self) -> Option<&'static str> {
+    fn collaboration_mode_label(&self) -> Option<&str> {
         if !self.collaboration_modes_enabled() {
             return None;
         }
+        if let Some(mask) = self
+            .active_collaboration_mask
+            .as_ref()
+            .filter(|mask| mask.custom_mode.is_some())
+        {
+            return Some(mask.name.as_str());
+        }
         match self.active_mode_kind() {
             ModeKind::Plan => Some("Plan"),
             ModeKind::Default => Some("Default"),
@@ -5517,12 +6169,55 @@ impl ChatWidget {
 
         if let Some(next_mask) = collaboration_modes::next_mask(
             self.models_manager.as_ref(),
+            &self.config,
             self.active_collaboration_mask.as_ref(),
         ) {
             self.set_collaboration_mask(next_mask);
         }
     }
 
+    /// Switch to the user-defined collaboration mode bound to `shortcut`.
+    fn switch_to_collaboration_mode_shortcut(&mut self, shortcut: char) {
+        let Some(mask) = collaboration_modes::mask_for_shortcut(
+            self.models_manager.as_ref(),
+            &self.config,
+            shortcut,
+        ) else {
+            return;
+        };
+        let name = mask.name.clone();
+        self.set_collaboration_mask(mask);
+        self.add_info_message(format!("Switched to {name} mode."), None);
+    }
+
+    /// Restore the collaboration mode recorded in a resumed session.
+    fn on_collaboration_mode_updated(
+        &mut self,
+        event: CollaborationModeUpdatedEvent,
+        from_replay: bool,
+    ) {
+        // Live updates echo a mode this widget already selected.
+        if !from_replay {
+            return;
+        }
+        let mode = event.collaboration_mode;
+        let mask = match mode.custom_mode.as_deref() {
+            Some(name) => collaboration_modes::mask_for_custom_mode(
+                self.models_manager.as_ref(),
+                &self.config,
+                name,
+            ),
+            None => collaboration_modes::mask_for_kind(
+                self.models_manager.as_ref(),
+                &self.config,
+                mode.mode,
+            ),
+        };
+        if let Some(mask) = mask {
+            self.set_collaboration_mask(mask);
+        }
+    }
+
     /// Update the active collaboration mask.
     ///
     /// When collaboration modes are enabled and a preset is selected,
@@ -5780,6 +6475,10 @@ impl ChatWidget {
         self.bottom_pane.on_file_search_result(query, matches);
     }
 
+    pub(crate) fn apply_symbol_search_result(&mut self, query: String, symbols: Vec<Symbol>) {
+        self.bottom_pane.on_symbol_search_result(query, symbols);
+    }
+
     /// Handles a Ctrl+C press at the chat-widget layer.
     ///
     /// The first press arms a time-bounded quit shortcut and shows a footer hint via the bottom
@@ -5995,6 +6694,8 @@ impl ChatWidget {
                     review_request: ReviewRequest {
                         target: ReviewTarget::UncommittedChanges,
                         user_facing_hint: None,
+                        only_changed_lines: false,
+                        baseline: None,
                     },
                 }));
             })],
@@ -6050,6 +6751,8 @@ impl ChatWidget {
                                 branch: branch.clone(),
                             },
                             user_facing_hint: None,
+                            only_changed_lines: false,
+                            baseline: None,
                         },
                     }));
                 })],
@@ -6088,6 +6791,8 @@ impl ChatWidget {
                                 title: Some(subject.clone()),
                             },
                             user_facing_hint: None,
+                            only_changed_lines: false,
+                            baseline: None,
                         },
                     }));
                 })],
@@ -6124,6 +6829,8 @@ impl ChatWidget {
                             instructions: trimmed,
                         },
                         user_facing_hint: None,
+                        only_changed_lines: false,
+                        baseline: None,
                     },
                 }));
             }),
@@ -6373,6 +7080,8 @@ pub(crate) fn show_review_commit_picker_with_entries(
                             title: Some(subject.clone()),
                         },
                         user_facing_hint: None,
+                        only_changed_lines: false,
+                        baseline: None,
                     },
                 }));
             })],
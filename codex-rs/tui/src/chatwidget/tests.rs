@@ -17,19 +17,24 @@ use assert_matches::assert_matches;
 use codex_common::approval_presets::builtin_approval_presets;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
+use codex_core::approval_memory::RememberedApproval;
+use codex_core::approval_memory::remember_approvals;
 use codex_core::config::Config;
 use codex_core::config::ConfigBuilder;
 use codex_core::config::Constrained;
 use codex_core::config::ConstraintError;
+use codex_core::config::collaboration_modes::CollaborationModeToml;
 use codex_core::config_loader::RequirementSource;
 use codex_core::features::Feature;
 use codex_core::models_manager::manager::ModelsManager;
+use codex_core::plan_artifacts::complete_step;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CollaborationModeUpdatedEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -37,6 +42,7 @@ use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecCommandStatus;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
@@ -599,6 +605,8 @@ async fn entered_review_mode_uses_request_hint() {
                 branch: "feature".to_string(),
             },
             user_facing_hint: Some("feature branch".to_string()),
+            only_changed_lines: false,
+            baseline: None,
         }),
     });
 
@@ -618,6 +626,8 @@ async fn entered_review_mode_defaults_to_current_changes_banner() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: None,
+            only_changed_lines: false,
+            baseline: None,
         }),
     });
 
@@ -652,6 +662,8 @@ async fn review_restores_context_window_indicator() {
                 branch: "feature".to_string(),
             },
             user_facing_hint: Some("feature branch".to_string()),
+            only_changed_lines: false,
+            baseline: None,
         }),
     });
 
@@ -830,6 +842,7 @@ async fn make_chatwidget_manual(
             reasoning_effort,
             developer_instructions: None,
         },
+        custom_mode: None,
     };
     let current_collaboration_mode = base_mode;
     let mut widget = ChatWidget {
@@ -873,6 +886,8 @@ async fn make_chatwidget_manual(
         retry_status_header: None,
         thread_id: None,
         thread_name: None,
+        pinned_messages: Vec::new(),
+        pinnable_message_ids: Vec::new(),
         forked_from: None,
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
@@ -889,6 +904,8 @@ async fn make_chatwidget_manual(
         saw_plan_item_this_turn: false,
         plan_delta_buffer: String::new(),
         plan_item_active: false,
+        saved_plan_path: None,
+        plan_run: None,
         last_separator_elapsed_secs: None,
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
@@ -1234,7 +1251,9 @@ async fn plan_implementation_popup_snapshot() {
 async fn plan_implementation_popup_no_selected_snapshot() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.open_plan_implementation_prompt();
-    chat.handle_key_event(KeyEvent::from(KeyCode::Down));
+    for _ in 0..3 {
+        chat.handle_key_event(KeyEvent::from(KeyCode::Down));
+    }
 
     let popup = render_bottom_popup(&chat, 80);
     assert_snapshot!("plan_implementation_popup_no_selected", popup);
@@ -1265,8 +1284,9 @@ async fn submit_user_message_with_mode_sets_coding_collaboration_mode() {
     chat.thread_id = Some(ThreadId::new());
     chat.set_feature_enabled(Feature::CollaborationModes, true);
 
-    let default_mode = collaboration_modes::default_mode_mask(chat.models_manager.as_ref())
-        .expect("expected default collaboration mode");
+    let default_mode =
+        collaboration_modes::default_mode_mask(chat.models_manager.as_ref(), &chat.config)
+            .expect("expected default collaboration mode");
     chat.submit_user_message_with_mode("Implement the plan.".to_string(), default_mode);
 
     match next_submit_op(&mut op_rx) {
@@ -1285,13 +1305,54 @@ async fn submit_user_message_with_mode_sets_coding_collaboration_mode() {
     }
 }
 
+#[tokio::test]
+async fn custom_collaboration_mode_shortcut_and_replay() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
+    chat.set_feature_enabled(Feature::CollaborationModes, true);
+    chat.config.collaboration_modes.insert(
+        "review-only".to_string(),
+        CollaborationModeToml {
+            shortcut: Some('r'),
+            ..Default::default()
+        },
+    );
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::ALT));
+    assert_eq!(
+        chat.active_collaboration_mask
+            .as_ref()
+            .and_then(|mask| mask.custom_mode.as_deref()),
+        Some("review-only")
+    );
+    assert_eq!(chat.collaboration_mode_label(), Some("review-only"));
+
+    chat.replay_initial_messages(vec![EventMsg::CollaborationModeUpdated(
+        CollaborationModeUpdatedEvent {
+            collaboration_mode: CollaborationMode {
+                mode: ModeKind::Plan,
+                settings: Settings {
+                    model: "gpt-5".to_string(),
+                    reasoning_effort: None,
+                    developer_instructions: None,
+                },
+                custom_mode: None,
+            },
+        },
+    )]);
+    assert_eq!(chat.active_mode_kind(), ModeKind::Plan);
+    assert_eq!(chat.collaboration_mode_label(), Some("Plan"));
+}
+
 #[tokio::test]
 async fn plan_implementation_popup_skips_replayed_turn_complete() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.replay_initial_messages(vec![EventMsg::TurnComplete(TurnCompleteEvent {
@@ -1309,14 +1370,17 @@ async fn plan_implementation_popup_skips_replayed_turn_complete() {
 async fn plan_implementation_popup_shows_once_when_replay_precedes_live_turn_complete() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.on_task_started();
     chat.on_plan_delta("- Step 1\n- Step 2\n".to_string());
-    chat.on_plan_item_completed("- Step 1\n- Step 2\n".to_string());
+    chat.on_plan_item_completed("- Step 1\n- Step 2\n".to_string(), false);
 
     chat.replay_initial_messages(vec![EventMsg::TurnComplete(TurnCompleteEvent {
         last_agent_message: Some("Plan details".to_string()),
@@ -1364,9 +1428,12 @@ async fn plan_implementation_popup_shows_once_when_replay_precedes_live_turn_com
 async fn plan_implementation_popup_skips_when_messages_queued() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
     chat.bottom_pane.set_task_running(true);
     chat.queue_user_message("Queued message".into());
@@ -1384,9 +1451,12 @@ async fn plan_implementation_popup_skips_when_messages_queued() {
 async fn plan_implementation_popup_skips_without_proposed_plan() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.on_task_started();
@@ -1410,14 +1480,17 @@ async fn plan_implementation_popup_skips_without_proposed_plan() {
 async fn plan_implementation_popup_shows_after_proposed_plan_output() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.on_task_started();
     chat.on_plan_delta("- Step 1\n- Step 2\n".to_string());
-    chat.on_plan_item_completed("- Step 1\n- Step 2\n".to_string());
+    chat.on_plan_item_completed("- Step 1\n- Step 2\n".to_string(), false);
     chat.on_task_complete(None, false);
 
     let popup = render_bottom_popup(&chat, 80);
@@ -1427,15 +1500,112 @@ async fn plan_implementation_popup_shows_after_proposed_plan_output() {
     );
 }
 
+#[tokio::test]
+async fn proposed_plan_is_saved_unless_replayed() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
+
+    chat.on_plan_item_completed("1. Replayed step\n".to_string(), true);
+    chat.on_plan_item_completed("1. Live step\n".to_string(), false);
+
+    let mut saved = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::SaveProposedPlan { plan } = ev {
+            saved.push(plan);
+        }
+    }
+    assert_eq!(saved, vec!["1. Live step\n".to_string()]);
+}
+
+#[tokio::test]
+async fn plan_run_submits_next_step_and_offers_to_continue() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
+    chat.thread_id = Some(ThreadId::new());
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("plan.md");
+    std::fs::write(
+        &path,
+        "# Ship it\n\n1. [x] First <!-- checkpoint: abc1234 -->\n2. Second\n3. Third\n",
+    )
+    .expect("write plan");
+
+    chat.start_plan_run(Some(path.clone()));
+    let text = match next_submit_op(&mut op_rx) {
+        Op::UserTurn { items, .. } => match items.as_slice() {
+            [UserInput::Text { text, .. }] => text.clone(),
+            other => panic!("expected a single text item, got {other:?}"),
+        },
+        other => panic!("expected Op::UserTurn, got {other:?}"),
+    };
+    assert!(
+        text.starts_with(&format!(
+            "Implement step 2 of 3 of the plan in {}:\n\nSecond\n",
+            path.display()
+        )),
+        "unexpected step message: {text}"
+    );
+
+    chat.on_task_started();
+    chat.on_task_complete(None, false);
+    let mut checkpoints = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::CheckpointPlanStep { path, step } = ev {
+            checkpoints.push((path, step));
+        }
+    }
+    assert_eq!(checkpoints, vec![(path.clone(), 2)]);
+
+    // The app records the step in the plan file before reporting back.
+    complete_step(&path, 2, Some("def4567890")).expect("complete step");
+    chat.on_plan_step_finished(2, Ok(Some("def4567890".to_string())));
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        popup.contains("Continue the plan? (2 of 3 steps done)"),
+        "popup: {popup}"
+    );
+    assert!(
+        popup.contains("Yes, continue with step 3"),
+        "popup: {popup}"
+    );
+    assert!(
+        popup.contains("Restore the checkpoint after step 2"),
+        "popup: {popup}"
+    );
+}
+
+#[tokio::test]
+async fn interrupted_plan_step_pauses_the_run() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
+    chat.thread_id = Some(ThreadId::new());
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("plan.md");
+    std::fs::write(&path, "- First\n- Second\n").expect("write plan");
+
+    chat.start_plan_run(Some(path));
+    chat.on_task_started();
+    chat.on_interrupted_turn(TurnAbortReason::Interrupted);
+    chat.on_task_complete(None, false);
+
+    assert_eq!(chat.plan_run, None);
+    while let Ok(ev) = rx.try_recv() {
+        assert!(
+            !matches!(ev, AppEvent::CheckpointPlanStep { .. }),
+            "interrupted step should not be checkpointed"
+        );
+    }
+}
+
 #[tokio::test]
 async fn plan_implementation_popup_skips_when_rate_limit_prompt_pending() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5")).await;
     chat.auth_manager =
         AuthManager::from_auth_for_testing(CodexAuth::create_dummy_chatgpt_auth_for_testing());
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.on_task_started();
@@ -1700,6 +1870,7 @@ fn end_exec(
             exit_code,
             duration: std::time::Duration::from_millis(5),
             formatted_output: aggregated,
+            status: ExecCommandStatus::Exited,
         }),
     });
 }
@@ -1989,6 +2160,7 @@ async fn exec_end_without_begin_uses_event_command() {
             exit_code: 0,
             duration: std::time::Duration::from_millis(5),
             formatted_output: "done".to_string(),
+            status: ExecCommandStatus::Exited,
         }),
     });
 
@@ -2339,6 +2511,54 @@ async fn slash_init_skips_when_project_doc_exists() {
     );
 }
 
+#[tokio::test]
+async fn slash_pin_pins_the_nth_most_recent_message() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    let thread_id = ThreadId::new();
+    for (idx, item) in [
+        codex_protocol::items::TurnItem::UserMessage(codex_protocol::items::UserMessageItem {
+            id: "user-1".to_string(),
+            content: Vec::new(),
+        }),
+        codex_protocol::items::TurnItem::AgentMessage(codex_protocol::items::AgentMessageItem {
+            id: "agent-1".to_string(),
+            content: Vec::new(),
+        }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        chat.handle_codex_event(Event {
+            id: format!("item-{idx}"),
+            msg: EventMsg::ItemCompleted(codex_core::protocol::ItemCompletedEvent {
+                thread_id,
+                turn_id: "turn-1".to_string(),
+                item,
+            }),
+        });
+    }
+
+    chat.dispatch_command(SlashCommand::Pin);
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::CodexOp(Op::PinMessage { item_id })) if item_id == "agent-1"
+    );
+
+    chat.dispatch_command_with_args(SlashCommand::Pin, "2".to_string(), Vec::new());
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::CodexOp(Op::PinMessage { item_id })) if item_id == "user-1"
+    );
+
+    chat.dispatch_command_with_args(SlashCommand::Pin, "3".to_string(), Vec::new());
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one error message");
+    assert!(
+        lines_to_single_string(&cells[0]).contains("No message to pin"),
+        "expected a missing message error"
+    );
+}
+
 #[tokio::test]
 async fn collab_mode_shift_tab_cycles_only_when_enabled_and_idle() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -2567,9 +2787,12 @@ async fn experimental_mode_plan_applies_on_startup() {
 async fn set_model_updates_active_collaboration_mask() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5.1")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.set_model("gpt-5.1-codex-mini");
@@ -2582,9 +2805,12 @@ async fn set_model_updates_active_collaboration_mask() {
 async fn set_reasoning_effort_updates_active_collaboration_mask() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("gpt-5.1")).await;
     chat.set_feature_enabled(Feature::CollaborationModes, true);
-    let plan_mask =
-        collaboration_modes::mask_for_kind(chat.models_manager.as_ref(), ModeKind::Plan)
-            .expect("expected plan collaboration mask");
+    let plan_mask = collaboration_modes::mask_for_kind(
+        chat.models_manager.as_ref(),
+        &chat.config,
+        ModeKind::Plan,
+    )
+    .expect("expected plan collaboration mask");
     chat.set_collaboration_mask(plan_mask);
 
     chat.set_reasoning_effort(None);
@@ -2925,6 +3151,8 @@ async fn custom_prompt_submit_sends_review_op() {
                         instructions: "please audit dependencies".to_string(),
                     },
                     user_facing_hint: None,
+                    only_changed_lines: false,
+                    baseline: None,
                 }
             );
         }
@@ -3234,6 +3462,76 @@ async fn personality_selection_popup_snapshot() {
     assert_snapshot!("personality_selection_popup", popup);
 }
 
+#[tokio::test]
+async fn theme_popup_selection_updates_and_persists_theme() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.dispatch_command(SlashCommand::Theme);
+
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("Select Theme"), "popup: {popup}");
+    assert!(popup.contains("High contrast"), "popup: {popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut events = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::UpdateTheme(theme) => events.push(format!("update {theme}")),
+            AppEvent::PersistThemeSelection { theme } => events.push(format!("persist {theme}")),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        events,
+        vec![
+            "update high-contrast".to_string(),
+            "persist high-contrast".to_string()
+        ]
+    );
+}
+
+#[tokio::test]
+async fn repo_approvals_popup_lists_and_revokes_remembered_approvals() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    let codex_home = tempdir().expect("tempdir");
+    let cwd = tempdir().expect("tempdir");
+    chat.config.codex_home = codex_home.path().to_path_buf();
+    chat.config.cwd = cwd.path().to_path_buf();
+
+    chat.dispatch_command(SlashCommand::RepoApprovals);
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        popup.contains("No approvals remembered for this repo"),
+        "popup: {popup}"
+    );
+    chat.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+
+    let approval = RememberedApproval::Command {
+        command: vec!["cargo".to_string(), "test".to_string()],
+        cwd: String::new(),
+    };
+    remember_approvals(
+        codex_home.path(),
+        &project_root(cwd.path()),
+        std::slice::from_ref(&approval),
+    )
+    .expect("remember approval");
+
+    chat.dispatch_command(SlashCommand::RepoApprovals);
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("cargo test"), "popup: {popup}");
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut revoked = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::RevokeRepoApproval { approval } = ev {
+            revoked.push(approval);
+        }
+    }
+    assert_eq!(revoked, vec![approval]);
+}
+
 #[tokio::test]
 async fn model_picker_hides_show_in_picker_false_models_from_cache() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(Some("test-visible-model")).await;
@@ -5014,6 +5312,7 @@ async fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             exit_code: 0,
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
+            status: ExecCommandStatus::Exited,
         }),
     });
     chat.handle_codex_event(Event {
@@ -5187,6 +5486,8 @@ async fn review_queues_user_messages_snapshot() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: Some("current changes".to_string()),
+            only_changed_lines: false,
+            baseline: None,
         }),
     });
     let _ = drain_insert_history(&mut rx);
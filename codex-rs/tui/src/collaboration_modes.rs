@@ -1,3 +1,4 @@
+use codex_core::config::Config;
 use codex_core::models_manager::manager::ModelsManager;
 use codex_protocol::config_types::CollaborationModeMask;
 use codex_protocol::config_types::ModeKind;
@@ -6,60 +7,104 @@ fn is_tui_mode(kind: ModeKind) -> bool {
     matches!(kind, ModeKind::Plan | ModeKind::Default)
 }
 
-fn filtered_presets(models_manager: &ModelsManager) -> Vec<CollaborationModeMask> {
+fn filtered_presets(models_manager: &ModelsManager, config: &Config) -> Vec<CollaborationModeMask> {
     models_manager
-        .list_collaboration_modes()
+        .list_collaboration_modes(config)
         .into_iter()
         .filter(|mask| mask.mode.is_some_and(is_tui_mode))
         .collect()
 }
 
-pub(crate) fn presets_for_tui(models_manager: &ModelsManager) -> Vec<CollaborationModeMask> {
-    filtered_presets(models_manager)
+pub(crate) fn presets_for_tui(
+    models_manager: &ModelsManager,
+    config: &Config,
+) -> Vec<CollaborationModeMask> {
+    filtered_presets(models_manager, config)
 }
 
-pub(crate) fn default_mask(models_manager: &ModelsManager) -> Option<CollaborationModeMask> {
-    let presets = filtered_presets(models_manager);
+pub(crate) fn default_mask(
+    models_manager: &ModelsManager,
+    config: &Config,
+) -> Option<CollaborationModeMask> {
+    let presets = filtered_presets(models_manager, config);
     presets
         .iter()
-        .find(|mask| mask.mode == Some(ModeKind::Default))
+        .find(|mask| mask.mode == Some(ModeKind::Default) && mask.custom_mode.is_none())
         .cloned()
         .or_else(|| presets.into_iter().next())
 }
 
+/// Returns the built-in preset for `kind`; user-defined modes built on the
+/// same kind are never returned.
 pub(crate) fn mask_for_kind(
     models_manager: &ModelsManager,
+    config: &Config,
     kind: ModeKind,
 ) -> Option<CollaborationModeMask> {
     if !is_tui_mode(kind) {
         return None;
     }
-    filtered_presets(models_manager)
+    filtered_presets(models_manager, config)
+        .into_iter()
+        .find(|mask| mask.mode == Some(kind) && mask.custom_mode.is_none())
+}
+
+/// Returns the preset for the user-defined mode `name`.
+pub(crate) fn mask_for_custom_mode(
+    models_manager: &ModelsManager,
+    config: &Config,
+    name: &str,
+) -> Option<CollaborationModeMask> {
+    filtered_presets(models_manager, config)
         .into_iter()
-        .find(|mask| mask.mode == Some(kind))
+        .find(|mask| mask.custom_mode.as_deref() == Some(name))
+}
+
+/// Returns the preset for the user-defined mode bound to `shortcut`.
+pub(crate) fn mask_for_shortcut(
+    models_manager: &ModelsManager,
+    config: &Config,
+    shortcut: char,
+) -> Option<CollaborationModeMask> {
+    let shortcut = shortcut.to_ascii_lowercase();
+    let (name, _) = config.collaboration_modes.iter().find(|(_, mode)| {
+        mode.shortcut
+            .is_some_and(|key| key.to_ascii_lowercase() == shortcut)
+    })?;
+    mask_for_custom_mode(models_manager, config, name)
 }
 
 /// Cycle to the next collaboration mode preset in list order.
 pub(crate) fn next_mask(
     models_manager: &ModelsManager,
+    config: &Config,
     current: Option<&CollaborationModeMask>,
 ) -> Option<CollaborationModeMask> {
-    let presets = filtered_presets(models_manager);
+    let presets = filtered_presets(models_manager, config);
     if presets.is_empty() {
         return None;
     }
-    let current_kind = current.and_then(|mask| mask.mode);
     let next_index = presets
         .iter()
-        .position(|mask| mask.mode == current_kind)
+        .position(|mask| {
+            current.is_some_and(|current| {
+                mask.mode == current.mode && mask.custom_mode == current.custom_mode
+            })
+        })
         .map_or(0, |idx| (idx + 1) % presets.len());
     presets.get(next_index).cloned()
 }
 
-pub(crate) fn default_mode_mask(models_manager: &ModelsManager) -> Option<CollaborationModeMask> {
-    mask_for_kind(models_manager, ModeKind::Default)
+pub(crate) fn default_mode_mask(
+    models_manager: &ModelsManager,
+    config: &Config,
+) -> Option<CollaborationModeMask> {
+    mask_for_kind(models_manager, config, ModeKind::Default)
 }
 
-pub(crate) fn plan_mask(models_manager: &ModelsManager) -> Option<CollaborationModeMask> {
-    mask_for_kind(models_manager, ModeKind::Plan)
+pub(crate) fn plan_mask(
+    models_manager: &ModelsManager,
+    config: &Config,
+) -> Option<CollaborationModeMask> {
+    mask_for_kind(models_manager, config, ModeKind::Plan)
 }
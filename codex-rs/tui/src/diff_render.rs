@@ -14,6 +14,9 @@ use std::path::PathBuf;
 
 use crate::exec_command::relativize_to_home;
 use crate::render::Insets;
+use crate::render::highlight::LanguageSpec;
+use crate::render::highlight::highlight_code_to_lines;
+use crate::render::highlight::language_for_path;
 use crate::render::line_utils::prefix_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::InsetRenderable;
@@ -42,13 +45,33 @@ impl DiffSummary {
 impl Renderable for FileChange {
     fn render(&self, area: Rect, buf: &mut Buffer) {
         let mut lines = vec![];
-        render_change(self, &mut lines, area.width as usize);
+        render_change(self, &mut lines, area.width as usize, None);
         Paragraph::new(lines).render(area, buf);
     }
 
     fn desired_height(&self, width: u16) -> u16 {
         let mut lines = vec![];
-        render_change(self, &mut lines, width as usize);
+        render_change(self, &mut lines, width as usize, None);
+        lines.len() as u16
+    }
+}
+
+/// A file change rendered with syntax highlighting for its path's language.
+struct HighlightedFileChange {
+    change: FileChange,
+    language: Option<&'static LanguageSpec>,
+}
+
+impl Renderable for HighlightedFileChange {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![];
+        render_change(&self.change, &mut lines, area.width as usize, self.language);
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        let mut lines = vec![];
+        render_change(&self.change, &mut lines, width as usize, self.language);
         lines.len() as u16
     }
 }
@@ -66,8 +89,12 @@ impl From<DiffSummary> for Box<dyn Renderable> {
             path.extend(render_line_count_summary(row.added, row.removed));
             rows.push(Box::new(path));
             rows.push(Box::new(RtLine::from("")));
+            let language = language_for_path(row.move_path.as_ref().unwrap_or(&row.path));
             rows.push(Box::new(InsetRenderable::new(
-                Box::new(row.change) as Box<dyn Renderable>,
+                Box::new(HighlightedFileChange {
+                    change: row.change,
+                    language,
+                }) as Box<dyn Renderable>,
                 Insets::tlbr(0, 2, 0, 0),
             )));
         }
@@ -103,6 +130,9 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
             FileChange::Add { content } => (content.lines().count(), 0),
             FileChange::Delete { content } => (0, content.lines().count()),
             FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
+            FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. }
+            | FileChange::ModeChange { .. } => (0, 0),
         };
         let move_path = match change {
             FileChange::Update {
@@ -153,7 +183,9 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
     let mut header_spans: Vec<RtSpan<'static>> = vec!["• ".dim()];
     if let [row] = &rows[..] {
         let verb = match &row.change {
-            FileChange::Add { .. } => "Added",
+            FileChange::Add { .. }
+            | FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. } => "Added",
             FileChange::Delete { .. } => "Deleted",
             _ => "Edited",
         };
@@ -186,17 +218,48 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
         }
 
         let mut lines = vec![];
-        render_change(&r.change, &mut lines, wrap_cols - 4);
+        let language = language_for_path(r.move_path.as_ref().unwrap_or(&r.path));
+        render_change(&r.change, &mut lines, wrap_cols - 4, language);
         out.extend(prefix_lines(lines, "    ".into(), "    ".into()));
     }
 
     out
 }
 
-fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize) {
+fn render_change(
+    change: &FileChange,
+    out: &mut Vec<RtLine<'static>>,
+    width: usize,
+    language: Option<&'static LanguageSpec>,
+) {
     match change {
+        FileChange::AddBinary { size_bytes } => {
+            out.push(RtLine::from(
+                format!("binary file ({size_bytes} bytes)").dim(),
+            ));
+        }
+        FileChange::AddSymlink { target } => {
+            out.push(RtLine::from(vec![
+                "symlink → ".dim(),
+                target.display().to_string().into(),
+            ]));
+        }
+        FileChange::ModeChange { executable } => {
+            let (old_mode, new_mode) = if *executable {
+                ("100644", "100755")
+            } else {
+                ("100755", "100644")
+            };
+            out.push(RtLine::from(vec![
+                "mode ".dim(),
+                old_mode.red(),
+                " → ".dim(),
+                new_mode.green(),
+            ]));
+        }
         FileChange::Add { content } => {
             let line_number_width = line_number_width(content.lines().count());
+            let highlighted = highlight_lines(content.lines(), language);
             for (i, raw) in content.lines().enumerate() {
                 out.extend(push_wrapped_diff_line(
                     i + 1,
@@ -204,11 +267,13 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                     raw,
                     width,
                     line_number_width,
+                    syntax_spans(&highlighted, i, raw),
                 ));
             }
         }
         FileChange::Delete { content } => {
             let line_number_width = line_number_width(content.lines().count());
+            let highlighted = highlight_lines(content.lines(), language);
             for (i, raw) in content.lines().enumerate() {
                 out.extend(push_wrapped_diff_line(
                     i + 1,
@@ -216,6 +281,7 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                     raw,
                     width,
                     line_number_width,
+                    syntax_spans(&highlighted, i, raw),
                 ));
             }
         }
@@ -253,6 +319,29 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                     }
                     is_first_hunk = false;
 
+                    // Highlight each side of the hunk as a whole so constructs
+                    // spanning several lines (strings, comments) stay intact.
+                    let old_side = highlight_lines(
+                        h.lines().iter().filter_map(|l| match l {
+                            diffy::Line::Delete(text) | diffy::Line::Context(text) => {
+                                Some(text.trim_end_matches('\n'))
+                            }
+                            diffy::Line::Insert(_) => None,
+                        }),
+                        language,
+                    );
+                    let new_side = highlight_lines(
+                        h.lines().iter().filter_map(|l| match l {
+                            diffy::Line::Insert(text) | diffy::Line::Context(text) => {
+                                Some(text.trim_end_matches('\n'))
+                            }
+                            diffy::Line::Delete(_) => None,
+                        }),
+                        language,
+                    );
+                    let mut old_idx = 0;
+                    let mut new_idx = 0;
+
                     let mut old_ln = h.old_range().start();
                     let mut new_ln = h.new_range().start();
                     for l in h.lines() {
@@ -265,8 +354,10 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                                     s,
                                     width,
                                     line_number_width,
+                                    syntax_spans(&new_side, new_idx, s),
                                 ));
                                 new_ln += 1;
+                                new_idx += 1;
                             }
                             diffy::Line::Delete(text) => {
                                 let s = text.trim_end_matches('\n');
@@ -276,8 +367,10 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                                     s,
                                     width,
                                     line_number_width,
+                                    syntax_spans(&old_side, old_idx, s),
                                 ));
                                 old_ln += 1;
+                                old_idx += 1;
                             }
                             diffy::Line::Context(text) => {
                                 let s = text.trim_end_matches('\n');
@@ -287,9 +380,12 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                                     s,
                                     width,
                                     line_number_width,
+                                    syntax_spans(&new_side, new_idx, s),
                                 ));
                                 old_ln += 1;
                                 new_ln += 1;
+                                old_idx += 1;
+                                new_idx += 1;
                             }
                         }
                     }
@@ -342,67 +438,133 @@ fn calculate_add_remove_from_diff(diff: &str) -> (usize, usize) {
     }
 }
 
+/// Highlight `lines` as one block of source in `language`, if any.
+fn highlight_lines<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    language: Option<&'static LanguageSpec>,
+) -> Option<Vec<RtLine<'static>>> {
+    let language = language?;
+    let source = lines.collect::<Vec<_>>().join("\n");
+    Some(highlight_code_to_lines(&source, language))
+}
+
+/// The highlighted spans for line `idx`, provided they still spell out `raw`
+/// (e.g. carriage returns can make the two disagree).
+fn syntax_spans<'a>(
+    highlighted: &'a Option<Vec<RtLine<'static>>>,
+    idx: usize,
+    raw: &str,
+) -> Option<&'a [RtSpan<'static>]> {
+    let line = highlighted.as_ref()?.get(idx)?;
+    let mut rest = raw;
+    for span in &line.spans {
+        rest = rest.strip_prefix(span.content.as_ref())?;
+    }
+    rest.is_empty().then_some(line.spans.as_slice())
+}
+
 fn push_wrapped_diff_line(
     line_number: usize,
     kind: DiffLineType,
     text: &str,
     width: usize,
     line_number_width: usize,
+    syntax: Option<&[RtSpan<'static>]>,
 ) -> Vec<RtLine<'static>> {
     let ln_str = line_number.to_string();
-    let mut remaining_text: &str = text;
 
     // Reserve a fixed number of spaces (equal to the widest line number plus a
     // trailing spacer) so the sign column stays aligned across the diff block.
     let gutter_width = line_number_width.max(1);
     let prefix_cols = gutter_width + 1;
 
-    let mut first = true;
     let (sign_char, line_style) = match kind {
         DiffLineType::Insert => ('+', style_add()),
         DiffLineType::Delete => ('-', style_del()),
         DiffLineType::Context => (' ', style_context()),
     };
+    // Added and removed lines keep their green/red foreground and only pick
+    // up modifiers from the syntax highlighting; context lines take it all.
+    let content_style = |syntax_style: Style| match kind {
+        DiffLineType::Insert | DiffLineType::Delete => Style {
+            add_modifier: syntax_style.add_modifier,
+            ..line_style
+        },
+        DiffLineType::Context => line_style.patch(syntax_style),
+    };
+    let mut remaining: Vec<RtSpan<'static>> = match syntax {
+        Some(spans) => spans
+            .iter()
+            .map(|span| RtSpan::styled(span.content.clone(), content_style(span.style)))
+            .collect(),
+        None => vec![RtSpan::styled(text.to_string(), line_style)],
+    };
     let mut lines: Vec<RtLine<'static>> = Vec::new();
 
+    let mut first = true;
     loop {
-        // Fit the content for the current terminal row:
-        // compute how many columns are available after the prefix, then split
-        // at a UTF-8 character boundary so this row's chunk fits exactly.
+        // Fit the content for the current terminal row: compute how many
+        // columns are available after the prefix and take that many
+        // characters from the remaining spans.
         let available_content_cols = width.saturating_sub(prefix_cols + 1).max(1);
-        let split_at_byte_index = remaining_text
-            .char_indices()
-            .nth(available_content_cols)
-            .map(|(i, _)| i)
-            .unwrap_or_else(|| remaining_text.len());
-        let (chunk, rest) = remaining_text.split_at(split_at_byte_index);
-        remaining_text = rest;
+        let (chunk, rest) = split_spans_at(remaining, available_content_cols);
+        remaining = rest;
 
+        let mut row: Vec<RtSpan<'static>> = Vec::with_capacity(chunk.len() + 2);
         if first {
             // Build gutter (right-aligned line number plus spacer) as a dimmed span
             let gutter = format!("{ln_str:>gutter_width$} ");
-            // Content with a sign ('+'/'-'/' ') styled per diff kind
-            let content = format!("{sign_char}{chunk}");
-            lines.push(RtLine::from(vec![
-                RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(content, line_style),
-            ]));
+            row.push(RtSpan::styled(gutter, style_gutter()));
+            // Sign ('+'/'-'/' ') styled per diff kind
+            row.push(RtSpan::styled(sign_char.to_string(), line_style));
             first = false;
         } else {
             // Continuation lines keep a space for the sign column so content aligns
             let gutter = format!("{:gutter_width$}  ", "");
-            lines.push(RtLine::from(vec![
-                RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(chunk.to_string(), line_style),
-            ]));
+            row.push(RtSpan::styled(gutter, style_gutter()));
         }
-        if remaining_text.is_empty() {
+        row.extend(chunk);
+        lines.push(RtLine::from(row));
+        if remaining.is_empty() {
             break;
         }
     }
     lines
 }
 
+/// Split `spans` after `cols` characters, breaking a span at a UTF-8
+/// character boundary when needed.
+fn split_spans_at(
+    spans: Vec<RtSpan<'static>>,
+    cols: usize,
+) -> (Vec<RtSpan<'static>>, Vec<RtSpan<'static>>) {
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    let mut budget = cols;
+    for span in spans {
+        if span.content.is_empty() {
+            continue;
+        }
+        if !tail.is_empty() || budget == 0 {
+            tail.push(span);
+            continue;
+        }
+        match span.content.char_indices().nth(budget) {
+            Some((split_at, _)) => {
+                let (left, right) = span.content.split_at(split_at);
+                head.push(RtSpan::styled(left.to_string(), span.style));
+                tail.push(RtSpan::styled(right.to_string(), span.style));
+                budget = 0;
+            }
+            None => {
+                budget -= span.content.chars().count();
+                head.push(span);
+            }
+        }
+    }
+    (head, tail)
+}
+
 fn line_number_width(max_line_number: usize) -> usize {
     if max_line_number == 0 {
         1
@@ -497,13 +659,72 @@ mod tests {
         let long_line = "this is a very long line that should wrap across multiple terminal columns and continue";
 
         // Call the wrapping function directly so we can precisely control the width
-        let lines =
-            push_wrapped_diff_line(1, DiffLineType::Insert, long_line, 80, line_number_width(1));
+        let lines = push_wrapped_diff_line(
+            1,
+            DiffLineType::Insert,
+            long_line,
+            80,
+            line_number_width(1),
+            None,
+        );
 
         // Render into a small terminal to capture the visual layout
         snapshot_lines("wrap_behavior_insert", lines, 90, 8);
     }
 
+    #[test]
+    fn update_diff_is_highlighted_by_file_extension() {
+        let original = "fn main() {\n    let a = 1;\n}\n";
+        let modified = "fn main() {\n    let a = 2;\n}\n";
+        let change = FileChange::Update {
+            unified_diff: diffy::create_patch(original, modified).to_string(),
+            move_path: None,
+        };
+        let render = |path: &str| {
+            let changes = HashMap::from([(PathBuf::from(path), change.clone())]);
+            // Skip the header, which names the file.
+            create_diff_summary(&changes, &PathBuf::from("/"), 80).split_off(1)
+        };
+        let text = |lines: &[RtLine<'static>]| -> Vec<String> {
+            lines
+                .iter()
+                .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect()
+        };
+
+        let plain = render("main.txt");
+        let highlighted = render("main.rs");
+        assert_eq!(text(&highlighted), text(&plain));
+
+        let keyword = highlighted[0]
+            .spans
+            .iter()
+            .find(|span| span.content == "fn")
+            .expect("keyword span in context line");
+        assert_ne!(keyword.style, style_context());
+
+        let inserted = highlighted
+            .iter()
+            .find(|line| line.spans.iter().any(|span| span.content == "+"))
+            .expect("inserted line");
+        assert!(
+            inserted
+                .spans
+                .iter()
+                // Skip the indent and the gutter.
+                .skip(2)
+                .all(|span| span.style.fg == Some(Color::Green))
+        );
+    }
+
+    #[test]
+    fn split_spans_at_breaks_inside_a_span() {
+        let spans = vec![RtSpan::raw("ab"), "cdé".green(), RtSpan::raw("")];
+        let (head, tail) = split_spans_at(spans, 4);
+        assert_eq!(head, vec![RtSpan::raw("ab"), "cd".green()]);
+        assert_eq!(tail, vec!["é".green()]);
+    }
+
     #[test]
     fn ui_snapshot_apply_update_block() {
         let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
@@ -4,19 +4,39 @@
 //! `AppEvent::StartFileSearch(query)`. This manager owns a single
 //! `codex-file-search` session for the current search root, updates the query
 //! on every keystroke, and drops the session when the query becomes empty.
+//!
+//! With the `symbol_index` feature, `@#name` queries search the symbol index of
+//! the repository containing the search root instead, shared with the threads
+//! of the thread manager, and answer with `AppEvent::SymbolSearchResult`.
 
+use codex_core::git_info::get_git_repo_root;
 use codex_file_search as file_search;
+use codex_symbol_index::SharedSymbolIndex;
+use codex_symbol_index::Symbol;
+use codex_symbol_index::SymbolIndexes;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 
+/// Prefix of `@` queries that search symbols instead of files.
+const SYMBOL_QUERY_PREFIX: char = '#';
+const MAX_SYMBOL_RESULTS: usize = 50;
+/// How long a symbol query waits for the index to finish its first build.
+const SYMBOL_INDEX_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub(crate) struct FileSearchManager {
     state: Arc<Mutex<SearchState>>,
     search_dir: PathBuf,
     app_tx: AppEventSender,
+    /// Set while symbol search is enabled.
+    symbol_indexes: Option<SymbolIndexes>,
+    /// Looked up on the first symbol query.
+    symbol_index: Option<SharedSymbolIndex>,
 }
 
 struct SearchState {
@@ -35,14 +55,24 @@ impl FileSearchManager {
             })),
             search_dir,
             app_tx: tx,
+            symbol_indexes: None,
+            symbol_index: None,
         }
     }
 
+    /// Routes `@#name` queries to an index from `symbol_indexes`, or disables
+    /// symbol search when `None`.
+    pub fn set_symbol_indexes(&mut self, symbol_indexes: Option<SymbolIndexes>) {
+        self.symbol_indexes = symbol_indexes;
+        self.symbol_index = None;
+    }
+
     /// Updates the directory used for file searches.
     /// This should be called when the session's CWD changes on resume.
     /// Drops the current session so it will be recreated with the new directory on next query.
     pub fn update_search_dir(&mut self, new_dir: PathBuf) {
         self.search_dir = new_dir;
+        self.symbol_index = None;
         #[expect(clippy::unwrap_used)]
         let mut st = self.state.lock().unwrap();
         st.session.take();
@@ -50,7 +80,7 @@ impl FileSearchManager {
     }
 
     /// Call whenever the user edits the `@` token.
-    pub fn on_user_query(&self, query: String) {
+    pub fn on_user_query(&mut self, query: String) {
         #[expect(clippy::unwrap_used)]
         let mut st = self.state.lock().unwrap();
         if query == st.latest_query {
@@ -59,6 +89,13 @@ impl FileSearchManager {
         st.latest_query.clear();
         st.latest_query.push_str(&query);
 
+        if self.symbol_indexes.is_some() && query.starts_with(SYMBOL_QUERY_PREFIX) {
+            st.session.take();
+            drop(st);
+            self.start_symbol_search(query);
+            return;
+        }
+
         if query.is_empty() {
             st.session.take();
             return;
@@ -72,6 +109,50 @@ impl FileSearchManager {
         }
     }
 
+    fn start_symbol_search(&mut self, query: String) {
+        let Some(symbol_indexes) = self.symbol_indexes.as_ref() else {
+            return;
+        };
+        let search_dir = &self.search_dir;
+        let index = self
+            .symbol_index
+            .get_or_insert_with(|| {
+                symbol_indexes.get_or_spawn(
+                    get_git_repo_root(search_dir).unwrap_or_else(|| search_dir.clone()),
+                )
+            })
+            .clone();
+        let search_dir = dunce::canonicalize(search_dir).unwrap_or_else(|_| search_dir.clone());
+        let state = self.state.clone();
+        let app_tx = self.app_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let is_latest = |query: &str| {
+                #[expect(clippy::unwrap_used)]
+                let st = state.lock().unwrap();
+                st.latest_query == query
+            };
+            let name = query.trim_start_matches(SYMBOL_QUERY_PREFIX);
+            let symbols = if name.is_empty() {
+                Vec::new()
+            } else {
+                index.wait_until_ready(SYMBOL_INDEX_TIMEOUT);
+                // The user may have typed on while the index was building.
+                if !is_latest(&query) {
+                    return;
+                }
+                index
+                    .search(name, None, MAX_SYMBOL_RESULTS)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|symbol| relative_to_search_dir(symbol, index.root(), &search_dir))
+                    .collect()
+            };
+            if is_latest(&query) {
+                app_tx.send(AppEvent::SymbolSearchResult { query, symbols });
+            }
+        });
+    }
+
     fn start_session_locked(&self, st: &mut SearchState) {
         st.session_token = st.session_token.wrapping_add(1);
         let session_token = st.session_token;
@@ -130,3 +211,14 @@ impl file_search::SessionReporter for TuiSessionReporter {
 
     fn on_complete(&self) {}
 }
+
+/// Rewrites the path of `symbol`, relative to the index root, to be relative to
+/// the search directory like file search results, or absolute outside of it.
+fn relative_to_search_dir(mut symbol: Symbol, index_root: &Path, search_dir: &Path) -> Symbol {
+    let absolute = index_root.join(&symbol.path);
+    symbol.path = match absolute.strip_prefix(search_dir) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => absolute,
+    };
+    symbol
+}
@@ -0,0 +1,225 @@
+//! Effective key bindings for the shortcuts that `[tui.keymap]` can rebind.
+//!
+//! The keymap is installed once at startup from the loaded config (see
+//! [`init`]) so that key handlers and footer hints anywhere in the TUI can
+//! consult it without threading it through every widget. Actions the user did
+//! not rebind keep their historical matching rules, which are slightly more
+//! lenient than a single chord (for example both Ctrl+V and Alt+V paste an
+//! image).
+
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use codex_core::config::keymap::ChordKey;
+use codex_core::config::keymap::KeyChord;
+pub(crate) use codex_core::config::keymap::KeymapAction;
+use codex_core::config::keymap::TuiKeymap;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+
+use crate::key_hint::KeyBinding;
+
+static KEYMAP: LazyLock<RwLock<Keymap>> = LazyLock::new(|| RwLock::new(Keymap::default()));
+
+/// Installs the keymap resolved from config. Called once during startup.
+pub(crate) fn init(keymap: &TuiKeymap) {
+    let keymap = Keymap::from_config(keymap);
+    match KEYMAP.write() {
+        Ok(mut guard) => *guard = keymap,
+        Err(poisoned) => *poisoned.into_inner() = keymap,
+    }
+}
+
+/// Returns the keymap installed by [`init`], or the defaults before that.
+pub(crate) fn current() -> Keymap {
+    match KEYMAP.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Keymap {
+    overrides: [Option<KeyBinding>; KeymapAction::ALL.len()],
+}
+
+impl Keymap {
+    pub(crate) fn from_config(keymap: &TuiKeymap) -> Self {
+        let mut overrides = [None; KeymapAction::ALL.len()];
+        for action in KeymapAction::ALL {
+            overrides[action as usize] = keymap.get(action).map(key_binding);
+        }
+        Self { overrides }
+    }
+
+    /// The binding the user configured for `action`, if any.
+    pub(crate) fn overridden(&self, action: KeymapAction) -> Option<KeyBinding> {
+        self.overrides[action as usize]
+    }
+
+    /// The binding to show in hints for `action`.
+    pub(crate) fn binding(&self, action: KeymapAction) -> KeyBinding {
+        self.overridden(action)
+            .unwrap_or_else(|| key_binding(action.default_chord()))
+    }
+
+    /// Whether `event` triggers `action`.
+    pub(crate) fn matches(&self, action: KeymapAction, event: KeyEvent) -> bool {
+        if let Some(binding) = self.overridden(action) {
+            return binding.is_press(event);
+        }
+        let pressed = event.kind == KeyEventKind::Press;
+        match action {
+            KeymapAction::Submit => {
+                event.code == KeyCode::Enter && event.modifiers == KeyModifiers::NONE
+            }
+            // The textarea inserts newlines for Shift+Enter, Ctrl+J and friends
+            // on its own, so there is nothing to intercept by default.
+            KeymapAction::Newline => false,
+            KeymapAction::Interrupt => event.code == KeyCode::Esc,
+            KeymapAction::Backtrack => {
+                event.code == KeyCode::Esc
+                    && matches!(event.kind, KeyEventKind::Press | KeyEventKind::Repeat)
+            }
+            KeymapAction::OpenTranscript => {
+                pressed
+                    && event.code == KeyCode::Char('t')
+                    && event.modifiers == KeyModifiers::CONTROL
+            }
+            KeymapAction::ExternalEditor => {
+                pressed
+                    && event.code == KeyCode::Char('g')
+                    && event.modifiers == KeyModifiers::CONTROL
+            }
+            KeymapAction::PasteImage => {
+                pressed
+                    && matches!(event.code, KeyCode::Char(c) if c.eq_ignore_ascii_case(&'v'))
+                    && (event.modifiers.contains(KeyModifiers::CONTROL)
+                        || event.modifiers.contains(KeyModifiers::ALT))
+            }
+            KeymapAction::Approve | KeymapAction::Deny => self.binding(action).is_press(event),
+        }
+    }
+}
+
+/// Converts a config chord into the form crossterm reports for it. Terminals
+/// deliver Shift+letter as the uppercase character and Shift+Tab as `BackTab`.
+fn key_binding(chord: KeyChord) -> KeyBinding {
+    let mut modifiers = KeyModifiers::NONE;
+    if chord.modifiers.ctrl {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+    if chord.modifiers.alt {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if chord.modifiers.shift {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    let shift = chord.modifiers.shift;
+    let key = match chord.key {
+        ChordKey::Char(c) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+        ChordKey::Char(c) => KeyCode::Char(c),
+        ChordKey::Enter => KeyCode::Enter,
+        ChordKey::Esc => KeyCode::Esc,
+        ChordKey::Tab if shift => KeyCode::BackTab,
+        ChordKey::Tab => KeyCode::Tab,
+        ChordKey::Backspace => KeyCode::Backspace,
+        ChordKey::Delete => KeyCode::Delete,
+        ChordKey::Insert => KeyCode::Insert,
+        ChordKey::Up => KeyCode::Up,
+        ChordKey::Down => KeyCode::Down,
+        ChordKey::Left => KeyCode::Left,
+        ChordKey::Right => KeyCode::Right,
+        ChordKey::Home => KeyCode::Home,
+        ChordKey::End => KeyCode::End,
+        ChordKey::PageUp => KeyCode::PageUp,
+        ChordKey::PageDown => KeyCode::PageDown,
+        ChordKey::F(n) => KeyCode::F(n),
+    };
+    KeyBinding::new(key, modifiers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config::keymap::TuiKeymapToml;
+    use crossterm::event::KeyEventState;
+    use pretty_assertions::assert_eq;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn keymap_from(toml: &str) -> Keymap {
+        let toml: TuiKeymapToml = toml::from_str(toml).expect("parse keymap");
+        let keymap =
+            codex_core::config::keymap::resolve_tui_keymap(Some(&toml)).expect("resolve keymap");
+        Keymap::from_config(&keymap)
+    }
+
+    #[test]
+    fn defaults_keep_legacy_matching() {
+        let keymap = Keymap::default();
+        assert!(keymap.matches(
+            KeymapAction::PasteImage,
+            press(KeyCode::Char('V'), KeyModifiers::ALT)
+        ));
+        assert!(keymap.matches(
+            KeymapAction::Submit,
+            press(KeyCode::Enter, KeyModifiers::NONE)
+        ));
+        assert!(!keymap.matches(
+            KeymapAction::Newline,
+            press(KeyCode::Enter, KeyModifiers::SHIFT)
+        ));
+        let repeat = KeyEvent {
+            kind: KeyEventKind::Repeat,
+            state: KeyEventState::NONE,
+            ..press(KeyCode::Char('t'), KeyModifiers::CONTROL)
+        };
+        assert!(!keymap.matches(KeymapAction::OpenTranscript, repeat));
+        assert_eq!(
+            keymap.binding(KeymapAction::ExternalEditor),
+            KeyBinding::new(KeyCode::Char('g'), KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn overrides_replace_default_bindings() {
+        let keymap = keymap_from(
+            r#"
+submit = "ctrl+enter"
+newline = "enter"
+open_transcript = "ctrl+shift+t"
+backtrack = "shift+tab"
+"#,
+        );
+
+        assert!(!keymap.matches(
+            KeymapAction::Submit,
+            press(KeyCode::Enter, KeyModifiers::NONE)
+        ));
+        assert!(keymap.matches(
+            KeymapAction::Submit,
+            press(KeyCode::Enter, KeyModifiers::CONTROL)
+        ));
+        assert!(keymap.matches(
+            KeymapAction::Newline,
+            press(KeyCode::Enter, KeyModifiers::NONE)
+        ));
+        assert!(keymap.matches(
+            KeymapAction::OpenTranscript,
+            press(
+                KeyCode::Char('T'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )
+        ));
+        assert_eq!(
+            keymap.overridden(KeymapAction::Backtrack),
+            Some(KeyBinding::new(KeyCode::BackTab, KeyModifiers::SHIFT))
+        );
+        assert_eq!(keymap.overridden(KeymapAction::PasteImage), None);
+    }
+}
@@ -77,6 +77,7 @@ mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
 mod key_hint;
+mod keymap;
 pub mod live_wrap;
 mod markdown;
 mod markdown_render;
@@ -101,6 +102,7 @@ mod streaming;
 mod style;
 mod terminal_palette;
 mod text_formatting;
+mod theme;
 mod tooltips;
 mod tui;
 mod ui_consts;
@@ -458,6 +460,8 @@ async fn run_ratatui_app(
 
     // Initialize high-fidelity session event logging if enabled.
     session_log::maybe_init(&initial_config);
+    keymap::init(&initial_config.tui_keymap);
+    theme::init(initial_config.tui_theme);
 
     let auth_manager = AuthManager::shared(
         initial_config.codex_home.clone(),
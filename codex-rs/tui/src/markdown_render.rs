@@ -1,3 +1,6 @@
+use crate::render::highlight::LanguageSpec;
+use crate::render::highlight::highlight_code_to_lines;
+use crate::render::highlight::language_for_fence;
 use crate::render::line_utils::line_to_static;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
@@ -99,6 +102,9 @@ where
     pending_marker_line: bool,
     in_paragraph: bool,
     in_code_block: bool,
+    /// Source of the current fenced code block, buffered until the block ends
+    /// so it can be highlighted as a whole. `None` for unrecognized languages.
+    code_block_source: Option<(&'static LanguageSpec, String)>,
     wrap_width: Option<usize>,
     current_line_content: Option<Line<'static>>,
     current_initial_indent: Vec<Span<'static>>,
@@ -124,6 +130,7 @@ where
             pending_marker_line: false,
             in_paragraph: false,
             in_code_block: false,
+            code_block_source: None,
             wrap_width,
             current_line_content: None,
             current_initial_indent: Vec::new(),
@@ -274,6 +281,19 @@ where
     }
 
     fn text(&mut self, text: CowStr<'a>) {
+        if let Some((_, source)) = self.code_block_source.as_mut() {
+            source.push_str(&text);
+            return;
+        }
+        let style = self.inline_styles.last().copied().unwrap_or_default();
+        let lines = text
+            .lines()
+            .map(|line| vec![Span::styled(line.to_string(), style)])
+            .collect();
+        self.push_text_lines(lines);
+    }
+
+    fn push_text_lines(&mut self, lines: Vec<Vec<Span<'static>>>) {
         if self.pending_marker_line {
             self.push_line(Line::default());
         }
@@ -294,7 +314,7 @@ where
                 self.push_line(Line::default());
             }
         }
-        for (i, line) in text.lines().enumerate() {
+        for (i, spans) in lines.into_iter().enumerate() {
             if self.needs_newline {
                 self.push_line(Line::default());
                 self.needs_newline = false;
@@ -302,12 +322,9 @@ where
             if i > 0 {
                 self.push_line(Line::default());
             }
-            let content = line.to_string();
-            let span = Span::styled(
-                content,
-                self.inline_styles.last().copied().unwrap_or_default(),
-            );
-            self.push_span(span);
+            for span in spans {
+                self.push_span(span);
+            }
         }
         self.needs_newline = false;
     }
@@ -394,12 +411,16 @@ where
         self.needs_newline = false;
     }
 
-    fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
+    fn start_codeblock(&mut self, lang: Option<String>, indent: Option<Span<'static>>) {
         self.flush_current_line();
         if !self.text.lines.is_empty() {
             self.push_blank_line();
         }
         self.in_code_block = true;
+        self.code_block_source = lang
+            .as_deref()
+            .and_then(language_for_fence)
+            .map(|language| (language, String::new()));
         self.indent_stack.push(IndentContext::new(
             vec![indent.unwrap_or_default()],
             None,
@@ -409,6 +430,17 @@ where
     }
 
     fn end_codeblock(&mut self) {
+        if let Some((language, source)) = self.code_block_source.take() {
+            if !source.is_empty() {
+                // Mirror `str::lines`, which drops the newline ending the block.
+                let source = source.strip_suffix('\n').unwrap_or(source.as_str());
+                let lines = highlight_code_to_lines(source, language)
+                    .into_iter()
+                    .map(|line| line.spans)
+                    .collect();
+                self.push_text_lines(lines);
+            }
+        }
         self.needs_newline = true;
         self.in_code_block = false;
         self.indent_stack.pop();
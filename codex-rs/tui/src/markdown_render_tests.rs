@@ -1,4 +1,5 @@
 use pretty_assertions::assert_eq;
+use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
@@ -652,12 +653,35 @@ fn link() {
 }
 
 #[test]
-fn code_block_unhighlighted() {
-    let text = render_markdown_text("```rust\nfn main() {}\n```\n");
+fn code_block_unknown_language_unhighlighted() {
+    let text = render_markdown_text("```text\nfn main() {}\n```\n");
     let expected = Text::from_iter([Line::from_iter(["", "fn main() {}"])]);
     assert_eq!(text, expected);
 }
 
+#[test]
+fn code_block_highlighted_by_fence_tag() {
+    let text = render_markdown_text("```rust\nfn main() {\n\n    1\n}\n```\n");
+    let lines: Vec<String> = text
+        .lines
+        .iter()
+        .map(|l| {
+            l.spans
+                .iter()
+                .map(|s| s.content.clone())
+                .collect::<String>()
+        })
+        .collect();
+    assert_eq!(lines, vec!["fn main() {", "", "    1", "}"]);
+
+    let keyword = text.lines[0]
+        .spans
+        .iter()
+        .find(|span| span.content == "fn")
+        .expect("keyword span");
+    assert_ne!(keyword.style, Style::default());
+}
+
 #[test]
 fn code_block_multiple_lines_root() {
     let md = "```\nfirst\nsecond\n```\n";
@@ -2,89 +2,312 @@ use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use std::path::Path;
 use std::sync::OnceLock;
 use tree_sitter_highlight::Highlight;
 use tree_sitter_highlight::HighlightConfiguration;
 use tree_sitter_highlight::HighlightEvent;
 use tree_sitter_highlight::Highlighter;
 
-// Ref: https://github.com/tree-sitter/tree-sitter-bash/blob/master/queries/highlights.scm
+// Capture names shared by the bundled highlight queries. Tree-sitter resolves
+// dotted captures by prefix, so `function.method` is styled as `function`.
+// Ref: https://tree-sitter.github.io/tree-sitter/3-syntax-highlighting.html
 #[derive(Copy, Clone)]
-enum BashHighlight {
+enum SyntaxHighlight {
+    Attribute,
     Comment,
     Constant,
+    Constructor,
     Embedded,
+    Escape,
     Function,
     Keyword,
     Number,
     Operator,
     Property,
     String,
+    Tag,
+    Type,
 }
 
-impl BashHighlight {
-    const ALL: [Self; 9] = [
+impl SyntaxHighlight {
+    const ALL: [Self; 14] = [
+        Self::Attribute,
         Self::Comment,
         Self::Constant,
+        Self::Constructor,
         Self::Embedded,
+        Self::Escape,
         Self::Function,
         Self::Keyword,
         Self::Number,
         Self::Operator,
         Self::Property,
         Self::String,
+        Self::Tag,
+        Self::Type,
     ];
 
     const fn as_str(self) -> &'static str {
         match self {
+            Self::Attribute => "attribute",
             Self::Comment => "comment",
             Self::Constant => "constant",
+            Self::Constructor => "constructor",
             Self::Embedded => "embedded",
+            Self::Escape => "escape",
             Self::Function => "function",
             Self::Keyword => "keyword",
             Self::Number => "number",
             Self::Operator => "operator",
             Self::Property => "property",
             Self::String => "string",
+            Self::Tag => "tag",
+            Self::Type => "type",
         }
     }
 
-    fn style(self) -> Style {
+    /// Style for shell commands shown in exec cells and approval prompts,
+    /// which only de-emphasize the noise around the command itself.
+    fn command_style(self) -> Style {
         match self {
             Self::Comment | Self::Operator | Self::String => Style::default().dim(),
             _ => Style::default(),
         }
     }
+
+    /// Style for source code in markdown fences and diffs. Sticks to the ANSI
+    /// colors from `tui/styles.md` so the terminal theme decides the actual
+    /// shades, and falls back to modifiers when colors are unavailable.
+    fn code_style(self, palette: CodePalette) -> Style {
+        match (palette, self) {
+            (_, Self::Comment) => Style::default().dim().italic(),
+            (_, Self::Attribute | Self::Operator) => Style::default().dim(),
+            (CodePalette::Ansi, Self::Keyword) => Style::default().magenta(),
+            (CodePalette::Ansi, Self::String) => Style::default().green(),
+            (CodePalette::Ansi, Self::Function | Self::Tag) => Style::default().cyan(),
+            (CodePalette::Ansi, Self::Type | Self::Constructor) => Style::default().bold(),
+            (CodePalette::Ansi, Self::Constant | Self::Number | Self::Escape) => {
+                Style::default().italic()
+            }
+            (CodePalette::Monochrome, Self::Keyword) => Style::default().bold(),
+            (CodePalette::Monochrome, Self::String) => Style::default().dim(),
+            (CodePalette::Monochrome, Self::Type | Self::Constructor) => Style::default().italic(),
+            _ => Style::default(),
+        }
+    }
 }
 
-static HIGHLIGHT_CONFIG: OnceLock<HighlightConfiguration> = OnceLock::new();
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum CodePalette {
+    Ansi,
+    Monochrome,
+}
+
+impl CodePalette {
+    fn detect() -> Self {
+        if crate::terminal_palette::supports_ansi_colors() {
+            Self::Ansi
+        } else {
+            Self::Monochrome
+        }
+    }
+}
 
 fn highlight_names() -> &'static [&'static str] {
-    static NAMES: OnceLock<[&'static str; BashHighlight::ALL.len()]> = OnceLock::new();
+    static NAMES: OnceLock<[&'static str; SyntaxHighlight::ALL.len()]> = OnceLock::new();
     NAMES
-        .get_or_init(|| BashHighlight::ALL.map(BashHighlight::as_str))
+        .get_or_init(|| SyntaxHighlight::ALL.map(SyntaxHighlight::as_str))
         .as_slice()
 }
 
-fn highlight_config() -> &'static HighlightConfiguration {
-    HIGHLIGHT_CONFIG.get_or_init(|| {
-        let language = tree_sitter_bash::LANGUAGE.into();
-        #[expect(clippy::expect_used)]
-        let mut config = HighlightConfiguration::new(
-            language,
-            "bash",
-            tree_sitter_bash::HIGHLIGHT_QUERY,
-            "",
-            "",
-        )
-        .expect("load bash highlight query");
-        config.configure(highlight_names());
-        config
-    })
+fn highlight_for(highlight: Highlight) -> SyntaxHighlight {
+    SyntaxHighlight::ALL[highlight.0]
 }
 
-fn highlight_for(highlight: Highlight) -> BashHighlight {
-    BashHighlight::ALL[highlight.0]
+/// A grammar bundled with the TUI, along with the names it is known by.
+/// Supporting another language only requires a new entry in [`LANGUAGES`].
+pub(crate) struct LanguageSpec {
+    name: &'static str,
+    /// Markdown fence tags, besides `name`, that select this language.
+    aliases: &'static [&'static str],
+    /// File extensions (without the dot) used to pick a language for diffs.
+    extensions: &'static [&'static str],
+    grammar: fn() -> tree_sitter::Language,
+    /// Highlight queries, concatenated in order. Grammars that extend another
+    /// one (e.g. TypeScript) list their own query before the base query.
+    highlights: &'static [&'static str],
+    locals: &'static [&'static str],
+    config: OnceLock<Option<HighlightConfiguration>>,
+}
+
+impl LanguageSpec {
+    const fn new(
+        name: &'static str,
+        aliases: &'static [&'static str],
+        extensions: &'static [&'static str],
+        grammar: fn() -> tree_sitter::Language,
+        highlights: &'static [&'static str],
+        locals: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            aliases,
+            extensions,
+            grammar,
+            highlights,
+            locals,
+            config: OnceLock::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn config(&self) -> Option<&HighlightConfiguration> {
+        self.config
+            .get_or_init(|| {
+                let mut config = HighlightConfiguration::new(
+                    (self.grammar)(),
+                    self.name,
+                    &self.highlights.join("\n"),
+                    "",
+                    &self.locals.join("\n"),
+                )
+                .ok()?;
+                config.configure(highlight_names());
+                Some(config)
+            })
+            .as_ref()
+    }
+}
+
+static BASH: LanguageSpec = LanguageSpec::new(
+    "bash",
+    &["sh", "shell", "zsh", "console"],
+    &["sh", "bash", "zsh"],
+    || tree_sitter_bash::LANGUAGE.into(),
+    &[tree_sitter_bash::HIGHLIGHT_QUERY],
+    &[],
+);
+
+static GO: LanguageSpec = LanguageSpec::new(
+    "go",
+    &["golang"],
+    &["go"],
+    || tree_sitter_go::LANGUAGE.into(),
+    &[tree_sitter_go::HIGHLIGHTS_QUERY],
+    &[],
+);
+
+static JAVASCRIPT: LanguageSpec = LanguageSpec::new(
+    "javascript",
+    &["js", "jsx", "mjs", "cjs"],
+    &["js", "jsx", "mjs", "cjs"],
+    || tree_sitter_javascript::LANGUAGE.into(),
+    &[
+        tree_sitter_javascript::HIGHLIGHT_QUERY,
+        tree_sitter_javascript::JSX_HIGHLIGHT_QUERY,
+    ],
+    &[tree_sitter_javascript::LOCALS_QUERY],
+);
+
+static JSON: LanguageSpec = LanguageSpec::new(
+    "json",
+    &["jsonc", "json5"],
+    &["json", "jsonc"],
+    || tree_sitter_json::LANGUAGE.into(),
+    &[tree_sitter_json::HIGHLIGHTS_QUERY],
+    &[],
+);
+
+static PYTHON: LanguageSpec = LanguageSpec::new(
+    "python",
+    &["py", "python3"],
+    &["py", "pyi"],
+    || tree_sitter_python::LANGUAGE.into(),
+    &[tree_sitter_python::HIGHLIGHTS_QUERY],
+    &[],
+);
+
+static RUST: LanguageSpec = LanguageSpec::new(
+    "rust",
+    &["rs"],
+    &["rs"],
+    || tree_sitter_rust::LANGUAGE.into(),
+    &[tree_sitter_rust::HIGHLIGHTS_QUERY],
+    &[],
+);
+
+static TYPESCRIPT: LanguageSpec = LanguageSpec::new(
+    "typescript",
+    &["ts", "mts", "cts"],
+    &["ts", "mts", "cts"],
+    || tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+    &[
+        tree_sitter_typescript::HIGHLIGHTS_QUERY,
+        tree_sitter_javascript::HIGHLIGHT_QUERY,
+    ],
+    &[
+        tree_sitter_typescript::LOCALS_QUERY,
+        tree_sitter_javascript::LOCALS_QUERY,
+    ],
+);
+
+static TSX: LanguageSpec = LanguageSpec::new(
+    "tsx",
+    &[],
+    &["tsx"],
+    || tree_sitter_typescript::LANGUAGE_TSX.into(),
+    &[
+        tree_sitter_typescript::HIGHLIGHTS_QUERY,
+        tree_sitter_javascript::HIGHLIGHT_QUERY,
+        tree_sitter_javascript::JSX_HIGHLIGHT_QUERY,
+    ],
+    &[
+        tree_sitter_typescript::LOCALS_QUERY,
+        tree_sitter_javascript::LOCALS_QUERY,
+    ],
+);
+
+static LANGUAGES: [&LanguageSpec; 8] = [
+    &BASH,
+    &GO,
+    &JAVASCRIPT,
+    &JSON,
+    &PYTHON,
+    &RUST,
+    &TYPESCRIPT,
+    &TSX,
+];
+
+/// Resolve a markdown fence info string such as `rust`, `py` or
+/// `ts title="a.ts"` to a bundled language.
+pub(crate) fn language_for_fence(info: &str) -> Option<&'static LanguageSpec> {
+    let tag = info
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('.')
+        .to_ascii_lowercase();
+    if tag.is_empty() {
+        return None;
+    }
+    LANGUAGES
+        .iter()
+        .copied()
+        .find(|lang| lang.name == tag || lang.aliases.contains(&tag.as_str()))
+}
+
+/// Pick a bundled language from the extension of `path`.
+pub(crate) fn language_for_path(path: &Path) -> Option<&'static LanguageSpec> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .copied()
+        .find(|lang| lang.extensions.contains(&extension.as_str()))
 }
 
 fn push_segment(lines: &mut Vec<Line<'static>>, segment: &str, style: Option<Style>) {
@@ -105,16 +328,28 @@ fn push_segment(lines: &mut Vec<Line<'static>>, segment: &str, style: Option<Sty
     }
 }
 
-/// Convert a bash script into per-line styled content using tree-sitter's
-/// bash highlight query. The highlighter is streamed so multi-line content is
-/// split into `Line`s while preserving style boundaries.
-pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
+/// Run the highlighter over `source`, splitting the result into `Line`s while
+/// preserving style boundaries. Falls back to unstyled lines if the source
+/// cannot be highlighted.
+fn highlight_to_lines(
+    source: &str,
+    config: Option<&HighlightConfiguration>,
+    style_for: impl Fn(SyntaxHighlight) -> Style,
+) -> Vec<Line<'static>> {
+    let plain = || -> Vec<Line<'static>> {
+        source
+            .split('\n')
+            .map(|line| Line::from(line.to_string()))
+            .collect()
+    };
+    let Some(config) = config else {
+        return plain();
+    };
     let mut highlighter = Highlighter::new();
-    let iterator =
-        match highlighter.highlight(highlight_config(), script.as_bytes(), None, |_| None) {
-            Ok(iter) => iter,
-            Err(_) => return vec![script.to_string().into()],
-        };
+    let iterator = match highlighter.highlight(config, source.as_bytes(), None, |_| None) {
+        Ok(iter) => iter,
+        Err(_) => return plain(),
+    };
 
     let mut lines: Vec<Line<'static>> = vec![Line::from("")];
     let mut highlight_stack: Vec<Highlight> = Vec::new();
@@ -129,10 +364,10 @@ pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
                 if start == end {
                     continue;
                 }
-                let style = highlight_stack.last().map(|h| highlight_for(*h).style());
-                push_segment(&mut lines, &script[start..end], style);
+                let style = highlight_stack.last().map(|h| style_for(highlight_for(*h)));
+                push_segment(&mut lines, &source[start..end], style);
             }
-            Err(_) => return vec![script.to_string().into()],
+            Err(_) => return plain(),
         }
     }
 
@@ -143,10 +378,34 @@ pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
     }
 }
 
+/// Convert a bash script into per-line styled content using tree-sitter's
+/// bash highlight query. The highlighter is streamed so multi-line content is
+/// split into `Line`s while preserving style boundaries.
+pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
+    highlight_to_lines(script, BASH.config(), SyntaxHighlight::command_style)
+}
+
+/// Highlight source code in `language`, one `Line` per line of `code`. Colors
+/// are only used when the terminal reports ANSI color support.
+pub(crate) fn highlight_code_to_lines(code: &str, language: &LanguageSpec) -> Vec<Line<'static>> {
+    highlight_code_with_palette(code, language, CodePalette::detect())
+}
+
+fn highlight_code_with_palette(
+    code: &str,
+    language: &LanguageSpec,
+    palette: CodePalette,
+) -> Vec<Line<'static>> {
+    highlight_to_lines(code, language.config(), |highlight| {
+        highlight.code_style(palette)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use ratatui::style::Color;
     use ratatui::style::Modifier;
 
     fn reconstructed(lines: &[Line<'static>]) -> String {
@@ -173,6 +432,15 @@ mod tests {
             .collect()
     }
 
+    fn style_of(lines: &[Line<'static>], token: &str) -> Style {
+        lines
+            .iter()
+            .flat_map(|l| l.spans.iter())
+            .find(|sp| sp.content.as_ref() == token)
+            .map(|sp| sp.style)
+            .unwrap_or_else(|| panic!("missing span for {token:?}"))
+    }
+
     #[test]
     fn dims_expected_bash_operators() {
         let s = "echo foo && bar || baz | qux & (echo hi)";
@@ -233,4 +501,72 @@ mod tests {
         let body_style = body_style.expect("missing heredoc span");
         assert!(body_style.add_modifier.contains(Modifier::DIM));
     }
+
+    #[test]
+    fn every_bundled_language_loads() {
+        for language in LANGUAGES {
+            assert!(
+                language.config().is_some(),
+                "failed to load highlight query for {}",
+                language.name
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_fence_tags_and_extensions() {
+        let name = |lang: Option<&LanguageSpec>| lang.map(LanguageSpec::name);
+        assert_eq!(name(language_for_fence("rust")), Some("rust"));
+        assert_eq!(name(language_for_fence("Py")), Some("python"));
+        assert_eq!(
+            name(language_for_fence("ts title=\"a.ts\"")),
+            Some("typescript")
+        );
+        assert_eq!(name(language_for_fence("sh,ignore")), Some("bash"));
+        assert_eq!(name(language_for_fence("text")), None);
+        assert_eq!(name(language_for_fence("")), None);
+        assert_eq!(
+            name(language_for_path(Path::new("src/lib.rs"))),
+            Some("rust")
+        );
+        assert_eq!(name(language_for_path(Path::new("App.TSX"))), Some("tsx"));
+        assert_eq!(name(language_for_path(Path::new("README.md"))), None);
+        assert_eq!(name(language_for_path(Path::new("Makefile"))), None);
+    }
+
+    #[test]
+    fn highlights_rust_with_ansi_palette() {
+        let code = "// entry\nfn main() {\n    let s = \"hi\";\n}";
+        let language = language_for_fence("rust").expect("rust");
+        let lines = highlight_code_with_palette(code, language, CodePalette::Ansi);
+        assert_eq!(reconstructed(&lines), code);
+        assert_eq!(lines.len(), 4);
+
+        assert_eq!(style_of(&lines, "fn").fg, Some(Color::Magenta));
+        assert_eq!(style_of(&lines, "let").fg, Some(Color::Magenta));
+        assert_eq!(style_of(&lines, "main").fg, Some(Color::Cyan));
+        assert_eq!(style_of(&lines, "\"hi\"").fg, Some(Color::Green));
+        let comment = style_of(&lines, "// entry");
+        assert!(comment.add_modifier.contains(Modifier::DIM));
+        assert!(comment.add_modifier.contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn monochrome_palette_uses_modifiers_only() {
+        let code = "def greet(name):\n    return f\"hi {name}\"\n";
+        let language = language_for_fence("python").expect("python");
+        let lines = highlight_code_with_palette(code, language, CodePalette::Monochrome);
+        assert_eq!(reconstructed(&lines), code);
+        assert!(
+            lines
+                .iter()
+                .flat_map(|l| l.spans.iter())
+                .all(|sp| sp.style.fg.is_none())
+        );
+        assert!(
+            style_of(&lines, "def")
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+    }
 }
@@ -14,6 +14,7 @@ pub enum SlashCommand {
     // more frequently used commands should be listed first.
     Model,
     Approvals,
+    RepoApprovals,
     Permissions,
     #[strum(serialize = "setup-elevated-sandbox")]
     ElevateSandbox,
@@ -21,12 +22,15 @@ pub enum SlashCommand {
     Skills,
     Review,
     Rename,
+    Pin,
+    Unpin,
     New,
     Resume,
     Fork,
     Init,
     Compact,
     Plan,
+    RunPlan,
     Collab,
     Agent,
     // Undo,
@@ -42,6 +46,7 @@ pub enum SlashCommand {
     Rollout,
     Ps,
     Personality,
+    Theme,
     TestApproval,
 }
 
@@ -55,6 +60,8 @@ impl SlashCommand {
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Rename => "rename the current thread",
+            SlashCommand::Pin => "pin a recent message so it survives compaction",
+            SlashCommand::Unpin => "list or remove pinned messages",
             SlashCommand::Resume => "resume a saved chat",
             SlashCommand::Fork => "fork the current chat",
             // SlashCommand::Undo => "ask Codex to undo a turn",
@@ -66,10 +73,13 @@ impl SlashCommand {
             SlashCommand::Ps => "list background terminals",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Personality => "choose a communication style for Codex",
+            SlashCommand::Theme => "choose a color theme",
             SlashCommand::Plan => "switch to Plan mode",
+            SlashCommand::RunPlan => "execute a saved plan step by step",
             SlashCommand::Collab => "change collaboration mode (experimental)",
             SlashCommand::Agent => "switch the active agent thread",
             SlashCommand::Approvals => "choose what Codex can do without approval",
+            SlashCommand::RepoApprovals => "list or revoke approvals remembered for this repo",
             SlashCommand::Permissions => "choose what Codex is allowed to do",
             SlashCommand::ElevateSandbox => "set up elevated agent sandbox",
             SlashCommand::Experimental => "toggle experimental features",
@@ -91,7 +101,11 @@ impl SlashCommand {
     pub fn supports_inline_args(self) -> bool {
         matches!(
             self,
-            SlashCommand::Review | SlashCommand::Rename | SlashCommand::Plan
+            SlashCommand::Review
+                | SlashCommand::Rename
+                | SlashCommand::Pin
+                | SlashCommand::Unpin
+                | SlashCommand::Plan
         )
     }
 
@@ -112,9 +126,12 @@ impl SlashCommand {
             | SlashCommand::Experimental
             | SlashCommand::Review
             | SlashCommand::Plan
+            | SlashCommand::RunPlan
             | SlashCommand::Logout => false,
             SlashCommand::Diff
             | SlashCommand::Rename
+            | SlashCommand::Pin
+            | SlashCommand::Unpin
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
@@ -122,6 +139,8 @@ impl SlashCommand {
             | SlashCommand::Mcp
             | SlashCommand::Apps
             | SlashCommand::Feedback
+            | SlashCommand::Theme
+            | SlashCommand::RepoApprovals
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
             SlashCommand::Rollout => true,
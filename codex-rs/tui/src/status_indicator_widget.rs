@@ -5,7 +5,6 @@ use std::time::Duration;
 use std::time::Instant;
 
 use codex_core::protocol::Op;
-use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
@@ -19,7 +18,8 @@ use unicode_width::UnicodeWidthStr;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::exec_cell::spinner;
-use crate::key_hint;
+use crate::keymap;
+use crate::keymap::KeymapAction;
 use crate::render::renderable::Renderable;
 use crate::shimmer::shimmer_spans;
 use crate::text_formatting::capitalize_first;
@@ -219,7 +219,7 @@ impl Renderable for StatusIndicatorWidget {
         if self.show_interrupt_hint {
             spans.extend(vec![
                 format!("({pretty_elapsed} • ").dim(),
-                key_hint::plain(KeyCode::Esc).into(),
+                keymap::current().binding(KeymapAction::Interrupt).into(),
                 " to interrupt)".dim(),
             ]);
         } else {
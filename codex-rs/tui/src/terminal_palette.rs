@@ -33,6 +33,12 @@ pub fn best_color(target: (u8, u8, u8)) -> Color {
     }
 }
 
+/// Returns whether the terminal reports support for at least the basic ANSI
+/// colors, so callers can fall back to modifiers like bold/dim otherwise.
+pub fn supports_ansi_colors() -> bool {
+    supports_color::on_cached(supports_color::Stream::Stdout).is_some_and(|level| level.has_basic)
+}
+
 pub fn requery_default_colors() {
     imp::requery_default_colors();
     bump_palette_version();
@@ -0,0 +1,203 @@
+//! Named color themes selected with `tui.theme` or `/theme`.
+//!
+//! Widgets keep choosing their own styles; the active theme is applied as a
+//! final pass over every rendered frame and over history lines before they are
+//! written to scrollback. That keeps diffs, markdown, the status indicator and
+//! approval overlays consistent without each widget knowing about themes.
+
+use std::ffi::OsString;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+pub(crate) use codex_core::config::types::TuiTheme;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+
+static THEME: LazyLock<RwLock<TuiTheme>> = LazyLock::new(|| RwLock::new(TuiTheme::Default));
+
+/// Installs the theme from config, falling back to `monochrome` when
+/// `NO_COLOR` is set and no theme was configured.
+pub(crate) fn init(configured: Option<TuiTheme>) {
+    set(resolve(configured, std::env::var_os("NO_COLOR")));
+}
+
+/// Switches the active theme for everything rendered from now on.
+pub(crate) fn set(theme: TuiTheme) {
+    match THEME.write() {
+        Ok(mut guard) => *guard = theme,
+        Err(poisoned) => *poisoned.into_inner() = theme,
+    }
+}
+
+pub(crate) fn current() -> TuiTheme {
+    match THEME.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+pub(crate) fn label(theme: TuiTheme) -> &'static str {
+    match theme {
+        TuiTheme::Default => "Default",
+        TuiTheme::HighContrast => "High contrast",
+        TuiTheme::Monochrome => "Monochrome",
+    }
+}
+
+pub(crate) fn description(theme: TuiTheme) -> &'static str {
+    match theme {
+        TuiTheme::Default => "Colors tuned to your terminal background.",
+        TuiTheme::HighContrast => "Bright colors and no dimmed text.",
+        TuiTheme::Monochrome => "No colors; emphasis uses bold and underlines.",
+    }
+}
+
+fn resolve(configured: Option<TuiTheme>, no_color: Option<OsString>) -> TuiTheme {
+    configured.unwrap_or_else(|| {
+        // https://no-color.org: any non-empty value disables color.
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            TuiTheme::Monochrome
+        } else {
+            TuiTheme::Default
+        }
+    })
+}
+
+/// Restyles every cell of a rendered frame for the active theme.
+pub(crate) fn apply_to_buffer(buf: &mut Buffer) {
+    let theme = current();
+    if theme == TuiTheme::Default {
+        return;
+    }
+    for cell in &mut buf.content {
+        cell.fg = adapt_color(theme, cell.fg, Layer::Foreground);
+        cell.bg = adapt_color(theme, cell.bg, Layer::Background);
+        cell.modifier = adapt_modifier(theme, cell.modifier);
+    }
+}
+
+/// Restyles a history line for the active theme before it reaches scrollback.
+pub(crate) fn apply_to_line(mut line: Line<'static>) -> Line<'static> {
+    let theme = current();
+    if theme == TuiTheme::Default {
+        return line;
+    }
+    line.style = adapt_style(theme, line.style);
+    for span in &mut line.spans {
+        span.style = adapt_style(theme, span.style);
+    }
+    line
+}
+
+#[derive(Clone, Copy)]
+enum Layer {
+    Foreground,
+    Background,
+}
+
+fn adapt_style(theme: TuiTheme, style: Style) -> Style {
+    Style {
+        fg: style
+            .fg
+            .map(|color| adapt_color(theme, color, Layer::Foreground)),
+        bg: style
+            .bg
+            .map(|color| adapt_color(theme, color, Layer::Background)),
+        add_modifier: adapt_modifier(theme, style.add_modifier),
+        ..style
+    }
+}
+
+fn adapt_color(theme: TuiTheme, color: Color, layer: Layer) -> Color {
+    match (theme, layer) {
+        (TuiTheme::Default, _) => color,
+        (TuiTheme::Monochrome, _) => Color::Reset,
+        // Subtle background tints (user messages, diff lines) stay; they never
+        // carry text on their own.
+        (TuiTheme::HighContrast, Layer::Background) => color,
+        (TuiTheme::HighContrast, Layer::Foreground) => match color {
+            Color::Red => Color::LightRed,
+            Color::Green => Color::LightGreen,
+            Color::Yellow => Color::LightYellow,
+            Color::Blue => Color::LightBlue,
+            Color::Magenta => Color::LightMagenta,
+            Color::Cyan => Color::LightCyan,
+            // Grays and blended truecolor/256-color shades are what make text
+            // hard to read against the background, so use the terminal's
+            // default foreground instead.
+            Color::Gray | Color::DarkGray | Color::Rgb(..) | Color::Indexed(_) => Color::Reset,
+            other => other,
+        },
+    }
+}
+
+fn adapt_modifier(theme: TuiTheme, modifier: Modifier) -> Modifier {
+    match theme {
+        TuiTheme::HighContrast => modifier - Modifier::DIM,
+        TuiTheme::Default | TuiTheme::Monochrome => modifier,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::layout::Rect;
+    use ratatui::style::Stylize;
+    use ratatui::text::Span;
+
+    #[test]
+    fn no_color_selects_monochrome_only_when_unconfigured() {
+        assert_eq!(resolve(None, None), TuiTheme::Default);
+        assert_eq!(resolve(None, Some("1".into())), TuiTheme::Monochrome);
+        assert_eq!(resolve(None, Some("".into())), TuiTheme::Default);
+        assert_eq!(
+            resolve(Some(TuiTheme::HighContrast), Some("1".into())),
+            TuiTheme::HighContrast
+        );
+    }
+
+    #[test]
+    fn high_contrast_brightens_colors_and_drops_dim() {
+        let style = adapt_style(
+            TuiTheme::HighContrast,
+            Style::default().green().dim().bold(),
+        );
+        assert_eq!(style, Style::default().light_green().bold());
+        let style = adapt_style(TuiTheme::HighContrast, Style::default().dark_gray());
+        assert_eq!(style, Style::default().fg(Color::Reset));
+    }
+
+    #[test]
+    fn monochrome_strips_colors_but_keeps_emphasis() {
+        let line = Line::from(vec![
+            Span::from("+added").green().on_dark_gray(),
+            Span::from("title").cyan().bold().underlined(),
+        ]);
+        let adapted: Vec<Style> = line
+            .spans
+            .iter()
+            .map(|span| adapt_style(TuiTheme::Monochrome, span.style))
+            .collect();
+        assert_eq!(
+            adapted,
+            vec![
+                Style::default().fg(Color::Reset).bg(Color::Reset),
+                Style::default()
+                    .fg(Color::Reset)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            ]
+        );
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buf.set_string(0, 0, "abc", Style::default().red().italic());
+        for cell in &mut buf.content {
+            cell.fg = adapt_color(TuiTheme::Monochrome, cell.fg, Layer::Foreground);
+        }
+        assert_eq!(buf[(1, 0)].fg, Color::Reset);
+        assert_eq!(buf[(1, 0)].modifier, Modifier::ITALIC);
+    }
+}
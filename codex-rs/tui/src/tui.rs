@@ -492,7 +492,11 @@ impl Tui {
             if !self.pending_history_lines.is_empty() {
                 crate::insert_history::insert_history_lines(
                     terminal,
-                    self.pending_history_lines.clone(),
+                    self.pending_history_lines
+                        .iter()
+                        .cloned()
+                        .map(crate::theme::apply_to_line)
+                        .collect(),
                 )?;
                 self.pending_history_lines.clear();
             }
@@ -512,6 +516,7 @@ impl Tui {
 
             terminal.draw(|frame| {
                 draw_fn(frame);
+                crate::theme::apply_to_buffer(frame.buffer_mut());
             })
         })?
     }
@@ -0,0 +1,398 @@
+//! Which lines of which files a diff touches.
+//!
+//! [`ChangedLines`] is built from unified diff text (a patch file or the
+//! output of `git diff`) and answers whether a given file and line range
+//! overlaps the change. Whole files or directories can also be marked as
+//! changed, e.g. for untracked files that have no diff against `HEAD`.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_stdout_all;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FileChange {
+    /// Every line of the file (or every file below the directory) changed.
+    Whole,
+    /// Inclusive, 1-based line ranges in the new version of the file.
+    Lines(Vec<(u32, u32)>),
+}
+
+/// The set of new-side lines touched by a diff, keyed by absolute path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    files: BTreeMap<PathBuf, FileChange>,
+}
+
+impl ChangedLines {
+    /// Parses unified diff text. File names in `+++` headers are resolved
+    /// against `root` after stripping git's `b/` prefix; deleted files are
+    /// ignored since no lines of them remain to be commented on.
+    pub fn from_unified_diff(diff: &str, root: &Path) -> Self {
+        let mut changed = Self::default();
+        let mut current: Option<PathBuf> = None;
+        let mut old_remaining = 0u32;
+        let mut new_remaining = 0u32;
+        let mut new_line = 0u32;
+
+        for line in diff.lines() {
+            if old_remaining > 0 || new_remaining > 0 {
+                match line.as_bytes().first() {
+                    Some(b'+') => {
+                        if let Some(path) = &current {
+                            changed.add_range(path.clone(), new_line, new_line);
+                        }
+                        new_line += 1;
+                        new_remaining = new_remaining.saturating_sub(1);
+                    }
+                    Some(b'-') => old_remaining = old_remaining.saturating_sub(1),
+                    Some(b'\\') => {}
+                    _ => {
+                        new_line += 1;
+                        old_remaining = old_remaining.saturating_sub(1);
+                        new_remaining = new_remaining.saturating_sub(1);
+                    }
+                }
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix("+++ ") {
+                current = parse_new_file_name(name).map(|name| root.join(name));
+            } else if let Some(header) = line.strip_prefix("@@ ")
+                && let Some((old_count, start, new_count)) = parse_hunk_header(header)
+            {
+                old_remaining = old_count;
+                new_remaining = new_count;
+                new_line = start;
+                // A pure deletion still anchors the hunk at a line of the new file.
+                if new_count == 0
+                    && let Some(path) = &current
+                {
+                    let anchor = start.max(1);
+                    changed.add_range(path.clone(), anchor, anchor);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Marks every line of `path` as changed. When `path` is a directory,
+    /// every file below it is covered.
+    pub fn mark_whole(&mut self, path: PathBuf) {
+        self.files.insert(path, FileChange::Whole);
+    }
+
+    /// Adds the changes in `other` to this set.
+    pub fn extend(&mut self, other: ChangedLines) {
+        for (path, change) in other.files {
+            match change {
+                FileChange::Whole => self.mark_whole(path),
+                FileChange::Lines(ranges) => {
+                    for (start, end) in ranges {
+                        self.add_range(path.clone(), start, end);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns true when no file is recorded as changed.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns true when the inclusive line range `start..=end` of `path`
+    /// overlaps a changed line.
+    pub fn touches(&self, path: &Path, start: u32, end: u32) -> bool {
+        let (start, end) = (start.min(end), start.max(end));
+        self.files
+            .iter()
+            .any(|(changed_path, change)| match change {
+                FileChange::Whole => path.starts_with(changed_path),
+                FileChange::Lines(ranges) => {
+                    changed_path == path
+                        && ranges.iter().any(|(from, to)| *from <= end && start <= *to)
+                }
+            })
+    }
+
+    fn add_range(&mut self, path: PathBuf, start: u32, end: u32) {
+        let change = self
+            .files
+            .entry(path)
+            .or_insert_with(|| FileChange::Lines(Vec::new()));
+        let FileChange::Lines(ranges) = change else {
+            return;
+        };
+        match ranges.last_mut() {
+            // Consecutive added lines extend the previous range.
+            Some((last_start, last_end))
+                if start >= *last_start && start <= last_end.saturating_add(1) =>
+            {
+                *last_end = end.max(*last_end);
+            }
+            _ => ranges.push((start, end)),
+        }
+    }
+}
+
+/// Returns the lines changed by `git diff <args>` in the repository that
+/// contains `repo_path`, with paths resolved against the canonical repository
+/// root.
+pub fn diff_changed_lines(
+    repo_path: &Path,
+    args: &[&str],
+) -> Result<ChangedLines, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = canonical_repository_root(repo_path)?;
+    let mut command = vec![
+        OsString::from("diff"),
+        OsString::from("--no-color"),
+        OsString::from("--no-ext-diff"),
+        OsString::from("--unified=0"),
+    ];
+    command.extend(args.iter().map(OsString::from));
+    let diff = run_git_for_stdout_all(repo_root.as_path(), command, None)?;
+    Ok(ChangedLines::from_unified_diff(&diff, &repo_root))
+}
+
+/// Returns the lines changed by `commit` relative to its first parent. A root
+/// commit counts every line it adds as changed.
+pub fn commit_changed_lines(
+    repo_path: &Path,
+    commit: &str,
+) -> Result<ChangedLines, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = canonical_repository_root(repo_path)?;
+    let diff = run_git_for_stdout_all(
+        repo_root.as_path(),
+        [
+            "diff-tree",
+            "-r",
+            "-p",
+            "--root",
+            "-m",
+            "--first-parent",
+            "--no-color",
+            "--no-ext-diff",
+            "--unified=0",
+            commit,
+        ],
+        None,
+    )?;
+    Ok(ChangedLines::from_unified_diff(&diff, &repo_root))
+}
+
+/// Returns the absolute paths of untracked, non-ignored files in the
+/// repository that contains `repo_path`.
+pub fn untracked_files(repo_path: &Path) -> Result<Vec<PathBuf>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = canonical_repository_root(repo_path)?;
+    let output = run_git_for_stdout_all(
+        repo_root.as_path(),
+        ["ls-files", "--others", "--exclude-standard", "-z"],
+        None,
+    )?;
+    Ok(output
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .map(|name| repo_root.join(name))
+        .collect())
+}
+
+/// Returns the canonical root of the git repository that contains `path`, or
+/// `path` itself (canonicalized) when it is not inside a repository. Paths of
+/// review targets and findings are resolved against this directory.
+pub fn repository_root_or_self(path: &Path) -> PathBuf {
+    match resolve_repository_root(path) {
+        Ok(root) => canonicalize_lenient(&root),
+        Err(_) => canonicalize_lenient(path),
+    }
+}
+
+/// Canonicalizes `path`. When it does not exist (e.g. a file a patch has yet
+/// to create), the closest existing ancestor is canonicalized instead and the
+/// remaining components are appended unchanged.
+pub fn canonicalize_lenient(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            canonicalize_lenient(parent).join(name)
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+fn canonical_repository_root(repo_path: &Path) -> Result<PathBuf, GitToolingError> {
+    Ok(canonicalize_lenient(&resolve_repository_root(repo_path)?))
+}
+
+fn parse_new_file_name(name: &str) -> Option<&str> {
+    // `diff -u` appends a tab and timestamp after the file name.
+    let name = name.split('\t').next().unwrap_or(name).trim_end();
+    let name = name
+        .strip_prefix('"')
+        .and_then(|name| name.strip_suffix('"'))
+        .unwrap_or(name);
+    if name == "/dev/null" {
+        return None;
+    }
+    Some(name.strip_prefix("b/").unwrap_or(name))
+}
+
+/// Parses `-a,b +c,d @@ ...` into `(b, c, d)`. Omitted counts default to 1.
+fn parse_hunk_header(header: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = header.split_whitespace();
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let count = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = count(old)?;
+    let (new_start, new_count) = count(new)?;
+    Some((old_count, new_start, new_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    const PATCH: &str = "\
+From 1234 Mon Sep 17 00:00:00 2001
+Subject: [PATCH] Tweak things
+
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,5 @@
+ fn a() {}
+-fn b() {}
++fn b() { 1 }
++++ looks like a header
+ fn c() {}
+ fn d() {}
+@@ -20,2 +21,0 @@ fn e() {}
+-fn f() {}
+-fn g() {}
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+
+    #[test]
+    fn parses_new_side_ranges_from_patch() {
+        let changed = ChangedLines::from_unified_diff(PATCH, Path::new("/repo"));
+        let lib = Path::new("/repo/src/lib.rs");
+
+        assert!(!changed.touches(lib, 1, 1));
+        assert!(changed.touches(lib, 2, 2));
+        assert!(changed.touches(lib, 3, 3));
+        assert!(!changed.touches(lib, 4, 5));
+        assert!(changed.touches(lib, 10, 21));
+        assert!(!changed.touches(Path::new("/repo/looks like a header"), 1, 1));
+        assert!(!changed.touches(Path::new("/repo/old.rs"), 1, 1));
+    }
+
+    #[test]
+    fn whole_paths_cover_nested_files() {
+        let mut changed = ChangedLines::default();
+        changed.mark_whole(PathBuf::from("/repo/src"));
+
+        assert!(changed.touches(Path::new("/repo/src/a/b.rs"), 100, 120));
+        assert!(!changed.touches(Path::new("/repo/srcs/b.rs"), 1, 1));
+    }
+
+    #[test]
+    fn diff_changed_lines_reads_commit_range() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .status()
+                .expect("git command");
+            assert!(status.success(), "git command failed: {args:?}");
+        };
+        git(&["init", "--initial-branch=main"]);
+        git(&["config", "user.name", "Tester"]);
+        git(&["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("a.txt"), "one\ntwo\nthree\n")?;
+        git(&["add", "."]);
+        git(&["commit", "-m", "first"]);
+        std::fs::write(repo.join("a.txt"), "one\n2\nthree\nfour\n")?;
+        git(&["commit", "-am", "second"]);
+        std::fs::write(repo.join("new.txt"), "new\n")?;
+
+        let changed = diff_changed_lines(repo, &["HEAD~1..HEAD"])?;
+        let root = resolve_repository_root(repo)?;
+        let a = root.join("a.txt");
+        assert!(!changed.touches(&a, 1, 1));
+        assert!(changed.touches(&a, 2, 2));
+        assert!(changed.touches(&a, 4, 4));
+        assert_eq!(untracked_files(repo)?, vec![root.join("new.txt")]);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_changed_lines_handles_root_commits() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .status()
+                .expect("git command");
+            assert!(status.success(), "git command failed: {args:?}");
+        };
+        git(&["init", "--initial-branch=main"]);
+        git(&["config", "user.name", "Tester"]);
+        git(&["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("a.txt"), "one\ntwo\n")?;
+        git(&["add", "."]);
+        git(&["commit", "-m", "first"]);
+        std::fs::write(repo.join("a.txt"), "one\n2\n")?;
+        git(&["commit", "-am", "second"]);
+
+        let root = repository_root_or_self(repo);
+        let a = root.join("a.txt");
+        let first = commit_changed_lines(repo, "HEAD~1")?;
+        assert!(first.touches(&a, 1, 1));
+        assert!(first.touches(&a, 2, 2));
+        let second = commit_changed_lines(repo, "HEAD")?;
+        assert!(!second.touches(&a, 1, 1));
+        assert!(second.touches(&a, 2, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn canonicalize_lenient_keeps_missing_components() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let canonical = temp.path().canonicalize()?;
+
+        assert_eq!(
+            canonicalize_lenient(&temp.path().join("missing/file.rs")),
+            canonical.join("missing/file.rs")
+        );
+        assert_eq!(repository_root_or_self(temp.path()), canonical);
+        Ok(())
+    }
+}
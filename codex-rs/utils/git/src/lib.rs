@@ -3,10 +3,12 @@ use std::path::PathBuf;
 
 mod apply;
 mod branch;
+mod changed_lines;
 mod errors;
 mod ghost_commits;
 mod operations;
 mod platform;
+mod worktree;
 
 pub use apply::ApplyGitRequest;
 pub use apply::ApplyGitResult;
@@ -15,6 +17,12 @@ pub use apply::extract_paths_from_patch;
 pub use apply::parse_git_apply_output;
 pub use apply::stage_paths;
 pub use branch::merge_base_with_head;
+pub use changed_lines::ChangedLines;
+pub use changed_lines::canonicalize_lenient;
+pub use changed_lines::commit_changed_lines;
+pub use changed_lines::diff_changed_lines;
+pub use changed_lines::repository_root_or_self;
+pub use changed_lines::untracked_files;
 pub use errors::GitToolingError;
 pub use ghost_commits::CreateGhostCommitOptions;
 pub use ghost_commits::GhostSnapshotConfig;
@@ -33,6 +41,9 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use ts_rs::TS;
+pub use worktree::GitWorktree;
+pub use worktree::create_worktree;
+pub use worktree::remove_stale_worktree;
 
 type CommitID = String;
 
@@ -0,0 +1,286 @@
+//! Scratch worktrees that let work happen on a separate checkout of the
+//! repository without touching the caller's working tree.
+//!
+//! [`create_worktree`] checks out the current `HEAD` on a new branch,
+//! [`GitWorktree::diff`] captures everything changed there (including new
+//! files) as a patch that can be fed to [`crate::apply_git_patch`], and
+//! [`GitWorktree::remove`] deletes both the checkout and its branch.
+//! [`remove_stale_worktree`] does the same for a worktree whose
+//! [`GitWorktree`] was lost, e.g. because the process that created it died.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::repo_subdir;
+use crate::operations::resolve_head;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout;
+use crate::operations::run_git_for_stdout_all;
+
+/// A linked worktree checked out on its own branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
+    branch: String,
+    base_commit: String,
+}
+
+/// Creates a worktree at `worktree_path` on a new `branch` starting from the
+/// current `HEAD` of the repository containing `repo_path`.
+///
+/// Uncommitted changes in the main checkout are not carried over.
+pub fn create_worktree(
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+) -> Result<GitWorktree, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let base_commit =
+        resolve_head(repo_root.as_path())?.ok_or_else(|| GitToolingError::NoCommits {
+            path: repo_root.clone(),
+        })?;
+
+    // Forget worktrees whose directories were deleted out from under git so
+    // their branches can be reused.
+    run_git_for_status(repo_root.as_path(), ["worktree", "prune"], None)?;
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![
+            OsString::from("worktree"),
+            OsString::from("add"),
+            OsString::from("-b"),
+            OsString::from(branch),
+            worktree_path.as_os_str().to_os_string(),
+            OsString::from(&base_commit),
+        ],
+        None,
+    )?;
+
+    Ok(GitWorktree {
+        repo_root,
+        path: worktree_path.to_path_buf(),
+        branch: branch.to_string(),
+        base_commit,
+    })
+}
+
+impl GitWorktree {
+    /// Root of the main checkout the worktree was created from.
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Root of the worktree checkout.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Scratch branch checked out in the worktree.
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Commit the worktree started from.
+    pub fn base_commit(&self) -> &str {
+        &self.base_commit
+    }
+
+    /// Maps a directory of the main checkout to the same directory inside the
+    /// worktree. Paths outside the repository map to the worktree root.
+    pub fn translate_path(&self, path: &Path) -> PathBuf {
+        match repo_subdir(&self.repo_root, path) {
+            Some(subdir) => self.path.join(subdir),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Returns every change made in the worktree since [`Self::base_commit`],
+    /// committed or not, as a binary-safe unified diff relative to the
+    /// repository root. Returns an empty string when nothing changed.
+    pub fn diff(&self) -> Result<String, GitToolingError> {
+        // Stage everything so new files show up in the diff.
+        run_git_for_status(self.path.as_path(), ["add", "--all"], None)?;
+        run_git_for_stdout_all(
+            self.path.as_path(),
+            vec![
+                OsString::from("diff"),
+                OsString::from("--cached"),
+                OsString::from("--binary"),
+                OsString::from(&self.base_commit),
+            ],
+            None,
+        )
+    }
+
+    /// Removes the worktree checkout and deletes its branch, discarding any
+    /// changes made there.
+    pub fn remove(&self) -> Result<(), GitToolingError> {
+        remove_worktree(&self.repo_root, &self.path, &self.branch)
+    }
+}
+
+/// Removes the worktree checked out at `worktree_path` and deletes `branch`
+/// from the repository it belongs to, discarding any changes made there.
+pub fn remove_stale_worktree(worktree_path: &Path, branch: &str) -> Result<(), GitToolingError> {
+    let common_dir = run_git_for_stdout(
+        worktree_path,
+        ["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        None,
+    )?;
+    let common_dir = PathBuf::from(common_dir);
+    let repo_root = resolve_repository_root(common_dir.parent().unwrap_or(&common_dir))?;
+    remove_worktree(&repo_root, worktree_path, branch)
+}
+
+fn remove_worktree(
+    repo_root: &Path,
+    worktree_path: &Path,
+    branch: &str,
+) -> Result<(), GitToolingError> {
+    if worktree_path.exists() {
+        run_git_for_status(
+            repo_root,
+            vec![
+                OsString::from("worktree"),
+                OsString::from("remove"),
+                OsString::from("--force"),
+                worktree_path.as_os_str().to_os_string(),
+            ],
+            None,
+        )?;
+    } else {
+        run_git_for_status(repo_root, ["worktree", "prune"], None)?;
+    }
+    run_git_for_status(repo_root, ["branch", "-D", branch], None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApplyGitRequest;
+    use crate::apply_git_patch;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn init_test_repo(repo_path: &Path) {
+        run_git_in(repo_path, &["init", "--initial-branch=main"]);
+        run_git_in(repo_path, &["config", "core.autocrlf", "false"]);
+        run_git_in(repo_path, &["config", "user.name", "Tester"]);
+        run_git_in(repo_path, &["config", "user.email", "test@example.com"]);
+    }
+
+    #[test]
+    fn worktree_changes_round_trip_into_main_checkout() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join("src"))?;
+        init_test_repo(&repo);
+        std::fs::write(repo.join("src/lib.rs"), "fn a() {}\n")?;
+        run_git_in(&repo, &["add", "."]);
+        run_git_in(&repo, &["commit", "-m", "init"]);
+
+        let worktree_path = temp.path().join("worktrees/agent");
+        let worktree = create_worktree(&repo.join("src"), &worktree_path, "codex/agent-test")?;
+        assert_eq!(
+            worktree.translate_path(&repo.join("src")),
+            worktree_path.join("src")
+        );
+
+        std::fs::write(worktree_path.join("src/lib.rs"), "fn b() {}\n")?;
+        std::fs::write(worktree_path.join("new.txt"), "new\n")?;
+        let diff = worktree.diff()?;
+        assert!(diff.contains("+fn b() {}"), "unexpected diff: {diff}");
+        assert!(diff.contains("new.txt"), "unexpected diff: {diff}");
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/lib.rs"))?,
+            "fn a() {}\n"
+        );
+
+        let result = apply_git_patch(&ApplyGitRequest {
+            cwd: repo.clone(),
+            diff,
+            revert: false,
+            preflight: false,
+        })?;
+        assert_eq!(result.exit_code, 0, "apply failed: {}", result.stderr);
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/lib.rs"))?,
+            "fn b() {}\n"
+        );
+        assert_eq!(std::fs::read_to_string(repo.join("new.txt"))?, "new\n");
+
+        worktree.remove()?;
+        assert!(!worktree_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_is_empty_without_changes() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(&repo)?;
+        init_test_repo(&repo);
+        std::fs::write(repo.join("a.txt"), "a\n")?;
+        run_git_in(&repo, &["add", "."]);
+        run_git_in(&repo, &["commit", "-m", "init"]);
+
+        let worktree = create_worktree(&repo, &temp.path().join("wt"), "codex/agent-empty")?;
+        assert_eq!(worktree.diff()?, "");
+
+        // Removing a worktree whose directory is already gone still cleans up
+        // the branch.
+        std::fs::remove_dir_all(worktree.path())?;
+        worktree.remove()?;
+        Ok(())
+    }
+
+    #[test]
+    fn stale_worktrees_are_removed_with_their_branch() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(&repo)?;
+        init_test_repo(&repo);
+        std::fs::write(repo.join("a.txt"), "a\n")?;
+        run_git_in(&repo, &["add", "."]);
+        run_git_in(&repo, &["commit", "-m", "init"]);
+
+        let worktree_path = temp.path().join("wt");
+        create_worktree(&repo, &worktree_path, "codex/agent-stale")?;
+        std::fs::write(worktree_path.join("a.txt"), "changed\n")?;
+
+        remove_stale_worktree(&worktree_path, "codex/agent-stale")?;
+        assert!(!worktree_path.exists());
+        let branches = run_git_for_stdout(&repo, ["branch", "--list", "codex/agent-*"], None)?;
+        assert_eq!(branches, "");
+        Ok(())
+    }
+
+    #[test]
+    fn create_worktree_requires_a_commit() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        init_test_repo(temp.path());
+        let err = create_worktree(temp.path(), &temp.path().join("wt"), "codex/agent-none")
+            .expect_err("repository without commits");
+        assert!(matches!(err, GitToolingError::NoCommits { .. }));
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+//! Embedded BPE tokenizers used for local context accounting.
+//!
+//! The encodings are compiled into the binary via `tiktoken-rs`, so counting
+//! tokens never requires network access. Each encoding is loaded lazily the
+//! first time it is used and shared for the lifetime of the process.
+
+use std::fmt;
+
+use tiktoken_rs::CoreBPE;
+use tiktoken_rs::Rank;
+
+/// BPE vocabularies that ship with Codex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncodingKind {
+    /// Used by the GPT-4o, GPT-4.1, o-series and GPT-5 families.
+    O200kBase,
+    /// Used by GPT-4 and GPT-3.5 era models.
+    Cl100kBase,
+}
+
+impl EncodingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EncodingKind::O200kBase => "o200k_base",
+            EncodingKind::Cl100kBase => "cl100k_base",
+        }
+    }
+}
+
+impl fmt::Display for EncodingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A cheap, copyable handle to a lazily-initialized BPE encoding.
+#[derive(Clone, Copy)]
+pub struct Tokenizer {
+    kind: EncodingKind,
+    bpe: &'static CoreBPE,
+}
+
+impl fmt::Debug for Tokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tokenizer")
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Tokenizer {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl Eq for Tokenizer {}
+
+impl Tokenizer {
+    pub fn new(kind: EncodingKind) -> Self {
+        let bpe = match kind {
+            EncodingKind::O200kBase => tiktoken_rs::o200k_base_singleton(),
+            EncodingKind::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+        };
+        Self { kind, bpe }
+    }
+
+    pub fn kind(&self) -> EncodingKind {
+        self.kind
+    }
+
+    /// Encodes `text` without treating special-token markers specially, so
+    /// user-supplied content such as `<|endoftext|>` is counted as plain text.
+    pub fn encode(&self, text: &str) -> Vec<Rank> {
+        self.bpe.encode_ordinary(text)
+    }
+
+    /// Returns the number of tokens `text` encodes to.
+    pub fn count(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        self.encode(text).len()
+    }
+
+    /// Encodes `text` once and keeps at most `head_tokens` tokens from its
+    /// start and `tail_tokens` tokens from its end. Returns `None` when the
+    /// whole text already fits in `head_tokens + tail_tokens`.
+    ///
+    /// A token that would split a multi-byte character is dropped, so both
+    /// slices always sit on UTF-8 character boundaries.
+    pub fn split_head_tail<'a>(
+        &self,
+        text: &'a str,
+        head_tokens: usize,
+        tail_tokens: usize,
+    ) -> Option<HeadTail<'a>> {
+        let tokens = self.encode(text);
+        let total_tokens = tokens.len();
+        if total_tokens <= head_tokens.saturating_add(tail_tokens) {
+            return None;
+        }
+
+        let mut head_count = head_tokens;
+        let head_len = loop {
+            match self.decoded_len(&tokens[..head_count]) {
+                Some(len) => break len,
+                None => head_count -= 1,
+            }
+        };
+        let mut tail_count = tail_tokens;
+        let tail_len = loop {
+            match self.decoded_len(&tokens[total_tokens - tail_count..]) {
+                Some(len) => break len,
+                None => tail_count -= 1,
+            }
+        };
+
+        let head = &text[..floor_char_boundary(text, head_len)];
+        let tail_start = ceil_char_boundary(text, text.len().saturating_sub(tail_len));
+        Some(HeadTail {
+            head,
+            tail: &text[tail_start.max(head.len())..],
+            total_tokens,
+            kept_tokens: head_count + tail_count,
+        })
+    }
+
+    /// Returns the byte length of `tokens` once decoded, or `None` when the
+    /// slice starts or ends inside a multi-byte character.
+    fn decoded_len(&self, tokens: &[Rank]) -> Option<usize> {
+        if tokens.is_empty() {
+            return Some(0);
+        }
+        self.bpe.decode(tokens.to_vec()).ok().map(|text| text.len())
+    }
+}
+
+/// The start and end of a text that were kept by
+/// [`Tokenizer::split_head_tail`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadTail<'a> {
+    pub head: &'a str,
+    pub tail: &'a str,
+    /// Number of tokens in the whole input.
+    pub total_tokens: usize,
+    /// Number of tokens kept across `head` and `tail`.
+    pub kept_tokens: usize,
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while index > 0 && !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    while index < text.len() && !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EncodingKind;
+    use super::Tokenizer;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn counts_tokens_for_known_encodings() {
+        let o200k = Tokenizer::new(EncodingKind::O200kBase);
+        let cl100k = Tokenizer::new(EncodingKind::Cl100kBase);
+
+        assert_eq!(o200k.count(""), 0);
+        assert_eq!(o200k.count("hello world"), 2);
+        assert_eq!(cl100k.count("hello world"), 2);
+    }
+
+    #[test]
+    fn special_token_markers_are_counted_as_text() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase);
+        assert!(tokenizer.count("<|endoftext|>") > 1);
+    }
+
+    #[test]
+    fn split_head_tail_respects_token_budget() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase);
+        let text = "fn main() {\n    println!(\"hello\");\n}\n";
+
+        let split = tokenizer
+            .split_head_tail(text, 3, 3)
+            .expect("text should not fit in six tokens");
+
+        assert!(text.starts_with(split.head));
+        assert!(text.ends_with(split.tail));
+        assert_eq!(split.total_tokens, tokenizer.count(text));
+        assert_eq!(split.kept_tokens, 6);
+        assert_eq!(tokenizer.count(split.head), 3);
+        assert!(tokenizer.count(split.tail) <= 4);
+        assert_eq!(tokenizer.split_head_tail(text, 500, 500), None);
+    }
+
+    #[test]
+    fn split_head_tail_stays_on_char_boundaries() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase);
+        let text = "😀😀😀😀😀😀😀😀";
+
+        for budget in 0..4 {
+            let Some(split) = tokenizer.split_head_tail(text, budget, budget) else {
+                continue;
+            };
+            assert!(text.starts_with(split.head));
+            assert!(text.ends_with(split.tail));
+            assert!(split.kept_tokens <= budget * 2);
+        }
+    }
+}